@@ -0,0 +1,477 @@
+use std::path::Path;
+
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch};
+use arrow::compute::{cast, concat, sort_to_indices, take, SortOptions};
+use arrow::datatypes::{DataType, Float64Type, Int64Type};
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ParquetRecordBatchReaderBuilder, RowSelection,
+};
+use parquet::arrow::ProjectionMask;
+
+use crate::footer;
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::progress::Progress;
+use crate::{cell_text, ViewerError};
+
+/// What the summary row shows for a column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Aggregate {
+    /// Non-null values.
+    #[default]
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    pub const ALL: [Aggregate; 5] = [
+        Aggregate::Count,
+        Aggregate::Sum,
+        Aggregate::Mean,
+        Aggregate::Min,
+        Aggregate::Max,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aggregate::Count => "count",
+            Aggregate::Sum => "sum",
+            Aggregate::Mean => "mean",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+        }
+    }
+
+    /// The aggregate after this one, wrapping around, for cycling through them.
+    pub fn next(self) -> Aggregate {
+        let index = Aggregate::ALL.iter().position(|&a| a == self).unwrap_or(0);
+        Aggregate::ALL[(index + 1) % Aggregate::ALL.len()]
+    }
+}
+
+/// How a column's values are added up for a sum or mean.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Numeric {
+    /// Signed or small unsigned integers, summed exactly.
+    Integer,
+    /// Floats, decimals and `UInt64`, summed as `f64`.
+    Float,
+}
+
+pub(crate) fn is_numeric(data_type: &DataType) -> bool {
+    numeric(data_type).is_some()
+}
+
+fn numeric(data_type: &DataType) -> Option<Numeric> {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32 => Some(Numeric::Integer),
+        DataType::UInt64
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Decimal32(_, _)
+        | DataType::Decimal64(_, _)
+        | DataType::Decimal128(_, _)
+        | DataType::Decimal256(_, _) => Some(Numeric::Float),
+        _ => None,
+    }
+}
+
+/// Running state of one aggregate over the batches of a column.
+pub(crate) struct Accumulator {
+    aggregate: Aggregate,
+    numeric: Option<Numeric>,
+    count: u64,
+    integer_sum: i128,
+    float_sum: f64,
+    /// Smallest or largest value so far, as a one-element array.
+    extreme: Option<ArrayRef>,
+}
+
+impl Accumulator {
+    pub(crate) fn new(aggregate: Aggregate, data_type: &DataType) -> Accumulator {
+        Accumulator {
+            aggregate,
+            numeric: numeric(data_type),
+            count: 0,
+            integer_sum: 0,
+            float_sum: 0.0,
+            extreme: None,
+        }
+    }
+
+    pub(crate) fn applies(&self, data_type: &DataType) -> bool {
+        match self.aggregate {
+            Aggregate::Count => true,
+            Aggregate::Sum | Aggregate::Mean => self.numeric.is_some(),
+            Aggregate::Min | Aggregate::Max => !data_type.is_nested(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, values: &dyn Array) -> Result<(), ViewerError> {
+        self.count += (values.len() - values.null_count()) as u64;
+        match (self.aggregate, self.numeric) {
+            (Aggregate::Sum | Aggregate::Mean, Some(Numeric::Integer)) => {
+                let values = cast(values, &DataType::Int64)?;
+                self.integer_sum += values
+                    .as_primitive::<Int64Type>()
+                    .iter()
+                    .flatten()
+                    .map(i128::from)
+                    .sum::<i128>();
+            }
+            (Aggregate::Sum | Aggregate::Mean, Some(Numeric::Float)) => {
+                let values = cast(values, &DataType::Float64)?;
+                self.float_sum += values
+                    .as_primitive::<Float64Type>()
+                    .iter()
+                    .flatten()
+                    .sum::<f64>();
+            }
+            (Aggregate::Min | Aggregate::Max, _) if values.null_count() < values.len() => {
+                let candidate = extreme(values, self.aggregate == Aggregate::Max)?;
+                self.extreme = Some(match self.extreme.take() {
+                    Some(current) => extreme(
+                        concat(&[current.as_ref(), candidate.as_ref()])?.as_ref(),
+                        self.aggregate == Aggregate::Max,
+                    )?,
+                    None => candidate,
+                });
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(&self) -> Result<Option<String>, ViewerError> {
+        let sum = || match self.numeric {
+            Some(Numeric::Integer) => self.integer_sum as f64,
+            _ => self.float_sum,
+        };
+        Ok(match self.aggregate {
+            Aggregate::Count => Some(self.count.to_string()),
+            Aggregate::Sum => Some(match self.numeric {
+                Some(Numeric::Integer) => self.integer_sum.to_string(),
+                _ => self.float_sum.to_string(),
+            }),
+            Aggregate::Mean if self.count > 0 => Some(format!("{:.4}", sum() / self.count as f64)),
+            Aggregate::Mean => None,
+            Aggregate::Min | Aggregate::Max => match &self.extreme {
+                Some(value) => Some(cell_text(value.as_ref(), 0)?),
+                None => None,
+            },
+        })
+    }
+}
+
+/// The smallest (or with `largest`, biggest) non-null value of `values`.
+fn extreme(values: &dyn Array, largest: bool) -> Result<ArrayRef, ViewerError> {
+    let options = SortOptions {
+        descending: largest,
+        nulls_first: false,
+    };
+    let indices = sort_to_indices(values, Some(options), Some(1))?;
+    Ok(take(values, &indices, None)?)
+}
+
+/// Compute `aggregate` of each named column over the whole file, or with `rows`,
+/// over those file rows only, e.g. the rows a filter kept.
+///
+/// Each result is `None` when the aggregate doesn't apply, such as the sum of a
+/// string column, or has no value, such as the mean of a column of nulls.
+/// `progress` counts row groups read.
+pub fn summarize(
+    path: &Path,
+    columns: &[(String, Aggregate)],
+    rows: Option<&[usize]>,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Vec<Option<String>>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let schema = metadata.schema().clone();
+
+    let positions: Vec<Option<usize>> = columns
+        .iter()
+        .map(|(name, _)| {
+            schema
+                .fields()
+                .iter()
+                .position(|field| field.name() == name)
+        })
+        .collect();
+    let mut accumulators: Vec<Option<Accumulator>> = columns
+        .iter()
+        .zip(&positions)
+        .map(|((_, aggregate), position)| {
+            let data_type = schema.field((*position)?).data_type();
+            let accumulator = Accumulator::new(*aggregate, data_type);
+            accumulator.applies(data_type).then_some(accumulator)
+        })
+        .collect();
+    let mut roots: Vec<usize> = positions
+        .iter()
+        .zip(&accumulators)
+        .filter_map(|(position, accumulator)| accumulator.as_ref().and(*position))
+        .collect();
+    roots.sort_unstable();
+    roots.dedup();
+
+    scan_rows(
+        path,
+        &metadata,
+        &roots,
+        rows,
+        bytes_read,
+        progress,
+        |batch| {
+            for (accumulator, position) in accumulators.iter_mut().zip(&positions) {
+                if let (Some(accumulator), Some(position)) = (accumulator, position) {
+                    let projected = roots.binary_search(position).expect("column is projected");
+                    accumulator.add(batch.column(projected).as_ref())?;
+                }
+            }
+            Ok(())
+        },
+    )?;
+
+    accumulators
+        .iter()
+        .map(|accumulator| match accumulator {
+            Some(accumulator) => accumulator.finish(),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+/// Smallest and largest value of a numeric column over the whole file, as
+/// `f64`, e.g. to scale a heat map. `None` for columns that aren't numeric or
+/// the file doesn't have, and for columns holding only nulls or NaNs.
+pub fn numeric_range(
+    path: &Path,
+    column: &str,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Option<(f64, f64)>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let Some(position) = metadata
+        .schema()
+        .fields()
+        .iter()
+        .position(|field| field.name() == column)
+        .filter(|&position| numeric(metadata.schema().field(position).data_type()).is_some())
+    else {
+        return Ok(None);
+    };
+
+    let mut range: Option<(f64, f64)> = None;
+    scan_rows(
+        path,
+        &metadata,
+        &[position],
+        None,
+        bytes_read,
+        progress,
+        |batch| {
+            let values = cast(batch.column(0), &DataType::Float64)?;
+            for value in values.as_primitive::<Float64Type>().iter().flatten() {
+                if value.is_nan() {
+                    continue;
+                }
+                range = Some(match range {
+                    Some((min, max)) => (min.min(value), max.max(value)),
+                    None => (value, value),
+                });
+            }
+            Ok(())
+        },
+    )?;
+    Ok(range)
+}
+
+/// Decode the `roots` columns, in ascending order, of every row group or with
+/// `rows`, of those file rows only, passing each batch to `visit`. Row groups
+/// without any of `rows` are skipped; `progress` counts row groups.
+pub(crate) fn scan_rows(
+    path: &Path,
+    metadata: &ArrowReaderMetadata,
+    roots: &[usize],
+    rows: Option<&[usize]>,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+    mut visit: impl FnMut(&RecordBatch) -> Result<(), ViewerError>,
+) -> Result<(), ViewerError> {
+    let mut rows = rows.map(<[usize]>::to_vec);
+    if let Some(rows) = &mut rows {
+        rows.sort_unstable();
+    }
+    let row_groups = metadata.metadata().row_groups();
+    progress.set_total(row_groups.len());
+
+    let mut start = 0;
+    for (index, row_group) in row_groups.iter().enumerate() {
+        progress.check()?;
+        let end = start + row_group.num_rows() as usize;
+        let selection = rows.as_ref().map(|rows| {
+            let first = rows.partition_point(|&row| row < start);
+            let last = rows.partition_point(|&row| row < end);
+            rows[first..last]
+                .iter()
+                .map(|&row| row - start..row - start + 1)
+                .collect::<Vec<_>>()
+        });
+        let group_rows = end - start;
+        start = end;
+        if roots.is_empty() || selection.as_ref().is_some_and(Vec::is_empty) {
+            progress.advance(1);
+            continue;
+        }
+
+        let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            CountingFile::open(path, bytes_read)?,
+            metadata.clone(),
+        )
+        .with_row_groups(vec![index])
+        .with_projection(ProjectionMask::roots(
+            metadata.parquet_schema(),
+            roots.iter().copied(),
+        ));
+        if let Some(ranges) = selection {
+            builder = builder.with_row_selection(RowSelection::from_consecutive_ranges(
+                ranges.into_iter(),
+                group_rows,
+            ));
+        }
+        for batch in builder.build()? {
+            progress.check()?;
+            visit(&batch?)?;
+        }
+        progress.advance(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn aggregates_cover_the_file_or_the_given_rows() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("amount", DataType::Int32, true),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(5), None, Some(-2), Some(9)])),
+                Arc::new(StringArray::from(vec!["pear", "apple", "fig", "kiwi"])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let amount = |aggregate| ("amount".to_string(), aggregate);
+        let columns = [
+            amount(Aggregate::Count),
+            amount(Aggregate::Sum),
+            amount(Aggregate::Mean),
+            amount(Aggregate::Min),
+            ("name".to_string(), Aggregate::Max),
+            ("name".to_string(), Aggregate::Sum),
+            ("missing".to_string(), Aggregate::Count),
+        ];
+        let counter = ReadCounter::default();
+
+        let whole = summarize(file.path(), &columns, None, &counter, &Progress::default())
+            .expect("summary should compute");
+        assert_eq!(
+            whole,
+            vec![
+                Some("3".to_string()),
+                Some("12".to_string()),
+                Some("4.0000".to_string()),
+                Some("-2".to_string()),
+                Some("pear".to_string()),
+                None,
+                None,
+            ]
+        );
+
+        let subset = summarize(
+            file.path(),
+            &columns[..5],
+            Some(&[3, 1]),
+            &counter,
+            &Progress::default(),
+        )
+        .expect("summary should compute");
+        assert_eq!(subset[0], Some("1".to_string()));
+        assert_eq!(subset[1], Some("9".to_string()));
+        assert_eq!(subset[3], Some("9".to_string()));
+        assert_eq!(subset[4], Some("kiwi".to_string()));
+    }
+
+    #[test]
+    fn numeric_range_spans_row_groups_and_skips_other_columns() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("amount", DataType::Int32, true),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(5), None, Some(-2), Some(9)])),
+                Arc::new(StringArray::from(vec!["pear", "apple", "fig", "kiwi"])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let range = |column| {
+            numeric_range(
+                file.path(),
+                column,
+                &ReadCounter::default(),
+                &Progress::default(),
+            )
+            .expect("range should compute")
+        };
+
+        assert_eq!(range("amount"), Some((-2.0, 9.0)));
+        assert_eq!(range("name"), None);
+        assert_eq!(range("missing"), None);
+    }
+
+    #[test]
+    fn aggregates_cycle_in_order() {
+        assert_eq!(Aggregate::Count.next(), Aggregate::Sum);
+        assert_eq!(Aggregate::Max.next(), Aggregate::Count);
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::OnceLock;
+
+use parquet::file::metadata::RowGroupMetaData;
+
+/// Decoded bytes aimed for per batch when no `--batch-size` is given.
+const TARGET_BATCH_BYTES: u64 = 8 << 20;
+
+/// Fewest rows per batch, however wide the rows are.
+const MIN_BATCH_ROWS: usize = 64;
+
+/// Most rows per batch, however narrow the rows are.
+const MAX_BATCH_ROWS: usize = 65_536;
+
+static BATCH_SIZE: OnceLock<usize> = OnceLock::new();
+
+/// Set the process-wide number of rows decoded per batch; `None` keeps it adaptive.
+pub fn init_batch_size(rows: Option<usize>) {
+    if let Some(rows) = rows {
+        let _ = BATCH_SIZE.set(rows.max(1));
+    }
+}
+
+/// The rows per batch set with `--batch-size`, if any.
+pub fn batch_size() -> Option<usize> {
+    BATCH_SIZE.get().copied()
+}
+
+/// Rows to decode per batch from `row_group` when reading the top-level
+/// columns `roots`, or all of them with `None`.
+///
+/// Without `--batch-size`, batches hold about [`TARGET_BATCH_BYTES`] going by
+/// the uncompressed size of the column chunks read, so very wide rows come in
+/// small batches that keep peak memory down and narrow ones in large batches
+/// that spare the per-batch overhead.
+pub fn batch_rows(row_group: &RowGroupMetaData, roots: Option<&[usize]>) -> usize {
+    if let Some(rows) = batch_size() {
+        return rows;
+    }
+    let descriptor = row_group.schema_descr();
+    let bytes: i64 = row_group
+        .columns()
+        .iter()
+        .enumerate()
+        .filter(|(leaf, _)| {
+            roots.is_none_or(|roots| roots.contains(&descriptor.get_column_root_idx(*leaf)))
+        })
+        .map(|(_, chunk)| chunk.uncompressed_size().max(0))
+        .sum();
+    let bytes_per_row = (bytes as u64 / row_group.num_rows().max(1) as u64).max(1);
+    (TARGET_BATCH_BYTES / bytes_per_row).clamp(MIN_BATCH_ROWS as u64, MAX_BATCH_ROWS as u64)
+        as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::arrow_reader::ArrowReaderMetadata;
+    use parquet::arrow::ArrowWriter;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn wide_columns_get_smaller_batches() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("blob", DataType::Utf8, false),
+        ]));
+        let rows = 200;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..rows)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..rows).map(|row| format!("{row:0>20000}")),
+                )),
+            ],
+        )
+        .expect("record batch should build");
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None)
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let metadata = ArrowReaderMetadata::load(&file.reopen().unwrap(), Default::default())
+            .expect("footer should load");
+        let row_group = metadata.metadata().row_group(0);
+        assert_eq!(batch_rows(row_group, Some(&[0])), MAX_BATCH_ROWS);
+        assert_eq!(batch_rows(row_group, None), 419);
+    }
+}
@@ -0,0 +1,315 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+use serde::Serialize;
+
+use crate::metrics::format_bytes;
+use crate::progress::Progress;
+use crate::ViewerError;
+
+/// Whether each timed read may find the file in the OS page cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cache {
+    /// The file is read once untimed first, so runs measure decoding rather than the disk.
+    #[default]
+    Warm,
+    /// The file is dropped from the page cache before every timed read.
+    Cold,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Times each measurement is taken; the report gives the median.
+    pub runs: usize,
+    pub cache: Cache,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            runs: 3,
+            cache: Cache::Warm,
+        }
+    }
+}
+
+/// Timed runs of one read, with how much it read.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Measurement {
+    pub times: Vec<Duration>,
+    /// Bytes of the file the read covers, compressed as stored.
+    pub bytes: u64,
+    pub rows: usize,
+}
+
+impl Measurement {
+    pub fn median(&self) -> Duration {
+        let mut times = self.times.clone();
+        times.sort_unstable();
+        times.get(times.len() / 2).copied().unwrap_or_default()
+    }
+
+    /// Stored bytes per second at the median time.
+    pub fn bytes_per_second(&self) -> f64 {
+        self.bytes as f64 / self.median().as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn rows_per_second(&self) -> f64 {
+        self.rows as f64 / self.median().as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// One aligned report line, e.g.
+    /// `scan           812.40 ms    150.2 MiB/s    1231000 rows/s`.
+    fn line(&self, name: &str) -> String {
+        let mut line = format!("{name:<24} {:>10.2} ms", millis(self.median()));
+        if self.bytes > 0 {
+            line.push_str(&format!(
+                " {:>14}/s {:>14.0} rows/s",
+                format_bytes(self.bytes_per_second() as u64),
+                self.rows_per_second()
+            ));
+        }
+        line
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "median_ms": millis(self.median()),
+            "runs_ms": self.times.iter().copied().map(millis).collect::<Vec<_>>(),
+            "bytes": self.bytes,
+            "rows": self.rows,
+            "bytes_per_second": self.bytes_per_second(),
+            "rows_per_second": self.rows_per_second(),
+        })
+    }
+}
+
+/// Read performance of one file: parsing its footer, decoding all of it, and
+/// decoding each top-level column on its own.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BenchReport {
+    pub file_bytes: u64,
+    pub rows: usize,
+    pub row_groups: usize,
+    pub options: BenchOptions,
+    /// Reading and parsing the footer and page index; no rows or bytes.
+    pub metadata: Measurement,
+    pub scan: Measurement,
+    pub columns: Vec<(String, Measurement)>,
+}
+
+impl BenchReport {
+    /// The report as text, one measurement per line with the same columns, so
+    /// two reports line up when diffed.
+    pub fn lines(&self) -> Vec<String> {
+        let cache = match self.options.cache {
+            Cache::Warm => "warm",
+            Cache::Cold => "cold",
+        };
+        let mut lines = vec![
+            format!(
+                "File: {}, {} rows, {} row groups",
+                format_bytes(self.file_bytes),
+                self.rows,
+                self.row_groups
+            ),
+            format!("Median of {} runs, {cache} cache", self.options.runs),
+            self.metadata.line("metadata"),
+            self.scan.line("scan"),
+        ];
+        lines.extend(
+            self.columns
+                .iter()
+                .map(|(column, measurement)| measurement.line(&format!("column {column}"))),
+        );
+        lines
+    }
+
+    pub fn to_json(&self) -> String {
+        let columns: serde_json::Map<String, serde_json::Value> = self
+            .columns
+            .iter()
+            .map(|(column, measurement)| (column.clone(), measurement.to_json()))
+            .collect();
+        let report = serde_json::json!({
+            "file_bytes": self.file_bytes,
+            "rows": self.rows,
+            "row_groups": self.row_groups,
+            "runs": self.options.runs,
+            "cache": self.options.cache,
+            "metadata": self.metadata.to_json(),
+            "scan": self.scan.to_json(),
+            "columns": columns,
+        });
+        serde_json::to_string_pretty(&report).expect("bench reports serialize to JSON")
+    }
+}
+
+/// Time reading the file at `path`: parsing the metadata, decoding every column,
+/// then decoding each top-level column alone, each `options.runs` times.
+///
+/// `progress` counts timed runs; cancelling it stops with [`ViewerError::Cancelled`].
+/// A cold cache needs the OS to drop the file from its page cache, which only
+/// Linux supports; elsewhere it fails with [`ViewerError::OpenFailed`].
+pub fn bench_file(
+    path: &Path,
+    options: &BenchOptions,
+    progress: &Progress,
+) -> Result<BenchReport, ViewerError> {
+    let runs = options.runs.max(1);
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    let metadata = builder.metadata().clone();
+    let schema_descr = builder.parquet_schema().clone();
+    let columns: Vec<String> = builder
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect();
+    drop(builder);
+
+    let mut report = BenchReport {
+        file_bytes: std::fs::metadata(path)?.len(),
+        rows: metadata.file_metadata().num_rows().max(0) as usize,
+        row_groups: metadata.num_row_groups(),
+        options: BenchOptions { runs, ..*options },
+        ..BenchReport::default()
+    };
+    progress.set_total(runs * (2 + columns.len()));
+
+    let prepare = || -> Result<(), ViewerError> {
+        match options.cache {
+            Cache::Warm => Ok(()),
+            Cache::Cold => Ok(evict_from_cache(path)?),
+        }
+    };
+    let timed = |read: &dyn Fn() -> Result<(), ViewerError>| -> Result<Vec<Duration>, ViewerError> {
+        (0..runs)
+            .map(|_| {
+                progress.check()?;
+                prepare()?;
+                let started = Instant::now();
+                read()?;
+                let elapsed = started.elapsed();
+                progress.advance(1);
+                Ok(elapsed)
+            })
+            .collect()
+    };
+    let decode = |projection: ProjectionMask| -> Result<(), ViewerError> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?
+            .with_projection(projection)
+            .build()?;
+        for batch in reader {
+            progress.check()?;
+            batch?;
+        }
+        Ok(())
+    };
+
+    if options.cache == Cache::Warm {
+        io::copy(&mut File::open(path)?, &mut io::sink())?;
+    }
+
+    report.metadata.times = timed(&|| {
+        ParquetMetaDataReader::new()
+            .with_page_index_policy(PageIndexPolicy::Optional)
+            .parse_and_finish(&File::open(path)?)?;
+        Ok(())
+    })?;
+    report.scan = Measurement {
+        times: timed(&|| decode(ProjectionMask::all()))?,
+        bytes: report.file_bytes,
+        rows: report.rows,
+    };
+    for (root, column) in columns.into_iter().enumerate() {
+        let bytes: u64 = metadata
+            .row_groups()
+            .iter()
+            .flat_map(|group| group.columns().iter().enumerate())
+            .filter(|(leaf, _)| schema_descr.get_column_root_idx(*leaf) == root)
+            .map(|(_, chunk)| chunk.compressed_size().max(0) as u64)
+            .sum();
+        let projection = ProjectionMask::roots(&schema_descr, [root]);
+        report.columns.push((
+            column,
+            Measurement {
+                times: timed(&|| decode(projection.clone()))?,
+                bytes,
+                rows: report.rows,
+            },
+        ));
+    }
+    Ok(report)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Ask the OS to drop the file at `path` from its page cache, so the next read
+/// comes from the disk.
+#[cfg(target_os = "linux")]
+fn evict_from_cache(path: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let file = File::open(path)?;
+    // SAFETY: the descriptor is open for the duration of the call.
+    match unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) } {
+        0 => Ok(()),
+        error => Err(io::Error::from_raw_os_error(error)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn evict_from_cache(_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "dropping a file from the page cache is only supported on Linux",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn bench_times_metadata_scan_and_every_column() {
+        let file =
+            write_test_parquet_with_row_groups(100, 40).expect("parquet write should succeed");
+        let options = BenchOptions {
+            runs: 2,
+            cache: Cache::Warm,
+        };
+
+        let report =
+            bench_file(file.path(), &options, &Progress::default()).expect("bench should succeed");
+        assert_eq!((report.rows, report.row_groups), (100, 3));
+        assert_eq!(report.metadata.times.len(), 2);
+        assert_eq!(report.scan.bytes, report.file_bytes);
+        let columns: Vec<&str> = report
+            .columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(columns, ["id", "name"]);
+        let column_bytes: u64 = report.columns.iter().map(|(_, column)| column.bytes).sum();
+        assert!(column_bytes > 0 && column_bytes < report.file_bytes);
+
+        let lines = report.lines();
+        assert_eq!(lines[1], "Median of 2 runs, warm cache");
+        assert!(lines[4].starts_with("column id "));
+        let json: serde_json::Value =
+            serde_json::from_str(&report.to_json()).expect("report should be JSON");
+        assert_eq!(json["columns"]["name"]["rows"], 100);
+        assert_eq!(json["cache"], "warm");
+    }
+}
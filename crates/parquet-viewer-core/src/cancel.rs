@@ -0,0 +1,103 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::future::{AbortHandle, Abortable};
+
+use crate::ViewerError;
+
+/// Stops a piece of background work once it is superseded, such as a viewport
+/// load after the user scrolled again. Clones share the same state.
+///
+/// Synchronous work polls [`check`](Self::check) between units; async work is
+/// wrapped in [`run`](Self::run), which drops it at its next await point.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<TokenState>);
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    /// Futures running under the token, aborted on cancellation.
+    running: Mutex<Vec<AbortHandle>>,
+}
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+        for handle in self.running().drain(..) {
+            handle.abort();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails with [`ViewerError::Cancelled`] once the token was cancelled.
+    pub fn check(&self) -> Result<(), ViewerError> {
+        if self.is_cancelled() {
+            Err(ViewerError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Drive `future` to completion unless the token is cancelled first.
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, ViewerError> {
+        let (handle, registration) = AbortHandle::new_pair();
+        {
+            let mut running = self.running();
+            running.retain(|handle| !handle.is_aborted());
+            running.push(handle.clone());
+        }
+        if self.is_cancelled() {
+            handle.abort();
+        }
+        Abortable::new(future, registration)
+            .await
+            .map_err(|_| ViewerError::Cancelled)
+    }
+
+    fn running(&self) -> std::sync::MutexGuard<'_, Vec<AbortHandle>> {
+        self.0
+            .running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::channel::oneshot;
+
+    use crate::source::block_on;
+
+    #[test]
+    fn cancelling_stops_pending_and_later_work() {
+        let token = CancellationToken::default();
+        assert_eq!(block_on(token.run(async { 7 })).unwrap(), 7);
+
+        // A future that would wait forever is dropped once the token is cancelled.
+        let (_sender, receiver) = oneshot::channel::<()>();
+        let waiting = std::thread::spawn({
+            let token = token.clone();
+            move || block_on(token.run(receiver))
+        });
+        while token.running().len() < 2 {
+            std::thread::yield_now();
+        }
+        token.cancel();
+        assert!(matches!(
+            waiting.join().unwrap(),
+            Err(ViewerError::Cancelled)
+        ));
+
+        assert!(matches!(token.check(), Err(ViewerError::Cancelled)));
+        assert!(matches!(
+            block_on(token.run(async { 7 })),
+            Err(ViewerError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use crate::extension::ARROW_JSON;
+use crate::render::EXTENSION_NAME_KEY;
+use crate::ViewerError;
+
+/// A type to show a column as in place of the one it is stored with, for
+/// columns written with the wrong type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewAs {
+    /// Integers counted in `TimeUnit`s since the Unix epoch, shown in UTC.
+    Timestamp(TimeUnit),
+    /// Strings or bytes holding JSON, pretty-printed in the cell inspector.
+    Json,
+    /// Bytes holding UTF-8 text; values that aren't valid UTF-8 show as null.
+    Text,
+}
+
+/// Casts picked per column, by file column index.
+pub type ColumnCasts = BTreeMap<usize, ViewAs>;
+
+impl ViewAs {
+    /// The types a column stored as `data_type` can be shown as.
+    pub fn choices(data_type: &DataType) -> Vec<ViewAs> {
+        match data_type {
+            data_type if data_type.is_integer() => [
+                TimeUnit::Second,
+                TimeUnit::Millisecond,
+                TimeUnit::Microsecond,
+                TimeUnit::Nanosecond,
+            ]
+            .into_iter()
+            .map(ViewAs::Timestamp)
+            .collect(),
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => vec![ViewAs::Json],
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+                vec![ViewAs::Text, ViewAs::Json]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn data_type(self) -> DataType {
+        match self {
+            ViewAs::Timestamp(unit) => DataType::Timestamp(unit, Some("+00:00".into())),
+            ViewAs::Json | ViewAs::Text => DataType::Utf8,
+        }
+    }
+
+    /// `values` of `field` cast to this type, with the field describing them.
+    /// Values that don't convert become null rather than failing the column.
+    pub fn cast(self, field: &Field, values: &ArrayRef) -> Result<(Field, ArrayRef), ViewerError> {
+        let options = CastOptions {
+            safe: true,
+            ..CastOptions::default()
+        };
+        let values = match self {
+            // Arrow only reinterprets 64-bit integers as timestamps.
+            ViewAs::Timestamp(_) => cast_with_options(
+                &cast_with_options(values, &DataType::Int64, &options)?,
+                &self.data_type(),
+                &options,
+            )?,
+            ViewAs::Json | ViewAs::Text => cast_with_options(values, &self.data_type(), &options)?,
+        };
+        let metadata = match self {
+            ViewAs::Json => {
+                HashMap::from([(EXTENSION_NAME_KEY.to_string(), ARROW_JSON.to_string())])
+            }
+            ViewAs::Timestamp(_) | ViewAs::Text => HashMap::new(),
+        };
+        let field =
+            Field::new(field.name(), values.data_type().clone(), true).with_metadata(metadata);
+        Ok((field, values))
+    }
+}
+
+impl fmt::Display for ViewAs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViewAs::Timestamp(TimeUnit::Second) => f.write_str("Timestamp (s)"),
+            ViewAs::Timestamp(TimeUnit::Millisecond) => f.write_str("Timestamp (ms)"),
+            ViewAs::Timestamp(TimeUnit::Microsecond) => f.write_str("Timestamp (µs)"),
+            ViewAs::Timestamp(TimeUnit::Nanosecond) => f.write_str("Timestamp (ns)"),
+            ViewAs::Json => f.write_str("JSON"),
+            ViewAs::Text => f.write_str("Text"),
+        }
+    }
+}
+
+/// `batch` with the columns of `casts` cast for display; the rest are kept as read.
+pub fn apply_casts(batch: &RecordBatch, casts: &ColumnCasts) -> Result<RecordBatch, ViewerError> {
+    if casts.is_empty() {
+        return Ok(batch.clone());
+    }
+
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    for (index, (field, values)) in schema.fields().iter().zip(batch.columns()).enumerate() {
+        match casts.get(&index) {
+            Some(view) => {
+                let (field, values) = view.cast(field, values)?;
+                fields.push(field);
+                columns.push(values);
+            }
+            None => {
+                fields.push(field.as_ref().clone());
+                columns.push(values.clone());
+            }
+        }
+    }
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{BinaryArray, Int64Array};
+
+    use crate::intern::Cell;
+    use crate::preview::batches_to_rows;
+
+    #[test]
+    fn epochs_and_bytes_are_shown_as_timestamps_and_text() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("at", DataType::Int64, false),
+            Field::new("payload", DataType::Binary, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1_700_000_000_000])),
+                Arc::new(BinaryArray::from(vec![&br#"{"a":1}"#[..]])),
+            ],
+        )
+        .expect("record batch should build");
+        assert_eq!(
+            ViewAs::choices(&DataType::Int64)[1],
+            ViewAs::Timestamp(TimeUnit::Millisecond)
+        );
+        assert!(ViewAs::choices(&DataType::Float64).is_empty());
+
+        let casts = ColumnCasts::from([
+            (0, ViewAs::Timestamp(TimeUnit::Millisecond)),
+            (1, ViewAs::Json),
+        ]);
+        let cast = apply_casts(&batch, &casts).expect("casts should apply");
+
+        assert_eq!(
+            cast.schema().field(1).metadata().get(EXTENSION_NAME_KEY),
+            Some(&ARROW_JSON.to_string())
+        );
+        let rows = batches_to_rows(&[cast], usize::MAX, 0).rows;
+        assert_eq!(
+            rows[0],
+            ["2023-11-14T22:13:20Z", r#"{"a":1}"#].map(Cell::from)
+        );
+    }
+}
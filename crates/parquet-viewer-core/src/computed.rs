@@ -0,0 +1,470 @@
+use std::iter::Peekable;
+use std::str::CharIndices;
+use std::sync::Arc;
+
+use arrow::array::{
+    new_null_array, Array, ArrayRef, AsArray, Float64Array, Int64Array, StringArray,
+};
+use arrow::compute::kernels::{length::length, numeric};
+use arrow::compute::{cast, unary};
+use arrow::datatypes::{DataType, Field, Float64Type, Int64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::ViewerError;
+
+/// A column shown next to those of the file, computed from them for each batch
+/// read, e.g. `total = price * qty`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComputedColumn {
+    pub name: String,
+    /// The expression as written.
+    pub expression: String,
+    expr: Expr,
+    data_type: DataType,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Column(String),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+    Call(Function, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Function {
+    Length,
+    Upper,
+    Lower,
+    Abs,
+}
+
+/// Functions an expression can call, for messages and help text.
+pub const FUNCTIONS: &str = "length, upper, lower, abs";
+
+impl ComputedColumn {
+    /// Read a definition written as `name = expression`. Expressions combine
+    /// columns, numbers and quoted strings with `+ - * / %` and parentheses,
+    /// and call the [`FUNCTIONS`]; names with spaces go in backquotes.
+    ///
+    /// The expression is checked against `schema`, the columns of the file.
+    pub fn parse(definition: &str, schema: &Schema) -> Result<ComputedColumn, ViewerError> {
+        let (name, expression) = definition.split_once('=').ok_or_else(|| {
+            invalid(format!(
+                "write the column as name = expression, not \"{definition}\""
+            ))
+        })?;
+        let (name, expression) = (name.trim(), expression.trim());
+        if name.is_empty() {
+            return Err(invalid("the column needs a name".to_string()));
+        }
+        if schema.field_with_name(name).is_ok() {
+            return Err(invalid(format!(
+                "the file already has a column named {name}"
+            )));
+        }
+
+        let mut parser = Parser::new(expression);
+        let expr = parser.expression()?;
+        if let Some(token) = parser.next()? {
+            return Err(invalid(format!("unexpected {token:?} in \"{expression}\"")));
+        }
+        // Evaluating over no rows finds unknown columns and mismatched types up front.
+        let data_type = expr
+            .evaluate(&RecordBatch::new_empty(Arc::new(schema.clone())))
+            .map_err(|error| match error {
+                ViewerError::FormatFailed(error) => invalid(error.to_string()),
+                error => error,
+            })?
+            .data_type()
+            .clone();
+        Ok(ComputedColumn {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            expr,
+            data_type,
+        })
+    }
+
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// The definition as [`ComputedColumn::parse`] reads it.
+    pub fn definition(&self) -> String {
+        format!("{} = {}", self.name, self.expression)
+    }
+
+    /// The column's values for the rows of `batch`; rows the expression fails
+    /// on, e.g. a remainder of division by zero, leave the whole batch null.
+    pub fn evaluate(&self, batch: &RecordBatch) -> ArrayRef {
+        self.expr.evaluate(batch).unwrap_or_else(|error| {
+            tracing::warn!(%error, column = self.name, "computed column failed");
+            new_null_array(&self.data_type, batch.num_rows())
+        })
+    }
+}
+
+/// `batch` with the values of `computed` appended as extra columns.
+pub fn append_computed(
+    batch: &RecordBatch,
+    computed: &[ComputedColumn],
+) -> Result<RecordBatch, ViewerError> {
+    if computed.is_empty() {
+        return Ok(batch.clone());
+    }
+
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone())
+        .collect();
+    let mut columns = batch.columns().to_vec();
+    for column in computed {
+        fields.push(Field::new(&column.name, column.data_type.clone(), true));
+        columns.push(column.evaluate(batch));
+    }
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+fn invalid(message: String) -> ViewerError {
+    ViewerError::InvalidExpression(message)
+}
+
+impl Expr {
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef, ViewerError> {
+        let rows = batch.num_rows();
+        Ok(match self {
+            Expr::Column(name) => batch
+                .column_by_name(name)
+                .cloned()
+                .ok_or_else(|| invalid(format!("there is no column named {name}")))?,
+            Expr::Integer(value) => Arc::new(Int64Array::from(vec![*value; rows])),
+            Expr::Float(value) => Arc::new(Float64Array::from(vec![*value; rows])),
+            Expr::Text(value) => Arc::new(StringArray::from(vec![value.as_str(); rows])),
+            Expr::Negate(value) => {
+                let value = numeric_operand(value.evaluate(batch)?, false)?;
+                numeric::neg_wrapping(&value)?
+            }
+            Expr::Binary(left, operator, right) => {
+                let (left, right) = (left.evaluate(batch)?, right.evaluate(batch)?);
+                let float = *operator == Operator::Divide
+                    || !(left.data_type().is_integer() && right.data_type().is_integer());
+                let (left, right) = (
+                    numeric_operand(left, float)?,
+                    numeric_operand(right, float)?,
+                );
+                match operator {
+                    Operator::Add => numeric::add_wrapping(&left, &right)?,
+                    Operator::Subtract => numeric::sub_wrapping(&left, &right)?,
+                    Operator::Multiply => numeric::mul_wrapping(&left, &right)?,
+                    Operator::Divide => numeric::div(&left, &right)?,
+                    Operator::Remainder => numeric::rem(&left, &right)?,
+                }
+            }
+            Expr::Call(function, argument) => {
+                let argument = argument.evaluate(batch)?;
+                match function {
+                    Function::Length => length(&argument)?,
+                    Function::Upper => map_text(&argument, str::to_uppercase)?,
+                    Function::Lower => map_text(&argument, str::to_lowercase)?,
+                    Function::Abs if argument.data_type().is_integer() => {
+                        let values = cast(&argument, &DataType::Int64)?;
+                        Arc::new(unary::<Int64Type, _, Int64Type>(
+                            values.as_primitive(),
+                            i64::wrapping_abs,
+                        ))
+                    }
+                    Function::Abs => {
+                        let values = numeric_operand(argument, true)?;
+                        Arc::new(unary::<Float64Type, _, Float64Type>(
+                            values.as_primitive(),
+                            f64::abs,
+                        ))
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// `values` as 64-bit integers, or floats with `float`, for arithmetic.
+fn numeric_operand(values: ArrayRef, float: bool) -> Result<ArrayRef, ViewerError> {
+    if !values.data_type().is_numeric() {
+        return Err(invalid(format!(
+            "arithmetic needs numbers, not {}",
+            values.data_type()
+        )));
+    }
+    let target = if float {
+        DataType::Float64
+    } else {
+        DataType::Int64
+    };
+    Ok(cast(&values, &target)?)
+}
+
+fn map_text(values: &ArrayRef, map: fn(&str) -> String) -> Result<ArrayRef, ViewerError> {
+    if !matches!(
+        values.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    ) {
+        return Err(invalid(format!(
+            "upper and lower need text, not {}",
+            values.data_type()
+        )));
+    }
+    let values = cast(values, &DataType::Utf8)?;
+    let mapped: StringArray = values
+        .as_string::<i32>()
+        .iter()
+        .map(|text| text.map(map))
+        .collect();
+    Ok(Arc::new(mapped))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Name(String),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Symbol(char),
+}
+
+/// Recursive descent over the tokens of one expression: sums of products of
+/// signed factors.
+struct Parser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    peeked: Option<Option<Token>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            chars: text.char_indices().peekable(),
+            peeked: None,
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, ViewerError> {
+        let mut expr = self.term()?;
+        while let Some(operator) =
+            self.operator(&[('+', Operator::Add), ('-', Operator::Subtract)])?
+        {
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(self.term()?));
+        }
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, ViewerError> {
+        let mut expr = self.factor()?;
+        while let Some(operator) = self.operator(&[
+            ('*', Operator::Multiply),
+            ('/', Operator::Divide),
+            ('%', Operator::Remainder),
+        ])? {
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(self.factor()?));
+        }
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ViewerError> {
+        match self.next()? {
+            Some(Token::Symbol('-')) => Ok(Expr::Negate(Box::new(self.factor()?))),
+            Some(Token::Symbol('(')) => {
+                let expr = self.expression()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(Token::Integer(value)) => Ok(Expr::Integer(value)),
+            Some(Token::Float(value)) => Ok(Expr::Float(value)),
+            Some(Token::Text(value)) => Ok(Expr::Text(value)),
+            Some(Token::Name(name)) if self.peek()? == Some(&Token::Symbol('(')) => {
+                let function = match name.to_ascii_lowercase().as_str() {
+                    "length" => Function::Length,
+                    "upper" => Function::Upper,
+                    "lower" => Function::Lower,
+                    "abs" => Function::Abs,
+                    _ => {
+                        return Err(invalid(format!(
+                            "unknown function {name}; there are {FUNCTIONS}"
+                        )))
+                    }
+                };
+                self.next()?;
+                let argument = self.expression()?;
+                self.expect(')')?;
+                Ok(Expr::Call(function, Box::new(argument)))
+            }
+            Some(Token::Name(name)) => Ok(Expr::Column(name)),
+            Some(token) => Err(invalid(format!(
+                "unexpected {token:?} in \"{}\"",
+                self.text
+            ))),
+            None => Err(invalid(format!("\"{}\" ends too early", self.text))),
+        }
+    }
+
+    fn operator(
+        &mut self,
+        operators: &[(char, Operator)],
+    ) -> Result<Option<Operator>, ViewerError> {
+        let found = match self.peek()? {
+            Some(Token::Symbol(symbol)) => operators
+                .iter()
+                .find(|(candidate, _)| candidate == symbol)
+                .map(|&(_, operator)| operator),
+            _ => None,
+        };
+        if found.is_some() {
+            self.next()?;
+        }
+        Ok(found)
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ViewerError> {
+        match self.next()? {
+            Some(Token::Symbol(found)) if found == symbol => Ok(()),
+            _ => Err(invalid(format!("missing `{symbol}` in \"{}\"", self.text))),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<&Token>, ViewerError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.lex()?);
+        }
+        Ok(self.peeked.as_ref().and_then(Option::as_ref))
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, ViewerError> {
+        match self.peeked.take() {
+            Some(token) => Ok(token),
+            None => self.lex(),
+        }
+    }
+
+    fn lex(&mut self) -> Result<Option<Token>, ViewerError> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        let Some((start, c)) = self.chars.next() else {
+            return Ok(None);
+        };
+        let token = match c {
+            '+' | '-' | '*' | '/' | '%' | '(' | ')' => Token::Symbol(c),
+            '\'' | '"' | '`' => {
+                let end = self
+                    .chars
+                    .find(|&(_, found)| found == c)
+                    .map(|(end, _)| end)
+                    .ok_or_else(|| invalid(format!("unclosed {c} in \"{}\"", self.text)))?;
+                let quoted = self.text[start + 1..end].to_string();
+                if c == '`' {
+                    Token::Name(quoted)
+                } else {
+                    Token::Text(quoted)
+                }
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let end = self.end_of(|c| c.is_ascii_digit() || c == '.');
+                let number = &self.text[start..end];
+                match number.parse::<i64>() {
+                    Ok(value) => Token::Integer(value),
+                    Err(_) => Token::Float(
+                        number
+                            .parse()
+                            .map_err(|_| invalid(format!("{number} is not a number")))?,
+                    ),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let end = self.end_of(|c| c.is_alphanumeric() || c == '_' || c == '.');
+                Token::Name(self.text[start..end].to_string())
+            }
+            c => return Err(invalid(format!("unexpected `{c}` in \"{}\"", self.text))),
+        };
+        Ok(Some(token))
+    }
+
+    /// Consume characters while `keep` holds, returning where they end.
+    fn end_of(&mut self, keep: fn(char) -> bool) -> usize {
+        while self.chars.next_if(|&(_, c)| keep(c)).is_some() {}
+        self.chars
+            .peek()
+            .map_or(self.text.len(), |&(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::intern::Cell;
+    use crate::preview::batches_to_rows;
+
+    #[test]
+    fn computed_columns_are_appended_to_each_batch() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("price", DataType::Float64, false),
+            Field::new("qty", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float64Array::from(vec![2.5, 4.0])),
+                Arc::new(arrow::array::Int32Array::from(vec![4, -3])),
+                Arc::new(StringArray::from(vec!["ab", "Straße"])),
+            ],
+        )
+        .expect("record batch should build");
+
+        let computed = [
+            ComputedColumn::parse("total = price * qty", &schema).expect("should parse"),
+            ComputedColumn::parse("size = length(name) + abs(qty) % 2", &schema)
+                .expect("should parse"),
+            ComputedColumn::parse("shout = upper(`name`)", &schema).expect("should parse"),
+        ];
+        assert_eq!(computed[1].data_type(), &DataType::Int64);
+        let rows = batches_to_rows(
+            &[append_computed(&batch, &computed).expect("columns should compute")],
+            usize::MAX,
+            0,
+        )
+        .rows;
+        assert_eq!(rows[0][3..], ["10.0", "2", "AB"].map(Cell::from));
+        assert_eq!(rows[1][3..], ["-12.0", "8", "STRASSE"].map(Cell::from));
+
+        for bad in [
+            "total price",
+            "total = price * ",
+            "total = missing + 1",
+            "total = name * 2",
+            "total = sqrt(price)",
+            "price = qty",
+        ] {
+            assert!(
+                matches!(
+                    ComputedColumn::parse(bad, &schema),
+                    Err(ViewerError::InvalidExpression(_))
+                ),
+                "{bad} should not parse"
+            );
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use arrow::array::Array;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+
+use crate::footer;
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::ViewerError;
+
+/// What a file must satisfy to pass the `assert` subcommand.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Contract {
+    pub min_rows: Option<usize>,
+    pub max_rows: Option<usize>,
+    /// Top-level columns the file must have.
+    pub require_columns: Vec<String>,
+    /// Top-level columns that must have no nulls; they must exist too.
+    pub non_null: Vec<String>,
+}
+
+/// Every way the file at `path` breaks `contract`, as one line each; empty if it
+/// holds.
+///
+/// Row counts come from the footer, as do null counts where every row group
+/// records one; only columns without them are read.
+pub fn check_contract(
+    path: &Path,
+    contract: &Contract,
+    bytes_read: &ReadCounter,
+) -> Result<Vec<String>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let parquet = metadata.metadata();
+    let rows = parquet.file_metadata().num_rows().max(0) as usize;
+    let mut violations = Vec::new();
+
+    if let Some(min) = contract.min_rows.filter(|&min| rows < min) {
+        violations.push(format!("{rows} rows, fewer than the minimum of {min}"));
+    }
+    if let Some(max) = contract.max_rows.filter(|&max| rows > max) {
+        violations.push(format!("{rows} rows, more than the maximum of {max}"));
+    }
+
+    let schema = metadata.schema();
+    for name in contract.require_columns.iter().chain(&contract.non_null) {
+        if schema.column_with_name(name).is_none()
+            && !violations.contains(&format!("missing column {name}"))
+        {
+            violations.push(format!("missing column {name}"));
+        }
+    }
+
+    for name in &contract.non_null {
+        let Some((root, field)) = schema.column_with_name(name) else {
+            continue;
+        };
+        let from_statistics = StatisticsConverter::try_new(
+            field.name(),
+            schema,
+            parquet.file_metadata().schema_descr(),
+        )
+        .ok()
+        .and_then(|converter| converter.row_group_null_counts(parquet.row_groups()).ok())
+        .filter(|counts| counts.null_count() == 0)
+        .map(|counts| counts.values().iter().sum::<u64>());
+        let nulls = match from_statistics {
+            Some(nulls) => nulls,
+            None => {
+                let builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+                    CountingFile::open(path, bytes_read)?,
+                    metadata.clone(),
+                );
+                let projection = ProjectionMask::roots(builder.parquet_schema(), [root]);
+                let mut nulls = 0;
+                for batch in builder.with_projection(projection).build()? {
+                    nulls += batch?.column(0).null_count() as u64;
+                }
+                nulls
+            }
+        };
+        if nulls > 0 {
+            violations.push(format!("{name} has {nulls} null(s)"));
+        }
+    }
+
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::{EnabledStatistics, WriterProperties};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn violations_are_listed_one_per_check() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("score", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..4)),
+                Arc::new(Int64Array::from(vec![Some(1), None, Some(3), None])),
+            ],
+        )
+        .expect("record batch should build");
+        let write = |statistics| {
+            let file = NamedTempFile::new().expect("temp file should be created");
+            let props = WriterProperties::builder()
+                .set_statistics_enabled(statistics)
+                .build();
+            let mut writer =
+                ArrowWriter::try_new(file.reopen().unwrap(), schema.clone(), Some(props))
+                    .expect("writer should be created");
+            writer.write(&batch).expect("batch should be written");
+            writer.close().expect("writer should close");
+            file
+        };
+
+        let contract = Contract {
+            min_rows: Some(5),
+            max_rows: Some(10),
+            require_columns: vec!["id".to_string(), "name".to_string()],
+            non_null: vec!["id".to_string(), "score".to_string()],
+        };
+        let expected = vec![
+            "4 rows, fewer than the minimum of 5".to_string(),
+            "missing column name".to_string(),
+            "score has 2 null(s)".to_string(),
+        ];
+        for statistics in [EnabledStatistics::Chunk, EnabledStatistics::None] {
+            let file = write(statistics);
+            let violations = check_contract(file.path(), &contract, &ReadCounter::default())
+                .expect("file should be checked");
+            assert_eq!(violations, expected);
+        }
+
+        let file = write(EnabledStatistics::Chunk);
+        let holds = Contract {
+            max_rows: Some(4),
+            non_null: vec!["id".to_string()],
+            ..Contract::default()
+        };
+        assert!(check_contract(file.path(), &holds, &ReadCounter::default())
+            .expect("file should be checked")
+            .is_empty());
+    }
+}
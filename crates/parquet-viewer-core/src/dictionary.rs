@@ -0,0 +1,277 @@
+use std::fs::File;
+use std::path::Path;
+
+use parquet::basic::{ConvertedType, Encoding, LogicalType, Type as PhysicalType};
+use parquet::column::page::Page;
+use parquet::errors::ParquetError;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::schema::types::ColumnDescriptor;
+
+use crate::ViewerError;
+
+/// The dictionary of one column chunk and how its data pages used it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DictionaryChunk {
+    /// Leaf column path, dotted.
+    pub column: String,
+    pub row_group: usize,
+    /// Row groups in the file, for stepping between chunks of the column.
+    pub row_groups: usize,
+    /// Values in the chunk, nulls included.
+    pub value_count: i64,
+    /// Dictionary entries in file order, or `None` if the chunk has no dictionary.
+    pub dictionary: Option<Vec<String>>,
+    pub data_pages: usize,
+    /// Data pages written without the dictionary, which writers switch to once the
+    /// dictionary outgrows its size limit.
+    pub fallback_pages: usize,
+}
+
+impl DictionaryChunk {
+    /// Distinct values in the dictionary.
+    pub fn cardinality(&self) -> Option<usize> {
+        self.dictionary.as_ref().map(Vec::len)
+    }
+
+    /// One line on the dictionary's size and whether the chunk fell back to
+    /// plain encoding.
+    pub fn summary(&self) -> String {
+        let Some(cardinality) = self.cardinality() else {
+            return format!(
+                "No dictionary: all {} data pages are plain encoded",
+                self.data_pages
+            );
+        };
+        let fallback = match self.fallback_pages {
+            0 => format!("all {} data pages use it", self.data_pages),
+            pages => format!(
+                "{pages} of {} data pages fell back to plain encoding",
+                self.data_pages
+            ),
+        };
+        format!(
+            "{cardinality} distinct values for {} values; {fallback}",
+            self.value_count
+        )
+    }
+}
+
+/// Read the dictionary page of `column`, a dotted leaf column path, in
+/// `row_group`, and count the data pages that don't use it.
+pub fn dictionary_chunk(
+    path: &Path,
+    column: &str,
+    row_group: usize,
+) -> Result<DictionaryChunk, ViewerError> {
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+    let metadata = reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+    let missing = || ViewerError::NoColumnChunk {
+        column: column.to_string(),
+        row_group,
+    };
+    let column_index = (0..schema.num_columns())
+        .find(|&index| schema.column(index).path().string() == column)
+        .ok_or_else(missing)?;
+    if row_group >= metadata.num_row_groups() {
+        return Err(missing());
+    }
+    let descriptor = schema.column(column_index);
+
+    let mut chunk = DictionaryChunk {
+        column: column.to_string(),
+        row_group,
+        row_groups: metadata.num_row_groups(),
+        value_count: metadata
+            .row_group(row_group)
+            .column(column_index)
+            .num_values(),
+        dictionary: None,
+        data_pages: 0,
+        fallback_pages: 0,
+    };
+    let mut pages = reader
+        .get_row_group(row_group)?
+        .get_column_page_reader(column_index)?;
+    while let Some(page) = pages.get_next_page()? {
+        match page {
+            Page::DictionaryPage {
+                buf, num_values, ..
+            } => chunk.dictionary = Some(decode_plain(&descriptor, &buf, num_values as usize)?),
+            page => {
+                chunk.data_pages += 1;
+                if !matches!(
+                    page.encoding(),
+                    Encoding::RLE_DICTIONARY | Encoding::PLAIN_DICTIONARY
+                ) {
+                    chunk.fallback_pages += 1;
+                }
+            }
+        }
+    }
+
+    Ok(chunk)
+}
+
+/// Decode `count` plain-encoded values, the encoding of dictionary pages, as
+/// text: strings for UTF-8 columns, hex for other binary values.
+pub(crate) fn decode_plain(
+    descriptor: &ColumnDescriptor,
+    buf: &[u8],
+    count: usize,
+) -> Result<Vec<String>, ParquetError> {
+    let truncated = || ParquetError::EOF("dictionary page ends early".to_string());
+    let fixed = |width: usize| -> Result<Vec<&[u8]>, ParquetError> {
+        let values: Vec<&[u8]> = buf.chunks_exact(width).take(count).collect();
+        if values.len() < count {
+            return Err(truncated());
+        }
+        Ok(values)
+    };
+
+    match descriptor.physical_type() {
+        PhysicalType::BOOLEAN => (0..count)
+            .map(|index| {
+                let byte = buf.get(index / 8).ok_or_else(truncated)?;
+                Ok((byte >> (index % 8) & 1 == 1).to_string())
+            })
+            .collect(),
+        PhysicalType::INT32 => Ok(fixed(4)?
+            .into_iter()
+            .map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()).to_string())
+            .collect()),
+        PhysicalType::INT64 => Ok(fixed(8)?
+            .into_iter()
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()).to_string())
+            .collect()),
+        PhysicalType::FLOAT => Ok(fixed(4)?
+            .into_iter()
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()).to_string())
+            .collect()),
+        PhysicalType::DOUBLE => Ok(fixed(8)?
+            .into_iter()
+            .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap()).to_string())
+            .collect()),
+        PhysicalType::INT96 => Ok(fixed(12)?.into_iter().map(hex).collect()),
+        PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+            let width = usize::try_from(descriptor.type_length())
+                .ok()
+                .filter(|&width| width > 0)
+                .ok_or_else(|| ParquetError::General("invalid fixed length".to_string()))?;
+            Ok(fixed(width)?.into_iter().map(hex).collect())
+        }
+        PhysicalType::BYTE_ARRAY => {
+            let text = is_text(descriptor);
+            let mut values = Vec::with_capacity(count);
+            let mut rest = buf;
+            for _ in 0..count {
+                let (length, tail) = rest.split_first_chunk::<4>().ok_or_else(truncated)?;
+                let length = u32::from_le_bytes(*length) as usize;
+                if tail.len() < length {
+                    return Err(truncated());
+                }
+                let (value, tail) = tail.split_at(length);
+                values.push(if text {
+                    String::from_utf8_lossy(value).into_owned()
+                } else {
+                    hex(value)
+                });
+                rest = tail;
+            }
+            Ok(values)
+        }
+    }
+}
+
+pub(crate) fn is_text(descriptor: &ColumnDescriptor) -> bool {
+    matches!(
+        descriptor.logical_type_ref(),
+        Some(LogicalType::String | LogicalType::Enum | LogicalType::Json)
+    ) || matches!(
+        descriptor.converted_type(),
+        ConvertedType::UTF8 | ConvertedType::ENUM | ConvertedType::JSON
+    )
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn dictionary_values_are_listed_with_their_cardinality() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("city", DataType::Utf8, false),
+            Field::new("id", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["Oslo", "Lima", "Oslo", "Pune"])),
+                Arc::new(Int64Array::from(vec![7, 7, 9, 7])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let city = dictionary_chunk(file.path(), "city", 1).expect("dictionary should load");
+        assert_eq!(
+            city.dictionary,
+            Some(vec!["Oslo".to_string(), "Pune".to_string()])
+        );
+        assert_eq!(city.row_groups, 2);
+        assert_eq!(
+            city.summary(),
+            "2 distinct values for 2 values; all 1 data pages use it"
+        );
+
+        let id = dictionary_chunk(file.path(), "id", 0).expect("dictionary should load");
+        assert_eq!(id.cardinality(), Some(1));
+        assert_eq!(id.dictionary.unwrap(), vec!["7".to_string()]);
+
+        assert!(matches!(
+            dictionary_chunk(file.path(), "city", 2),
+            Err(ViewerError::NoColumnChunk { .. })
+        ));
+    }
+
+    #[test]
+    fn plain_fallback_is_counted() {
+        let schema = Arc::new(Schema::new(vec![Field::new("text", DataType::Utf8, false)]));
+        let text: Vec<String> = (0..2000).map(|v| format!("value-{v:0>40}")).collect();
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(StringArray::from(text))]).unwrap();
+        let props = WriterProperties::builder()
+            .set_dictionary_page_size_limit(1024)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let chunk = dictionary_chunk(file.path(), "text", 0).expect("dictionary should load");
+
+        assert!(chunk.cardinality().unwrap() < 2000);
+        assert!(chunk.fallback_pages > 0);
+        assert!(chunk.summary().contains("fell back to plain encoding"));
+    }
+}
@@ -0,0 +1,311 @@
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaDataReader};
+
+use crate::ViewerError;
+
+/// The library that wrote a file, parsed from the footer's `created_by`, e.g.
+/// `parquet-mr version 1.12.3 (build f8dced18)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Writer {
+    pub application: String,
+    pub version: Option<String>,
+    pub build: Option<String>,
+}
+
+impl Writer {
+    pub fn parse(created_by: &str) -> Writer {
+        let created_by = created_by.trim();
+        let (rest, build) = match created_by
+            .strip_suffix(')')
+            .and_then(|s| s.rsplit_once("(build "))
+        {
+            Some((rest, build)) => (rest.trim_end(), Some(build.trim().to_string())),
+            None => (created_by, None),
+        };
+        match rest.split_once(" version ") {
+            Some((application, version)) => Writer {
+                application: application.trim().to_string(),
+                version: Some(version.trim().to_string()),
+                build,
+            },
+            None => Writer {
+                application: rest.to_string(),
+                version: None,
+                build,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Writer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.application)?;
+        if let Some(version) = &self.version {
+            write!(f, " {version}")?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, " (build {build})")?;
+        }
+        Ok(())
+    }
+}
+
+/// How many of a file's column chunks have some feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub present: usize,
+    pub total: usize,
+}
+
+impl Coverage {
+    fn count<'a>(
+        chunks: impl Iterator<Item = &'a ColumnChunkMetaData>,
+        has: impl Fn(&ColumnChunkMetaData) -> bool,
+    ) -> Coverage {
+        chunks.fold(Coverage::default(), |coverage, chunk| Coverage {
+            present: coverage.present + usize::from(has(chunk)),
+            total: coverage.total + 1,
+        })
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.present == 0 {
+            write!(f, "none of {} column chunks", self.total)
+        } else if self.present == self.total {
+            write!(f, "all {} column chunks", self.total)
+        } else {
+            write!(f, "{} of {} column chunks", self.present, self.total)
+        }
+    }
+}
+
+/// How a file was written: the writer and the optional features it used, which
+/// decide how well readers can skip data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WriterReport {
+    pub created_by: Option<String>,
+    pub writer: Option<Writer>,
+    pub format_version: i32,
+    pub row_groups: usize,
+    pub codecs: Vec<String>,
+    pub encodings: Vec<String>,
+    pub dictionary: Coverage,
+    pub min_max: Coverage,
+    pub null_counts: Coverage,
+    pub column_index: Coverage,
+    pub offset_index: Coverage,
+    pub bloom_filters: Coverage,
+    /// Sort order declared by the first row group, as `column ASC|DESC`.
+    pub sorted_by: Vec<String>,
+}
+
+impl WriterReport {
+    /// Statistics granularity available for pruning.
+    pub fn statistics_level(&self) -> &'static str {
+        if self.column_index.present > 0 {
+            "page"
+        } else if self.min_max.present > 0 {
+            "column chunk"
+        } else {
+            "none"
+        }
+    }
+
+    /// The report as readable lines, shared by the `doctor` subcommand and the UI.
+    pub fn lines(&self) -> Vec<String> {
+        let writer = self.writer.as_ref().map_or_else(
+            || "unknown (no created_by recorded)".to_string(),
+            Writer::to_string,
+        );
+        let sorted_by = if self.sorted_by.is_empty() {
+            "not declared".to_string()
+        } else {
+            self.sorted_by.join(", ")
+        };
+        vec![
+            format!("Written by: {writer}"),
+            format!("Format version: {}", self.format_version),
+            format!("Row groups: {}", self.row_groups),
+            format!("Compression: {}", self.codecs.join(", ")),
+            format!("Encodings: {}", self.encodings.join(", ")),
+            format!("Dictionary pages: {}", self.dictionary),
+            format!(
+                "Statistics: {} level; min/max in {}, null counts in {}",
+                self.statistics_level(),
+                self.min_max,
+                self.null_counts
+            ),
+            format!(
+                "Page index: column index in {}, offset index in {}",
+                self.column_index, self.offset_index
+            ),
+            format!("Bloom filters: {}", self.bloom_filters),
+            format!("Sorted by: {sorted_by}"),
+        ]
+    }
+}
+
+pub fn writer_report(path: &Path) -> Result<WriterReport, ViewerError> {
+    let metadata = ParquetMetaDataReader::new().parse_and_finish(&File::open(path)?)?;
+    let file_metadata = metadata.file_metadata();
+    let chunks = || {
+        metadata
+            .row_groups()
+            .iter()
+            .flat_map(|group| group.columns())
+    };
+
+    let mut codecs = Vec::new();
+    let mut encodings = Vec::new();
+    for chunk in chunks() {
+        let codec = chunk.compression().to_string();
+        if !codecs.contains(&codec) {
+            codecs.push(codec);
+        }
+        for encoding in chunk.encodings() {
+            let encoding = encoding.to_string();
+            if !encodings.contains(&encoding) {
+                encodings.push(encoding);
+            }
+        }
+    }
+    encodings.sort();
+
+    let schema = file_metadata.schema_descr();
+    let sorted_by = metadata
+        .row_groups()
+        .first()
+        .and_then(|group| group.sorting_columns())
+        .map(|columns| {
+            columns
+                .iter()
+                .map(|sorting| {
+                    let name = usize::try_from(sorting.column_idx)
+                        .ok()
+                        .filter(|&index| index < schema.num_columns())
+                        .map_or_else(
+                            || format!("column {}", sorting.column_idx),
+                            |index| schema.column(index).path().string(),
+                        );
+                    let order = if sorting.descending { "DESC" } else { "ASC" };
+                    format!("{name} {order}")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let created_by = file_metadata.created_by().map(str::to_string);
+    Ok(WriterReport {
+        writer: created_by.as_deref().map(Writer::parse),
+        created_by,
+        format_version: file_metadata.version(),
+        row_groups: metadata.num_row_groups(),
+        codecs,
+        encodings,
+        dictionary: Coverage::count(chunks(), |chunk| chunk.dictionary_page_offset().is_some()),
+        min_max: Coverage::count(chunks(), |chunk| {
+            chunk
+                .statistics()
+                .is_some_and(|stats| stats.min_bytes_opt().is_some())
+        }),
+        null_counts: Coverage::count(chunks(), |chunk| {
+            chunk
+                .statistics()
+                .is_some_and(|stats| stats.null_count_opt().is_some())
+        }),
+        column_index: Coverage::count(chunks(), |chunk| chunk.column_index_offset().is_some()),
+        offset_index: Coverage::count(chunks(), |chunk| chunk.offset_index_offset().is_some()),
+        bloom_filters: Coverage::count(chunks(), |chunk| chunk.bloom_filter_offset().is_some()),
+        sorted_by,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::metadata::SortingColumn;
+    use parquet::file::properties::{EnabledStatistics, WriterProperties};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn created_by_is_split_into_application_version_and_build() {
+        assert_eq!(
+            Writer::parse(
+                "parquet-mr version 1.12.3 (build f8dced182c4c1fbdec6ccb3185537b5a01e6ed6b)"
+            ),
+            Writer {
+                application: "parquet-mr".to_string(),
+                version: Some("1.12.3".to_string()),
+                build: Some("f8dced182c4c1fbdec6ccb3185537b5a01e6ed6b".to_string()),
+            }
+        );
+        let duckdb = Writer::parse("DuckDB");
+        assert_eq!(duckdb.application, "DuckDB");
+        assert_eq!(duckdb.version, None);
+        assert_eq!(
+            Writer::parse("parquet-cpp-arrow version 14.0.1").to_string(),
+            "parquet-cpp-arrow 14.0.1"
+        );
+    }
+
+    #[test]
+    fn report_describes_the_writer_features_used() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["a", "b", "a"])),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_statistics_enabled(EnabledStatistics::Chunk)
+            .set_column_dictionary_enabled("id".into(), false)
+            .set_column_bloom_filter_enabled("name".into(), true)
+            .set_sorting_columns(Some(vec![SortingColumn {
+                column_idx: 0,
+                descending: false,
+                nulls_first: false,
+            }]))
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let report = writer_report(file.path()).expect("report should load");
+
+        assert_eq!(report.writer.as_ref().unwrap().application, "parquet-rs");
+        assert_eq!(report.row_groups, 1);
+        assert_eq!(
+            report.dictionary,
+            Coverage {
+                present: 1,
+                total: 2
+            }
+        );
+        assert_eq!(report.bloom_filters.to_string(), "1 of 2 column chunks");
+        assert_eq!(report.min_max.to_string(), "all 2 column chunks");
+        assert_eq!(report.column_index.present, 0);
+        assert_eq!(report.statistics_level(), "column chunk");
+        assert_eq!(report.sorted_by, vec!["id ASC".to_string()]);
+        assert!(report
+            .lines()
+            .contains(&"Bloom filters: 1 of 2 column chunks".to_string()));
+    }
+}
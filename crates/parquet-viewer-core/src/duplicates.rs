@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::aggregate::scan_rows;
+use crate::footer;
+use crate::metrics::ReadCounter;
+use crate::placeholders::placeholders;
+use crate::progress::Progress;
+use crate::render;
+use crate::ViewerError;
+
+/// A key shared by more than one row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateKey {
+    /// Cell text of each key column, `null` for nulls, as the table shows them.
+    pub key: Vec<String>,
+    /// File rows with this key, ascending.
+    pub rows: Vec<usize>,
+}
+
+/// The outcome of a duplicate scan.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Duplicates {
+    /// Columns the rows were compared on.
+    pub columns: Vec<String>,
+    /// Keys with more than one row, most copies first.
+    pub keys: Vec<DuplicateKey>,
+    pub scanned_rows: usize,
+}
+
+impl Duplicates {
+    /// Rows whose key is shared with another row.
+    pub fn duplicated_rows(&self) -> usize {
+        self.keys.iter().map(|key| key.rows.len()).sum()
+    }
+
+    /// Rows that could be dropped while keeping one row per key.
+    pub fn extra_rows(&self) -> usize {
+        self.duplicated_rows() - self.keys.len()
+    }
+
+    /// The duplicated rows with each key's rows next to each other, in the order
+    /// of [`Duplicates::keys`], for showing them as the table's row order.
+    pub fn rows(&self) -> Vec<usize> {
+        self.keys
+            .iter()
+            .flat_map(|key| key.rows.iter().copied())
+            .collect()
+    }
+
+    /// One line for the panel's title, e.g. `12 of 40 rows share 5 keys (7 extra copies)`.
+    pub fn summary(&self) -> String {
+        if self.keys.is_empty() {
+            return format!("No duplicates in {} rows", self.scanned_rows);
+        }
+        format!(
+            "{} of {} rows share {} key{} ({} extra cop{})",
+            self.duplicated_rows(),
+            self.scanned_rows,
+            self.keys.len(),
+            if self.keys.len() == 1 { "" } else { "s" },
+            self.extra_rows(),
+            if self.extra_rows() == 1 { "y" } else { "ies" }
+        )
+    }
+}
+
+/// Find the rows of the file sharing the values of `columns` with another row;
+/// with no columns, rows are compared on every column. Columns the file doesn't
+/// have are ignored. `progress` counts row groups.
+pub fn find_duplicates(
+    path: &Path,
+    columns: &[String],
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Duplicates, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let schema = metadata.schema().clone();
+
+    let mut roots: Vec<usize> = if columns.is_empty() {
+        (0..schema.fields().len()).collect()
+    } else {
+        columns
+            .iter()
+            .filter_map(|column| {
+                schema
+                    .fields()
+                    .iter()
+                    .position(|field| field.name() == column)
+            })
+            .collect()
+    };
+    roots.sort_unstable();
+    roots.dedup();
+
+    // Rows are compared on the text cells show, so that duplicates look the same
+    // in the table; the first row of each key is kept apart so unique keys don't
+    // allocate.
+    let renderers = render::registry().column_renderers(&schema);
+    let mut seen: HashMap<Vec<String>, (usize, Vec<usize>)> = HashMap::new();
+    let mut row = 0;
+    scan_rows(
+        path,
+        &metadata,
+        &roots,
+        None,
+        bytes_read,
+        progress,
+        |batch| {
+            for index in 0..batch.num_rows() {
+                let key = roots
+                    .iter()
+                    .enumerate()
+                    .map(|(projected, &column)| {
+                        let values = batch.column(projected);
+                        if values.is_null(index) {
+                            Ok(placeholders().null.clone())
+                        } else {
+                            renderers.cell_text(column, values.as_ref(), index)
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                seen.entry(key)
+                    .and_modify(|(_, more)| more.push(row))
+                    .or_insert((row, Vec::new()));
+                row += 1;
+            }
+            Ok(())
+        },
+    )?;
+
+    let mut keys: Vec<DuplicateKey> = seen
+        .into_iter()
+        .filter(|(_, (_, more))| !more.is_empty())
+        .map(|(key, (first, more))| DuplicateKey {
+            key,
+            rows: std::iter::once(first).chain(more).collect(),
+        })
+        .collect();
+    keys.sort_by(|a, b| {
+        b.rows
+            .len()
+            .cmp(&a.rows.len())
+            .then_with(|| a.rows[0].cmp(&b.rows[0]))
+    });
+
+    Ok(Duplicates {
+        columns: roots
+            .iter()
+            .map(|&column| schema.field(column).name().clone())
+            .collect(),
+        keys,
+        scanned_rows: row,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn duplicates_are_found_across_row_groups_by_row_or_key() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("email", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 1, 3, 4, 1])),
+                Arc::new(StringArray::from(vec![
+                    Some("a@x"),
+                    Some("b@x"),
+                    Some("a@x"),
+                    None,
+                    None,
+                    Some("c@x"),
+                ])),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(4)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let counter = ReadCounter::default();
+
+        let whole_rows = find_duplicates(file.path(), &[], &counter, &Progress::default())
+            .expect("duplicates should be found");
+        assert_eq!(whole_rows.columns, vec!["id", "email"]);
+        assert_eq!(
+            whole_rows.keys,
+            vec![DuplicateKey {
+                key: vec!["1".to_string(), "a@x".to_string()],
+                rows: vec![0, 2],
+            }]
+        );
+        assert_eq!(whole_rows.scanned_rows, 6);
+
+        let by_id = find_duplicates(
+            file.path(),
+            &["id".to_string()],
+            &counter,
+            &Progress::default(),
+        )
+        .expect("duplicates should be found");
+        assert_eq!(by_id.rows(), vec![0, 2, 5]);
+        assert_eq!(by_id.extra_rows(), 2);
+        assert_eq!(by_id.summary(), "3 of 6 rows share 1 key (2 extra copies)");
+
+        let by_email = find_duplicates(
+            file.path(),
+            &["email".to_string()],
+            &counter,
+            &Progress::default(),
+        )
+        .expect("duplicates should be found");
+        assert_eq!(by_email.keys.len(), 2);
+        assert_eq!(by_email.keys[1].key, vec!["null"]);
+        assert_eq!(by_email.keys[1].rows, vec![3, 4]);
+    }
+}
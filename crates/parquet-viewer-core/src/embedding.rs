@@ -0,0 +1,168 @@
+use arrow::array::{Array, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::error::ArrowError;
+
+use crate::cell_text;
+use crate::render::CellRenderer;
+
+/// Leading values shown in a cell's summary.
+const SUMMARY_VALUES: usize = 4;
+
+/// Values per line of the inspector's full vector view.
+const VALUES_PER_LINE: usize = 8;
+
+/// Widest sparkline, in characters; longer vectors are averaged into buckets.
+const SPARKLINE_WIDTH: usize = 64;
+
+pub(crate) const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Whether `field` holds fixed-size float vectors, such as embeddings.
+pub fn is_embedding_column(_schema: &Schema, field: &Field) -> bool {
+    matches!(
+        field.data_type(),
+        DataType::FixedSizeList(item, _)
+            if matches!(item.data_type(), DataType::Float16 | DataType::Float32 | DataType::Float64)
+    )
+}
+
+/// Summarizes vectors as their dimension, L2 norm and first values in cells, and
+/// shows them in full with a sparkline in the inspector.
+pub struct EmbeddingRenderer;
+
+/// One vector's values, as text in their own precision and as numbers.
+struct Vector {
+    text: Vec<String>,
+    values: Vec<f64>,
+}
+
+impl Vector {
+    fn read(array: &dyn Array, row: usize) -> Result<Option<Vector>, ArrowError> {
+        let Some(lists) = array.as_fixed_size_list_opt() else {
+            return Ok(None);
+        };
+        let items = lists.value(row);
+        let text = (0..items.len())
+            .map(|index| cell_text(items.as_ref(), index))
+            .collect::<Result<_, _>>()?;
+        let numbers = cast(items.as_ref(), &DataType::Float64)?;
+        let values = numbers
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect();
+        Ok(Some(Vector { text, values }))
+    }
+
+    fn norm(&self) -> f64 {
+        self.values
+            .iter()
+            .map(|value| value * value)
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn summary(&self) -> String {
+        let mut shown = self.text[..self.text.len().min(SUMMARY_VALUES)].join(", ");
+        if self.text.len() > SUMMARY_VALUES {
+            shown.push_str(", …");
+        }
+        format!(
+            "dim {} · norm {:.3} · [{shown}]",
+            self.values.len(),
+            self.norm()
+        )
+    }
+}
+
+impl CellRenderer for EmbeddingRenderer {
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        match Vector::read(array, row)? {
+            Some(vector) => Ok(vector.summary()),
+            None => cell_text(array, row),
+        }
+    }
+
+    fn inspect(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        let Some(vector) = Vector::read(array, row)? else {
+            return cell_text(array, row);
+        };
+        let mut lines = vec![vector.summary(), sparkline(&vector.values), String::new()];
+        for (line, chunk) in vector.text.chunks(VALUES_PER_LINE).enumerate() {
+            lines.push(format!(
+                "{:>5}: {}",
+                line * VALUES_PER_LINE,
+                chunk.join(", ")
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Values drawn as block characters from lowest to highest, averaged into at most
+/// [`SPARKLINE_WIDTH`] buckets.
+pub fn sparkline(values: &[f64]) -> String {
+    let values: Vec<f64> = values
+        .iter()
+        .copied()
+        .filter(|value| value.is_finite())
+        .collect();
+    if values.is_empty() {
+        return String::new();
+    }
+    let bucket = values.len().div_ceil(SPARKLINE_WIDTH);
+    let means: Vec<f64> = values
+        .chunks(bucket)
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect();
+    let low = means.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = means.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+    means
+        .iter()
+        .map(|mean| {
+            if high > low {
+                SPARK_LEVELS[((mean - low) / (high - low) * top).round() as usize]
+            } else {
+                SPARK_LEVELS[SPARK_LEVELS.len() / 2]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{FixedSizeListArray, Float32Array};
+
+    #[test]
+    fn embeddings_are_summarized_in_cells_and_listed_in_full_when_inspected() {
+        let values = Float32Array::from((0..10).map(|v| v as f32 * 0.5).collect::<Vec<_>>());
+        let item = Arc::new(Field::new("item", DataType::Float32, true));
+        let vectors = FixedSizeListArray::new(item.clone(), 10, Arc::new(values), None);
+        let field = Field::new("embedding", DataType::FixedSizeList(item, 10), true);
+
+        assert!(is_embedding_column(&Schema::empty(), &field));
+        assert_eq!(
+            EmbeddingRenderer.render(&vectors, 0).unwrap(),
+            "dim 10 · norm 8.441 · [0.0, 0.5, 1.0, 1.5, …]"
+        );
+
+        let inspected = EmbeddingRenderer.inspect(&vectors, 0).unwrap();
+        let lines: Vec<&str> = inspected.lines().collect();
+        assert_eq!(lines[1], "▁▂▃▃▄▅▆▆▇█");
+        assert_eq!(lines[3], "    0: 0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5");
+        assert_eq!(lines[4], "    8: 4.0, 4.5");
+    }
+
+    #[test]
+    fn long_vectors_are_bucketed_into_the_sparkline() {
+        let values: Vec<f64> = (0..1000).map(f64::from).collect();
+
+        assert_eq!(sparkline(&values).chars().count(), 63);
+        assert_eq!(sparkline(&[1.0, 1.0]), "▅▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+}
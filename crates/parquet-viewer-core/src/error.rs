@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Everything that can go wrong while reading, filtering or writing Parquet files.
+#[derive(Debug, Error)]
+pub enum ViewerError {
+    #[error("failed to open parquet file: {0}")]
+    OpenFailed(#[from] std::io::Error),
+
+    #[error("failed to read parquet batches: {0}")]
+    ReadFailed(#[from] parquet::errors::ParquetError),
+
+    #[error("failed to format parquet preview: {0}")]
+    FormatFailed(#[from] arrow::error::ArrowError),
+
+    #[error("output path must differ from the input file: {}", .0.display())]
+    SameInputOutput(PathBuf),
+
+    #[error("input schemas are incompatible:\n  {}", .0.join("\n  "))]
+    SchemaMismatch(Vec<String>),
+
+    #[error("{0} column chunk(s) failed verification")]
+    VerificationFailed(usize),
+
+    #[error("{0} check(s) failed")]
+    ContractViolated(usize),
+
+    #[error("no column chunk for {column} in row group {row_group}")]
+    NoColumnChunk { column: String, row_group: usize },
+
+    #[error("{} has no column named {column}", .path.display())]
+    NoSuchColumn { column: String, path: PathBuf },
+
+    #[error("invalid regular expression: {0}")]
+    InvalidPattern(#[from] regex::Error),
+
+    #[error("invalid filter: {0}")]
+    InvalidFilter(String),
+
+    #[error("invalid highlight rule: {0}")]
+    InvalidRule(String),
+
+    #[error("pasted text is not a table: {0}")]
+    InvalidPaste(String),
+
+    #[error("invalid computed column: {0}")]
+    InvalidExpression(String),
+
+    #[error("invalid value: {0}")]
+    InvalidValue(String),
+
+    #[error("failed to write spreadsheet: {0}")]
+    SpreadsheetFailed(#[from] rust_xlsxwriter::XlsxError),
+
+    #[error("cancelled")]
+    Cancelled,
+}
+
+impl ViewerError {
+    /// Stable name of the kind of error, e.g. `file_missing`, for scripts.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ViewerError::OpenFailed(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                "file_missing"
+            }
+            ViewerError::OpenFailed(_) => "io",
+            ViewerError::ReadFailed(_) => "corrupt_file",
+            ViewerError::VerificationFailed(_) | ViewerError::NoColumnChunk { .. } => {
+                "verification_failed"
+            }
+            ViewerError::SchemaMismatch(_) => "schema_mismatch",
+            ViewerError::InvalidFilter(_) | ViewerError::InvalidPattern(_) => "invalid_filter",
+            ViewerError::NoSuchColumn { .. } => "no_such_column",
+            ViewerError::SameInputOutput(_) => "invalid_arguments",
+            ViewerError::ContractViolated(_) => "contract_violated",
+            ViewerError::Cancelled => "cancelled",
+            ViewerError::FormatFailed(_)
+            | ViewerError::InvalidRule(_)
+            | ViewerError::InvalidPaste(_)
+            | ViewerError::InvalidExpression(_)
+            | ViewerError::InvalidValue(_)
+            | ViewerError::SpreadsheetFailed(_) => "error",
+        }
+    }
+
+    /// Exit code of the command line for this error. Codes never change meaning,
+    /// so scripts can branch on them; 2 is left to usage errors, as clap reports them.
+    pub fn exit_code(&self) -> u8 {
+        match self.kind() {
+            "file_missing" => 3,
+            "io" => 4,
+            "corrupt_file" => 5,
+            "verification_failed" => 6,
+            "schema_mismatch" => 7,
+            "invalid_filter" => 8,
+            "no_such_column" => 9,
+            "invalid_arguments" => 10,
+            "contract_violated" => 11,
+            "cancelled" => 130,
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io;
+
+    #[test]
+    fn errors_map_to_stable_kinds_and_exit_codes() {
+        let missing = ViewerError::from(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!((missing.kind(), missing.exit_code()), ("file_missing", 3));
+        let denied = ViewerError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!((denied.kind(), denied.exit_code()), ("io", 4));
+
+        let corrupt = ViewerError::from(parquet::errors::ParquetError::General(
+            "Invalid Parquet file. Corrupt footer".to_string(),
+        ));
+        assert_eq!(corrupt.exit_code(), 5);
+        assert_eq!(ViewerError::SchemaMismatch(Vec::new()).exit_code(), 7);
+        assert_eq!(ViewerError::InvalidFilter(String::new()).exit_code(), 8);
+        assert_eq!(ViewerError::ContractViolated(1).exit_code(), 11);
+        assert_eq!(ViewerError::Cancelled.exit_code(), 130);
+        assert_eq!(ViewerError::InvalidPaste(String::new()).exit_code(), 1);
+    }
+}
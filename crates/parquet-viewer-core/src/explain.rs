@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use crate::metrics::format_bytes;
+use crate::pushdown::RowGroupPlan;
+
+/// How a filter scan evaluated one filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterCheck {
+    /// Min/max statistics ruled out row groups and pages before rows were checked.
+    Statistics,
+    /// A pattern run inside the Parquet reader, so rows it rejected were never decoded.
+    Pushdown,
+    /// Checked against every decoded row.
+    PerRow,
+    /// The file has no such column.
+    Ignored,
+}
+
+impl FilterCheck {
+    pub fn describe(self) -> &'static str {
+        match self {
+            FilterCheck::Statistics => "pruned with min/max statistics, then checked per row",
+            FilterCheck::Pushdown => "evaluated inside the Parquet reader",
+            FilterCheck::PerRow => "checked on every decoded row; statistics can't prune it",
+            FilterCheck::Ignored => "ignored; the file has no such column",
+        }
+    }
+}
+
+/// What a filter scan did: what the statistics ruled out before decoding and
+/// what the rest cost, to tell whether a file supports efficient filtering.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// Every row group and what the statistics left of it.
+    pub row_groups: Vec<RowGroupPlan>,
+    /// Each filter as the query bar writes it, and how it was evaluated.
+    pub filters: Vec<(String, FilterCheck)>,
+    pub page_index: bool,
+    /// Reading the statistics and deciding what to decode.
+    pub planning: Duration,
+    /// Decoding what was left, evaluating the filters on it and sorting.
+    pub scanning: Duration,
+    pub bytes_read: u64,
+    pub rows_kept: usize,
+}
+
+impl ScanReport {
+    /// Row groups with no rows left to decode.
+    pub fn row_groups_skipped(&self) -> usize {
+        self.row_groups
+            .iter()
+            .filter(|plan| plan.rows_to_read == 0)
+            .count()
+    }
+
+    /// One line each on row groups, pages, rows, time and bytes, e.g.
+    /// `Row groups: 3 of 12 read, 9 ruled out by statistics`.
+    pub fn summary(&self) -> Vec<String> {
+        let sum =
+            |count: fn(&RowGroupPlan) -> usize| self.row_groups.iter().map(count).sum::<usize>();
+        let skipped = self.row_groups_skipped();
+        let pages = if self.page_index {
+            format!(
+                "Pages: {} of {} data pages of the filtered columns skipped with the page index",
+                sum(|plan| plan.pages_skipped),
+                sum(|plan| plan.pages)
+            )
+        } else {
+            "Pages: none skipped; the file has no page index".to_string()
+        };
+        vec![
+            format!(
+                "Row groups: {} of {} read, {skipped} ruled out by statistics",
+                self.row_groups.len() - skipped,
+                self.row_groups.len()
+            ),
+            pages,
+            format!(
+                "Rows: {} of {} decoded, {} kept",
+                sum(|plan| plan.rows_to_read),
+                sum(|plan| plan.rows),
+                self.rows_kept
+            ),
+            format!(
+                "Time: {:.1} ms planning, {:.1} ms decoding and evaluating",
+                self.planning.as_secs_f64() * 1000.0,
+                self.scanning.as_secs_f64() * 1000.0
+            ),
+            format!("Read: {}", format_bytes(self.bytes_read)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cast::ColumnCasts;
+    use crate::filter::{explain_row_order, ValueFilter};
+    use crate::metrics::ReadCounter;
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+    use crate::progress::Progress;
+
+    #[test]
+    fn explain_reports_what_statistics_ruled_out() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let filter = |column: &str, value: &str| ValueFilter {
+            column: column.to_string(),
+            value: value.to_string(),
+            exclude: false,
+            regex: false,
+        };
+        let counter = ReadCounter::default();
+
+        let (rows, report) = explain_row_order(
+            file.path(),
+            &[filter("id", "3"), filter("missing", "x")],
+            None,
+            &ColumnCasts::new(),
+            &counter,
+            &Progress::default(),
+        )
+        .expect("scan should succeed");
+        assert_eq!(rows, vec![3]);
+        assert_eq!(report.row_groups_skipped(), 2);
+        assert_eq!(report.rows_kept, 1);
+        assert_eq!(
+            report.filters,
+            [
+                ("id = 3".to_string(), FilterCheck::Statistics),
+                ("missing = x".to_string(), FilterCheck::Ignored),
+            ]
+        );
+        assert!(report.bytes_read > 0);
+        assert_eq!(counter.bytes(), report.bytes_read);
+        let summary = report.summary();
+        assert_eq!(
+            summary[0],
+            "Row groups: 1 of 3 read, 2 ruled out by statistics"
+        );
+        assert_eq!(summary[2], "Rows: 2 of 6 decoded, 1 kept");
+    }
+}
@@ -0,0 +1,305 @@
+use std::fs::File;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::UInt64Array;
+use arrow::compute::{concat_batches, take_record_batch};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+
+use crate::aggregate::scan_rows;
+use crate::footer;
+use crate::intern::Cell;
+use crate::metrics::{format_bytes, ReadCounter};
+use crate::output::ensure_distinct;
+use crate::preview::DataPreview;
+use crate::progress::Progress;
+use crate::rewrite::RewriteOptions;
+use crate::selection::delimited_record;
+use crate::spill::memory_limit;
+use crate::ViewerError;
+
+/// Rows decoded at a time while exporting, which is also how often progress moves.
+const EXPORT_CHUNK_ROWS: usize = 10_000;
+
+/// Write the file rows of `runs`, in order, to `destination` as CSV with the
+/// given columns. A cancelled export removes the partly written file.
+pub fn export_rows(
+    preview: &DataPreview,
+    runs: &[Range<usize>],
+    columns: &[usize],
+    destination: &Path,
+    progress: &Progress,
+) -> Result<(), ViewerError> {
+    ensure_distinct([preview.path.as_path()], destination)?;
+    let write = || -> Result<(), ViewerError> {
+        let mut output = std::io::BufWriter::new(std::fs::File::create(destination)?);
+        let header: Vec<String> = columns
+            .iter()
+            .map(|&column| preview.columns[column].clone())
+            .collect();
+        writeln!(output, "{}", delimited_record(&header, ','))?;
+
+        for run in runs {
+            for start in run.clone().step_by(EXPORT_CHUNK_ROWS) {
+                progress.check()?;
+                let end = (start + EXPORT_CHUNK_ROWS).min(run.end);
+                for row in preview.rows_for_range(start..end)?.rows {
+                    let fields: Vec<Cell> =
+                        columns.iter().map(|&column| row[column].clone()).collect();
+                    writeln!(output, "{}", delimited_record(&fields, ','))?;
+                }
+                progress.advance(end - start);
+            }
+        }
+        output.flush()?;
+        Ok(())
+    };
+
+    let result = write();
+    if matches!(result, Err(ViewerError::Cancelled)) {
+        let _ = std::fs::remove_file(destination);
+    }
+    result
+}
+
+/// Write the file rows `rows`, in the given order, and the top-level `columns`
+/// of the file at `path` to a new Parquet file at `destination`, keeping their
+/// Arrow types. Rows out of file order, as after sorting, are gathered in memory
+/// before they are written. A cancelled export removes the partly written file;
+/// `progress` counts row groups read.
+pub fn export_parquet(
+    path: &Path,
+    rows: &[usize],
+    columns: &[usize],
+    destination: &Path,
+    options: &RewriteOptions,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<(), ViewerError> {
+    ensure_distinct([path], destination)?;
+
+    let write = || -> Result<(), ViewerError> {
+        let metadata = footer::reader_metadata(path, bytes_read)?;
+        let schema = Arc::new(metadata.schema().project(columns)?);
+        // Columns are decoded in file order and put back in the order asked for.
+        let mut roots = columns.to_vec();
+        roots.sort_unstable();
+        let order: Vec<usize> = columns
+            .iter()
+            .filter_map(|column| roots.binary_search(column).ok())
+            .collect();
+
+        let mut writer = ArrowWriter::try_new(
+            File::create(destination)?,
+            schema.clone(),
+            Some(options.writer_properties(None)),
+        )?;
+        let in_file_order = rows.is_sorted();
+        let mut gathered = Vec::new();
+        scan_rows(
+            path,
+            &metadata,
+            &roots,
+            Some(rows),
+            bytes_read,
+            progress,
+            |batch| {
+                let batch = batch.project(&order)?;
+                if in_file_order {
+                    writer.write(&batch)?;
+                } else {
+                    gathered.push(batch);
+                }
+                Ok(())
+            },
+        )?;
+
+        if !in_file_order {
+            let batch = concat_batches(&schema, &gathered)?;
+            writer.write(&in_row_order(&batch, rows)?)?;
+        }
+        writer.close()?;
+        Ok(())
+    };
+
+    let result = write();
+    if matches!(result, Err(ViewerError::Cancelled)) {
+        let _ = std::fs::remove_file(destination);
+    }
+    result
+}
+
+/// Decode the file rows `rows` and the top-level `columns` of the file at
+/// `path` into one batch, with rows and columns in the order given; `progress`
+/// counts row groups read. For bounded exports, which hold every row at once,
+/// so rows outgrowing `--memory-limit` are refused.
+pub(crate) fn read_rows(
+    path: &Path,
+    rows: &[usize],
+    columns: &[usize],
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<RecordBatch, ViewerError> {
+    read_rows_within(path, rows, columns, memory_limit(), bytes_read, progress)
+}
+
+/// [`read_rows`], refusing rows that decode to more than `limit` bytes.
+fn read_rows_within(
+    path: &Path,
+    rows: &[usize],
+    columns: &[usize],
+    limit: Option<usize>,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<RecordBatch, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let schema = Arc::new(metadata.schema().project(columns)?);
+    let mut roots = columns.to_vec();
+    roots.sort_unstable();
+    let order: Vec<usize> = columns
+        .iter()
+        .filter_map(|column| roots.binary_search(column).ok())
+        .collect();
+
+    let mut gathered = Vec::new();
+    let mut held = 0;
+    scan_rows(
+        path,
+        &metadata,
+        &roots,
+        Some(rows),
+        bytes_read,
+        progress,
+        |batch| {
+            held += batch.get_array_memory_size();
+            if let Some(limit) = limit.filter(|&limit| held > limit) {
+                return Err(ViewerError::InvalidValue(format!(
+                    "the rows take more than the {} memory limit",
+                    format_bytes(limit as u64)
+                )));
+            }
+            gathered.push(batch.project(&order)?);
+            Ok(())
+        },
+    )?;
+    in_row_order(&concat_batches(&schema, &gathered)?, rows)
+}
+
+/// `batch`, holding the file rows `rows` in file order, with its rows put in
+/// the order of `rows`.
+fn in_row_order(batch: &RecordBatch, rows: &[usize]) -> Result<RecordBatch, ViewerError> {
+    if rows.is_sorted() {
+        return Ok(batch.clone());
+    }
+    let mut sorted = rows.to_vec();
+    sorted.sort_unstable();
+    let indices: UInt64Array = rows
+        .iter()
+        .filter_map(|row| sorted.binary_search(row).ok())
+        .map(|index| index as u64)
+        .collect();
+    Ok(take_record_batch(batch, &indices)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::preview::load_preview;
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn export_writes_runs_and_removes_cancelled_files() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let preview =
+            load_preview(&file.path().to_path_buf(), 2, false).expect("preview should load");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("export.csv");
+
+        let progress = Progress::default();
+        export_rows(&preview, &[4..6, 0..1], &[1, 0], &destination, &progress)
+            .expect("export should succeed");
+        assert_eq!(
+            std::fs::read_to_string(&destination).unwrap(),
+            "name,id\nname-4,4\nname-5,5\nname-0,0\n"
+        );
+        assert_eq!(progress.done(), 3);
+
+        let cancelled = Progress::default();
+        cancelled.cancel();
+        let result = export_rows(&preview, &[0..3, 3..6], &[0], &destination, &cancelled);
+        assert!(matches!(result, Err(ViewerError::Cancelled)));
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn parquet_export_keeps_row_and_column_order() {
+        use arrow::array::{Int32Array, StringArray};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use parquet::basic::{Compression, ZstdLevel};
+
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("export.parquet");
+        let options = RewriteOptions {
+            compression: Compression::ZSTD(ZstdLevel::default()),
+            ..RewriteOptions::default()
+        };
+
+        export_parquet(
+            file.path(),
+            &[5, 0, 3],
+            &[1, 0],
+            &destination,
+            &options,
+            &ReadCounter::default(),
+            &Progress::default(),
+        )
+        .expect("export should succeed");
+
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(File::open(&destination).unwrap()).unwrap();
+        assert_eq!(
+            reader.metadata().row_group(0).column(0).compression(),
+            options.compression
+        );
+        let batch = reader.build().unwrap().next().unwrap().unwrap();
+        assert_eq!(batch.schema().field(0).name(), "name");
+        let names = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(
+            names,
+            &StringArray::from(vec!["name-5", "name-0", "name-3"])
+        );
+        let ids = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(ids, &Int32Array::from(vec![5, 0, 3]));
+    }
+
+    #[test]
+    fn rows_past_the_memory_limit_are_refused() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let read = |limit| {
+            read_rows_within(
+                file.path(),
+                &[5, 0, 3],
+                &[0],
+                limit,
+                &ReadCounter::default(),
+                &Progress::default(),
+            )
+        };
+
+        assert_eq!(read(None).expect("rows should be read").num_rows(), 3);
+        assert!(matches!(read(Some(1)), Err(ViewerError::InvalidValue(_))));
+    }
+}
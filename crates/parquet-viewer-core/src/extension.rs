@@ -0,0 +1,83 @@
+use arrow::array::{Array, AsArray};
+use arrow::error::ArrowError;
+
+use crate::cell_text;
+use crate::render::CellRenderer;
+
+/// Canonical Arrow extension type of UUIDs, and of Parquet's UUID logical type.
+pub const ARROW_UUID: &str = "arrow.uuid";
+
+/// Canonical Arrow extension type of JSON text, and of Parquet's JSON logical type.
+pub const ARROW_JSON: &str = "arrow.json";
+
+/// Shows 16-byte UUIDs in their canonical `8-4-4-4-12` hex form.
+pub struct UuidRenderer;
+
+impl CellRenderer for UuidRenderer {
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        match array.as_fixed_size_binary_opt() {
+            Some(values) if values.value_length() == 16 => Ok(uuid_text(values.value(row))),
+            _ => cell_text(array, row),
+        }
+    }
+}
+
+fn uuid_text(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(36);
+    for (index, byte) in bytes.iter().enumerate() {
+        if matches!(index, 4 | 6 | 8 | 10) {
+            text.push('-');
+        }
+        text.push_str(&format!("{byte:02x}"));
+    }
+    text
+}
+
+/// Keeps JSON on one line in cells and pretty-prints it in the inspector.
+pub struct JsonRenderer;
+
+impl CellRenderer for JsonRenderer {
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        cell_text(array, row)
+    }
+
+    fn inspect(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        let text = cell_text(array, row)?;
+        Ok(serde_json::from_str::<serde_json::Value>(&text)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{FixedSizeBinaryArray, StringArray};
+
+    #[test]
+    fn uuids_use_the_canonical_form() {
+        let bytes: [u8; 16] = [
+            0x55, 0x0e, 0x84, 0x00, 0xe2, 0x9b, 0x41, 0xd4, 0xa7, 0x16, 0x44, 0x66, 0x55, 0x44,
+            0x00, 0x00,
+        ];
+        let values = FixedSizeBinaryArray::try_from_iter([bytes].into_iter()).unwrap();
+
+        assert_eq!(
+            UuidRenderer.render(&values, 0).unwrap(),
+            "550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn json_is_pretty_printed_only_when_inspected() {
+        let values = StringArray::from(vec![r#"{"a":[1,2]}"#, "not json"]);
+
+        assert_eq!(JsonRenderer.render(&values, 0).unwrap(), r#"{"a":[1,2]}"#);
+        assert_eq!(
+            JsonRenderer.inspect(&values, 0).unwrap(),
+            "{\n  \"a\": [\n    1,\n    2\n  ]\n}"
+        );
+        assert_eq!(JsonRenderer.inspect(&values, 1).unwrap(), "not json");
+    }
+}
@@ -0,0 +1,877 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use arrow::array::{
+    new_empty_array, Array, ArrayRef, AsArray, BooleanArray, Int64Array, RecordBatch,
+};
+use arrow::compute::{concat, filter, not, SortOptions};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::ArrowError;
+use parquet::arrow::arrow_reader::{
+    ArrowPredicate, ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions,
+    ParquetRecordBatchReaderBuilder, RowFilter,
+};
+use parquet::arrow::{is_virtual_column, ProjectionMask, RowNumber};
+use parquet::file::metadata::PageIndexPolicy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::batching;
+use crate::cast::ColumnCasts;
+use crate::explain::{FilterCheck, ScanReport};
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::placeholders::placeholders;
+use crate::progress::Progress;
+use crate::pushdown::{explain_scan, prunes, RowGroupScan};
+use crate::render;
+use crate::spill::{memory_limit, SpillingSort};
+use crate::ViewerError;
+
+/// Keep (or with `exclude`, drop) rows whose cell in `column` displays as `value`.
+///
+/// With `regex`, `value` is a regular expression that only has to match part of
+/// the cell text, and null cells never match.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueFilter {
+    pub column: String,
+    pub value: String,
+    pub exclude: bool,
+    #[serde(default)]
+    pub regex: bool,
+}
+
+impl ValueFilter {
+    /// The compiled pattern of a regex filter; `None` for plain value filters.
+    pub fn pattern(&self) -> Result<Option<Regex>, ViewerError> {
+        Ok(if self.regex {
+            Some(Regex::new(&self.value)?)
+        } else {
+            None
+        })
+    }
+
+    /// Read a filter written the way [`ValueFilter::describe`] writes it, e.g.
+    /// `status = ERROR`, `status != OK` or `message ~ /timed? out/`; the slashes
+    /// around a pattern are optional.
+    pub fn parse(text: &str) -> Result<ValueFilter, ViewerError> {
+        let invalid = |reason: &str| ViewerError::InvalidFilter(format!("{reason}: {text}"));
+        let (index, symbol, exclude, regex) = text
+            .char_indices()
+            .find_map(|(index, _)| {
+                FILTER_OPERATORS
+                    .iter()
+                    .find(|(symbol, ..)| text[index..].starts_with(symbol))
+                    .map(|&(symbol, exclude, regex)| (index, symbol, exclude, regex))
+            })
+            .ok_or_else(|| invalid("no operator such as `=`, `!=` or `~`"))?;
+
+        let column = text[..index].trim();
+        if column.is_empty() {
+            return Err(invalid("no column named"));
+        }
+        let value = text[index + symbol.len()..].trim();
+        let value = match regex {
+            true => value
+                .strip_prefix('/')
+                .and_then(|value| value.strip_suffix('/'))
+                .unwrap_or(value),
+            false => value,
+        };
+        let filter = ValueFilter {
+            column: column.to_string(),
+            value: value.to_string(),
+            exclude,
+            regex,
+        };
+        filter.pattern()?;
+        Ok(filter)
+    }
+
+    pub fn describe(&self) -> String {
+        match (self.regex, self.exclude) {
+            (true, false) => format!("{} ~ /{}/", self.column, self.value),
+            (true, true) => format!("{} !~ /{}/", self.column, self.value),
+            (false, false) => format!("{} = {}", self.column, self.value),
+            (false, true) => format!("{} ≠ {}", self.column, self.value),
+        }
+    }
+}
+
+/// Order rows by the values of `column`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub fn describe(&self) -> String {
+        let direction = if self.descending {
+            "descending"
+        } else {
+            "ascending"
+        };
+        format!("{} {direction}", self.column)
+    }
+}
+
+/// Filter operators with whether they exclude and match a pattern; those that
+/// start another operator come first.
+const FILTER_OPERATORS: [(&str, bool, bool); 5] = [
+    ("!~", true, true),
+    ("~", false, true),
+    ("!=", true, false),
+    ("≠", true, false),
+    ("=", false, false),
+];
+
+/// Filters and sort as the query bar writes them: the filters joined by ` | `,
+/// then `sorted by <column> ascending` or `descending`.
+pub fn describe_query(filters: &[ValueFilter], sort: Option<&SortKey>) -> String {
+    filters
+        .iter()
+        .map(ValueFilter::describe)
+        .chain(sort.map(|sort| format!("sorted by {}", sort.describe())))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Read a query written the way [`describe_query`] writes it; blank text clears
+/// the filters and sort.
+pub fn parse_query(text: &str) -> Result<(Vec<ValueFilter>, Option<SortKey>), ViewerError> {
+    let mut filters = Vec::new();
+    let mut sort = None;
+    for part in text
+        .split(" | ")
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        if !part.to_ascii_lowercase().starts_with("sorted by ") {
+            filters.push(ValueFilter::parse(part)?);
+            continue;
+        }
+        let key = part["sorted by ".len()..].trim();
+        let (column, descending) = match key.rsplit_once(' ') {
+            Some((column, "descending" | "desc")) => (column, true),
+            Some((column, "ascending" | "asc")) => (column, false),
+            _ => (key, false),
+        };
+        sort = Some(SortKey {
+            column: column.trim().to_string(),
+            descending,
+        });
+    }
+    Ok((filters, sort))
+}
+
+/// A [`ValueFilter`] resolved against the file's columns, with its pattern compiled once.
+#[derive(Clone, Debug)]
+struct CompiledFilter {
+    column: usize,
+    value: String,
+    pattern: Option<Regex>,
+    exclude: bool,
+}
+
+impl CompiledFilter {
+    /// Whether a cell passes the filter; `text` is `None` for nulls.
+    fn matches(&self, text: Option<&str>) -> bool {
+        let found = match (&self.pattern, text) {
+            (Some(pattern), Some(text)) => pattern.is_match(text),
+            (Some(_), None) => false,
+            (None, text) => text.unwrap_or(&placeholders().null) == self.value,
+        };
+        found != self.exclude
+    }
+
+    /// Evaluate a regex filter over a whole string column at once, for use as a
+    /// row filter inside the reader.
+    fn mask(&self, values: &dyn Array) -> Result<BooleanArray, ArrowError> {
+        let Some(pattern) = &self.pattern else {
+            return Err(ArrowError::InvalidArgumentError(
+                "only regex filters are evaluated as row filters".to_string(),
+            ));
+        };
+        let found: BooleanArray = match values.data_type() {
+            DataType::Utf8 => values
+                .as_string::<i32>()
+                .iter()
+                .map(|text| Some(text.is_some_and(|text| pattern.is_match(text))))
+                .collect(),
+            DataType::LargeUtf8 => values
+                .as_string::<i64>()
+                .iter()
+                .map(|text| Some(text.is_some_and(|text| pattern.is_match(text))))
+                .collect(),
+            other => {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "regex row filters need a string column, not {other}"
+                )))
+            }
+        };
+
+        if self.exclude {
+            not(&found)
+        } else {
+            Ok(found)
+        }
+    }
+}
+
+/// Filters resolved against the columns of one schema, to check whole batches
+/// of it against the cell text.
+pub(crate) struct BatchFilter {
+    filters: Vec<CompiledFilter>,
+    renderers: render::ColumnRenderers,
+}
+
+impl BatchFilter {
+    /// Resolve `filters` against `schema`; one naming a column it doesn't have
+    /// is [`ViewerError::NoSuchColumn`] for the file at `path`.
+    pub(crate) fn new(
+        filters: &[ValueFilter],
+        schema: &Schema,
+        path: &Path,
+    ) -> Result<BatchFilter, ViewerError> {
+        let filters = filters
+            .iter()
+            .map(|value_filter| {
+                let column = schema
+                    .fields()
+                    .iter()
+                    .position(|field| field.name() == &value_filter.column)
+                    .ok_or_else(|| ViewerError::NoSuchColumn {
+                        column: value_filter.column.clone(),
+                        path: path.to_path_buf(),
+                    })?;
+                Ok(CompiledFilter {
+                    column,
+                    value: value_filter.value.clone(),
+                    pattern: value_filter.pattern()?,
+                    exclude: value_filter.exclude,
+                })
+            })
+            .collect::<Result<_, ViewerError>>()?;
+        Ok(BatchFilter {
+            filters,
+            renderers: render::registry().column_renderers(schema),
+        })
+    }
+
+    /// Which rows of `batch` pass every filter.
+    pub(crate) fn mask(&self, batch: &RecordBatch) -> Result<BooleanArray, ViewerError> {
+        (0..batch.num_rows())
+            .map(|index| -> Result<Option<bool>, ViewerError> {
+                for value_filter in &self.filters {
+                    let values = batch.column(value_filter.column);
+                    let text = if values.is_null(index) {
+                        None
+                    } else {
+                        Some(self.renderers.cell_text(
+                            value_filter.column,
+                            values.as_ref(),
+                            index,
+                        )?)
+                    };
+                    if !value_filter.matches(text.as_deref()) {
+                        return Ok(Some(false));
+                    }
+                }
+                Ok(Some(true))
+            })
+            .collect()
+    }
+}
+
+/// Name of the virtual column carrying each row's index in the file.
+const ROW_NUMBER_COLUMN: &str = "__parquet_viewer_row_number";
+
+/// File row indices that pass every filter, in display order.
+///
+/// Row groups and pages that statistics show can't match are skipped, see
+/// [`plan_scan`](crate::pushdown::plan_scan), and only the filtered and sorted
+/// columns are decoded. Regex filters on string columns run inside the reader as row filters, so rows they reject are never
+/// materialized; other filters are checked against the cell text, including
+/// that of custom cell renderers. Columns in `casts` are filtered and sorted by
+/// the values they are shown as, never by their statistics. Filters and
+/// sort keys naming columns the file doesn't have are ignored, and row groups
+/// that fail to decode are left out rather than failing the whole scan.
+///
+/// `progress` counts scanned row groups; cancelling it stops the scan with
+/// [`ViewerError::Cancelled`].
+pub fn row_order(
+    path: &Path,
+    filters: &[ValueFilter],
+    sort: Option<&SortKey>,
+    casts: &ColumnCasts,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Vec<usize>, ViewerError> {
+    Ok(explain_row_order(path, filters, sort, casts, bytes_read, progress)?.0)
+}
+
+/// [`row_order`], along with a [`ScanReport`] of what the statistics ruled out,
+/// how each filter was evaluated, how long planning and scanning took and how
+/// many bytes the scan read.
+pub fn explain_row_order(
+    path: &Path,
+    filters: &[ValueFilter],
+    sort: Option<&SortKey>,
+    casts: &ColumnCasts,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<(Vec<usize>, ScanReport), ViewerError> {
+    let scan_read = ReadCounter::default();
+    let result = scan_rows(path, filters, sort, casts, &scan_read, progress);
+    bytes_read.add(scan_read.bytes() as usize);
+    let (rows, mut report) = result?;
+    report.bytes_read = scan_read.bytes();
+    report.rows_kept = rows.len();
+    Ok((rows, report))
+}
+
+fn scan_rows(
+    path: &Path,
+    filters: &[ValueFilter],
+    sort: Option<&SortKey>,
+    casts: &ColumnCasts,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<(Vec<usize>, ScanReport), ViewerError> {
+    let started = Instant::now();
+    let file = CountingFile::open(path, bytes_read)?;
+    let row_number =
+        Field::new(ROW_NUMBER_COLUMN, DataType::Int64, false).with_extension_type(RowNumber);
+    let options = ArrowReaderOptions::new()
+        .with_virtual_columns(vec![Arc::new(row_number)])?
+        .with_page_index_policy(PageIndexPolicy::Optional);
+    let metadata = ArrowReaderMetadata::load(&file, options)?;
+    let schema = metadata.schema().clone();
+    let schema_descr = metadata.metadata().file_metadata().schema_descr_ptr();
+
+    let position = |name: &str| {
+        schema
+            .fields()
+            .iter()
+            .position(|field| field.name() == name && !is_virtual_column(field))
+    };
+
+    // Filters match the text cells show, so columns with a custom renderer are
+    // always checked against its output, and cast columns against the cast values.
+    let shown_fields = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(column, field)| match casts.get(&column) {
+            Some(view) => Ok(view.cast(field, &new_empty_array(field.data_type()))?.0),
+            None => Ok(field.as_ref().clone()),
+        })
+        .collect::<Result<Vec<_>, ViewerError>>()?;
+    let renderers = render::registry().column_renderers(&Schema::new(shown_fields));
+    let mut pushed = Vec::new();
+    let mut checked = Vec::new();
+    let mut report = ScanReport {
+        page_index: metadata.metadata().offset_index().is_some(),
+        ..ScanReport::default()
+    };
+    for value_filter in filters {
+        let Some(column) = position(&value_filter.column) else {
+            report
+                .filters
+                .push((value_filter.describe(), FilterCheck::Ignored));
+            continue;
+        };
+        let compiled = CompiledFilter {
+            column,
+            value: value_filter.value.clone(),
+            pattern: value_filter.pattern()?,
+            exclude: value_filter.exclude,
+        };
+        let is_cast = casts.contains_key(&column);
+        let is_string = matches!(
+            schema.field(column).data_type(),
+            DataType::Utf8 | DataType::LargeUtf8
+        );
+        let check = if is_cast {
+            FilterCheck::PerRow
+        } else if prunes(&metadata, value_filter) {
+            FilterCheck::Statistics
+        } else if compiled.pattern.is_some() && is_string && !renderers.has_renderer(column) {
+            FilterCheck::Pushdown
+        } else {
+            FilterCheck::PerRow
+        };
+        report.filters.push((value_filter.describe(), check));
+        if check == FilterCheck::Pushdown {
+            pushed.push(compiled);
+        } else {
+            checked.push(compiled);
+        }
+    }
+    let sort = sort.and_then(|sort| Some((position(&sort.column)?, sort)));
+
+    if pushed.is_empty() && checked.is_empty() && sort.is_none() {
+        let rows = metadata.metadata().file_metadata().num_rows() as usize;
+        return Ok(((0..rows).collect(), report));
+    }
+
+    let mut roots: Vec<usize> = checked
+        .iter()
+        .map(|value_filter| value_filter.column)
+        .collect();
+    roots.extend(sort.map(|(column, _)| column));
+    roots.sort_unstable();
+    roots.dedup();
+    let projected = |column: usize| roots.binary_search(&column).expect("column is projected");
+    let shown = |batch: &RecordBatch, column: usize| -> Result<ArrayRef, ViewerError> {
+        let values = batch.column(projected(column));
+        match casts.get(&column) {
+            Some(view) => Ok(view.cast(schema.field(column), values)?.1),
+            None => Ok(values.clone()),
+        }
+    };
+
+    // File rows kept in one row group, and their sort values.
+    let scan_row_group = |scan: RowGroupScan| -> Result<(Vec<usize>, Vec<ArrayRef>), ViewerError> {
+        let predicates: Vec<Box<dyn ArrowPredicate>> = pushed
+            .iter()
+            .map(|value_filter| {
+                let value_filter = value_filter.clone();
+                let mask = ProjectionMask::roots(&schema_descr, [value_filter.column]);
+                Box::new(ArrowPredicateFn::new(mask, move |batch| {
+                    value_filter.mask(batch.column(0).as_ref())
+                })) as _
+            })
+            .collect();
+        let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            CountingFile::open(path, bytes_read)?,
+            metadata.clone(),
+        )
+        .with_row_groups(vec![scan.row_group])
+        .with_projection(ProjectionMask::roots(&schema_descr, roots.clone()))
+        .with_batch_size(batching::batch_rows(
+            metadata.metadata().row_group(scan.row_group),
+            Some(&roots),
+        ));
+        if !predicates.is_empty() {
+            builder = builder.with_row_filter(RowFilter::new(predicates));
+        }
+        if let Some(selection) = scan.selection {
+            builder = builder.with_row_selection(selection);
+        }
+
+        let mut group_kept = Vec::new();
+        let mut group_values = Vec::new();
+        for batch in builder.build()? {
+            progress.check()?;
+            let batch = batch?;
+            let row_numbers = batch
+                .column_by_name(ROW_NUMBER_COLUMN)
+                .and_then(|column| column.as_any().downcast_ref::<Int64Array>())
+                .expect("row numbers are read with every batch");
+
+            let checked_values = checked
+                .iter()
+                .map(|value_filter| shown(&batch, value_filter.column))
+                .collect::<Result<Vec<_>, ViewerError>>()?;
+            let mut keep = Vec::with_capacity(batch.num_rows());
+            for index in 0..batch.num_rows() {
+                let mut matches = true;
+                for (value_filter, values) in checked.iter().zip(&checked_values) {
+                    let text = if values.is_null(index) {
+                        None
+                    } else {
+                        Some(renderers.cell_text(value_filter.column, values.as_ref(), index)?)
+                    };
+                    if !value_filter.matches(text.as_deref()) {
+                        matches = false;
+                        break;
+                    }
+                }
+                if matches {
+                    group_kept.push(row_numbers.value(index) as usize);
+                }
+                keep.push(matches);
+            }
+
+            if let Some((column, _)) = sort {
+                let values = shown(&batch, column)?;
+                group_values.push(filter(&values, &BooleanArray::from(keep))?);
+            }
+        }
+        Ok((group_kept, group_values))
+    };
+
+    // Row groups and pages whose statistics rule out every filter are never read.
+    // The rest are decoded a pool's worth at a time, concurrently, and merged back
+    // in file order, so that a sort can spill their values before the next ones
+    // are decoded.
+    // Statistics describe the stored values, so cast columns never prune.
+    let stored_filters: Vec<ValueFilter> = filters
+        .iter()
+        .filter(|value_filter| {
+            position(&value_filter.column).is_none_or(|column| !casts.contains_key(&column))
+        })
+        .cloned()
+        .collect();
+    let (scans, plans) = explain_scan(&metadata, &stored_filters)?;
+    report.row_groups = plans;
+    report.planning = started.elapsed();
+    progress.set_total(scans.len());
+    let mut kept = Vec::new();
+    let mut sorter = sort.map(|(_, sort)| {
+        let options = SortOptions {
+            descending: sort.descending,
+            nulls_first: false,
+        };
+        SpillingSort::new(options, memory_limit())
+    });
+    for chunk in scans.chunks(rayon::current_num_threads()) {
+        let results: Vec<_> = chunk
+            .to_vec()
+            .into_par_iter()
+            .map(|scan| (scan.row_group, scan_row_group(scan)))
+            .collect();
+        progress.advance(results.len());
+
+        for (row_group, result) in results {
+            match result {
+                Ok((group_kept, group_values)) => match &mut sorter {
+                    Some(sorter) if !group_kept.is_empty() => {
+                        let values =
+                            concat(&group_values.iter().map(AsRef::as_ref).collect::<Vec<_>>())?;
+                        sorter.push(&group_kept, values)?;
+                    }
+                    Some(_) => {}
+                    None => kept.extend(group_kept),
+                },
+                Err(ViewerError::OpenFailed(error)) => return Err(ViewerError::OpenFailed(error)),
+                Err(ViewerError::Cancelled) => return Err(ViewerError::Cancelled),
+                Err(error) => {
+                    tracing::warn!(row_group, %error, "leaving unreadable row group out of the filtered rows");
+                }
+            }
+        }
+    }
+
+    let rows = match sorter {
+        Some(sorter) => {
+            if sorter.spilled_runs() > 0 {
+                tracing::info!(
+                    runs = sorter.spilled_runs(),
+                    "merging sort runs spilled to disk"
+                );
+            }
+            sorter.finish()?
+        }
+        None => kept,
+    };
+    report.scanning = started.elapsed() - report.planning;
+    Ok((rows, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    fn write_fixture() -> NamedTempFile {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("kind", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![5, 3, 9, 1, 7])),
+                Arc::new(StringArray::from(vec![
+                    Some("a"),
+                    Some("b"),
+                    Some("a"),
+                    None,
+                    Some("a"),
+                ])),
+            ],
+        )
+        .expect("record batch should build");
+
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+        file
+    }
+
+    #[test]
+    fn queries_read_back_what_the_query_bar_shows() {
+        let filters = vec![
+            ValueFilter {
+                column: "level".to_string(),
+                value: "ERROR".to_string(),
+                exclude: false,
+                regex: false,
+            },
+            ValueFilter {
+                column: "message".to_string(),
+                value: "timed? out".to_string(),
+                exclude: true,
+                regex: true,
+            },
+            ValueFilter {
+                column: "host name".to_string(),
+                value: "a=b".to_string(),
+                exclude: true,
+                regex: false,
+            },
+        ];
+        let sort = SortKey {
+            column: "ts".to_string(),
+            descending: true,
+        };
+        let query = describe_query(&filters, Some(&sort));
+        assert_eq!(
+            query,
+            "level = ERROR | message !~ /timed? out/ | host name ≠ a=b | sorted by ts descending"
+        );
+        assert_eq!(parse_query(&query).unwrap(), (filters, Some(sort)));
+
+        let (typed, sort) = parse_query("host!=web-1 | kind ~ ^a").unwrap();
+        assert_eq!((typed[0].exclude, typed[1].regex), (true, true));
+        assert_eq!(typed[1].value, "^a");
+        assert_eq!(sort, None);
+        assert_eq!(parse_query("  ").unwrap(), (Vec::new(), None));
+        assert!(matches!(
+            parse_query("level ERROR"),
+            Err(ViewerError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            parse_query("kind ~ /(/"),
+            Err(ViewerError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn filters_keep_matching_rows_across_row_groups() {
+        let file = write_fixture();
+        let only_a = ValueFilter {
+            column: "kind".to_string(),
+            value: "a".to_string(),
+            exclude: false,
+            regex: false,
+        };
+        let not_null = ValueFilter {
+            column: "kind".to_string(),
+            value: "null".to_string(),
+            exclude: true,
+            regex: false,
+        };
+
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[only_a],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![0, 2, 4]
+        );
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[not_null],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![0, 1, 2, 4]
+        );
+    }
+
+    #[test]
+    fn sort_orders_filtered_rows_by_value() {
+        let file = write_fixture();
+        let only_a = ValueFilter {
+            column: "kind".to_string(),
+            value: "a".to_string(),
+            exclude: false,
+            regex: false,
+        };
+        let by_id = SortKey {
+            column: "id".to_string(),
+            descending: true,
+        };
+
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[only_a],
+                Some(&by_id),
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![2, 4, 0]
+        );
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[],
+                Some(&SortKey {
+                    descending: false,
+                    ..by_id
+                }),
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![3, 1, 0, 4, 2]
+        );
+    }
+
+    #[test]
+    fn regex_filters_match_within_cells_and_skip_nulls() {
+        let file = write_fixture();
+        let pattern = |value: &str, exclude| ValueFilter {
+            column: "kind".to_string(),
+            value: value.to_string(),
+            exclude,
+            regex: true,
+        };
+        let on_id = ValueFilter {
+            column: "id".to_string(),
+            value: "^[13]$".to_string(),
+            exclude: false,
+            regex: true,
+        };
+
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[pattern("^[ab]$", false)],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![0, 1, 2, 4]
+        );
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[pattern("a", true)],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[on_id],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![1, 3]
+        );
+        assert!(matches!(
+            row_order(
+                file.path(),
+                &[pattern("(", false)],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            ),
+            Err(ViewerError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn cast_columns_are_filtered_by_the_values_they_show() {
+        use arrow::array::Int64Array;
+        use arrow::datatypes::TimeUnit;
+
+        use crate::cast::ViewAs;
+
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![Field::new("at", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![
+                1_700_000_002_000,
+                1_700_000_001_000,
+                1_700_000_000_000,
+            ]))],
+        )
+        .expect("record batch should build");
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None)
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let shown = ValueFilter {
+            column: "at".to_string(),
+            value: "2023-11-14T22:13:21Z".to_string(),
+            exclude: false,
+            regex: false,
+        };
+        let by_at = SortKey {
+            column: "at".to_string(),
+            descending: false,
+        };
+        let casts = ColumnCasts::from([(0, ViewAs::Timestamp(TimeUnit::Millisecond))]);
+        let scan = |casts: &ColumnCasts| {
+            row_order(
+                file.path(),
+                std::slice::from_ref(&shown),
+                None,
+                casts,
+                &ReadCounter::default(),
+                &Progress::default(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(scan(&casts), vec![1]);
+        assert!(scan(&ColumnCasts::new()).is_empty());
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[],
+                Some(&by_at),
+                &casts,
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![2, 1, 0]
+        );
+    }
+}
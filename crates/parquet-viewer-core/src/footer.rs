@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::ViewerError;
+
+/// Footers kept parsed, most recently used last.
+const CACHED_FOOTERS: usize = 16;
+
+/// A parsed footer, valid while the file keeps the size and modification time
+/// it had when the footer was read.
+struct CachedFooter {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+    metadata: Arc<ParquetMetaData>,
+}
+
+fn footers() -> MutexGuard<'static, Vec<CachedFooter>> {
+    static FOOTERS: OnceLock<Mutex<Vec<CachedFooter>>> = OnceLock::new();
+    FOOTERS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The footer of the file at `path`, parsed once and shared by every read of
+/// the file after that, including after it is closed and opened again. A file
+/// modified since is parsed anew.
+pub fn parquet_metadata(
+    path: &Path,
+    bytes_read: &ReadCounter,
+) -> Result<Arc<ParquetMetaData>, ViewerError> {
+    let file = std::fs::metadata(path)?;
+    let (len, modified) = (file.len(), file.modified().ok());
+    let path = std::fs::canonicalize(path)?;
+
+    let mut cached = footers();
+    if let Some(index) = cached.iter().position(|footer| footer.path == path) {
+        let footer = cached.remove(index);
+        if footer.len == len && footer.modified == modified {
+            let metadata = footer.metadata.clone();
+            cached.push(footer);
+            return Ok(metadata);
+        }
+    }
+    // Parsing holds the lock, so that two reads of a new file don't both parse it.
+    let metadata = Arc::new(
+        ParquetMetaDataReader::new().parse_and_finish(&CountingFile::open(&path, bytes_read)?)?,
+    );
+    if cached.len() >= CACHED_FOOTERS {
+        cached.remove(0);
+    }
+    cached.push(CachedFooter {
+        path,
+        len,
+        modified,
+        metadata: metadata.clone(),
+    });
+    Ok(metadata)
+}
+
+/// [`parquet_metadata`] with the Arrow schema of the file, for building readers.
+pub fn reader_metadata(
+    path: &Path,
+    bytes_read: &ReadCounter,
+) -> Result<ArrowReaderMetadata, ViewerError> {
+    Ok(ArrowReaderMetadata::try_new(
+        parquet_metadata(path, bytes_read)?,
+        ArrowReaderOptions::new(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn footers_are_parsed_once_until_the_file_changes() {
+        let file = write_test_parquet_with_row_groups(4, 2).expect("parquet write should succeed");
+
+        let bytes_read = ReadCounter::default();
+        let first = parquet_metadata(file.path(), &bytes_read).expect("footer should load");
+        let read = bytes_read.bytes();
+        assert!(read > 0);
+        let again = parquet_metadata(file.path(), &bytes_read).expect("footer should load");
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(bytes_read.bytes(), read);
+
+        let rewritten =
+            write_test_parquet_with_row_groups(6, 3).expect("parquet write should succeed");
+        std::fs::copy(rewritten.path(), file.path()).expect("file should be replaced");
+        let changed = parquet_metadata(file.path(), &bytes_read).expect("footer should load");
+        assert_eq!(changed.file_metadata().num_rows(), 6);
+    }
+}
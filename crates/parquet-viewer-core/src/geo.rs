@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{Field, Schema};
+use arrow::error::ArrowError;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::cell_text;
+use crate::render::CellRenderer;
+
+/// Footer key-value entry holding GeoParquet metadata.
+pub const GEO_METADATA_KEY: &str = "geo";
+
+/// Arrow extension type of WKB geometry columns written by GeoArrow.
+pub const GEOARROW_WKB: &str = "geoarrow.wkb";
+
+/// The `geo` metadata of a GeoParquet file.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct GeoMetadata {
+    #[serde(default)]
+    pub version: String,
+    pub primary_column: String,
+    pub columns: BTreeMap<String, GeoColumn>,
+}
+
+/// How one geometry column is encoded, and what it covers.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct GeoColumn {
+    pub encoding: String,
+    #[serde(default)]
+    pub geometry_types: Vec<String>,
+    /// PROJJSON or an identifier; `None` when the file marks the CRS as unknown.
+    #[serde(default = "default_crs")]
+    pub crs: Option<Value>,
+    /// `[xmin, ymin, xmax, ymax]`, or with Z values `[xmin, ymin, zmin, xmax, ymax, zmax]`.
+    pub bbox: Option<Vec<f64>>,
+}
+
+/// GeoParquet defaults a missing CRS to longitude/latitude on WGS84.
+fn default_crs() -> Option<Value> {
+    Some(Value::String("OGC:CRS84".to_string()))
+}
+
+impl GeoMetadata {
+    /// Parse the metadata from the footer's key-value entries, if the file has any.
+    pub fn from_key_values<'a>(
+        mut entries: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+    ) -> Option<GeoMetadata> {
+        let (_, value) = entries.find(|(key, _)| *key == GEO_METADATA_KEY)?;
+        match serde_json::from_str(value?) {
+            Ok(metadata) => Some(metadata),
+            Err(error) => {
+                tracing::warn!(%error, "ignoring invalid GeoParquet metadata");
+                None
+            }
+        }
+    }
+
+    /// One line per geometry column, the primary one first, e.g.
+    /// `geometry: WKB Point, CRS EPSG:4326, bbox [0, 0, 1, 1]`.
+    pub fn summary(&self) -> Vec<String> {
+        let mut columns: Vec<(&String, &GeoColumn)> = self.columns.iter().collect();
+        columns.sort_by_key(|(name, _)| **name != self.primary_column);
+        columns
+            .into_iter()
+            .map(|(name, column)| {
+                let mut line = format!("{name}: {}", column.encoding);
+                if !column.geometry_types.is_empty() {
+                    line.push(' ');
+                    line.push_str(&column.geometry_types.join("/"));
+                }
+                line.push_str(&format!(", CRS {}", column.crs_label()));
+                if let Some(bbox) = column.bbox_label() {
+                    line.push_str(&format!(", bbox {bbox}"));
+                }
+                line
+            })
+            .collect()
+    }
+
+    fn from_schema(schema: &Schema) -> Option<GeoMetadata> {
+        Self::from_key_values(
+            schema
+                .metadata()
+                .iter()
+                .map(|(key, value)| (key.as_str(), Some(value.as_str()))),
+        )
+    }
+}
+
+impl GeoColumn {
+    /// Short name of the CRS, such as `EPSG:4326`.
+    pub fn crs_label(&self) -> String {
+        let Some(crs) = &self.crs else {
+            return "unknown".to_string();
+        };
+        if let Some(text) = crs.as_str() {
+            return text.to_string();
+        }
+        let id = &crs["id"];
+        match (id["authority"].as_str(), &id["code"]) {
+            (Some(authority), Value::Number(code)) => format!("{authority}:{code}"),
+            (Some(authority), Value::String(code)) => format!("{authority}:{code}"),
+            _ => crs["name"].as_str().unwrap_or("PROJJSON").to_string(),
+        }
+    }
+
+    pub fn bbox_label(&self) -> Option<String> {
+        let bbox = self.bbox.as_ref()?;
+        let values: Vec<String> = bbox.iter().map(f64::to_string).collect();
+        Some(format!("[{}]", values.join(", ")))
+    }
+}
+
+/// Whether the schema's GeoParquet metadata declares `field` a WKB geometry column.
+pub fn is_wkb_column(schema: &Schema, field: &Field) -> bool {
+    GeoMetadata::from_schema(schema)
+        .and_then(|metadata| metadata.columns.get(field.name()).cloned())
+        .is_some_and(|column| column.encoding.eq_ignore_ascii_case("WKB"))
+}
+
+/// Shows WKB geometries as WKT; values that fail to parse keep the default text.
+pub struct WkbRenderer;
+
+impl CellRenderer for WkbRenderer {
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        let bytes = if let Some(values) = array.as_binary_opt::<i32>() {
+            values.value(row)
+        } else if let Some(values) = array.as_binary_opt::<i64>() {
+            values.value(row)
+        } else if let Some(values) = array.as_binary_view_opt() {
+            values.value(row)
+        } else {
+            return cell_text(array, row);
+        };
+        match wkb_to_wkt(bytes) {
+            Ok(text) => Ok(text),
+            Err(_) => cell_text(array, row),
+        }
+    }
+}
+
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// Convert ISO or extended (PostGIS) WKB to WKT.
+pub fn wkb_to_wkt(bytes: &[u8]) -> Result<String, String> {
+    let mut text = String::new();
+    Wkb { bytes, position: 0 }.geometry(&mut text, true)?;
+    Ok(text)
+}
+
+struct Wkb<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Wkb<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let end = self.position + N;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| format!("geometry ends early at byte {}", self.position))?;
+        self.position = end;
+        Ok(bytes.try_into().expect("slice has N bytes"))
+    }
+
+    fn u32(&mut self, little_endian: bool) -> Result<u32, String> {
+        let bytes = self.take::<4>()?;
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self, little_endian: bool) -> Result<f64, String> {
+        let bytes = self.take::<8>()?;
+        Ok(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Write one geometry, prefixed with its type when `tagged`; members of
+    /// multi-geometries are written without it.
+    fn geometry(&mut self, text: &mut String, tagged: bool) -> Result<(), String> {
+        let little_endian = match self.take::<1>()? {
+            [0] => false,
+            [1] => true,
+            [other] => return Err(format!("invalid byte order {other}")),
+        };
+        let code = self.u32(little_endian)?;
+        if code & EWKB_SRID != 0 {
+            self.u32(little_endian)?;
+        }
+        let base = code & 0x0FFF_FFFF;
+        let (kind, iso_dimensions) = (base % 1000, base / 1000);
+        let z = code & EWKB_Z != 0 || matches!(iso_dimensions, 1 | 3);
+        let m = code & EWKB_M != 0 || matches!(iso_dimensions, 2 | 3);
+        let dimensions = 2 + usize::from(z) + usize::from(m);
+
+        if tagged {
+            let name = match kind {
+                1 => "POINT",
+                2 => "LINESTRING",
+                3 => "POLYGON",
+                4 => "MULTIPOINT",
+                5 => "MULTILINESTRING",
+                6 => "MULTIPOLYGON",
+                7 => "GEOMETRYCOLLECTION",
+                _ => return Err(format!("unsupported geometry type {code}")),
+            };
+            text.push_str(name);
+            text.push_str(match (z, m) {
+                (true, true) => " ZM ",
+                (true, false) => " Z ",
+                (false, true) => " M ",
+                (false, false) => " ",
+            });
+        }
+
+        match kind {
+            1 => {
+                let start = text.len();
+                text.push('(');
+                let empty = self.coordinate(text, little_endian, dimensions)?;
+                if empty {
+                    text.truncate(start);
+                    text.push_str("EMPTY");
+                } else {
+                    text.push(')');
+                }
+                Ok(())
+            }
+            2 => self.points(text, little_endian, dimensions),
+            3 => self.list(text, little_endian, |wkb, text| {
+                wkb.points(text, little_endian, dimensions)
+            }),
+            4..=6 => self.list(text, little_endian, |wkb, text| wkb.geometry(text, false)),
+            7 => self.list(text, little_endian, |wkb, text| wkb.geometry(text, true)),
+            _ => Err(format!("unsupported geometry type {code}")),
+        }
+    }
+
+    /// Write one coordinate; returns whether every ordinate is NaN, which WKB
+    /// uses for an empty point.
+    fn coordinate(
+        &mut self,
+        text: &mut String,
+        little_endian: bool,
+        dimensions: usize,
+    ) -> Result<bool, String> {
+        let mut empty = true;
+        for index in 0..dimensions {
+            let value = self.f64(little_endian)?;
+            empty &= value.is_nan();
+            if index > 0 {
+                text.push(' ');
+            }
+            let _ = write!(text, "{value}");
+        }
+        Ok(empty)
+    }
+
+    fn points(
+        &mut self,
+        text: &mut String,
+        little_endian: bool,
+        dimensions: usize,
+    ) -> Result<(), String> {
+        self.list(text, little_endian, |wkb, text| {
+            wkb.coordinate(text, little_endian, dimensions).map(|_| ())
+        })
+    }
+
+    /// Write a count-prefixed list as `(a, b, …)`, or `EMPTY`.
+    fn list(
+        &mut self,
+        text: &mut String,
+        little_endian: bool,
+        mut item: impl FnMut(&mut Self, &mut String) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let count = self.u32(little_endian)?;
+        if count == 0 {
+            text.push_str("EMPTY");
+            return Ok(());
+        }
+        text.push('(');
+        for index in 0..count {
+            if index > 0 {
+                text.push_str(", ");
+            }
+            item(self, text)?;
+        }
+        text.push(')');
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Vec<u8> {
+        let mut bytes = vec![1];
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(x.to_le_bytes());
+        bytes.extend(y.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn wkb_is_written_as_wkt() {
+        assert_eq!(wkb_to_wkt(&point(1.5, -2.0)).unwrap(), "POINT (1.5 -2)");
+
+        // Big-endian LINESTRING Z with two points.
+        let mut line = vec![0];
+        line.extend(1002u32.to_be_bytes());
+        line.extend(2u32.to_be_bytes());
+        for value in [0.0f64, 1.0, 2.0, 3.0, 4.0, 5.0] {
+            line.extend(value.to_be_bytes());
+        }
+        assert_eq!(wkb_to_wkt(&line).unwrap(), "LINESTRING Z (0 1 2, 3 4 5)");
+
+        let mut multi = vec![1];
+        multi.extend(4u32.to_le_bytes());
+        multi.extend(2u32.to_le_bytes());
+        multi.extend(point(1.0, 2.0));
+        multi.extend(point(3.0, 4.0));
+        assert_eq!(wkb_to_wkt(&multi).unwrap(), "MULTIPOINT ((1 2), (3 4))");
+
+        let mut empty = vec![1];
+        empty.extend(3u32.to_le_bytes());
+        empty.extend(0u32.to_le_bytes());
+        assert_eq!(wkb_to_wkt(&empty).unwrap(), "POLYGON EMPTY");
+
+        assert!(wkb_to_wkt(&point(1.0, 2.0)[..12]).is_err());
+    }
+
+    #[test]
+    fn geo_metadata_reports_crs_and_bbox() {
+        let json = r#"{
+            "version": "1.1.0",
+            "primary_column": "geometry",
+            "columns": {
+                "geometry": {
+                    "encoding": "WKB",
+                    "geometry_types": ["Point"],
+                    "crs": {"name": "WGS 84", "id": {"authority": "EPSG", "code": 4326}},
+                    "bbox": [-10.5, 40, 3, 52.25]
+                },
+                "outline": {"encoding": "WKB", "geometry_types": [], "crs": null}
+            }
+        }"#;
+        let metadata = GeoMetadata::from_key_values([("geo", Some(json))].into_iter())
+            .expect("metadata should parse");
+
+        let geometry = &metadata.columns["geometry"];
+        assert_eq!(metadata.primary_column, "geometry");
+        assert_eq!(geometry.crs_label(), "EPSG:4326");
+        assert_eq!(geometry.bbox_label().unwrap(), "[-10.5, 40, 3, 52.25]");
+        assert_eq!(metadata.columns["outline"].crs_label(), "unknown");
+        assert_eq!(
+            metadata.summary(),
+            vec![
+                "geometry: WKB Point, CRS EPSG:4326, bbox [-10.5, 40, 3, 52.25]".to_string(),
+                "outline: WKB, CRS unknown".to_string(),
+            ]
+        );
+
+        let schema = Schema::new(vec![
+            Field::new("geometry", arrow::datatypes::DataType::Binary, true),
+            Field::new("name", arrow::datatypes::DataType::Utf8, true),
+        ])
+        .with_metadata([("geo".to_string(), json.to_string())].into());
+        assert!(is_wkb_column(&schema, schema.field(0)));
+        assert!(!is_wkb_column(&schema, schema.field(1)));
+    }
+}
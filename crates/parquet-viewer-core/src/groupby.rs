@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use arrow::array::UInt32Array;
+use arrow::compute::take;
+
+use crate::aggregate::{scan_rows, Accumulator, Aggregate};
+use crate::footer;
+use crate::metrics::ReadCounter;
+use crate::placeholders::placeholders;
+use crate::progress::Progress;
+use crate::render;
+use crate::ViewerError;
+
+/// Distinct keys tracked before further keys are counted together as other rows,
+/// so grouping by a near-unique column can't exhaust memory.
+const MAX_GROUPS: usize = 100_000;
+
+/// Rows sharing one combination of key values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    /// Cell text of each key column, `null` for nulls, as the table shows them.
+    pub key: Vec<String>,
+    pub rows: u64,
+    /// The aggregate of the value column over the group's rows, if one was asked for.
+    pub value: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Groups {
+    /// Largest groups first.
+    pub groups: Vec<Group>,
+    /// Rows whose key came after [`MAX_GROUPS`] distinct keys had been seen.
+    pub other_rows: u64,
+}
+
+/// Count the rows of each distinct combination of `keys` over the whole file,
+/// or with `rows`, over those file rows only, and compute `value`'s aggregate
+/// per group. Keys naming columns the file doesn't have are ignored.
+pub fn group_by(
+    path: &Path,
+    keys: &[String],
+    value: Option<&(String, Aggregate)>,
+    rows: Option<&[usize]>,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Groups, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let schema = metadata.schema().clone();
+    let position = |name: &str| {
+        schema
+            .fields()
+            .iter()
+            .position(|field| field.name() == name)
+    };
+
+    let keys: Vec<usize> = keys.iter().filter_map(|key| position(key)).collect();
+    let value = value.and_then(|(column, aggregate)| {
+        let column = position(column)?;
+        let accumulator = Accumulator::new(*aggregate, schema.field(column).data_type());
+        accumulator
+            .applies(schema.field(column).data_type())
+            .then_some((column, *aggregate))
+    });
+    let mut roots = keys.clone();
+    roots.extend(value.map(|(column, _)| column));
+    roots.sort_unstable();
+    roots.dedup();
+    let projected = |column: usize| roots.binary_search(&column).expect("column is projected");
+
+    // Keys are matched on the text cells show, so that a group's filter keeps
+    // exactly its rows.
+    let renderers = render::registry().column_renderers(&schema);
+    let mut index: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut groups: Vec<(Group, Option<Accumulator>)> = Vec::new();
+    let mut other_rows = 0;
+    scan_rows(
+        path,
+        &metadata,
+        &roots,
+        rows,
+        bytes_read,
+        progress,
+        |batch| {
+            let mut members: HashMap<usize, Vec<u32>> = HashMap::new();
+            for row in 0..batch.num_rows() {
+                let key = keys
+                    .iter()
+                    .map(|&column| {
+                        let values = batch.column(projected(column));
+                        if values.is_null(row) {
+                            Ok(placeholders().null.clone())
+                        } else {
+                            renderers.cell_text(column, values.as_ref(), row)
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let group = match index.get(&key) {
+                    Some(&group) => group,
+                    None if groups.len() < MAX_GROUPS => {
+                        index.insert(key.clone(), groups.len());
+                        let accumulator = value.map(|(column, aggregate)| {
+                            Accumulator::new(aggregate, schema.field(column).data_type())
+                        });
+                        groups.push((
+                            Group {
+                                key,
+                                rows: 0,
+                                value: None,
+                            },
+                            accumulator,
+                        ));
+                        groups.len() - 1
+                    }
+                    None => {
+                        other_rows += 1;
+                        continue;
+                    }
+                };
+                groups[group].0.rows += 1;
+                members.entry(group).or_default().push(row as u32);
+            }
+
+            if let Some((column, _)) = value {
+                let values = batch.column(projected(column));
+                for (group, rows) in members {
+                    if let Some(accumulator) = &mut groups[group].1 {
+                        let rows = take(values.as_ref(), &UInt32Array::from(rows), None)?;
+                        accumulator.add(rows.as_ref())?;
+                    }
+                }
+            }
+            Ok(())
+        },
+    )?;
+
+    let mut groups = groups
+        .into_iter()
+        .map(|(mut group, accumulator)| {
+            if let Some(accumulator) = accumulator {
+                group.value = accumulator.finish()?;
+            }
+            Ok(group)
+        })
+        .collect::<Result<Vec<_>, ViewerError>>()?;
+    groups.sort_by(|a, b| b.rows.cmp(&a.rows).then_with(|| a.key.cmp(&b.key)));
+
+    Ok(Groups { groups, other_rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn rows_are_counted_and_aggregated_per_key() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("city", DataType::Utf8, true),
+            Field::new("kind", DataType::Utf8, false),
+            Field::new("amount", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("Oslo"),
+                    Some("Lima"),
+                    Some("Oslo"),
+                    None,
+                    Some("Oslo"),
+                ])),
+                Arc::new(StringArray::from(vec!["a", "a", "b", "a", "a"])),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let counter = ReadCounter::default();
+
+        let by_city = group_by(
+            file.path(),
+            &["city".to_string()],
+            Some(&("amount".to_string(), Aggregate::Sum)),
+            None,
+            &counter,
+            &Progress::default(),
+        )
+        .expect("groups should compute");
+        assert_eq!(
+            by_city.groups[0],
+            Group {
+                key: vec!["Oslo".to_string()],
+                rows: 3,
+                value: Some("9".to_string()),
+            }
+        );
+        assert_eq!(by_city.groups.len(), 3);
+        assert_eq!(by_city.groups[2].key, vec!["null".to_string()]);
+
+        let by_both = group_by(
+            file.path(),
+            &["city".to_string(), "kind".to_string()],
+            None,
+            Some(&[0, 2, 4]),
+            &counter,
+            &Progress::default(),
+        )
+        .expect("groups should compute");
+        assert_eq!(by_both.groups.len(), 2);
+        assert_eq!(by_both.groups[0].key, vec!["Oslo", "a"]);
+        assert_eq!(by_both.groups[0].rows, 2);
+        assert_eq!(by_both.groups[0].value, None);
+        assert_eq!(by_both.other_rows, 0);
+    }
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use arrow::array::AsArray;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type};
+
+use crate::aggregate::{is_numeric, scan_rows};
+use crate::embedding::SPARK_LEVELS;
+use crate::footer;
+use crate::metrics::ReadCounter;
+use crate::progress::Progress;
+use crate::ViewerError;
+
+/// Bars in a header histogram.
+pub const HISTOGRAM_BINS: usize = 12;
+
+/// Rows sampled for header histograms, spread evenly over the file.
+const SAMPLE_ROWS: usize = 5_000;
+
+/// Distribution of a numeric column's sampled values over equal-width bins.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// Bin the finite `values`; `None` if there are none. A constant column puts
+    /// every value in the middle bin.
+    pub fn from_values(values: &[f64], bins: usize) -> Option<Histogram> {
+        let finite = || values.iter().copied().filter(|value| value.is_finite());
+        let min = finite().reduce(f64::min)?;
+        let max = finite().reduce(f64::max)?;
+        let mut counts = vec![0; bins.max(1)];
+        let last = counts.len() - 1;
+        for value in finite() {
+            let bin = if max > min {
+                (((value - min) / (max - min)) * counts.len() as f64) as usize
+            } else {
+                counts.len() / 2
+            };
+            counts[bin.min(last)] += 1;
+        }
+        Some(Histogram { min, max, counts })
+    }
+
+    /// The bins as block characters scaled to the fullest bin; empty bins are
+    /// blank so that gaps between modes show.
+    pub fn sparkline(&self) -> String {
+        let fullest = self.counts.iter().copied().max().unwrap_or(0);
+        let top = SPARK_LEVELS.len() - 1;
+        self.counts
+            .iter()
+            .map(|&count| match count {
+                0 => ' ',
+                count => {
+                    SPARK_LEVELS[((count as f64 / fullest as f64) * top as f64).round() as usize]
+                }
+            })
+            .collect()
+    }
+}
+
+/// A histogram of each numeric top-level column over an even sample of the
+/// file's rows; `None` for other columns and columns without finite values.
+/// `progress` counts row groups.
+pub fn sample_histograms(
+    path: &Path,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Vec<Option<Histogram>>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let fields = metadata.schema().fields().clone();
+    let roots: Vec<usize> = (0..fields.len())
+        .filter(|&column| is_numeric(fields[column].data_type()))
+        .collect();
+
+    let row_count = metadata.metadata().file_metadata().num_rows() as usize;
+    let step = row_count.div_ceil(SAMPLE_ROWS).max(1);
+    let sample: Vec<usize> = (0..row_count).step_by(step).collect();
+
+    let mut values: Vec<Vec<f64>> = vec![Vec::new(); roots.len()];
+    scan_rows(
+        path,
+        &metadata,
+        &roots,
+        Some(&sample),
+        bytes_read,
+        progress,
+        |batch| {
+            for (projected, values) in values.iter_mut().enumerate() {
+                let column = cast(batch.column(projected), &DataType::Float64)?;
+                values.extend(column.as_primitive::<Float64Type>().iter().flatten());
+            }
+            Ok(())
+        },
+    )?;
+
+    let mut histograms = vec![None; fields.len()];
+    for (column, values) in roots.into_iter().zip(values) {
+        histograms[column] = Histogram::from_values(&values, HISTOGRAM_BINS);
+    }
+    Ok(histograms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Float64Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn bimodal_and_constant_columns_are_recognizable() {
+        let bimodal = Histogram::from_values(&[0.0, 0.0, 0.5, 10.0, 10.0, 9.5], 4).unwrap();
+        assert_eq!(bimodal.counts, vec![3, 0, 0, 3]);
+        assert_eq!(bimodal.sparkline(), "█  █");
+
+        let constant = Histogram::from_values(&[7.0, 7.0, f64::NAN], 4).unwrap();
+        assert_eq!(constant.counts, vec![0, 0, 2, 0]);
+        assert_eq!(Histogram::from_values(&[f64::NAN], 4), None);
+    }
+
+    #[test]
+    fn numeric_columns_are_sampled() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new("score", DataType::Float64, true),
+        ]));
+        let scores: Vec<Option<f64>> = (0..12_000)
+            .map(|row| (row % 10 != 0).then_some(row as f64))
+            .collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["x"; 12_000])),
+                Arc::new(Float64Array::from(scores)),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let histograms =
+            sample_histograms(file.path(), &ReadCounter::default(), &Progress::default())
+                .expect("histograms should compute");
+        assert_eq!(histograms[0], None);
+        let score = histograms[1].as_ref().expect("score is numeric");
+        assert_eq!(score.counts.len(), HISTOGRAM_BINS);
+        assert!(score.counts.iter().sum::<u64>() <= SAMPLE_ROWS as u64);
+        assert!(score.max > 11_000.0);
+    }
+}
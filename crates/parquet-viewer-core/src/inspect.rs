@@ -0,0 +1,285 @@
+use arrow::array::{Array, AsArray, RecordBatch};
+
+use crate::metrics::format_bytes;
+use crate::preview::window_chunks;
+use crate::provenance::{cell_origin, CellOrigin};
+use crate::render::{self, ColumnRenderers};
+use crate::source::{self, read_chunk};
+use crate::{DataPreview, ViewerError};
+
+/// One cell in full, for the cell inspector.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellDetail {
+    pub column: String,
+    /// Arrow type of the column, with its extension type if it has one.
+    pub data_type: String,
+    pub text: String,
+    /// Image stored in a binary cell, which the inspector can preview.
+    pub image: Option<EmbeddedImage>,
+    /// Where the cell is stored in the file, filled in for a single inspected cell.
+    pub origin: Option<CellOrigin>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageKind {
+    Png,
+    Jpeg,
+}
+
+impl ImageKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageKind::Png => "PNG",
+            ImageKind::Jpeg => "JPEG",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmbeddedImage {
+    pub kind: ImageKind,
+    pub bytes: Vec<u8>,
+}
+
+/// Recognize PNG and JPEG data by their leading magic bytes.
+pub fn sniff_image(bytes: &[u8]) -> Option<ImageKind> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(ImageKind::Png)
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some(ImageKind::Jpeg)
+    } else {
+        None
+    }
+}
+
+/// The image in a binary cell, if its bytes look like one.
+fn embedded_image(array: &dyn Array, row: usize) -> Option<EmbeddedImage> {
+    if array.is_null(row) {
+        return None;
+    }
+    let bytes = if let Some(values) = array.as_binary_opt::<i32>() {
+        values.value(row)
+    } else if let Some(values) = array.as_binary_opt::<i64>() {
+        values.value(row)
+    } else if let Some(values) = array.as_binary_view_opt() {
+        values.value(row)
+    } else {
+        return None;
+    };
+    Some(EmbeddedImage {
+        kind: sniff_image(bytes)?,
+        bytes: bytes.to_vec(),
+    })
+}
+
+/// Every cell of one row, for the record view.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordDetail {
+    /// File row.
+    pub row: usize,
+    pub cells: Vec<CellDetail>,
+}
+
+impl RecordDetail {
+    /// The row as a JSON object of column names to the cells' text, in column order.
+    pub fn to_json(&self) -> String {
+        // Written by hand because `serde_json::Map` sorts its keys.
+        let fields: Vec<String> = self
+            .cells
+            .iter()
+            .map(|cell| {
+                format!(
+                    "  {}: {}",
+                    json_string(&cell.column),
+                    json_string(&cell.text)
+                )
+            })
+            .collect();
+        if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}\n}}", fields.join(",\n"))
+        }
+    }
+}
+
+fn json_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+/// Read file row `row` as a single-row batch; `None` if the row doesn't exist.
+fn read_row(preview: &DataPreview, row: usize) -> Result<Option<RecordBatch>, ViewerError> {
+    let Some(chunk) = window_chunks(&preview.row_groups, row..row + 1)
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let batches = source::block_on(read_chunk(preview.source.as_ref(), &chunk))?;
+    batches
+        .into_iter()
+        .find(|batch| batch.num_rows() > 0)
+        .map(|batch| preview.display_batch(&batch))
+        .transpose()
+}
+
+/// The first row's cell of `column` in `batch`, in full.
+fn cell_detail(
+    batch: &RecordBatch,
+    renderers: &ColumnRenderers,
+    column: usize,
+) -> Result<CellDetail, ViewerError> {
+    let schema = batch.schema();
+    let field = schema.field(column);
+    let data_type = match field.extension_type_name() {
+        Some(extension) => format!("{} ({extension})", field.data_type()),
+        None => field.data_type().to_string(),
+    };
+    let values = batch.column(column).as_ref();
+    let image = embedded_image(values, 0);
+    // Images are previewed rather than shown as pages of hex.
+    let text = match &image {
+        Some(image) => format!(
+            "{} image, {}",
+            image.kind.name(),
+            format_bytes(image.bytes.len() as u64)
+        ),
+        None => renderers.inspect_text(column, values, 0)?,
+    };
+
+    Ok(CellDetail {
+        column: field.name().clone(),
+        data_type,
+        text,
+        image,
+        origin: None,
+    })
+}
+
+/// Read the cell at file row `row` of `column`; `None` if the row doesn't exist.
+pub fn inspect_cell(
+    preview: &DataPreview,
+    row: usize,
+    column: usize,
+) -> Result<Option<CellDetail>, ViewerError> {
+    let Some(batch) = read_row(preview, row)? else {
+        return Ok(None);
+    };
+    let renderers = render::registry().column_renderers(&batch.schema());
+    let mut detail = cell_detail(&batch, &renderers, column)?;
+    detail.origin = cell_origin(&preview.path, &preview.bytes_read, row, &detail.column)?;
+    Ok(Some(detail))
+}
+
+/// Read every cell of file row `row`, columns viewed as another type cast to it; `None` if the row doesn't exist.
+pub fn inspect_row(preview: &DataPreview, row: usize) -> Result<Option<RecordDetail>, ViewerError> {
+    let Some(batch) = read_row(preview, row)? else {
+        return Ok(None);
+    };
+    let renderers = render::registry().column_renderers(&batch.schema());
+    let cells = (0..batch.num_columns())
+        .map(|column| cell_detail(&batch, &renderers, column))
+        .collect::<Result<_, _>>()?;
+    Ok(Some(RecordDetail { row, cells }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow::array::{BinaryArray, FixedSizeBinaryArray, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::arrow_writer::ArrowWriter;
+    use tempfile::NamedTempFile;
+
+    use crate::extension::{ARROW_JSON, ARROW_UUID};
+    use crate::load_preview;
+    use crate::render::EXTENSION_NAME_KEY;
+
+    fn extension(name: &str) -> HashMap<String, String> {
+        HashMap::from([(EXTENSION_NAME_KEY.to_string(), name.to_string())])
+    }
+
+    #[test]
+    fn uuid_and_json_columns_are_shown_in_full() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::FixedSizeBinary(16), false)
+                .with_metadata(extension(ARROW_UUID)),
+            Field::new("payload", DataType::Utf8, false).with_metadata(extension(ARROW_JSON)),
+        ]));
+        let ids = FixedSizeBinaryArray::try_from_iter([[0x11u8; 16]].into_iter()).unwrap();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(ids),
+                Arc::new(StringArray::from(vec![r#"{"k":1}"#])),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 10, false).expect("preview should load");
+        assert_eq!(&*preview.rows[0][0], "11111111-1111-1111-1111-111111111111");
+
+        let detail = inspect_cell(&preview, 0, 1)
+            .expect("cell should be read")
+            .expect("row exists");
+        assert_eq!(detail.column, "payload");
+        assert_eq!(detail.data_type, "Utf8 (arrow.json)");
+        assert_eq!(detail.text, "{\n  \"k\": 1\n}");
+        assert!(inspect_cell(&preview, 5, 0).unwrap().is_none());
+
+        let record = inspect_row(&preview, 0)
+            .expect("row should be read")
+            .expect("row exists");
+        assert_eq!(record.cells.len(), 2);
+        assert_eq!(record.cells[0].column, "id");
+        assert_eq!(
+            record.to_json(),
+            "{\n  \"id\": \"11111111-1111-1111-1111-111111111111\",\n  \"payload\": \"{\\n  \\\"k\\\": 1\\n}\"\n}"
+        );
+        assert!(inspect_row(&preview, 5).unwrap().is_none());
+    }
+
+    #[test]
+    fn binary_cells_with_image_magic_offer_a_preview() {
+        assert_eq!(
+            sniff_image(&[0xff, 0xd8, 0xff, 0xe0]),
+            Some(ImageKind::Jpeg)
+        );
+        assert_eq!(sniff_image(b"GIF89a"), None);
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "image",
+            DataType::Binary,
+            true,
+        )]));
+        let values = BinaryArray::from_opt_vec(vec![Some(png.as_slice()), Some(b"text")]);
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(values)]).unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let preview =
+            load_preview(&file.path().to_path_buf(), 10, false).expect("preview should load");
+
+        let detail = inspect_cell(&preview, 0, 0).unwrap().expect("row exists");
+        let image = detail.image.expect("PNG should be recognized");
+        assert_eq!(image.kind, ImageKind::Png);
+        assert_eq!(image.bytes, png);
+        assert_eq!(detail.text, "PNG image, 16 B");
+        assert!(inspect_cell(&preview, 1, 0)
+            .unwrap()
+            .unwrap()
+            .image
+            .is_none());
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Text of one cell. Cells with the same text in a column share it, see [`CellInterner`].
+pub type Cell = Arc<str>;
+
+/// Distinct values a column may have before it stops being interned.
+const MAX_INTERNED_VALUES: usize = 1024;
+
+/// Shares the text of repeated cells in each column, so that low-cardinality
+/// columns such as enums or country codes hold one copy of each value rather
+/// than one per row.
+///
+/// A column with more than [`MAX_INTERNED_VALUES`] distinct values is taken to
+/// be high-cardinality; its cells are stored as they come from then on.
+#[derive(Debug, Default)]
+pub struct CellInterner {
+    columns: Vec<Option<HashSet<Cell>>>,
+}
+
+impl CellInterner {
+    /// The cell for `text` in `column`, shared with earlier cells of the same text.
+    pub fn intern(&mut self, column: usize, text: String) -> Cell {
+        if self.columns.len() <= column {
+            self.columns
+                .resize_with(column + 1, || Some(HashSet::new()));
+        }
+        let Some(values) = &mut self.columns[column] else {
+            return Cell::from(text);
+        };
+        if let Some(cell) = values.get(text.as_str()) {
+            return cell.clone();
+        }
+        let cell = Cell::from(text);
+        if values.len() < MAX_INTERNED_VALUES {
+            values.insert(cell.clone());
+        } else {
+            self.columns[column] = None;
+        }
+        cell
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_values_share_their_text_until_a_column_is_too_varied() {
+        let mut interner = CellInterner::default();
+        let first = interner.intern(1, "DE".to_string());
+        let again = interner.intern(1, "DE".to_string());
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(!Arc::ptr_eq(&first, &interner.intern(0, "DE".to_string())));
+
+        for value in 0..=MAX_INTERNED_VALUES {
+            interner.intern(2, value.to_string());
+        }
+        let first = interner.intern(2, "0".to_string());
+        let again = interner.intern(2, "0".to_string());
+        assert_eq!(first, again);
+        assert!(!Arc::ptr_eq(&first, &again));
+    }
+}
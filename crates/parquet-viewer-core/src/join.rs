@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanBufferBuilder, UInt64Array};
+use arrow::compute::{cast, concat_batches, take};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ArrowWriter;
+
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::spill::memory_limit;
+use crate::ViewerError;
+
+/// Which rows of the left file a join keeps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Left rows with at least one matching right row.
+    #[default]
+    Inner,
+    /// Every left row, with nulls for the right columns where nothing matches.
+    Left,
+}
+
+impl JoinKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            JoinKind::Inner => "Inner join",
+            JoinKind::Left => "Left join",
+        }
+    }
+}
+
+/// The second file of a join and the columns rows are matched on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JoinSpec {
+    pub right: PathBuf,
+    pub left_key: String,
+    pub right_key: String,
+    pub kind: JoinKind,
+}
+
+/// Outcome of a join, reported back to the user.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JoinSummary {
+    pub left_rows: usize,
+    pub right_rows: usize,
+    /// Rows written to the result.
+    pub rows: usize,
+    /// Left rows whose key matched no right row, including null keys.
+    pub unmatched_left: usize,
+}
+
+impl JoinSummary {
+    /// One line for a notification, e.g. `120 rows; 3 of 100 left rows have no match`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} rows; {} of {} left rows have no match",
+            self.rows, self.unmatched_left, self.left_rows
+        )
+    }
+}
+
+/// Top-level column names of the file at `path`, for picking a join key.
+pub fn column_names(path: &Path) -> Result<Vec<String>, ViewerError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    Ok(builder
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect())
+}
+
+/// Join the file at `left` with `spec.right` on equal keys and write the result
+/// to `destination`: every left column followed by every right column, those
+/// whose name is taken getting a `_right` suffix.
+///
+/// This is a hash join on Arrow kernels rather than a DataFusion query: the
+/// right file's keys are hashed and the left file is probed against them.
+/// Keys are compared as values of a type both columns cast to, so an `Int32`
+/// key matches an `Int64` one and a `Utf8` key a `LargeUtf8` one, while keys of
+/// unrelated types are refused; null keys match nothing. The right file is held
+/// in memory while the left one streams past it, so the smaller file belongs on
+/// the right. Should it outgrow `--memory-limit`, it is held a part at a time,
+/// the left file being read once per part, and the result is grouped by part.
+/// `destination` only appears once the join is complete; `progress` counts rows
+/// read.
+pub fn join_files(
+    left: &Path,
+    spec: &JoinSpec,
+    destination: &Path,
+    progress: &Progress,
+) -> Result<JoinSummary, ViewerError> {
+    join_within(left, spec, destination, memory_limit(), progress)
+}
+
+/// [`join_files`], holding at most `limit` bytes of the right file at a time.
+fn join_within(
+    left: &Path,
+    spec: &JoinSpec,
+    destination: &Path,
+    limit: Option<usize>,
+    progress: &Progress,
+) -> Result<JoinSummary, ViewerError> {
+    ensure_distinct([left, spec.right.as_path()], destination)?;
+
+    let left_metadata = ArrowReaderMetadata::load(&File::open(left)?, Default::default())?;
+    let right_builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&spec.right)?)?;
+    let left_schema = left_metadata.schema().clone();
+    let right_schema = right_builder.schema().clone();
+    let left_key = key_column(&left_schema, &spec.left_key, left)?;
+    let right_key = key_column(&right_schema, &spec.right_key, &spec.right)?;
+    let key_type = common_key_type(
+        left_schema.field(left_key).data_type(),
+        right_schema.field(right_key).data_type(),
+    )
+    .ok_or_else(|| {
+        ViewerError::InvalidValue(format!(
+            "keys `{}` ({}) and `{}` ({}) can't be compared",
+            spec.left_key,
+            left_schema.field(left_key).data_type(),
+            spec.right_key,
+            right_schema.field(right_key).data_type(),
+        ))
+    })?;
+    let converter = RowConverter::new(vec![SortField::new(key_type.clone())])?;
+    // Keys as bytes that are equal exactly when the typed values are; `None`
+    // for null keys.
+    let keys = |column: &ArrayRef| -> Result<Vec<Option<Vec<u8>>>, ViewerError> {
+        let column = cast(column, &key_type)?;
+        let rows = converter.convert_columns(&[column.clone()])?;
+        Ok((0..column.len())
+            .map(|row| {
+                column
+                    .is_valid(row)
+                    .then(|| rows.row(row).as_ref().to_vec())
+            })
+            .collect())
+    };
+    let left_total = left_metadata.metadata().file_metadata().num_rows().max(0) as usize;
+    let right_total = right_builder.metadata().file_metadata().num_rows().max(0) as usize;
+    progress.set_total(left_total + right_total);
+
+    let schema = joined_schema(&left_schema, &right_schema, spec.kind);
+    let mut right_batches = right_builder.build()?.peekable();
+    let mut summary = JoinSummary {
+        right_rows: right_total,
+        ..JoinSummary::default()
+    };
+    write_atomically(destination, |file| {
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None)?;
+        // Left rows matched by an earlier part of the right file, a bit each,
+        // kept only once the right file takes more than one part.
+        let mut matched_before: Option<BooleanBufferBuilder> = None;
+        let mut passes = 0;
+        loop {
+            // The next part of the right file that fits the limit, always at
+            // least one batch.
+            let mut batches = Vec::new();
+            let mut held = 0;
+            while batches.is_empty() || limit.is_none_or(|limit| held <= limit) {
+                let Some(batch) = right_batches.next() else {
+                    break;
+                };
+                progress.check()?;
+                let batch = batch?;
+                held += batch.get_array_memory_size();
+                progress.advance(batch.num_rows());
+                batches.push(batch);
+            }
+            let right = concat_batches(&right_schema, &batches)?;
+            drop(batches);
+            let last = right_batches.peek().is_none();
+            if !last && matched_before.is_none() {
+                matched_before = Some(BooleanBufferBuilder::new(left_total));
+            }
+            passes += 1;
+            if passes > 1 {
+                progress.set_total(left_total * passes + right_total);
+            }
+
+            let mut matches: HashMap<Vec<u8>, Vec<u64>> = HashMap::new();
+            for (row, key) in keys(right.column(right_key))?.into_iter().enumerate() {
+                if let Some(key) = key {
+                    matches.entry(key).or_default().push(row as u64);
+                }
+            }
+
+            let left_batches = ParquetRecordBatchReaderBuilder::new_with_metadata(
+                File::open(left)?,
+                left_metadata.clone(),
+            )
+            .build()?;
+            let mut start = 0;
+            for batch in left_batches {
+                progress.check()?;
+                let batch = batch?;
+                let mut left_rows = Vec::new();
+                let mut right_rows = Vec::new();
+                for (row, key) in keys(batch.column(left_key))?.into_iter().enumerate() {
+                    match key.and_then(|key| matches.get(&key)) {
+                        Some(matched) => {
+                            if let Some(bits) = &mut matched_before {
+                                mark(bits, start + row);
+                            }
+                            left_rows.extend(std::iter::repeat_n(row as u64, matched.len()));
+                            right_rows.extend(matched.iter().copied().map(Some));
+                        }
+                        // Rows nothing matched are known once the last part is in.
+                        None if last && !is_marked(matched_before.as_ref(), start + row) => {
+                            summary.unmatched_left += 1;
+                            if spec.kind == JoinKind::Left {
+                                left_rows.push(row as u64);
+                                right_rows.push(None);
+                            }
+                        }
+                        None => {}
+                    }
+                }
+
+                let left_rows = UInt64Array::from(left_rows);
+                let right_rows = UInt64Array::from(right_rows);
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|column| take(column, &left_rows, None))
+                    .chain(
+                        right
+                            .columns()
+                            .iter()
+                            .map(|column| take(column, &right_rows, None)),
+                    )
+                    .collect::<Result<Vec<_>, _>>()?;
+                writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+                summary.rows += left_rows.len();
+                start += batch.num_rows();
+                progress.advance(batch.num_rows());
+            }
+            if last {
+                summary.left_rows = start;
+                break;
+            }
+        }
+        writer.close()?;
+        Ok(())
+    })?;
+    Ok(summary)
+}
+
+/// Set bit `index`, growing `bits` should the footer undercount the rows.
+fn mark(bits: &mut BooleanBufferBuilder, index: usize) {
+    if index >= bits.len() {
+        bits.append_n(index + 1 - bits.len(), false);
+    }
+    bits.set_bit(index, true);
+}
+
+fn is_marked(bits: Option<&BooleanBufferBuilder>, index: usize) -> bool {
+    bits.is_some_and(|bits| index < bits.len() && bits.get_bit(index))
+}
+
+/// The type two key columns are compared as: their own when they share it,
+/// else one both cast to without losing what tells keys apart. `None` for keys
+/// of unrelated types, e.g. a number and a string.
+fn common_key_type(left: &DataType, right: &DataType) -> Option<DataType> {
+    let value_type = |data_type: &DataType| match data_type {
+        DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+        data_type => data_type.clone(),
+    };
+    let (left, right) = (value_type(left), value_type(right));
+    let is_decimal = |data_type: &DataType| {
+        matches!(
+            data_type,
+            DataType::Decimal32(..)
+                | DataType::Decimal64(..)
+                | DataType::Decimal128(..)
+                | DataType::Decimal256(..)
+        )
+    };
+    let scale = |data_type: &DataType| match data_type {
+        DataType::Decimal32(_, scale)
+        | DataType::Decimal64(_, scale)
+        | DataType::Decimal128(_, scale)
+        | DataType::Decimal256(_, scale) => *scale,
+        _ => 0,
+    };
+    let is_string = |data_type: &DataType| {
+        matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        )
+    };
+    let is_binary = |data_type: &DataType| {
+        matches!(
+            data_type,
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView
+        )
+    };
+
+    if left == right {
+        return Some(left);
+    }
+    Some(match (&left, &right) {
+        (a, b) if a.is_integer() && b.is_integer() => {
+            if a.is_unsigned_integer() && b.is_unsigned_integer() {
+                DataType::UInt64
+            } else if matches!(a, DataType::UInt64) || matches!(b, DataType::UInt64) {
+                DataType::Decimal128(20, 0)
+            } else {
+                DataType::Int64
+            }
+        }
+        (a, b) if (is_decimal(a) || a.is_integer()) && (is_decimal(b) || b.is_integer()) => {
+            DataType::Decimal256(76, scale(a).max(scale(b)))
+        }
+        (a, b) if a.is_numeric() && b.is_numeric() => DataType::Float64,
+        (a, b) if is_string(a) && is_string(b) => DataType::LargeUtf8,
+        (a, b) if is_binary(a) && is_binary(b) => DataType::LargeBinary,
+        (DataType::Date32 | DataType::Date64, DataType::Date32 | DataType::Date64) => {
+            DataType::Date64
+        }
+        (DataType::Timestamp(a, a_zone), DataType::Timestamp(b, b_zone)) if a_zone == b_zone => {
+            DataType::Timestamp((*a).max(*b), a_zone.clone())
+        }
+        _ => return None,
+    })
+}
+
+fn key_column(schema: &Schema, column: &str, path: &Path) -> Result<usize, ViewerError> {
+    schema
+        .fields()
+        .iter()
+        .position(|field| field.name() == column)
+        .ok_or_else(|| ViewerError::NoSuchColumn {
+            column: column.to_string(),
+            path: path.to_path_buf(),
+        })
+}
+
+/// Left columns, then right ones renamed where they clash and, for a left join,
+/// made nullable.
+fn joined_schema(left: &Schema, right: &Schema, kind: JoinKind) -> SchemaRef {
+    let mut fields: Vec<Field> = left
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone())
+        .collect();
+    for field in right.fields() {
+        let mut name = field.name().clone();
+        while fields.iter().any(|taken| taken.name() == &name) {
+            name.push_str("_right");
+        }
+        let nullable = field.is_nullable() || kind == JoinKind::Left;
+        fields.push(
+            field
+                .as_ref()
+                .clone()
+                .with_name(name)
+                .with_nullable(nullable),
+        );
+    }
+    Arc::new(Schema::new(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Int32Array, Int64Array, StringArray};
+    use tempfile::NamedTempFile;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    fn write_customers() -> NamedTempFile {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("customer_id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(1), Some(3), None])),
+                Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    fn read(path: &Path) -> RecordBatch {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        concat_batches(&batches[0].schema(), &batches).unwrap()
+    }
+
+    #[test]
+    fn inner_and_left_joins_match_keys_across_types() {
+        let orders =
+            write_test_parquet_with_row_groups(4, 2).expect("parquet write should succeed");
+        let customers = write_customers();
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("joined.parquet");
+        let mut spec = JoinSpec {
+            right: customers.path().to_path_buf(),
+            left_key: "id".to_string(),
+            right_key: "customer_id".to_string(),
+            kind: JoinKind::Inner,
+        };
+
+        let summary = join_files(orders.path(), &spec, &destination, &Progress::default())
+            .expect("join should succeed");
+        assert_eq!(
+            summary,
+            JoinSummary {
+                left_rows: 4,
+                right_rows: 4,
+                rows: 3,
+                unmatched_left: 2,
+            }
+        );
+        let joined = read(&destination);
+        let columns: Vec<&String> = joined
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .collect();
+        assert_eq!(columns, ["id", "name", "customer_id", "name_right"]);
+        let ids = joined
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(ids, &Int32Array::from(vec![1, 1, 3]));
+
+        spec.kind = JoinKind::Left;
+        let summary = join_files(orders.path(), &spec, &destination, &Progress::default())
+            .expect("join should succeed");
+        assert_eq!(summary.rows, 5);
+        let joined = read(&destination);
+        assert!(joined.schema().field(3).is_nullable());
+        let names = joined
+            .column(3)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(
+            names,
+            &StringArray::from(vec![None, Some("a"), Some("b"), None, Some("c")])
+        );
+
+        spec.right_key = "missing".to_string();
+        assert!(matches!(
+            join_files(orders.path(), &spec, &destination, &Progress::default()),
+            Err(ViewerError::NoSuchColumn { .. })
+        ));
+        spec.left_key = "name".to_string();
+        spec.right_key = "customer_id".to_string();
+        assert!(matches!(
+            join_files(orders.path(), &spec, &destination, &Progress::default()),
+            Err(ViewerError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn right_files_past_the_limit_are_joined_a_part_at_a_time() {
+        let left = write_test_parquet_with_row_groups(3000, 1000).unwrap();
+        let right = write_test_parquet_with_row_groups(2500, 1000).unwrap();
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let spec = JoinSpec {
+            right: right.path().to_path_buf(),
+            left_key: "id".to_string(),
+            right_key: "id".to_string(),
+            kind: JoinKind::Left,
+        };
+        let join = |limit, name| {
+            let destination = output.path().join(name);
+            let summary = join_within(
+                left.path(),
+                &spec,
+                &destination,
+                limit,
+                &Progress::default(),
+            )
+            .expect("join should succeed");
+            let joined = read(&destination);
+            let mut pairs: Vec<(i32, Option<i32>)> = (0..joined.num_rows())
+                .map(|row| {
+                    let id = |column: usize| {
+                        let ids = joined
+                            .column(column)
+                            .as_any()
+                            .downcast_ref::<Int32Array>()
+                            .unwrap();
+                        ids.is_valid(row).then(|| ids.value(row))
+                    };
+                    (id(0).unwrap(), id(2))
+                })
+                .collect();
+            pairs.sort();
+            (summary, pairs)
+        };
+
+        let (whole, in_memory) = join(None, "whole.parquet");
+        let (parts, in_parts) = join(Some(1), "parts.parquet");
+        assert_eq!(
+            whole,
+            JoinSummary {
+                left_rows: 3000,
+                right_rows: 2500,
+                rows: 3000,
+                unmatched_left: 500,
+            }
+        );
+        assert_eq!(parts, whole);
+        assert_eq!(in_parts, in_memory);
+        assert_eq!(in_memory[2499], (2499, Some(2499)));
+        assert_eq!(in_memory[2500], (2500, None));
+    }
+}
@@ -0,0 +1,78 @@
+//! Reading, filtering and exporting Parquet files for ParquetViewer.
+//!
+//! Nothing here depends on the UI, so other front-ends can embed it and it can
+//! be tested on its own. [`load_preview`] opens a file; rows beyond its head are
+//! read through [`DataPreview::rows_for_range`] or streamed with
+//! [`source::spawn_reads`], and [`filter::row_order`] scans for filtered and
+//! sorted rows.
+
+pub mod aggregate;
+pub mod batching;
+pub mod bench;
+pub mod cancel;
+pub mod cast;
+pub mod computed;
+pub mod contract;
+pub mod dictionary;
+pub mod doctor;
+pub mod duplicates;
+pub mod embedding;
+pub mod error;
+pub mod explain;
+pub mod export;
+pub mod extension;
+pub mod filter;
+pub mod footer;
+pub mod geo;
+pub mod groupby;
+pub mod histogram;
+pub mod hyperloglog;
+pub mod inspect;
+pub mod intern;
+pub mod join;
+pub mod locale;
+pub mod merge;
+pub mod metadata;
+pub mod metrics;
+pub mod minimap;
+pub mod output;
+pub mod pagedump;
+pub mod pages;
+pub mod placeholders;
+pub mod postgres;
+pub mod prefetch;
+pub mod preview;
+pub mod profile;
+pub mod progress;
+pub mod projection;
+pub mod provenance;
+pub mod pushdown;
+pub mod recover;
+pub mod render;
+pub mod report;
+pub mod retype;
+pub mod rewrite;
+pub mod rowdiff;
+pub mod rules;
+pub mod scratch;
+pub mod seek;
+pub mod selection;
+pub mod sizes;
+pub mod snapshot;
+pub mod sortedness;
+pub mod source;
+pub mod spill;
+pub mod stats;
+pub mod stream;
+pub mod thrift;
+pub mod timeindex;
+pub mod timeline;
+pub mod truncate;
+pub mod utf8;
+pub mod validate;
+pub mod verify;
+pub mod width;
+pub mod xlsx;
+
+pub use error::ViewerError;
+pub use preview::{cell_text, load_preview, DataPreview};
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::str::FromStr;
+
+use arrow::array::Array;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::ArrowError;
+
+use crate::cell_text;
+use crate::render::CellRenderer;
+
+/// Order of the day, month and year in a date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+/// How numbers and dates are written in a language and region, e.g. `de-DE`
+/// writes `1.234,5` and `05.03.2024`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    /// Tag the locale was picked by, as given.
+    pub tag: String,
+    pub decimal: char,
+    /// Separator between groups of three digits, if digits are grouped.
+    pub grouping: Option<char>,
+    pub date_order: DateOrder,
+    pub date_separator: char,
+}
+
+/// Locales by language, and by language and region where regions differ;
+/// the first entry of a language is its default.
+const LOCALES: &[(&str, char, Option<char>, DateOrder, char)] = &[
+    ("en-us", '.', Some(','), DateOrder::MonthDayYear, '/'),
+    ("en-gb", '.', Some(','), DateOrder::DayMonthYear, '/'),
+    ("en-au", '.', Some(','), DateOrder::DayMonthYear, '/'),
+    ("en-ca", '.', Some(','), DateOrder::YearMonthDay, '-'),
+    ("de", ',', Some('.'), DateOrder::DayMonthYear, '.'),
+    ("de-ch", '.', Some('\''), DateOrder::DayMonthYear, '.'),
+    ("fr", ',', Some('\u{202f}'), DateOrder::DayMonthYear, '/'),
+    ("fr-ca", ',', Some('\u{a0}'), DateOrder::YearMonthDay, '-'),
+    ("es", ',', Some('.'), DateOrder::DayMonthYear, '/'),
+    ("it", ',', Some('.'), DateOrder::DayMonthYear, '/'),
+    ("pt", ',', Some('.'), DateOrder::DayMonthYear, '/'),
+    ("nl", ',', Some('.'), DateOrder::DayMonthYear, '-'),
+    ("pl", ',', Some('\u{a0}'), DateOrder::DayMonthYear, '.'),
+    ("ru", ',', Some('\u{a0}'), DateOrder::DayMonthYear, '.'),
+    ("sv", ',', Some('\u{a0}'), DateOrder::YearMonthDay, '-'),
+    ("ja", '.', Some(','), DateOrder::YearMonthDay, '/'),
+    ("zh", '.', Some(','), DateOrder::YearMonthDay, '/'),
+    ("ko", '.', Some(','), DateOrder::YearMonthDay, '.'),
+];
+
+impl Locale {
+    /// Values as Arrow writes them: no grouping, `.` decimals and ISO 8601 dates.
+    pub fn posix() -> Self {
+        Self {
+            tag: "C".to_string(),
+            decimal: '.',
+            grouping: None,
+            date_order: DateOrder::YearMonthDay,
+            date_separator: '-',
+        }
+    }
+
+    /// `text` written in this locale if it is a plain decimal number such as
+    /// Arrow writes, e.g. `-1234567.5`; anything else, such as `NaN` or
+    /// `1e-7`, unchanged.
+    pub fn format_number(&self, text: &str) -> String {
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", text),
+        };
+        let (whole, fraction) = match unsigned.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (unsigned, None),
+        };
+        let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !digits(whole) || !fraction.is_none_or(digits) {
+            return text.to_string();
+        }
+
+        let mut formatted = sign.to_string();
+        for (index, digit) in whole.chars().enumerate() {
+            let left = whole.len() - index;
+            if index > 0 && left % 3 == 0 {
+                formatted.extend(self.grouping);
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    /// `text` with its leading ISO 8601 date, e.g. `2024-03-05` or
+    /// `2024-03-05T10:00:00Z`, written in this locale and the time set apart by
+    /// a space; anything else unchanged.
+    pub fn format_date(&self, text: &str) -> String {
+        let bytes = text.as_bytes();
+        let is_date = bytes.len() >= 10
+            && bytes[4] == b'-'
+            && bytes[7] == b'-'
+            && [0..4, 5..7, 8..10]
+                .iter()
+                .all(|range| bytes[range.clone()].iter().all(u8::is_ascii_digit));
+        if !is_date {
+            return text.to_string();
+        }
+
+        let (year, month, day) = (&text[0..4], &text[5..7], &text[8..10]);
+        let parts = match self.date_order {
+            DateOrder::DayMonthYear => [day, month, year],
+            DateOrder::MonthDayYear => [month, day, year],
+            DateOrder::YearMonthDay => [year, month, day],
+        };
+        let date = parts.join(&self.date_separator.to_string());
+        let time = &text[10..];
+        match time.strip_prefix('T') {
+            Some(time) => format!("{date} {time}"),
+            None => format!("{date}{time}"),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Parse a tag such as `de-DE`, `en_GB.UTF-8`, `fr` or `C`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tag = input.trim();
+        let base = tag
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        if matches!(base.as_str(), "c" | "posix") {
+            return Ok(Self::posix());
+        }
+        let language = base.split('-').next().unwrap_or_default();
+        let (_, decimal, grouping, date_order, date_separator) = LOCALES
+            .iter()
+            .find(|(name, ..)| *name == base)
+            .or_else(|| {
+                LOCALES
+                    .iter()
+                    .find(|(name, ..)| name.split('-').next() == Some(language))
+            })
+            .copied()
+            .ok_or_else(|| format!("unknown locale \"{tag}\", e.g. en-US, de-DE or C"))?;
+        Ok(Self {
+            tag: tag.to_string(),
+            decimal,
+            grouping,
+            date_order,
+            date_separator,
+        })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.tag)
+    }
+}
+
+/// Whether a column holds numbers or dates, which [`LocaleRenderer`] writes.
+pub fn is_localized_column(_schema: &Schema, field: &Field) -> bool {
+    is_number(field.data_type()) || is_date(field.data_type())
+}
+
+fn is_number(data_type: &DataType) -> bool {
+    data_type.is_numeric()
+}
+
+fn is_date(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Date32 | DataType::Date64 | DataType::Timestamp(..)
+    )
+}
+
+/// Writes numbers and dates the way a locale does.
+pub struct LocaleRenderer(pub Locale);
+
+impl CellRenderer for LocaleRenderer {
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        let text = cell_text(array, row)?;
+        Ok(if is_number(array.data_type()) {
+            self.0.format_number(&text)
+        } else if is_date(array.data_type()) {
+            self.0.format_date(&text)
+        } else {
+            text
+        })
+    }
+
+    fn presentation_only(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Date32Array, Float64Array, Int64Array};
+
+    #[test]
+    fn tags_pick_a_language_and_region() {
+        let german: Locale = "de_DE.UTF-8".parse().unwrap();
+        assert_eq!((german.decimal, german.grouping), (',', Some('.')));
+        assert_eq!(german.tag, "de_DE.UTF-8");
+
+        let british: Locale = "en-GB".parse().unwrap();
+        assert_eq!(british.date_order, DateOrder::DayMonthYear);
+        let english: Locale = "en".parse().unwrap();
+        assert_eq!(english.date_order, DateOrder::MonthDayYear);
+
+        assert_eq!("C".parse::<Locale>().unwrap(), Locale::posix());
+        assert!("xx-YY".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn numbers_and_dates_are_written_as_the_locale_does() {
+        let german: Locale = "de-DE".parse().unwrap();
+        assert_eq!(german.format_number("-1234567.25"), "-1.234.567,25");
+        assert_eq!(german.format_number("123"), "123");
+        assert_eq!(german.format_number("1000"), "1.000");
+        assert_eq!(german.format_number("NaN"), "NaN");
+        assert_eq!(german.format_number("1e-7"), "1e-7");
+        assert_eq!(german.format_date("2024-03-05"), "05.03.2024");
+        assert_eq!(
+            german.format_date("2024-03-05T10:00:00Z"),
+            "05.03.2024 10:00:00Z"
+        );
+
+        let american: Locale = "en-US".parse().unwrap();
+        assert_eq!(american.format_date("2024-03-05"), "03/05/2024");
+        assert_eq!(Locale::posix().format_number("1234.5"), "1234.5");
+        assert_eq!(Locale::posix().format_date("not a date"), "not a date");
+    }
+
+    #[test]
+    fn the_renderer_localizes_number_and_date_columns() {
+        let renderer = LocaleRenderer("fr-FR".parse().unwrap());
+        let ints = Int64Array::from(vec![1234567]);
+        let floats = Float64Array::from(vec![0.5]);
+        let dates = Date32Array::from(vec![19787]);
+
+        assert_eq!(
+            renderer.render(&ints, 0).unwrap(),
+            "1\u{202f}234\u{202f}567"
+        );
+        assert_eq!(renderer.render(&floats, 0).unwrap(), "0,5");
+        assert_eq!(renderer.render(&dates, 0).unwrap(), "05/03/2024");
+    }
+}
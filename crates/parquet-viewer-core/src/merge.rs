@@ -0,0 +1,230 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::ArrowWriter;
+
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::rewrite::RewriteOptions;
+use crate::ViewerError;
+
+/// Outcome of a merge, reported back to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeSummary {
+    pub files: usize,
+    pub rows: usize,
+    pub output_row_groups: usize,
+    pub output_bytes: u64,
+}
+
+/// Concatenate `inputs` into a single file at `output`.
+///
+/// Every schema is checked before anything is written so that all mismatches are
+/// reported at once. Columns must agree on name, order and type; nullability may
+/// differ and is widened to nullable in the output.
+pub fn merge_files(
+    inputs: &[PathBuf],
+    output: &Path,
+    options: &RewriteOptions,
+    progress: &Progress,
+) -> Result<MergeSummary, ViewerError> {
+    ensure_distinct(inputs.iter().map(PathBuf::as_path), output)?;
+
+    let mut schemas = Vec::with_capacity(inputs.len());
+    let mut total_rows = 0;
+    for input in inputs {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(input)?)?;
+        schemas.push(builder.schema().clone());
+        total_rows += builder.metadata().file_metadata().num_rows() as usize;
+    }
+    progress.set_total(total_rows);
+
+    let Some(first) = schemas.first() else {
+        return Ok(MergeSummary {
+            files: 0,
+            rows: 0,
+            output_row_groups: 0,
+            output_bytes: 0,
+        });
+    };
+
+    let mut mismatches = Vec::new();
+    for (input, schema) in inputs.iter().zip(&schemas).skip(1) {
+        for mismatch in schema_mismatches(first, schema) {
+            mismatches.push(format!("{}: {mismatch}", input.display()));
+        }
+    }
+    if !mismatches.is_empty() {
+        return Err(ViewerError::SchemaMismatch(mismatches));
+    }
+
+    let merged_schema = merged_schema(&schemas);
+    let props = options.writer_properties(None);
+    let (rows, metadata) = write_atomically(output, |file| {
+        let mut writer = ArrowWriter::try_new(file, merged_schema.clone(), Some(props))?;
+        let mut rows = 0;
+        for input in inputs {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(input)?)?.build()?;
+            for batch in reader {
+                progress.check()?;
+                let batch = batch?;
+                rows += batch.num_rows();
+                let batch = RecordBatch::try_new(merged_schema.clone(), batch.columns().to_vec())?;
+                writer.write(&batch)?;
+                progress.advance(batch.num_rows());
+            }
+        }
+        Ok((rows, writer.close()?))
+    })?;
+
+    Ok(MergeSummary {
+        files: inputs.len(),
+        rows,
+        output_row_groups: metadata.num_row_groups(),
+        output_bytes: std::fs::metadata(output)?.len(),
+    })
+}
+
+/// Describe every way `actual` differs from `expected`, ignoring nullability.
+fn schema_mismatches(expected: &Schema, actual: &Schema) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    if expected.fields().len() != actual.fields().len() {
+        mismatches.push(format!(
+            "has {} columns, expected {}",
+            actual.fields().len(),
+            expected.fields().len()
+        ));
+    }
+
+    for (index, (expected, actual)) in expected.fields().iter().zip(actual.fields()).enumerate() {
+        if expected.name() != actual.name() {
+            mismatches.push(format!(
+                "column {} is named `{}`, expected `{}`",
+                index + 1,
+                actual.name(),
+                expected.name()
+            ));
+        } else if expected.data_type() != actual.data_type() {
+            mismatches.push(format!(
+                "column `{}` has type {}, expected {}",
+                actual.name(),
+                actual.data_type(),
+                expected.data_type()
+            ));
+        }
+    }
+
+    mismatches
+}
+
+/// Use the first schema, marking a column nullable if any input allows nulls.
+fn merged_schema(schemas: &[SchemaRef]) -> SchemaRef {
+    let first = &schemas[0];
+    let fields: Vec<Field> = first
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let nullable = schemas
+                .iter()
+                .any(|schema| schema.field(index).is_nullable());
+            field.as_ref().clone().with_nullable(nullable)
+        })
+        .collect();
+
+    Arc::new(Schema::new_with_metadata(fields, first.metadata().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::ArrayRef;
+    use arrow::array::{Int32Array, Int64Array};
+    use arrow::datatypes::DataType;
+    use tempfile::TempDir;
+
+    fn write_file(dir: &TempDir, name: &str, field: Field, column: ArrayRef) -> PathBuf {
+        let path = dir.path().join(name);
+        let schema = Arc::new(Schema::new(vec![field]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![column]).unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+
+    #[test]
+    fn merge_concatenates_compatible_files() {
+        let dir = TempDir::new().unwrap();
+        let first = write_file(
+            &dir,
+            "a.parquet",
+            Field::new("id", DataType::Int32, false),
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+        );
+        let second = write_file(
+            &dir,
+            "b.parquet",
+            Field::new("id", DataType::Int32, true),
+            Arc::new(Int32Array::from(vec![Some(4), None])),
+        );
+        let output = dir.path().join("merged.parquet");
+
+        let summary = merge_files(
+            &[first, second],
+            &output,
+            &RewriteOptions::default(),
+            &Progress::default(),
+        )
+        .expect("merge should succeed");
+
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.rows, 5);
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new(File::open(&output).unwrap()).unwrap();
+        assert!(builder.schema().field(0).is_nullable());
+        assert_eq!(builder.metadata().file_metadata().num_rows(), 5);
+    }
+
+    #[test]
+    fn merge_reports_schema_mismatches() {
+        let dir = TempDir::new().unwrap();
+        let first = write_file(
+            &dir,
+            "a.parquet",
+            Field::new("id", DataType::Int32, false),
+            Arc::new(Int32Array::from(vec![1])),
+        );
+        let second = write_file(
+            &dir,
+            "b.parquet",
+            Field::new("id", DataType::Int64, false),
+            Arc::new(Int64Array::from(vec![1])),
+        );
+        let output = dir.path().join("merged.parquet");
+
+        let error = merge_files(
+            &[first, second],
+            &output,
+            &RewriteOptions::default(),
+            &Progress::default(),
+        )
+        .expect_err("merge should fail");
+
+        match error {
+            ViewerError::SchemaMismatch(mismatches) => {
+                assert_eq!(mismatches.len(), 1);
+                assert!(mismatches[0].contains("b.parquet"));
+                assert!(mismatches[0].contains("Int64"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(!output.exists());
+    }
+}
@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::path::Path;
+
+use parquet::arrow::parquet_to_arrow_schema;
+use parquet::file::metadata::{KeyValue, ParquetMetaDataReader};
+
+use crate::ViewerError;
+
+/// Footer key holding the IPC-encoded Arrow schema written by Arrow-based writers.
+pub const ARROW_SCHEMA_KEY: &str = "ARROW:schema";
+
+/// Free-form metadata of a file, where dataframe libraries keep provenance such
+/// as pandas index and dtype information or the writing library's version.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MetadataEntries {
+    /// Footer key-value entries in file order, except the encoded Arrow schema.
+    pub key_values: Vec<(String, String)>,
+    /// Metadata of the embedded Arrow schema and of its fields, the latter keyed
+    /// as `column: key`, sorted by key.
+    pub schema: Vec<(String, String)>,
+}
+
+impl MetadataEntries {
+    pub fn is_empty(&self) -> bool {
+        self.key_values.is_empty() && self.schema.is_empty()
+    }
+
+    /// Every entry as `key: value` lines, for the clipboard.
+    pub fn to_text(&self) -> String {
+        self.key_values
+            .iter()
+            .chain(&self.schema)
+            .map(|(key, value)| format!("{key}: {value}\n"))
+            .collect()
+    }
+}
+
+pub fn metadata_entries(path: &Path) -> Result<MetadataEntries, ViewerError> {
+    let metadata = ParquetMetaDataReader::new().parse_and_finish(&File::open(path)?)?;
+    let file_metadata = metadata.file_metadata();
+    let entries = file_metadata
+        .key_value_metadata()
+        .map_or(&[][..], Vec::as_slice);
+
+    let key_values = entries
+        .iter()
+        .filter(|entry| entry.key != ARROW_SCHEMA_KEY)
+        .map(|entry| (entry.key.clone(), entry.value.clone().unwrap_or_default()))
+        .collect();
+
+    // Converting with only the encoded schema keeps the footer entries, which
+    // are listed above, out of the schema's metadata.
+    let arrow_schema: Vec<KeyValue> = entries
+        .iter()
+        .filter(|entry| entry.key == ARROW_SCHEMA_KEY)
+        .cloned()
+        .collect();
+    let mut schema = Vec::new();
+    if !arrow_schema.is_empty() {
+        let decoded = parquet_to_arrow_schema(file_metadata.schema_descr(), Some(&arrow_schema))?;
+        schema.extend(
+            decoded
+                .metadata()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        for field in decoded.fields() {
+            schema.extend(
+                field
+                    .metadata()
+                    .iter()
+                    .map(|(key, value)| (format!("{}: {key}", field.name()), value.clone())),
+            );
+        }
+        schema.sort();
+    }
+
+    Ok(MetadataEntries { key_values, schema })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn footer_and_schema_metadata_are_listed_separately() {
+        let field = Field::new("id", DataType::Int32, false)
+            .with_metadata(HashMap::from([("unit".to_string(), "count".to_string())]));
+        let schema = Arc::new(Schema::new_with_metadata(
+            vec![field],
+            HashMap::from([("pandas".to_string(), r#"{"index_columns": []}"#.to_string())]),
+        ));
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1]))])
+            .unwrap();
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                "origin".to_string(),
+                "sensor-7".to_string(),
+            )]))
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let entries = metadata_entries(file.path()).expect("metadata should load");
+
+        assert!(entries
+            .key_values
+            .contains(&("origin".to_string(), "sensor-7".to_string())));
+        assert!(entries
+            .key_values
+            .iter()
+            .all(|(key, _)| key != ARROW_SCHEMA_KEY));
+        assert_eq!(
+            entries.schema,
+            vec![
+                ("id: unit".to_string(), "count".to_string()),
+                ("pandas".to_string(), r#"{"index_columns": []}"#.to_string()),
+            ]
+        );
+        assert!(entries.to_text().contains("origin: sensor-7\n"));
+    }
+}
@@ -0,0 +1,222 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use parquet::arrow::arrow_reader::ArrowReaderOptions;
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::errors::Result as ParquetResult;
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaData, ParquetMetaDataReader};
+use parquet::file::reader::{ChunkReader, Length};
+
+/// Running total of bytes read from one file, shared by every reader opened for it.
+#[derive(Clone, Debug, Default)]
+pub struct ReadCounter(Arc<AtomicU64>);
+
+impl ReadCounter {
+    pub fn bytes(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add(&self, bytes: usize) {
+        self.0.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// A file handle that reports everything the Parquet reader pulls from disk to a [`ReadCounter`].
+pub struct CountingFile {
+    file: File,
+    counter: ReadCounter,
+}
+
+impl CountingFile {
+    pub fn open(path: &Path, counter: &ReadCounter) -> io::Result<Self> {
+        Ok(Self {
+            file: File::open(path)?,
+            counter: counter.clone(),
+        })
+    }
+}
+
+impl Length for CountingFile {
+    fn len(&self) -> u64 {
+        self.file.len()
+    }
+}
+
+impl ChunkReader for CountingFile {
+    type T = CountingRead<BufReader<File>>;
+
+    fn get_read(&self, start: u64) -> ParquetResult<Self::T> {
+        Ok(CountingRead {
+            inner: self.file.get_read(start)?,
+            counter: self.counter.clone(),
+        })
+    }
+
+    fn get_bytes(&self, start: u64, length: usize) -> ParquetResult<Bytes> {
+        let bytes = self.file.get_bytes(start, length)?;
+        self.counter.add(bytes.len());
+        Ok(bytes)
+    }
+}
+
+pub struct CountingRead<R> {
+    inner: R,
+    counter: ReadCounter,
+}
+
+impl<R: Read> Read for CountingRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.counter.add(read);
+        Ok(read)
+    }
+}
+
+/// The async counterpart of [`CountingFile`], for the streaming reader.
+pub struct CountingAsyncFile {
+    file: tokio::fs::File,
+    len: u64,
+    counter: ReadCounter,
+}
+
+impl CountingAsyncFile {
+    pub async fn open(path: &Path, counter: &ReadCounter) -> io::Result<Self> {
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+        Ok(Self {
+            file,
+            len,
+            counter: counter.clone(),
+        })
+    }
+}
+
+impl AsyncFileReader for CountingAsyncFile {
+    fn get_bytes(&mut self, range: Range<u64>) -> BoxFuture<'_, ParquetResult<Bytes>> {
+        async move {
+            let bytes = self.file.get_bytes(range).await?;
+            self.counter.add(bytes.len());
+            Ok(bytes)
+        }
+        .boxed()
+    }
+
+    fn get_metadata<'a>(
+        &'a mut self,
+        options: Option<&'a ArrowReaderOptions>,
+    ) -> BoxFuture<'a, ParquetResult<Arc<ParquetMetaData>>> {
+        let len = self.len;
+        load_metadata(self, len, options)
+    }
+}
+
+/// File contents already in memory, such as a memory-mapped file, counting the
+/// bytes the Parquet reader asks for as if they were read from disk.
+pub struct CountingBytes {
+    bytes: Bytes,
+    counter: ReadCounter,
+}
+
+impl CountingBytes {
+    pub fn new(bytes: Bytes, counter: &ReadCounter) -> Self {
+        Self {
+            bytes,
+            counter: counter.clone(),
+        }
+    }
+}
+
+impl AsyncFileReader for CountingBytes {
+    fn get_bytes(&mut self, range: Range<u64>) -> BoxFuture<'_, ParquetResult<Bytes>> {
+        let bytes = self.bytes.slice(range.start as usize..range.end as usize);
+        self.counter.add(bytes.len());
+        async move { Ok(bytes) }.boxed()
+    }
+
+    fn get_metadata<'a>(
+        &'a mut self,
+        options: Option<&'a ArrowReaderOptions>,
+    ) -> BoxFuture<'a, ParquetResult<Arc<ParquetMetaData>>> {
+        let len = self.bytes.len() as u64;
+        load_metadata(self, len, options)
+    }
+}
+
+/// Read the footer through `reader` so that it is counted too.
+fn load_metadata<'a, R: AsyncFileReader>(
+    reader: &'a mut R,
+    len: u64,
+    options: Option<&'a ArrowReaderOptions>,
+) -> BoxFuture<'a, ParquetResult<Arc<ParquetMetaData>>> {
+    async move {
+        let policy = if options.is_some_and(ArrowReaderOptions::page_index) {
+            PageIndexPolicy::Optional
+        } else {
+            PageIndexPolicy::Skip
+        };
+        let metadata = ParquetMetaDataReader::new()
+            .with_page_index_policy(policy)
+            .load_and_finish(reader, len)
+            .await?;
+        Ok(Arc::new(metadata))
+    }
+    .boxed()
+}
+
+/// Human-readable size, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn counting_file_tracks_reads_and_bytes() {
+        let mut file = NamedTempFile::new().expect("temp file should be created");
+        file.write_all(&[7u8; 64]).unwrap();
+        let counter = ReadCounter::default();
+        let reader = CountingFile::open(file.path(), &counter).expect("file should open");
+
+        reader.get_bytes(8, 16).expect("bytes should be read");
+        let mut buffer = Vec::new();
+        reader
+            .get_read(60)
+            .expect("reader should open")
+            .read_to_end(&mut buffer)
+            .unwrap();
+
+        assert_eq!(counter.bytes(), 20);
+    }
+
+    #[test]
+    fn sizes_are_formatted_with_binary_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::footer;
+use crate::metrics::ReadCounter;
+use crate::ViewerError;
+
+/// What the minimap shades each row group by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MinimapMetric {
+    /// Share of the row group's values that are null, over all columns.
+    NullDensity,
+    /// Compressed bytes per row.
+    BytesPerRow,
+}
+
+impl MinimapMetric {
+    pub const ALL: [MinimapMetric; 2] = [MinimapMetric::NullDensity, MinimapMetric::BytesPerRow];
+}
+
+impl fmt::Display for MinimapMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinimapMetric::NullDensity => f.write_str("Null Density"),
+            MinimapMetric::BytesPerRow => f.write_str("Bytes per Row"),
+        }
+    }
+}
+
+/// `metric` for each row group of the file at `path`, from the footer alone.
+/// Column chunks without a null count are counted as having no nulls.
+pub fn row_group_metrics(
+    path: &Path,
+    metric: MinimapMetric,
+    bytes_read: &ReadCounter,
+) -> Result<Vec<f64>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    Ok(metadata
+        .metadata()
+        .row_groups()
+        .iter()
+        .map(|row_group| {
+            let rows = row_group.num_rows().max(1) as f64;
+            match metric {
+                MinimapMetric::NullDensity => {
+                    let nulls: u64 = row_group
+                        .columns()
+                        .iter()
+                        .filter_map(|chunk| chunk.statistics()?.null_count_opt())
+                        .sum();
+                    nulls as f64 / (rows * row_group.num_columns().max(1) as f64)
+                }
+                MinimapMetric::BytesPerRow => row_group.compressed_size() as f64 / rows,
+            }
+        })
+        .collect())
+}
+
+/// Each of `values` as the share of the others below it, from 0 to 1, so that
+/// shades spread evenly however skewed the values are. Equal values share a shade.
+pub fn equalize(values: &[f64]) -> Vec<f32> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let top = (values.len().max(2) - 1) as f32;
+    values
+        .iter()
+        .map(|value| sorted.partition_point(|other| other < value) as f32 / top)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn null_density_is_shaded_by_rank() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "value",
+            DataType::Int64,
+            true,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![
+                Some(1),
+                Some(2),
+                None,
+                Some(4),
+                None,
+                None,
+            ]))],
+        )
+        .expect("record batch should build");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let nulls = row_group_metrics(
+            file.path(),
+            MinimapMetric::NullDensity,
+            &ReadCounter::default(),
+        )
+        .expect("footer should load");
+        assert_eq!(nulls, vec![0.0, 0.5, 1.0]);
+        assert_eq!(equalize(&nulls), vec![0.0, 0.5, 1.0]);
+        assert_eq!(
+            equalize(&[1.0, 1000.0, 1.0, 2.0]),
+            vec![0.0, 1.0, 0.0, 2.0 / 3.0]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use crate::ViewerError;
+
+/// Refuse to write `output` if it is one of `inputs`, however either is
+/// named: `./in.parquet`, an absolute path, a symlink or a hard link to an
+/// input all count. Creating the output would otherwise truncate the input
+/// before it is read.
+pub fn ensure_distinct<'a>(
+    inputs: impl IntoIterator<Item = &'a Path>,
+    output: &Path,
+) -> Result<(), ViewerError> {
+    for input in inputs {
+        if input == output || same_file(input, output)? {
+            return Err(ViewerError::SameInputOutput(output.to_path_buf()));
+        }
+    }
+    Ok(())
+}
+
+/// Write `output` through `write`, which is handed a new file in the same
+/// directory that replaces `output` only once `write` succeeds. A failed or
+/// cancelled write removes it, leaving no truncated file behind, nor a file
+/// that was already there damaged.
+pub fn write_atomically<T>(
+    output: &Path,
+    write: impl FnOnce(File) -> Result<T, ViewerError>,
+) -> Result<T, ViewerError> {
+    let directory = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".parquet-viewer-").suffix(".tmp");
+    // Temporary files are private by default; the output gets the permissions a
+    // newly created file would.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let temporary = builder.tempfile_in(directory)?;
+    let value = write(temporary.reopen()?)?;
+    temporary.persist(output).map_err(|error| error.error)?;
+    Ok(value)
+}
+
+/// Whether `a` and `b` are the same file; `false` if either doesn't exist.
+fn same_file(a: &Path, b: &Path) -> io::Result<bool> {
+    if !a.exists() || !b.exists() {
+        return Ok(false);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let (a, b) = (std::fs::metadata(a)?, std::fs::metadata(b)?);
+        Ok(a.dev() == b.dev() && a.ino() == b.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        // Without file identities, resolving both paths catches everything but
+        // hard links.
+        Ok(std::fs::canonicalize(a)? == std::fs::canonicalize(b)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn other_names_for_an_input_are_refused() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let input = dir.path().join("in.parquet");
+        std::fs::write(&input, b"PAR1").unwrap();
+        let refused = |output: &Path| {
+            matches!(
+                ensure_distinct([input.as_path()], output),
+                Err(ViewerError::SameInputOutput(_))
+            )
+        };
+
+        assert!(refused(&input));
+        assert!(refused(&dir.path().join(".").join("in.parquet")));
+        #[cfg(unix)]
+        {
+            let hard_link = dir.path().join("hard.parquet");
+            std::fs::hard_link(&input, &hard_link).unwrap();
+            assert!(refused(&hard_link));
+            let symlink = dir.path().join("link.parquet");
+            std::os::unix::fs::symlink(&input, &symlink).unwrap();
+            assert!(refused(&symlink));
+        }
+
+        assert!(ensure_distinct([input.as_path()], &dir.path().join("out.parquet")).is_ok());
+    }
+
+    #[test]
+    fn failed_writes_leave_the_output_alone() {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let output = dir.path().join("out.parquet");
+        std::fs::write(&output, b"old").unwrap();
+
+        let result = write_atomically(&output, |mut file| {
+            io::Write::write_all(&mut file, b"partial")?;
+            Err::<(), _>(ViewerError::Cancelled)
+        });
+        assert!(matches!(result, Err(ViewerError::Cancelled)));
+        assert_eq!(std::fs::read(&output).unwrap(), b"old");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        write_atomically(&output, |mut file| {
+            io::Write::write_all(&mut file, b"new")?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), b"new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,266 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use parquet::basic::Type as PhysicalType;
+use parquet::column::page::Page;
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::ColumnDescriptor;
+
+use crate::dictionary::{decode_plain, hex, is_text};
+use crate::ViewerError;
+
+/// The header of one page of a column chunk, as the page reader decoded it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageHeader {
+    /// Position of the page within its column chunk.
+    pub page: usize,
+    /// Page type, e.g. `DATA_PAGE_V2`.
+    pub kind: String,
+    pub encoding: String,
+    /// Values in the page, nulls included.
+    pub values: u32,
+    /// Rows in the page, which only v2 data pages record.
+    pub rows: Option<u32>,
+    pub nulls: Option<u64>,
+    /// Size of the page once decompressed, levels included.
+    pub uncompressed_size: usize,
+    /// Offset in the file and compressed size with header of a data page, from
+    /// the offset index.
+    pub location: Option<(u64, u64)>,
+    /// Smallest and largest value, if the page has statistics.
+    pub min_max: Option<(String, String)>,
+    /// Where the decompressed page starts in the raw output, if it was written.
+    pub raw_offset: Option<u64>,
+}
+
+/// The pages of one column chunk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageDump {
+    /// Leaf column path, dotted.
+    pub column: String,
+    pub row_group: usize,
+    pub pages: Vec<PageHeader>,
+}
+
+impl PageDump {
+    /// A line for the chunk, then one per page.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Column {}, row group {}: {} pages",
+            self.column,
+            self.row_group,
+            self.pages.len()
+        )];
+        for page in &self.pages {
+            let mut line = format!(
+                "page {}: {}, {}, {} values",
+                page.page, page.kind, page.encoding, page.values
+            );
+            if let Some(rows) = page.rows {
+                line += &format!(", {rows} rows");
+            }
+            if let Some(nulls) = page.nulls {
+                line += &format!(", {nulls} nulls");
+            }
+            line += &format!(", {} bytes uncompressed", page.uncompressed_size);
+            if let Some((offset, size)) = page.location {
+                line += &format!(", {size} bytes at offset {offset}");
+            }
+            if let Some((min, max)) = &page.min_max {
+                line += &format!(", min {min}, max {max}");
+            }
+            if let Some(offset) = page.raw_offset {
+                line += &format!(", raw at {offset}");
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// Read the page headers of `column`, a dotted leaf column path, in
+/// `row_group`, writing each page's decompressed bytes back to back to `raw`
+/// if given.
+pub fn dump_pages(
+    path: &Path,
+    column: &str,
+    row_group: usize,
+    raw: Option<&Path>,
+) -> Result<PageDump, ViewerError> {
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+    let metadata = reader.metadata();
+    let schema = metadata.file_metadata().schema_descr();
+    let missing = || ViewerError::NoColumnChunk {
+        column: column.to_string(),
+        row_group,
+    };
+    let leaf = (0..schema.num_columns())
+        .find(|&index| schema.column(index).path().string() == column)
+        .ok_or_else(missing)?;
+    if row_group >= metadata.num_row_groups() {
+        return Err(missing());
+    }
+    let descriptor = schema.column(leaf);
+    // The page reader skips the page index, which is read here for where data pages are.
+    let index = ParquetMetaDataReader::new()
+        .with_page_index_policy(PageIndexPolicy::Optional)
+        .parse_and_finish(&File::open(path)?)?;
+    let locations = index
+        .offset_index()
+        .and_then(|index| index.get(row_group)?.get(leaf))
+        .map(|chunk| chunk.page_locations().as_slice())
+        .unwrap_or_default();
+
+    let mut raw = raw
+        .map(|raw| File::create(raw).map(BufWriter::new))
+        .transpose()?;
+    let mut raw_written = 0;
+    let mut dump = PageDump {
+        column: column.to_string(),
+        row_group,
+        pages: Vec::new(),
+    };
+    let mut data_pages = 0;
+    let mut pages = reader
+        .get_row_group(row_group)?
+        .get_column_page_reader(leaf)?;
+    while let Some(page) = pages.get_next_page()? {
+        let location = match &page {
+            Page::DictionaryPage { .. } => None,
+            _ => {
+                data_pages += 1;
+                locations
+                    .get(data_pages - 1)
+                    .map(|location| (location.offset as u64, location.compressed_page_size as u64))
+            }
+        };
+        let (rows, nulls) = match &page {
+            Page::DataPageV2 {
+                num_rows,
+                num_nulls,
+                ..
+            } => (Some(*num_rows), Some(u64::from(*num_nulls))),
+            page => (None, page.statistics().and_then(Statistics::null_count_opt)),
+        };
+        let raw_offset = match &mut raw {
+            Some(raw) => {
+                raw.write_all(page.buffer())?;
+                let offset = raw_written;
+                raw_written += page.buffer().len() as u64;
+                Some(offset)
+            }
+            None => None,
+        };
+        dump.pages.push(PageHeader {
+            page: dump.pages.len(),
+            kind: format!("{:?}", page.page_type()),
+            encoding: format!("{:?}", page.encoding()),
+            values: page.num_values(),
+            rows,
+            nulls,
+            uncompressed_size: page.buffer().len(),
+            location,
+            min_max: page
+                .statistics()
+                .and_then(|statistics| min_max(&descriptor, statistics)),
+            raw_offset,
+        });
+    }
+    if let Some(mut raw) = raw {
+        raw.flush()?;
+    }
+
+    Ok(dump)
+}
+
+/// The smallest and largest value of page `statistics` as text, written as the
+/// dictionary viewer writes values.
+fn min_max(descriptor: &ColumnDescriptor, statistics: &Statistics) -> Option<(String, String)> {
+    let value = |bytes: &[u8]| match descriptor.physical_type() {
+        // Statistics hold byte arrays without the length plain encoding puts first.
+        PhysicalType::BYTE_ARRAY if is_text(descriptor) => {
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        }
+        PhysicalType::BYTE_ARRAY => Some(hex(bytes)),
+        _ => decode_plain(descriptor, bytes, 1).ok()?.pop(),
+    };
+    Some((
+        value(statistics.min_bytes_opt()?)?,
+        value(statistics.max_bytes_opt()?)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::{EnabledStatistics, WriterProperties};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn page_headers_are_listed_and_raw_pages_written() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("city", DataType::Utf8, false),
+        ]));
+        let cities = ["Oslo", "Lima", "Pune"];
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1_000)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..1_000).map(|row| cities[row % 3]),
+                )),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(600)
+            .set_data_page_row_count_limit(250)
+            .set_write_batch_size(50)
+            .set_dictionary_enabled(false)
+            .set_column_dictionary_enabled("city".into(), true)
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .set_write_page_header_statistics(true)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let ids = dump_pages(file.path(), "id", 1, None).unwrap();
+        assert_eq!(ids.pages.len(), 2);
+        let first = &ids.pages[0];
+        assert_eq!((first.kind.as_str(), first.values), ("DATA_PAGE", 250));
+        assert_eq!(first.min_max, Some(("600".to_string(), "849".to_string())));
+        assert!(first.location.is_some());
+        assert_eq!(first.raw_offset, None);
+        assert_eq!(ids.lines().len(), 3);
+
+        let raw = NamedTempFile::new().unwrap();
+        let cities = dump_pages(file.path(), "city", 0, Some(raw.path())).unwrap();
+        assert_eq!(cities.pages[0].kind, "DICTIONARY_PAGE");
+        assert_eq!(cities.pages[0].location, None);
+        assert_eq!(cities.pages[1].encoding, "RLE_DICTIONARY");
+        assert_eq!(
+            cities.pages[1].raw_offset,
+            Some(cities.pages[0].uncompressed_size as u64)
+        );
+        let total: usize = cities.pages.iter().map(|page| page.uncompressed_size).sum();
+        assert_eq!(std::fs::metadata(raw.path()).unwrap().len(), total as u64);
+
+        assert!(matches!(
+            dump_pages(file.path(), "id", 2, None),
+            Err(ViewerError::NoColumnChunk { .. })
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::ViewerError;
+
+/// A data page and the file row it begins at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageStart {
+    pub row: usize,
+    pub row_group: usize,
+    /// Position of the page within its column chunk.
+    pub page: usize,
+}
+
+/// Where the data pages of each top-level column begin, from the offset index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PageBoundaries {
+    /// Page starts of each top-level column, by ascending row.
+    columns: Vec<Vec<PageStart>>,
+}
+
+impl PageBoundaries {
+    /// The page of `column` that begins at file row `row`, if any. A nested
+    /// column reports the page of its first leaf that begins there.
+    pub fn page_at(&self, column: usize, row: usize) -> Option<&PageStart> {
+        let starts = self.columns.get(column)?;
+        starts
+            .binary_search_by_key(&row, |start| start.row)
+            .ok()
+            .map(|index| &starts[index])
+    }
+
+    /// Pages of `column` over the whole file.
+    pub fn page_count(&self, column: usize) -> usize {
+        self.columns.get(column).map_or(0, Vec::len)
+    }
+}
+
+/// Read the offset index of the file at `path`; `None` if it was written without one.
+pub fn page_boundaries(
+    path: &Path,
+    bytes_read: &ReadCounter,
+) -> Result<Option<PageBoundaries>, ViewerError> {
+    let file = CountingFile::open(path, bytes_read)?;
+    let metadata = ParquetMetaDataReader::new()
+        .with_page_index_policy(PageIndexPolicy::Optional)
+        .parse_and_finish(&file)?;
+    let Some(offset_index) = metadata.offset_index() else {
+        return Ok(None);
+    };
+    let schema = metadata.file_metadata().schema_descr();
+
+    let mut columns = vec![Vec::new(); schema.root_schema().get_fields().len()];
+    let mut first_row = 0;
+    for (row_group, (chunks, group)) in offset_index.iter().zip(metadata.row_groups()).enumerate() {
+        for (leaf, chunk) in chunks.iter().enumerate() {
+            let starts: &mut Vec<PageStart> = &mut columns[schema.get_column_root_idx(leaf)];
+            for (page, location) in chunk.page_locations().iter().enumerate() {
+                starts.push(PageStart {
+                    row: first_row + location.first_row_index as usize,
+                    row_group,
+                    page,
+                });
+            }
+        }
+        first_row += group.num_rows() as usize;
+    }
+    for starts in &mut columns {
+        // Keeps the first leaf's page where the leaves of a nested column share a row.
+        starts.sort_by_key(|start| start.row);
+        starts.dedup_by_key(|start| start.row);
+    }
+    Ok(Some(PageBoundaries { columns }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    fn write_file(properties: WriterProperties) -> NamedTempFile {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(0..1_000))],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    #[test]
+    fn pages_are_found_from_the_offset_index() {
+        let file = write_file(
+            WriterProperties::builder()
+                .set_max_row_group_size(600)
+                .set_data_page_row_count_limit(250)
+                .set_write_batch_size(50)
+                .build(),
+        );
+
+        let pages = page_boundaries(file.path(), &ReadCounter::default())
+            .expect("offset index should load")
+            .expect("the writer adds an offset index");
+        assert_eq!(
+            pages.page_at(0, 600),
+            Some(&PageStart {
+                row: 600,
+                row_group: 1,
+                page: 0
+            })
+        );
+        assert_eq!(pages.page_at(0, 250).map(|start| start.page), Some(1));
+        assert_eq!(pages.page_at(0, 251), None);
+        assert_eq!(pages.page_count(0), 5);
+
+        let without_index = write_file(
+            WriterProperties::builder()
+                .set_offset_index_disabled(true)
+                .build(),
+        );
+        assert_eq!(
+            page_boundaries(without_index.path(), &ReadCounter::default()).unwrap(),
+            None
+        );
+    }
+}
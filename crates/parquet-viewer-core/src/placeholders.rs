@@ -0,0 +1,123 @@
+use std::sync::OnceLock;
+
+use arrow::datatypes::DataType;
+use serde::{Deserialize, Serialize};
+
+/// Text shown for values that have none of their own or aren't numbers: nulls,
+/// NaN, infinities and empty strings.
+///
+/// Filters, highlight rules and exports see the same text as the table, so a
+/// filter for nulls is written with [`Placeholders::null`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Placeholders {
+    pub null: String,
+    pub nan: String,
+    /// Positive infinity; negative infinity is shown with a `-` in front.
+    pub infinity: String,
+    pub empty: String,
+}
+
+impl Default for Placeholders {
+    fn default() -> Self {
+        Self {
+            null: "null".to_string(),
+            nan: "NaN".to_string(),
+            infinity: "inf".to_string(),
+            empty: String::new(),
+        }
+    }
+}
+
+/// Which kind of placeholder a cell shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Special {
+    Null,
+    NaN,
+    Infinity,
+    Empty,
+}
+
+impl Placeholders {
+    /// Text of a non-null value as Arrow writes it, `text`, in a column of
+    /// `data_type`, with NaN, infinities and empty strings replaced.
+    pub fn replace(&self, data_type: &DataType, text: String) -> String {
+        if data_type.is_floating() {
+            match text.as_str() {
+                "NaN" => return self.nan.clone(),
+                "inf" => return self.infinity.clone(),
+                "-inf" => return format!("-{}", self.infinity),
+                _ => {}
+            }
+        }
+        if is_string(data_type) && text.is_empty() {
+            return self.empty.clone();
+        }
+        text
+    }
+
+    /// Which placeholder `text` is, if any, so the table can set it apart from
+    /// values.
+    pub fn special(&self, text: &str) -> Option<Special> {
+        if text == self.null {
+            Some(Special::Null)
+        } else if text == self.nan {
+            Some(Special::NaN)
+        } else if text.strip_prefix('-').unwrap_or(text) == self.infinity {
+            Some(Special::Infinity)
+        } else if text == self.empty {
+            Some(Special::Empty)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => true,
+        DataType::Dictionary(_, values) => is_string(values),
+        _ => false,
+    }
+}
+
+static PLACEHOLDERS: OnceLock<Placeholders> = OnceLock::new();
+
+/// Set the process-wide placeholder text.
+pub fn init_placeholders(placeholders: Placeholders) {
+    let _ = PLACEHOLDERS.set(placeholders);
+}
+
+/// The placeholders set with `--show-null` and friends or in the settings, or
+/// the defaults.
+pub fn placeholders() -> &'static Placeholders {
+    PLACEHOLDERS.get_or_init(Placeholders::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_values_are_replaced_and_recognized() {
+        let placeholders = Placeholders {
+            null: "∅".to_string(),
+            nan: "not a number".to_string(),
+            infinity: "∞".to_string(),
+            empty: "(empty)".to_string(),
+        };
+        let float = DataType::Float64;
+        assert_eq!(placeholders.replace(&float, "NaN".into()), "not a number");
+        assert_eq!(placeholders.replace(&float, "-inf".into()), "-∞");
+        assert_eq!(placeholders.replace(&float, "1.5".into()), "1.5");
+        assert_eq!(placeholders.replace(&DataType::Utf8, "".into()), "(empty)");
+        assert_eq!(placeholders.replace(&DataType::Utf8, "NaN".into()), "NaN");
+        assert_eq!(placeholders.replace(&DataType::Binary, "".into()), "");
+
+        assert_eq!(placeholders.special("∅"), Some(Special::Null));
+        assert_eq!(placeholders.special("-∞"), Some(Special::Infinity));
+        assert_eq!(placeholders.special("(empty)"), Some(Special::Empty));
+        assert_eq!(placeholders.special("value"), None);
+        assert_eq!(Placeholders::default().special(""), Some(Special::Empty));
+    }
+}
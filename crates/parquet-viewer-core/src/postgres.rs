@@ -0,0 +1,521 @@
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Field, Float16Type, Float32Type, Float64Type, Int16Type,
+    Int32Type, Int64Type, Int8Type, Schema, Time32MillisecondType, Time32SecondType,
+    Time64MicrosecondType, Time64NanosecondType, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt16Type, UInt32Type,
+    UInt8Type,
+};
+use arrow::util::display::array_value_to_string;
+
+use crate::dictionary::hex;
+use crate::filter::ValueFilter;
+use crate::metrics::ReadCounter;
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::stream::{RowStream, StreamSummary};
+use crate::ViewerError;
+
+/// The two formats `COPY ... FROM` reads besides CSV.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// Tab-separated text with `\N` for nulls.
+    Text,
+    /// PostgreSQL's binary tuple format, which loads fastest but needs the
+    /// table's column types to match exactly, as [`create_table`] writes them.
+    Binary,
+}
+
+/// Signature every binary `COPY` file starts with.
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// Days and microseconds from the Unix epoch to 2000-01-01, PostgreSQL's epoch.
+const EPOCH_DAYS: i64 = 10_957;
+const EPOCH_MICROS: i64 = EPOCH_DAYS * 86_400_000_000;
+
+/// The PostgreSQL type values of `data_type` load into, if there is one; nested
+/// types have none.
+pub fn postgres_type(data_type: &DataType) -> Option<String> {
+    Some(match data_type {
+        DataType::Boolean => "boolean".to_string(),
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => "smallint".to_string(),
+        DataType::Int32 | DataType::UInt16 => "integer".to_string(),
+        DataType::Int64 | DataType::UInt32 => "bigint".to_string(),
+        DataType::UInt64 => "numeric(20, 0)".to_string(),
+        DataType::Float16 | DataType::Float32 => "real".to_string(),
+        DataType::Float64 => "double precision".to_string(),
+        DataType::Decimal32(precision, scale)
+        | DataType::Decimal64(precision, scale)
+        | DataType::Decimal128(precision, scale)
+        | DataType::Decimal256(precision, scale) => format!("numeric({precision}, {scale})"),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "text".to_string(),
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => "bytea".to_string(),
+        DataType::Date32 | DataType::Date64 => "date".to_string(),
+        DataType::Timestamp(_, None) => "timestamp".to_string(),
+        DataType::Timestamp(_, Some(_)) => "timestamptz".to_string(),
+        DataType::Time32(_) | DataType::Time64(_) => "time".to_string(),
+        DataType::Dictionary(_, values) => return postgres_type(values),
+        _ => return None,
+    })
+}
+
+/// A `CREATE TABLE` statement for a table named `table` with the columns of
+/// `schema`, non-nullable columns `NOT NULL`.
+pub fn create_table(table: &str, schema: &Schema) -> Result<String, ViewerError> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let not_null = if field.is_nullable() { "" } else { " NOT NULL" };
+            Ok(format!(
+                "    {} {}{not_null}",
+                quote_identifier(field.name()),
+                column_type(field)?
+            ))
+        })
+        .collect::<Result<Vec<_>, ViewerError>>()?;
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n);\n",
+        quote_identifier(table),
+        columns.join(",\n")
+    ))
+}
+
+fn column_type(field: &Field) -> Result<String, ViewerError> {
+    postgres_type(field.data_type()).ok_or_else(|| {
+        ViewerError::InvalidValue(format!(
+            "column `{}` is {}, which has no PostgreSQL type to load into",
+            field.name(),
+            field.data_type()
+        ))
+    })
+}
+
+/// `name` in double quotes, which keeps its case and any odd characters.
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Write the rows of the file at `path` that pass `filters` to `destination`
+/// in a format PostgreSQL's `COPY ... FROM` loads, with the top-level `columns`
+/// named or all of them, streamed as [`stream_csv`](crate::stream::stream_csv)
+/// streams. Values are written as stored, not as the viewer shows them, and
+/// nested columns are refused. A failed or cancelled export leaves no partly
+/// written file behind.
+pub fn stream_copy(
+    path: &Path,
+    filters: &[ValueFilter],
+    columns: &[String],
+    destination: &Path,
+    format: CopyFormat,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<StreamSummary, ViewerError> {
+    ensure_distinct([path], destination)?;
+    let stream = RowStream::new(path, filters, columns, bytes_read)?;
+    for field in stream.schema().fields() {
+        column_type(field)?;
+    }
+
+    write_atomically(destination, |file| {
+        let mut output = BufWriter::new(file);
+        if format == CopyFormat::Binary {
+            output.write_all(BINARY_SIGNATURE)?;
+            // No flags and no header extension.
+            output.write_all(&[0; 8])?;
+        }
+        let mut record = Vec::new();
+        let summary = stream.run(bytes_read, progress, |batch| {
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|values| match values.data_type() {
+                    DataType::Dictionary(_, value_type) => cast(values, value_type),
+                    _ => Ok(values.clone()),
+                })
+                .collect::<Result<Vec<ArrayRef>, _>>()?;
+            for row in 0..batch.num_rows() {
+                record.clear();
+                match format {
+                    CopyFormat::Text => text_record(&mut record, &columns, row)?,
+                    CopyFormat::Binary => binary_record(&mut record, &columns, row)?,
+                }
+                output.write_all(&record)?;
+            }
+            Ok(())
+        })?;
+        if format == CopyFormat::Binary {
+            output.write_all(&(-1i16).to_be_bytes())?;
+        }
+        output.flush()?;
+        Ok(summary)
+    })
+}
+
+/// One row as a line of the text format: fields separated by tabs, nulls as
+/// `\N` and backslashes, tabs and line breaks escaped.
+fn text_record(record: &mut Vec<u8>, columns: &[ArrayRef], row: usize) -> Result<(), ViewerError> {
+    for (index, values) in columns.iter().enumerate() {
+        if index > 0 {
+            record.push(b'\t');
+        }
+        if values.is_null(row) {
+            record.extend_from_slice(b"\\N");
+            continue;
+        }
+        let text = match values.data_type() {
+            DataType::Binary => format!("\\x{}", hex(values.as_binary::<i32>().value(row))),
+            DataType::LargeBinary => format!("\\x{}", hex(values.as_binary::<i64>().value(row))),
+            DataType::BinaryView => format!("\\x{}", hex(values.as_binary_view().value(row))),
+            DataType::FixedSizeBinary(_) => {
+                format!("\\x{}", hex(values.as_fixed_size_binary().value(row)))
+            }
+            _ => array_value_to_string(values, row)?,
+        };
+        for byte in text.bytes() {
+            match byte {
+                b'\\' => record.extend_from_slice(b"\\\\"),
+                b'\t' => record.extend_from_slice(b"\\t"),
+                b'\n' => record.extend_from_slice(b"\\n"),
+                b'\r' => record.extend_from_slice(b"\\r"),
+                byte => record.push(byte),
+            }
+        }
+    }
+    record.push(b'\n');
+    Ok(())
+}
+
+/// One row as a binary tuple: the field count, then each field's length, -1
+/// for null, and its value in network byte order.
+fn binary_record(
+    record: &mut Vec<u8>,
+    columns: &[ArrayRef],
+    row: usize,
+) -> Result<(), ViewerError> {
+    record.extend_from_slice(&(columns.len() as i16).to_be_bytes());
+    for values in columns {
+        if values.is_null(row) {
+            record.extend_from_slice(&(-1i32).to_be_bytes());
+            continue;
+        }
+        let start = record.len();
+        record.extend_from_slice(&[0; 4]);
+        binary_value(record, values.as_ref(), row)?;
+        let length = (record.len() - start - 4) as i32;
+        record[start..start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+    Ok(())
+}
+
+fn binary_value(record: &mut Vec<u8>, values: &dyn Array, row: usize) -> Result<(), ViewerError> {
+    let out_of_range = |value: i64, unit: TimeUnit| {
+        ViewerError::InvalidValue(format!(
+            "{value} ({unit:?}) is out of range for a PostgreSQL timestamp"
+        ))
+    };
+    let micros = |value: i64, unit: TimeUnit| {
+        match unit {
+            TimeUnit::Second => value.checked_mul(1_000_000),
+            TimeUnit::Millisecond => value.checked_mul(1_000),
+            TimeUnit::Microsecond => Some(value),
+            TimeUnit::Nanosecond => Some(value.div_euclid(1_000)),
+        }
+        .ok_or_else(|| out_of_range(value, unit))
+    };
+    match values.data_type() {
+        DataType::Boolean => record.push(u8::from(values.as_boolean().value(row))),
+        DataType::Int8 => record.extend_from_slice(
+            &i16::from(values.as_primitive::<Int8Type>().value(row)).to_be_bytes(),
+        ),
+        DataType::Int16 => {
+            record.extend_from_slice(&values.as_primitive::<Int16Type>().value(row).to_be_bytes())
+        }
+        DataType::UInt8 => record.extend_from_slice(
+            &i16::from(values.as_primitive::<UInt8Type>().value(row)).to_be_bytes(),
+        ),
+        DataType::Int32 => {
+            record.extend_from_slice(&values.as_primitive::<Int32Type>().value(row).to_be_bytes())
+        }
+        DataType::UInt16 => record.extend_from_slice(
+            &i32::from(values.as_primitive::<UInt16Type>().value(row)).to_be_bytes(),
+        ),
+        DataType::Int64 => {
+            record.extend_from_slice(&values.as_primitive::<Int64Type>().value(row).to_be_bytes())
+        }
+        DataType::UInt32 => record.extend_from_slice(
+            &i64::from(values.as_primitive::<UInt32Type>().value(row)).to_be_bytes(),
+        ),
+        DataType::Float16 => record.extend_from_slice(
+            &values
+                .as_primitive::<Float16Type>()
+                .value(row)
+                .to_f32()
+                .to_be_bytes(),
+        ),
+        DataType::Float32 => record.extend_from_slice(
+            &values
+                .as_primitive::<Float32Type>()
+                .value(row)
+                .to_be_bytes(),
+        ),
+        DataType::Float64 => record.extend_from_slice(
+            &values
+                .as_primitive::<Float64Type>()
+                .value(row)
+                .to_be_bytes(),
+        ),
+        DataType::UInt64
+        | DataType::Decimal32(..)
+        | DataType::Decimal64(..)
+        | DataType::Decimal128(..)
+        | DataType::Decimal256(..) => numeric(record, &array_value_to_string(values, row)?),
+        DataType::Utf8 => record.extend_from_slice(values.as_string::<i32>().value(row).as_bytes()),
+        DataType::LargeUtf8 => {
+            record.extend_from_slice(values.as_string::<i64>().value(row).as_bytes())
+        }
+        DataType::Utf8View => {
+            record.extend_from_slice(values.as_string_view().value(row).as_bytes())
+        }
+        DataType::Binary => record.extend_from_slice(values.as_binary::<i32>().value(row)),
+        DataType::LargeBinary => record.extend_from_slice(values.as_binary::<i64>().value(row)),
+        DataType::BinaryView => record.extend_from_slice(values.as_binary_view().value(row)),
+        DataType::FixedSizeBinary(_) => {
+            record.extend_from_slice(values.as_fixed_size_binary().value(row))
+        }
+        DataType::Date32 => {
+            let days = i64::from(values.as_primitive::<Date32Type>().value(row)) - EPOCH_DAYS;
+            record.extend_from_slice(&(days as i32).to_be_bytes())
+        }
+        DataType::Date64 => {
+            let millis = values.as_primitive::<Date64Type>().value(row);
+            let days = millis.div_euclid(86_400_000) - EPOCH_DAYS;
+            record.extend_from_slice(&(days as i32).to_be_bytes())
+        }
+        DataType::Timestamp(unit, _) => {
+            let value = match unit {
+                TimeUnit::Second => values.as_primitive::<TimestampSecondType>().value(row),
+                TimeUnit::Millisecond => {
+                    values.as_primitive::<TimestampMillisecondType>().value(row)
+                }
+                TimeUnit::Microsecond => {
+                    values.as_primitive::<TimestampMicrosecondType>().value(row)
+                }
+                TimeUnit::Nanosecond => values.as_primitive::<TimestampNanosecondType>().value(row),
+            };
+            let since_2000 = micros(value, *unit)?
+                .checked_sub(EPOCH_MICROS)
+                .ok_or_else(|| out_of_range(value, *unit))?;
+            record.extend_from_slice(&since_2000.to_be_bytes())
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            let value = values.as_primitive::<Time32SecondType>().value(row);
+            record.extend_from_slice(&micros(i64::from(value), TimeUnit::Second)?.to_be_bytes())
+        }
+        DataType::Time32(_) => {
+            let value = values.as_primitive::<Time32MillisecondType>().value(row);
+            record
+                .extend_from_slice(&micros(i64::from(value), TimeUnit::Millisecond)?.to_be_bytes())
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            let value = values.as_primitive::<Time64NanosecondType>().value(row);
+            record.extend_from_slice(&micros(value, TimeUnit::Nanosecond)?.to_be_bytes())
+        }
+        DataType::Time64(_) => {
+            let value = values.as_primitive::<Time64MicrosecondType>().value(row);
+            record.extend_from_slice(&value.to_be_bytes())
+        }
+        other => {
+            return Err(ViewerError::InvalidValue(format!(
+                "{other} has no PostgreSQL type to load into"
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// A decimal number written out, e.g. `-1234.50`, as a binary `numeric`: digit
+/// count, weight of the first digit, sign and scale, then the base-10000 digits.
+fn numeric(record: &mut Vec<u8>, text: &str) {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+    // Pad both parts to whole base-10000 digits either side of the point.
+    let integer_digits = integer.len().div_ceil(4);
+    let mut decimal = "0".repeat(integer_digits * 4 - integer.len());
+    decimal += integer;
+    decimal += fraction;
+    decimal += &"0".repeat(fraction.len().div_ceil(4) * 4 - fraction.len());
+    let mut digits: Vec<i16> = decimal
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .fold(0, |value, digit| value * 10 + i16::from(digit - b'0'))
+        })
+        .collect();
+
+    let mut weight = integer_digits as i16 - 1;
+    let leading = digits.iter().take_while(|&&digit| digit == 0).count();
+    digits.drain(..leading);
+    weight -= leading as i16;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    if digits.is_empty() {
+        weight = 0;
+    }
+    let sign: u16 = if negative && !digits.is_empty() {
+        0x4000
+    } else {
+        0
+    };
+
+    record.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    record.extend_from_slice(&weight.to_be_bytes());
+    record.extend_from_slice(&sign.to_be_bytes());
+    record.extend_from_slice(&(fraction.len() as i16).to_be_bytes());
+    for digit in digits {
+        record.extend_from_slice(&digit.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{BinaryArray, Decimal128Array, Int64Array, StringArray};
+    use arrow::datatypes::Field;
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use tempfile::NamedTempFile;
+
+    fn write_parquet(batch: &RecordBatch) -> NamedTempFile {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), batch.schema(), None).unwrap();
+        writer.write(batch).unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    #[test]
+    fn rows_are_written_in_both_copy_formats() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("note", DataType::Utf8, true),
+            Field::new("blob", DataType::Binary, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("tab\there\\"), None])),
+                Arc::new(BinaryArray::from(vec![Some(&[0xde, 0xad][..]), None])),
+            ],
+        )
+        .unwrap();
+        let file = write_parquet(&batch);
+        let output = tempfile::tempdir().unwrap();
+
+        let text = output.path().join("rows.txt");
+        let summary = stream_copy(
+            file.path(),
+            &[],
+            &[],
+            &text,
+            CopyFormat::Text,
+            &ReadCounter::default(),
+            &Progress::default(),
+        )
+        .unwrap();
+        assert_eq!(summary.rows_written, 2);
+        assert_eq!(
+            std::fs::read_to_string(&text).unwrap(),
+            "1\ttab\\there\\\\\t\\\\xdead\n2\t\\N\t\\N\n"
+        );
+
+        let binary = output.path().join("rows.bin");
+        stream_copy(
+            file.path(),
+            &[],
+            &["id".to_string()],
+            &binary,
+            CopyFormat::Binary,
+            &ReadCounter::default(),
+            &Progress::default(),
+        )
+        .unwrap();
+        let mut expected = BINARY_SIGNATURE.to_vec();
+        expected.extend_from_slice(&[0; 8]);
+        for id in [1i64, 2] {
+            expected.extend_from_slice(&1i16.to_be_bytes());
+            expected.extend_from_slice(&8i32.to_be_bytes());
+            expected.extend_from_slice(&id.to_be_bytes());
+        }
+        expected.extend_from_slice(&(-1i16).to_be_bytes());
+        assert_eq!(std::fs::read(&binary).unwrap(), expected);
+
+        assert_eq!(
+            create_table("rows", &schema).unwrap(),
+            "CREATE TABLE \"rows\" (\n    \"id\" bigint NOT NULL,\n    \"note\" text,\n    \"blob\" bytea\n);\n"
+        );
+    }
+
+    #[test]
+    fn decimals_become_binary_numerics() {
+        let encode = |text: &str| {
+            let mut record = Vec::new();
+            numeric(&mut record, text);
+            record
+                .chunks(2)
+                .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>()
+        };
+        // Digit count, weight, sign, scale, digits.
+        assert_eq!(encode("12345.678"), [3, 1, 0, 3, 1, 2345, 6780]);
+        assert_eq!(encode("-0.0001"), [1, -1, 0x4000, 4, 1]);
+        assert_eq!(encode("10000"), [1, 1, 0, 0, 1]);
+        assert_eq!(encode("0.00"), [0, 0, 0, 2]);
+
+        let decimals = Decimal128Array::from(vec![-123_450])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let mut record = Vec::new();
+        binary_value(&mut record, &decimals, 0).unwrap();
+        assert_eq!(record, {
+            let mut expected = Vec::new();
+            numeric(&mut expected, "-1234.50");
+            expected
+        });
+    }
+
+    #[test]
+    fn timestamps_past_the_microsecond_range_are_refused() {
+        use arrow::array::TimestampSecondArray;
+
+        let mut record = Vec::new();
+        binary_value(
+            &mut record,
+            &TimestampSecondArray::from(vec![946_684_800]),
+            0,
+        )
+        .unwrap();
+        assert_eq!(record, 0i64.to_be_bytes());
+        assert!(matches!(
+            binary_value(&mut record, &TimestampSecondArray::from(vec![i64::MAX]), 0),
+            Err(ViewerError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use arrow::record_batch::RecordBatch;
+
+use crate::intern::{Cell, CellInterner};
+use crate::preview::{BatchFormatter, RowWindow, UNREADABLE_CELL};
+
+/// Screens read ahead on each side of the viewport unless `settings.json` says otherwise.
+pub const DEFAULT_PREFETCH_SCREENS: usize = 1;
+
+/// Upper bound for the prefetch window, so that a typo can't read half the file.
+pub const MAX_PREFETCH_SCREENS: usize = 8;
+
+/// Display rows to keep decoded around a viewport starting at `start`.
+///
+/// The window is aligned to whole screens, so that it only moves, and new reads
+/// only start, when scrolling crosses a screen boundary.
+pub fn prefetch_range(
+    start: usize,
+    rows_per_view: usize,
+    screens: usize,
+    row_count: usize,
+) -> Range<usize> {
+    let rows_per_view = rows_per_view.max(1);
+    let screen = start / rows_per_view;
+    let first = screen.saturating_sub(screens) * rows_per_view;
+    // The viewport can straddle two screens; read ahead from the second.
+    let last = (screen + 2 + screens) * rows_per_view;
+    first.min(row_count)..last.min(row_count)
+}
+
+/// Cell text kept for rows that were shown, so that showing them again doesn't
+/// format them again; it is dropped whole once it grows past this many cells.
+const MAX_CACHED_CELLS: usize = 64 * 1024;
+
+/// Rows read ahead of scrolling, kept as the decoded batches and only turned
+/// into text once they are shown.
+///
+/// With a memory limit, the batches farthest from the rows stored last and
+/// the [protected](RowCache::protect) ones are dropped once they outgrow it;
+/// they are read again if scrolled back to.
+#[derive(Debug, Default)]
+pub struct RowCache {
+    /// Bytes of decoded batches to hold at most; `None` holds every one.
+    limit: Option<usize>,
+    /// Consecutive file rows by the first of them.
+    parts: BTreeMap<usize, CachedPart>,
+    /// File rows the view is waiting on, never evicted.
+    protected: Vec<Range<usize>>,
+    /// Text of cells formatted so far, by file row and column.
+    text: HashMap<(usize, usize), Cell>,
+    interner: CellInterner,
+}
+
+#[derive(Debug)]
+enum CachedPart {
+    /// Decoded rows, with the bytes of their values.
+    Batch(RecordBatch, usize),
+    /// Rows of a row group that failed to decode, with the number of columns.
+    Unreadable(usize, usize),
+}
+
+impl CachedPart {
+    fn len(&self) -> usize {
+        match self {
+            CachedPart::Batch(batch, _) => batch.num_rows(),
+            CachedPart::Unreadable(rows, _) => *rows,
+        }
+    }
+
+    fn batch(batch: RecordBatch) -> CachedPart {
+        let bytes = batch_bytes(&batch);
+        CachedPart::Batch(batch, bytes)
+    }
+
+    /// Memory held by the decoded values.
+    fn bytes(&self) -> usize {
+        match self {
+            CachedPart::Batch(_, bytes) => *bytes,
+            CachedPart::Unreadable(..) => 0,
+        }
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> CachedPart {
+        match self {
+            CachedPart::Batch(batch, _) => CachedPart::batch(batch.slice(offset, len)),
+            CachedPart::Unreadable(_, columns) => CachedPart::Unreadable(len, *columns),
+        }
+    }
+}
+
+/// Bytes of the values in `batch`'s rows. A slice shares its buffers with the
+/// batch it was cut from, so it is counted by its own rows rather than by the
+/// buffers, which would count the shared ones once per slice.
+fn batch_bytes(batch: &RecordBatch) -> usize {
+    batch
+        .columns()
+        .iter()
+        .map(|column| {
+            column
+                .to_data()
+                .get_slice_memory_size()
+                .unwrap_or_else(|_| column.get_array_memory_size())
+        })
+        .sum()
+}
+
+impl RowCache {
+    /// An empty cache holding at most `limit` bytes of decoded batches, e.g.
+    /// [`memory_limit`](crate::spill::memory_limit).
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            ..Self::default()
+        }
+    }
+
+    /// Store the decoded `batches`, consecutive file rows starting at `first_row`,
+    /// dropping the rows farthest from them should the cache outgrow its limit.
+    pub fn insert(&mut self, first_row: usize, batches: Vec<RecordBatch>) {
+        let mut row = first_row;
+        for batch in batches {
+            let rows = batch.num_rows();
+            if rows > 0 {
+                self.parts.insert(row, CachedPart::batch(batch));
+            }
+            row += rows;
+        }
+        self.evict(first_row..row);
+    }
+
+    /// Keep the parts holding `runs`, e.g. the viewport being loaded or the
+    /// prefetch window, whatever later inserts evict, in place of the runs
+    /// protected before.
+    pub fn protect(&mut self, runs: Vec<Range<usize>>) {
+        self.protected = runs;
+    }
+
+    /// Mark `rows`, `columns` wide, as unreadable, to be shown as placeholders.
+    pub fn insert_unreadable(&mut self, rows: Range<usize>, columns: usize) {
+        if !rows.is_empty() {
+            self.parts
+                .insert(rows.start, CachedPart::Unreadable(rows.len(), columns));
+        }
+    }
+
+    /// The rows of `run` as text if every one of them is cached, with the cells
+    /// that failed or hold invalid UTF-8 among those formatted for the first time.
+    pub fn get(&mut self, run: Range<usize>) -> Option<RowWindow> {
+        if !self.contains(run.clone()) {
+            return None;
+        }
+
+        let mut window = RowWindow::default();
+        let mut row = run.start;
+        while row < run.end {
+            let (&first, part) = self.parts.range(..=row).next_back()?;
+            let end = (first + part.len()).min(run.end);
+            match part {
+                CachedPart::Batch(batch, _) => {
+                    let formatter = BatchFormatter::new(batch);
+                    for file_row in row..end {
+                        let cells = (0..batch.num_columns())
+                            .map(|column| match self.text.get(&(file_row, column)) {
+                                Some(text) => text.clone(),
+                                None => {
+                                    let text = formatter.cell(
+                                        file_row - first,
+                                        column,
+                                        file_row,
+                                        &mut window,
+                                    );
+                                    let text = self.interner.intern(column, text);
+                                    if self.text.len() >= MAX_CACHED_CELLS {
+                                        self.text.clear();
+                                    }
+                                    self.text.insert((file_row, column), text.clone());
+                                    text
+                                }
+                            })
+                            .collect();
+                        window.rows.push(cells);
+                    }
+                }
+                CachedPart::Unreadable(_, columns) => {
+                    let placeholder = vec![Cell::from(UNREADABLE_CELL); *columns];
+                    window
+                        .rows
+                        .extend(std::iter::repeat_n(placeholder, end - row));
+                }
+            }
+            row = end;
+        }
+        Some(window)
+    }
+
+    pub fn contains(&self, run: Range<usize>) -> bool {
+        let mut row = run.start;
+        while row < run.end {
+            match self.parts.range(..=row).next_back() {
+                Some((&first, part)) if row < first + part.len() => row = first + part.len(),
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Drop every row outside `runs`.
+    pub fn retain(&mut self, runs: &[Range<usize>]) {
+        let parts = std::mem::take(&mut self.parts);
+        for (first, part) in parts {
+            for run in runs {
+                let start = run.start.max(first);
+                let end = run.end.min(first + part.len());
+                if start < end {
+                    self.parts
+                        .insert(start, part.slice(start - first, end - start));
+                }
+            }
+        }
+        self.text
+            .retain(|(row, _), _| runs.iter().any(|run| run.contains(row)));
+    }
+
+    /// Drop the parts farthest from `inserted` and the protected runs until the
+    /// batches fit the limit; those overlapping any of them stay whatever their
+    /// size.
+    fn evict(&mut self, inserted: Range<usize>) {
+        let Some(limit) = self.limit else {
+            return;
+        };
+        let kept: Vec<Range<usize>> = self.protected.iter().cloned().chain([inserted]).collect();
+        let mut held: usize = self.parts.values().map(CachedPart::bytes).sum();
+        while held > limit {
+            let farthest = self
+                .parts
+                .iter()
+                .filter(|(_, part)| part.bytes() > 0)
+                .filter_map(|(&first, part)| {
+                    let end = first + part.len();
+                    // A part overlapping a kept run gets `None`, which sorts
+                    // first, and stays.
+                    let distance = kept
+                        .iter()
+                        .map(|run| {
+                            if end <= run.start {
+                                Some(run.start - end)
+                            } else if first >= run.end {
+                                Some(first - run.end)
+                            } else {
+                                None
+                            }
+                        })
+                        .min()
+                        .flatten()?;
+                    Some((distance, first))
+                })
+                .max();
+            let Some((_, first)) = farthest else {
+                return;
+            };
+            let part = self.parts.remove(&first).expect("part was just found");
+            held -= part.bytes();
+            let rows = first..first + part.len();
+            self.text.retain(|(row, _), _| !rows.contains(row));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Int64Array, StringArray};
+
+    #[test]
+    fn prefetch_window_moves_a_screen_at_a_time() {
+        assert_eq!(prefetch_range(0, 10, 1, 1_000), 0..30);
+        assert_eq!(prefetch_range(9, 10, 1, 1_000), 0..30);
+        assert_eq!(prefetch_range(25, 10, 1, 1_000), 10..50);
+        assert_eq!(prefetch_range(25, 10, 0, 1_000), 20..40);
+        assert_eq!(prefetch_range(995, 10, 1, 1_000), 980..1_000);
+    }
+
+    #[test]
+    fn cache_serves_only_complete_runs() {
+        let batch = RecordBatch::try_from_iter([(
+            "letter",
+            Arc::new(StringArray::from(vec!["a", "b", "c"])) as ArrayRef,
+        )])
+        .expect("record batch should build");
+        let row = |value: &str| vec![Cell::from(value)];
+        let mut cache = RowCache::default();
+        cache.insert(10, vec![batch]);
+        cache.insert_unreadable(13..15, 1);
+
+        let window = cache.get(11..14).expect("rows should be cached");
+        assert_eq!(window.rows, vec![row("b"), row("c"), row(UNREADABLE_CELL)]);
+        assert!(cache.get(11..16).is_none());
+        assert!(cache.contains(10..13));
+
+        cache.retain(&[12..20, 40..50]);
+        assert!(!cache.contains(10..11));
+        assert!(cache.contains(12..15));
+        assert_eq!(
+            cache.get(12..13).expect("row should be cached").rows,
+            vec![row("c")]
+        );
+    }
+
+    #[test]
+    fn rows_farthest_from_the_last_stored_go_past_the_limit() {
+        let batch = || {
+            RecordBatch::try_from_iter([(
+                "letter",
+                Arc::new(StringArray::from(vec!["a", "b"])) as ArrayRef,
+            )])
+            .expect("record batch should build")
+        };
+        let size = batch_bytes(&batch());
+        let mut cache = RowCache::new(Some(2 * size));
+        cache.insert(0, vec![batch()]);
+        cache.insert(10, vec![batch()]);
+        cache.insert_unreadable(20..22, 1);
+        assert!(cache.contains(0..2) && cache.contains(10..12));
+
+        cache.insert(4, vec![batch()]);
+        assert!(cache.contains(0..2) && cache.contains(4..6));
+        assert!(!cache.contains(10..12));
+        assert!(cache.contains(20..22));
+
+        // Rows just stored stay, however large.
+        let mut tiny = RowCache::new(Some(1));
+        tiny.insert(0, vec![batch(), batch()]);
+        assert!(tiny.contains(0..4));
+    }
+
+    #[test]
+    fn slices_count_their_own_rows_and_protected_runs_stay() {
+        let numbers = |values: Vec<i64>| {
+            RecordBatch::try_from_iter([("n", Arc::new(Int64Array::from(values)) as ArrayRef)])
+                .expect("record batch should build")
+        };
+        let whole = batch_bytes(&numbers(vec![1, 2, 3, 4]));
+        let mut cache = RowCache::new(Some(whole));
+        cache.insert(0, vec![numbers(vec![1, 2, 3, 4])]);
+        cache.retain(&[0..1, 3..4]);
+        cache.insert(10, vec![numbers(vec![5])]);
+        assert!(cache.contains(0..1) && cache.contains(3..4) && cache.contains(10..11));
+
+        // The chunks of one viewport don't evict each other.
+        let mut cache = RowCache::new(Some(whole));
+        cache.protect(vec![100..104, 200..204]);
+        cache.insert(100, vec![numbers(vec![1, 2, 3, 4])]);
+        cache.insert(200, vec![numbers(vec![1, 2, 3, 4])]);
+        assert!(cache.contains(100..104) && cache.contains(200..204));
+
+        cache.protect(Vec::new());
+        cache.insert(300, vec![numbers(vec![1, 2, 3, 4])]);
+        assert!(!cache.contains(100..104) && !cache.contains(200..204));
+        assert!(cache.contains(300..304));
+    }
+}
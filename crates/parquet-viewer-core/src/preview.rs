@@ -0,0 +1,969 @@
+use std::any::Any;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use arrow::util::pretty::pretty_format_batches;
+use rayon::prelude::*;
+
+use crate::cast::{apply_casts, ColumnCasts, ViewAs};
+use crate::computed::{append_computed, ComputedColumn};
+use crate::geo::GeoMetadata;
+use crate::intern::{Cell, CellInterner};
+use crate::metrics::ReadCounter;
+use crate::placeholders::placeholders;
+use crate::render;
+use crate::source::{self, read_chunk, ChunkRead, DataSource, LocalFile, MappedFile};
+use crate::spill;
+use crate::utf8;
+use crate::width::isolate;
+use crate::ViewerError;
+
+/// Footer facts and the decoded head of one Parquet file, plus the means to read
+/// any other rows of it.
+#[derive(Clone)]
+pub struct DataPreview {
+    pub path: PathBuf,
+    pub formatted_rows: String,
+    pub columns: Vec<String>,
+    /// Arrow type each column is read as.
+    pub data_types: Vec<DataType>,
+    /// Columns shown as another type, applied to every row read.
+    pub casts: ColumnCasts,
+    /// Columns computed from the others, shown after those of the file.
+    pub computed: Vec<ComputedColumn>,
+    /// File columns decoded for rows past the head, so that scrolling a wide
+    /// table only reads the ones on screen; `None` reads them all.
+    pub projection: Option<Vec<usize>>,
+    pub rows: Vec<Vec<Cell>>,
+    pub row_count: usize,
+    pub column_count: usize,
+    /// Row range covered by each row group, in file order.
+    pub row_groups: Vec<Range<usize>>,
+    /// Row groups in the previewed head of the file that failed to decode.
+    pub unreadable: Vec<UnreadableRange>,
+    /// Cells in the previewed head of the file that couldn't be rendered.
+    pub cell_errors: Vec<CellError>,
+    /// Cells in the previewed head of the file whose invalid UTF-8 was replaced.
+    pub lossy_cells: Vec<(usize, usize)>,
+    pub file_size: u64,
+    /// Distinct compression codecs used by the column chunks.
+    pub codecs: Vec<String>,
+    /// Bytes read from disk for this file so far, footer included.
+    pub bytes_read: ReadCounter,
+    /// Where rows outside the preview are read from.
+    pub source: Arc<dyn DataSource>,
+    /// GeoParquet metadata, for files with geometry columns.
+    pub geo: Option<GeoMetadata>,
+}
+
+pub fn load_preview(
+    path: &PathBuf,
+    row_limit: usize,
+    mmap: bool,
+) -> Result<DataPreview, ViewerError> {
+    let bytes_read = ReadCounter::default();
+    let source: Arc<dyn DataSource> = if mmap {
+        Arc::new(MappedFile::open(path, &bytes_read)?)
+    } else {
+        Arc::new(LocalFile::open(path, &bytes_read)?)
+    };
+    let file_size = std::fs::metadata(path)?.len();
+    let reader_metadata = source.metadata().clone();
+    let metadata = reader_metadata.metadata().clone();
+    let row_count = metadata.file_metadata().num_rows() as usize;
+    let geo = metadata
+        .file_metadata()
+        .key_value_metadata()
+        .and_then(|entries| {
+            GeoMetadata::from_key_values(
+                entries
+                    .iter()
+                    .map(|entry| (entry.key.as_str(), entry.value.as_deref())),
+            )
+        });
+    let column_count = metadata.file_metadata().schema_descr().columns().len();
+
+    // Column names come from the footer so that a corrupt first row group
+    // doesn't prevent the schema from being shown.
+    let columns = reader_metadata
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect::<Vec<_>>();
+    let data_types = reader_metadata
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.data_type().clone())
+        .collect();
+
+    let mut row_groups = Vec::with_capacity(metadata.num_row_groups());
+    let mut codecs = Vec::new();
+    let mut next_row = 0;
+    let mut decoded_bytes = 0;
+    for row_group in metadata.row_groups() {
+        let rows = row_group.num_rows() as usize;
+        decoded_bytes += row_group.total_byte_size().max(0) as u64;
+        row_groups.push(next_row..next_row + rows);
+        next_row += rows;
+
+        for column in row_group.columns() {
+            let codec = column.compression().to_string();
+            if !codecs.contains(&codec) {
+                codecs.push(codec);
+            }
+        }
+    }
+
+    let preview_limit = head_limit(row_limit, row_count, decoded_bytes, spill::memory_limit());
+    let parts = load_window(
+        source.as_ref(),
+        window_chunks(&row_groups, 0..preview_limit),
+    )?;
+
+    let batches: Vec<RecordBatch> = parts
+        .iter()
+        .filter_map(|part| match part {
+            WindowPart::Batches(batches) => Some(batches.as_slice()),
+            WindowPart::Unreadable(_) => None,
+        })
+        .flatten()
+        .cloned()
+        .collect();
+    let unreadable: Vec<UnreadableRange> = parts
+        .iter()
+        .filter_map(|part| match part {
+            WindowPart::Unreadable(range) => Some(range.clone()),
+            WindowPart::Batches(_) => None,
+        })
+        .collect();
+
+    let formatted_rows = if batches.is_empty() {
+        "(no rows found)".to_string()
+    } else {
+        format_rows(&columns, &batches_to_rows(&batches, usize::MAX, 0).rows)?
+    };
+
+    let mut preview = DataPreview {
+        path: path.clone(),
+        formatted_rows,
+        columns,
+        data_types,
+        casts: ColumnCasts::new(),
+        computed: Vec::new(),
+        projection: None,
+        rows: Vec::new(),
+        row_count,
+        column_count,
+        row_groups,
+        unreadable,
+        cell_errors: Vec::new(),
+        lossy_cells: Vec::new(),
+        file_size,
+        codecs,
+        bytes_read,
+        source,
+        geo,
+    };
+    let head = preview.window_rows(&parts, 0);
+    preview.rows = head.rows;
+    preview.cell_errors = head.cell_errors;
+    preview.lossy_cells = head.lossy_cells;
+    Ok(preview)
+}
+
+/// Lay out rendered cell text as a table, so custom renderers show in the
+/// terminal too. Right-to-left text is isolated so that it can't pull the
+/// table's borders out of place.
+pub fn format_rows(columns: &[String], rows: &[Vec<Cell>]) -> Result<String, ArrowError> {
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|name| Field::new(isolate(name), DataType::Utf8, false))
+        .collect();
+    let values: Vec<ArrayRef> = (0..columns.len())
+        .map(|column| {
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|row| isolate(&row[column])),
+            )) as ArrayRef
+        })
+        .collect();
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), values)?;
+    Ok(pretty_format_batches(&[batch])?.to_string())
+}
+
+/// Rows to preload from the top of the file: `row_limit`, cut down so that their
+/// estimated decoded size stays within `memory_limit`, but always at least one row.
+fn head_limit(
+    row_limit: usize,
+    row_count: usize,
+    decoded_bytes: u64,
+    memory_limit: Option<usize>,
+) -> usize {
+    let rows = row_limit.min(row_count);
+    let Some(memory_limit) = memory_limit else {
+        return rows;
+    };
+    let bytes_per_row = (decoded_bytes / row_count.max(1) as u64).max(1);
+    let fitting = (memory_limit as u64 / bytes_per_row).max(1);
+    rows.min(fitting as usize)
+}
+
+/// Rows that could not be decoded because their row group is corrupt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnreadableRange {
+    pub row_group: usize,
+    pub rows: Range<usize>,
+    pub message: String,
+}
+
+/// A cell whose value decoded but couldn't be turned into text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellError {
+    /// Row in the file.
+    pub row: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Decoded rows for a viewport, with placeholders standing in for unreadable rows.
+#[derive(Debug, Default)]
+pub struct RowWindow {
+    pub rows: Vec<Vec<Cell>>,
+    pub unreadable: Vec<UnreadableRange>,
+    /// Cells shown as [`ERROR_CELL`], with why.
+    pub cell_errors: Vec<CellError>,
+    /// File row and column of cells whose invalid UTF-8 was replaced, read with
+    /// `--lossy-utf8`.
+    pub lossy_cells: Vec<(usize, usize)>,
+}
+
+/// Cell text shown for rows whose row group failed to decode.
+pub const UNREADABLE_CELL: &str = "<unreadable>";
+
+/// Cell text shown for a single value that couldn't be rendered.
+pub const ERROR_CELL: &str = "<error>";
+
+pub enum WindowPart {
+    Batches(Vec<RecordBatch>),
+    Unreadable(UnreadableRange),
+}
+
+/// Read `chunks`, each within one row group, so that a corrupt row group only
+/// affects its own rows.
+fn load_window(
+    source: &dyn DataSource,
+    chunks: Vec<ChunkRead>,
+) -> Result<Vec<WindowPart>, ViewerError> {
+    // Large ranges, e.g. for export, span many row groups; decode them concurrently.
+    chunks
+        .into_par_iter()
+        .map(|chunk| {
+            let result = source::block_on(read_chunk(source, &chunk));
+            window_part(chunk, result)
+        })
+        .collect()
+}
+
+/// The part of each row group that overlaps `range`, with all columns.
+pub fn window_chunks(row_groups: &[Range<usize>], range: Range<usize>) -> Vec<ChunkRead> {
+    row_groups
+        .iter()
+        .enumerate()
+        .filter_map(|(row_group, rows)| {
+            let start = range.start.max(rows.start);
+            let end = range.end.min(rows.end);
+            (start < end).then(|| ChunkRead {
+                row_group,
+                offset: start - rows.start,
+                rows: start..end,
+                columns: None,
+            })
+        })
+        .collect()
+}
+
+/// Keep a chunk that failed to decode as a placeholder; only failing to open the file is an error.
+pub fn window_part(
+    chunk: ChunkRead,
+    result: Result<Vec<RecordBatch>, ViewerError>,
+) -> Result<WindowPart, ViewerError> {
+    match result {
+        Ok(batches) => Ok(WindowPart::Batches(batches)),
+        Err(ViewerError::OpenFailed(error)) => Err(ViewerError::OpenFailed(error)),
+        Err(error) => {
+            tracing::warn!(row_group = chunk.row_group, %error, "skipping unreadable row group");
+            Ok(WindowPart::Unreadable(UnreadableRange {
+                row_group: chunk.row_group,
+                rows: chunk.rows,
+                message: error.to_string(),
+            }))
+        }
+    }
+}
+
+/// Text of the cells of `batches`, up to `row_limit` rows, along with the cells
+/// that failed or hold invalid UTF-8, their rows counted from `first_row`.
+///
+/// A value that fails to render, or whose renderer panics, reads [`ERROR_CELL`]
+/// instead of failing the rows around it.
+pub fn batches_to_rows(batches: &[RecordBatch], row_limit: usize, first_row: usize) -> RowWindow {
+    let mut window = RowWindow::default();
+    let mut interner = CellInterner::default();
+
+    for batch in batches {
+        let formatter = BatchFormatter::new(batch);
+        for row_index in 0..batch.num_rows() {
+            let file_row = first_row + window.rows.len();
+            let row = (0..batch.num_columns())
+                .map(|column| {
+                    let text = formatter.cell(row_index, column, file_row, &mut window);
+                    interner.intern(column, text)
+                })
+                .collect();
+            window.rows.push(row);
+
+            if window.rows.len() >= row_limit {
+                return window;
+            }
+        }
+    }
+
+    window
+}
+
+/// Turns the cells of one batch into text, with the renderers for its schema
+/// looked up once.
+pub struct BatchFormatter<'a> {
+    batch: &'a RecordBatch,
+    renderers: render::ColumnRenderers,
+    lossy: Vec<bool>,
+}
+
+impl<'a> BatchFormatter<'a> {
+    pub fn new(batch: &'a RecordBatch) -> Self {
+        let schema = batch.schema();
+        Self {
+            renderers: render::registry().column_renderers(&schema),
+            lossy: schema
+                .fields()
+                .iter()
+                .map(|field| utf8::is_lossy_column(&schema, field))
+                .collect(),
+            batch,
+        }
+    }
+
+    /// Text of the cell at `row_index` of `column`, noting in `window` under
+    /// file row `file_row` if it failed to render or held invalid UTF-8.
+    pub fn cell(
+        &self,
+        row_index: usize,
+        column: usize,
+        file_row: usize,
+        window: &mut RowWindow,
+    ) -> String {
+        let values = self.batch.column(column).as_ref();
+        if self.lossy[column] && utf8::has_invalid_utf8(values, row_index) {
+            window.lossy_cells.push((file_row, column));
+        }
+        let text = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.renderers.cell_text(column, values, row_index)
+        }));
+        let message = match text {
+            Ok(Ok(text)) => return text,
+            Ok(Err(error)) => error.to_string(),
+            Err(payload) => panic_message(payload.as_ref()),
+        };
+        window.cell_errors.push(CellError {
+            row: file_row,
+            column,
+            message,
+        });
+        ERROR_CELL.to_string()
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown error");
+    format!("rendering the value panicked: {message}")
+}
+
+/// Default text shown for one cell, for columns without a registered renderer.
+pub fn cell_text(array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+    let placeholders = placeholders();
+    if array.is_null(row) {
+        Ok(placeholders.null.clone())
+    } else {
+        Ok(placeholders.replace(array.data_type(), array_value_to_string(array, row)?))
+    }
+}
+
+impl DataPreview {
+    pub fn rows_for_range(&self, range: Range<usize>) -> Result<RowWindow, ViewerError> {
+        if range.start >= self.row_count {
+            return Ok(RowWindow::default());
+        }
+
+        let end = range.end.min(self.row_count);
+        let parts = load_window(self.source.as_ref(), self.chunks(range.start..end))?;
+
+        Ok(self.window_rows(&parts, range.start))
+    }
+
+    /// The rows of `run` from the head [`load_preview`] decoded, if it holds
+    /// every one of them, so that showing them doesn't read the file again. Their
+    /// cell errors were reported with the head.
+    pub fn head_rows(&self, run: Range<usize>) -> Option<RowWindow> {
+        if run.end > self.rows.len() {
+            return None;
+        }
+        let unreadable = self
+            .unreadable
+            .iter()
+            .filter(|known| known.rows.start < run.end && run.start < known.rows.end)
+            .map(|known| UnreadableRange {
+                rows: known.rows.start.max(run.start)..known.rows.end.min(run.end),
+                ..known.clone()
+            })
+            .collect();
+        Some(RowWindow {
+            rows: self.rows[run].to_vec(),
+            unreadable,
+            ..RowWindow::default()
+        })
+    }
+
+    /// The reads for file rows `range`, decoding only the columns in
+    /// [`DataPreview::projection`] unless computed columns need the others.
+    pub fn chunks(&self, range: Range<usize>) -> Vec<ChunkRead> {
+        let mut chunks = window_chunks(&self.row_groups, range);
+        if self.computed.is_empty() {
+            for chunk in &mut chunks {
+                chunk.columns = self.projection.clone();
+            }
+        }
+        chunks
+    }
+
+    /// Decode only the file columns `columns` from now on, or all of them with
+    /// `None`. Returns whether columns were added, so that rows read before
+    /// show nulls for them and need reading again.
+    pub fn set_projection(&mut self, columns: Option<Vec<usize>>) -> bool {
+        let added = match (&self.projection, &columns) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(read), Some(columns)) => columns.iter().any(|column| !read.contains(column)),
+        };
+        self.projection = columns.filter(|columns| !columns.is_empty());
+        added
+    }
+
+    /// `batch` as shown: with the computed columns appended and the casts applied.
+    pub fn display_batch(&self, batch: &RecordBatch) -> Result<RecordBatch, ViewerError> {
+        apply_casts(&append_computed(batch, &self.computed)?, &self.casts)
+    }
+
+    /// Each of `batches` as [`DataPreview::display_batch`] shows it, or as
+    /// stored should that fail.
+    pub fn display_batches(&self, batches: &[RecordBatch]) -> Vec<RecordBatch> {
+        batches
+            .iter()
+            .map(|batch| {
+                self.display_batch(batch).unwrap_or_else(|error| {
+                    tracing::warn!(%error, "showing columns as stored");
+                    batch.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Rows of consecutive `parts`, the first of them starting at file row
+    /// `first_row`, as [`DataPreview::display_batch`] shows them.
+    pub fn window_rows(&self, parts: &[WindowPart], first_row: usize) -> RowWindow {
+        let mut window = RowWindow::default();
+
+        for part in parts {
+            match part {
+                WindowPart::Batches(batches) => {
+                    let batches = self.display_batches(batches);
+                    let part = batches_to_rows(&batches, usize::MAX, first_row + window.rows.len());
+                    window.rows.extend(part.rows);
+                    window.cell_errors.extend(part.cell_errors);
+                    window.lossy_cells.extend(part.lossy_cells);
+                }
+                WindowPart::Unreadable(range) => {
+                    let placeholder = vec![Cell::from(UNREADABLE_CELL); self.columns.len()];
+                    window
+                        .rows
+                        .extend(std::iter::repeat_n(placeholder, range.rows.len()));
+                    window.unreadable.push(range.clone());
+                }
+            }
+        }
+
+        window
+    }
+
+    /// Decode the head of the file again after a change to how it is shown,
+    /// with all columns like [`load_preview`] does.
+    fn reload_head(&mut self) -> Result<(), ViewerError> {
+        let chunks = window_chunks(&self.row_groups, 0..self.rows.len());
+        let head = self.window_rows(&load_window(self.source.as_ref(), chunks)?, 0);
+        self.rows = head.rows;
+        self.unreadable = head.unreadable;
+        self.cell_errors = head.cell_errors;
+        self.lossy_cells = head.lossy_cells;
+        Ok(())
+    }
+
+    /// Show `column` as `view`, or as stored with `None`, and decode the head of
+    /// the file again to match; on failure the casts are left as they were.
+    /// Rows read before keep their old text.
+    pub fn set_view_as(&mut self, column: usize, view: Option<ViewAs>) -> Result<(), ViewerError> {
+        let previous = self.casts.clone();
+        match view {
+            Some(view) => self.casts.insert(column, view),
+            None => self.casts.remove(&column),
+        };
+        self.reload_head().inspect_err(|_| self.casts = previous)
+    }
+
+    /// Index of the first computed column; the file's own columns come before it.
+    pub fn computed_start(&self) -> usize {
+        self.columns.len() - self.computed.len()
+    }
+
+    /// Add a column computed from `definition`, written as `name = expression`,
+    /// and decode the head of the file again to show it.
+    pub fn add_computed(&mut self, definition: &str) -> Result<(), ViewerError> {
+        let start = self.computed_start();
+        let fields: Vec<Field> = self.columns[..start]
+            .iter()
+            .zip(&self.data_types)
+            .map(|(name, data_type)| Field::new(name, data_type.clone(), true))
+            .collect();
+        let column = ComputedColumn::parse(definition, &Schema::new(fields))?;
+        if self.computed.iter().any(|other| other.name == column.name) {
+            return Err(ViewerError::InvalidExpression(format!(
+                "there already is a computed column named {}",
+                column.name
+            )));
+        }
+
+        self.columns.push(column.name.clone());
+        self.data_types.push(column.data_type().clone());
+        self.computed.push(column);
+        self.reload_head().inspect_err(|_| {
+            self.computed.pop();
+            self.columns.pop();
+            self.data_types.pop();
+        })
+    }
+
+    /// Drop the computed column at column index `column`; other columns are left alone.
+    pub fn remove_computed(&mut self, column: usize) -> Result<(), ViewerError> {
+        let Some(index) = column.checked_sub(self.computed_start()) else {
+            return Ok(());
+        };
+        if index >= self.computed.len() {
+            return Ok(());
+        }
+
+        self.computed.remove(index);
+        self.columns.remove(column);
+        self.data_types.remove(column);
+        // Casts of the computed columns after it move down with them.
+        self.casts = std::mem::take(&mut self.casts)
+            .into_iter()
+            .filter(|(cast, _)| *cast != column)
+            .map(|(cast, view)| (if cast > column { cast - 1 } else { cast }, view))
+            .collect();
+        self.reload_head()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::{Seek, SeekFrom, Write};
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray, TimestampSecondArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::arrow::arrow_writer::ArrowWriter;
+    use parquet::file::metadata::KeyValue;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    fn write_test_parquet(rows: usize) -> Result<NamedTempFile, ViewerError> {
+        write_test_parquet_with_row_groups(rows, rows.max(1))
+    }
+
+    pub(crate) fn write_test_parquet_with_row_groups(
+        rows: usize,
+        row_group_size: usize,
+    ) -> Result<NamedTempFile, ViewerError> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+
+        let ids: Vec<i32> = (0..rows as i32).collect();
+        let names: Vec<String> = ids.iter().map(|v| format!("name-{v}")).collect();
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(ids)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )?;
+
+        let file = NamedTempFile::new()?;
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(row_group_size)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen()?, schema, Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        Ok(file)
+    }
+
+    #[test]
+    fn load_preview_reports_metadata() {
+        let file = write_test_parquet(4).expect("parquet write should succeed");
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 10, false).expect("preview should load");
+
+        assert_eq!(preview.row_count, 4);
+        assert_eq!(preview.column_count, 2);
+        assert!(preview.formatted_rows.contains("id"));
+        assert!(preview.formatted_rows.contains("name-0"));
+        assert_eq!(preview.columns, vec!["id".to_string(), "name".to_string()]);
+        assert_eq!(preview.rows.len(), 4);
+        assert_eq!(preview.rows[0], ["0", "name-0"].map(Cell::from));
+    }
+
+    #[test]
+    fn load_preview_reads_geoparquet_geometries() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "geometry",
+            DataType::Binary,
+            false,
+        )]));
+        let mut point = vec![1];
+        point.extend(1u32.to_le_bytes());
+        point.extend(1.0f64.to_le_bytes());
+        point.extend(2.0f64.to_le_bytes());
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(arrow::array::BinaryArray::from_vec(vec![&point]))],
+        )
+        .unwrap();
+        let geo = r#"{"version": "1.1.0", "primary_column": "geometry",
+            "columns": {"geometry": {"encoding": "WKB", "geometry_types": ["Point"]}}}"#;
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                "geo".to_string(),
+                geo.to_string(),
+            )]))
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 10, false).expect("preview should load");
+
+        let geo = preview.geo.expect("geo metadata should be read");
+        assert_eq!(geo.columns["geometry"].crs_label(), "OGC:CRS84");
+        assert_eq!(preview.rows[0], ["POINT (1 2)"].map(Cell::from));
+        assert!(preview.formatted_rows.contains("POINT (1 2)"));
+    }
+
+    #[test]
+    fn load_preview_reports_file_and_read_metrics() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 2, false).expect("preview should load");
+        let after_preview = preview.bytes_read.bytes();
+
+        assert_eq!(preview.file_size, file.as_file().metadata().unwrap().len());
+        assert_eq!(preview.codecs, vec!["UNCOMPRESSED".to_string()]);
+        assert!(after_preview > 0 && after_preview <= preview.file_size * 2);
+
+        preview
+            .rows_for_range(4..6)
+            .expect("range fetch should succeed");
+        assert!(preview.bytes_read.bytes() > after_preview);
+    }
+
+    #[test]
+    fn load_preview_respects_row_limit() {
+        let file = write_test_parquet(5).expect("parquet write should succeed");
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 2, false).expect("preview should load");
+
+        assert!(preview.formatted_rows.contains("name-0"));
+        assert!(preview.formatted_rows.contains("name-1"));
+        assert!(!preview.formatted_rows.contains("name-2"));
+        assert_eq!(preview.rows.len(), 2);
+    }
+
+    #[test]
+    fn rows_for_range_fetches_requested_slice() {
+        let file = write_test_parquet(6).expect("parquet write should succeed");
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 6, false).expect("preview should load");
+
+        let rows = preview
+            .rows_for_range(2..5)
+            .expect("range fetch should succeed")
+            .rows;
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], ["2", "name-2"].map(Cell::from));
+        assert_eq!(rows[2], ["4", "name-4"].map(Cell::from));
+    }
+
+    #[test]
+    fn only_projected_columns_are_decoded() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let mut preview =
+            load_preview(&file.path().to_path_buf(), 2, false).expect("preview should load");
+
+        assert!(!preview.set_projection(Some(vec![1])));
+        let rows = preview
+            .rows_for_range(3..5)
+            .expect("range fetch should succeed")
+            .rows;
+        assert_eq!(rows[0], ["null", "name-3"].map(Cell::from));
+        assert_eq!(rows[1], ["null", "name-4"].map(Cell::from));
+
+        assert!(preview.set_projection(None));
+        let rows = preview
+            .rows_for_range(3..4)
+            .expect("range fetch should succeed")
+            .rows;
+        assert_eq!(rows[0], ["3", "name-3"].map(Cell::from));
+    }
+
+    #[test]
+    fn columns_viewed_as_another_type_are_read_that_way() {
+        let file = write_test_parquet(6).expect("parquet write should succeed");
+        let mut preview =
+            load_preview(&file.path().to_path_buf(), 3, false).expect("preview should load");
+
+        let view = ViewAs::Timestamp(arrow::datatypes::TimeUnit::Second);
+        assert_eq!(ViewAs::choices(&preview.data_types[0])[0], view);
+        preview
+            .set_view_as(0, Some(view))
+            .expect("head should be read again");
+        assert_eq!(&*preview.rows[2][0], "1970-01-01T00:00:02Z");
+        let rows = preview
+            .rows_for_range(4..5)
+            .expect("range fetch should succeed")
+            .rows;
+        assert_eq!(&*rows[0][0], "1970-01-01T00:00:04Z");
+
+        preview
+            .set_view_as(0, None)
+            .expect("head should be read again");
+        assert_eq!(&*preview.rows[2][0], "2");
+    }
+
+    #[test]
+    fn computed_columns_are_shown_after_the_file_columns() {
+        let file = write_test_parquet(6).expect("parquet write should succeed");
+        let mut preview =
+            load_preview(&file.path().to_path_buf(), 3, false).expect("preview should load");
+
+        assert!(preview.add_computed("twice = id * 2").is_ok());
+        assert!(preview.add_computed("twice = id").is_err());
+        assert!(preview.add_computed("broken = missing + 1").is_err());
+        assert_eq!(preview.columns, vec!["id", "name", "twice"]);
+        assert_eq!(&*preview.rows[2][2], "4");
+        let rows = preview
+            .rows_for_range(5..6)
+            .expect("range fetch should succeed")
+            .rows;
+        assert_eq!(rows[0], ["5", "name-5", "10"].map(Cell::from));
+
+        preview
+            .remove_computed(2)
+            .expect("head should be read again");
+        assert_eq!(preview.columns, vec!["id", "name"]);
+        assert_eq!(preview.rows[2].len(), 2);
+    }
+
+    #[test]
+    fn head_is_cut_to_the_memory_limit() {
+        assert_eq!(head_limit(20, 1_000, 100_000, None), 20);
+        assert_eq!(head_limit(20, 10, 1_000, None), 10);
+        assert_eq!(head_limit(20, 1_000, 100_000, Some(500)), 5);
+        assert_eq!(head_limit(20, 1_000, 100_000, Some(1)), 1);
+        assert_eq!(head_limit(20, 0, 0, Some(1)), 0);
+    }
+
+    #[test]
+    fn mapped_files_read_the_same_rows() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 2, true).expect("preview should load");
+        let after_preview = preview.bytes_read.bytes();
+        let rows = preview
+            .rows_for_range(3..5)
+            .expect("range fetch should succeed")
+            .rows;
+
+        assert_eq!(preview.rows[1], ["1", "name-1"].map(Cell::from));
+        assert_eq!(rows[0], ["3", "name-3"].map(Cell::from));
+        assert_eq!(rows[1], ["4", "name-4"].map(Cell::from));
+        assert!(preview.bytes_read.bytes() > after_preview);
+    }
+
+    #[test]
+    fn rows_for_range_returns_empty_when_start_out_of_bounds() {
+        let file = write_test_parquet(2).expect("parquet write should succeed");
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 2, false).expect("preview should load");
+
+        let rows = preview
+            .rows_for_range(5..8)
+            .expect("range fetch should succeed")
+            .rows;
+
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn batches_to_rows_stops_at_limit() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![
+                    "name-1".to_string(),
+                    "name-2".to_string(),
+                    "name-3".to_string(),
+                ])),
+            ],
+        )
+        .expect("record batch should build");
+
+        let window = batches_to_rows(&[batch], 2, 0);
+        let rows = window.rows;
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], ["1", "name-1"].map(Cell::from));
+        assert_eq!(rows[1], ["2", "name-2"].map(Cell::from));
+        assert!(window.cell_errors.is_empty());
+    }
+
+    #[test]
+    fn values_that_fail_to_render_become_error_cells() {
+        let timestamps = TimestampSecondArray::from(vec![0, 60]).with_timezone("Not/AZone");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("at", timestamps.data_type().clone(), false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int32Array::from(vec![1, 2])), Arc::new(timestamps)],
+        )
+        .expect("record batch should build");
+
+        let RowWindow {
+            rows,
+            cell_errors: errors,
+            ..
+        } = batches_to_rows(&[batch], usize::MAX, 100);
+
+        assert_eq!(rows[1], ["2", ERROR_CELL].map(Cell::from));
+        assert_eq!(errors.len(), 2);
+        assert_eq!((errors[1].row, errors[1].column), (101, 1));
+        assert!(!errors[1].message.is_empty());
+    }
+
+    #[test]
+    fn corrupt_row_group_is_reported_without_failing_the_preview() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let offset = {
+            let builder =
+                ParquetRecordBatchReaderBuilder::try_new(File::open(file.path()).unwrap())
+                    .expect("footer should load");
+            let column = builder.metadata().row_group(1).column(1);
+            column
+                .dictionary_page_offset()
+                .unwrap_or_else(|| column.data_page_offset())
+        };
+        let mut handle = file.reopen().expect("temp file should reopen");
+        handle.seek(SeekFrom::Start(offset as u64)).unwrap();
+        handle.write_all(&[0xff; 8]).unwrap();
+
+        let preview =
+            load_preview(&file.path().to_path_buf(), 6, false).expect("preview should load");
+
+        assert_eq!(preview.row_count, 6);
+        assert_eq!(preview.unreadable.len(), 1);
+        assert_eq!(preview.unreadable[0].row_group, 1);
+        assert_eq!(preview.unreadable[0].rows, 2..4);
+        assert_eq!(preview.rows.len(), 6);
+        assert_eq!(preview.rows[2], vec![Cell::from(UNREADABLE_CELL); 2]);
+        assert_eq!(preview.rows[4], ["4", "name-4"].map(Cell::from));
+
+        let window = preview
+            .rows_for_range(3..5)
+            .expect("range fetch should succeed");
+        assert_eq!(window.unreadable[0].rows, 3..4);
+        assert_eq!(window.rows[1], ["4", "name-4"].map(Cell::from));
+
+        let head = preview.head_rows(3..5).expect("the head holds the rows");
+        assert_eq!(head.rows, window.rows);
+        assert_eq!(head.unreadable, window.unreadable);
+    }
+
+    #[test]
+    fn head_rows_are_served_only_when_the_head_holds_them() {
+        let file = write_test_parquet(6).expect("parquet write should succeed");
+        let preview =
+            load_preview(&file.path().to_path_buf(), 3, false).expect("preview should load");
+
+        let head = preview.head_rows(1..3).expect("the head holds the rows");
+        assert_eq!(
+            head.rows,
+            preview
+                .rows_for_range(1..3)
+                .expect("range fetch should succeed")
+                .rows
+        );
+        assert!(preview.head_rows(2..4).is_none());
+    }
+}
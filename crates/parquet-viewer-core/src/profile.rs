@@ -0,0 +1,613 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::{BuildHasher, RandomState};
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, TimeUnit, TimestampMillisecondType};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::file::metadata::ParquetMetaDataBuilder;
+use serde::Serialize;
+
+use crate::aggregate::{scan_rows, Accumulator, Aggregate};
+use crate::footer;
+use crate::hyperloglog::{self, HyperLogLog};
+use crate::metrics::ReadCounter;
+use crate::progress::Progress;
+use crate::render;
+use crate::ViewerError;
+
+/// Most frequent values listed per column.
+pub const TOP_VALUES: usize = 5;
+
+/// Distinct values tracked per column; past it the distinct count is a lower
+/// bound and the top values only count values seen before.
+const MAX_DISTINCT: usize = 100_000;
+
+/// Files with more rows than this are profiled from a sample of row groups in
+/// the viewer, where a full scan would take too long.
+pub const SAMPLE_ABOVE_ROWS: usize = 100_000_000;
+
+/// Row groups read for a sampled profile in the viewer.
+pub const SAMPLE_ROW_GROUPS: usize = 32;
+
+/// Timestamps and dates before 1900-01-01 or from 2100-01-01 on are reported as
+/// out of range; they are usually sentinels or unit mix-ups.
+const EARLIEST_TIMESTAMP_MS: i64 = -2_208_988_800_000;
+const LATEST_TIMESTAMP_MS: i64 = 4_102_444_800_000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Data quality figures of one column.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ColumnProfile {
+    pub column: String,
+    pub data_type: String,
+    pub nulls: u64,
+    pub null_percent: f64,
+    /// Distinct non-null values, as the table shows them.
+    pub distinct: u64,
+    /// False when the column had more than the tracked distinct values.
+    pub distinct_is_exact: bool,
+    /// True when `distinct` is a HyperLogLog estimate, as in sampled profiles.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub distinct_is_estimate: bool,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Most frequent non-null values, most frequent first.
+    pub top_values: Vec<ValueCount>,
+    /// Empty or whitespace-only values, for string columns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blank_strings: Option<u64>,
+    /// Values before 1900 or after 2099, for timestamp and date columns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_of_range_timestamps: Option<u64>,
+}
+
+impl ColumnProfile {
+    /// The profile as one readable line, shared by the `profile` subcommand and the UI.
+    pub fn line(&self) -> String {
+        let mut line = format!(
+            "{} ({}): {:.1}% null, {}{} distinct",
+            self.column,
+            self.data_type,
+            self.null_percent,
+            self.distinct_mark(),
+            self.distinct
+        );
+        if let (Some(min), Some(max)) = (&self.min, &self.max) {
+            let _ = write!(line, ", min {min}, max {max}");
+        }
+        if !self.top_values.is_empty() {
+            let top: Vec<String> = self
+                .top_values
+                .iter()
+                .map(|top| format!("{} ({})", top.value, top.count))
+                .collect();
+            let _ = write!(line, "; top: {}", top.join(", "));
+        }
+        if let Some(blank) = self.blank_strings.filter(|&blank| blank > 0) {
+            let _ = write!(line, "; {blank} blank strings");
+        }
+        if let Some(out_of_range) = self.out_of_range_timestamps.filter(|&count| count > 0) {
+            let _ = write!(line, "; {out_of_range} timestamps outside 1900–2099");
+        }
+        line
+    }
+
+    /// What the distinct count is prefixed with when it isn't exact.
+    fn distinct_mark(&self) -> &'static str {
+        if self.distinct_is_estimate {
+            "≈"
+        } else if self.distinct_is_exact {
+            ""
+        } else {
+            "≥"
+        }
+    }
+}
+
+/// The row groups a sampled profile read.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Sample {
+    pub row_groups: usize,
+    pub total_row_groups: usize,
+    pub rows: u64,
+    /// Seed the row groups were picked with, to pick them again.
+    pub seed: u64,
+}
+
+impl Sample {
+    /// How far the figures of a profile of this sample can be trusted.
+    pub fn note(&self) -> String {
+        // Widest 95% interval of a proportion, at 50%, in percentage points.
+        let margin = 1.96 * (0.25 / self.rows.max(1) as f64).sqrt() * 100.0;
+        format!(
+            "Sampled {} of {} row groups at random (seed {}), {} rows: null shares are \
+             within ±{margin:.2} points at 95% confidence if row groups are alike; min, max \
+             and top values only cover the sample; distinct counts are HyperLogLog \
+             estimates (±{:.1}%) of the sampled values, so the file may have more",
+            self.row_groups,
+            self.total_row_groups,
+            self.seed,
+            self.rows,
+            hyperloglog::STANDARD_ERROR * 100.0,
+        )
+    }
+}
+
+/// A per-column data quality report of a whole file, or of a sample of its row
+/// groups.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Profile {
+    /// Rows of the whole file.
+    pub rows: u64,
+    pub columns: Vec<ColumnProfile>,
+    /// The row groups the figures come from, if not all of them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<Sample>,
+}
+
+impl Profile {
+    pub fn lines(&self) -> Vec<String> {
+        std::iter::once(format!("Rows: {}", self.rows))
+            .chain(self.sample.as_ref().map(Sample::note))
+            .chain(self.columns.iter().map(ColumnProfile::line))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("profiles serialize to JSON")
+    }
+
+    /// The report as a standalone HTML page with one table row per column.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} \
+             th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}</style>\n\
+             </head>\n<body>\n<h1>{title}</h1>\n<p>{rows} rows</p>\n{note}<table>\n<tr><th>Column</th><th>Type</th>\
+             <th>Nulls</th><th>Distinct</th><th>Min</th><th>Max</th><th>Top values</th>\
+             <th>Blank strings</th><th>Out-of-range timestamps</th></tr>\n",
+            title = escape_html(title),
+            rows = self.rows,
+            note = self
+                .sample
+                .as_ref()
+                .map(|sample| format!("<p><em>{}</em></p>\n", escape_html(&sample.note())))
+                .unwrap_or_default(),
+        );
+        for column in &self.columns {
+            let top: Vec<String> = column
+                .top_values
+                .iter()
+                .map(|top| format!("{} ({})", escape_html(&top.value), top.count))
+                .collect();
+            let optional =
+                |count: Option<u64>| count.map(|count| count.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{} ({:.1}%)</td><td>{}{}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&column.column),
+                escape_html(&column.data_type),
+                column.nulls,
+                column.null_percent,
+                column.distinct_mark(),
+                column.distinct,
+                escape_html(column.min.as_deref().unwrap_or_default()),
+                escape_html(column.max.as_deref().unwrap_or_default()),
+                top.join("<br>"),
+                optional(column.blank_strings),
+                optional(column.out_of_range_timestamps),
+            );
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Running figures of one column while the file is scanned.
+struct ColumnState {
+    nulls: u64,
+    counts: HashMap<String, u64>,
+    overflowed: bool,
+    /// Distinct values of a sampled profile, which are estimated past the tracked ones.
+    sketch: Option<HyperLogLog>,
+    min_max: Option<(Accumulator, Accumulator)>,
+    blank_strings: Option<u64>,
+    out_of_range_timestamps: Option<u64>,
+}
+
+impl ColumnState {
+    fn new(data_type: &DataType, sampled: bool) -> ColumnState {
+        let min = Accumulator::new(Aggregate::Min, data_type);
+        let max = Accumulator::new(Aggregate::Max, data_type);
+        ColumnState {
+            nulls: 0,
+            counts: HashMap::new(),
+            overflowed: false,
+            sketch: sampled.then(HyperLogLog::default),
+            min_max: min.applies(data_type).then_some((min, max)),
+            blank_strings: is_string(data_type).then_some(0),
+            out_of_range_timestamps: is_timestamp(data_type).then_some(0),
+        }
+    }
+
+    fn add(
+        &mut self,
+        values: &dyn Array,
+        text: impl Fn(usize) -> Result<String, ViewerError>,
+    ) -> Result<(), ViewerError> {
+        self.nulls += values.null_count() as u64;
+        for row in 0..values.len() {
+            if values.is_null(row) {
+                continue;
+            }
+            let text = text(row)?;
+            if let Some(sketch) = &mut self.sketch {
+                sketch.insert(&text);
+            }
+            if let Some(count) = self.counts.get_mut(&text) {
+                *count += 1;
+            } else if self.counts.len() < MAX_DISTINCT {
+                self.counts.insert(text, 1);
+            } else {
+                self.overflowed = true;
+            }
+        }
+        if let Some((min, max)) = &mut self.min_max {
+            min.add(values)?;
+            max.add(values)?;
+        }
+        if let Some(blank) = &mut self.blank_strings {
+            let strings = cast(values, &DataType::Utf8)?;
+            *blank += strings
+                .as_string::<i32>()
+                .iter()
+                .flatten()
+                .filter(|value| value.trim().is_empty())
+                .count() as u64;
+        }
+        if let Some(out_of_range) = &mut self.out_of_range_timestamps {
+            let timestamps = cast(values, &DataType::Timestamp(TimeUnit::Millisecond, None))?;
+            *out_of_range += timestamps
+                .as_primitive::<TimestampMillisecondType>()
+                .iter()
+                .flatten()
+                .filter(|&ms| !(EARLIEST_TIMESTAMP_MS..LATEST_TIMESTAMP_MS).contains(&ms))
+                .count() as u64;
+        }
+        Ok(())
+    }
+
+    fn finish(
+        self,
+        column: String,
+        data_type: &DataType,
+        rows: u64,
+    ) -> Result<ColumnProfile, ViewerError> {
+        let (min, max) = match &self.min_max {
+            Some((min, max)) => (min.finish()?, max.finish()?),
+            None => (None, None),
+        };
+        let distinct = match &self.sketch {
+            Some(sketch) if self.overflowed => sketch.estimate().max(self.counts.len() as u64),
+            _ => self.counts.len() as u64,
+        };
+        let mut top_values: Vec<ValueCount> = self
+            .counts
+            .into_iter()
+            .map(|(value, count)| ValueCount { value, count })
+            .collect();
+        top_values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        top_values.truncate(TOP_VALUES);
+        Ok(ColumnProfile {
+            column,
+            data_type: data_type.to_string(),
+            nulls: self.nulls,
+            null_percent: if rows == 0 {
+                0.0
+            } else {
+                self.nulls as f64 * 100.0 / rows as f64
+            },
+            distinct,
+            distinct_is_exact: !self.overflowed,
+            distinct_is_estimate: self.sketch.is_some(),
+            min,
+            max,
+            top_values,
+            blank_strings: self.blank_strings,
+            out_of_range_timestamps: self.out_of_range_timestamps,
+        })
+    }
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    )
+}
+
+fn is_timestamp(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64
+    )
+}
+
+/// Profile every top-level column of the file in one pass. `progress` counts
+/// row groups.
+pub fn profile(
+    path: &Path,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Profile, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    profile_row_groups(path, &metadata, None, bytes_read, progress)
+}
+
+/// Profile `row_groups` row groups picked at random, or with `seed` as before,
+/// for files too big to scan whole; a file with no more row groups than that is
+/// profiled whole. `progress` counts row groups.
+pub fn sample_profile(
+    path: &Path,
+    row_groups: usize,
+    seed: Option<u64>,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Profile, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let parquet = metadata.metadata();
+    let total_row_groups = parquet.num_row_groups();
+    if row_groups >= total_row_groups {
+        return profile_row_groups(path, &metadata, None, bytes_read, progress);
+    }
+
+    let seed = seed.unwrap_or_else(|| RandomState::new().hash_one(path));
+    let picked: Vec<_> = pick(total_row_groups, row_groups, seed)
+        .into_iter()
+        .map(|index| parquet.row_group(index).clone())
+        .collect();
+    let sample = Sample {
+        row_groups,
+        total_row_groups,
+        rows: picked.iter().map(|group| group.num_rows() as u64).sum(),
+        seed,
+    };
+    let sampled = ParquetMetaDataBuilder::new(parquet.file_metadata().clone())
+        .set_row_groups(picked)
+        .build();
+    let sampled = ArrowReaderMetadata::try_new(
+        Arc::new(sampled),
+        ArrowReaderOptions::new().with_schema(metadata.schema().clone()),
+    )?;
+    profile_row_groups(path, &sampled, Some(sample), bytes_read, progress)
+}
+
+/// `count` of the indices below `total`, in ascending order, picked at random
+/// from `seed`.
+fn pick(total: usize, count: usize, seed: u64) -> Vec<usize> {
+    // SplitMix64, which is enough to spread a sample.
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    let mut indices: Vec<usize> = (0..total).collect();
+    let count = count.min(total);
+    for index in 0..count {
+        let other = index + (next() % (total - index) as u64) as usize;
+        indices.swap(index, other);
+    }
+    indices.truncate(count);
+    indices.sort_unstable();
+    indices
+}
+
+/// Profile the row groups of `metadata`, which with `sample` are a sample of
+/// the file's.
+fn profile_row_groups(
+    path: &Path,
+    metadata: &ArrowReaderMetadata,
+    sample: Option<Sample>,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Profile, ViewerError> {
+    let schema = metadata.schema().clone();
+    let roots: Vec<usize> = (0..schema.fields().len()).collect();
+
+    let renderers = render::registry().column_renderers(&schema);
+    let mut states: Vec<ColumnState> = schema
+        .fields()
+        .iter()
+        .map(|field| ColumnState::new(field.data_type(), sample.is_some()))
+        .collect();
+    let mut rows = 0;
+    scan_rows(
+        path,
+        metadata,
+        &roots,
+        None,
+        bytes_read,
+        progress,
+        |batch| {
+            rows += batch.num_rows() as u64;
+            for (column, state) in states.iter_mut().enumerate() {
+                let values = batch.column(column);
+                state.add(values.as_ref(), |row| {
+                    Ok(renderers.cell_text(column, values.as_ref(), row)?)
+                })?;
+            }
+            Ok(())
+        },
+    )?;
+
+    let columns = states
+        .into_iter()
+        .zip(schema.fields())
+        .map(|(state, field)| state.finish(field.name().clone(), field.data_type(), rows))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Profile {
+        rows: metadata.metadata().file_metadata().num_rows() as u64,
+        columns,
+        sample,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Date32Array, Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use tempfile::NamedTempFile;
+
+    fn profiled_file() -> Profile {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("day", DataType::Date32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(3), None, Some(1), Some(3)])),
+                Arc::new(StringArray::from(vec![
+                    Some("ann"),
+                    Some("  "),
+                    Some("<b>"),
+                    Some("ann"),
+                ])),
+                // 2024-01-01, 1970-01-01, 0001-01-01 and a null.
+                Arc::new(Date32Array::from(vec![
+                    Some(19_723),
+                    Some(0),
+                    Some(-719_162),
+                    None,
+                ])),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        profile(file.path(), &ReadCounter::default(), &Progress::default())
+            .expect("profile should compute")
+    }
+
+    #[test]
+    fn columns_are_profiled_in_one_pass() {
+        let profile = profiled_file();
+        assert_eq!(profile.rows, 4);
+
+        let id = &profile.columns[0];
+        assert_eq!(id.nulls, 1);
+        assert_eq!(id.null_percent, 25.0);
+        assert_eq!(id.distinct, 2);
+        assert!(id.distinct_is_exact);
+        assert_eq!(id.min.as_deref(), Some("1"));
+        assert_eq!(id.max.as_deref(), Some("3"));
+        assert_eq!(
+            id.top_values[0],
+            ValueCount {
+                value: "3".to_string(),
+                count: 2
+            }
+        );
+        assert_eq!(id.blank_strings, None);
+
+        let name = &profile.columns[1];
+        assert_eq!(name.blank_strings, Some(1));
+        assert_eq!(name.out_of_range_timestamps, None);
+        assert_eq!(profile.columns[2].out_of_range_timestamps, Some(1));
+        assert_eq!(
+            id.line(),
+            "id (Int32): 25.0% null, 2 distinct, min 1, max 3; top: 3 (2), 1 (1)"
+        );
+    }
+
+    #[test]
+    fn reports_export_as_json_and_escaped_html() {
+        let profile = profiled_file();
+
+        let json: serde_json::Value = serde_json::from_str(&profile.to_json()).unwrap();
+        assert_eq!(json["rows"], 4);
+        assert_eq!(json["columns"][1]["blank_strings"], 1);
+        assert!(json["columns"][0].get("blank_strings").is_none());
+
+        let html = profile.to_html("people.parquet");
+        assert!(html.contains("<title>people.parquet</title>"));
+        assert!(html.contains("&lt;b&gt; (1)"));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn a_sample_of_row_groups_is_profiled_with_a_note() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..1_000))],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let properties = parquet::file::properties::WriterProperties::builder()
+            .set_max_row_group_size(100)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let sampled = |row_groups| {
+            sample_profile(
+                file.path(),
+                row_groups,
+                Some(7),
+                &ReadCounter::default(),
+                &Progress::default(),
+            )
+            .unwrap()
+        };
+        let profile = sampled(3);
+        assert_eq!(profile.rows, 1_000);
+        let sample = profile
+            .sample
+            .clone()
+            .expect("3 of 10 row groups are sampled");
+        assert_eq!((sample.row_groups, sample.rows, sample.seed), (3, 300, 7));
+        let id = &profile.columns[0];
+        assert_eq!(id.distinct, 300);
+        assert!(id.distinct_is_estimate);
+        assert!(id.line().contains("≈300 distinct"));
+        assert!(profile.lines()[1].starts_with("Sampled 3 of 10 row groups"));
+        assert_eq!(sampled(3), profile);
+
+        assert_eq!(sampled(10).sample, None);
+        assert_eq!(pick(10, 4, 1).len(), 4);
+        assert!(pick(10, 4, 1).windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,146 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::cancel::CancellationToken;
+use crate::ViewerError;
+
+/// How often the headless progress line is redrawn.
+const TERMINAL_REFRESH: Duration = Duration::from_millis(250);
+
+/// How far a long operation has got, shared with whoever shows it and can cancel it.
+///
+/// `done` and `total` count whatever unit the operation reports in, such as rows
+/// or row groups; `total` stays 0 until the operation knows it.
+#[derive(Clone, Debug, Default)]
+pub struct Progress(Arc<ProgressState>);
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    done: AtomicU64,
+    total: AtomicU64,
+    token: CancellationToken,
+}
+
+impl Progress {
+    pub fn set_total(&self, total: usize) {
+        self.0.total.store(total as u64, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, done: usize) {
+        self.0.done.fetch_add(done as u64, Ordering::Relaxed);
+    }
+
+    pub fn done(&self) -> u64 {
+        self.0.done.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.total.load(Ordering::Relaxed)
+    }
+
+    /// Percentage done, between 0 and 100; 0 while the total is unknown.
+    pub fn percent(&self) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => (self.done().min(total) as f32 / total as f32) * 100.0,
+        }
+    }
+
+    /// Ask the operation to stop at its next check.
+    pub fn cancel(&self) {
+        self.0.token.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.token.is_cancelled()
+    }
+
+    /// Called by the operation between units of work; fails once it was cancelled.
+    pub fn check(&self) -> Result<(), ViewerError> {
+        self.0.token.check()
+    }
+}
+
+/// Redraws a `label: done/total unit (percent)` line on stderr until dropped, for
+/// the headless subcommands.
+pub struct TerminalProgress {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TerminalProgress {
+    pub fn start(progress: &Progress, label: &'static str, unit: &'static str) -> Self {
+        let progress = progress.clone();
+        let (stop, stopped) = mpsc::channel();
+        let thread = std::thread::spawn(move || loop {
+            let finished = !matches!(
+                stopped.recv_timeout(TERMINAL_REFRESH),
+                Err(mpsc::RecvTimeoutError::Timeout)
+            );
+            let line = progress_line(&progress, label, unit);
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r{line}");
+            if finished {
+                let _ = writeln!(stderr);
+                return;
+            }
+            let _ = stderr.flush();
+        });
+
+        Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for TerminalProgress {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Text for one progress report, e.g. `Rewriting: 1200/5000 rows (24%)`.
+pub fn progress_line(progress: &Progress, label: &str, unit: &str) -> String {
+    match progress.total() {
+        0 => format!("{label}: {} {unit}", progress.done()),
+        total => format!(
+            "{label}: {}/{total} {unit} ({:.0}%)",
+            progress.done(),
+            progress.percent()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_reports_and_cancels() {
+        let progress = Progress::default();
+        assert_eq!(
+            progress_line(&progress, "Scanning", "row groups"),
+            "Scanning: 0 row groups"
+        );
+
+        progress.set_total(8);
+        progress.advance(2);
+        assert_eq!(progress.percent(), 25.0);
+        assert_eq!(
+            progress_line(&progress, "Scanning", "row groups"),
+            "Scanning: 2/8 row groups (25%)"
+        );
+
+        assert!(progress.check().is_ok());
+        progress.clone().cancel();
+        assert!(matches!(progress.check(), Err(ViewerError::Cancelled)));
+    }
+}
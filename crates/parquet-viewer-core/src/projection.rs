@@ -0,0 +1,218 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use parquet::arrow::add_encoded_arrow_schema_to_metadata;
+use parquet::column::writer::ColumnCloseResult;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+
+use crate::metadata::ARROW_SCHEMA_KEY;
+use crate::metrics::ReadCounter;
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::rewrite::{carried_metadata, RewriteSummary};
+use crate::{footer, ViewerError};
+
+/// Write a copy of `input` to `output` with only the top-level `columns`
+/// named, in that order.
+///
+/// Column chunks are copied byte for byte, pages, encodings, compression and
+/// statistics included, so nothing is decoded and the copy runs at disk speed.
+/// The page index and bloom filters aren't carried over. Footer key-value
+/// metadata is, with an embedded Arrow schema cut down to the kept columns.
+/// `output` only appears once the copy is complete; `progress` counts row
+/// groups copied.
+pub fn select_columns(
+    input: &Path,
+    output: &Path,
+    columns: &[String],
+    progress: &Progress,
+) -> Result<RewriteSummary, ViewerError> {
+    ensure_distinct([input], output)?;
+    if columns.is_empty() {
+        return Err(ViewerError::InvalidValue(
+            "no columns selected to keep".to_string(),
+        ));
+    }
+
+    let metadata = footer::reader_metadata(input, &ReadCounter::default())?;
+    let parquet = metadata.metadata();
+    let descr = parquet.file_metadata().schema_descr();
+    let root = descr.root_schema();
+    let mut roots = Vec::with_capacity(columns.len());
+    for column in columns {
+        let position = root
+            .get_fields()
+            .iter()
+            .position(|field| field.name() == column)
+            .ok_or_else(|| ViewerError::NoSuchColumn {
+                column: column.clone(),
+                path: input.to_path_buf(),
+            })?;
+        if roots.contains(&position) {
+            return Err(ViewerError::InvalidValue(format!(
+                "column `{column}` is selected more than once"
+            )));
+        }
+        roots.push(position);
+    }
+    // Leaf columns of each kept top-level column, in the order they're written.
+    let leaves: Vec<usize> = roots
+        .iter()
+        .flat_map(|&root| {
+            (0..descr.num_columns()).filter(move |&leaf| descr.get_column_root_idx(leaf) == root)
+        })
+        .collect();
+
+    let schema = Type::group_type_builder(root.name())
+        .with_fields(
+            roots
+                .iter()
+                .map(|&position| root.get_fields()[position].clone())
+                .collect(),
+        )
+        .build()?;
+    let mut props = WriterProperties::builder()
+        .set_key_value_metadata(carried_metadata(parquet))
+        .build();
+    let has_arrow_schema = parquet
+        .file_metadata()
+        .key_value_metadata()
+        .is_some_and(|entries| entries.iter().any(|entry| entry.key == ARROW_SCHEMA_KEY));
+    if has_arrow_schema {
+        add_encoded_arrow_schema_to_metadata(&metadata.schema().project(&roots)?, &mut props);
+    }
+
+    let source = File::open(input)?;
+    let input_bytes = source.metadata()?.len();
+    let row_groups = parquet.row_groups();
+    progress.set_total(row_groups.len());
+    let (rows, written) = write_atomically(output, |file| {
+        let mut writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(props))?;
+        let mut rows = 0;
+        for group in row_groups {
+            progress.check()?;
+            let mut row_group = writer.next_row_group()?;
+            for &leaf in &leaves {
+                let chunk = group.column(leaf);
+                row_group.append_column(
+                    &source,
+                    ColumnCloseResult {
+                        bytes_written: chunk.compressed_size() as u64,
+                        rows_written: group.num_rows() as u64,
+                        metadata: chunk.clone(),
+                        bloom_filter: None,
+                        column_index: None,
+                        offset_index: None,
+                    },
+                )?;
+            }
+            row_group.close()?;
+            rows += group.num_rows() as usize;
+            progress.advance(1);
+        }
+        Ok((rows, writer.close()?))
+    })?;
+
+    Ok(RewriteSummary {
+        rows,
+        input_row_groups: row_groups.len(),
+        output_row_groups: written.num_row_groups(),
+        input_bytes,
+        output_bytes: std::fs::metadata(output)?.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::AsArray;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn kept_columns_are_copied_in_the_order_asked() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("slim.parquet");
+
+        let summary = select_columns(
+            file.path(),
+            &destination,
+            &["name".to_string()],
+            &Progress::default(),
+        )
+        .expect("select should succeed");
+        assert_eq!(summary.rows, 6);
+        assert_eq!(summary.output_row_groups, 3);
+        assert!(summary.output_bytes < summary.input_bytes);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&destination).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let names: Vec<String> = reader
+            .map(Result::unwrap)
+            .flat_map(|batch| {
+                assert_eq!(batch.num_columns(), 1);
+                batch
+                    .column(0)
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|name| name.unwrap().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            (0..6).map(|id| format!("name-{id}")).collect::<Vec<_>>()
+        );
+
+        select_columns(
+            file.path(),
+            &destination,
+            &["name".to_string(), "id".to_string()],
+            &Progress::default(),
+        )
+        .expect("select should succeed");
+        let reordered = footer::reader_metadata(&destination, &ReadCounter::default()).unwrap();
+        let names: Vec<&String> = reordered
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .collect();
+        assert_eq!(names, ["name", "id"]);
+    }
+
+    #[test]
+    fn unknown_and_repeated_columns_are_refused() {
+        let file = write_test_parquet_with_row_groups(2, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("refused.parquet");
+
+        assert!(matches!(
+            select_columns(
+                file.path(),
+                &destination,
+                &["city".to_string()],
+                &Progress::default()
+            ),
+            Err(ViewerError::NoSuchColumn { .. })
+        ));
+        assert!(matches!(
+            select_columns(
+                file.path(),
+                &destination,
+                &["id".to_string(), "id".to_string()],
+                &Progress::default()
+            ),
+            Err(ViewerError::InvalidValue(_))
+        ));
+        assert!(!destination.exists());
+    }
+}
@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+
+use crate::metrics::{format_bytes, CountingFile, ReadCounter};
+use crate::ViewerError;
+
+/// Where in the file a stored cell was read from, for cross-checking with
+/// other Parquet tools.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellOrigin {
+    pub row_group: usize,
+    /// Row within the row group.
+    pub row_in_group: usize,
+    /// Dotted path of the column chunk; the first leaf's for nested columns.
+    pub column_path: String,
+    /// Byte offset and length of the column chunk in the file.
+    pub chunk_offset: u64,
+    pub chunk_length: u64,
+    /// The data page holding the row, known when the file has an offset index.
+    pub page: Option<PageOrigin>,
+}
+
+/// A data page within its column chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageOrigin {
+    /// Position of the page within its column chunk.
+    pub page: usize,
+    pub offset: u64,
+    pub compressed_size: u64,
+    /// Row within the row group the page begins at.
+    pub first_row: usize,
+}
+
+impl CellOrigin {
+    /// One line each for the row group, column chunk and page, counted from one.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "Row group {}, row {} of the group",
+                self.row_group + 1,
+                self.row_in_group + 1
+            ),
+            format!(
+                "Column chunk {} at bytes {}–{} ({})",
+                self.column_path,
+                self.chunk_offset,
+                self.chunk_offset + self.chunk_length,
+                format_bytes(self.chunk_length)
+            ),
+        ];
+        lines.push(match &self.page {
+            Some(page) => format!(
+                "Page {} at bytes {}–{} ({}), from row {} of the group",
+                page.page + 1,
+                page.offset,
+                page.offset + page.compressed_size,
+                format_bytes(page.compressed_size),
+                page.first_row + 1
+            ),
+            None => "Page unknown: the file has no offset index".to_string(),
+        });
+        lines
+    }
+}
+
+/// Find where file row `row` of the top-level column named `column` is
+/// stored; `None` if the row or column isn't in the file, as for computed columns.
+pub fn cell_origin(
+    path: &Path,
+    bytes_read: &ReadCounter,
+    row: usize,
+    column: &str,
+) -> Result<Option<CellOrigin>, ViewerError> {
+    let file = CountingFile::open(path, bytes_read)?;
+    let metadata = ParquetMetaDataReader::new()
+        .with_page_index_policy(PageIndexPolicy::Optional)
+        .parse_and_finish(&file)?;
+    let schema = metadata.file_metadata().schema_descr();
+    let Some(root) = schema
+        .root_schema()
+        .get_fields()
+        .iter()
+        .position(|field| field.name() == column)
+    else {
+        return Ok(None);
+    };
+    let Some(leaf) =
+        (0..schema.num_columns()).find(|&leaf| schema.get_column_root_idx(leaf) == root)
+    else {
+        return Ok(None);
+    };
+
+    let mut first_row = 0;
+    for (row_group, group) in metadata.row_groups().iter().enumerate() {
+        let rows = group.num_rows() as usize;
+        if row >= first_row + rows {
+            first_row += rows;
+            continue;
+        }
+        let row_in_group = row - first_row;
+        let chunk = group.column(leaf);
+        let (chunk_offset, chunk_length) = chunk.byte_range();
+        let page = metadata
+            .offset_index()
+            .and_then(|index| index.get(row_group)?.get(leaf))
+            .and_then(|pages| {
+                pages
+                    .page_locations()
+                    .iter()
+                    .enumerate()
+                    .rfind(|(_, location)| location.first_row_index as usize <= row_in_group)
+            })
+            .map(|(page, location)| PageOrigin {
+                page,
+                offset: location.offset as u64,
+                compressed_size: location.compressed_page_size as u64,
+                first_row: location.first_row_index as usize,
+            });
+        return Ok(Some(CellOrigin {
+            row_group,
+            row_in_group,
+            column_path: chunk.column_path().string(),
+            chunk_offset,
+            chunk_length,
+            page,
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn cells_are_traced_to_their_row_group_chunk_and_page() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("value", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1_000)),
+                Arc::new(Int64Array::from_iter_values(1_000..2_000)),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(600)
+            .set_data_page_row_count_limit(250)
+            .set_write_batch_size(50)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let origin = cell_origin(file.path(), &ReadCounter::default(), 900, "value")
+            .unwrap()
+            .expect("the row is in the file");
+        assert_eq!((origin.row_group, origin.row_in_group), (1, 300));
+        assert_eq!(origin.column_path, "value");
+        let page = origin.page.expect("the writer adds an offset index");
+        assert_eq!((page.page, page.first_row), (1, 250));
+        assert!(page.offset >= origin.chunk_offset);
+        assert!(page.offset + page.compressed_size <= origin.chunk_offset + origin.chunk_length);
+
+        let reader = ReadCounter::default();
+        assert_eq!(
+            cell_origin(file.path(), &reader, 1_000, "value").unwrap(),
+            None
+        );
+        assert_eq!(
+            cell_origin(file.path(), &reader, 0, "computed").unwrap(),
+            None
+        );
+    }
+}
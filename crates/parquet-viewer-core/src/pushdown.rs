@@ -0,0 +1,345 @@
+use arrow::array::{Array, ArrayRef, BooleanArray, Scalar, StringArray};
+use arrow::compute::cast;
+use arrow::compute::kernels::cmp::{gt, lt};
+use arrow::datatypes::DataType;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, RowSelection, RowSelector};
+use parquet::file::metadata::{ParquetColumnIndex, ParquetOffsetIndex, RowGroupMetaData};
+
+use crate::filter::ValueFilter;
+use crate::placeholders::{placeholders, Placeholders};
+use crate::ViewerError;
+
+/// A row group that may hold matching rows, and when the page index narrows it
+/// down further, the rows within it that are worth decoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowGroupScan {
+    pub row_group: usize,
+    pub selection: Option<RowSelection>,
+}
+
+/// What the statistics left of one row group, for explaining a scan.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RowGroupPlan {
+    pub row_group: usize,
+    pub rows: usize,
+    /// Rows left to decode; 0 when the statistics ruled the row group out.
+    pub rows_to_read: usize,
+    /// Data pages of the filtered columns in the row group, and how many of them
+    /// the page index ruled out; both 0 without a page index.
+    pub pages: usize,
+    pub pages_skipped: usize,
+}
+
+/// Decide which row groups and pages to decode for `filters` from the min/max
+/// statistics in the footer and, if the file has one, the page index.
+///
+/// Only `column = value` filters on integer, string and date columns prune, and
+/// not when the value is a placeholder. Anything the statistics can't rule out,
+/// including values that don't cast to the column type and missing statistics,
+/// is kept.
+pub fn plan_scan(
+    metadata: &ArrowReaderMetadata,
+    filters: &[ValueFilter],
+) -> Result<Vec<RowGroupScan>, ViewerError> {
+    Ok(explain_scan(metadata, filters)?.0)
+}
+
+/// [`plan_scan`], along with what it decided for every row group.
+pub fn explain_scan(
+    metadata: &ArrowReaderMetadata,
+    filters: &[ValueFilter],
+) -> Result<(Vec<RowGroupScan>, Vec<RowGroupPlan>), ViewerError> {
+    explain_scan_with(metadata, filters, placeholders())
+}
+
+fn explain_scan_with(
+    metadata: &ArrowReaderMetadata,
+    filters: &[ValueFilter],
+    placeholders: &Placeholders,
+) -> Result<(Vec<RowGroupScan>, Vec<RowGroupPlan>), ViewerError> {
+    let parquet = metadata.metadata();
+    let row_groups = parquet.row_groups();
+    let bounds: Vec<_> = filters
+        .iter()
+        .filter_map(|value_filter| prunable(metadata, value_filter, placeholders))
+        .collect();
+
+    let mut candidates = vec![true; row_groups.len()];
+    for (converter, target) in &bounds {
+        let may_match = may_match(
+            &converter.row_group_mins(row_groups)?,
+            &converter.row_group_maxes(row_groups)?,
+            target,
+        )?;
+        for (candidate, may_match) in candidates.iter_mut().zip(may_match) {
+            *candidate &= may_match;
+        }
+    }
+
+    let page_index = parquet.column_index().zip(parquet.offset_index());
+    let mut scans = Vec::new();
+    let mut plans = Vec::with_capacity(row_groups.len());
+    for (row_group, group) in row_groups.iter().enumerate() {
+        let mut plan = RowGroupPlan {
+            row_group,
+            rows: group.num_rows() as usize,
+            ..RowGroupPlan::default()
+        };
+        if !candidates[row_group] {
+            plans.push(plan);
+            continue;
+        }
+
+        let mut selection: Option<RowSelection> = None;
+        if let Some((column_index, offset_index)) = page_index {
+            for (converter, target) in &bounds {
+                let Some((pages, may_match)) = page_selection(
+                    converter,
+                    target,
+                    column_index,
+                    offset_index,
+                    row_groups,
+                    row_group,
+                )?
+                else {
+                    continue;
+                };
+                plan.pages += may_match.len();
+                plan.pages_skipped += may_match.iter().filter(|&&may_match| !may_match).count();
+                selection = Some(match selection {
+                    Some(selection) => selection.intersection(&pages),
+                    None => pages,
+                });
+            }
+        }
+
+        plan.rows_to_read = selection
+            .as_ref()
+            .map_or(plan.rows, RowSelection::row_count);
+        plans.push(plan);
+        if selection
+            .as_ref()
+            .is_some_and(|selection| !selection.selects_any())
+        {
+            continue;
+        }
+        scans.push(RowGroupScan {
+            row_group,
+            selection,
+        });
+    }
+    Ok((scans, plans))
+}
+
+/// Whether min/max statistics can rule out rows for `value_filter`.
+pub(crate) fn prunes(metadata: &ArrowReaderMetadata, value_filter: &ValueFilter) -> bool {
+    prunable(metadata, value_filter, placeholders()).is_some()
+}
+
+/// Statistics for the filtered column and the filter value cast to its type, if
+/// the filter can be checked against min/max values.
+fn prunable<'a>(
+    metadata: &'a ArrowReaderMetadata,
+    value_filter: &ValueFilter,
+    placeholders: &Placeholders,
+) -> Option<(StatisticsConverter<'a>, Scalar<ArrayRef>)> {
+    // A placeholder stands for nulls, NaN, infinities or empty strings rather
+    // than for text that is stored, and statistics leave nulls and NaN out.
+    if value_filter.exclude
+        || value_filter.regex
+        || placeholders.special(&value_filter.value).is_some()
+    {
+        return None;
+    }
+
+    let converter = StatisticsConverter::try_new(
+        &value_filter.column,
+        metadata.schema(),
+        metadata.metadata().file_metadata().schema_descr(),
+    )
+    .ok()?;
+    let data_type = converter.arrow_field().data_type();
+    // Floats are left out: NaN isn't covered by the statistics and -0.0 equals 0.0.
+    if !(data_type.is_integer()
+        || matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Date32
+        ))
+    {
+        return None;
+    }
+
+    let target = cast(
+        &StringArray::from(vec![value_filter.value.as_str()]),
+        data_type,
+    )
+    .ok()?;
+    if target.is_null(0) {
+        return None;
+    }
+    Some((converter, Scalar::new(target)))
+}
+
+/// For each min/max pair, whether `target` may lie between them; unknown bounds may.
+fn may_match(
+    mins: &ArrayRef,
+    maxes: &ArrayRef,
+    target: &Scalar<ArrayRef>,
+) -> Result<Vec<bool>, ViewerError> {
+    let above = gt(mins, target)?;
+    let below = lt(maxes, target)?;
+    let ruled_out = |flags: &BooleanArray, index| flags.is_valid(index) && flags.value(index);
+
+    Ok((0..mins.len())
+        .map(|index| !ruled_out(&above, index) && !ruled_out(&below, index))
+        .collect())
+}
+
+/// Rows of `row_group` in pages whose bounds may hold `target`, with whether
+/// each page may, or `None` without page locations for the column.
+fn page_selection(
+    converter: &StatisticsConverter,
+    target: &Scalar<ArrayRef>,
+    column_index: &ParquetColumnIndex,
+    offset_index: &ParquetOffsetIndex,
+    row_groups: &[RowGroupMetaData],
+    row_group: usize,
+) -> Result<Option<(RowSelection, Vec<bool>)>, ViewerError> {
+    let groups = [row_group];
+    let Some(row_counts) = converter.data_page_row_counts(offset_index, row_groups, &groups)?
+    else {
+        return Ok(None);
+    };
+    let may_match = may_match(
+        &converter.data_page_mins(column_index, offset_index, &groups)?,
+        &converter.data_page_maxes(column_index, offset_index, &groups)?,
+        target,
+    )?;
+
+    let selectors: Vec<RowSelector> = row_counts
+        .values()
+        .iter()
+        .zip(&may_match)
+        .map(|(&rows, &may_match)| {
+            if may_match {
+                RowSelector::select(rows as usize)
+            } else {
+                RowSelector::skip(rows as usize)
+            }
+        })
+        .collect();
+    Ok(Some((selectors.into(), may_match)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::arrow_reader::ArrowReaderOptions;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::metadata::PageIndexPolicy;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    use crate::cast::ColumnCasts;
+    use crate::filter::row_order;
+    use crate::metrics::ReadCounter;
+    use crate::progress::Progress;
+
+    #[test]
+    fn statistics_prune_row_groups_and_pages() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(50)
+            .set_data_page_row_count_limit(10)
+            .set_write_batch_size(10)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema.clone(), Some(props))
+            .expect("writer should be created");
+        for start in (0..100).step_by(10) {
+            let ids = Int64Array::from_iter_values(start..start + 10);
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(ids)])
+                .expect("record batch should build");
+            writer.write(&batch).expect("batch should be written");
+        }
+        writer.close().expect("writer should close");
+
+        let options = ArrowReaderOptions::new().with_page_index_policy(PageIndexPolicy::Optional);
+        let metadata = ArrowReaderMetadata::load(&file.reopen().unwrap(), options)
+            .expect("metadata should load");
+        let equals = |value: &str| ValueFilter {
+            column: "id".to_string(),
+            value: value.to_string(),
+            exclude: false,
+            regex: false,
+        };
+
+        let scans = plan_scan(&metadata, &[equals("73")]).unwrap();
+        assert_eq!(scans.len(), 1);
+        assert_eq!(scans[0].row_group, 1);
+        let selection = scans[0].selection.as_ref().expect("page index is written");
+        assert_eq!(selection.row_count(), 10);
+
+        assert!(plan_scan(&metadata, &[equals("500")]).unwrap().is_empty());
+        assert_eq!(plan_scan(&metadata, &[equals("x")]).unwrap().len(), 2);
+        assert_eq!(
+            row_order(
+                file.path(),
+                &[equals("73")],
+                None,
+                &ColumnCasts::new(),
+                &ReadCounter::default(),
+                &Progress::default()
+            )
+            .unwrap(),
+            vec![73]
+        );
+    }
+
+    #[test]
+    fn placeholders_never_prune() {
+        use arrow::array::StringArray;
+
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![Field::new("name", DataType::Utf8, false)]));
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema.clone(), Some(props))
+            .expect("writer should be created");
+        let names = StringArray::from(vec!["", "", "a", "b"]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(names)]).expect("record batch should build");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let metadata = ArrowReaderMetadata::load(&file.reopen().unwrap(), Default::default())
+            .expect("metadata should load");
+        let placeholders = Placeholders {
+            empty: "(empty)".to_string(),
+            ..Placeholders::default()
+        };
+        let equals = |value: &str| ValueFilter {
+            column: "name".to_string(),
+            value: value.to_string(),
+            exclude: false,
+            regex: false,
+        };
+
+        // The first row group holds only empty strings, shown as `(empty)`.
+        let scan = |value: &str| {
+            let (scans, _) = explain_scan_with(&metadata, &[equals(value)], &placeholders).unwrap();
+            scans.iter().map(|scan| scan.row_group).collect::<Vec<_>>()
+        };
+        assert_eq!(scan("(empty)"), [0, 1]);
+        assert_eq!(scan("null"), [0, 1]);
+        assert_eq!(scan("-inf"), [0, 1]);
+        assert_eq!(scan("a"), [1]);
+    }
+}
@@ -0,0 +1,687 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use memmap2::Mmap;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::arrow::arrow_writer::ArrowWriter;
+use parquet::arrow::parquet_to_arrow_schema;
+use parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
+use parquet::file::metadata::{
+    ColumnChunkMetaData, FileMetaData, KeyValue, ParquetMetaDataBuilder, ParquetMetaDataReader,
+    RowGroupMetaData,
+};
+use parquet::schema::types::{ColumnDescPtr, ColumnDescriptor, SchemaDescPtr};
+
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::rewrite::RewriteOptions;
+use crate::ViewerError;
+
+/// Bytes every Parquet file starts with.
+const MAGIC: &[u8] = b"PAR1";
+
+/// Key written by `ArrowWriter` itself; copying it from the input would duplicate it.
+const ARROW_SCHEMA_KEY: &str = "ARROW:schema";
+
+/// Outcome of a recovery, reported back to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoverySummary {
+    /// Page headers found from the start of the file.
+    pub pages: usize,
+    pub row_groups: usize,
+    pub rows: usize,
+    /// Byte offset recovery stopped at and why, e.g. where the file was cut off.
+    pub stopped: Option<(u64, String)>,
+    pub output_bytes: u64,
+}
+
+/// Salvage the row groups of `input`, a file whose footer is missing or
+/// corrupt, into a new file at `output`.
+///
+/// Page headers are read one after another from the start of the file, and
+/// split into column chunks and row groups by their row counts and dictionary
+/// pages, which only works out for top-level columns or v2 data pages. The
+/// schema and codecs are those of `reference`, a healthy file written the same
+/// way, or of `input`'s own footer if it still reads. Row groups are kept up to
+/// the first one that is cut off, can't be told apart from the next or doesn't
+/// decode. `progress` counts row groups decoded.
+pub fn recover_file(
+    input: &Path,
+    output: &Path,
+    reference: Option<&Path>,
+    options: &RewriteOptions,
+    progress: &Progress,
+) -> Result<RecoverySummary, ViewerError> {
+    ensure_distinct([input].into_iter().chain(reference), output)?;
+
+    let reference =
+        ParquetMetaDataReader::new().parse_and_finish(&File::open(reference.unwrap_or(input))?)?;
+    let schema = reference.file_metadata().schema_descr_ptr();
+    let key_value_metadata = reference.file_metadata().key_value_metadata().cloned();
+    let codecs: Vec<Compression> = match reference.row_groups().first() {
+        Some(group) => group
+            .columns()
+            .iter()
+            .map(|chunk| chunk.compression())
+            .collect(),
+        None => vec![Compression::UNCOMPRESSED; schema.num_columns()],
+    };
+
+    let file = File::open(input)?;
+    // SAFETY: the map is only read, and recovery never writes to its input.
+    let bytes = Bytes::from_owner(unsafe { Mmap::map(&file)? });
+    let (pages, mut stopped) = scan_pages(&bytes);
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < pages.len() {
+        match row_group_at(&pages, start, schema.columns()) {
+            Ok((chunks, rows)) => {
+                start = chunks.last().map_or(start, |chunk| chunk.end);
+                groups.push((chunks, rows));
+            }
+            Err(reason) => {
+                stopped = Some((pages[start].offset, reason.to_string()));
+                break;
+            }
+        }
+    }
+
+    let arrow_schema = Arc::new(parquet_to_arrow_schema(
+        &schema,
+        key_value_metadata.as_ref(),
+    )?);
+    let props = options.writer_properties(
+        key_value_metadata
+            .as_ref()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.key != ARROW_SCHEMA_KEY)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|entries| !entries.is_empty()),
+    );
+
+    progress.set_total(groups.len());
+    let mut summary = RecoverySummary {
+        pages: pages.len(),
+        row_groups: 0,
+        rows: 0,
+        stopped: None,
+        output_bytes: 0,
+    };
+    write_atomically(output, |file| {
+        let mut writer = ArrowWriter::try_new(file, arrow_schema, Some(props))?;
+        for (chunks, rows) in &groups {
+            progress.check()?;
+            let group = RowGroup {
+                pages: &pages,
+                chunks,
+                rows: *rows,
+            };
+            let mut decoded = group.read(&bytes, &schema, &key_value_metadata, &codecs);
+            // Page headers don't record the codec, so others are tried if the reference's fail.
+            for codec in fallback_codecs() {
+                if decoded.is_ok() {
+                    break;
+                }
+                let codecs = vec![codec; codecs.len()];
+                decoded = group.read(&bytes, &schema, &key_value_metadata, &codecs);
+            }
+            match decoded {
+                Ok(batches) => {
+                    for batch in &batches {
+                        writer.write(batch)?;
+                    }
+                    summary.row_groups += 1;
+                    summary.rows += *rows as usize;
+                }
+                Err(error) => {
+                    stopped = Some((
+                        pages[chunks[0].start].offset,
+                        format!("the row group doesn't decode: {error}"),
+                    ));
+                    break;
+                }
+            }
+            progress.advance(1);
+        }
+        writer.close()?;
+        Ok(())
+    })?;
+
+    summary.stopped = stopped;
+    summary.output_bytes = std::fs::metadata(output)?.len();
+    Ok(summary)
+}
+
+/// Codecs tried for a row group that doesn't decode with the reference file's.
+fn fallback_codecs() -> [Compression; 6] {
+    [
+        Compression::UNCOMPRESSED,
+        Compression::SNAPPY,
+        Compression::ZSTD(ZstdLevel::default()),
+        Compression::GZIP(GzipLevel::default()),
+        Compression::LZ4_RAW,
+        Compression::BROTLI(BrotliLevel::default()),
+    ]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PageKind {
+    Data,
+    Dictionary,
+    DataV2,
+}
+
+/// A page found from its header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RawPage {
+    kind: PageKind,
+    /// Offset of the page header in the file.
+    offset: u64,
+    header_size: usize,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    /// Values in the page, nulls included.
+    values: i64,
+    /// Rows in the page, which only v2 data pages record.
+    rows: Option<i64>,
+}
+
+impl RawPage {
+    /// Rows the page holds as a page of `leaf`, if the header tells.
+    fn rows(&self, leaf: &ColumnDescriptor) -> Option<i64> {
+        match self.kind {
+            PageKind::Dictionary => Some(0),
+            PageKind::DataV2 => self.rows,
+            // Values of a column not in a list are its rows.
+            PageKind::Data => (leaf.max_rep_level() == 0).then_some(self.values),
+        }
+    }
+}
+
+/// Read page headers one after another from the start of `bytes`, up to the
+/// end or the first bytes that aren't a page, and where and why that was.
+fn scan_pages(bytes: &[u8]) -> (Vec<RawPage>, Option<(u64, String)>) {
+    if !bytes.starts_with(MAGIC) {
+        return (
+            Vec::new(),
+            Some((0, "the file doesn't start with PAR1".to_string())),
+        );
+    }
+    let mut pages = Vec::new();
+    let mut offset = MAGIC.len();
+    while offset < bytes.len() {
+        let Some(mut page) = page_header(&bytes[offset..]) else {
+            // Where a healthy file's page indexes or footer begin, or the damage.
+            return (pages, Some((offset as u64, "no page header".to_string())));
+        };
+        page.offset = offset as u64;
+        offset += page.header_size + page.compressed_size;
+        pages.push(page);
+    }
+    (pages, None)
+}
+
+/// The page whose header starts `bytes`, if it is a whole page of a type that
+/// holds values.
+fn page_header(bytes: &[u8]) -> Option<RawPage> {
+    let mut reader = Compact { bytes, position: 0 };
+    let (mut kind, mut uncompressed, mut compressed, mut details) = (None, None, None, None);
+    let mut last = 0;
+    while let Some((id, field)) = reader.field(&mut last)? {
+        match (id, field) {
+            (1, I32) => kind = Some(reader.int()?),
+            (2, I32) => uncompressed = Some(reader.int()?),
+            (3, I32) => compressed = Some(reader.int()?),
+            (5 | 7 | 8, STRUCT) => details = Some((id, reader.int_fields()?)),
+            _ => reader.skip(field, 0)?,
+        }
+    }
+
+    // Field ids of DataPageHeader, DictionaryPageHeader and DataPageHeaderV2.
+    let (kind, values, rows) = match (kind?, details?) {
+        (0, (5, fields)) => (PageKind::Data, fields[1]?, None),
+        (2, (7, fields)) => (PageKind::Dictionary, fields[1]?, None),
+        (3, (8, fields)) => (PageKind::DataV2, fields[1]?, Some(fields[3]?)),
+        _ => return None,
+    };
+    let page = RawPage {
+        kind,
+        offset: 0,
+        header_size: reader.position,
+        compressed_size: usize::try_from(compressed?).ok()?,
+        uncompressed_size: usize::try_from(uncompressed?).ok()?,
+        values,
+        rows,
+    };
+    let whole = reader.position + page.compressed_size <= bytes.len();
+    (whole && values >= 0 && rows.is_none_or(|rows| rows >= 0)).then_some(page)
+}
+
+/// Split the pages of the row group that begins at page `start` into its column
+/// chunks, with its rows.
+///
+/// The first column's chunk is tried at every length, each giving a row count
+/// every other column's chunk must reach exactly. Only one length may work out.
+fn row_group_at(
+    pages: &[RawPage],
+    start: usize,
+    leaves: &[ColumnDescPtr],
+) -> Result<(Vec<Range<usize>>, i64), &'static str> {
+    let first = leaves.first().ok_or("the schema has no columns")?;
+    let dictionary = pages[start].kind == PageKind::Dictionary;
+    let mut found = Vec::new();
+    let mut rows = 0;
+    for page in &pages[start + usize::from(dictionary)..] {
+        if page.kind == PageKind::Dictionary || found.len() > 1 {
+            break;
+        }
+        rows += page
+            .rows(first)
+            .ok_or("pages of columns in lists need v2 data pages")?;
+        let Some(chunks) = split_chunks(pages, start, leaves, rows) else {
+            continue;
+        };
+        // A column that starts its chunks with a dictionary page starts the next one so too.
+        let end = chunks.last().map_or(start, |chunk| chunk.end);
+        if dictionary
+            && pages
+                .get(end)
+                .is_some_and(|next| next.kind != PageKind::Dictionary)
+        {
+            continue;
+        }
+        found.push((chunks, rows));
+    }
+    match found.len() {
+        0 => Err("the row group is incomplete"),
+        1 => Ok(found.remove(0)),
+        _ => Err("the row group's column chunks can't be told apart"),
+    }
+}
+
+/// The pages from `start` split into one column chunk per leaf, each of `rows`
+/// rows and with a dictionary page only first, or `None` if they don't split so.
+fn split_chunks(
+    pages: &[RawPage],
+    start: usize,
+    leaves: &[ColumnDescPtr],
+    rows: i64,
+) -> Option<Vec<Range<usize>>> {
+    if rows <= 0 {
+        return None;
+    }
+    let mut chunks = Vec::with_capacity(leaves.len());
+    let mut next = start;
+    for leaf in leaves {
+        let first = next;
+        if pages.get(next)?.kind == PageKind::Dictionary {
+            next += 1;
+        }
+        let mut seen = 0;
+        while seen < rows {
+            let page = pages.get(next)?;
+            if page.kind == PageKind::Dictionary {
+                return None;
+            }
+            seen += page.rows(leaf)?;
+            next += 1;
+        }
+        if seen != rows {
+            return None;
+        }
+        chunks.push(first..next);
+    }
+    Some(chunks)
+}
+
+/// A row group found from its pages.
+struct RowGroup<'a> {
+    pages: &'a [RawPage],
+    /// Pages of each column chunk, by leaf.
+    chunks: &'a [Range<usize>],
+    rows: i64,
+}
+
+impl RowGroup<'_> {
+    /// Decode the row group from `bytes` as if a footer described it, its
+    /// column chunks compressed with `codecs`.
+    fn read(
+        &self,
+        bytes: &Bytes,
+        schema: &SchemaDescPtr,
+        key_value_metadata: &Option<Vec<KeyValue>>,
+        codecs: &[Compression],
+    ) -> Result<Vec<RecordBatch>, ViewerError> {
+        let mut columns = Vec::with_capacity(self.chunks.len());
+        for ((leaf, chunk), codec) in schema.columns().iter().zip(self.chunks).zip(codecs) {
+            let pages = &self.pages[chunk.clone()];
+            let dictionary = pages
+                .first()
+                .filter(|page| page.kind == PageKind::Dictionary);
+            let data = pages
+                .iter()
+                .filter(|page| page.kind != PageKind::Dictionary);
+            columns.push(
+                ColumnChunkMetaData::builder(leaf.clone())
+                    .set_compression(*codec)
+                    .set_num_values(data.clone().map(|page| page.values).sum())
+                    .set_total_compressed_size(
+                        pages
+                            .iter()
+                            .map(|page| (page.header_size + page.compressed_size) as i64)
+                            .sum(),
+                    )
+                    .set_total_uncompressed_size(
+                        pages
+                            .iter()
+                            .map(|page| (page.header_size + page.uncompressed_size) as i64)
+                            .sum(),
+                    )
+                    .set_data_page_offset(data.map(|page| page.offset as i64).next().unwrap_or(0))
+                    .set_dictionary_page_offset(dictionary.map(|page| page.offset as i64))
+                    .build()?,
+            );
+        }
+        let group = RowGroupMetaData::builder(schema.clone())
+            .set_num_rows(self.rows)
+            .set_total_byte_size(columns.iter().map(|chunk| chunk.uncompressed_size()).sum())
+            .set_column_metadata(columns)
+            .build()?;
+        let file = FileMetaData::new(
+            1,
+            self.rows,
+            None,
+            key_value_metadata.clone(),
+            schema.clone(),
+            None,
+        );
+        let metadata = ParquetMetaDataBuilder::new(file)
+            .set_row_groups(vec![group])
+            .build();
+        let metadata = ArrowReaderMetadata::try_new(Arc::new(metadata), ArrowReaderOptions::new())?;
+        let batches = ParquetRecordBatchReaderBuilder::new_with_metadata(bytes.clone(), metadata)
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?;
+        let decoded: usize = batches.iter().map(RecordBatch::num_rows).sum();
+        if decoded as i64 != self.rows {
+            return Err(ViewerError::InvalidValue(format!(
+                "decoded {decoded} rows, expected {}",
+                self.rows
+            )));
+        }
+        Ok(batches)
+    }
+}
+
+// Field types of the Thrift compact protocol.
+const BOOLEAN_TRUE: u8 = 1;
+const BOOLEAN_FALSE: u8 = 2;
+const BYTE: u8 = 3;
+const I16: u8 = 4;
+const I32: u8 = 5;
+const I64: u8 = 6;
+const DOUBLE: u8 = 7;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const SET: u8 = 10;
+const MAP: u8 = 11;
+const STRUCT: u8 = 12;
+
+/// Deepest nesting of structures skipped, past which the bytes are taken for garbage.
+const MAX_DEPTH: usize = 16;
+
+/// Reads the Thrift compact protocol page headers are written in, giving
+/// `None` for anything malformed or cut off.
+struct Compact<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Compact<'_> {
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.position)?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn take(&mut self, len: usize) -> Option<()> {
+        let end = self.position.checked_add(len)?;
+        if end > self.bytes.len() {
+            return None;
+        }
+        self.position = end;
+        Some(())
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// A zigzag-encoded integer.
+    fn int(&mut self) -> Option<i64> {
+        let value = self.varint()?;
+        Some((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    /// Id and type of the next field of a structure whose previous field was
+    /// `last`, or `None` at its end.
+    fn field(&mut self, last: &mut i16) -> Option<Option<(i16, u8)>> {
+        let header = self.byte()?;
+        if header == 0 {
+            return Some(None);
+        }
+        let id = match header >> 4 {
+            0 => i16::try_from(self.int()?).ok()?,
+            delta => last.checked_add(i16::from(delta))?,
+        };
+        *last = id;
+        Some(Some((id, header & 0x0f)))
+    }
+
+    /// Skip a value of type `kind`; booleans of a field are in its header.
+    fn skip(&mut self, kind: u8, depth: usize) -> Option<()> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match kind {
+            BOOLEAN_TRUE | BOOLEAN_FALSE => {}
+            BYTE => self.take(1)?,
+            I16 | I32 | I64 => {
+                self.varint()?;
+            }
+            DOUBLE => self.take(8)?,
+            BINARY => {
+                let len = usize::try_from(self.varint()?).ok()?;
+                self.take(len)?;
+            }
+            LIST | SET => {
+                let header = self.byte()?;
+                let size = match header >> 4 {
+                    15 => self.varint()?,
+                    size => u64::from(size),
+                };
+                for _ in 0..size {
+                    self.element(header & 0x0f, depth)?;
+                }
+            }
+            MAP => {
+                let size = self.varint()?;
+                if size > 0 {
+                    let kinds = self.byte()?;
+                    for _ in 0..size {
+                        self.element(kinds >> 4, depth)?;
+                        self.element(kinds & 0x0f, depth)?;
+                    }
+                }
+            }
+            STRUCT => {
+                let mut last = 0;
+                while let Some((_, field)) = self.field(&mut last)? {
+                    self.skip(field, depth + 1)?;
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Skip an element of a collection, where booleans take a byte each.
+    fn element(&mut self, kind: u8, depth: usize) -> Option<()> {
+        match kind {
+            BOOLEAN_TRUE | BOOLEAN_FALSE => self.take(1),
+            kind => self.skip(kind, depth + 1),
+        }
+    }
+
+    /// The 32-bit integer fields of a structure by id, for ids up to 8; other
+    /// fields are skipped.
+    fn int_fields(&mut self) -> Option<[Option<i64>; 9]> {
+        let mut fields = [None; 9];
+        let mut last = 0;
+        while let Some((id, field)) = self.field(&mut last)? {
+            match usize::try_from(id) {
+                Ok(id @ 1..=8) if field == I32 => fields[id] = Some(self.int()?),
+                _ => self.skip(field, 1)?,
+            }
+        }
+        Some(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Array, Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    fn write_file() -> NamedTempFile {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("city", DataType::Utf8, true),
+        ]));
+        let cities = [Some("Oslo"), None, Some("Pune")];
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..1_000)),
+                Arc::new(StringArray::from_iter(
+                    (0..1_000).map(|row| cities[row % 3]),
+                )),
+            ],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(300)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(50)
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    fn read_ids(path: &Path) -> Vec<i64> {
+        ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                let ids = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                ids.values().to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn row_groups_before_the_cut_are_recovered() {
+        let healthy = write_file();
+        let metadata = ParquetMetaDataReader::new()
+            .parse_and_finish(&File::open(healthy.path()).unwrap())
+            .unwrap();
+        // Cut the file inside the last row group's second column.
+        let cut = metadata.row_group(3).column(1).data_page_offset() as usize + 10;
+        let bytes = std::fs::read(healthy.path()).unwrap();
+        let truncated = NamedTempFile::new().unwrap();
+        std::fs::write(truncated.path(), &bytes[..cut]).unwrap();
+        let output = NamedTempFile::new().unwrap();
+
+        let summary = recover_file(
+            truncated.path(),
+            output.path(),
+            Some(healthy.path()),
+            &RewriteOptions::default(),
+            &Progress::default(),
+        )
+        .expect("the row groups before the cut should recover");
+        assert_eq!((summary.row_groups, summary.rows), (3, 900));
+        let (stopped_at, _) = summary.stopped.expect("the last row group is cut off");
+        assert!(stopped_at < cut as u64);
+        assert_eq!(read_ids(output.path()), (0..900).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn a_readable_footer_stands_in_for_the_reference() {
+        let healthy = write_file();
+        let output = NamedTempFile::new().unwrap();
+        let summary = recover_file(
+            healthy.path(),
+            output.path(),
+            None,
+            &RewriteOptions::default(),
+            &Progress::default(),
+        )
+        .unwrap();
+        assert_eq!((summary.row_groups, summary.rows), (4, 1_000));
+        assert_eq!(read_ids(output.path()), (0..1_000).collect::<Vec<_>>());
+
+        assert!(matches!(
+            recover_file(
+                healthy.path(),
+                healthy.path(),
+                None,
+                &RewriteOptions::default(),
+                &Progress::default(),
+            ),
+            Err(ViewerError::SameInputOutput(_))
+        ));
+    }
+
+    #[test]
+    fn garbage_is_not_taken_for_a_page_header() {
+        assert_eq!(page_header(&[0x15, 0x02, 0x19, 0xff]), None);
+        assert_eq!(page_header(&[]), None);
+        let (pages, stopped) = scan_pages(b"PAR1\x00\x00");
+        assert!(pages.is_empty());
+        assert_eq!(stopped, Some((4, "no page header".to_string())));
+    }
+}
@@ -0,0 +1,273 @@
+use std::sync::{Arc, OnceLock};
+
+use arrow::array::Array;
+use arrow::datatypes::{Field, Schema};
+use arrow::error::ArrowError;
+
+use crate::{cell_text, embedding, extension, geo, utf8};
+
+/// Field metadata key holding the name of an Arrow extension type.
+pub const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+
+/// Turns the values of one column into the text shown in its cells, in place
+/// of the default Arrow formatting.
+pub trait CellRenderer: Send + Sync {
+    /// Text for the non-null value at `row`; nulls are shown as the null
+    /// placeholder without reaching the renderer.
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError>;
+
+    /// Full text for the cell inspector, e.g. pretty-printed; the cell text by default.
+    fn inspect(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        self.render(array, row)
+    }
+
+    /// Whether the renderer only writes values another way, e.g. in a locale's
+    /// format, rather than showing something the stored value doesn't say. Typed
+    /// exports keep such columns typed.
+    fn presentation_only(&self) -> bool {
+        false
+    }
+}
+
+/// Which columns a registered renderer applies to.
+#[derive(Clone, Debug)]
+pub enum RendererMatch {
+    /// Columns with exactly this name.
+    Column(String),
+    /// Columns whose field carries this Arrow extension type name.
+    ExtensionType(String),
+    /// Columns this function picks given the file schema, e.g. from its metadata.
+    Detect(fn(&Schema, &Field) -> bool),
+}
+
+impl RendererMatch {
+    fn matches(&self, schema: &Schema, field: &Field) -> bool {
+        match self {
+            RendererMatch::Column(name) => field.name() == name,
+            RendererMatch::ExtensionType(name) => {
+                field.metadata().get(EXTENSION_NAME_KEY) == Some(name)
+            }
+            RendererMatch::Detect(detect) => detect(schema, field),
+        }
+    }
+
+    /// Lower ranks win when several renderers match a column.
+    fn rank(&self) -> u8 {
+        match self {
+            RendererMatch::Column(_) => 0,
+            RendererMatch::ExtensionType(_) => 1,
+            RendererMatch::Detect(_) => 2,
+        }
+    }
+}
+
+/// Renderers registered for specific columns.
+#[derive(Clone, Default)]
+pub struct RendererRegistry {
+    renderers: Vec<(RendererMatch, Arc<dyn CellRenderer>)>,
+}
+
+static REGISTRY: OnceLock<RendererRegistry> = OnceLock::new();
+
+/// Set the process-wide registry used to format cells.
+pub fn install_registry(registry: RendererRegistry) {
+    let _ = REGISTRY.set(registry);
+}
+
+/// The installed registry, or the built-in renderers if none was installed.
+pub fn registry() -> &'static RendererRegistry {
+    REGISTRY.get_or_init(RendererRegistry::builtin)
+}
+
+impl RendererRegistry {
+    /// Renderers for well-known encodings: UUIDs, JSON, GeoParquet and GeoArrow
+    /// WKB geometries, float vectors such as embeddings, and strings read with
+    /// `--lossy-utf8`.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(
+            RendererMatch::ExtensionType(extension::ARROW_UUID.to_string()),
+            Arc::new(extension::UuidRenderer),
+        );
+        registry.register(
+            RendererMatch::ExtensionType(extension::ARROW_JSON.to_string()),
+            Arc::new(extension::JsonRenderer),
+        );
+        registry.register(
+            RendererMatch::ExtensionType(geo::GEOARROW_WKB.to_string()),
+            Arc::new(geo::WkbRenderer),
+        );
+        registry.register(
+            RendererMatch::Detect(geo::is_wkb_column),
+            Arc::new(geo::WkbRenderer),
+        );
+        registry.register(
+            RendererMatch::Detect(embedding::is_embedding_column),
+            Arc::new(embedding::EmbeddingRenderer),
+        );
+        registry.register(
+            RendererMatch::Detect(utf8::is_lossy_column),
+            Arc::new(utf8::LossyUtf8Renderer),
+        );
+        registry
+    }
+
+    pub fn register(&mut self, selector: RendererMatch, renderer: Arc<dyn CellRenderer>) {
+        self.renderers.push((selector, renderer));
+    }
+
+    /// The renderer for `field` of `schema`: one registered for its name wins
+    /// over one for its extension type, which wins over detected ones; among
+    /// equals, earlier registrations win.
+    pub fn renderer_for(&self, schema: &Schema, field: &Field) -> Option<Arc<dyn CellRenderer>> {
+        self.renderers
+            .iter()
+            .filter(|(selector, _)| selector.matches(schema, field))
+            .min_by_key(|(selector, _)| selector.rank())
+            .map(|(_, renderer)| renderer.clone())
+    }
+
+    /// Renderers for every column of `schema`, resolved once per batch.
+    pub fn column_renderers(&self, schema: &Schema) -> ColumnRenderers {
+        ColumnRenderers {
+            renderers: schema
+                .fields()
+                .iter()
+                .map(|field| self.renderer_for(schema, field))
+                .collect(),
+        }
+    }
+}
+
+/// The renderer, if any, of each column of one schema.
+pub struct ColumnRenderers {
+    renderers: Vec<Option<Arc<dyn CellRenderer>>>,
+}
+
+impl ColumnRenderers {
+    pub fn has_renderer(&self, column: usize) -> bool {
+        matches!(self.renderers.get(column), Some(Some(_)))
+    }
+
+    /// Whether `column` has a renderer that does more than write its values
+    /// another way, so its text is all an export can keep.
+    pub fn reinterprets(&self, column: usize) -> bool {
+        matches!(
+            self.renderers.get(column),
+            Some(Some(renderer)) if !renderer.presentation_only()
+        )
+    }
+
+    /// Text for one cell of `column`, through its renderer when it has one.
+    pub fn cell_text(
+        &self,
+        column: usize,
+        array: &dyn Array,
+        row: usize,
+    ) -> Result<String, ArrowError> {
+        match self.renderers.get(column).and_then(Option::as_ref) {
+            Some(renderer) if !array.is_null(row) => renderer.render(array, row),
+            _ => cell_text(array, row),
+        }
+    }
+
+    /// Text for one cell of `column` as shown in the cell inspector.
+    pub fn inspect_text(
+        &self,
+        column: usize,
+        array: &dyn Array,
+        row: usize,
+    ) -> Result<String, ArrowError> {
+        match self.renderers.get(column).and_then(Option::as_ref) {
+            Some(renderer) if !array.is_null(row) => renderer.inspect(array, row),
+            _ => cell_text(array, row),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use arrow::array::{BinaryArray, Int32Array};
+    use arrow::datatypes::DataType;
+
+    struct Hex;
+
+    impl CellRenderer for Hex {
+        fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+            let values = array
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .ok_or_else(|| ArrowError::CastError("expected binary".to_string()))?;
+            let hex: String = values
+                .value(row)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            Ok(format!("0x{hex}"))
+        }
+    }
+
+    struct Fixed(&'static str);
+
+    impl CellRenderer for Fixed {
+        fn render(&self, _array: &dyn Array, _row: usize) -> Result<String, ArrowError> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    fn tagged(name: &str, extension: &str) -> Field {
+        Field::new(name, DataType::Binary, true).with_metadata(HashMap::from([(
+            EXTENSION_NAME_KEY.to_string(),
+            extension.to_string(),
+        )]))
+    }
+
+    #[test]
+    fn renderers_are_selected_by_column_name_or_extension_type() {
+        let mut registry = RendererRegistry::default();
+        registry.register(
+            RendererMatch::ExtensionType("example.hex".into()),
+            Arc::new(Hex),
+        );
+        registry.register(
+            RendererMatch::Column("id".into()),
+            Arc::new(Fixed("by name")),
+        );
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            tagged("payload", "example.hex"),
+            Field::new("plain", DataType::Binary, true),
+        ]);
+
+        let renderers = registry.column_renderers(&schema);
+        let ids = Int32Array::from(vec![7]);
+        let payload = BinaryArray::from_opt_vec(vec![Some(&[0xab, 0x01][..]), None]);
+
+        assert_eq!(renderers.cell_text(0, &ids, 0).unwrap(), "by name");
+        assert_eq!(renderers.cell_text(1, &payload, 0).unwrap(), "0xab01");
+        assert_eq!(renderers.cell_text(1, &payload, 1).unwrap(), "null");
+        assert_eq!(renderers.cell_text(2, &payload, 0).unwrap(), "ab01");
+    }
+
+    #[test]
+    fn column_name_wins_over_extension_type() {
+        let mut registry = RendererRegistry::default();
+        registry.register(
+            RendererMatch::ExtensionType("example.hex".into()),
+            Arc::new(Hex),
+        );
+        registry.register(
+            RendererMatch::Column("payload".into()),
+            Arc::new(Fixed("by name")),
+        );
+
+        let renderers =
+            registry.column_renderers(&Schema::new(vec![tagged("payload", "example.hex")]));
+        let payload = BinaryArray::from_vec(vec![&[0xab][..]]);
+
+        assert_eq!(renderers.cell_text(0, &payload, 0).unwrap(), "by name");
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt::Write;
+
+use crate::footer;
+use crate::metadata::metadata_entries;
+use crate::metrics::format_bytes;
+use crate::preview::DataPreview;
+use crate::profile::escape_html;
+use crate::stats::all_column_stats;
+use crate::ViewerError;
+
+/// A standalone HTML page describing the file of `preview`, for sharing a
+/// snapshot of the data with people who won't open the file themselves: an
+/// overview, the schema with each column's footer statistics, the file's
+/// key-value metadata and the previewed rows as a sample.
+///
+/// Nothing but the footer and the preview is read, and the page needs no
+/// stylesheet or script besides its own.
+pub fn html_report(preview: &DataPreview) -> Result<String, ViewerError> {
+    let metadata = footer::reader_metadata(&preview.path, &preview.bytes_read)?;
+    let title = preview
+        .path
+        .file_name()
+        .unwrap_or(preview.path.as_os_str())
+        .to_string_lossy();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>body {{ font-family: sans-serif; margin: 2em; }} table {{ border-collapse: collapse; margin-bottom: 2em; }} \
+         th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }} \
+         th {{ background: #f4f4f4; }} .muted {{ color: #888; }}</style>\n\
+         </head>\n<body>\n<h1>{title}</h1>\n",
+        title = escape_html(&title),
+    );
+
+    let file = metadata.metadata().file_metadata();
+    let overview = [
+        ("Rows", preview.row_count.to_string()),
+        ("Columns", metadata.schema().fields().len().to_string()),
+        ("Row groups", preview.row_groups.len().to_string()),
+        ("Size", format_bytes(preview.file_size)),
+        ("Compression", preview.codecs.join(", ")),
+        (
+            "Written by",
+            file.created_by().unwrap_or_default().to_string(),
+        ),
+    ];
+    html.push_str("<h2>Overview</h2>\n<table>\n");
+    for (label, value) in overview {
+        let _ = writeln!(
+            html,
+            "<tr><th>{label}</th><td>{}</td></tr>",
+            escape_html(&value)
+        );
+    }
+    html.push_str("</table>\n");
+
+    html.push_str(
+        "<h2>Columns</h2>\n<table>\n<tr><th>Column</th><th>Type</th><th>Nullable</th>\
+         <th>Nulls</th><th>Min</th><th>Max</th><th>Size on disk</th></tr>\n",
+    );
+    // Nested columns have no statistics of their own.
+    for (field, stats) in metadata
+        .schema()
+        .fields()
+        .iter()
+        .zip(all_column_stats(&metadata))
+    {
+        let stat = |value: Option<String>| match value {
+            Some(value) => escape_html(&value),
+            None => "<span class=\"muted\">unknown</span>".to_string(),
+        };
+        let nulls = stats
+            .as_ref()
+            .and_then(|stats| stats.null_count)
+            .map(|nulls| nulls.to_string());
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td></tr>",
+            escape_html(field.name()),
+            escape_html(&field.data_type().to_string()),
+            if field.is_nullable() { "yes" } else { "no" },
+            stat(nulls),
+            stat(stats.as_ref().and_then(|stats| stats.min.clone())),
+            stat(stats.as_ref().and_then(|stats| stats.max.clone())),
+            stat(stats.map(|stats| format_bytes(stats.compressed_bytes))),
+        );
+    }
+    html.push_str("</table>\n");
+
+    let entries = metadata_entries(&preview.path)?;
+    if !entries.is_empty() {
+        html.push_str("<h2>Metadata</h2>\n<table>\n");
+        for (key, value) in entries.key_values.iter().chain(&entries.schema) {
+            let _ = writeln!(
+                html,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape_html(key),
+                escape_html(value)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    let _ = writeln!(
+        html,
+        "<h2>Sample</h2>\n<p class=\"muted\">The first {} of {} rows.</p>\n<table>",
+        preview.rows.len(),
+        preview.row_count
+    );
+    html.push_str("<tr>");
+    for column in &preview.columns {
+        let _ = write!(html, "<th>{}</th>", escape_html(column));
+    }
+    html.push_str("</tr>\n");
+    for row in &preview.rows {
+        html.push_str("<tr>");
+        for cell in row {
+            let _ = write!(html, "<td>{}</td>", escape_html(cell.as_ref()));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    Ok(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::load_preview;
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn the_report_has_schema_stats_and_sample_rows() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let preview =
+            load_preview(&file.path().to_path_buf(), 2, false).expect("preview should load");
+
+        let html = html_report(&preview).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<tr><th>Rows</th><td>6</td></tr>"));
+        assert!(html.contains("<tr><th>Row groups</th><td>3</td></tr>"));
+        assert!(html.contains("<td>id</td><td>Int32</td><td>no</td><td>0</td><td>0</td><td>5</td>"));
+        assert!(html.contains("The first 2 of 6 rows."));
+        assert!(html.contains("<tr><td>1</td><td>name-1</td></tr>"));
+        assert!(!html.contains("name-2"));
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use serde::Deserialize;
+
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::rewrite::{carried_metadata, RewriteOptions, RewriteSummary};
+use crate::ViewerError;
+
+/// Renames and type changes of top-level columns, each keyed by the column's
+/// name in the input file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaChanges {
+    pub renames: BTreeMap<String, String>,
+    pub casts: BTreeMap<String, DataType>,
+}
+
+/// A mapping file, e.g.
+/// `{"rename": {"ts": "event_time"}, "cast": {"id": "Int64"}}`, with each type
+/// written as the viewer shows it.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MappingFile {
+    #[serde(default)]
+    rename: BTreeMap<String, String>,
+    #[serde(default)]
+    cast: BTreeMap<String, String>,
+}
+
+impl SchemaChanges {
+    /// Add a rename written as `old=new`.
+    pub fn add_rename(&mut self, text: &str) -> Result<(), ViewerError> {
+        let (old, new) = split_pair(text, "rename", "OLD=NEW")?;
+        self.renames.insert(old.to_string(), new.to_string());
+        Ok(())
+    }
+
+    /// Add a cast written as `column=Type`, e.g. `id=Int64`.
+    pub fn add_cast(&mut self, text: &str) -> Result<(), ViewerError> {
+        let (column, data_type) = split_pair(text, "cast", "COLUMN=TYPE")?;
+        self.casts
+            .insert(column.to_string(), parse_type(column, data_type)?);
+        Ok(())
+    }
+
+    /// Add the renames and casts of the JSON mapping file at `path`.
+    pub fn add_mapping_file(&mut self, path: &Path) -> Result<(), ViewerError> {
+        let file: MappingFile = serde_json::from_reader(File::open(path)?)
+            .map_err(|error| ViewerError::InvalidValue(format!("{}: {error}", path.display())))?;
+        self.renames.extend(file.rename);
+        for (column, data_type) in file.cast {
+            let data_type = parse_type(&column, &data_type)?;
+            self.casts.insert(column, data_type);
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.casts.is_empty()
+    }
+
+    /// `schema` with the changes applied. Every column named must exist, every
+    /// cast must be one that keeps all values, and no two columns may end up
+    /// with the same name.
+    fn apply(&self, schema: &Schema, path: &Path) -> Result<Schema, ViewerError> {
+        for column in self.renames.keys().chain(self.casts.keys()) {
+            if schema.column_with_name(column).is_none() {
+                return Err(ViewerError::NoSuchColumn {
+                    column: column.clone(),
+                    path: path.to_path_buf(),
+                });
+            }
+        }
+
+        let mut fields = Vec::with_capacity(schema.fields().len());
+        for field in schema.fields() {
+            let mut changed = Field::clone(field);
+            if let Some(data_type) = self.casts.get(field.name()) {
+                if !is_widening(field.data_type(), data_type) {
+                    return Err(ViewerError::InvalidValue(format!(
+                        "casting `{}` from {} to {data_type} could lose values",
+                        field.name(),
+                        field.data_type()
+                    )));
+                }
+                changed = changed.with_data_type(data_type.clone());
+            }
+            if let Some(name) = self.renames.get(field.name()) {
+                changed = changed.with_name(name);
+            }
+            fields.push(changed);
+        }
+
+        for (position, field) in fields.iter().enumerate() {
+            if fields[..position]
+                .iter()
+                .any(|earlier| earlier.name() == field.name())
+            {
+                return Err(ViewerError::InvalidValue(format!(
+                    "more than one column would be named `{}`",
+                    field.name()
+                )));
+            }
+        }
+        Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+    }
+}
+
+fn split_pair<'a>(
+    text: &'a str,
+    flag: &str,
+    form: &str,
+) -> Result<(&'a str, &'a str), ViewerError> {
+    match text.split_once('=') {
+        Some((left, right)) if !left.trim().is_empty() && !right.trim().is_empty() => {
+            Ok((left.trim(), right.trim()))
+        }
+        _ => Err(ViewerError::InvalidValue(format!(
+            "{flag} `{text}` isn't written as {form}"
+        ))),
+    }
+}
+
+fn parse_type(column: &str, data_type: &str) -> Result<DataType, ViewerError> {
+    data_type
+        .parse::<DataType>()
+        .map_err(|error| ViewerError::InvalidValue(format!("type for `{column}`: {error}")))
+}
+
+/// Whether every value of type `from` is kept exactly as type `to`.
+fn is_widening(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    // Bits of integer a type holds exactly, and whether it holds negatives.
+    let integer = |data_type: &DataType| match data_type {
+        Int8 => Some((8, true)),
+        Int16 => Some((16, true)),
+        Int32 => Some((32, true)),
+        Int64 => Some((64, true)),
+        UInt8 => Some((8, false)),
+        UInt16 => Some((16, false)),
+        UInt32 => Some((32, false)),
+        UInt64 => Some((64, false)),
+        _ => None,
+    };
+    // Bits of integer a float's mantissa holds exactly.
+    let mantissa = |data_type: &DataType| match data_type {
+        Float16 => Some(11),
+        Float32 => Some(24),
+        Float64 => Some(53),
+        _ => None,
+    };
+
+    match (from, to) {
+        _ if from == to => true,
+        (Float16, Float32 | Float64) | (Float32, Float64) => true,
+        (Utf8, LargeUtf8 | Utf8View) | (Binary, LargeBinary | BinaryView) => true,
+        (Date32, Date64) => true,
+        (Decimal128(from_precision, from_scale), Decimal128(to_precision, to_scale))
+        | (Decimal128(from_precision, from_scale), Decimal256(to_precision, to_scale))
+        | (Decimal256(from_precision, from_scale), Decimal256(to_precision, to_scale)) => {
+            // Room for as many digits on both sides of the point.
+            to_scale >= from_scale
+                && i16::from(*to_precision) - i16::from(*to_scale)
+                    >= i16::from(*from_precision) - i16::from(*from_scale)
+        }
+        _ => match (integer(from), integer(to), mantissa(to)) {
+            (Some((from_bits, from_signed)), Some((to_bits, to_signed)), _) => {
+                if from_signed == to_signed {
+                    to_bits >= from_bits
+                } else {
+                    !from_signed && to_bits > from_bits
+                }
+            }
+            (Some((from_bits, from_signed)), None, Some(mantissa)) => {
+                from_bits - u32::from(from_signed) <= mantissa
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Write `input` to `output` with the columns renamed and cast as in
+/// `changes`, a batch at a time so that no more than a batch is held however
+/// big the file is. Casts must widen the type, e.g. Int32 to Int64 or Utf8 to
+/// LargeUtf8, and are checked against the schema before anything is written.
+///
+/// Footer key-value metadata is carried over as for a rewrite, and field
+/// metadata stays with its column. `progress` counts rows written.
+pub fn rewrite_schema(
+    input: &Path,
+    output: &Path,
+    changes: &SchemaChanges,
+    options: &RewriteOptions,
+    progress: &Progress,
+) -> Result<RewriteSummary, ViewerError> {
+    ensure_distinct([input], output)?;
+
+    let source = File::open(input)?;
+    let input_bytes = source.metadata()?.len();
+    let builder = ParquetRecordBatchReaderBuilder::try_new(source)?;
+    let input_row_groups = builder.metadata().num_row_groups();
+    progress.set_total(builder.metadata().file_metadata().num_rows() as usize);
+    let schema = Arc::new(changes.apply(builder.schema(), input)?);
+    let key_value_metadata = carried_metadata(builder.metadata());
+    let reader = builder.build()?;
+
+    let props = options.writer_properties(key_value_metadata);
+    let (rows, metadata) = write_atomically(output, |file| {
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(props))?;
+        let mut rows = 0;
+        for batch in reader {
+            progress.check()?;
+            let batch = batch?;
+            let columns = batch
+                .columns()
+                .iter()
+                .zip(schema.fields())
+                .map(|(values, field)| {
+                    if values.data_type() == field.data_type() {
+                        Ok(values.clone())
+                    } else {
+                        cast(values, field.data_type())
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let batch = RecordBatch::try_new(schema.clone(), columns)?;
+            rows += batch.num_rows();
+            writer.write(&batch)?;
+            progress.advance(batch.num_rows());
+        }
+        Ok((rows, writer.close()?))
+    })?;
+
+    Ok(RewriteSummary {
+        rows,
+        input_row_groups,
+        output_row_groups: metadata.num_row_groups(),
+        input_bytes,
+        output_bytes: std::fs::metadata(output)?.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{AsArray, Int64Array};
+    use arrow::datatypes::Int64Type;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn columns_are_renamed_and_widened() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("renamed.parquet");
+
+        let mut changes = SchemaChanges::default();
+        changes.add_rename("name = label").unwrap();
+        changes.add_cast("id=Int64").unwrap();
+        changes.add_cast("name=LargeUtf8").unwrap();
+        let summary = rewrite_schema(
+            file.path(),
+            &destination,
+            &changes,
+            &RewriteOptions::default(),
+            &Progress::default(),
+        )
+        .expect("rewrite should succeed");
+        assert_eq!(summary.rows, 6);
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&destination).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batch = reader.map(Result::unwrap).next().unwrap();
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "id");
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).name(), "label");
+        assert_eq!(schema.field(1).data_type(), &DataType::LargeUtf8);
+        assert_eq!(
+            batch.column(0).as_primitive::<Int64Type>(),
+            &Int64Array::from(vec![0, 1, 2, 3, 4, 5])
+        );
+    }
+
+    #[test]
+    fn lossy_casts_and_clashing_names_are_refused() {
+        let file = write_test_parquet_with_row_groups(2, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("refused.parquet");
+        let rewrite = |changes: &SchemaChanges| {
+            rewrite_schema(
+                file.path(),
+                &destination,
+                changes,
+                &RewriteOptions::default(),
+                &Progress::default(),
+            )
+        };
+
+        let mut narrowing = SchemaChanges::default();
+        narrowing.add_cast("id=Int16").unwrap();
+        assert!(matches!(
+            rewrite(&narrowing),
+            Err(ViewerError::InvalidValue(_))
+        ));
+
+        let mut clash = SchemaChanges::default();
+        clash.add_rename("name=id").unwrap();
+        assert!(matches!(rewrite(&clash), Err(ViewerError::InvalidValue(_))));
+
+        let mut missing = SchemaChanges::default();
+        missing.add_rename("city=town").unwrap();
+        assert!(matches!(
+            rewrite(&missing),
+            Err(ViewerError::NoSuchColumn { .. })
+        ));
+        assert!(!destination.exists());
+
+        assert!(SchemaChanges::default().add_cast("id").is_err());
+        assert!(SchemaChanges::default().add_cast("id=Integer").is_err());
+    }
+
+    #[test]
+    fn widening_keeps_every_value() {
+        assert!(is_widening(&DataType::Int32, &DataType::Int64));
+        assert!(is_widening(&DataType::UInt32, &DataType::Int64));
+        assert!(!is_widening(&DataType::UInt64, &DataType::Int64));
+        assert!(!is_widening(&DataType::Int8, &DataType::UInt16));
+        assert!(is_widening(&DataType::Int32, &DataType::Float64));
+        assert!(!is_widening(&DataType::Int64, &DataType::Float64));
+        assert!(is_widening(&DataType::Utf8, &DataType::LargeUtf8));
+        assert!(!is_widening(&DataType::LargeUtf8, &DataType::Utf8));
+        assert!(is_widening(
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(12, 3)
+        ));
+        assert!(!is_widening(
+            &DataType::Decimal128(10, 2),
+            &DataType::Decimal128(10, 3)
+        ));
+    }
+}
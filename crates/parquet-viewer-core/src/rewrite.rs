@@ -0,0 +1,219 @@
+use std::fs::File;
+use std::path::Path;
+
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::metadata::{KeyValue, ParquetMetaData};
+use parquet::file::properties::{EnabledStatistics, WriterProperties};
+
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::ViewerError;
+
+/// Key written by `ArrowWriter` itself; copying it from the input would duplicate it.
+const ARROW_SCHEMA_KEY: &str = "ARROW:schema";
+
+/// Writer settings applied when rewriting a file.
+#[derive(Debug, Clone)]
+pub struct RewriteOptions {
+    pub compression: Compression,
+    pub row_group_size: Option<usize>,
+    pub dictionary: bool,
+    pub page_index: bool,
+}
+
+impl Default for RewriteOptions {
+    fn default() -> Self {
+        Self {
+            compression: Compression::SNAPPY,
+            row_group_size: None,
+            dictionary: true,
+            page_index: true,
+        }
+    }
+}
+
+/// Outcome of a rewrite, reported back to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteSummary {
+    pub rows: usize,
+    pub input_row_groups: usize,
+    pub output_row_groups: usize,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+}
+
+impl RewriteOptions {
+    pub(crate) fn writer_properties(
+        &self,
+        key_value_metadata: Option<Vec<KeyValue>>,
+    ) -> WriterProperties {
+        let statistics = if self.page_index {
+            EnabledStatistics::Page
+        } else {
+            EnabledStatistics::Chunk
+        };
+
+        let mut builder = WriterProperties::builder()
+            .set_compression(self.compression)
+            .set_dictionary_enabled(self.dictionary)
+            .set_statistics_enabled(statistics)
+            .set_offset_index_disabled(!self.page_index)
+            .set_key_value_metadata(key_value_metadata);
+
+        if let Some(row_group_size) = self.row_group_size {
+            builder = builder.set_max_row_group_size(row_group_size);
+        }
+
+        builder.build()
+    }
+}
+
+/// Footer key-value entries of `metadata` to copy to a rewritten file, or
+/// `None` if it has none besides the encoded Arrow schema.
+pub(crate) fn carried_metadata(metadata: &ParquetMetaData) -> Option<Vec<KeyValue>> {
+    metadata
+        .file_metadata()
+        .key_value_metadata()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|entry| entry.key != ARROW_SCHEMA_KEY)
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .filter(|entries| !entries.is_empty())
+}
+
+/// Stream every batch of `input` into `output` using the requested writer settings.
+///
+/// Footer key-value metadata (e.g. pandas schema hints) is carried over to the new file.
+/// `progress` counts rows written.
+pub fn rewrite_file(
+    input: &Path,
+    output: &Path,
+    options: &RewriteOptions,
+    progress: &Progress,
+) -> Result<RewriteSummary, ViewerError> {
+    ensure_distinct([input], output)?;
+
+    let source = File::open(input)?;
+    let input_bytes = source.metadata()?.len();
+    let builder = ParquetRecordBatchReaderBuilder::try_new(source)?;
+    let input_row_groups = builder.metadata().num_row_groups();
+    progress.set_total(builder.metadata().file_metadata().num_rows() as usize);
+    let key_value_metadata = carried_metadata(builder.metadata());
+    let schema = builder.schema().clone();
+    let reader = builder.build()?;
+
+    let props = options.writer_properties(key_value_metadata);
+    let (rows, metadata) = write_atomically(output, |file| {
+        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+        let mut rows = 0;
+        for batch in reader {
+            progress.check()?;
+            let batch = batch?;
+            rows += batch.num_rows();
+            writer.write(&batch)?;
+            progress.advance(batch.num_rows());
+        }
+        Ok((rows, writer.close()?))
+    })?;
+
+    Ok(RewriteSummary {
+        rows,
+        input_row_groups,
+        output_row_groups: metadata.num_row_groups(),
+        input_bytes,
+        output_bytes: std::fs::metadata(output)?.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn write_input(rows: i32) -> NamedTempFile {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from((0..rows).collect::<Vec<_>>()))],
+        )
+        .expect("record batch should build");
+
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let props = WriterProperties::builder()
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                "origin".to_string(),
+                "test".to_string(),
+            )]))
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            file.reopen().expect("temp file should reopen"),
+            schema,
+            Some(props),
+        )
+        .expect("writer should build");
+        writer.write(&batch).expect("batch should write");
+        writer.close().expect("writer should close");
+        file
+    }
+
+    #[test]
+    fn rewrite_applies_writer_settings() {
+        let input = write_input(100);
+        let dir = TempDir::new().expect("temp dir should be created");
+        let output = dir.path().join("out.parquet");
+        let options = RewriteOptions {
+            compression: Compression::ZSTD(Default::default()),
+            row_group_size: Some(30),
+            dictionary: false,
+            page_index: false,
+        };
+
+        let summary = rewrite_file(input.path(), &output, &options, &Progress::default())
+            .expect("rewrite should work");
+
+        assert_eq!(summary.rows, 100);
+        assert_eq!(summary.input_row_groups, 1);
+        assert_eq!(summary.output_row_groups, 4);
+
+        let reader = SerializedFileReader::new(File::open(&output).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 100);
+        assert!(matches!(
+            metadata.row_group(0).column(0).compression(),
+            Compression::ZSTD(_)
+        ));
+        let keys: Vec<_> = metadata
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .map(|entry| entry.key.as_str())
+            .collect();
+        assert!(keys.contains(&"origin"));
+    }
+
+    #[test]
+    fn rewrite_rejects_in_place_output() {
+        let input = write_input(1);
+
+        let result = rewrite_file(
+            input.path(),
+            input.path(),
+            &RewriteOptions::default(),
+            &Progress::default(),
+        );
+
+        assert!(matches!(result, Err(ViewerError::SameInputOutput(_))));
+    }
+}
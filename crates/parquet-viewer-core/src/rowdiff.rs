@@ -0,0 +1,88 @@
+use crate::inspect::RecordDetail;
+
+/// One column of two rows side by side; a side is `None` when its file has
+/// no column of that name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnDiff {
+    pub column: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl ColumnDiff {
+    pub fn changed(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// The columns of `left` and `right` matched by name, in the column order of
+/// `left` followed by the columns only `right` has, so that rows of two
+/// different files can be compared too.
+pub fn diff_records(left: &RecordDetail, right: &RecordDetail) -> Vec<ColumnDiff> {
+    let text = |record: &RecordDetail, column: &str| {
+        record
+            .cells
+            .iter()
+            .find(|cell| cell.column == column)
+            .map(|cell| cell.text.clone())
+    };
+
+    let mut diffs: Vec<ColumnDiff> = left
+        .cells
+        .iter()
+        .map(|cell| ColumnDiff {
+            column: cell.column.clone(),
+            left: Some(cell.text.clone()),
+            right: text(right, &cell.column),
+        })
+        .collect();
+    diffs.extend(
+        right
+            .cells
+            .iter()
+            .filter(|cell| text(left, &cell.column).is_none())
+            .map(|cell| ColumnDiff {
+                column: cell.column.clone(),
+                left: None,
+                right: Some(cell.text.clone()),
+            }),
+    );
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::inspect::CellDetail;
+
+    fn record(row: usize, cells: &[(&str, &str)]) -> RecordDetail {
+        RecordDetail {
+            row,
+            cells: cells
+                .iter()
+                .map(|(column, text)| CellDetail {
+                    column: column.to_string(),
+                    data_type: "Utf8".to_string(),
+                    text: text.to_string(),
+                    image: None,
+                    origin: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn columns_are_matched_by_name() {
+        let left = record(0, &[("id", "1"), ("name", "a"), ("only_left", "x")]);
+        let right = record(4, &[("name", "b"), ("id", "1"), ("only_right", "y")]);
+
+        let diffs = diff_records(&left, &right);
+        let columns: Vec<&str> = diffs.iter().map(|diff| diff.column.as_str()).collect();
+        assert_eq!(columns, vec!["id", "name", "only_left", "only_right"]);
+        let changed: Vec<bool> = diffs.iter().map(ColumnDiff::changed).collect();
+        assert_eq!(changed, vec![false, true, true, true]);
+        assert_eq!(diffs[1].right.as_deref(), Some("b"));
+        assert_eq!(diffs[3].left, None);
+    }
+}
@@ -0,0 +1,252 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::placeholders::placeholders;
+use crate::ViewerError;
+
+/// Background a highlight rule paints matching cells with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleColor {
+    Red,
+    #[default]
+    Yellow,
+    Green,
+    Blue,
+}
+
+impl RuleColor {
+    pub const ALL: [RuleColor; 4] = [
+        RuleColor::Red,
+        RuleColor::Yellow,
+        RuleColor::Green,
+        RuleColor::Blue,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleColor::Red => "red",
+            RuleColor::Yellow => "yellow",
+            RuleColor::Green => "green",
+            RuleColor::Blue => "blue",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    /// Operators as written in rules, two-character ones first so that `<=`
+    /// isn't read as `<`.
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("!=", Comparison::NotEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ];
+
+    fn symbol(self) -> &'static str {
+        Comparison::OPERATORS
+            .iter()
+            .find(|(_, comparison)| *comparison == self)
+            .map_or("=", |(symbol, _)| symbol)
+    }
+
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Greater => ordering.is_gt(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::NotEqual => ordering.is_ne(),
+        }
+    }
+}
+
+/// What a cell has to satisfy for a rule to highlight it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    IsNull,
+    IsNotNull,
+    /// The cell contains the text, ignoring case.
+    Contains(String),
+    /// Numbers compare as numbers, anything else as text, which orders ISO
+    /// dates and timestamps correctly.
+    Compare(Comparison, String),
+}
+
+/// A rule painting the cells of one column that meet a condition, e.g.
+/// `amount < 0 => red` or `ts is null => yellow`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    pub column: String,
+    pub condition: Condition,
+    #[serde(default)]
+    pub color: RuleColor,
+}
+
+impl HighlightRule {
+    /// Parse `<column> <condition> [=> <color>]`, where the condition is one of
+    /// `< <= > >= = != <value>`, `contains <text>`, `is null` or `is not null`.
+    /// Values may be quoted; the color defaults to yellow.
+    pub fn parse(text: &str) -> Result<HighlightRule, ViewerError> {
+        let invalid = |reason: &str| ViewerError::InvalidRule(format!("{reason}: {text}"));
+        let (rule, color) = match text.rsplit_once("=>") {
+            Some((rule, color)) => {
+                let color = color.trim();
+                let color = RuleColor::ALL
+                    .into_iter()
+                    .find(|known| known.name().eq_ignore_ascii_case(color))
+                    .ok_or_else(|| {
+                        invalid("the color must be one of red, yellow, green or blue")
+                    })?;
+                (rule.trim(), color)
+            }
+            None => (text.trim(), RuleColor::default()),
+        };
+
+        let lowercase = rule.to_ascii_lowercase();
+        let (column, condition) = if let Some(column) = lowercase.strip_suffix(" is not null") {
+            (&rule[..column.len()], Condition::IsNotNull)
+        } else if let Some(column) = lowercase.strip_suffix(" is null") {
+            (&rule[..column.len()], Condition::IsNull)
+        } else if let Some(index) = lowercase.find(" contains ") {
+            let value = unquote(&rule[index + " contains ".len()..]);
+            (&rule[..index], Condition::Contains(value))
+        } else {
+            let (index, symbol, comparison) = rule
+                .char_indices()
+                .find_map(|(index, _)| {
+                    Comparison::OPERATORS
+                        .iter()
+                        .find(|(symbol, _)| rule[index..].starts_with(symbol))
+                        .map(|&(symbol, comparison)| (index, symbol, comparison))
+                })
+                .ok_or_else(|| invalid("no condition such as `< 0` or `is null`"))?;
+            let value = unquote(&rule[index + symbol.len()..]);
+            (&rule[..index], Condition::Compare(comparison, value))
+        };
+
+        let column = column.trim();
+        if column.is_empty() {
+            return Err(invalid("no column named"));
+        }
+        Ok(HighlightRule {
+            column: column.to_string(),
+            condition,
+            color,
+        })
+    }
+
+    /// Whether a cell showing `text` meets the condition; null cells show the
+    /// null placeholder, `null` by default.
+    pub fn matches(&self, text: &str) -> bool {
+        let null = &placeholders().null;
+        match &self.condition {
+            Condition::IsNull => text == null,
+            Condition::IsNotNull => text != null,
+            Condition::Contains(value) => text.to_lowercase().contains(&value.to_lowercase()),
+            // Nulls have no value to compare.
+            Condition::Compare(_, _) if text == null => false,
+            Condition::Compare(comparison, value) => {
+                let ordering = match (text.trim().parse::<f64>(), value.parse::<f64>()) {
+                    (Ok(cell), Ok(value)) => cell.partial_cmp(&value),
+                    _ => Some(text.cmp(value)),
+                };
+                ordering.is_some_and(|ordering| comparison.holds(ordering))
+            }
+        }
+    }
+}
+
+impl fmt::Display for HighlightRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.column)?;
+        match &self.condition {
+            Condition::IsNull => write!(f, "is null")?,
+            Condition::IsNotNull => write!(f, "is not null")?,
+            Condition::Contains(value) => write!(f, "contains {value:?}")?,
+            Condition::Compare(comparison, value) if value.parse::<f64>().is_ok() => {
+                write!(f, "{} {value}", comparison.symbol())?
+            }
+            Condition::Compare(comparison, value) => {
+                write!(f, "{} {value:?}", comparison.symbol())?
+            }
+        }
+        write!(f, " => {}", self.color.name())
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|value| value.strip_suffix('\''))
+        })
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_parse_conditions_and_colors() {
+        let negative = HighlightRule::parse("amount < 0 => red").unwrap();
+        assert_eq!(
+            negative,
+            HighlightRule {
+                column: "amount".to_string(),
+                condition: Condition::Compare(Comparison::Less, "0".to_string()),
+                color: RuleColor::Red,
+            }
+        );
+        assert_eq!(negative.to_string(), "amount < 0 => red");
+
+        let missing = HighlightRule::parse("created at IS NULL").unwrap();
+        assert_eq!(missing.column, "created at");
+        assert_eq!(missing.condition, Condition::IsNull);
+        assert_eq!(missing.color, RuleColor::Yellow);
+
+        let status = HighlightRule::parse("status contains 'fail' => Blue").unwrap();
+        assert_eq!(status.condition, Condition::Contains("fail".to_string()));
+        assert_eq!(HighlightRule::parse(&status.to_string()).unwrap(), status);
+
+        assert!(HighlightRule::parse("amount 0").is_err());
+        assert!(HighlightRule::parse("< 0").is_err());
+        assert!(HighlightRule::parse("amount < 0 => pink").is_err());
+    }
+
+    #[test]
+    fn numbers_compare_numerically_and_text_lexically() {
+        let rule = |text| HighlightRule::parse(text).unwrap();
+
+        assert!(rule("amount < 0").matches("-3.5"));
+        assert!(!rule("amount < 0").matches("10"));
+        assert!(rule("amount >= 10").matches("10.0"));
+        assert!(!rule("amount < 0").matches("null"));
+        assert!(rule("day < 2020-01-01").matches("2019-12-31"));
+        assert!(rule("code != 'ok'").matches("error"));
+        assert!(rule("ts is null").matches("null"));
+        assert!(rule("ts is not null").matches("2024-01-01"));
+        assert!(rule("message contains FAIL").matches("test failed"));
+    }
+}
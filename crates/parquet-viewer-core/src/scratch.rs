@@ -0,0 +1,83 @@
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::compute::concat_batches;
+use arrow::csv::reader::Format;
+use arrow::csv::ReaderBuilder;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+
+use crate::ViewerError;
+
+/// Lines read to infer the column types of pasted text.
+const INFER_ROWS: usize = 1_000;
+
+/// Parse tab- or comma-separated text with a header line, as copied from a
+/// spreadsheet or another table, inferring each column's type. Text whose
+/// header has a tab is read as tab-separated.
+pub fn parse_table(text: &str) -> Result<RecordBatch, ViewerError> {
+    let text = text.trim_matches(['\r', '\n']);
+    let header = text.lines().next().unwrap_or_default();
+    if header.trim().is_empty() {
+        return Err(ViewerError::InvalidPaste("there is no text".to_string()));
+    }
+    let delimiter = if header.contains('\t') { b'\t' } else { b',' };
+    let format = Format::default()
+        .with_header(true)
+        .with_delimiter(delimiter);
+
+    let (schema, _) = format.infer_schema(Cursor::new(text), Some(INFER_ROWS))?;
+    let schema = Arc::new(schema);
+    let batches = ReaderBuilder::new(schema.clone())
+        .with_format(format)
+        .build(Cursor::new(text))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let batch = concat_batches(&schema, &batches)?;
+    if batch.num_rows() == 0 {
+        return Err(ViewerError::InvalidPaste(
+            "there are no rows below the header".to_string(),
+        ));
+    }
+    Ok(batch)
+}
+
+/// Parse pasted text with [`parse_table`] and write it to `destination` as
+/// Parquet, so that it can be viewed like any other file; returns the row count.
+pub fn write_scratch(text: &str, destination: &Path) -> Result<usize, ViewerError> {
+    let batch = parse_table(text)?;
+    let mut writer = ArrowWriter::try_new(File::create(destination)?, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(batch.num_rows())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::datatypes::DataType;
+
+    #[test]
+    fn pasted_tables_get_inferred_types() {
+        let batch = parse_table("id\tname\tscore\n1\tada\t2.5\n2\t\t3\n").unwrap();
+        let schema = batch.schema();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+
+        let csv = parse_table("city,active\r\nOslo,true\r\n").unwrap();
+        assert_eq!(csv.schema().field(1).data_type(), &DataType::Boolean);
+
+        assert!(matches!(
+            parse_table("id,name\n"),
+            Err(ViewerError::InvalidPaste(_))
+        ));
+        assert!(matches!(
+            parse_table("  \n"),
+            Err(ViewerError::InvalidPaste(_))
+        ));
+    }
+}
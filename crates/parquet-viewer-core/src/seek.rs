@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use arrow::array::{Array, ArrayRef, Scalar, StringArray};
+use arrow::compute::kernels::cmp::{gt, lt};
+use arrow::compute::{cast_with_options, CastOptions};
+use arrow::datatypes::DataType;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+
+use crate::footer;
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::progress::Progress;
+use crate::sortedness::Sortedness;
+use crate::ViewerError;
+
+/// The first file row of the sorted top-level column `column` at or past
+/// `value` in its order: the first value ≥ `value` in an ascending column, or
+/// ≤ it in a descending one. `None` if every value comes before it.
+///
+/// The row group statistics are binary searched for the row group to read, so
+/// that only one column chunk is decoded however long the file is; its rows
+/// are then scanned, moving on to the next row group should none qualify;
+/// `progress` counts those row groups.
+pub fn seek_value(
+    path: &Path,
+    column: usize,
+    order: Sortedness,
+    value: &str,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<Option<usize>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let field = metadata.schema().field(column).clone();
+    let target = parse_value(value, field.data_type())?;
+    let target = Scalar::new(target);
+
+    let parquet = metadata.metadata();
+    let converter = StatisticsConverter::try_new(
+        field.name(),
+        metadata.schema(),
+        parquet.file_metadata().schema_descr(),
+    )?;
+    let row_groups = parquet.row_groups();
+    // Row groups whose values all come before `value`; a prefix when the column is sorted.
+    let before = match order {
+        Sortedness::Ascending => lt(&converter.row_group_maxes(row_groups)?, &target)?,
+        Sortedness::Descending => gt(&converter.row_group_mins(row_groups)?, &target)?,
+    };
+    let before: Vec<bool> = (0..before.len())
+        .map(|index| before.is_valid(index) && before.value(index))
+        .collect();
+    let first = before.partition_point(|&before| before);
+    progress.set_total(row_groups.len() - first);
+
+    let mut start: usize = row_groups[..first]
+        .iter()
+        .map(|row_group| row_group.num_rows() as usize)
+        .sum();
+    for (index, row_group) in row_groups.iter().enumerate().skip(first) {
+        let builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            CountingFile::open(path, bytes_read)?,
+            metadata.clone(),
+        );
+        let projection = ProjectionMask::roots(builder.parquet_schema(), [column]);
+        let mut offset = 0;
+        for batch in builder
+            .with_row_groups(vec![index])
+            .with_projection(projection)
+            .build()?
+        {
+            progress.check()?;
+            let values = batch?.column(0).clone();
+            let before = match order {
+                Sortedness::Ascending => lt(&values, &target)?,
+                Sortedness::Descending => gt(&values, &target)?,
+            };
+            if let Some(row) =
+                (0..before.len()).find(|&row| before.is_valid(row) && !before.value(row))
+            {
+                return Ok(Some(start + offset + row));
+            }
+            offset += values.len();
+        }
+        start += row_group.num_rows() as usize;
+        progress.advance(1);
+    }
+    Ok(None)
+}
+
+/// `value` typed as `data_type`, for comparing with a column's values.
+fn parse_value(value: &str, data_type: &DataType) -> Result<ArrayRef, ViewerError> {
+    let text = StringArray::from(vec![value.trim()]);
+    let options = CastOptions {
+        safe: false,
+        ..CastOptions::default()
+    };
+    cast_with_options(&text, data_type, &options).map_err(|_| {
+        ViewerError::InvalidValue(format!("\"{}\" is not a {data_type}", value.trim()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn values_are_found_through_the_row_group_statistics() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("countdown", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values((0..20).map(|row| row * 10))),
+                Arc::new(Int64Array::from_iter_values((0..20).rev())),
+            ],
+        )
+        .expect("record batch should build");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(4)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let bytes_read = ReadCounter::default();
+        let seek = |column, order, value| {
+            seek_value(
+                file.path(),
+                column,
+                order,
+                value,
+                &bytes_read,
+                &Progress::default(),
+            )
+            .expect("seek should succeed")
+        };
+        assert_eq!(seek(0, Sortedness::Ascending, "95"), Some(10));
+        assert_eq!(seek(0, Sortedness::Ascending, "100"), Some(10));
+        assert_eq!(seek(0, Sortedness::Ascending, "-5"), Some(0));
+        assert_eq!(seek(0, Sortedness::Ascending, "500"), None);
+        assert_eq!(seek(1, Sortedness::Descending, "7"), Some(12));
+        assert!(matches!(
+            seek_value(
+                file.path(),
+                0,
+                Sortedness::Ascending,
+                "ten",
+                &bytes_read,
+                &Progress::default()
+            ),
+            Err(ViewerError::InvalidValue(_))
+        ));
+
+        let cancelled = Progress::default();
+        cancelled.cancel();
+        assert!(matches!(
+            seek_value(
+                file.path(),
+                0,
+                Sortedness::Ascending,
+                "95",
+                &bytes_read,
+                &cancelled
+            ),
+            Err(ViewerError::Cancelled)
+        ));
+    }
+}
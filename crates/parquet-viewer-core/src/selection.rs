@@ -0,0 +1,352 @@
+use std::ops::Range;
+
+use crate::width::display_width;
+
+/// Most rows copied to the clipboard at once; larger selections are exported to a file.
+pub const CLIPBOARD_ROW_LIMIT: usize = 10_000;
+
+/// Rows selected in the preview, by display row: the row's position in the
+/// filtered and sorted view, not in the file.
+///
+/// The anchor is the row of the last plain or toggling click; Shift+click
+/// selects everything between it and the clicked row. Rows are kept as runs, so
+/// selecting every row of a large file takes no more memory than selecting one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SelectedRows {
+    /// Ascending, non-empty runs with gaps between them.
+    runs: Vec<Range<usize>>,
+    anchor: Option<usize>,
+}
+
+impl SelectedRows {
+    /// Select only `row`.
+    pub fn select(&mut self, row: usize) {
+        self.runs.clear();
+        self.runs.push(row..row + 1);
+        self.anchor = Some(row);
+    }
+
+    /// Add or remove `row` without touching the rest of the selection.
+    pub fn toggle(&mut self, row: usize) {
+        let index = self.runs.partition_point(|run| run.end <= row);
+        match self.runs.get(index) {
+            Some(run) if run.start <= row => {
+                let after = row + 1..run.end;
+                self.runs[index].end = row;
+                if !after.is_empty() {
+                    self.runs.insert(index + 1, after);
+                }
+                if self.runs[index].is_empty() {
+                    self.runs.remove(index);
+                }
+            }
+            _ => {
+                self.runs.insert(index, row..row + 1);
+                // Join the runs either side when the row closes a gap.
+                if index + 1 < self.runs.len() && self.runs[index + 1].start == row + 1 {
+                    self.runs[index].end = self.runs.remove(index + 1).end;
+                }
+                if index > 0 && self.runs[index - 1].end == row {
+                    self.runs[index - 1].end = self.runs.remove(index).end;
+                }
+            }
+        }
+        self.anchor = Some(row);
+    }
+
+    /// Replace the selection with the rows between the anchor and `row`, inclusive.
+    pub fn extend_to(&mut self, row: usize) {
+        let anchor = self.anchor.unwrap_or(row);
+        self.runs.clear();
+        self.runs.push(anchor.min(row)..anchor.max(row) + 1);
+        self.anchor = Some(anchor);
+    }
+
+    pub fn contains(&self, row: usize) -> bool {
+        let index = self.runs.partition_point(|run| run.end <= row);
+        self.runs.get(index).is_some_and(|run| run.start <= row)
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(ExactSizeIterator::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Selected rows as ascending runs of consecutive rows, so they can be read range by range.
+    pub fn runs(&self) -> Vec<Range<usize>> {
+        self.runs.clone()
+    }
+}
+
+/// Group rows into runs of consecutive ascending indices, keeping their order.
+pub fn runs(rows: impl IntoIterator<Item = usize>) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for row in rows {
+        match runs.last_mut() {
+            Some(run) if run.end == row => run.end += 1,
+            _ => runs.push(row..row + 1),
+        }
+    }
+    runs
+}
+
+/// Text formats selected rows can be copied to the clipboard in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardFormat {
+    /// Tab-separated, which spreadsheets paste into cells.
+    Tsv,
+    Csv,
+    /// An array of objects of column names to the cells' text.
+    Json,
+    Markdown,
+}
+
+impl ClipboardFormat {
+    pub const ALL: [ClipboardFormat; 4] = [
+        ClipboardFormat::Tsv,
+        ClipboardFormat::Csv,
+        ClipboardFormat::Json,
+        ClipboardFormat::Markdown,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ClipboardFormat::Tsv => "TSV",
+            ClipboardFormat::Csv => "CSV",
+            ClipboardFormat::Json => "JSON",
+            ClipboardFormat::Markdown => "Markdown",
+        }
+    }
+
+    /// Format a header and rows in this format.
+    pub fn format(self, header: &[String], rows: &[Vec<impl AsRef<str>>]) -> String {
+        match self {
+            ClipboardFormat::Tsv => to_delimited(header, rows, '\t'),
+            ClipboardFormat::Csv => to_delimited(header, rows, ','),
+            ClipboardFormat::Json => to_json(header, rows),
+            ClipboardFormat::Markdown => to_markdown(header, rows),
+        }
+    }
+}
+
+/// Format a header and rows as delimited text, quoting fields the way CSV expects.
+///
+/// Fields containing the delimiter, a quote or a line break are wrapped in quotes
+/// with embedded quotes doubled; everything else is written as is.
+pub fn to_delimited(header: &[String], rows: &[Vec<impl AsRef<str>>], delimiter: char) -> String {
+    let mut output = String::new();
+    push_record(&mut output, header, delimiter);
+    output.push('\n');
+    for record in rows {
+        push_record(&mut output, record, delimiter);
+        output.push('\n');
+    }
+    output
+}
+
+/// Format a single record, without a trailing line break.
+pub fn delimited_record(fields: &[impl AsRef<str>], delimiter: char) -> String {
+    let mut output = String::new();
+    push_record(&mut output, fields, delimiter);
+    output
+}
+
+/// Format a header and rows as a GitHub-flavored Markdown table, padded so that
+/// it also reads well as plain text.
+///
+/// Pipes are escaped and line breaks become `<br>`, which keeps every row on one line.
+pub fn to_markdown(header: &[String], rows: &[Vec<impl AsRef<str>>]) -> String {
+    let escape = |field: &str| {
+        field
+            .replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace(['\n', '\r'], "<br>")
+    };
+    let header: Vec<String> = header.iter().map(|field| escape(field)).collect();
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|field| escape(field.as_ref())).collect())
+        .collect();
+
+    // The separator row needs at least three dashes.
+    let mut widths: Vec<usize> = header
+        .iter()
+        .map(|field| display_width(field).max(3))
+        .collect();
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(field));
+        }
+    }
+
+    let line = |fields: &[String]| {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(index, &width)| {
+                let field = fields.get(index).map_or("", String::as_str);
+                format!("{field}{}", " ".repeat(width - display_width(field)))
+            })
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let separator: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+
+    let mut output = line(&header);
+    output.push_str(&line(&separator));
+    for row in &rows {
+        output.push_str(&line(row));
+    }
+    output
+}
+
+/// Format a header and rows as a JSON array with an object per row, keys in
+/// column order. Cells stay strings, as the viewer shows them.
+pub fn to_json(header: &[String], rows: &[Vec<impl AsRef<str>>]) -> String {
+    // Written by hand because `serde_json::Map` sorts its keys.
+    let string = |text: &str| serde_json::Value::from(text).to_string();
+    let objects: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let fields: Vec<String> = header
+                .iter()
+                .zip(row)
+                .map(|(column, field)| {
+                    format!("    {}: {}", string(column), string(field.as_ref()))
+                })
+                .collect();
+            format!("  {{\n{}\n  }}", fields.join(",\n"))
+        })
+        .collect();
+    if objects.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+fn push_record(output: &mut String, fields: &[impl AsRef<str>], delimiter: char) {
+    for (index, field) in fields.iter().enumerate() {
+        let field = field.as_ref();
+        if index > 0 {
+            output.push(delimiter);
+        }
+
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_build_runs_of_selected_rows() {
+        let mut selection = SelectedRows::default();
+        selection.select(4);
+        selection.extend_to(2);
+        selection.toggle(9);
+        selection.toggle(3);
+
+        assert_eq!(selection.len(), 3);
+        assert_eq!(selection.runs(), vec![2..3, 4..5, 9..10]);
+
+        selection.extend_to(11);
+        assert_eq!(selection.runs(), vec![3..12]);
+
+        selection.toggle(5);
+        selection.toggle(7);
+        assert_eq!(selection.runs(), vec![3..5, 6..7, 8..12]);
+        assert!(!selection.contains(5) && selection.contains(6));
+        selection.toggle(5);
+        assert_eq!(selection.runs(), vec![3..7, 8..12]);
+        assert_eq!(selection.len(), 8);
+    }
+
+    #[test]
+    fn selecting_a_huge_range_stays_one_run() {
+        let mut selection = SelectedRows::default();
+        selection.select(0);
+        selection.extend_to(usize::MAX - 1);
+
+        assert_eq!(selection.len(), usize::MAX);
+        assert!(selection.contains(1_000_000_000));
+        assert_eq!(selection.runs().len(), 1);
+    }
+
+    #[test]
+    fn delimited_output_quotes_fields_that_need_it() {
+        let header = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            vec!["1".to_string(), "plain".to_string()],
+            vec!["2".to_string(), "a,b \"c\"".to_string()],
+        ];
+
+        assert_eq!(
+            to_delimited(&header, &rows, ','),
+            "id,note\n1,plain\n2,\"a,b \"\"c\"\"\"\n"
+        );
+        assert_eq!(
+            to_delimited(&header, &rows, '\t'),
+            "id\tnote\n1\tplain\n2\t\"a,b \"\"c\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn clipboard_formats_escape_embedded_line_breaks() {
+        let header = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![vec!["1".to_string(), "two\nlines\t\"quoted\"".to_string()]];
+
+        assert_eq!(
+            ClipboardFormat::Tsv.format(&header, &rows),
+            "id\tnote\n1\t\"two\nlines\t\"\"quoted\"\"\"\n"
+        );
+        assert_eq!(
+            ClipboardFormat::Csv.format(&header, &rows),
+            "id,note\n1,\"two\nlines\t\"\"quoted\"\"\"\n"
+        );
+        assert_eq!(
+            ClipboardFormat::Json.format(&header, &rows),
+            "[\n  {\n    \"id\": \"1\",\n    \"note\": \"two\\nlines\\t\\\"quoted\\\"\"\n  }\n]\n"
+        );
+        assert_eq!(
+            ClipboardFormat::Json.format(&header, &Vec::<Vec<String>>::new()),
+            "[]\n"
+        );
+    }
+
+    #[test]
+    fn markdown_tables_are_padded_and_escaped() {
+        let header = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            vec!["1".to_string(), "a | b".to_string()],
+            vec!["22".to_string(), "two\nlines".to_string()],
+        ];
+
+        assert_eq!(
+            to_markdown(&header, &rows),
+            "| id  | note         |\n\
+             | --- | ------------ |\n\
+             | 1   | a \\| b       |\n\
+             | 22  | two<br>lines |\n"
+        );
+
+        // Wide characters take two columns each.
+        let rows = vec![vec!["1".to_string(), "日本".to_string()]];
+        assert_eq!(
+            to_markdown(&header, &rows),
+            "| id  | note |\n\
+             | --- | ---- |\n\
+             | 1   | 日本 |\n"
+        );
+    }
+}
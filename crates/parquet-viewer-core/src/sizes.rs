@@ -0,0 +1,241 @@
+use std::cmp::Reverse;
+use std::fs::File;
+use std::path::Path;
+
+use parquet::file::metadata::ParquetMetaDataReader;
+
+use crate::metrics::format_bytes;
+use crate::ViewerError;
+
+/// Bytes taken by one column or row group, as stored and once decompressed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizeEntry {
+    pub label: String,
+    pub compressed: u64,
+    pub uncompressed: u64,
+}
+
+impl SizeEntry {
+    /// Fraction of `total` compressed bytes taken by this entry.
+    pub fn share(&self, total: u64) -> f64 {
+        if total == 0 {
+            0.0
+        } else {
+            self.compressed as f64 / total as f64
+        }
+    }
+
+    /// Uncompressed bytes per compressed byte.
+    pub fn ratio(&self) -> f64 {
+        ratio(self.compressed, self.uncompressed)
+    }
+}
+
+/// One column chunk's sizes and the settings that decide how well it compresses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkSize {
+    pub column: String,
+    pub row_group: usize,
+    pub codec: String,
+    pub dictionary: bool,
+    pub compressed: u64,
+    pub uncompressed: u64,
+}
+
+impl ChunkSize {
+    /// Uncompressed bytes per compressed byte.
+    pub fn ratio(&self) -> f64 {
+        ratio(self.compressed, self.uncompressed)
+    }
+
+    /// Whether the codec saves too little for its decompression cost to be worth
+    /// it, or the chunk is stored uncompressed.
+    pub fn barely_compressed(&self) -> bool {
+        self.ratio() < POOR_RATIO
+    }
+}
+
+/// Ratios below this suggest trying another codec or dictionary encoding.
+const POOR_RATIO: f64 = 1.1;
+
+fn ratio(compressed: u64, uncompressed: u64) -> f64 {
+    if compressed == 0 {
+        1.0
+    } else {
+        uncompressed as f64 / compressed as f64
+    }
+}
+
+/// Where a file's bytes go, from the column chunk sizes in the footer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SizeBreakdown {
+    pub compressed: u64,
+    pub uncompressed: u64,
+    /// Leaf columns by dotted path, largest first.
+    pub columns: Vec<SizeEntry>,
+    /// Row groups in file order.
+    pub row_groups: Vec<SizeEntry>,
+    /// Column chunks in file order.
+    pub chunks: Vec<ChunkSize>,
+}
+
+impl SizeBreakdown {
+    /// Uncompressed bytes per compressed byte over the whole file.
+    pub fn ratio(&self) -> f64 {
+        ratio(self.compressed, self.uncompressed)
+    }
+
+    /// Whole-file compression summary, e.g. `1.2 MiB compressed from 4.8 MiB
+    /// (4.00x); 2 of 12 column chunks compress less than 1.1x`.
+    pub fn summary(&self) -> String {
+        let poor = self
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.barely_compressed())
+            .count();
+        format!(
+            "{} compressed from {} ({:.2}x); {poor} of {} column chunks compress less than {POOR_RATIO}x",
+            format_bytes(self.compressed),
+            format_bytes(self.uncompressed),
+            self.ratio(),
+            self.chunks.len()
+        )
+    }
+}
+
+pub fn size_breakdown(path: &Path) -> Result<SizeBreakdown, ViewerError> {
+    let metadata = ParquetMetaDataReader::new().parse_and_finish(&File::open(path)?)?;
+    let schema = metadata.file_metadata().schema_descr();
+
+    let mut breakdown = SizeBreakdown {
+        columns: (0..schema.num_columns())
+            .map(|index| SizeEntry {
+                label: schema.column(index).path().string(),
+                compressed: 0,
+                uncompressed: 0,
+            })
+            .collect(),
+        ..SizeBreakdown::default()
+    };
+    for (index, row_group) in metadata.row_groups().iter().enumerate() {
+        let mut group = SizeEntry {
+            label: format!("Row group {index} ({} rows)", row_group.num_rows()),
+            compressed: 0,
+            uncompressed: 0,
+        };
+        for (column, chunk) in row_group.columns().iter().enumerate() {
+            let compressed = chunk.compressed_size().max(0) as u64;
+            let uncompressed = chunk.uncompressed_size().max(0) as u64;
+            breakdown.columns[column].compressed += compressed;
+            breakdown.columns[column].uncompressed += uncompressed;
+            group.compressed += compressed;
+            group.uncompressed += uncompressed;
+            breakdown.chunks.push(ChunkSize {
+                column: chunk.column_path().string(),
+                row_group: index,
+                codec: chunk.compression().to_string(),
+                dictionary: chunk.dictionary_page_offset().is_some(),
+                compressed,
+                uncompressed,
+            });
+        }
+        breakdown.compressed += group.compressed;
+        breakdown.uncompressed += group.uncompressed;
+        breakdown.row_groups.push(group);
+    }
+    breakdown
+        .columns
+        .sort_by_key(|column| Reverse(column.compressed));
+
+    Ok(breakdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn sizes_add_up_per_column_and_row_group() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("text", DataType::Utf8, false),
+        ]));
+        let text: Vec<String> = (0..100).map(|v| format!("{v:0>200}")).collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from(text)),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(40)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let breakdown = size_breakdown(file.path()).expect("sizes should load");
+
+        assert_eq!(breakdown.columns[0].label, "text", "largest column first");
+        assert_eq!(breakdown.row_groups.len(), 3);
+        assert_eq!(breakdown.row_groups[2].label, "Row group 2 (20 rows)");
+        let column_total: u64 = breakdown.columns.iter().map(|c| c.compressed).sum();
+        let group_total: u64 = breakdown.row_groups.iter().map(|g| g.compressed).sum();
+        assert_eq!(column_total, breakdown.compressed);
+        assert_eq!(group_total, breakdown.compressed);
+        assert!(breakdown.columns[0].share(breakdown.compressed) > 0.5);
+    }
+
+    #[test]
+    fn ratios_are_reported_per_column_chunk() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("text", DataType::Utf8, false),
+        ]));
+        let text: Vec<String> = (0..100).map(|v| format!("{v:0>200}")).collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from(text)),
+            ],
+        )
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_column_compression("text".into(), Compression::SNAPPY)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let breakdown = size_breakdown(file.path()).expect("sizes should load");
+
+        assert_eq!(breakdown.chunks.len(), 2);
+        let id = &breakdown.chunks[0];
+        assert_eq!(
+            (id.column.as_str(), id.codec.as_str()),
+            ("id", "UNCOMPRESSED")
+        );
+        assert!(id.barely_compressed());
+        let text = &breakdown.chunks[1];
+        assert!(text.codec.starts_with("SNAPPY"));
+        assert!(text.ratio() > 2.0, "repetitive text compresses well");
+        assert!(breakdown.ratio() > 1.0);
+        assert!(breakdown
+            .summary()
+            .ends_with("1 of 2 column chunks compress less than 1.1x"));
+    }
+}
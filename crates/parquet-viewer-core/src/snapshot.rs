@@ -0,0 +1,60 @@
+use sha2::{Digest, Sha256};
+
+use crate::preview::DataPreview;
+use crate::selection::delimited_record;
+
+/// The previewed head of a file as canonical text: a line per column with its
+/// name and Arrow type, then a tab-separated line per row of rendered cells.
+///
+/// It depends only on the file's schema and values, not on how the file was
+/// written, so two files with the same data give the same snapshot.
+pub fn snapshot_text(preview: &DataPreview) -> String {
+    let mut text = String::new();
+    for (name, data_type) in preview.columns.iter().zip(&preview.data_types) {
+        text.push_str(&format!("{name}: {data_type}\n"));
+    }
+    text.push('\n');
+    for row in &preview.rows {
+        text.push_str(&delimited_record(row, '\t'));
+        text.push('\n');
+    }
+    text
+}
+
+/// SHA-256 of [`snapshot_text`], in hex, for asserting that a pipeline's
+/// output is unchanged without keeping the file itself.
+pub fn snapshot_digest(preview: &DataPreview) -> String {
+    Sha256::digest(snapshot_text(preview).as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::load_preview;
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn snapshots_follow_the_values_not_the_layout() {
+        let one = write_test_parquet_with_row_groups(4, 4).expect("parquet write should succeed");
+        let split = write_test_parquet_with_row_groups(4, 1).expect("parquet write should succeed");
+        let longer =
+            write_test_parquet_with_row_groups(5, 5).expect("parquet write should succeed");
+        let preview = |file: &tempfile::NamedTempFile, rows| {
+            load_preview(&file.path().to_path_buf(), rows, false).expect("preview should load")
+        };
+
+        assert_eq!(
+            snapshot_text(&preview(&one, 2)),
+            "id: Int32\nname: Utf8\n\n0\tname-0\n1\tname-1\n"
+        );
+        let digest = snapshot_digest(&preview(&one, 10));
+        assert_eq!(digest.len(), 64);
+        assert_eq!(snapshot_digest(&preview(&split, 10)), digest);
+        assert_ne!(snapshot_digest(&preview(&longer, 10)), digest);
+        assert_eq!(snapshot_digest(&preview(&longer, 4)), digest);
+    }
+}
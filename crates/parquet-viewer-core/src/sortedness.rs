@@ -0,0 +1,165 @@
+use std::fmt;
+use std::path::Path;
+
+use arrow::array::{Array, ArrayRef};
+use arrow::compute::bool_and;
+use arrow::compute::kernels::cmp::{gt_eq, lt, lt_eq};
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::file::metadata::RowGroupMetaData;
+
+use crate::footer;
+use crate::metrics::ReadCounter;
+use crate::ViewerError;
+
+/// The order a column's rows are in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sortedness {
+    Ascending,
+    Descending,
+}
+
+impl fmt::Display for Sortedness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sortedness::Ascending => f.write_str("sorted ↑"),
+            Sortedness::Descending => f.write_str("sorted ↓"),
+        }
+    }
+}
+
+/// The order of each top-level column of the file at `path`, or `None` for
+/// columns that aren't sorted, by file column index.
+///
+/// A column is sorted when every row group declares it as its first sorting
+/// column, or when the min/max statistics of two or more row groups follow one
+/// another without overlapping. Statistics can't show the order of the rows
+/// within a row group, so searches relying on the order check the rows they read.
+pub fn sorted_columns(
+    path: &Path,
+    bytes_read: &ReadCounter,
+) -> Result<Vec<Option<Sortedness>>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let parquet = metadata.metadata();
+    let descriptor = parquet.file_metadata().schema_descr();
+    let row_groups = parquet.row_groups();
+
+    let mut sorted = Vec::with_capacity(metadata.schema().fields().len());
+    for (root, field) in metadata.schema().fields().iter().enumerate() {
+        // Only flat columns have a single chunk whose statistics cover them.
+        let mut leaves = (0..descriptor.num_columns())
+            .filter(|&leaf| descriptor.get_column_root_idx(leaf) == root);
+        let leaf = match (leaves.next(), leaves.next()) {
+            (Some(leaf), None) => leaf,
+            _ => {
+                sorted.push(None);
+                continue;
+            }
+        };
+        let order = match declared_order(row_groups, leaf) {
+            Some(order) => Some(order),
+            None => StatisticsConverter::try_new(field.name(), metadata.schema(), descriptor)
+                .ok()
+                .and_then(|converter| {
+                    let mins = converter.row_group_mins(row_groups).ok()?;
+                    let maxes = converter.row_group_maxes(row_groups).ok()?;
+                    statistics_order(&mins, &maxes)
+                }),
+        };
+        sorted.push(order);
+    }
+    Ok(sorted)
+}
+
+/// The order every row group's first sorting column gives leaf column `leaf`.
+fn declared_order(row_groups: &[RowGroupMetaData], leaf: usize) -> Option<Sortedness> {
+    let mut orders = row_groups.iter().map(|row_group| {
+        let first = row_group.sorting_columns()?.first()?;
+        (first.column_idx as usize == leaf).then_some(if first.descending {
+            Sortedness::Descending
+        } else {
+            Sortedness::Ascending
+        })
+    });
+    let order = orders.next()??;
+    orders.all(|other| other == Some(order)).then_some(order)
+}
+
+/// The order of row groups whose statistics are `mins` and `maxes`, if each one
+/// starts where the one before it ends.
+fn statistics_order(mins: &ArrayRef, maxes: &ArrayRef) -> Option<Sortedness> {
+    let count = mins.len();
+    if count < 2 || mins.null_count() > 0 || maxes.null_count() > 0 {
+        return None;
+    }
+    let holds =
+        |result: Result<_, _>| result.ok().and_then(|values| bool_and(&values)) == Some(true);
+    let earlier = |values: &ArrayRef| values.slice(0, count - 1);
+    let later = |values: &ArrayRef| values.slice(1, count - 1);
+    let (first_min, first_max) = (mins.slice(0, 1), maxes.slice(0, 1));
+    let (last_min, last_max) = (mins.slice(count - 1, 1), maxes.slice(count - 1, 1));
+
+    // A column holding one value throughout is left unmarked.
+    if holds(lt_eq(&earlier(maxes), &later(mins))) && holds(lt(&first_min, &last_max)) {
+        Some(Sortedness::Ascending)
+    } else if holds(gt_eq(&earlier(mins), &later(maxes))) && holds(lt(&last_min, &first_max)) {
+        Some(Sortedness::Descending)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn row_groups_in_order_mark_a_column_sorted() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("countdown", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("constant", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..9)),
+                Arc::new(Int64Array::from_iter_values((0..9).rev())),
+                Arc::new(StringArray::from_iter_values(
+                    ["c", "a", "b"].iter().cycle().take(9),
+                )),
+                Arc::new(Int64Array::from(vec![1; 9])),
+            ],
+        )
+        .expect("record batch should build");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(3)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let sorted =
+            sorted_columns(file.path(), &ReadCounter::default()).expect("footer should load");
+
+        assert_eq!(
+            sorted,
+            vec![
+                Some(Sortedness::Ascending),
+                Some(Sortedness::Descending),
+                None,
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::fs::File;
+use std::future::Future;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use arrow::array::{new_null_array, ArrayRef};
+use arrow::datatypes::{Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future::BoxFuture;
+use futures::{FutureExt, TryStreamExt};
+use memmap2::Mmap;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, RowSelection, RowSelector};
+use parquet::arrow::async_reader::AsyncFileReader;
+use parquet::arrow::{ParquetRecordBatchStreamBuilder, ProjectionMask};
+use tokio::runtime::Runtime;
+
+use crate::batching;
+use crate::cancel::CancellationToken;
+use crate::footer;
+use crate::metrics::{CountingAsyncFile, CountingBytes, ReadCounter};
+use crate::utf8;
+use crate::ViewerError;
+
+/// Threads that drive file I/O; decoding of large ranges is spread over rayon instead.
+const IO_THREADS: usize = 2;
+
+/// Where a file's bytes come from.
+///
+/// Every read of row data goes through [`read_chunk`], so a new kind of source,
+/// such as HTTP or an object store, only has to hand out an [`AsyncFileReader`]
+/// and the footer it parsed once.
+pub trait DataSource: Send + Sync {
+    fn open(&self) -> BoxFuture<'_, Result<Box<dyn AsyncFileReader>, ViewerError>>;
+
+    /// The file's footer, shared by every read so that none parses it again.
+    fn metadata(&self) -> &ArrowReaderMetadata;
+}
+
+/// A Parquet file on local disk, counting what is read into the status bar metrics.
+pub struct LocalFile {
+    path: PathBuf,
+    bytes_read: ReadCounter,
+    metadata: ArrowReaderMetadata,
+}
+
+impl LocalFile {
+    pub fn open(path: impl Into<PathBuf>, bytes_read: &ReadCounter) -> Result<Self, ViewerError> {
+        let path = path.into();
+        Ok(Self {
+            metadata: footer::reader_metadata(&path, bytes_read)?,
+            path,
+            bytes_read: bytes_read.clone(),
+        })
+    }
+}
+
+impl DataSource for LocalFile {
+    fn open(&self) -> BoxFuture<'_, Result<Box<dyn AsyncFileReader>, ViewerError>> {
+        async move {
+            let file = CountingAsyncFile::open(&self.path, &self.bytes_read).await?;
+            Ok(Box::new(file) as Box<dyn AsyncFileReader>)
+        }
+        .boxed()
+    }
+
+    fn metadata(&self) -> &ArrowReaderMetadata {
+        &self.metadata
+    }
+}
+
+/// A local Parquet file mapped into memory once, so that reads after the first
+/// don't open the file or make a syscall per range.
+///
+/// The file must not be truncated while it is open; the map would then point
+/// past its end.
+pub struct MappedFile {
+    bytes: Bytes,
+    bytes_read: ReadCounter,
+    metadata: ArrowReaderMetadata,
+}
+
+impl MappedFile {
+    pub fn open(path: &Path, bytes_read: &ReadCounter) -> Result<Self, ViewerError> {
+        let file = File::open(path)?;
+        // SAFETY: the map is only read, and the viewer doesn't write to files it shows.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self {
+            bytes: Bytes::from_owner(map),
+            bytes_read: bytes_read.clone(),
+            metadata: footer::reader_metadata(path, bytes_read)?,
+        })
+    }
+}
+
+impl DataSource for MappedFile {
+    fn open(&self) -> BoxFuture<'_, Result<Box<dyn AsyncFileReader>, ViewerError>> {
+        let reader = CountingBytes::new(self.bytes.clone(), &self.bytes_read);
+        async move { Ok(Box::new(reader) as Box<dyn AsyncFileReader>) }.boxed()
+    }
+
+    fn metadata(&self) -> &ArrowReaderMetadata {
+        &self.metadata
+    }
+}
+
+/// Contiguous rows of one row group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkRead {
+    pub row_group: usize,
+    /// Rows of the row group skipped before `rows` starts.
+    pub offset: usize,
+    /// The rows read, as file row indices.
+    pub rows: Range<usize>,
+    /// Top-level columns to decode; the others read as nulls. `None` decodes them all.
+    pub columns: Option<Vec<usize>>,
+}
+
+/// Decode one chunk through the async stream reader; with `--lossy-utf8`, string
+/// columns are read as bytes, see [`utf8::lossy_reader_metadata`].
+pub async fn read_chunk(
+    source: &dyn DataSource,
+    chunk: &ChunkRead,
+) -> Result<Vec<RecordBatch>, ViewerError> {
+    let limit = chunk.rows.len();
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let selection = RowSelection::from(vec![
+        RowSelector::skip(chunk.offset),
+        RowSelector::select(limit),
+    ]);
+    let reader = source.open().await?;
+    let metadata = source.metadata().clone();
+    let metadata = if utf8::lossy_utf8() {
+        utf8::lossy_reader_metadata(&metadata)?.unwrap_or(metadata)
+    } else {
+        metadata
+    };
+    let batch_size = batching::batch_rows(
+        metadata.metadata().row_group(chunk.row_group),
+        chunk.columns.as_deref(),
+    );
+    let builder = ParquetRecordBatchStreamBuilder::new_with_metadata(reader, metadata);
+    // Decoded batches drop the footer's key-value metadata, which cell
+    // renderers use to recognize columns such as GeoParquet geometries.
+    let schema = builder.schema().clone();
+    let mut builder = builder
+        .with_row_groups(vec![chunk.row_group])
+        .with_row_selection(selection)
+        .with_batch_size(batch_size.min(limit));
+    if let Some(columns) = &chunk.columns {
+        let mask = ProjectionMask::roots(builder.parquet_schema(), columns.iter().copied());
+        builder = builder.with_projection(mask);
+    }
+    let stream = builder.build()?;
+
+    let batches: Vec<RecordBatch> = stream.try_collect().await?;
+    batches
+        .into_iter()
+        .map(|batch| match &chunk.columns {
+            Some(columns) => with_null_columns(&batch, &schema, columns),
+            None => Ok(batch.with_schema(schema.clone())?),
+        })
+        .collect()
+}
+
+/// `batch`, holding only the top-level `columns` of `schema`, widened back to
+/// all of them with the others null, so that column indices stay those of the file.
+fn with_null_columns(
+    batch: &RecordBatch,
+    schema: &SchemaRef,
+    columns: &[usize],
+) -> Result<RecordBatch, ViewerError> {
+    let mut read = batch.columns().iter();
+    let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let array = columns
+                .contains(&index)
+                .then(|| read.next().cloned())
+                .flatten();
+            match array {
+                Some(array) => (field.as_ref().clone(), array),
+                None => (
+                    field.as_ref().clone().with_nullable(true),
+                    new_null_array(field.data_type(), batch.num_rows()),
+                ),
+            }
+        })
+        .unzip();
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    Ok(RecordBatch::try_new(Arc::new(schema), arrays)?)
+}
+
+/// Read `chunks` in the background, sending each one's batches as soon as it is
+/// decoded, in order.
+///
+/// Cancelling `token` abandons the read in flight and the ones after it, as
+/// does dropping the receiver once the current read finishes.
+pub fn spawn_reads(
+    source: Arc<dyn DataSource>,
+    chunks: Vec<ChunkRead>,
+    token: CancellationToken,
+) -> mpsc::UnboundedReceiver<(ChunkRead, Result<Vec<RecordBatch>, ViewerError>)> {
+    let (sender, receiver) = mpsc::unbounded();
+    runtime().spawn(async move {
+        for chunk in chunks {
+            let Ok(result) = token.run(read_chunk(source.as_ref(), &chunk)).await else {
+                break;
+            };
+            if sender.unbounded_send((chunk, result)).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Run a read to completion for callers that need the rows right away, such as
+/// the CLI and export. Must not be called from an I/O thread.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    runtime().block_on(future)
+}
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(IO_THREADS)
+            .thread_name("parquet-io")
+            .enable_io()
+            .build()
+            .expect("the I/O runtime should start")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use futures::StreamExt;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn chunks_stream_back_in_order() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..10))],
+        )
+        .expect("record batch should build");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(4)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let bytes_read = ReadCounter::default();
+        let chunks = vec![
+            ChunkRead {
+                row_group: 2,
+                offset: 1,
+                rows: 9..10,
+                columns: None,
+            },
+            ChunkRead {
+                row_group: 0,
+                offset: 2,
+                rows: 2..4,
+                columns: None,
+            },
+        ];
+        let received: Vec<_> = block_on(
+            spawn_reads(
+                Arc::new(LocalFile::open(file.path(), &bytes_read).expect("footer should load")),
+                chunks,
+                CancellationToken::default(),
+            )
+            .collect(),
+        );
+
+        let ids: Vec<Vec<i32>> = received
+            .into_iter()
+            .map(|(_, batches)| {
+                batches
+                    .expect("chunk should decode")
+                    .iter()
+                    .flat_map(|batch| {
+                        let ids = batch.column(0).as_any().downcast_ref::<Int32Array>();
+                        ids.expect("ids are Int32").values().to_vec()
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(ids, vec![vec![9], vec![2, 3]]);
+        assert!(bytes_read.bytes() > 0);
+    }
+}
@@ -0,0 +1,351 @@
+use std::fs::File;
+use std::io::{BufReader, Seek};
+use std::mem::size_of;
+use std::sync::{Arc, OnceLock};
+
+use arrow::array::{Array, ArrayRef, UInt64Array};
+use arrow::compute::{concat, lexsort_to_indices, take, SortColumn, SortOptions};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, Rows, SortField};
+
+use crate::ViewerError;
+
+/// Rows per batch written to a spill file, and so read back per run while merging.
+const SPILL_BATCH_ROWS: usize = 8192;
+
+static MEMORY_LIMIT: OnceLock<usize> = OnceLock::new();
+
+/// Set the process-wide budget, in bytes, for decoded data held by one operation.
+pub fn init_memory_limit(limit: Option<usize>) {
+    if let Some(limit) = limit {
+        let _ = MEMORY_LIMIT.set(limit);
+    }
+}
+
+/// The budget set with `--memory-limit`; `None` leaves memory use unbounded.
+pub fn memory_limit() -> Option<usize> {
+    MEMORY_LIMIT.get().copied()
+}
+
+/// Parse a size such as `512MiB`, `2G` or `1048576`; units are powers of 1024.
+pub fn parse_size(input: &str) -> Result<usize, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (digits, unit) = input.split_at(split);
+    let number: usize = digits
+        .parse()
+        .map_err(|_| format!("\"{input}\" is not a size, e.g. 512MiB"))?;
+    let shift = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        "t" | "tb" | "tib" => 40,
+        _ => return Err(format!("unknown size unit in \"{input}\"")),
+    };
+    number
+        .checked_mul(1 << shift)
+        .ok_or_else(|| format!("\"{input}\" is too large"))
+}
+
+/// Sorts file rows by one column's values, writing sorted runs to temporary
+/// files whenever the buffered values outgrow the memory limit and merging the
+/// runs at the end.
+///
+/// Only the sort values are spilled; the returned row order is held in memory.
+pub struct SpillingSort {
+    options: SortOptions,
+    limit: Option<usize>,
+    rows: Vec<u64>,
+    values: Vec<ArrayRef>,
+    buffered: usize,
+    runs: Vec<File>,
+    /// Type of the values, known once the first ones are pushed.
+    data_type: Option<DataType>,
+}
+
+impl SpillingSort {
+    pub fn new(options: SortOptions, limit: Option<usize>) -> Self {
+        Self {
+            options,
+            limit,
+            rows: Vec::new(),
+            values: Vec::new(),
+            buffered: 0,
+            runs: Vec::new(),
+            data_type: None,
+        }
+    }
+
+    /// Add file `rows` with their sort `values`, in file order.
+    pub fn push(&mut self, rows: &[usize], values: ArrayRef) -> Result<(), ViewerError> {
+        self.buffered += values.get_array_memory_size() + rows.len() * size_of::<u64>();
+        self.rows.extend(rows.iter().map(|&row| row as u64));
+        self.data_type = Some(values.data_type().clone());
+        self.values.push(values);
+
+        if self.limit.is_some_and(|limit| self.buffered > limit) {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Number of runs written to disk so far.
+    pub fn spilled_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// File rows ordered by their values; ties keep file order across runs.
+    pub fn finish(mut self) -> Result<Vec<usize>, ViewerError> {
+        if self.runs.is_empty() {
+            let Some(sorted) = self.sort_buffered()? else {
+                return Ok(Vec::new());
+            };
+            return Ok(row_numbers(&sorted)
+                .values()
+                .iter()
+                .map(|&row| row as usize)
+                .collect());
+        }
+
+        self.spill()?;
+        let data_type = self.data_type.clone().expect("spilled runs hold values");
+        let converter =
+            RowConverter::new(vec![SortField::new_with_options(data_type, self.options)])?;
+        let mut cursors = std::mem::take(&mut self.runs)
+            .into_iter()
+            .map(|file| RunCursor::open(file, &converter))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut order = Vec::new();
+        loop {
+            let mut smallest: Option<usize> = None;
+            for (index, cursor) in cursors.iter().enumerate() {
+                if cursor.is_done() {
+                    continue;
+                }
+                if smallest.is_none_or(|smallest| cursor.key() < cursors[smallest].key()) {
+                    smallest = Some(index);
+                }
+            }
+            let Some(smallest) = smallest else {
+                return Ok(order);
+            };
+            order.push(cursors[smallest].row());
+            cursors[smallest].advance(&converter)?;
+        }
+    }
+
+    /// Sort what is buffered into one batch of `row` and `value` columns.
+    fn sort_buffered(&mut self) -> Result<Option<RecordBatch>, ViewerError> {
+        if self.values.is_empty() {
+            return Ok(None);
+        }
+        let values = concat(&self.values.iter().map(AsRef::as_ref).collect::<Vec<_>>())?;
+        let rows = UInt64Array::from(std::mem::take(&mut self.rows));
+        self.values.clear();
+        self.buffered = 0;
+
+        // Ties are broken by file row, so that they keep file order within a run
+        // as well as across runs.
+        let indices = lexsort_to_indices(
+            &[
+                SortColumn {
+                    values: values.clone(),
+                    options: Some(self.options),
+                },
+                SortColumn {
+                    values: Arc::new(rows.clone()),
+                    options: None,
+                },
+            ],
+            None,
+        )?;
+        let schema = Schema::new(vec![
+            Field::new("row", DataType::UInt64, false),
+            Field::new("value", values.data_type().clone(), true),
+        ]);
+        Ok(Some(RecordBatch::try_new(
+            Arc::new(schema),
+            vec![take(&rows, &indices, None)?, take(&values, &indices, None)?],
+        )?))
+    }
+
+    fn spill(&mut self) -> Result<(), ViewerError> {
+        let Some(sorted) = self.sort_buffered()? else {
+            return Ok(());
+        };
+        tracing::debug!(rows = sorted.num_rows(), "spilling sorted run to disk");
+
+        let mut writer = StreamWriter::try_new_buffered(tempfile::tempfile()?, &sorted.schema())?;
+        let mut offset = 0;
+        while offset < sorted.num_rows() {
+            let length = SPILL_BATCH_ROWS.min(sorted.num_rows() - offset);
+            writer.write(&sorted.slice(offset, length))?;
+            offset += length;
+        }
+        writer.finish()?;
+        let mut file = writer
+            .into_inner()?
+            .into_inner()
+            .map_err(|error| error.into_error())?;
+        file.rewind()?;
+        self.runs.push(file);
+        Ok(())
+    }
+}
+
+fn row_numbers(batch: &RecordBatch) -> &UInt64Array {
+    batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<UInt64Array>()
+        .expect("spilled rows are UInt64")
+}
+
+/// Read position in one sorted run; only one batch of it is in memory at a time.
+struct RunCursor {
+    reader: StreamReader<BufReader<File>>,
+    rows: UInt64Array,
+    keys: Rows,
+    position: usize,
+}
+
+impl RunCursor {
+    fn open(file: File, converter: &RowConverter) -> Result<Self, ViewerError> {
+        let mut cursor = Self {
+            reader: StreamReader::try_new_buffered(file, None)?,
+            rows: UInt64Array::from(Vec::<u64>::new()),
+            keys: converter.empty_rows(0, 0),
+            position: 0,
+        };
+        cursor.load_batch(converter)?;
+        Ok(cursor)
+    }
+
+    fn is_done(&self) -> bool {
+        self.position >= self.rows.len()
+    }
+
+    fn key(&self) -> arrow::row::Row<'_> {
+        self.keys.row(self.position)
+    }
+
+    fn row(&self) -> usize {
+        self.rows.value(self.position) as usize
+    }
+
+    fn advance(&mut self, converter: &RowConverter) -> Result<(), ViewerError> {
+        self.position += 1;
+        if self.is_done() {
+            self.load_batch(converter)?;
+        }
+        Ok(())
+    }
+
+    /// Move on to the next non-empty batch, leaving the cursor done at the end of the run.
+    fn load_batch(&mut self, converter: &RowConverter) -> Result<(), ViewerError> {
+        self.position = 0;
+        self.rows = UInt64Array::from(Vec::<u64>::new());
+        for batch in self.reader.by_ref() {
+            let batch = batch?;
+            if batch.num_rows() > 0 {
+                self.keys = converter.convert_columns(&[batch.column(1).clone()])?;
+                self.rows = row_numbers(&batch).clone();
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Int32Array, StringArray};
+
+    #[test]
+    fn sizes_accept_binary_units() {
+        assert_eq!(parse_size("1048576"), Ok(1 << 20));
+        assert_eq!(parse_size("512MiB"), Ok(512 << 20));
+        assert_eq!(parse_size("2 g"), Ok(2 << 30));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("5 parsecs").is_err());
+    }
+
+    #[test]
+    fn spilled_runs_merge_into_the_in_memory_order() {
+        let groups: Vec<(Vec<usize>, ArrayRef)> = vec![
+            (
+                vec![0, 1, 2],
+                Arc::new(StringArray::from(vec![Some("m"), None, Some("c")])),
+            ),
+            (vec![3, 4], Arc::new(StringArray::from(vec!["x", "d"]))),
+            (
+                vec![5, 6, 7],
+                Arc::new(StringArray::from(vec!["a", "q", "n"])),
+            ),
+        ];
+        let options = SortOptions {
+            descending: false,
+            nulls_first: false,
+        };
+
+        let sorted = |limit| {
+            let mut sort = SpillingSort::new(options, limit);
+            for (rows, values) in &groups {
+                sort.push(rows, values.clone())
+                    .expect("push should succeed");
+            }
+            let runs = sort.spilled_runs();
+            (sort.finish().expect("sort should finish"), runs)
+        };
+
+        let (in_memory, runs) = sorted(None);
+        assert_eq!(runs, 0);
+        assert_eq!(in_memory, vec![5, 2, 4, 0, 7, 6, 3, 1]);
+        let (spilled, runs) = sorted(Some(1));
+        assert_eq!(runs, 3);
+        assert_eq!(spilled, in_memory);
+    }
+
+    #[test]
+    fn descending_runs_merge_descending() {
+        let options = SortOptions {
+            descending: true,
+            nulls_first: false,
+        };
+        let mut sort = SpillingSort::new(options, Some(1));
+        sort.push(&[0, 1], Arc::new(Int32Array::from(vec![1, 9])))
+            .unwrap();
+        sort.push(&[2, 3], Arc::new(Int32Array::from(vec![5, 7])))
+            .unwrap();
+
+        assert_eq!(sort.finish().unwrap(), vec![1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn ties_keep_file_order() {
+        let options = SortOptions {
+            descending: true,
+            nulls_first: false,
+        };
+        let sorted = |limit| {
+            let mut sort = SpillingSort::new(options, limit);
+            sort.push(&[0, 1, 2], Arc::new(Int32Array::from(vec![1, 2, 1])))
+                .unwrap();
+            sort.push(&[3, 4, 5], Arc::new(Int32Array::from(vec![2, 1, 2])))
+                .unwrap();
+            sort.finish().unwrap()
+        };
+
+        assert_eq!(sorted(None), vec![1, 3, 5, 0, 2, 4]);
+        assert_eq!(sorted(Some(1)), vec![1, 3, 5, 0, 2, 4]);
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::path::Path;
+
+use arrow::array::Array;
+use arrow::compute::{sort_to_indices, SortOptions};
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::ArrowReaderMetadata;
+
+use crate::{cell_text, ViewerError};
+
+/// Summary of one column, taken from the footer's row group statistics.
+///
+/// Nothing is decoded, so values are only as complete as the writer made them:
+/// `None` means at least one row group didn't record the statistic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnStats {
+    pub column: String,
+    pub data_type: String,
+    pub row_groups: usize,
+    pub null_count: Option<u64>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub compressed_bytes: u64,
+    pub uncompressed_bytes: u64,
+}
+
+pub fn column_stats(path: &Path, column: &str) -> Result<ColumnStats, ViewerError> {
+    let metadata = ArrowReaderMetadata::load(&File::open(path)?, Default::default())?;
+
+    // Leaf chunks under this column; nested columns span several.
+    let (compressed_bytes, uncompressed_bytes) = metadata
+        .metadata()
+        .row_groups()
+        .iter()
+        .flat_map(|group| group.columns())
+        .filter(|chunk| chunk.column_path().parts().first().map(String::as_str) == Some(column))
+        .fold((0, 0), |(compressed, uncompressed), chunk| {
+            (
+                compressed + chunk.compressed_size() as u64,
+                uncompressed + chunk.uncompressed_size() as u64,
+            )
+        });
+    stats_of(&metadata, column, (compressed_bytes, uncompressed_bytes))
+}
+
+/// [`column_stats`] of every top-level column, in schema order, from a footer
+/// that has already been read; `None` for columns the footer has no statistics
+/// for, such as nested ones. Chunk sizes are added up in a single pass, so this
+/// stays quick for files with thousands of columns.
+pub fn all_column_stats(metadata: &ArrowReaderMetadata) -> Vec<Option<ColumnStats>> {
+    let parquet = metadata.metadata();
+    let descr = parquet.file_metadata().schema_descr();
+    let mut sizes = vec![(0, 0); metadata.schema().fields().len()];
+    for group in parquet.row_groups() {
+        for (leaf, chunk) in group.columns().iter().enumerate() {
+            if let Some((compressed, uncompressed)) = sizes.get_mut(descr.get_column_root_idx(leaf))
+            {
+                *compressed += chunk.compressed_size() as u64;
+                *uncompressed += chunk.uncompressed_size() as u64;
+            }
+        }
+    }
+
+    metadata
+        .schema()
+        .fields()
+        .iter()
+        .zip(sizes)
+        .map(|(field, sizes)| stats_of(metadata, field.name(), sizes).ok())
+        .collect()
+}
+
+/// Statistics of `column` from `metadata`, given the compressed and
+/// uncompressed bytes of its chunks.
+fn stats_of(
+    metadata: &ArrowReaderMetadata,
+    column: &str,
+    (compressed_bytes, uncompressed_bytes): (u64, u64),
+) -> Result<ColumnStats, ViewerError> {
+    let parquet = metadata.metadata();
+    let converter = StatisticsConverter::try_new(
+        column,
+        metadata.schema(),
+        parquet.file_metadata().schema_descr(),
+    )?;
+    let row_groups = parquet.row_groups();
+
+    let null_counts = converter.row_group_null_counts(row_groups)?;
+    let null_count = (null_counts.null_count() == 0).then(|| null_counts.values().iter().sum());
+
+    let mins = converter.row_group_mins(row_groups)?;
+    let maxes = converter.row_group_maxes(row_groups)?;
+
+    Ok(ColumnStats {
+        column: column.to_string(),
+        data_type: converter.arrow_field().data_type().to_string(),
+        row_groups: row_groups.len(),
+        null_count,
+        min: extreme(mins.as_ref(), false)?,
+        max: extreme(maxes.as_ref(), true)?,
+        compressed_bytes,
+        uncompressed_bytes,
+    })
+}
+
+/// Smallest (or with `largest`, biggest) per-row-group value, if every row group has one.
+fn extreme(values: &dyn Array, largest: bool) -> Result<Option<String>, ViewerError> {
+    if values.is_empty() || values.null_count() > 0 {
+        return Ok(None);
+    }
+
+    let options = SortOptions {
+        descending: largest,
+        nulls_first: false,
+    };
+    let indices = sort_to_indices(values, Some(options), Some(1))?;
+    Ok(Some(cell_text(values, indices.value(0) as usize)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn stats_combine_row_groups() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "value",
+            DataType::Int64,
+            true,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(vec![
+                Some(4),
+                None,
+                Some(-2),
+                Some(10),
+                None,
+            ]))],
+        )
+        .expect("record batch should build");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let stats = column_stats(file.path(), "value").expect("stats should load");
+
+        assert_eq!(stats.row_groups, 3);
+        assert_eq!(stats.null_count, Some(2));
+        assert_eq!(stats.min, None, "the all-null row group has no minimum");
+        assert_eq!(stats.data_type, "Int64");
+        assert!(stats.compressed_bytes > 0);
+        assert!(column_stats(file.path(), "missing").is_err());
+
+        let metadata = ArrowReaderMetadata::load(&file.reopen().unwrap(), Default::default())
+            .expect("metadata should load");
+        assert_eq!(all_column_stats(&metadata), vec![Some(stats)]);
+    }
+}
@@ -0,0 +1,316 @@
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::Arc;
+
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+
+use crate::filter::{BatchFilter, ValueFilter};
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::output::{ensure_distinct, write_atomically};
+use crate::progress::Progress;
+use crate::pushdown::{plan_scan, RowGroupScan};
+use crate::render;
+use crate::selection::delimited_record;
+use crate::{batching, footer, ViewerError};
+
+/// Batches one stage of a stream may have waiting for the next. A stage that
+/// gets this far ahead blocks until the next catches up, so a stream holds a
+/// few batches at a time however big the file is.
+const STAGE_CAPACITY: usize = 4;
+
+/// What a stream read and passed on.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StreamSummary {
+    /// Rows decoded; row groups the statistics ruled out aren't counted.
+    pub rows_read: usize,
+    /// Rows that passed the filters.
+    pub rows_written: usize,
+}
+
+/// Every row of a file that passes some filters, read front to back in
+/// batches: one thread decodes, another filters and the caller's thread
+/// writes, joined by bounded channels so that the slowest stage sets the pace.
+pub struct RowStream {
+    path: PathBuf,
+    metadata: ArrowReaderMetadata,
+    scans: Vec<RowGroupScan>,
+    /// Top-level columns decoded, in file order.
+    roots: Vec<usize>,
+    /// Positions in `roots` of the columns passed on, in the order asked for.
+    output: Vec<usize>,
+    schema: SchemaRef,
+    filter: BatchFilter,
+}
+
+impl RowStream {
+    /// A stream of the top-level `columns` named, or all of them if none are,
+    /// of the rows of the file at `path` that pass `filters`. Row groups and
+    /// pages whose statistics rule the filters out are never read.
+    pub fn new(
+        path: &Path,
+        filters: &[ValueFilter],
+        columns: &[String],
+        bytes_read: &ReadCounter,
+    ) -> Result<RowStream, ViewerError> {
+        let metadata = footer::reader_metadata(path, bytes_read)?;
+        let position = |column: &str| {
+            metadata
+                .schema()
+                .fields()
+                .iter()
+                .position(|field| field.name() == column)
+                .ok_or_else(|| ViewerError::NoSuchColumn {
+                    column: column.to_string(),
+                    path: path.to_path_buf(),
+                })
+        };
+        let wanted: Vec<usize> = if columns.is_empty() {
+            (0..metadata.schema().fields().len()).collect()
+        } else {
+            columns
+                .iter()
+                .map(|column| position(column))
+                .collect::<Result<_, _>>()?
+        };
+        let mut roots = wanted.clone();
+        for value_filter in filters {
+            roots.push(position(&value_filter.column)?);
+        }
+        roots.sort_unstable();
+        roots.dedup();
+        let output = wanted
+            .iter()
+            .map(|column| roots.binary_search(column).expect("column is decoded"))
+            .collect::<Vec<_>>();
+
+        let decoded = metadata.schema().project(&roots)?;
+        Ok(RowStream {
+            path: path.to_path_buf(),
+            scans: plan_scan(&metadata, filters)?,
+            schema: Arc::new(decoded.project(&output)?),
+            filter: BatchFilter::new(filters, &decoded, path)?,
+            metadata,
+            roots,
+            output,
+        })
+    }
+
+    /// Schema of the batches passed on.
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Hand every batch of kept rows to `write`, in file order. An error from
+    /// `write` stops the stream and is returned.
+    ///
+    /// `progress` counts row groups read; cancelling it stops the stream with
+    /// [`ViewerError::Cancelled`].
+    pub fn run(
+        &self,
+        bytes_read: &ReadCounter,
+        progress: &Progress,
+        mut write: impl FnMut(&RecordBatch) -> Result<(), ViewerError>,
+    ) -> Result<StreamSummary, ViewerError> {
+        progress.set_total(self.scans.len());
+        let (decoded, to_filter) = sync_channel(STAGE_CAPACITY);
+        let (filtered, to_write) = sync_channel(STAGE_CAPACITY);
+        // Returning drops `to_write`, which stops the stages before it.
+        std::thread::scope(|scope| {
+            scope.spawn(|| self.decode(bytes_read, progress, decoded));
+            scope.spawn(|| self.filter(to_filter, filtered));
+
+            let mut summary = StreamSummary::default();
+            for batch in to_write {
+                let (rows_read, batch) = batch?;
+                progress.check()?;
+                write(&batch)?;
+                summary.rows_read += rows_read;
+                summary.rows_written += batch.num_rows();
+            }
+            Ok(summary)
+        })
+    }
+
+    fn decode(
+        &self,
+        bytes_read: &ReadCounter,
+        progress: &Progress,
+        decoded: SyncSender<Result<RecordBatch, ViewerError>>,
+    ) {
+        let schema_descr = self.metadata.metadata().file_metadata().schema_descr_ptr();
+        let send_all = || -> Result<(), ViewerError> {
+            for scan in &self.scans {
+                let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+                    CountingFile::open(&self.path, bytes_read)?,
+                    self.metadata.clone(),
+                )
+                .with_row_groups(vec![scan.row_group])
+                .with_projection(ProjectionMask::roots(&schema_descr, self.roots.clone()))
+                .with_batch_size(batching::batch_rows(
+                    self.metadata.metadata().row_group(scan.row_group),
+                    Some(&self.roots),
+                ));
+                if let Some(selection) = scan.selection.clone() {
+                    builder = builder.with_row_selection(selection);
+                }
+                for batch in builder.build()? {
+                    progress.check()?;
+                    if decoded.send(Ok(batch?)).is_err() {
+                        // The writer stopped.
+                        return Ok(());
+                    }
+                }
+                progress.advance(1);
+            }
+            Ok(())
+        };
+        if let Err(error) = send_all() {
+            let _ = decoded.send(Err(error));
+        }
+    }
+
+    /// Pass on the kept rows of each batch with the rows it had before.
+    fn filter(
+        &self,
+        to_filter: Receiver<Result<RecordBatch, ViewerError>>,
+        filtered: SyncSender<Result<(usize, RecordBatch), ViewerError>>,
+    ) {
+        for batch in to_filter {
+            let batch = batch.and_then(|batch| {
+                let kept = filter_record_batch(&batch, &self.filter.mask(&batch)?)?;
+                Ok((batch.num_rows(), kept.project(&self.output)?))
+            });
+            let failed = batch.is_err();
+            if filtered.send(batch).is_err() || failed {
+                return;
+            }
+        }
+    }
+}
+
+/// Write the rows of the file at `path` that pass `filters` to `destination`
+/// as CSV, with the top-level `columns` named or all of them, streaming so that
+/// memory stays the same however big the file is. Cells are written as the
+/// viewer shows them. A failed or cancelled export leaves no partly written
+/// file behind.
+pub fn stream_csv(
+    path: &Path,
+    filters: &[ValueFilter],
+    columns: &[String],
+    destination: &Path,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<StreamSummary, ViewerError> {
+    ensure_distinct([path], destination)?;
+    let stream = RowStream::new(path, filters, columns, bytes_read)?;
+
+    write_atomically(destination, |file| {
+        let mut output = BufWriter::new(file);
+        let header: Vec<&String> = stream
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name())
+            .collect();
+        writeln!(output, "{}", delimited_record(&header, ','))?;
+
+        let renderers = render::registry().column_renderers(stream.schema());
+        let summary = stream.run(bytes_read, progress, |batch| {
+            for row in 0..batch.num_rows() {
+                let fields = batch
+                    .columns()
+                    .iter()
+                    .enumerate()
+                    .map(|(column, values)| renderers.cell_text(column, values.as_ref(), row))
+                    .collect::<Result<Vec<_>, _>>()?;
+                writeln!(output, "{}", delimited_record(&fields, ','))?;
+            }
+            Ok(())
+        })?;
+        output.flush()?;
+        Ok(summary)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn filtered_rows_are_streamed_to_csv() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("export.csv");
+
+        let filters = [ValueFilter::parse("name !~ ^name-[23]$").unwrap()];
+        let progress = Progress::default();
+        let summary = stream_csv(
+            file.path(),
+            &filters,
+            &["name".to_string(), "id".to_string()],
+            &destination,
+            &ReadCounter::default(),
+            &progress,
+        )
+        .expect("export should succeed");
+        assert_eq!(
+            std::fs::read_to_string(&destination).unwrap(),
+            "name,id\nname-0,0\nname-1,1\nname-4,4\nname-5,5\n"
+        );
+        assert_eq!(
+            summary,
+            StreamSummary {
+                rows_read: 6,
+                rows_written: 4
+            }
+        );
+        assert_eq!(progress.done(), 3);
+
+        let missing = [ValueFilter::parse("city = Oslo").unwrap()];
+        assert!(matches!(
+            stream_csv(
+                file.path(),
+                &missing,
+                &[],
+                &destination,
+                &ReadCounter::default(),
+                &Progress::default(),
+            ),
+            Err(ViewerError::NoSuchColumn { .. })
+        ));
+
+        let cancelled = Progress::default();
+        cancelled.cancel();
+        let result = stream_csv(
+            file.path(),
+            &[],
+            &[],
+            &destination,
+            &ReadCounter::default(),
+            &cancelled,
+        );
+        assert!(matches!(result, Err(ViewerError::Cancelled)));
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn a_failing_writer_stops_the_stream() {
+        let file =
+            write_test_parquet_with_row_groups(100, 1).expect("parquet write should succeed");
+        let stream = RowStream::new(file.path(), &[], &[], &ReadCounter::default()).unwrap();
+        let mut batches = 0;
+        let result = stream.run(&ReadCounter::default(), &Progress::default(), |_| {
+            batches += 1;
+            Err(ViewerError::InvalidValue("full".to_string()))
+        });
+        assert!(matches!(result, Err(ViewerError::InvalidValue(_))));
+        assert_eq!(batches, 1);
+    }
+}
@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::path::Path;
+
+use parquet::basic::ConvertedType;
+use parquet::file::metadata::{
+    ColumnChunkMetaData, FileMetaData, ParquetMetaDataReader, RowGroupMetaData,
+};
+use parquet::file::statistics::Statistics;
+use parquet::schema::types::Type;
+use serde_json::{json, Value};
+
+use crate::dictionary::hex;
+use crate::ViewerError;
+
+/// The footer of the file at `path` as its Thrift structures, `FileMetaData`
+/// with its `RowGroup`s and `ColumnChunk`s, in pretty-printed JSON.
+///
+/// Fields keep their names from `parquet.thrift`; unset optional fields are
+/// `null` and binary values such as statistics are hex.
+pub fn footer_json(path: &Path) -> Result<String, ViewerError> {
+    let metadata = ParquetMetaDataReader::new().parse_and_finish(&File::open(path)?)?;
+    let footer = file_metadata(metadata.file_metadata(), metadata.row_groups());
+    Ok(serde_json::to_string_pretty(&footer).expect("footers serialize to JSON"))
+}
+
+fn file_metadata(file: &FileMetaData, row_groups: &[RowGroupMetaData]) -> Value {
+    let mut schema = Vec::new();
+    schema_elements(file.schema_descr().root_schema(), &mut schema);
+    json!({
+        "version": file.version(),
+        "schema": schema,
+        "num_rows": file.num_rows(),
+        "row_groups": row_groups.iter().map(row_group).collect::<Vec<_>>(),
+        "key_value_metadata": file.key_value_metadata().map(|pairs| {
+            pairs
+                .iter()
+                .map(|pair| json!({ "key": pair.key, "value": pair.value }))
+                .collect::<Vec<_>>()
+        }),
+        "created_by": file.created_by(),
+        "column_orders": file.column_orders().map(|orders| {
+            orders.iter().map(|order| format!("{order:?}")).collect::<Vec<_>>()
+        }),
+    })
+}
+
+/// Append `field` and its descendants, depth first, as the footer lists them.
+fn schema_elements(field: &Type, elements: &mut Vec<Value>) {
+    let info = field.get_basic_info();
+    let mut element = json!({
+        "name": info.name(),
+        "repetition_type": info.has_repetition().then(|| format!("{:?}", info.repetition())),
+        "converted_type": (info.converted_type() != ConvertedType::NONE)
+            .then(|| info.converted_type().to_string()),
+        "logicalType": info.logical_type_ref().map(|logical| format!("{logical:?}")),
+        "field_id": info.has_id().then(|| info.id()),
+    });
+    match field {
+        Type::PrimitiveType {
+            physical_type,
+            type_length,
+            scale,
+            precision,
+            ..
+        } => {
+            element["type"] = json!(physical_type.to_string());
+            element["type_length"] = json!(*type_length);
+            element["scale"] = json!(*scale);
+            element["precision"] = json!(*precision);
+            elements.push(element);
+        }
+        Type::GroupType { fields, .. } => {
+            element["num_children"] = json!(fields.len());
+            elements.push(element);
+            for child in fields {
+                schema_elements(child, elements);
+            }
+        }
+    }
+}
+
+fn row_group(group: &RowGroupMetaData) -> Value {
+    json!({
+        "columns": group.columns().iter().map(column_chunk).collect::<Vec<_>>(),
+        "total_byte_size": group.total_byte_size(),
+        "num_rows": group.num_rows(),
+        "sorting_columns": group.sorting_columns().map(|columns| {
+            columns
+                .iter()
+                .map(|column| {
+                    json!({
+                        "column_idx": column.column_idx,
+                        "descending": column.descending,
+                        "nulls_first": column.nulls_first,
+                    })
+                })
+                .collect::<Vec<_>>()
+        }),
+        "file_offset": group.file_offset(),
+        "total_compressed_size": group.compressed_size(),
+        "ordinal": group.ordinal(),
+    })
+}
+
+fn column_chunk(chunk: &ColumnChunkMetaData) -> Value {
+    json!({
+        "file_path": chunk.file_path(),
+        "file_offset": chunk.file_offset(),
+        "meta_data": {
+            "type": chunk.column_type().to_string(),
+            "encodings": chunk
+                .encodings()
+                .into_iter()
+                .map(|encoding| encoding.to_string())
+                .collect::<Vec<_>>(),
+            "path_in_schema": chunk.column_path().parts(),
+            "codec": chunk.compression().to_string(),
+            "num_values": chunk.num_values(),
+            "total_uncompressed_size": chunk.uncompressed_size(),
+            "total_compressed_size": chunk.compressed_size(),
+            "data_page_offset": chunk.data_page_offset(),
+            "index_page_offset": chunk.index_page_offset(),
+            "dictionary_page_offset": chunk.dictionary_page_offset(),
+            "statistics": chunk.statistics().map(statistics),
+            "encoding_stats": chunk.page_encoding_stats().map(|stats| {
+                stats
+                    .iter()
+                    .map(|stats| {
+                        json!({
+                            "page_type": format!("{:?}", stats.page_type),
+                            "encoding": stats.encoding.to_string(),
+                            "count": stats.count,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }),
+            "bloom_filter_offset": chunk.bloom_filter_offset(),
+            "bloom_filter_length": chunk.bloom_filter_length(),
+        },
+        "offset_index_offset": chunk.offset_index_offset(),
+        "offset_index_length": chunk.offset_index_length(),
+        "column_index_offset": chunk.column_index_offset(),
+        "column_index_length": chunk.column_index_length(),
+    })
+}
+
+fn statistics(statistics: &Statistics) -> Value {
+    json!({
+        "null_count": statistics.null_count_opt(),
+        "distinct_count": statistics.distinct_count_opt(),
+        "min_value": statistics.min_bytes_opt().map(hex),
+        "max_value": statistics.max_bytes_opt().map(hex),
+        "is_min_value_exact": statistics.min_is_exact(),
+        "is_max_value_exact": statistics.max_is_exact(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn the_footer_is_dumped_with_thrift_field_names() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![Some(1), None, Some(3)]))],
+        )
+        .unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let footer: Value = serde_json::from_str(&footer_json(file.path()).unwrap()).unwrap();
+        assert_eq!(footer["num_rows"], 3);
+        assert_eq!(footer["schema"][0]["num_children"], 1);
+        assert_eq!(footer["schema"][1]["name"], "id");
+        assert_eq!(footer["schema"][1]["type"], "INT32");
+        assert_eq!(footer["schema"][1]["repetition_type"], "OPTIONAL");
+        assert_eq!(footer["row_groups"].as_array().unwrap().len(), 2);
+
+        let chunk = &footer["row_groups"][0]["columns"][0]["meta_data"];
+        assert_eq!(chunk["path_in_schema"], json!(["id"]));
+        assert_eq!(chunk["num_values"], 2);
+        assert_eq!(chunk["statistics"]["null_count"], 1);
+        // 1 as a little-endian INT32.
+        assert_eq!(chunk["statistics"]["min_value"], "01000000");
+    }
+}
@@ -0,0 +1,254 @@
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Int64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Int64Type};
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+
+use crate::footer;
+use crate::metrics::{CountingFile, ReadCounter};
+use crate::{cell_text, ViewerError};
+
+/// Times of one row group, from its statistics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeSpan {
+    pub row_group: usize,
+    /// File rows of the row group.
+    pub rows: Range<usize>,
+    /// Earliest and latest time, in the column's unit.
+    pub min: i64,
+    pub max: i64,
+}
+
+/// Where the times of a file's first timestamp column fall, read from the
+/// footer's row group statistics, for the timeline scrubber.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeIndex {
+    pub column: String,
+    /// Index of the column among the file's top-level columns.
+    pub root: usize,
+    pub data_type: DataType,
+    /// Row groups with statistics, in file order; those without are left out.
+    pub spans: Vec<TimeSpan>,
+}
+
+impl TimeIndex {
+    pub fn min(&self) -> i64 {
+        self.spans.iter().map(|span| span.min).min().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> i64 {
+        self.spans.iter().map(|span| span.max).max().unwrap_or(0)
+    }
+
+    /// The time `fraction` of the way from the earliest to the latest time.
+    pub fn time_at(&self, fraction: f64) -> i64 {
+        let (min, max) = (self.min(), self.max());
+        min + ((max - min) as f64 * fraction.clamp(0.0, 1.0)) as i64
+    }
+
+    /// Rows falling in each of `buckets` equal slices of the time range. The
+    /// rows of a row group are counted as spread evenly over its span.
+    pub fn density(&self, buckets: usize) -> Vec<usize> {
+        let mut counts = vec![0; buckets];
+        if buckets == 0 {
+            return counts;
+        }
+        let (min, max) = (self.min() as f64, self.max() as f64);
+        let width = (max - min) / buckets as f64;
+        let bucket = |time: f64| {
+            if width > 0.0 {
+                (((time - min) / width) as usize).min(buckets - 1)
+            } else {
+                0
+            }
+        };
+
+        for span in &self.spans {
+            let (first, last) = (bucket(span.min as f64), bucket(span.max as f64));
+            let rows = span.rows.len();
+            let slices = last - first + 1;
+            for (index, count) in counts[first..=last].iter_mut().enumerate() {
+                // Remainders go to the first slices so that every row is counted.
+                *count += rows / slices + usize::from(index < rows % slices);
+            }
+        }
+        counts
+    }
+
+    /// `time` as the column shows it.
+    pub fn format(&self, time: i64) -> Result<String, ViewerError> {
+        let value: ArrayRef = Arc::new(Int64Array::from(vec![time]));
+        Ok(cell_text(cast(&value, &self.data_type)?.as_ref(), 0)?)
+    }
+}
+
+/// The time index of the first top-level timestamp column of the file at
+/// `path`, or `None` if it has no such column or no row group statistics for it.
+pub fn time_index(path: &Path, bytes_read: &ReadCounter) -> Result<Option<TimeIndex>, ViewerError> {
+    let metadata = footer::reader_metadata(path, bytes_read)?;
+    let Some((root, field)) = metadata
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .find(|(_, field)| matches!(field.data_type(), DataType::Timestamp(_, _)))
+    else {
+        return Ok(None);
+    };
+
+    let parquet = metadata.metadata();
+    let converter = StatisticsConverter::try_new(
+        field.name(),
+        metadata.schema(),
+        parquet.file_metadata().schema_descr(),
+    )?;
+    let row_groups = parquet.row_groups();
+    let mins = cast(&converter.row_group_mins(row_groups)?, &DataType::Int64)?;
+    let maxes = cast(&converter.row_group_maxes(row_groups)?, &DataType::Int64)?;
+    let (mins, maxes) = (
+        mins.as_primitive::<Int64Type>(),
+        maxes.as_primitive::<Int64Type>(),
+    );
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (index, row_group) in row_groups.iter().enumerate() {
+        let rows = start..start + row_group.num_rows() as usize;
+        start = rows.end;
+        if mins.is_valid(index) && maxes.is_valid(index) && !rows.is_empty() {
+            spans.push(TimeSpan {
+                row_group: index,
+                rows,
+                min: mins.value(index),
+                max: maxes.value(index),
+            });
+        }
+    }
+
+    Ok((!spans.is_empty()).then(|| TimeIndex {
+        column: field.name().clone(),
+        root,
+        data_type: field.data_type().clone(),
+        spans,
+    }))
+}
+
+/// The file row nearest to `time`. Statistics pick the row group: the first
+/// whose span holds `time`, else the one closest to it. Only the time column
+/// of that row group is read, and searched with a binary search when its rows
+/// are in time order, or row by row when they aren't.
+pub fn row_near(
+    path: &Path,
+    index: &TimeIndex,
+    time: i64,
+    bytes_read: &ReadCounter,
+) -> Result<Option<usize>, ViewerError> {
+    let distance = |span: &TimeSpan| {
+        if time < span.min {
+            span.min.abs_diff(time)
+        } else {
+            time.saturating_sub(span.max).unsigned_abs()
+        }
+    };
+    let Some(span) = index
+        .spans
+        .iter()
+        .find(|span| span.min <= time && time <= span.max)
+        .or_else(|| index.spans.iter().min_by_key(|span| distance(span)))
+    else {
+        return Ok(None);
+    };
+
+    let file = CountingFile::open(path, bytes_read)?;
+    let builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+        file,
+        footer::reader_metadata(path, bytes_read)?,
+    );
+    let projection = ProjectionMask::roots(builder.parquet_schema(), [index.root]);
+    let mut times: Vec<Option<i64>> = Vec::with_capacity(span.rows.len());
+    for batch in builder
+        .with_row_groups(vec![span.row_group])
+        .with_projection(projection)
+        .build()?
+    {
+        let values = cast(batch?.column(0), &DataType::Int64)?;
+        times.extend(values.as_primitive::<Int64Type>().iter());
+    }
+
+    let known: Vec<(usize, i64)> = times
+        .iter()
+        .enumerate()
+        .filter_map(|(row, time)| time.map(|time| (row, time)))
+        .collect();
+    let sorted = known.windows(2).all(|pair| pair[0].1 <= pair[1].1);
+    let row = if sorted {
+        let first = known.partition_point(|&(_, value)| value < time);
+        known.get(first).or(known.last()).map(|&(row, _)| row)
+    } else {
+        known
+            .iter()
+            .min_by_key(|&&(_, value)| value.abs_diff(time))
+            .map(|&(row, _)| row)
+    };
+    Ok(row.map(|row| span.rows.start + row))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Int32Array, TimestampSecondArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn times_map_to_rows_through_row_group_statistics() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let times = TimestampSecondArray::from((0..8).map(|row| row * 10).collect::<Vec<i64>>());
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("at", times.data_type().clone(), false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..8)),
+                Arc::new(times),
+            ],
+        )
+        .expect("record batch should build");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(4)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(props))
+            .expect("writer should be created");
+        writer.write(&batch).expect("batch should be written");
+        writer.close().expect("writer should close");
+
+        let bytes_read = ReadCounter::default();
+        let index = time_index(file.path(), &bytes_read)
+            .expect("footer should load")
+            .expect("the file has a timestamp column");
+        assert_eq!((index.column.as_str(), index.root), ("at", 1));
+        assert_eq!((index.min(), index.max()), (0, 70));
+        assert_eq!(index.density(2), vec![4, 4]);
+        assert_eq!(
+            index.format(index.time_at(1.0)).unwrap(),
+            "1970-01-01T00:01:10"
+        );
+
+        let row = |time| row_near(file.path(), &index, time, &bytes_read).unwrap();
+        assert_eq!(row(45), Some(5));
+        assert_eq!(row(40), Some(4));
+        assert_eq!(row(-5), Some(0));
+        assert_eq!(row(500), Some(7));
+    }
+}
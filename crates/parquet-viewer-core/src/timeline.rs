@@ -0,0 +1,244 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::Path;
+
+use arrow::array::Array;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::ArrowReaderMetadata;
+use parquet::basic::{Encoding, PageType};
+use parquet::file::metadata::ColumnChunkMetaData;
+
+use crate::{cell_text, ViewerError};
+
+/// How one row group stored a column: what the writer chose and the statistics
+/// it recorded. Nested columns combine the chunks of their leaves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkEntry {
+    pub row_group: usize,
+    pub rows: u64,
+    /// Encodings used by the chunk's pages, e.g. `PLAIN, RLE, RLE_DICTIONARY`.
+    pub encodings: Vec<String>,
+    pub codec: String,
+    pub dictionary: bool,
+    /// Whether some data pages were written without the dictionary; `None` if
+    /// the writer didn't record page encoding stats.
+    pub fallback: Option<bool>,
+    pub compressed: u64,
+    pub uncompressed: u64,
+    pub null_count: Option<u64>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+impl ChunkEntry {
+    /// Whether the writer stored this chunk differently from the `previous`
+    /// row group's: another codec or encoding, or the dictionary dropped or
+    /// fallen back from.
+    pub fn changed_from(&self, previous: &ChunkEntry) -> bool {
+        self.encodings != previous.encodings
+            || self.codec != previous.codec
+            || self.dictionary != previous.dictionary
+            || (self.fallback == Some(true) && previous.fallback != Some(true))
+    }
+}
+
+/// One column's chunk in every row group, in file order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnTimeline {
+    pub column: String,
+    pub chunks: Vec<ChunkEntry>,
+}
+
+impl ColumnTimeline {
+    /// For each row group, whether it stored the column differently from the one before.
+    pub fn changes(&self) -> Vec<bool> {
+        std::iter::once(false)
+            .chain(
+                self.chunks
+                    .windows(2)
+                    .map(|pair| pair[1].changed_from(&pair[0])),
+            )
+            .take(self.chunks.len())
+            .collect()
+    }
+
+    /// One line on where the storage changes, e.g. `12 row groups; stored
+    /// differently from row group 4, 9`, counting row groups from one.
+    pub fn summary(&self) -> String {
+        let changed: Vec<String> = self
+            .changes()
+            .iter()
+            .enumerate()
+            .filter(|(_, &changed)| changed)
+            .map(|(row_group, _)| (row_group + 1).to_string())
+            .collect();
+        if changed.is_empty() {
+            format!("{} row group(s), all stored alike", self.chunks.len())
+        } else {
+            format!(
+                "{} row group(s); stored differently from row group {}",
+                self.chunks.len(),
+                changed.join(", ")
+            )
+        }
+    }
+}
+
+/// Read the encodings, codec, sizes and statistics of `column`, a top-level
+/// column, in each row group from the footer.
+pub fn column_timeline(path: &Path, column: &str) -> Result<ColumnTimeline, ViewerError> {
+    let metadata = ArrowReaderMetadata::load(&File::open(path)?, Default::default())?;
+    let parquet = metadata.metadata();
+    let row_groups = parquet.row_groups();
+
+    // Only leaf columns have statistics, so nested columns go without.
+    let statistics = match StatisticsConverter::try_new(
+        column,
+        metadata.schema(),
+        parquet.file_metadata().schema_descr(),
+    ) {
+        Ok(converter) if converter.parquet_column_index().is_some() => Some((
+            converter.row_group_null_counts(row_groups)?,
+            converter.row_group_mins(row_groups)?,
+            converter.row_group_maxes(row_groups)?,
+        )),
+        _ => None,
+    };
+
+    let mut chunks = Vec::with_capacity(row_groups.len());
+    for (row_group, group) in row_groups.iter().enumerate() {
+        let leaves: Vec<&ColumnChunkMetaData> = group
+            .columns()
+            .iter()
+            .filter(|chunk| chunk.column_path().parts().first().map(String::as_str) == Some(column))
+            .collect();
+        if leaves.is_empty() {
+            return Err(ViewerError::NoColumnChunk {
+                column: column.to_string(),
+                row_group,
+            });
+        }
+
+        let encodings: BTreeSet<String> = leaves
+            .iter()
+            .flat_map(|chunk| chunk.encodings())
+            .map(|encoding| format!("{encoding:?}"))
+            .collect();
+        let codecs: BTreeSet<String> = leaves
+            .iter()
+            .map(|chunk| chunk.compression().to_string())
+            .collect();
+        let fallback = leaves
+            .iter()
+            .map(|chunk| fell_back(chunk))
+            .try_fold(false, |any, fell_back| Some(any || fell_back?));
+        let (null_count, min, max) = match &statistics {
+            Some((null_counts, mins, maxes)) => (
+                null_counts
+                    .is_valid(row_group)
+                    .then(|| null_counts.value(row_group)),
+                mins.is_valid(row_group)
+                    .then(|| cell_text(mins.as_ref(), row_group))
+                    .transpose()?,
+                maxes
+                    .is_valid(row_group)
+                    .then(|| cell_text(maxes.as_ref(), row_group))
+                    .transpose()?,
+            ),
+            None => (None, None, None),
+        };
+
+        chunks.push(ChunkEntry {
+            row_group,
+            rows: group.num_rows().max(0) as u64,
+            encodings: encodings.into_iter().collect(),
+            codec: codecs.into_iter().collect::<Vec<_>>().join(", "),
+            dictionary: leaves
+                .iter()
+                .any(|chunk| chunk.dictionary_page_offset().is_some()),
+            fallback,
+            compressed: leaves
+                .iter()
+                .map(|chunk| chunk.compressed_size().max(0) as u64)
+                .sum(),
+            uncompressed: leaves
+                .iter()
+                .map(|chunk| chunk.uncompressed_size().max(0) as u64)
+                .sum(),
+            null_count,
+            min,
+            max,
+        });
+    }
+
+    Ok(ColumnTimeline {
+        column: column.to_string(),
+        chunks,
+    })
+}
+
+/// Whether a dictionary-encoded chunk has data pages that don't use the dictionary.
+fn fell_back(chunk: &ColumnChunkMetaData) -> Option<bool> {
+    if chunk.dictionary_page_offset().is_none() {
+        return Some(false);
+    }
+    let stats = chunk.page_encoding_stats()?;
+    Some(stats.iter().any(|stats| {
+        matches!(
+            stats.page_type,
+            PageType::DATA_PAGE | PageType::DATA_PAGE_V2
+        ) && !matches!(
+            stats.encoding,
+            Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY
+        ) && stats.count > 0
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::StringArray;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn dictionary_fallback_shows_as_a_change() {
+        let schema = Arc::new(Schema::new(vec![Field::new("name", DataType::Utf8, true)]));
+        // The first row group repeats a few values; the second has too many
+        // distinct ones for the dictionary limit.
+        let names: Vec<String> = (0..200)
+            .map(|row| match row {
+                0..100 => format!("name {}", row % 3),
+                _ => format!("a rather long and unique name {row}"),
+            })
+            .collect();
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(StringArray::from(names))]).unwrap();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(100)
+            .set_dictionary_page_size_limit(256)
+            .set_write_batch_size(10)
+            .build();
+        let file = NamedTempFile::new().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(file.reopen().unwrap(), schema, Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let timeline = column_timeline(file.path(), "name").expect("timeline should load");
+        assert_eq!(timeline.chunks.len(), 2);
+        assert_eq!(timeline.chunks[0].fallback, Some(false));
+        assert_eq!(timeline.chunks[1].fallback, Some(true));
+        assert_eq!(timeline.chunks[0].min.as_deref(), Some("name 0"));
+        assert_eq!(timeline.chunks[0].null_count, Some(0));
+        assert_eq!(timeline.changes(), vec![false, true]);
+        assert!(timeline.summary().ends_with("from row group 2"));
+
+        assert!(column_timeline(file.path(), "missing").is_err());
+    }
+}
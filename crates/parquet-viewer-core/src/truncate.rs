@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::width::{display_width, prefix_end, suffix_start};
+
+/// Marks where text was cut.
+pub const ELLIPSIS: char = '…';
+
+/// Which part of a long value is cut to fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Truncation {
+    /// Keep the start, as for prose and messages.
+    #[default]
+    End,
+    /// Keep the start and the end, as for paths and identifiers that differ in their tails.
+    Middle,
+}
+
+/// `text` cut to at most `max_width` terminal columns, the cut marked with
+/// [`ELLIPSIS`] and made between grapheme clusters, so that wide characters and
+/// letters with combining marks are kept whole; borrowed unchanged when it
+/// already fits.
+pub fn truncate(text: &str, max_width: usize, mode: Truncation) -> Cow<'_, str> {
+    if display_width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let keep = max_width.saturating_sub(1);
+    let truncated = match mode {
+        Truncation::End => format!("{}{ELLIPSIS}", &text[..prefix_end(text, keep)]),
+        Truncation::Middle => {
+            let head_end = prefix_end(text, keep - keep / 2);
+            let tail_start = suffix_start(text, keep / 2).max(head_end);
+            format!("{}{ELLIPSIS}{}", &text[..head_end], &text[tail_start..])
+        }
+    };
+    Cow::Owned(truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_values_are_cut_at_the_end_or_in_the_middle() {
+        assert!(matches!(
+            truncate("short", 5, Truncation::End),
+            Cow::Borrowed("short")
+        ));
+        assert_eq!(truncate("abcdefghij", 5, Truncation::End), "abcd…");
+        assert_eq!(truncate("abcdefghij", 5, Truncation::Middle), "ab…ij");
+        assert_eq!(truncate("abcdefghij", 6, Truncation::Middle), "abc…ij");
+        assert_eq!(truncate("größenänderung", 4, Truncation::Middle), "gr…g");
+        assert_eq!(truncate("abc", 1, Truncation::Middle), "…");
+    }
+
+    #[test]
+    fn wide_characters_and_combining_marks_are_cut_whole() {
+        assert_eq!(truncate("日本語のテキスト", 5, Truncation::End), "日本…");
+        assert_eq!(truncate("日本語のテキスト", 6, Truncation::End), "日本…");
+        assert_eq!(truncate("日本語のテキスト", 7, Truncation::Middle), "日…ト");
+        let accented = "e\u{301}".repeat(4);
+        assert_eq!(
+            truncate(&accented, 3, Truncation::End),
+            format!("{}…", "e\u{301}".repeat(2))
+        );
+        assert!(matches!(
+            truncate(&accented, 4, Truncation::End),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+use std::sync::{Arc, OnceLock};
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::ArrowError;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::basic::{ConvertedType, LogicalType, Type as PhysicalType};
+use parquet::file::metadata::{FileMetaData, ParquetMetaData, ParquetMetaDataBuilder};
+use parquet::schema::types::{SchemaDescriptor, Type, TypePtr};
+
+use crate::cell_text;
+use crate::render::CellRenderer;
+use crate::ViewerError;
+
+/// Field metadata key marking a string column read as bytes, whose invalid
+/// UTF-8 is shown with replacement characters.
+pub const LOSSY_UTF8_KEY: &str = "parquet_viewer:lossy_utf8";
+
+static LOSSY_UTF8: OnceLock<bool> = OnceLock::new();
+
+/// Set whether string columns are read tolerantly for the whole process.
+pub fn init_lossy_utf8(enabled: bool) {
+    let _ = LOSSY_UTF8.set(enabled);
+}
+
+/// Whether `--lossy-utf8` was given; otherwise a single invalid string makes
+/// its whole row group unreadable.
+pub fn lossy_utf8() -> bool {
+    LOSSY_UTF8.get().copied().unwrap_or(false)
+}
+
+/// Reader metadata that reads the top-level string columns of `metadata` as
+/// plain bytes, so that decoding doesn't check them for valid UTF-8, or `None`
+/// if the file has no such columns.
+///
+/// The bytes columns carry [`LOSSY_UTF8_KEY`], which picks [`LossyUtf8Renderer`]
+/// to show them as text again.
+pub fn lossy_reader_metadata(
+    metadata: &ArrowReaderMetadata,
+) -> Result<Option<ArrowReaderMetadata>, ViewerError> {
+    let parquet = metadata.metadata();
+    let file = parquet.file_metadata();
+    let root = file.schema_descr().root_schema();
+    let fields = root.get_fields();
+    if !fields.iter().any(is_string) {
+        return Ok(None);
+    }
+
+    let mut parquet_fields = Vec::with_capacity(fields.len());
+    let mut arrow_fields = Vec::with_capacity(fields.len());
+    for (field, arrow_field) in fields.iter().zip(metadata.schema().fields()) {
+        match as_bytes(arrow_field.data_type()).filter(|_| is_string(field)) {
+            Some(data_type) => {
+                let info = field.get_basic_info();
+                parquet_fields.push(Arc::new(
+                    Type::primitive_type_builder(info.name(), PhysicalType::BYTE_ARRAY)
+                        .with_repetition(info.repetition())
+                        .with_id(info.has_id().then(|| info.id()))
+                        .build()?,
+                ));
+                let mut field_metadata = arrow_field.metadata().clone();
+                field_metadata.insert(LOSSY_UTF8_KEY.to_string(), "true".to_string());
+                arrow_fields.push(
+                    Field::new(arrow_field.name(), data_type, arrow_field.is_nullable())
+                        .with_metadata(field_metadata),
+                );
+            }
+            None => {
+                parquet_fields.push(field.clone());
+                arrow_fields.push(arrow_field.as_ref().clone());
+            }
+        }
+    }
+
+    let schema = Type::group_type_builder(root.name())
+        .with_fields(parquet_fields)
+        .build()?;
+    let file = FileMetaData::new(
+        file.version(),
+        file.num_rows(),
+        file.created_by().map(str::to_string),
+        file.key_value_metadata().cloned(),
+        Arc::new(SchemaDescriptor::new(Arc::new(schema))),
+        file.column_orders().cloned(),
+    );
+    let lossy: ParquetMetaData = ParquetMetaDataBuilder::new(file)
+        .set_row_groups(parquet.row_groups().to_vec())
+        .set_column_index(parquet.column_index().cloned())
+        .set_offset_index(parquet.offset_index().cloned())
+        .build();
+    let schema = Schema::new_with_metadata(arrow_fields, metadata.schema().metadata().clone());
+    let options = ArrowReaderOptions::new().with_schema(Arc::new(schema));
+    Ok(Some(ArrowReaderMetadata::try_new(
+        Arc::new(lossy),
+        options,
+    )?))
+}
+
+fn is_string(field: &TypePtr) -> bool {
+    field.is_primitive()
+        && field.get_physical_type() == PhysicalType::BYTE_ARRAY
+        && (matches!(
+            field.get_basic_info().logical_type_ref(),
+            Some(LogicalType::String)
+        ) || field.get_basic_info().converted_type() == ConvertedType::UTF8)
+}
+
+/// The bytes type read in place of a string type.
+fn as_bytes(data_type: &DataType) -> Option<DataType> {
+    match data_type {
+        DataType::Utf8 => Some(DataType::Binary),
+        DataType::LargeUtf8 => Some(DataType::LargeBinary),
+        DataType::Utf8View => Some(DataType::BinaryView),
+        DataType::Dictionary(key, value) => {
+            as_bytes(value).map(|value| DataType::Dictionary(key.clone(), Box::new(value)))
+        }
+        _ => None,
+    }
+}
+
+pub fn is_lossy_column(_schema: &Schema, field: &Field) -> bool {
+    field.metadata().contains_key(LOSSY_UTF8_KEY)
+}
+
+/// The bytes of the value at `row`, for the array types [`as_bytes`] reads.
+fn value_bytes(array: &dyn Array, row: usize) -> Option<&[u8]> {
+    if let Some(values) = array.as_binary_opt::<i32>() {
+        Some(values.value(row))
+    } else if let Some(values) = array.as_binary_opt::<i64>() {
+        Some(values.value(row))
+    } else if let Some(values) = array.as_binary_view_opt() {
+        Some(values.value(row))
+    } else if let Some(dictionary) = array.as_any_dictionary_opt() {
+        let key = *dictionary.normalized_keys().get(row)?;
+        value_bytes(dictionary.values().as_ref(), key)
+    } else {
+        None
+    }
+}
+
+/// Whether the value at `row` of a lossily read column isn't valid UTF-8, so
+/// its cell shows replacement characters.
+pub fn has_invalid_utf8(array: &dyn Array, row: usize) -> bool {
+    !array.is_null(row)
+        && value_bytes(array, row).is_some_and(|bytes| std::str::from_utf8(bytes).is_err())
+}
+
+/// Shows the bytes of a lossily read string column as text, with U+FFFD in
+/// place of invalid UTF-8.
+pub struct LossyUtf8Renderer;
+
+impl CellRenderer for LossyUtf8Renderer {
+    fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+        match value_bytes(array, row) {
+            Some(bytes) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+            None => cell_text(array, row),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::data_type::{ByteArray, ByteArrayType};
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use tempfile::NamedTempFile;
+
+    /// A file with a string column `name` whose second value is invalid UTF-8.
+    fn write_invalid_utf8_parquet() -> NamedTempFile {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let schema = Arc::new(
+            parse_message_type("message test { required int32 id; required binary name (UTF8); }")
+                .expect("schema should parse"),
+        );
+        let mut writer = SerializedFileWriter::new(
+            file.reopen().expect("temp file should reopen"),
+            schema,
+            Default::default(),
+        )
+        .expect("writer should open");
+        let mut row_group = writer.next_row_group().expect("row group should open");
+        let mut ids = row_group.next_column().unwrap().expect("id column");
+        ids.typed::<parquet::data_type::Int32Type>()
+            .write_batch(&[1, 2], None, None)
+            .expect("ids should write");
+        ids.close().expect("id column should close");
+        let mut names = row_group.next_column().unwrap().expect("name column");
+        names
+            .typed::<ByteArrayType>()
+            .write_batch(
+                &[
+                    ByteArray::from("ok"),
+                    ByteArray::from(vec![b'b', 0xff, b'd']),
+                ],
+                None,
+                None,
+            )
+            .expect("names should write");
+        names.close().expect("name column should close");
+        row_group.close().expect("row group should close");
+        writer.close().expect("writer should close");
+        file
+    }
+
+    #[test]
+    fn invalid_strings_read_as_bytes_and_render_with_replacements() {
+        let file = write_invalid_utf8_parquet();
+        let open = || File::open(file.path()).expect("file should open");
+        let strict = ParquetRecordBatchReaderBuilder::try_new(open())
+            .and_then(|builder| builder.build())
+            .expect("reader should build")
+            .next()
+            .expect("one batch");
+        assert!(strict.is_err());
+
+        let metadata = ArrowReaderMetadata::load(&open(), ArrowReaderOptions::new())
+            .expect("metadata should load");
+        let lossy = lossy_reader_metadata(&metadata)
+            .expect("metadata should convert")
+            .expect("the file has a string column");
+        let batch = ParquetRecordBatchReaderBuilder::new_with_metadata(open(), lossy)
+            .build()
+            .expect("reader should build")
+            .next()
+            .expect("one batch")
+            .expect("lossy read should succeed");
+
+        let schema = batch.schema();
+        assert!(!is_lossy_column(&schema, schema.field(0)));
+        assert!(is_lossy_column(&schema, schema.field(1)));
+        let names = batch.column(1).as_ref();
+        assert_eq!(LossyUtf8Renderer.render(names, 1).unwrap(), "b\u{fffd}d");
+        assert!(!has_invalid_utf8(names, 0));
+        assert!(has_invalid_utf8(names, 1));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::FileReader;
+use serde::Deserialize;
+
+use crate::footer;
+use crate::metrics::ReadCounter;
+use crate::ViewerError;
+
+/// An expected schema written as JSON, e.g.
+/// `{"fields": [{"name": "id", "type": "Int64", "nullable": false}]}`, with each
+/// type written as the viewer shows it.
+#[derive(Deserialize)]
+struct SchemaFile {
+    fields: Vec<FieldSpec>,
+}
+
+#[derive(Deserialize)]
+struct FieldSpec {
+    name: String,
+    #[serde(rename = "type")]
+    data_type: String,
+    #[serde(default = "nullable_by_default")]
+    nullable: bool,
+}
+
+fn nullable_by_default() -> bool {
+    true
+}
+
+/// The schema in the file at `path`: the schema of an Arrow IPC or Parquet
+/// file, told apart by their magic bytes, or else a JSON schema definition.
+pub fn load_expected_schema(path: &Path) -> Result<Schema, ViewerError> {
+    let mut magic = [0; 6];
+    let read = File::open(path)?.read(&mut magic)?;
+    match &magic[..read] {
+        b"ARROW1" => Ok(FileReader::try_new(File::open(path)?, None)?
+            .schema()
+            .as_ref()
+            .clone()),
+        [b'P', b'A', b'R', b'1', ..] => Ok(footer::reader_metadata(path, &ReadCounter::default())?
+            .schema()
+            .as_ref()
+            .clone()),
+        _ => {
+            let file: SchemaFile = serde_json::from_reader(File::open(path)?).map_err(|error| {
+                ViewerError::InvalidValue(format!("{}: {error}", path.display()))
+            })?;
+            let fields = file
+                .fields
+                .into_iter()
+                .map(|field| {
+                    let data_type = field.data_type.parse::<DataType>().map_err(|error| {
+                        ViewerError::InvalidValue(format!("type of `{}`: {error}", field.name))
+                    })?;
+                    Ok(Arc::new(Field::new(field.name, data_type, field.nullable)))
+                })
+                .collect::<Result<Vec<_>, ViewerError>>()?;
+            Ok(Schema::new(fields))
+        }
+    }
+}
+
+/// Every way the schema of the Parquet file at `path` differs from `expected`
+/// in its top-level column names, types, nullability and order, as one line
+/// each; empty if they match.
+pub fn validate_schema(
+    path: &Path,
+    expected: &Schema,
+    bytes_read: &ReadCounter,
+) -> Result<Vec<String>, ViewerError> {
+    let actual = footer::reader_metadata(path, bytes_read)?.schema().clone();
+    Ok(schema_differences(expected, &actual))
+}
+
+fn schema_differences(expected: &Schema, actual: &Schema) -> Vec<String> {
+    let mut differences = Vec::new();
+    // Positions in both schemas of each column they share, in expected order.
+    let mut shared = Vec::new();
+
+    for (position, field) in expected.fields().iter().enumerate() {
+        let Some((actual_position, actual_field)) = actual.column_with_name(field.name()) else {
+            differences.push(format!(
+                "missing column `{}`, expected as column {}",
+                field.name(),
+                position + 1
+            ));
+            continue;
+        };
+        shared.push((position, actual_position));
+        if actual_field.data_type() != field.data_type() {
+            differences.push(format!(
+                "column `{}` has type {}, expected {}",
+                field.name(),
+                actual_field.data_type(),
+                field.data_type()
+            ));
+        }
+        if actual_field.is_nullable() != field.is_nullable() {
+            let nullability = |nullable| if nullable { "nullable" } else { "non-null" };
+            differences.push(format!(
+                "column `{}` is {}, expected {}",
+                field.name(),
+                nullability(actual_field.is_nullable()),
+                nullability(field.is_nullable())
+            ));
+        }
+    }
+
+    for (position, field) in actual.fields().iter().enumerate() {
+        if expected.column_with_name(field.name()).is_none() {
+            differences.push(format!(
+                "unexpected column `{}` at column {}",
+                field.name(),
+                position + 1
+            ));
+        }
+    }
+
+    // Order is judged among the shared columns only, so that a missing or extra
+    // column is reported once rather than as every column after it moving.
+    let mut actual_order: Vec<usize> = shared.iter().map(|&(_, actual)| actual).collect();
+    actual_order.sort_unstable();
+    for (rank, &(position, actual_position)) in shared.iter().enumerate() {
+        if actual_order[rank] != actual_position {
+            differences.push(format!(
+                "column `{}` is column {}, expected column {}",
+                expected.field(position).name(),
+                actual_position + 1,
+                position + 1
+            ));
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    fn json_schema(json: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().expect("temp file should be created");
+        file.write_all(json.as_bytes())
+            .expect("schema should be written");
+        file
+    }
+
+    #[test]
+    fn differences_name_each_column_and_what_is_wrong() {
+        let file = write_test_parquet_with_row_groups(4, 4).expect("parquet write should succeed");
+
+        let matching = json_schema(
+            r#"{"fields": [{"name": "id", "type": "Int32", "nullable": false},
+                           {"name": "name", "type": "Utf8", "nullable": false}]}"#,
+        );
+        let expected = load_expected_schema(matching.path()).expect("schema should load");
+        assert!(
+            validate_schema(file.path(), &expected, &ReadCounter::default())
+                .expect("file should be validated")
+                .is_empty()
+        );
+        let same = load_expected_schema(file.path()).expect("parquet schema should load");
+        assert_eq!(same, expected);
+
+        let different = json_schema(
+            r#"{"fields": [{"name": "name", "type": "Utf8"},
+                           {"name": "score", "type": "Float64"},
+                           {"name": "id", "type": "Int64", "nullable": false}]}"#,
+        );
+        let expected = load_expected_schema(different.path()).expect("schema should load");
+        assert_eq!(
+            validate_schema(file.path(), &expected, &ReadCounter::default())
+                .expect("file should be validated"),
+            vec![
+                "column `name` is non-null, expected nullable",
+                "missing column `score`, expected as column 2",
+                "column `id` has type Int32, expected Int64",
+                "column `name` is column 2, expected column 1",
+                "column `id` is column 1, expected column 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_types_are_reported_with_their_column() {
+        let schema = json_schema(r#"{"fields": [{"name": "id", "type": "Integer"}]}"#);
+        let error = load_expected_schema(schema.path()).expect_err("type should not parse");
+        assert!(error.to_string().contains("type of `id`"));
+    }
+}
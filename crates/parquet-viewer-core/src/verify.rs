@@ -0,0 +1,214 @@
+use std::fs::File;
+use std::path::Path;
+
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+};
+use parquet::arrow::ProjectionMask;
+use parquet::file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+
+use crate::progress::Progress;
+use crate::ViewerError;
+
+/// A column chunk that could not be read back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyFailure {
+    pub row_group: usize,
+    pub column: String,
+    /// Index of the failing page within the column chunk, when the page layer failed.
+    pub page: Option<usize>,
+    pub message: String,
+}
+
+/// Result of checking every page and value of a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub row_groups: usize,
+    pub column_chunks: usize,
+    pub pages: usize,
+    pub rows: usize,
+    pub failures: Vec<VerifyFailure>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Read every page header, check page checksums and decode every column chunk of `path`.
+///
+/// The footer must be readable; anything below it is checked per column chunk so
+/// a single corrupt chunk doesn't hide problems elsewhere in the file.
+/// `progress` counts checked column chunks.
+pub fn verify_file(path: &Path, progress: &Progress) -> Result<VerifyReport, ViewerError> {
+    let file = File::open(path)?;
+    let reader = SerializedFileReader::new(file.try_clone()?)?;
+    let arrow_metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::new())?;
+    let metadata = reader.metadata();
+
+    let mut report = VerifyReport {
+        row_groups: metadata.num_row_groups(),
+        ..Default::default()
+    };
+
+    progress.set_total(
+        metadata
+            .row_groups()
+            .iter()
+            .map(|row_group| row_group.num_columns())
+            .sum(),
+    );
+
+    for row_group_index in 0..metadata.num_row_groups() {
+        let row_group = reader.get_row_group(row_group_index)?;
+        let expected_rows = row_group.metadata().num_rows() as usize;
+        report.rows += expected_rows;
+
+        for column_index in 0..row_group.num_columns() {
+            progress.check()?;
+            progress.advance(1);
+            report.column_chunks += 1;
+            let column = row_group
+                .metadata()
+                .column(column_index)
+                .column_path()
+                .string();
+
+            match check_pages(row_group.as_ref(), column_index) {
+                Ok(pages) => report.pages += pages,
+                Err((page, message)) => {
+                    report.pages += page;
+                    report.failures.push(VerifyFailure {
+                        row_group: row_group_index,
+                        column,
+                        page: Some(page),
+                        message,
+                    });
+                    continue;
+                }
+            }
+
+            let decoded =
+                decode_column_chunk(&file, &arrow_metadata, row_group_index, column_index);
+            let message = match decoded {
+                Ok(rows) if rows == expected_rows => continue,
+                Ok(rows) => format!("decoded {rows} rows, expected {expected_rows}"),
+                Err(error) => error.to_string(),
+            };
+            report.failures.push(VerifyFailure {
+                row_group: row_group_index,
+                column,
+                page: None,
+                message,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Walk the pages of one column chunk, returning the page count or the failing page.
+fn check_pages(
+    row_group: &dyn RowGroupReader,
+    column_index: usize,
+) -> Result<usize, (usize, String)> {
+    let mut pages = row_group
+        .get_column_page_reader(column_index)
+        .map_err(|error| (0, error.to_string()))?;
+
+    let mut count = 0;
+    loop {
+        match pages.get_next_page() {
+            Ok(Some(_)) => count += 1,
+            Ok(None) => return Ok(count),
+            Err(error) => return Err((count, error.to_string())),
+        }
+    }
+}
+
+/// Decode a single column chunk to Arrow, returning the number of rows produced.
+fn decode_column_chunk(
+    file: &File,
+    metadata: &ArrowReaderMetadata,
+    row_group_index: usize,
+    column_index: usize,
+) -> Result<usize, ViewerError> {
+    let projection = ProjectionMask::leaves(metadata.parquet_schema(), [column_index]);
+    let reader =
+        ParquetRecordBatchReaderBuilder::new_with_metadata(file.try_clone()?, metadata.clone())
+            .with_row_groups(vec![row_group_index])
+            .with_projection(projection)
+            .build()?;
+
+    let mut rows = 0;
+    for batch in reader {
+        rows += batch?.num_rows();
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Seek, SeekFrom, Write};
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parquet::arrow::arrow_writer::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use tempfile::NamedTempFile;
+
+    fn write_row_groups(row_groups: usize) -> NamedTempFile {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .build();
+        let mut writer = ArrowWriter::try_new(file.reopen().unwrap(), schema.clone(), Some(props))
+            .expect("writer should build");
+        let ids: Vec<i32> = (0..(row_groups * 10) as i32).collect();
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(ids))]).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        file
+    }
+
+    #[test]
+    fn verify_accepts_valid_file() {
+        let file = write_row_groups(3);
+
+        let report = verify_file(file.path(), &Progress::default()).expect("verify should run");
+
+        assert!(report.is_ok());
+        assert_eq!(report.row_groups, 3);
+        assert_eq!(report.column_chunks, 3);
+        assert_eq!(report.rows, 30);
+        assert!(report.pages >= 3);
+    }
+
+    #[test]
+    fn verify_locates_corrupt_page_header() {
+        let file = write_row_groups(3);
+        let offset = {
+            let reader = SerializedFileReader::new(File::open(file.path()).unwrap()).unwrap();
+            let column = reader.metadata().row_group(1).column(0);
+            column
+                .dictionary_page_offset()
+                .unwrap_or_else(|| column.data_page_offset())
+        };
+        let mut handle = file.reopen().unwrap();
+        handle.seek(SeekFrom::Start(offset as u64)).unwrap();
+        handle.write_all(&[0xff; 8]).unwrap();
+
+        let report = verify_file(file.path(), &Progress::default()).expect("footer is intact");
+
+        assert_eq!(report.failures.len(), 1);
+        let failure = &report.failures[0];
+        assert_eq!(failure.row_group, 1);
+        assert_eq!(failure.column, "id");
+        assert_eq!(failure.page, Some(0));
+    }
+}
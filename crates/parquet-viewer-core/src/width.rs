@@ -0,0 +1,98 @@
+use std::borrow::Cow;
+
+use unicode_bidi::{bidi_class, BidiClass};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Starts text laid out in the direction of its first strong character, apart
+/// from what surrounds it.
+const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+/// Ends text started with [`FIRST_STRONG_ISOLATE`].
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// Columns `text` takes in a terminal: wide characters such as CJK count two,
+/// combining marks and other zero-width characters nothing.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// End of the longest run of whole grapheme clusters from the start of `text`
+/// that fits in `width` columns, as a byte index.
+pub fn prefix_end(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return index;
+        }
+    }
+    text.len()
+}
+
+/// Start of the longest run of whole grapheme clusters at the end of `text`
+/// that fits in `width` columns, as a byte index.
+pub fn suffix_start(text: &str, width: usize) -> usize {
+    let mut used = 0;
+    let mut start = text.len();
+    for (index, grapheme) in text.grapheme_indices(true).rev() {
+        used += grapheme.width();
+        if used > width {
+            break;
+        }
+        start = index;
+    }
+    start
+}
+
+/// Whether `text` reads right to left, going by its first strongly directional
+/// character as the Unicode bidirectional algorithm does.
+pub fn is_rtl(text: &str) -> bool {
+    text.chars()
+        .map(bidi_class)
+        .find(|class| matches!(class, BidiClass::L | BidiClass::R | BidiClass::AL))
+        .is_some_and(|class| class != BidiClass::L)
+}
+
+/// `text` wrapped in directional isolates when it holds right-to-left
+/// characters, so that a terminal reordering it leaves the table's borders and
+/// neighbouring cells where they are.
+pub fn isolate(text: &str) -> Cow<'_, str> {
+    let has_rtl = text
+        .chars()
+        .any(|c| matches!(bidi_class(c), BidiClass::R | BidiClass::AL));
+    if has_rtl {
+        Cow::Owned(format!(
+            "{FIRST_STRONG_ISOLATE}{text}{POP_DIRECTIONAL_ISOLATE}"
+        ))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_count_terminal_columns_and_cuts_keep_graphemes_whole() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("e\u{301}"), 1);
+
+        assert_eq!(prefix_end("日本語", 3), "日".len());
+        assert_eq!(prefix_end("e\u{301}e\u{301}", 1), "e\u{301}".len());
+        assert_eq!(suffix_start("日本語", 4), "日".len());
+        assert_eq!(suffix_start("abc", 0), 3);
+    }
+
+    #[test]
+    fn right_to_left_text_is_detected_and_isolated() {
+        assert!(is_rtl("שלום world"));
+        assert!(is_rtl("123 مرحبا"));
+        assert!(!is_rtl("hello שלום"));
+        assert!(!is_rtl("42"));
+
+        assert_eq!(isolate("plain"), "plain");
+        assert_eq!(isolate("id שלום"), "\u{2068}id שלום\u{2069}");
+    }
+}
@@ -0,0 +1,406 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Int64Type, TimeUnit, UInt64Type};
+use arrow::record_batch::RecordBatch;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+
+use crate::export::read_rows;
+use crate::metrics::ReadCounter;
+use crate::output::ensure_distinct;
+use crate::progress::Progress;
+use crate::render::{self, ColumnRenderers, RendererRegistry};
+use crate::ViewerError;
+
+/// Most rows an Excel export takes. Spreadsheets are for handing over small
+/// extracts; bigger ones belong in CSV or Parquet.
+pub const XLSX_ROW_LIMIT: usize = 100_000;
+
+/// Columns a worksheet has room for.
+const XLSX_COLUMN_LIMIT: usize = 16_384;
+
+/// Characters a cell holds.
+const CELL_CHARS: usize = 32_767;
+
+/// Largest integer a spreadsheet number holds exactly, 2^53.
+const EXACT_INTEGER: u64 = 1 << 53;
+
+/// Days from Excel's day 0, 1899-12-30, to the Unix epoch.
+const EXCEL_EPOCH_DAYS: f64 = 25_569.0;
+
+/// Serial number of 1900-03-01; Excel counts a 29 February 1900 that never
+/// was, so it shows earlier dates wrongly.
+const FIRST_EXACT_SERIAL: f64 = 61.0;
+
+/// Write the file rows `rows`, in the given order, and the top-level `columns`
+/// of the file at `path` to a new Excel workbook at `destination`, one row per
+/// file row under a bold, frozen header.
+///
+/// Numbers, booleans, dates, times and timestamps become typed cells, whatever
+/// locale the viewer shows them in; integers too big for a spreadsheet number
+/// to hold exactly, NaN and infinities, and every other value are written as
+/// text, as the viewer shows it. Timestamps
+/// with a time zone are written in UTC, as Excel has no time zones. More than
+/// [`XLSX_ROW_LIMIT`] rows are refused before anything is read, and rows that
+/// decode to more than `--memory-limit` once read; `progress` counts row groups
+/// read.
+pub fn export_xlsx(
+    path: &Path,
+    rows: &[usize],
+    columns: &[usize],
+    destination: &Path,
+    bytes_read: &ReadCounter,
+    progress: &Progress,
+) -> Result<(), ViewerError> {
+    ensure_distinct([path], destination)?;
+    if rows.len() > XLSX_ROW_LIMIT {
+        return Err(ViewerError::InvalidValue(format!(
+            "{} rows are more than the {XLSX_ROW_LIMIT} an Excel export takes",
+            rows.len()
+        )));
+    }
+    if columns.len() > XLSX_COLUMN_LIMIT {
+        return Err(ViewerError::InvalidValue(format!(
+            "{} columns are more than the {XLSX_COLUMN_LIMIT} a worksheet has",
+            columns.len()
+        )));
+    }
+
+    let batch = read_rows(path, rows, columns, bytes_read, progress)?;
+    let mut workbook = Workbook::new();
+    write_sheet(
+        workbook.add_worksheet(),
+        &batch,
+        render::registry(),
+        progress,
+    )?;
+    workbook.save(destination)?;
+    Ok(())
+}
+
+/// What one cell of a worksheet holds.
+#[derive(Clone, Debug, PartialEq)]
+enum SheetCell {
+    Number(f64, NumberFormat),
+    Boolean(bool),
+    Text(String),
+}
+
+/// How a number cell is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NumberFormat {
+    General,
+    Date,
+    DateTime,
+    Time,
+}
+
+fn write_sheet(
+    sheet: &mut Worksheet,
+    batch: &RecordBatch,
+    registry: &RendererRegistry,
+    progress: &Progress,
+) -> Result<(), ViewerError> {
+    let header = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+    let time = Format::new().set_num_format("hh:mm:ss");
+    let schema = batch.schema();
+    let renderers = registry.column_renderers(&schema);
+
+    for (column, field) in schema.fields().iter().enumerate() {
+        sheet.write_string_with_format(0, column as u16, field.name(), &header)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (column, values) in batch.columns().iter().enumerate() {
+        progress.check()?;
+        let sheet_column = column as u16;
+        for (row, cell) in column_cells(&renderers, column, values)?
+            .into_iter()
+            .enumerate()
+        {
+            let sheet_row = row as u32 + 1;
+            match cell {
+                None => {}
+                Some(SheetCell::Number(number, NumberFormat::General)) => {
+                    sheet.write_number(sheet_row, sheet_column, number)?;
+                }
+                Some(SheetCell::Number(number, format)) => {
+                    let format = match format {
+                        NumberFormat::Date => &date,
+                        NumberFormat::DateTime => &datetime,
+                        _ => &time,
+                    };
+                    sheet.write_number_with_format(sheet_row, sheet_column, number, format)?;
+                }
+                Some(SheetCell::Boolean(value)) => {
+                    sheet.write_boolean(sheet_row, sheet_column, value)?;
+                }
+                Some(SheetCell::Text(text)) => {
+                    sheet.write_string(sheet_row, sheet_column, text)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The cells of one column, `None` for nulls.
+///
+/// Typed cells are picked from the Arrow type. A renderer that only writes
+/// values differently, such as the locale's, is ignored; one that shows
+/// something else than the stored value makes the column text.
+fn column_cells(
+    renderers: &ColumnRenderers,
+    column: usize,
+    values: &ArrayRef,
+) -> Result<Vec<Option<SheetCell>>, ViewerError> {
+    let values = match values.data_type() {
+        DataType::Dictionary(_, value_type) => cast(values, value_type)?,
+        _ => values.clone(),
+    };
+    let plain = !renderers.reinterprets(column);
+    // Typed columns as numbers, with the format that shows them and the
+    // smallest number that does; other columns are written as text.
+    let typed = match values.data_type() {
+        _ if !plain => None,
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64 => Some((
+            cast(&values, &DataType::Float64)?,
+            NumberFormat::General,
+            f64::MIN,
+        )),
+        DataType::Decimal32(precision, _)
+        | DataType::Decimal64(precision, _)
+        | DataType::Decimal128(precision, _)
+        | DataType::Decimal256(precision, _)
+            if *precision <= 15 =>
+        {
+            Some((
+                cast(&values, &DataType::Float64)?,
+                NumberFormat::General,
+                f64::MIN,
+            ))
+        }
+        DataType::Date32 => Some((
+            in_days(&values, 1, EXCEL_EPOCH_DAYS)?,
+            NumberFormat::Date,
+            FIRST_EXACT_SERIAL,
+        )),
+        DataType::Date64 => Some((
+            in_days(&values, 86_400_000, EXCEL_EPOCH_DAYS)?,
+            NumberFormat::Date,
+            FIRST_EXACT_SERIAL,
+        )),
+        DataType::Timestamp(unit, _) => Some((
+            in_days(&values, ticks_per_day(*unit), EXCEL_EPOCH_DAYS)?,
+            NumberFormat::DateTime,
+            FIRST_EXACT_SERIAL,
+        )),
+        DataType::Time32(unit) | DataType::Time64(unit) => Some((
+            in_days(&values, ticks_per_day(*unit), 0.0)?,
+            NumberFormat::Time,
+            0.0,
+        )),
+        _ => None,
+    };
+
+    (0..values.len())
+        .map(|row| -> Result<_, ViewerError> {
+            if values.is_null(row) {
+                return Ok(None);
+            }
+            let number = typed.as_ref().and_then(|(numbers, format, minimum)| {
+                let number = numbers.as_primitive::<Float64Type>().value(row);
+                (number.is_finite() && number >= *minimum).then_some((number, *format))
+            });
+            Ok(Some(match (values.data_type(), number) {
+                (_, Some((number, format))) => SheetCell::Number(number, format),
+                (DataType::Boolean, _) if plain => {
+                    SheetCell::Boolean(values.as_boolean().value(row))
+                }
+                (DataType::Int64, _) if plain => {
+                    let value = values.as_primitive::<Int64Type>().value(row);
+                    if value.unsigned_abs() <= EXACT_INTEGER {
+                        SheetCell::Number(value as f64, NumberFormat::General)
+                    } else {
+                        SheetCell::Text(value.to_string())
+                    }
+                }
+                (DataType::UInt64, _) if plain => {
+                    let value = values.as_primitive::<UInt64Type>().value(row);
+                    if value <= EXACT_INTEGER {
+                        SheetCell::Number(value as f64, NumberFormat::General)
+                    } else {
+                        SheetCell::Text(value.to_string())
+                    }
+                }
+                _ => {
+                    let mut text = renderers.cell_text(column, values.as_ref(), row)?;
+                    if let Some((cut, _)) = text.char_indices().nth(CELL_CHARS) {
+                        text.truncate(cut);
+                    }
+                    SheetCell::Text(text)
+                }
+            }))
+        })
+        .collect()
+}
+
+fn ticks_per_day(unit: TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 86_400,
+        TimeUnit::Millisecond => 86_400_000,
+        TimeUnit::Microsecond => 86_400_000_000,
+        TimeUnit::Nanosecond => 86_400_000_000_000,
+    }
+}
+
+/// Values counted in units of which there are `per_day` in a day, as days
+/// after `epoch`: Excel serial numbers of dates and timestamps with the Unix
+/// epoch's serial number, fractions of a day for times of day with 0.
+fn in_days(values: &dyn Array, per_day: i64, epoch: f64) -> Result<ArrayRef, ViewerError> {
+    let ticks = match values.data_type() {
+        DataType::Date32 | DataType::Time32(_) => {
+            cast(&cast(values, &DataType::Int32)?, &DataType::Int64)?
+        }
+        _ => cast(values, &DataType::Int64)?,
+    };
+    Ok(Arc::new(
+        ticks
+            .as_primitive::<Int64Type>()
+            .unary::<_, Float64Type>(|ticks| ticks as f64 / per_day as f64 + epoch),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    use crate::preview::tests::write_test_parquet_with_row_groups;
+
+    #[test]
+    fn rows_are_written_as_typed_cells_up_to_the_limit() {
+        let file = write_test_parquet_with_row_groups(6, 2).expect("parquet write should succeed");
+        let output = tempfile::tempdir().expect("temp dir should be created");
+        let destination = output.path().join("export.xlsx");
+
+        export_xlsx(
+            file.path(),
+            &[4, 1],
+            &[1, 0],
+            &destination,
+            &ReadCounter::default(),
+            &Progress::default(),
+        )
+        .expect("export should succeed");
+        // A workbook is a zip archive; its start is enough to tell one was written.
+        let mut signature = [0; 2];
+        std::fs::File::open(&destination)
+            .unwrap()
+            .read_exact(&mut signature)
+            .unwrap();
+        assert_eq!(&signature, b"PK");
+
+        let too_many: Vec<usize> = (0..=XLSX_ROW_LIMIT).collect();
+        let refused = output.path().join("refused.xlsx");
+        assert!(matches!(
+            export_xlsx(
+                file.path(),
+                &too_many,
+                &[0],
+                &refused,
+                &ReadCounter::default(),
+                &Progress::default(),
+            ),
+            Err(ViewerError::InvalidValue(_))
+        ));
+        assert!(!refused.exists());
+    }
+
+    #[test]
+    fn dates_and_times_become_excel_serial_numbers() {
+        use arrow::array::{Date32Array, Time32SecondArray, TimestampSecondArray};
+
+        let dates = Date32Array::from(vec![0, 19_723]);
+        let serials = in_days(&dates, 1, EXCEL_EPOCH_DAYS).unwrap();
+        // 1970-01-01 and 2024-01-01.
+        assert_eq!(
+            serials.as_primitive::<Float64Type>().values().to_vec(),
+            [25_569.0, 45_292.0]
+        );
+
+        let noon = TimestampSecondArray::from(vec![43_200]);
+        let serials = in_days(&noon, ticks_per_day(TimeUnit::Second), EXCEL_EPOCH_DAYS).unwrap();
+        assert_eq!(serials.as_primitive::<Float64Type>().value(0), 25_569.5);
+
+        let quarter_past_six = Time32SecondArray::from(vec![22_500]);
+        let fractions = in_days(&quarter_past_six, ticks_per_day(TimeUnit::Second), 0.0).unwrap();
+        assert_eq!(
+            fractions.as_primitive::<Float64Type>().value(0),
+            0.260_416_666_666_666_7
+        );
+    }
+
+    #[test]
+    fn locale_renderers_leave_cells_typed() {
+        use arrow::array::{Date32Array, Int32Array};
+        use arrow::datatypes::{Field, Int32Type, Schema};
+        use arrow::error::ArrowError;
+
+        use crate::locale::{is_localized_column, LocaleRenderer};
+        use crate::render::{CellRenderer, RendererMatch};
+
+        struct Hex;
+        impl CellRenderer for Hex {
+            fn render(&self, array: &dyn Array, row: usize) -> Result<String, ArrowError> {
+                Ok(format!(
+                    "{:#x}",
+                    array
+                        .as_primitive::<arrow::datatypes::Int32Type>()
+                        .value(row)
+                ))
+            }
+        }
+
+        let mut registry = RendererRegistry::default();
+        registry.register(RendererMatch::Column("flags".to_string()), Arc::new(Hex));
+        registry.register(
+            RendererMatch::Detect(is_localized_column),
+            Arc::new(LocaleRenderer("de-DE".parse().unwrap())),
+        );
+        let schema = Schema::new(vec![
+            Field::new("amount", DataType::Int32, true),
+            Field::new("day", DataType::Date32, true),
+            Field::new("flags", DataType::Int32, true),
+        ]);
+        let renderers = registry.column_renderers(&schema);
+        let amount: ArrayRef = Arc::new(Int32Array::from(vec![Some(1234), None]));
+        let day: ArrayRef = Arc::new(Date32Array::from(vec![19_723]));
+        let flags: ArrayRef = Arc::new(Int32Array::from(vec![255]));
+
+        assert_eq!(
+            column_cells(&renderers, 0, &amount).unwrap(),
+            [Some(SheetCell::Number(1234.0, NumberFormat::General)), None]
+        );
+        assert_eq!(
+            column_cells(&renderers, 1, &day).unwrap(),
+            [Some(SheetCell::Number(45_292.0, NumberFormat::Date))]
+        );
+        assert_eq!(
+            column_cells(&renderers, 2, &flags).unwrap(),
+            [Some(SheetCell::Text("0xff".to_string()))]
+        );
+    }
+}
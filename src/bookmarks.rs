@@ -0,0 +1,180 @@
+use gpui::{actions, div, prelude::*};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Disableable, Sizable, StyledExt};
+
+use crate::PreviewView;
+
+actions!(
+    parquet_viewer,
+    [
+        ToggleBookmark,
+        NextBookmark,
+        PreviousBookmark,
+        ToggleBookmarksPanel
+    ]
+);
+
+/// The bookmark to jump to from display row `current`, wrapping around at either end.
+///
+/// `positions` are the display rows of the bookmarks in ascending order; bookmarks
+/// hidden by a filter are expected to be left out already.
+pub fn step(positions: &[usize], current: usize, backwards: bool) -> Option<usize> {
+    if backwards {
+        positions
+            .iter()
+            .rev()
+            .find(|&&position| position < current)
+            .or(positions.last())
+            .copied()
+    } else {
+        positions
+            .iter()
+            .find(|&&position| position > current)
+            .or(positions.first())
+            .copied()
+    }
+}
+
+impl PreviewView {
+    /// Bookmark the selected row, or remove its bookmark.
+    pub(crate) fn toggle_bookmark(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        if let Some((row, _)) = self.selected_cell {
+            self.toggle_bookmark_at(row, cx);
+        }
+    }
+
+    pub(crate) fn toggle_bookmark_at(
+        &mut self,
+        display_row: usize,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let row = self.file_row(display_row);
+        if !self.bookmarks.remove(&row) {
+            self.bookmarks.insert(row);
+        }
+        cx.notify();
+    }
+
+    /// Display rows of the bookmarks that the current filter shows, ascending.
+    fn bookmark_positions(&self) -> Vec<usize> {
+        match &self.row_order {
+            Some(order) => order
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| self.bookmarks.contains(row))
+                .map(|(position, _)| position)
+                .collect(),
+            None => self.bookmarks.iter().copied().collect(),
+        }
+    }
+
+    /// Jump to the next bookmark after the selected row, or with `backwards`, the previous one.
+    pub(crate) fn step_bookmark(&mut self, backwards: bool, cx: &mut gpui::Context<PreviewView>) {
+        let current = self
+            .selected_cell
+            .map_or(self.visible_range.start, |(row, _)| row);
+        if let Some(row) = step(&self.bookmark_positions(), current, backwards) {
+            self.reveal_row(row, cx);
+        }
+    }
+
+    pub(crate) fn toggle_bookmarks_panel(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.show_bookmarks = !self.show_bookmarks;
+        cx.notify();
+    }
+}
+
+/// Panel listing the bookmarked rows; clicking one jumps to it.
+pub fn render_bookmarks(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_bookmarks_panel(cx);
+        },
+    );
+
+    let display_rows: Vec<(usize, Option<usize>)> = view
+        .bookmarks
+        .iter()
+        .map(|&row| {
+            let position = match &view.row_order {
+                Some(order) => order.iter().position(|&shown| shown == row),
+                None => Some(row),
+            };
+            (row, position)
+        })
+        .collect();
+
+    div()
+        .flex()
+        .flex_row()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .flex_1()
+                .gap_1()
+                .child(
+                    div()
+                        .font_medium()
+                        .child(format!("Bookmarks ({})", view.bookmarks.len())),
+                )
+                .when(view.bookmarks.is_empty(), |this| {
+                    this.child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child("Select a row and press Ctrl/Cmd+B to bookmark it"),
+                    )
+                })
+                .child(div().flex().flex_row().flex_wrap().gap_1().children(
+                    display_rows.into_iter().map(|(row, position)| {
+                        Button::new(("bookmark", row))
+                            .ghost()
+                            .small()
+                            .label(format!("Row {}", row + 1))
+                            // Bookmarks hidden by the current filter can't be jumped to.
+                            .disabled(position.is_none())
+                            .on_click(cx.listener(
+                                move |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                                    if let Some(position) = position {
+                                        view.reveal_row(position, cx);
+                                    }
+                                },
+                            ))
+                    }),
+                )),
+        )
+        .child(
+            Button::new("close-bookmarks")
+                .ghost()
+                .label("Close")
+                .on_click(close),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepping_wraps_around_the_bookmarks() {
+        let positions = [3, 10, 42];
+
+        assert_eq!(step(&positions, 0, false), Some(3));
+        assert_eq!(step(&positions, 3, false), Some(10));
+        assert_eq!(step(&positions, 42, false), Some(3));
+        assert_eq!(step(&positions, 10, true), Some(3));
+        assert_eq!(step(&positions, 3, true), Some(42));
+        assert_eq!(step(&[], 5, false), None);
+    }
+}
@@ -0,0 +1,20 @@
+use parquet_viewer_core::cancel::CancellationToken;
+
+/// A UI task and the token its background work runs under; dropping it, e.g. by
+/// replacing it with the next load, cancels both.
+pub struct CancellableTask {
+    token: CancellationToken,
+    _task: gpui::Task<()>,
+}
+
+impl CancellableTask {
+    pub fn new(token: CancellationToken, task: gpui::Task<()>) -> Self {
+        Self { token, _task: task }
+    }
+}
+
+impl Drop for CancellableTask {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
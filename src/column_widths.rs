@@ -0,0 +1,102 @@
+use gpui::{px, Pixels, Styled};
+use parquet_viewer_core::intern::Cell;
+use parquet_viewer_core::width::display_width;
+
+/// Narrowest a column gets, however little it holds or however many there are.
+pub const MIN_COLUMN_WIDTH: f32 = 80.0;
+
+/// Widest a column's preferred width gets, in characters, so that one long value
+/// doesn't squeeze every other column.
+const MAX_COLUMN_CHARS: usize = 48;
+
+/// Average width of a character as a share of the font size.
+const CHAR_WIDTH: f32 = 0.6;
+
+/// Padding and border of a cell either side of its text.
+const CELL_PADDING: f32 = 17.0;
+
+/// Preferred width of each column, shared by the header, the rows and the
+/// summary row so that their edges line up whatever the cells hold.
+///
+/// Widths are kept in characters, by source column, and only ever grow to fit
+/// what has been shown, so that columns hold still while scrolling.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnWidths {
+    chars: Vec<usize>,
+}
+
+impl ColumnWidths {
+    /// Widen `column` to fit `text`, wide characters counting two.
+    pub fn fit(&mut self, column: usize, text: &str) {
+        let head = text
+            .char_indices()
+            .nth(MAX_COLUMN_CHARS)
+            .map_or(text, |(end, _)| &text[..end]);
+        self.fit_chars(column, display_width(head));
+    }
+
+    /// Widen `column` to fit `chars` characters.
+    pub fn fit_chars(&mut self, column: usize, chars: usize) {
+        if self.chars.len() <= column {
+            self.chars.resize(column + 1, 0);
+        }
+        self.chars[column] = self.chars[column].max(chars.min(MAX_COLUMN_CHARS));
+    }
+
+    /// Widen the displayed `columns` to fit the cells of `rows`.
+    pub fn fit_rows(&mut self, columns: &[usize], rows: &[Vec<Cell>]) {
+        for row in rows {
+            for &column in columns {
+                if let Some(cell) = row.get(column) {
+                    self.fit(column, cell);
+                }
+            }
+        }
+    }
+
+    /// Preferred width of `column` in a table with the given font size.
+    pub fn width(&self, column: usize, font_size: f32) -> Pixels {
+        let chars = self.chars.get(column).copied().unwrap_or_default();
+        px((chars as f32 * CHAR_WIDTH * font_size + CELL_PADDING).max(MIN_COLUMN_WIDTH))
+    }
+}
+
+/// Characters of text that fit on one line of a cell `width` wide, going by the
+/// average character width.
+pub fn chars_per_line(width: Pixels, font_size: f32) -> usize {
+    ((f32::from(width) - CELL_PADDING) / (CHAR_WIDTH * font_size)).max(1.0) as usize
+}
+
+/// Size a header, row or summary cell of a column preferring `width`. Columns
+/// grow and shrink in proportion to their preferred widths to fill the table,
+/// and never by their content, so every row lays them out alike.
+pub fn column_cell<E: Styled>(cell: E, width: Pixels) -> E {
+    cell.flex_basis(width)
+        .flex_grow()
+        .flex_shrink()
+        .min_w(px(MIN_COLUMN_WIDTH))
+        .overflow_hidden()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_grow_to_the_widest_text_seen_within_bounds() {
+        let mut widths = ColumnWidths::default();
+        widths.fit(1, "status");
+        widths.fit_rows(
+            &[1],
+            &[
+                vec![Cell::from("ignored"), Cell::from("ok")],
+                vec![Cell::from("ignored"), Cell::from("x".repeat(200))],
+            ],
+        );
+        widths.fit(1, "short");
+
+        assert_eq!(widths.width(0, 10.0), px(MIN_COLUMN_WIDTH));
+        let widest = MAX_COLUMN_CHARS as f32 * CHAR_WIDTH * 10.0 + CELL_PADDING;
+        assert_eq!(widths.width(1, 10.0), px(widest));
+    }
+}
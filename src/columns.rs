@@ -0,0 +1,306 @@
+use gpui::{div, prelude::*};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputState};
+use gpui_component::{ActiveTheme, StyledExt, WindowExt};
+use parquet_viewer_core::cast::ViewAs;
+use parquet_viewer_core::computed::FUNCTIONS;
+use parquet_viewer_core::prefetch::RowCache;
+use parquet_viewer_core::spill;
+use parquet_viewer_core::stats::{column_stats, ColumnStats};
+use parquet_viewer_core::ViewerError;
+
+use crate::context_menu::{
+    AddComputedColumn, HideColumn, RemoveComputedColumn, ShowAllColumns, ShowColumnStats,
+    ViewAsChoices, ViewColumnAs,
+};
+use crate::PreviewView;
+
+impl PreviewView {
+    pub(crate) fn hide_column(
+        &mut self,
+        action: &HideColumn,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        // Keep at least one column so there is still a header to right-click.
+        if self.hidden_columns.len() + 1 < self.preview.columns.len() {
+            self.hidden_columns.insert(action.column);
+            self.project_displayed_columns();
+            cx.notify();
+        }
+    }
+
+    pub(crate) fn show_all_columns(
+        &mut self,
+        _: &ShowAllColumns,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.hidden_columns.clear();
+        if self.project_displayed_columns() {
+            self.reload_rows(cx);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn show_column_stats(
+        &mut self,
+        action: &ShowColumnStats,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = &self.preview.columns[action.column];
+        self.column_stats = Some(column_stats(&self.preview.path, column).map_err(|error| {
+            tracing::error!(%error, column, "failed to read column statistics");
+            format!("Statistics for {column} could not be read: {error}")
+        }));
+        cx.notify();
+    }
+
+    pub(crate) fn view_as_choices(&self, column: usize) -> ViewAsChoices {
+        ViewAsChoices {
+            choices: ViewAs::choices(&self.preview.data_types[column]),
+            current: self.preview.casts.get(&column).copied(),
+        }
+    }
+
+    /// Show a column as another type, reading the rows on screen again.
+    pub(crate) fn view_column_as(
+        &mut self,
+        action: &ViewColumnAs,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if let Err(error) = self.preview.set_view_as(action.column, action.view) {
+            let column = &self.preview.columns[action.column];
+            tracing::error!(%error, column, "failed to change the column's type");
+            window.push_notification(format!("{column} could not be shown that way: {error}"), cx);
+            return;
+        }
+        // Rows decoded with the old type must be read again.
+        self.row_cache = RowCache::new(spill::memory_limit());
+        self.prefetch_range = None;
+        self._prefetch_task = None;
+        self.cell_errors
+            .retain(|&(_, column), _| column != action.column);
+        self.record_cell_errors(self.preview.cell_errors.clone());
+        self.load_visible_rows(self.visible_range.start, cx);
+
+        // Filters and sorts on the column match the values it now shows.
+        let column = &self.preview.columns[action.column];
+        let queried = self
+            .filters
+            .iter()
+            .any(|value_filter| &value_filter.column == column)
+            || self
+                .sort
+                .as_ref()
+                .is_some_and(|sort| &sort.column == column);
+        if queried {
+            self.update_row_order(self.filters.clone(), self.sort.clone(), cx);
+        }
+    }
+
+    /// Ask for a `name = expression` definition and show it as a new column.
+    pub(crate) fn add_computed_column(
+        &mut self,
+        _: &AddComputedColumn,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Column definition, e.g. total = price * qty")
+        });
+        let view = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let input = dialog_input.clone();
+            let view = view.clone();
+            dialog
+                .title("Add computed column")
+                .child(Input::new(&dialog_input))
+                .child(format!("Functions: {FUNCTIONS}"))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let definition = input.read(cx).value().to_string();
+                    let added = view.update(cx, |view, cx| {
+                        view.preview.add_computed(&definition)?;
+                        view.column_order.push(view.preview.columns.len() - 1);
+                        view.reload_rows(cx);
+                        Ok::<_, ViewerError>(())
+                    });
+                    // Keep the dialog open to fix a bad definition.
+                    match added {
+                        Ok(Err(error)) => {
+                            window.push_notification(error.to_string(), cx);
+                            false
+                        }
+                        _ => true,
+                    }
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    pub(crate) fn remove_computed_column(
+        &mut self,
+        action: &RemoveComputedColumn,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = action.column;
+        if let Err(error) = self.preview.remove_computed(column) {
+            tracing::error!(%error, "failed to read the rows again");
+            window.push_notification(format!("The rows could not be read again: {error}"), cx);
+        }
+        let shift = |index: usize| if index > column { index - 1 } else { index };
+        self.column_order = self
+            .column_order
+            .iter()
+            .filter(|&&index| index != column)
+            .map(|&index| shift(index))
+            .collect();
+        self.hidden_columns = self
+            .hidden_columns
+            .iter()
+            .filter(|&&index| index != column)
+            .map(|&index| shift(index))
+            .collect();
+        self.selected_cell = None;
+        self.project_displayed_columns();
+        self.reload_rows(cx);
+    }
+
+    /// Decode only the file columns on screen when reading rows from now on.
+    /// Returns whether columns came back into view, so that the rows read
+    /// before need reading again.
+    pub(crate) fn project_displayed_columns(&mut self) -> bool {
+        let file_columns = self.preview.computed_start();
+        let shown: Vec<usize> = self
+            .displayed_columns()
+            .into_iter()
+            .filter(|&column| column < file_columns)
+            .collect();
+        let projection = (shown.len() < file_columns).then_some(shown);
+        self.preview.set_projection(projection)
+    }
+
+    /// Read the rows on screen again after the columns shown have changed.
+    pub(crate) fn reload_rows(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.row_cache = RowCache::new(spill::memory_limit());
+        self.prefetch_range = None;
+        self._prefetch_task = None;
+        self.cell_errors.clear();
+        self.lossy_cells.clear();
+        self.record_cell_errors(self.preview.cell_errors.clone());
+        self.lossy_cells
+            .extend(self.preview.lossy_cells.iter().copied());
+        self.load_visible_rows(self.visible_range.start, cx);
+    }
+
+    /// Show only the columns named `names`, in that order, as a link's `cols`
+    /// asks. Names the file doesn't have are skipped and reported.
+    pub(crate) fn show_only_columns(
+        &mut self,
+        names: &[String],
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Result<(), String> {
+        let mut shown = Vec::new();
+        let mut missing = Vec::new();
+        for name in names {
+            match self
+                .preview
+                .columns
+                .iter()
+                .position(|column| column == name)
+            {
+                Some(column) if !shown.contains(&column) => shown.push(column),
+                Some(_) => {}
+                None => missing.push(name.as_str()),
+            }
+        }
+        if !shown.is_empty() {
+            let rest: Vec<usize> = self
+                .column_order
+                .iter()
+                .copied()
+                .filter(|column| !shown.contains(column))
+                .collect();
+            self.hidden_columns = rest.iter().copied().collect();
+            self.column_order = shown.into_iter().chain(rest).collect();
+            if self.project_displayed_columns() {
+                self.reload_rows(cx);
+            }
+            cx.notify();
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("the file has no column {}", missing.join(", ")))
+        }
+    }
+}
+
+/// Footer statistics for the column picked from the context menu.
+pub fn render_column_stats(
+    stats: &Result<ColumnStats, String>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.column_stats = None;
+            cx.notify();
+        },
+    );
+
+    let lines = match stats {
+        Ok(stats) => {
+            let optional = |value: &Option<String>| {
+                value.clone().unwrap_or_else(|| "not recorded".to_string())
+            };
+            vec![
+                format!("{} ({})", stats.column, stats.data_type),
+                format!(
+                    "Nulls: {}",
+                    stats
+                        .null_count
+                        .map_or_else(|| "not recorded".to_string(), |count| count.to_string())
+                ),
+                format!("Min: {}", optional(&stats.min)),
+                format!("Max: {}", optional(&stats.max)),
+                format!(
+                    "Size: {} bytes compressed, {} bytes uncompressed across {} row group(s)",
+                    stats.compressed_bytes, stats.uncompressed_bytes, stats.row_groups
+                ),
+            ]
+        }
+        Err(message) => vec![message.clone()],
+    };
+
+    div()
+        .flex()
+        .flex_row()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(div().flex().flex_col().flex_1().gap_1().children(
+            lines.into_iter().enumerate().map(|(index, line)| {
+                div()
+                    .when(index == 0, |this| this.font_medium())
+                    .child(line)
+            }),
+        ))
+        .child(
+            Button::new("close-column-stats")
+                .ghost()
+                .label("Close")
+                .on_click(close),
+        )
+}
@@ -0,0 +1,201 @@
+use gpui::{div, prelude::*, px, Action};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, StyledExt};
+
+use crate::PreviewView;
+
+/// Most cells the comparison strip holds; pinning another drops the oldest.
+const MAX_PINS: usize = 8;
+
+/// Width of one pinned cell in the comparison strip.
+const PIN_WIDTH: f32 = 220.0;
+
+/// Pin one cell's value to the comparison strip. `row` is a display row.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct PinCell {
+    pub row: usize,
+    pub column: usize,
+    pub value: String,
+}
+
+/// A cell kept in the comparison strip while the table scrolls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PinnedCell {
+    /// File row.
+    pub row: usize,
+    pub column: String,
+    pub value: String,
+}
+
+/// Cells pinned for comparison, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pins(Vec<PinnedCell>);
+
+impl Pins {
+    /// Pin `cell`, unless it already is, dropping the oldest pin when full.
+    pub fn pin(&mut self, cell: PinnedCell) {
+        if self
+            .0
+            .iter()
+            .any(|pinned| pinned.row == cell.row && pinned.column == cell.column)
+        {
+            return;
+        }
+        if self.0.len() == MAX_PINS {
+            self.0.remove(0);
+        }
+        self.0.push(cell);
+    }
+
+    pub fn unpin(&mut self, index: usize) {
+        if index < self.0.len() {
+            self.0.remove(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn cells(&self) -> &[PinnedCell] {
+        &self.0
+    }
+}
+
+/// Strip along the bottom of the window with the pinned cells side by side.
+pub fn render_compare_strip(
+    pins: &Pins,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let clear = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.pins.clear();
+            cx.notify();
+        },
+    );
+
+    div()
+        .flex()
+        .flex_row()
+        .items_start()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .id("pinned-cells")
+                .flex()
+                .flex_row()
+                .flex_1()
+                .gap_2()
+                .overflow_x_scroll()
+                .children(pins.cells().iter().enumerate().map(|(index, cell)| {
+                    let unpin = cx.listener(
+                        move |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                            view.pins.unpin(index);
+                            cx.notify();
+                        },
+                    );
+                    div()
+                        .flex()
+                        .flex_col()
+                        .flex_none()
+                        .w(px(PIN_WIDTH))
+                        .p_1()
+                        .border_1()
+                        .border_color(theme.border)
+                        .rounded(theme.radius)
+                        .child(
+                            div()
+                                .flex()
+                                .flex_row()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .truncate()
+                                        .text_color(theme.muted_foreground)
+                                        .child(format!("{} · row {}", cell.column, cell.row + 1)),
+                                )
+                                .child(
+                                    Button::new(("unpin-cell", index))
+                                        .ghost()
+                                        .xsmall()
+                                        .label("×")
+                                        .on_click(unpin),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .font_family("monospace")
+                                .font_medium()
+                                .whitespace_normal()
+                                .child(cell.value.clone()),
+                        )
+                })),
+        )
+        .child(
+            Button::new("clear-pins")
+                .ghost()
+                .label("Clear")
+                .on_click(clear),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn pin_cell(
+        &mut self,
+        action: &PinCell,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.pins.pin(PinnedCell {
+            row: self.file_row(action.row),
+            column: self.preview.columns[action.column].clone(),
+            value: action.value.clone(),
+        });
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(row: usize) -> PinnedCell {
+        PinnedCell {
+            row,
+            column: "price".to_string(),
+            value: row.to_string(),
+        }
+    }
+
+    #[test]
+    fn pinning_skips_duplicates_and_drops_the_oldest_when_full() {
+        let mut pins = Pins::default();
+        pins.pin(cell(1));
+        pins.pin(cell(1));
+        assert_eq!(pins.cells().len(), 1);
+
+        for row in 2..=MAX_PINS + 1 {
+            pins.pin(cell(row));
+        }
+        assert_eq!(pins.cells().len(), MAX_PINS);
+        assert_eq!(pins.cells()[0].row, 2);
+
+        pins.unpin(0);
+        assert_eq!(pins.cells()[0].row, 3);
+        pins.unpin(MAX_PINS);
+        assert_eq!(pins.cells().len(), MAX_PINS - 1);
+    }
+}
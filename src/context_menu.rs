@@ -0,0 +1,300 @@
+use gpui::{actions, Action};
+use gpui_component::menu::PopupMenu;
+use parquet_viewer_core::cast::ViewAs;
+
+use crate::compare::PinCell;
+use crate::heatmap::ToggleHeatMap;
+use crate::row_diff::MarkRowForDiff;
+use crate::word_wrap::ToggleWordWrap;
+
+actions!(
+    parquet_viewer,
+    [ShowAllColumns, ClearFilters, AddComputedColumn]
+);
+
+/// Copy one cell's text. `row` is a display row, `column` a file column index.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct CopyValue {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// Show one cell in full in the cell inspector. `row` is a display row.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct InspectCell {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// Copy one display row as tab-separated values.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct CopyRow {
+    pub row: usize,
+}
+
+/// Keep only rows whose cell in `column` shows `value`, or with `exclude`, hide them.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct FilterByValue {
+    pub column: usize,
+    pub value: String,
+    pub exclude: bool,
+}
+
+/// Ask for a regular expression and keep the rows of `column` that match it.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct FilterByPattern {
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SortByColumn {
+    pub column: usize,
+    pub descending: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct HideColumn {
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ShowColumnStats {
+    pub column: usize,
+}
+
+/// Add the column to the group-by explorer's keys.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct GroupByColumn {
+    pub column: usize,
+}
+
+/// Look for rows sharing the column's value, along with any columns already
+/// picked for the duplicate scan.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct FindDuplicatesByColumn {
+    pub column: usize,
+}
+
+/// Aggregate the column per group in the group-by explorer.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct AggregateInGroups {
+    pub column: usize,
+}
+
+/// Show the dictionary page of the column's first column chunk.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ShowDictionary {
+    pub column: usize,
+}
+
+/// Show how every row group stored the column: encodings, codec, sizes and statistics.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ShowChunkTimeline {
+    pub column: usize,
+}
+
+/// Show the column as another type, or as stored with `None`.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ViewColumnAs {
+    pub column: usize,
+    pub view: Option<ViewAs>,
+}
+
+/// Ask for a value and scroll to the first row at or past it in a sorted column.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct JumpToValue {
+    pub column: usize,
+}
+
+/// Drop a column added with [`AddComputedColumn`].
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct RemoveComputedColumn {
+    pub column: usize,
+}
+
+/// Types the right-clicked column can be viewed as, and the one it is viewed as now.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ViewAsChoices {
+    pub choices: Vec<ViewAs>,
+    pub current: Option<ViewAs>,
+}
+
+/// What the last right-click landed on, so the menu can be built for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContextTarget {
+    Cell {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    Header {
+        column: usize,
+    },
+}
+
+impl ContextTarget {
+    pub fn column(&self) -> usize {
+        match self {
+            ContextTarget::Cell { column, .. } | ContextTarget::Header { column } => *column,
+        }
+    }
+}
+
+pub fn build_menu(
+    menu: PopupMenu,
+    target: Option<&ContextTarget>,
+    view_as: &ViewAsChoices,
+    computed: bool,
+    sorted: bool,
+) -> PopupMenu {
+    match target {
+        Some(ContextTarget::Cell { row, column, value }) => {
+            let (row, column) = (*row, *column);
+            let menu = menu
+                .menu("Copy Value", Box::new(CopyValue { row, column }))
+                .menu("Copy Row", Box::new(CopyRow { row }))
+                .menu("Inspect Cell", Box::new(InspectCell { row, column }))
+                .menu("Mark Row for Diff", Box::new(MarkRowForDiff { row }))
+                .menu(
+                    "Pin to Compare",
+                    Box::new(PinCell {
+                        row,
+                        column,
+                        value: value.clone(),
+                    }),
+                )
+                .separator();
+            // The scan reads filtered columns from the file, which doesn't
+            // have computed ones.
+            let menu = if computed {
+                menu
+            } else {
+                menu.menu(
+                    "Filter by This Value",
+                    Box::new(FilterByValue {
+                        column,
+                        value: value.clone(),
+                        exclude: false,
+                    }),
+                )
+                .menu(
+                    "Exclude This Value",
+                    Box::new(FilterByValue {
+                        column,
+                        value: value.clone(),
+                        exclude: true,
+                    }),
+                )
+                .separator()
+            };
+            column_items(menu, column, view_as, computed, sorted)
+        }
+        Some(ContextTarget::Header { column }) => {
+            column_items(menu, *column, view_as, computed, sorted)
+        }
+        None => menu,
+    }
+}
+
+fn column_items(
+    menu: PopupMenu,
+    column: usize,
+    view_as: &ViewAsChoices,
+    computed: bool,
+    sorted: bool,
+) -> PopupMenu {
+    let menu = if computed {
+        menu
+    } else {
+        menu.menu(
+            "Sort Ascending",
+            Box::new(SortByColumn {
+                column,
+                descending: false,
+            }),
+        )
+        .menu(
+            "Sort Descending",
+            Box::new(SortByColumn {
+                column,
+                descending: true,
+            }),
+        )
+        .menu("Filter by Pattern…", Box::new(FilterByPattern { column }))
+    };
+    let menu = if sorted {
+        menu.menu("Jump to Value…", Box::new(JumpToValue { column }))
+    } else {
+        menu
+    };
+    let menu = menu
+        .menu("Hide Column", Box::new(HideColumn { column }))
+        .menu("Show Column Stats", Box::new(ShowColumnStats { column }))
+        .menu("Heat Map", Box::new(ToggleHeatMap { column }))
+        .menu("Wrap Text", Box::new(ToggleWordWrap { column }))
+        .menu("Group by This Column", Box::new(GroupByColumn { column }))
+        .menu(
+            "Aggregate in Groups",
+            Box::new(AggregateInGroups { column }),
+        )
+        .menu(
+            "Find Duplicates by Column",
+            Box::new(FindDuplicatesByColumn { column }),
+        )
+        .menu("Show Dictionary", Box::new(ShowDictionary { column }))
+        .menu(
+            "Show Chunk Timeline",
+            Box::new(ShowChunkTimeline { column }),
+        );
+    let menu = if view_as.choices.is_empty() {
+        menu
+    } else {
+        view_as.choices.iter().fold(
+            menu.separator().menu_with_check(
+                "View As Stored Type",
+                view_as.current.is_none(),
+                Box::new(ViewColumnAs { column, view: None }),
+            ),
+            |menu, &choice| {
+                menu.menu_with_check(
+                    format!("View As {choice}"),
+                    view_as.current == Some(choice),
+                    Box::new(ViewColumnAs {
+                        column,
+                        view: Some(choice),
+                    }),
+                )
+            },
+        )
+    };
+    let menu = menu
+        .separator()
+        .menu("Add Computed Column…", Box::new(AddComputedColumn));
+    let menu = if computed {
+        menu.menu(
+            "Remove Computed Column",
+            Box::new(RemoveComputedColumn { column }),
+        )
+    } else {
+        menu
+    };
+    menu.separator()
+        .menu("Show All Columns", Box::new(ShowAllColumns))
+        .menu("Clear Filters and Sort", Box::new(ClearFilters))
+}
@@ -0,0 +1,185 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+use crate::go_to_row::{parse_goto, StartPosition};
+
+/// URI scheme of links that open the viewer, e.g.
+/// `parquet-viewer://open?path=/data/events.parquet&row=100&cols=ts,user_id`.
+pub const SCHEME: &str = "parquet-viewer";
+
+/// A file to open and where to open it, from the command line or a
+/// `parquet-viewer://` link.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeepLink {
+    pub path: PathBuf,
+    pub start: Option<StartPosition>,
+    /// Columns to show, in this order; empty shows them as the file was last left.
+    pub columns: Vec<String>,
+}
+
+impl DeepLink {
+    pub fn is_link(text: &str) -> bool {
+        text.strip_prefix(SCHEME)
+            .is_some_and(|rest| rest.starts_with(':'))
+    }
+
+    /// Read a `parquet-viewer://open` link. `path` is required; `row` takes a
+    /// row as `--goto` does, `col` selects a cell in that row, and `cols` lists
+    /// the columns to show, separated by commas.
+    pub fn parse(text: &str) -> Result<DeepLink, String> {
+        let url = Url::parse(text).map_err(|error| format!("\"{text}\" is not a link: {error}"))?;
+        if url.scheme() != SCHEME || url.host_str() != Some("open") {
+            return Err(format!("\"{text}\" is not a {SCHEME}://open link"));
+        }
+
+        let mut link = DeepLink::default();
+        let mut row = None;
+        let mut column = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "path" => link.path = PathBuf::from(value.as_ref()),
+                "row" => row = Some(value.into_owned()),
+                "col" => column = Some(value.into_owned()),
+                "cols" => {
+                    link.columns = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|column| !column.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                other => tracing::warn!(parameter = other, "ignoring unknown link parameter"),
+            }
+        }
+        if link.path.as_os_str().is_empty() {
+            return Err(format!(
+                "\"{text}\" doesn't say which file to open (path=...)"
+            ));
+        }
+        if let Some(row) = row {
+            link.start = Some(StartPosition {
+                column,
+                ..parse_goto(&row)?
+            });
+        } else if column.is_some() {
+            return Err(format!("\"{text}\" selects a column without a row"));
+        }
+        Ok(link)
+    }
+}
+
+/// Desktop entry that makes the viewer at `exe` the handler of `parquet-viewer://` links.
+pub fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Parquet Viewer\n\
+         Exec=\"{}\" %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{SCHEME};\n",
+        exe.display()
+    )
+}
+
+/// Register the running executable as the handler of `parquet-viewer://` links
+/// for the current user, and say what was done. macOS reads the scheme from the
+/// app bundle's `Info.plist` instead, so there it only explains that.
+pub fn register() -> io::Result<String> {
+    let exe = std::env::current_exe()?;
+    register_for(&exe)
+}
+
+#[cfg(target_os = "linux")]
+fn register_for(exe: &Path) -> io::Result<String> {
+    let applications = dirs::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?
+        .join("applications");
+    std::fs::create_dir_all(&applications)?;
+    let entry = applications.join("parquet-viewer.desktop");
+    std::fs::write(&entry, desktop_entry(exe))?;
+    run(std::process::Command::new("xdg-mime").args([
+        "default",
+        "parquet-viewer.desktop",
+        &format!("x-scheme-handler/{SCHEME}"),
+    ]))?;
+    Ok(format!(
+        "Wrote {} and made it the handler of {SCHEME}:// links",
+        entry.display()
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn register_for(exe: &Path) -> io::Result<String> {
+    let key = format!(r"HKCU\Software\Classes\{SCHEME}");
+    let open_key = format!(r"{key}\shell\open\command");
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    for args in [
+        vec![key.as_str(), "/ve", "/d", "URL:Parquet Viewer link"],
+        vec![key.as_str(), "/v", "URL Protocol", "/d", ""],
+        vec![open_key.as_str(), "/ve", "/d", command.as_str()],
+    ] {
+        run(std::process::Command::new("reg")
+            .arg("add")
+            .args(args)
+            .arg("/f"))?;
+    }
+    Ok(format!(
+        "Registered {} for {SCHEME}:// links",
+        exe.display()
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn register_for(_exe: &Path) -> io::Result<String> {
+    Ok(format!(
+        "The app bundle registers {SCHEME}:// links; build it with `cargo bundle --release` \
+         and open it once"
+    ))
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn run(command: &mut std::process::Command) -> io::Result<()> {
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{command:?} failed with {status}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_name_a_file_row_and_columns() {
+        let link = DeepLink::parse(
+            "parquet-viewer://open?path=%2Fdata%2Fmy%20events.parquet&row=5,432,101&col=user_id&cols=ts,%20user_id",
+        )
+        .expect("link should parse");
+        assert_eq!(
+            link,
+            DeepLink {
+                path: PathBuf::from("/data/my events.parquet"),
+                start: Some(StartPosition {
+                    row: "5,432,101".to_string(),
+                    column: Some("user_id".to_string()),
+                }),
+                columns: vec!["ts".to_string(), "user_id".to_string()],
+            }
+        );
+        assert!(DeepLink::is_link("parquet-viewer://open?path=a"));
+        assert!(!DeepLink::is_link("parquet-viewer.parquet"));
+
+        assert!(DeepLink::parse("parquet-viewer://open?row=1").is_err());
+        assert!(DeepLink::parse("parquet-viewer://delete?path=a").is_err());
+        assert!(DeepLink::parse("parquet-viewer://open?path=a&row=0").is_err());
+        assert!(desktop_entry(Path::new("/usr/bin/parquet_viewer"))
+            .contains("Exec=\"/usr/bin/parquet_viewer\" %u\n"));
+    }
+}
@@ -0,0 +1,316 @@
+use gpui::{actions, div, prelude::*, px};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, StyledExt};
+use parquet_viewer_core::duplicates::{find_duplicates, Duplicates};
+use parquet_viewer_core::progress::Progress;
+
+use crate::context_menu::FindDuplicatesByColumn;
+use crate::PreviewView;
+
+actions!(parquet_viewer, [FindDuplicateRows]);
+
+/// Duplicated keys listed in the panel; the table shows all of their rows.
+const KEYS_SHOWN: usize = 100;
+
+/// File rows listed per key before the rest are summarized as a count.
+const ROWS_SHOWN: usize = 10;
+
+/// Tallest the key list grows before it scrolls.
+const KEY_LIST_HEIGHT: f32 = 240.0;
+
+/// A duplicate scan over the whole file and whether the table is narrowed to
+/// its rows.
+#[derive(Default)]
+pub struct DuplicatesPanel {
+    /// Columns rows are compared on; empty compares whole rows.
+    pub columns: Vec<String>,
+    pub result: Option<Result<Duplicates, String>>,
+    /// Whether the table shows only the duplicated rows.
+    pub showing_duplicates: bool,
+    /// Progress of the running scan, cancelled when it is replaced.
+    pub progress: Option<Progress>,
+}
+
+impl DuplicatesPanel {
+    pub fn cancel(&mut self) {
+        if let Some(progress) = self.progress.take() {
+            progress.cancel();
+        }
+    }
+}
+
+impl Drop for DuplicatesPanel {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Panel with the compared columns, counts of the duplicated rows and the most
+/// duplicated keys, and a button narrowing the table to the duplicated rows.
+pub fn render_duplicates_panel(
+    panel: &DuplicatesPanel,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.close_duplicates(cx);
+        },
+    );
+
+    let columns = div()
+        .flex()
+        .flex_row()
+        .flex_wrap()
+        .items_center()
+        .gap_1()
+        .child("Compared on")
+        .when(panel.columns.is_empty(), |this| this.child("all columns"))
+        .children(panel.columns.iter().enumerate().map(|(index, column)| {
+            let remove = cx.listener(
+                move |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                    view.remove_duplicate_column(index, cx);
+                },
+            );
+            Button::new(("duplicate-column", index))
+                .ghost()
+                .xsmall()
+                .label(format!("{column} ×"))
+                .on_click(remove)
+        }));
+
+    let (title, body) = match &panel.result {
+        None => (
+            "Duplicates".to_string(),
+            div()
+                .text_color(theme.muted_foreground)
+                .child("Looking for duplicates…")
+                .into_any_element(),
+        ),
+        Some(Err(message)) => (
+            "Duplicates".to_string(),
+            div().child(message.clone()).into_any_element(),
+        ),
+        Some(Ok(duplicates)) => {
+            let hidden = duplicates.keys.len().saturating_sub(KEYS_SHOWN);
+            let body = div()
+                .id("duplicate-keys")
+                .flex()
+                .flex_col()
+                .max_h(px(KEY_LIST_HEIGHT))
+                .overflow_y_scroll()
+                .children(duplicates.keys.iter().take(KEYS_SHOWN).map(|key| {
+                    let mut rows = key
+                        .rows
+                        .iter()
+                        .take(ROWS_SHOWN)
+                        .map(|row| (row + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    if key.rows.len() > ROWS_SHOWN {
+                        rows.push_str(&format!(", … {} more", key.rows.len() - ROWS_SHOWN));
+                    }
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_2()
+                        .px_1()
+                        .child(
+                            div()
+                                .w(px(80.0))
+                                .flex_none()
+                                .child(format!("{} rows", key.rows.len())),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .truncate()
+                                .child(key.key.join(" · ")),
+                        )
+                        .child(
+                            div()
+                                .flex_none()
+                                .text_color(theme.muted_foreground)
+                                .child(format!("rows {rows}")),
+                        )
+                }))
+                .when(hidden > 0, |this| {
+                    this.child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child(format!("… and {hidden} more keys")),
+                    )
+                })
+                .into_any_element();
+            (duplicates.summary(), body)
+        }
+    };
+    let has_duplicates =
+        matches!(&panel.result, Some(Ok(duplicates)) if !duplicates.keys.is_empty());
+    let toggle = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_only_duplicates(cx);
+        },
+    );
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().font_medium().child(title))
+                .child(div().flex_1().child(columns))
+                .when(has_duplicates || panel.showing_duplicates, |this| {
+                    this.child(
+                        Button::new("only-duplicates")
+                            .ghost()
+                            .label(if panel.showing_duplicates {
+                                "Show All Rows"
+                            } else {
+                                "Show Only Duplicates"
+                            })
+                            .on_click(toggle),
+                    )
+                })
+                .child(
+                    Button::new("close-duplicates")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(body)
+}
+
+impl PreviewView {
+    /// Look for rows duplicated across every column.
+    pub(crate) fn find_duplicate_rows(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.duplicates
+            .get_or_insert_with(DuplicatesPanel::default)
+            .columns
+            .clear();
+        self.refresh_duplicates(cx);
+    }
+
+    pub(crate) fn find_duplicates_by_column(
+        &mut self,
+        action: &FindDuplicatesByColumn,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        let panel = self.duplicates.get_or_insert_with(DuplicatesPanel::default);
+        if !panel.columns.contains(&column) {
+            panel.columns.push(column);
+        }
+        self.refresh_duplicates(cx);
+    }
+
+    pub(crate) fn remove_duplicate_column(
+        &mut self,
+        index: usize,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if let Some(panel) = &mut self.duplicates {
+            if index < panel.columns.len() {
+                panel.columns.remove(index);
+            }
+        }
+        self.refresh_duplicates(cx);
+    }
+
+    /// Close the duplicates panel, showing every row again if the table was
+    /// narrowed to the duplicates.
+    pub(crate) fn close_duplicates(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        if let Some(panel) = self.duplicates.take() {
+            if panel.showing_duplicates {
+                self.update_row_order(Vec::new(), None, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Narrow the table to the duplicated rows, each key's rows next to each
+    /// other, or go back to showing every row. Narrowing replaces the filters and
+    /// sort, since the rows no longer follow them.
+    pub(crate) fn toggle_only_duplicates(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        let Some(panel) = &self.duplicates else {
+            return;
+        };
+        if panel.showing_duplicates {
+            self.update_row_order(Vec::new(), None, cx);
+            return;
+        }
+        let Some(Ok(duplicates)) = &panel.result else {
+            return;
+        };
+        let rows = duplicates.rows();
+        self.apply_row_order(Vec::new(), None, Some(rows));
+        if let Some(panel) = &mut self.duplicates {
+            panel.showing_duplicates = true;
+        }
+        self.load_visible_rows(0, cx);
+        self.refresh_summary(cx);
+        cx.notify();
+    }
+
+    /// Scan the whole file for duplicates on the panel's columns in the background.
+    fn refresh_duplicates(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        // The rows shown were the old scan's duplicates.
+        if self
+            .duplicates
+            .as_ref()
+            .is_some_and(|panel| panel.showing_duplicates)
+        {
+            self.update_row_order(Vec::new(), None, cx);
+        }
+        let Some(panel) = &mut self.duplicates else {
+            return;
+        };
+        panel.cancel();
+        panel.result = None;
+        let progress = Progress::default();
+        panel.progress = Some(progress.clone());
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let columns = panel.columns.clone();
+            let progress = progress.clone();
+            async move { find_duplicates(&path, &columns, &bytes_read, &progress) }
+        });
+        cx.spawn(async move |view, cx| {
+            let duplicates = task.await;
+            view.update(cx, |view, cx| {
+                // Cancelled scans were for columns the panel no longer compares.
+                if progress.is_cancelled() {
+                    return;
+                }
+                let Some(panel) = &mut view.duplicates else {
+                    return;
+                };
+                panel.progress = None;
+                panel.result = Some(duplicates.map_err(|error| {
+                    tracing::error!(%error, "failed to find duplicates");
+                    format!("Duplicates could not be found: {error}")
+                }));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+}
@@ -0,0 +1,172 @@
+use gpui::{actions, div, prelude::*, px, Action};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, StyledExt};
+use parquet_viewer_core::explain::ScanReport;
+use parquet_viewer_core::pushdown::RowGroupPlan;
+
+use crate::history::HistoryEntry;
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ToggleQueryExplain]);
+
+/// Tallest the row group list of the explain panel grows before it scrolls.
+const ROW_GROUP_LIST_HEIGHT: f32 = 160.0;
+
+/// Show what the scan of a history entry pruned and what it cost.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ExplainQuery {
+    pub index: usize,
+}
+
+/// One row group of an explained scan, e.g. `#3  0 of 1000 rows decoded, ruled
+/// out by statistics` or `#4  250 of 1000 rows decoded, 3 of 4 pages skipped`.
+pub fn describe_row_group(plan: &RowGroupPlan) -> String {
+    let decoded = format!(
+        "#{}  {} of {} rows decoded",
+        plan.row_group, plan.rows_to_read, plan.rows
+    );
+    if plan.rows_to_read == 0 {
+        format!("{decoded}, ruled out by statistics")
+    } else if plan.pages_skipped > 0 {
+        format!(
+            "{decoded}, {} of {} pages skipped",
+            plan.pages_skipped, plan.pages
+        )
+    } else {
+        decoded
+    }
+}
+
+/// The scan behind a history entry: the summary of what was pruned, read and
+/// how long it took, how each filter was evaluated, and every row group.
+pub fn render_explain_panel(
+    entry: &HistoryEntry,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let report: &ScanReport = &entry.report;
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_query_explain(cx);
+        },
+    );
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_none().font_semibold().child("Explain"))
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .truncate()
+                        .font_family("monospace")
+                        .child(entry.query.clone()),
+                )
+                .child(
+                    Button::new("close-query-explain")
+                        .ghost()
+                        .xsmall()
+                        .label("×")
+                        .on_click(close),
+                ),
+        )
+        .children(report.summary().into_iter().map(|line| div().child(line)))
+        .children(report.filters.iter().map(|(filter, check)| {
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(
+                    div()
+                        .flex_none()
+                        .font_family("monospace")
+                        .child(filter.clone()),
+                )
+                .child(
+                    div()
+                        .text_color(theme.muted_foreground)
+                        .child(check.describe()),
+                )
+        }))
+        .when(!report.row_groups.is_empty(), |this| {
+            this.child(
+                div()
+                    .id("explain-row-groups")
+                    .flex()
+                    .flex_col()
+                    .max_h(px(ROW_GROUP_LIST_HEIGHT))
+                    .overflow_y_scroll()
+                    .text_color(theme.muted_foreground)
+                    .font_family("monospace")
+                    .children(
+                        report
+                            .row_groups
+                            .iter()
+                            .map(|plan| div().child(describe_row_group(plan))),
+                    ),
+            )
+        })
+}
+
+impl PreviewView {
+    pub(crate) fn explain_query(
+        &mut self,
+        action: &ExplainQuery,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if action.index < self.history.entries().len() {
+            self.explained_query = Some(action.index);
+            cx.notify();
+        }
+    }
+
+    /// Show or hide the explain panel, opening it on the last query run.
+    pub(crate) fn toggle_query_explain(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.explained_query = match self.explained_query {
+            Some(_) => None,
+            None => self.history.entries().len().checked_sub(1),
+        };
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_groups_say_what_was_skipped() {
+        let mut plan = RowGroupPlan {
+            row_group: 3,
+            rows: 1000,
+            ..RowGroupPlan::default()
+        };
+        assert_eq!(
+            describe_row_group(&plan),
+            "#3  0 of 1000 rows decoded, ruled out by statistics"
+        );
+        plan.rows_to_read = 250;
+        plan.pages = 4;
+        plan.pages_skipped = 3;
+        assert_eq!(
+            describe_row_group(&plan),
+            "#3  250 of 1000 rows decoded, 3 of 4 pages skipped"
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use gpui::ClipboardItem;
+use gpui_component::WindowExt;
+use parquet::basic::Compression;
+use parquet_viewer_core::export::export_rows;
+use parquet_viewer_core::intern::Cell;
+use parquet_viewer_core::progress::Progress;
+use parquet_viewer_core::rewrite::RewriteOptions;
+use parquet_viewer_core::selection::{delimited_record, to_markdown, ClipboardFormat};
+use parquet_viewer_core::xlsx::XLSX_ROW_LIMIT;
+use parquet_viewer_core::ViewerError;
+
+use crate::context_menu::{CopyRow, CopyValue};
+use crate::PreviewView;
+
+/// What an export writes: the selected rows as CSV, as Parquet with a codec,
+/// or as an Excel workbook.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExportFormat {
+    Csv,
+    Parquet(Compression),
+    Xlsx,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet(_) => "parquet",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl PreviewView {
+    pub(crate) fn copy_value(
+        &mut self,
+        action: &CopyValue,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        match self.rows_for_display(action.row..action.row + 1) {
+            Ok(window) => {
+                if let Some(row) = window.rows.first() {
+                    cx.write_to_clipboard(ClipboardItem::new_string(
+                        row[action.column].to_string(),
+                    ));
+                }
+            }
+            Err(error) => tracing::error!(%error, "failed to copy value"),
+        }
+    }
+
+    pub(crate) fn copy_row(
+        &mut self,
+        action: &CopyRow,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        match self.rows_for_display(action.row..action.row + 1) {
+            Ok(window) => {
+                if let Some(row) = window.rows.first() {
+                    let fields: Vec<Cell> = self
+                        .displayed_columns()
+                        .into_iter()
+                        .map(|column| row[column].clone())
+                        .collect();
+                    cx.write_to_clipboard(ClipboardItem::new_string(delimited_record(
+                        &fields, '\t',
+                    )));
+                }
+            }
+            Err(error) => tracing::error!(%error, "failed to copy row"),
+        }
+    }
+
+    /// Copy the selected rows to the clipboard as tab-separated text.
+    pub(crate) fn copy_selection(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.copy_selection_as(ClipboardFormat::Tsv, window, cx);
+    }
+
+    /// Copy the selected rows with a header to the clipboard in `format`.
+    pub(crate) fn copy_selection_as(
+        &mut self,
+        format: ClipboardFormat,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        match self.selected_table_rows() {
+            Ok(Some(rows)) => {
+                let text = format.format(&self.displayed_header(), &rows);
+                cx.write_to_clipboard(ClipboardItem::new_string(text));
+            }
+            Ok(None) => {}
+            Err(error) => {
+                tracing::error!(%error, "failed to copy selected rows");
+                window.push_notification(format!("Copying failed: {error}"), cx);
+            }
+        }
+    }
+
+    /// Copy the selected rows, or the rows in view if none are selected, to the
+    /// clipboard as a Markdown table for pasting into issues and pull requests.
+    pub(crate) fn copy_view_as_markdown(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let rows = match self.selected_table_rows() {
+            Ok(Some(rows)) => rows,
+            Ok(None) => {
+                let columns = self.displayed_columns();
+                self.visible_rows
+                    .iter()
+                    .map(|row| columns.iter().map(|&column| row[column].clone()).collect())
+                    .collect()
+            }
+            Err(error) => {
+                tracing::error!(%error, "failed to copy rows as Markdown");
+                window.push_notification(format!("Copying failed: {error}"), cx);
+                return;
+            }
+        };
+        let text = to_markdown(&self.displayed_header(), &rows);
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+    }
+
+    /// Ask for a destination and write the selected rows there as CSV.
+    pub(crate) fn export_selection(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if self.selected_rows.is_empty() {
+            return;
+        }
+        self.prompt_for_export(ExportFormat::Csv, window, cx);
+    }
+
+    /// Ask for a destination and write the selected rows, or every row the
+    /// filter shows if none are selected, there as Parquet.
+    pub(crate) fn export_parquet(
+        &mut self,
+        compression: Compression,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if self.display_row_count() == 0 {
+            return;
+        }
+        self.prompt_for_export(ExportFormat::Parquet(compression), window, cx);
+    }
+
+    /// Ask for a destination and write the selected rows, or every row the
+    /// filter shows if none are selected, there as an Excel workbook, unless
+    /// they are more than [`XLSX_ROW_LIMIT`].
+    pub(crate) fn export_xlsx(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let rows = if self.selected_rows.is_empty() {
+            self.display_row_count()
+        } else {
+            self.selected_rows.len()
+        };
+        if rows == 0 {
+            return;
+        }
+        if rows > XLSX_ROW_LIMIT {
+            window.push_notification(
+                format!(
+                    "{rows} rows are too many for Excel; select or filter down to {XLSX_ROW_LIMIT} or export to CSV or Parquet"
+                ),
+                cx,
+            );
+            return;
+        }
+        self.prompt_for_export(ExportFormat::Xlsx, window, cx);
+    }
+
+    /// File rows of the selected rows, or of every row the filter shows if
+    /// none are selected, in display order.
+    fn rows_to_export(&self) -> Vec<usize> {
+        if self.selected_rows.is_empty() {
+            (0..self.display_row_count())
+                .map(|row| self.file_row(row))
+                .collect()
+        } else {
+            self.selected_rows
+                .runs()
+                .into_iter()
+                .flatten()
+                .map(|row| self.file_row(row))
+                .collect()
+        }
+    }
+
+    fn prompt_for_export(
+        &mut self,
+        format: ExportFormat,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let path = &self.preview.path;
+        let directory = path.parent().unwrap_or(Path::new("."));
+        let suggested_name = format!(
+            "{}-selection.{}",
+            path.file_stem().unwrap_or_default().to_string_lossy(),
+            format.extension()
+        );
+        let destination = cx.prompt_for_new_path(directory, Some(&suggested_name));
+
+        cx.spawn_in(window, async move |view, cx| {
+            let destination = match destination.await {
+                Ok(Ok(Some(destination))) => destination,
+                Ok(Err(error)) => {
+                    tracing::error!(?error, "failed to show the save dialog");
+                    return;
+                }
+                _ => return,
+            };
+
+            view.update(cx, |view, cx| view.start_export(destination, format, cx))
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Write the rows to export to `destination` in the background, showing progress.
+    fn start_export(
+        &mut self,
+        destination: PathBuf,
+        format: ExportFormat,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let columns = self.displayed_columns();
+        let progress = Progress::default();
+        let export = match format {
+            ExportFormat::Csv => {
+                let runs: Vec<Range<usize>> = self
+                    .selected_rows
+                    .runs()
+                    .into_iter()
+                    .flat_map(|run| self.file_runs(run))
+                    .collect();
+                progress.set_total(self.selected_rows.len());
+                self.start_operation("Exporting", "rows", progress.clone(), cx);
+                cx.background_spawn({
+                    let preview = self.preview.clone();
+                    let progress = progress.clone();
+                    let destination = destination.clone();
+                    async move { export_rows(&preview, &runs, &columns, &destination, &progress) }
+                })
+            }
+            ExportFormat::Parquet(compression) => {
+                let rows = self.rows_to_export();
+                let options = RewriteOptions {
+                    compression,
+                    ..RewriteOptions::default()
+                };
+                self.start_operation("Exporting", "row groups", progress.clone(), cx);
+                cx.background_spawn({
+                    let path = self.preview.path.clone();
+                    let bytes_read = self.preview.bytes_read.clone();
+                    let progress = progress.clone();
+                    let destination = destination.clone();
+                    async move {
+                        export_parquet(
+                            &path,
+                            &rows,
+                            &columns,
+                            &destination,
+                            &options,
+                            &bytes_read,
+                            &progress,
+                        )
+                    }
+                })
+            }
+            ExportFormat::Xlsx => {
+                let rows = self.rows_to_export();
+                self.start_operation("Exporting", "row groups", progress.clone(), cx);
+                cx.background_spawn({
+                    let path = self.preview.path.clone();
+                    let bytes_read = self.preview.bytes_read.clone();
+                    let progress = progress.clone();
+                    let destination = destination.clone();
+                    async move {
+                        export_xlsx(&path, &rows, &columns, &destination, &bytes_read, &progress)
+                    }
+                })
+            }
+        };
+
+        cx.spawn(async move |view, cx| {
+            let result = export.await;
+            if !progress.is_cancelled() {
+                view.update(cx, |view, cx| {
+                    view.operation = None;
+                    cx.notify();
+                })
+                .ok();
+            }
+            match result {
+                Ok(()) | Err(ViewerError::Cancelled) => {}
+                Err(error) => {
+                    tracing::error!(%error, path = %destination.to_string_lossy(), "failed to export rows");
+                }
+            }
+        })
+        .detach();
+    }
+}
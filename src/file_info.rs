@@ -0,0 +1,714 @@
+use std::path::Path;
+
+use gpui::{actions, div, prelude::*, px, relative, ClipboardItem};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Disableable, Sizable, StyledExt};
+use parquet_viewer_core::dictionary::{dictionary_chunk, DictionaryChunk};
+use parquet_viewer_core::doctor::{writer_report, WriterReport};
+use parquet_viewer_core::metadata::{metadata_entries, MetadataEntries};
+use parquet_viewer_core::metrics::format_bytes;
+use parquet_viewer_core::sizes::{size_breakdown, ChunkSize, SizeBreakdown, SizeEntry};
+use parquet_viewer_core::timeline::{column_timeline, ColumnTimeline};
+use parquet_viewer_core::ViewerError;
+
+use crate::context_menu::{ShowChunkTimeline, ShowDictionary};
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ToggleMetadataPanel, ToggleSizePanel]);
+
+/// Tallest the metadata and size panels grow before they scroll.
+const METADATA_PANEL_HEIGHT: f32 = 320.0;
+
+/// Dictionary entries listed before the rest are summarized as a count.
+const DICTIONARY_ENTRIES_SHOWN: usize = 1000;
+
+/// Width of the label column of the size panel's bars.
+const SIZE_LABEL_WIDTH: f32 = 220.0;
+
+/// Widths of the chunk timeline's fixed columns: row group, rows, codec and sizes.
+const TIMELINE_NARROW_WIDTH: f32 = 60.0;
+const TIMELINE_WIDE_WIDTH: f32 = 150.0;
+
+/// What the metadata panel shows about a file.
+pub struct FileInfo {
+    pub report: WriterReport,
+    pub entries: MetadataEntries,
+}
+
+impl FileInfo {
+    pub fn load(path: &Path) -> Result<FileInfo, ViewerError> {
+        Ok(FileInfo {
+            report: writer_report(path)?,
+            entries: metadata_entries(path)?,
+        })
+    }
+}
+
+/// Panel with a report of how the file was written, then the footer key-value
+/// metadata and the Arrow schema metadata, with a copy button per entry and one
+/// for everything.
+pub fn render_metadata_panel(
+    info: &Result<FileInfo, String>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_metadata_panel(cx);
+        },
+    );
+
+    let body = match info {
+        Ok(FileInfo { report, entries }) => div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child("How this file was written"),
+                    )
+                    .children(report.lines().into_iter().map(|line| div().child(line))),
+            )
+            .when(entries.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_color(theme.muted_foreground)
+                        .child("This file has no key-value or schema metadata."),
+                )
+            })
+            .when(!entries.is_empty(), |this| {
+                this.child(render_section(
+                    "Footer key-value metadata",
+                    &entries.key_values,
+                    0,
+                    cx,
+                ))
+                .child(render_section(
+                    "Arrow schema metadata",
+                    &entries.schema,
+                    entries.key_values.len(),
+                    cx,
+                ))
+            })
+            .into_any_element(),
+        Err(message) => div().child(message.clone()).into_any_element(),
+    };
+    let all = info.as_ref().ok().map(|info| {
+        let mut text = info.report.lines().join("\n");
+        text.push_str("\n\n");
+        text.push_str(&info.entries.to_text());
+        text
+    });
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child("File metadata"))
+                .when_some(all, |this, all| {
+                    this.child(
+                        Button::new("copy-all-metadata")
+                            .ghost()
+                            .label("Copy All")
+                            .on_click(move |_, _, cx| {
+                                cx.write_to_clipboard(ClipboardItem::new_string(all.clone()))
+                            }),
+                    )
+                })
+                .child(
+                    Button::new("close-metadata")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(
+            div()
+                .id("metadata-entries")
+                .max_h(px(METADATA_PANEL_HEIGHT))
+                .overflow_y_scroll()
+                .child(body),
+        )
+}
+
+/// One titled list of entries; `first_id` keeps the copy buttons' ids unique
+/// across sections.
+fn render_section(
+    title: &'static str,
+    entries: &[(String, String)],
+    first_id: usize,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(
+            div()
+                .text_color(theme.muted_foreground)
+                .child(format!("{title} ({})", entries.len())),
+        )
+        .children(entries.iter().enumerate().map(|(index, (key, value))| {
+            let value_text = value.clone();
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .child(
+                    div()
+                        .w(px(180.0))
+                        .flex_none()
+                        .font_medium()
+                        .child(key.clone()),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .font_family("monospace")
+                        .whitespace_normal()
+                        .child(value.clone()),
+                )
+                .child(
+                    Button::new(("copy-metadata", first_id + index))
+                        .ghost()
+                        .xsmall()
+                        .label("Copy")
+                        .on_click(move |_, _, cx| {
+                            cx.write_to_clipboard(ClipboardItem::new_string(value_text.clone()))
+                        }),
+                )
+        }))
+}
+
+/// Panel with bars of the compressed bytes of each column, largest first, and of
+/// each row group, then the compression ratio of every column chunk.
+pub fn render_size_panel(
+    breakdown: &Result<SizeBreakdown, String>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_size_panel(cx);
+        },
+    );
+
+    let (title, body) = match breakdown {
+        Ok(breakdown) => (
+            format!("File size: {}", breakdown.summary()),
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .child(render_size_bars(
+                    "By column",
+                    &breakdown.columns,
+                    breakdown.compressed,
+                    cx,
+                ))
+                .child(render_size_bars(
+                    "By row group",
+                    &breakdown.row_groups,
+                    breakdown.compressed,
+                    cx,
+                ))
+                .child(render_chunk_ratios(&breakdown.chunks, cx))
+                .into_any_element(),
+        ),
+        Err(message) => (
+            "File size".to_string(),
+            div().child(message.clone()).into_any_element(),
+        ),
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child(title))
+                .child(
+                    Button::new("close-sizes")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(
+            div()
+                .id("size-bars")
+                .max_h(px(METADATA_PANEL_HEIGHT))
+                .overflow_y_scroll()
+                .child(body),
+        )
+}
+
+fn render_size_bars(
+    title: &'static str,
+    entries: &[SizeEntry],
+    total: u64,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(div().text_color(theme.muted_foreground).child(title))
+        .children(entries.iter().map(|entry| {
+            let share = entry.share(total);
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(
+                    div()
+                        .w(px(SIZE_LABEL_WIDTH))
+                        .flex_none()
+                        .truncate()
+                        .child(entry.label.clone()),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .h(px(10.0))
+                        .rounded(theme.radius)
+                        .bg(theme.muted)
+                        .child(
+                            div()
+                                .h_full()
+                                .w(relative(share as f32))
+                                .rounded(theme.radius)
+                                .bg(theme.primary),
+                        ),
+                )
+                .child(div().w(px(200.0)).flex_none().child(format!(
+                    "{} ({:.1}%) · {:.2}x",
+                    format_bytes(entry.compressed),
+                    share * 100.0,
+                    entry.ratio()
+                )))
+        }))
+}
+
+/// Compressed and uncompressed size, codec and ratio of every column chunk, with
+/// the chunks that barely compress highlighted as candidates for another codec or
+/// dictionary encoding.
+fn render_chunk_ratios(
+    chunks: &[ChunkSize],
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .child(
+            div()
+                .text_color(theme.muted_foreground)
+                .child("Compression by column chunk"),
+        )
+        .children(chunks.iter().map(|chunk| {
+            let dictionary = if chunk.dictionary {
+                "dictionary"
+            } else {
+                "no dictionary"
+            };
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .when(chunk.barely_compressed(), |this| {
+                    this.text_color(theme.warning)
+                })
+                .child(
+                    div()
+                        .w(px(SIZE_LABEL_WIDTH))
+                        .flex_none()
+                        .truncate()
+                        .child(format!("{} · row group {}", chunk.column, chunk.row_group)),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .child(format!("{} · {dictionary}", chunk.codec)),
+                )
+                .child(div().w(px(200.0)).flex_none().child(format!(
+                    "{} → {} · {:.2}x",
+                    format_bytes(chunk.uncompressed),
+                    format_bytes(chunk.compressed),
+                    chunk.ratio()
+                )))
+        }))
+}
+
+/// Panel with the dictionary entries of one column chunk, its cardinality and
+/// how many data pages fell back to plain encoding, with buttons to step
+/// through the column's row groups.
+pub fn render_dictionary_panel(
+    chunk: &Result<DictionaryChunk, String>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.dictionary_panel = None;
+            cx.notify();
+        },
+    );
+
+    let (title, body) = match chunk {
+        Ok(chunk) => {
+            let entries = chunk.dictionary.as_deref().unwrap_or_default();
+            let hidden = entries.len().saturating_sub(DICTIONARY_ENTRIES_SHOWN);
+            (
+                format!(
+                    "Dictionary of {}, row group {} of {}",
+                    chunk.column,
+                    chunk.row_group + 1,
+                    chunk.row_groups
+                ),
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child(chunk.summary()),
+                    )
+                    .children(
+                        entries
+                            .iter()
+                            .take(DICTIONARY_ENTRIES_SHOWN)
+                            .enumerate()
+                            .map(|(index, value)| {
+                                div()
+                                    .flex()
+                                    .flex_row()
+                                    .gap_2()
+                                    .child(
+                                        div()
+                                            .w(px(60.0))
+                                            .flex_none()
+                                            .text_color(theme.muted_foreground)
+                                            .child(index.to_string()),
+                                    )
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .min_w_0()
+                                            .font_family("monospace")
+                                            .truncate()
+                                            .child(value.clone()),
+                                    )
+                            }),
+                    )
+                    .when(hidden > 0, |this| {
+                        this.child(
+                            div()
+                                .text_color(theme.muted_foreground)
+                                .child(format!("… and {hidden} more")),
+                        )
+                    })
+                    .into_any_element(),
+            )
+        }
+        Err(message) => (
+            "Dictionary".to_string(),
+            div().child(message.clone()).into_any_element(),
+        ),
+    };
+    let step = chunk.as_ref().ok().map(|chunk| {
+        (
+            chunk.column.clone(),
+            chunk.row_group,
+            chunk.row_group + 1 < chunk.row_groups,
+        )
+    });
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child(title))
+                .when_some(step, |this, (column, row_group, has_next)| {
+                    let previous_column = column.clone();
+                    this.child(
+                        Button::new("dictionary-previous")
+                            .ghost()
+                            .label("Previous Row Group")
+                            .disabled(row_group == 0)
+                            .on_click(cx.listener(move |view, _, _window, cx| {
+                                view.show_dictionary_chunk(
+                                    &previous_column,
+                                    row_group.saturating_sub(1),
+                                    cx,
+                                );
+                            })),
+                    )
+                    .child(
+                        Button::new("dictionary-next")
+                            .ghost()
+                            .label("Next Row Group")
+                            .disabled(!has_next)
+                            .on_click(cx.listener(move |view, _, _window, cx| {
+                                view.show_dictionary_chunk(&column, row_group + 1, cx);
+                            })),
+                    )
+                })
+                .child(
+                    Button::new("close-dictionary")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(
+            div()
+                .id("dictionary-entries")
+                .max_h(px(METADATA_PANEL_HEIGHT))
+                .overflow_y_scroll()
+                .child(body),
+        )
+}
+
+/// Panel with one line per row group showing how it stored a column, so that a
+/// writer changing course, e.g. falling back from the dictionary after a few row
+/// groups, stands out. Row groups stored differently from the one before are
+/// highlighted.
+pub fn render_timeline_panel(
+    timeline: &Result<ColumnTimeline, String>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.timeline_panel = None;
+            cx.notify();
+        },
+    );
+    let row = |cells: [String; 6]| {
+        let [row_group, rows, encoding, codec, sizes, stats] = cells;
+        div()
+            .flex()
+            .flex_row()
+            .gap_2()
+            .child(
+                div()
+                    .w(px(TIMELINE_NARROW_WIDTH))
+                    .flex_none()
+                    .child(row_group),
+            )
+            .child(div().w(px(TIMELINE_NARROW_WIDTH)).flex_none().child(rows))
+            .child(div().flex_1().min_w_0().truncate().child(encoding))
+            .child(div().w(px(TIMELINE_NARROW_WIDTH)).flex_none().child(codec))
+            .child(div().w(px(TIMELINE_WIDE_WIDTH)).flex_none().child(sizes))
+            .child(div().flex_1().min_w_0().truncate().child(stats))
+    };
+
+    let (title, body) =
+        match timeline {
+            Ok(timeline) => (
+                format!("Column chunks of {}", timeline.column),
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child(timeline.summary()),
+                    )
+                    .child(
+                        row([
+                            "Row group".into(),
+                            "Rows".into(),
+                            "Encodings".into(),
+                            "Codec".into(),
+                            "Size".into(),
+                            "Nulls · min … max".into(),
+                        ])
+                        .text_color(theme.muted_foreground),
+                    )
+                    .children(timeline.chunks.iter().zip(timeline.changes()).map(
+                        |(chunk, changed)| {
+                            let dictionary = match (chunk.dictionary, chunk.fallback) {
+                                (false, _) => "no dictionary",
+                                (true, Some(true)) => "dictionary, fell back",
+                                (true, _) => "dictionary",
+                            };
+                            let unknown = || "?".to_string();
+                            row([
+                                (chunk.row_group + 1).to_string(),
+                                chunk.rows.to_string(),
+                                format!("{} · {dictionary}", chunk.encodings.join(", ")),
+                                chunk.codec.clone(),
+                                format!(
+                                    "{} → {}",
+                                    format_bytes(chunk.uncompressed),
+                                    format_bytes(chunk.compressed)
+                                ),
+                                format!(
+                                    "{} · {} … {}",
+                                    chunk
+                                        .null_count
+                                        .map_or_else(unknown, |count| count.to_string()),
+                                    chunk.min.clone().unwrap_or_else(unknown),
+                                    chunk.max.clone().unwrap_or_else(unknown)
+                                ),
+                            ])
+                            .when(changed || chunk.fallback == Some(true), |this| {
+                                this.text_color(theme.warning)
+                            })
+                        },
+                    ))
+                    .into_any_element(),
+            ),
+            Err(message) => (
+                "Column chunks".to_string(),
+                div().child(message.clone()).into_any_element(),
+            ),
+        };
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child(title))
+                .child(
+                    Button::new("close-timeline")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(
+            div()
+                .id("timeline-chunks")
+                .max_h(px(METADATA_PANEL_HEIGHT))
+                .overflow_y_scroll()
+                .child(body),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn show_dictionary(
+        &mut self,
+        action: &ShowDictionary,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        self.show_dictionary_chunk(&column, 0, cx);
+    }
+
+    /// Load the dictionary of `column`'s chunk in `row_group` into the dictionary panel.
+    pub(crate) fn show_dictionary_chunk(
+        &mut self,
+        column: &str,
+        row_group: usize,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.dictionary_panel = Some(
+            dictionary_chunk(&self.preview.path, column, row_group).map_err(|error| {
+                tracing::error!(%error, column, row_group, "failed to read dictionary page");
+                format!("The dictionary of {column} could not be read: {error}")
+            }),
+        );
+        cx.notify();
+    }
+
+    pub(crate) fn show_chunk_timeline(
+        &mut self,
+        action: &ShowChunkTimeline,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = &self.preview.columns[action.column];
+        self.timeline_panel = Some(
+            column_timeline(&self.preview.path, column).map_err(|error| {
+                tracing::error!(%error, column, "failed to read the column chunk timeline");
+                format!("The column chunks of {column} could not be read: {error}")
+            }),
+        );
+        cx.notify();
+    }
+
+    pub(crate) fn toggle_metadata_panel(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.metadata_panel = match self.metadata_panel {
+            Some(_) => None,
+            None => Some(FileInfo::load(&self.preview.path).map_err(|error| {
+                tracing::error!(%error, "failed to read file metadata");
+                format!("Metadata could not be read: {error}")
+            })),
+        };
+        cx.notify();
+    }
+
+    pub(crate) fn toggle_size_panel(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.size_panel = match self.size_panel {
+            Some(_) => None,
+            None => Some(size_breakdown(&self.preview.path).map_err(|error| {
+                tracing::error!(%error, "failed to read column chunk sizes");
+                format!("Sizes could not be read: {error}")
+            })),
+        };
+        cx.notify();
+    }
+}
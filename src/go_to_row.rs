@@ -0,0 +1,175 @@
+use gpui::{actions, prelude::*};
+use gpui_component::input::{Input, InputState};
+use gpui_component::WindowExt;
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [GoToRow]);
+
+/// Display row that a "Go to row" entry points at, out of `row_count` rows.
+///
+/// Accepts a 1-based row number (digit separators `,`, `_` and spaces are
+/// ignored) or a percentage such as `50%`. Targets past the end land on the
+/// last row.
+pub fn parse_row_target(input: &str, row_count: usize) -> Result<usize, String> {
+    let input = input.trim();
+    let last_row = row_count.saturating_sub(1);
+
+    if let Some(percent) = input.strip_suffix('%') {
+        let percent: f64 = percent
+            .trim()
+            .parse()
+            .ok()
+            .filter(|percent: &f64| (0.0..=100.0).contains(percent))
+            .ok_or_else(|| format!("\"{input}\" is not a percentage between 0% and 100%"))?;
+        let row = (row_count as f64 * percent / 100.0).floor() as usize;
+        return Ok(row.min(last_row));
+    }
+
+    let digits: String = input
+        .chars()
+        .filter(|c| !matches!(c, ',' | '_' | ' '))
+        .collect();
+    match digits.parse::<usize>() {
+        Ok(0) | Err(_) => Err(format!("\"{input}\" is not a row number; rows start at 1")),
+        Ok(row) => Ok((row - 1).min(last_row)),
+    }
+}
+
+/// Where a file opens from the command line: a row as `parse_row_target`
+/// takes it and, for `--select`, the column of the cell to select.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StartPosition {
+    pub row: String,
+    pub column: Option<String>,
+}
+
+/// Read `--goto ROW`, checking the row before the file is open.
+pub fn parse_goto(input: &str) -> Result<StartPosition, String> {
+    parse_row_target(input, usize::MAX)?;
+    Ok(StartPosition {
+        row: input.trim().to_string(),
+        column: None,
+    })
+}
+
+/// Read `--select ROW,COL`. The column comes after the last comma, so row
+/// numbers may keep their digit separators, e.g. `5,432,101,user_id`.
+pub fn parse_select(input: &str) -> Result<StartPosition, String> {
+    let (row, column) = input
+        .rsplit_once(',')
+        .filter(|(_, column)| !column.trim().is_empty())
+        .ok_or_else(|| format!("\"{input}\" is not ROW,COLUMN, e.g. 1200,user_id"))?;
+    Ok(StartPosition {
+        column: Some(column.trim().to_string()),
+        ..parse_goto(row)?
+    })
+}
+
+/// Index of the column `target` names, or numbers from 1 when no column has
+/// that name.
+pub fn resolve_column(columns: &[String], target: &str) -> Result<usize, String> {
+    columns
+        .iter()
+        .position(|column| column == target)
+        .or_else(|| {
+            target
+                .parse::<usize>()
+                .ok()
+                .filter(|number| (1..=columns.len()).contains(number))
+                .map(|number| number - 1)
+        })
+        .ok_or_else(|| format!("the file has no column \"{target}\""))
+}
+
+impl PreviewView {
+    /// Ask for a row number or percentage and scroll there.
+    pub(crate) fn open_go_to_row(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if self.display_row_count() == 0 {
+            return;
+        }
+
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Row number or percentage, e.g. 1200 or 50%")
+        });
+        let view = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let input = dialog_input.clone();
+            let view = view.clone();
+            dialog
+                .title("Go to row")
+                .child(Input::new(&dialog_input))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let text = input.read(cx).value();
+                    match view.update(cx, |view, cx| view.go_to_row(&text, cx)) {
+                        Ok(Err(message)) => {
+                            window.push_notification(message, cx);
+                            false
+                        }
+                        _ => true,
+                    }
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    /// Scroll to and select the row `target` points at; see [`parse_row_target`].
+    pub(crate) fn go_to_row(
+        &mut self,
+        target: &str,
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Result<(), String> {
+        let row = parse_row_target(target, self.display_row_count())?;
+        self.reveal_row(row, cx);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_accept_row_numbers_and_percentages() {
+        assert_eq!(parse_row_target("1", 100), Ok(0));
+        assert_eq!(parse_row_target(" 10,000 ", 20_000), Ok(9_999));
+        assert_eq!(parse_row_target("500", 100), Ok(99));
+        assert_eq!(parse_row_target("50%", 10_000_000), Ok(5_000_000));
+        assert_eq!(parse_row_target("100%", 10), Ok(9));
+        assert_eq!(parse_row_target("0%", 10), Ok(0));
+
+        assert!(parse_row_target("0", 10).is_err());
+        assert!(parse_row_target("150%", 10).is_err());
+        assert!(parse_row_target("abc", 10).is_err());
+    }
+
+    #[test]
+    fn start_positions_keep_digit_separators_and_find_columns() {
+        assert_eq!(
+            parse_select("5,432,101,user_id"),
+            Ok(StartPosition {
+                row: "5,432,101".to_string(),
+                column: Some("user_id".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_goto(" 50% ").map(|start| start.row),
+            Ok("50%".to_string())
+        );
+        assert!(parse_select("12").is_err());
+        assert!(parse_select("0,id").is_err());
+
+        let columns = ["id".to_string(), "2".to_string(), "name".to_string()];
+        assert_eq!(resolve_column(&columns, "name"), Ok(2));
+        assert_eq!(resolve_column(&columns, "2"), Ok(1));
+        assert_eq!(resolve_column(&columns, "3"), Ok(2));
+        assert!(resolve_column(&columns, "4").is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use gpui::{div, prelude::*, px, MouseButton};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, StyledExt};
+use parquet_viewer_core::aggregate::Aggregate;
+use parquet_viewer_core::filter::ValueFilter;
+use parquet_viewer_core::groupby::group_by;
+use parquet_viewer_core::groupby::Groups;
+use parquet_viewer_core::progress::Progress;
+
+use crate::context_menu::{AggregateInGroups, GroupByColumn};
+use crate::PreviewView;
+
+/// Groups listed before the rest are summarized as a count.
+const GROUPS_SHOWN: usize = 200;
+
+/// Tallest the group list grows before it scrolls.
+const GROUP_LIST_HEIGHT: f32 = 280.0;
+
+/// Width of the row count column of the group list.
+const COUNT_WIDTH: f32 = 100.0;
+
+/// The group-by explorer: key columns, an optional aggregated column and the
+/// groups of the whole file, computed in the background.
+#[derive(Default)]
+pub struct GroupByPanel {
+    pub keys: Vec<String>,
+    pub value: Option<(String, Aggregate)>,
+    pub groups: Option<Result<Groups, String>>,
+    pub collapsed: bool,
+    /// Progress of the running computation, cancelled when it is replaced.
+    pub progress: Option<Progress>,
+}
+
+impl GroupByPanel {
+    pub fn cancel(&mut self) {
+        if let Some(progress) = self.progress.take() {
+            progress.cancel();
+        }
+    }
+}
+
+impl Drop for GroupByPanel {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Collapsible panel with the group keys as removable chips, the aggregated
+/// column, and one line per group with its row count; clicking a group filters
+/// the table to its rows.
+pub fn render_group_by_panel(
+    panel: &GroupByPanel,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.group_by = None;
+            cx.notify();
+        },
+    );
+    let collapse = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            if let Some(panel) = &mut view.group_by {
+                panel.collapsed = !panel.collapsed;
+            }
+            cx.notify();
+        },
+    );
+
+    let keys = div()
+        .flex()
+        .flex_row()
+        .flex_wrap()
+        .items_center()
+        .gap_1()
+        .child("Group by")
+        .children(panel.keys.iter().enumerate().map(|(index, key)| {
+            let remove = cx.listener(
+                move |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                    view.remove_group_key(index, cx);
+                },
+            );
+            Button::new(("group-key", index))
+                .ghost()
+                .xsmall()
+                .label(format!("{key} ×"))
+                .on_click(remove)
+        }))
+        .when_some(panel.value.as_ref(), |this, (column, aggregate)| {
+            let cycle = cx.listener(
+                |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                    view.cycle_group_aggregate(cx);
+                },
+            );
+            this.child("with").child(
+                Button::new("group-value")
+                    .ghost()
+                    .xsmall()
+                    .label(format!("{} of {column}", aggregate.name()))
+                    .on_click(cycle),
+            )
+        });
+
+    let body = match &panel.groups {
+        None => div()
+            .text_color(theme.muted_foreground)
+            .child("Grouping…")
+            .into_any_element(),
+        Some(Err(message)) => div().child(message.clone()).into_any_element(),
+        Some(Ok(groups)) => {
+            let hidden = groups.groups.len().saturating_sub(GROUPS_SHOWN);
+            div()
+                .id("groups")
+                .flex()
+                .flex_col()
+                .max_h(px(GROUP_LIST_HEIGHT))
+                .overflow_y_scroll()
+                .children(groups.groups.iter().take(GROUPS_SHOWN).enumerate().map(
+                    |(index, group)| {
+                        let click_handler = cx.listener(
+                            move |view: &mut PreviewView, _: &gpui::MouseDownEvent, _window, cx| {
+                                view.filter_to_group(index, cx);
+                            },
+                        );
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap_2()
+                            .px_1()
+                            .cursor_pointer()
+                            .hover(|this| this.bg(theme.table_hover))
+                            .on_mouse_down(MouseButton::Left, click_handler)
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .truncate()
+                                    .child(group.key.join(" · ")),
+                            )
+                            .child(
+                                div()
+                                    .w(px(COUNT_WIDTH))
+                                    .flex_none()
+                                    .child(format!("{} rows", group.rows)),
+                            )
+                            .when(panel.value.is_some(), |this| {
+                                this.child(
+                                    div().w(px(COUNT_WIDTH)).flex_none().child(
+                                        group.value.clone().unwrap_or_else(|| "—".to_string()),
+                                    ),
+                                )
+                            })
+                    },
+                ))
+                .when(hidden > 0, |this| {
+                    this.child(
+                        div()
+                            .text_color(theme.muted_foreground)
+                            .child(format!("… and {hidden} more groups")),
+                    )
+                })
+                .when(groups.other_rows > 0, |this| {
+                    this.child(div().text_color(theme.muted_foreground).child(format!(
+                        "{} rows with keys beyond the distinct keys tracked",
+                        groups.other_rows
+                    )))
+                })
+                .into_any_element()
+        }
+    };
+    let title = match &panel.groups {
+        Some(Ok(groups)) => format!("Groups ({})", groups.groups.len()),
+        _ => "Groups".to_string(),
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().font_medium().child(title))
+                .child(div().flex_1().child(keys))
+                .child(
+                    Button::new("collapse-groups")
+                        .ghost()
+                        .label(if panel.collapsed {
+                            "Expand"
+                        } else {
+                            "Collapse"
+                        })
+                        .on_click(collapse),
+                )
+                .child(
+                    Button::new("close-groups")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .when(!panel.collapsed, |this| this.child(body))
+}
+
+impl PreviewView {
+    pub(crate) fn group_by_column(
+        &mut self,
+        action: &GroupByColumn,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        let panel = self.group_by.get_or_insert_with(GroupByPanel::default);
+        if !panel.keys.contains(&column) {
+            panel.keys.push(column);
+        }
+        panel.collapsed = false;
+        self.refresh_groups(cx);
+    }
+
+    pub(crate) fn aggregate_in_groups(
+        &mut self,
+        action: &AggregateInGroups,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        let panel = self.group_by.get_or_insert_with(GroupByPanel::default);
+        panel.value = Some((column, Aggregate::Sum));
+        panel.collapsed = false;
+        self.refresh_groups(cx);
+    }
+
+    /// Drop the key at `index`, closing the panel once nothing is left to show.
+    pub(crate) fn remove_group_key(&mut self, index: usize, cx: &mut gpui::Context<PreviewView>) {
+        if let Some(panel) = &mut self.group_by {
+            if index < panel.keys.len() {
+                panel.keys.remove(index);
+            }
+            if panel.keys.is_empty() && panel.value.is_none() {
+                self.group_by = None;
+                cx.notify();
+                return;
+            }
+        }
+        self.refresh_groups(cx);
+    }
+
+    /// Switch the aggregated column to its next aggregate, skipping the count,
+    /// which every group shows already.
+    pub(crate) fn cycle_group_aggregate(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        if let Some((_, aggregate)) = self
+            .group_by
+            .as_mut()
+            .and_then(|panel| panel.value.as_mut())
+        {
+            *aggregate = match aggregate.next() {
+                Aggregate::Count => Aggregate::Count.next(),
+                next => next,
+            };
+        }
+        self.refresh_groups(cx);
+    }
+
+    /// Group the whole file by the panel's keys in the background.
+    fn refresh_groups(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        let Some(panel) = &mut self.group_by else {
+            return;
+        };
+        panel.cancel();
+        panel.groups = None;
+        if panel.keys.is_empty() {
+            panel.groups = Some(Ok(Default::default()));
+            cx.notify();
+            return;
+        }
+        let progress = Progress::default();
+        panel.progress = Some(progress.clone());
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let keys = panel.keys.clone();
+            let value = panel.value.clone();
+            let progress = progress.clone();
+            async move { group_by(&path, &keys, value.as_ref(), None, &bytes_read, &progress) }
+        });
+        cx.spawn(async move |view, cx| {
+            let groups = task.await;
+            view.update(cx, |view, cx| {
+                // Cancelled computations were for keys the panel no longer shows.
+                if progress.is_cancelled() {
+                    return;
+                }
+                let Some(panel) = &mut view.group_by else {
+                    return;
+                };
+                panel.progress = None;
+                panel.groups = Some(groups.map_err(|error| {
+                    tracing::error!(%error, "failed to group rows");
+                    format!("Rows could not be grouped: {error}")
+                }));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+
+    /// Filter the table to the rows of the group at `index`, replacing any
+    /// filters on its key columns.
+    pub(crate) fn filter_to_group(&mut self, index: usize, cx: &mut gpui::Context<PreviewView>) {
+        let Some(panel) = &self.group_by else {
+            return;
+        };
+        let Some(Ok(groups)) = &panel.groups else {
+            return;
+        };
+        let Some(group) = groups.groups.get(index) else {
+            return;
+        };
+        let mut filters: Vec<ValueFilter> = self
+            .filters
+            .iter()
+            .filter(|filter| !panel.keys.contains(&filter.column))
+            .cloned()
+            .collect();
+        filters.extend(
+            panel
+                .keys
+                .iter()
+                .zip(&group.key)
+                .map(|(column, value)| ValueFilter {
+                    column: column.clone(),
+                    value: value.clone(),
+                    exclude: false,
+                    regex: false,
+                }),
+        );
+        self.update_row_order(filters, self.sort.clone(), cx);
+    }
+}
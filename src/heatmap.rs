@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use gpui::{Action, Hsla};
+use gpui_component::{Theme, WindowExt};
+use parquet_viewer_core::aggregate::numeric_range;
+use parquet_viewer_core::progress::Progress;
+
+use crate::PreviewView;
+
+/// Turn the heat map of a numeric column on or off.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ToggleHeatMap {
+    pub column: usize,
+}
+
+/// Strongest background of a heat-mapped cell, for the column's largest value.
+const MAX_OPACITY: f32 = 0.6;
+
+/// A column shown as a heat map, scaled to its values over the whole file.
+pub enum HeatMap {
+    /// The column's range is still being computed.
+    Scanning(Progress),
+    Ready {
+        min: f64,
+        max: f64,
+    },
+}
+
+impl HeatMap {
+    /// Where a cell showing `text` falls between the column's smallest (0) and
+    /// largest (1) value; `None` for nulls, text that isn't a number, or while
+    /// the range is unknown.
+    pub fn position(&self, text: &str) -> Option<f32> {
+        let HeatMap::Ready { min, max } = self else {
+            return None;
+        };
+        let value: f64 = text
+            .trim()
+            .parse()
+            .ok()
+            .filter(|value: &f64| !value.is_nan())?;
+        if max <= min {
+            return Some(1.0);
+        }
+        Some(((value - min) / (max - min)).clamp(0.0, 1.0) as f32)
+    }
+}
+
+/// Heat-mapped columns by name.
+#[derive(Default)]
+pub struct HeatMaps(HashMap<String, HeatMap>);
+
+impl HeatMaps {
+    pub fn get(&self, column: &str) -> Option<&HeatMap> {
+        self.0.get(column)
+    }
+
+    pub fn insert(&mut self, column: String, heat_map: HeatMap) {
+        self.remove(&column);
+        self.0.insert(column, heat_map);
+    }
+
+    /// Stop heat-mapping `column`, cancelling its scan; whether it was shown.
+    pub fn remove(&mut self, column: &str) -> bool {
+        match self.0.remove(column) {
+            Some(HeatMap::Scanning(progress)) => {
+                progress.cancel();
+                true
+            }
+            Some(HeatMap::Ready { .. }) => true,
+            None => false,
+        }
+    }
+
+    /// Background of a cell of `column` showing `text`, if the column is heat-mapped.
+    pub fn background(&self, column: &str, text: &str, theme: &Theme) -> Option<Hsla> {
+        let position = self.get(column)?.position(text)?;
+        Some(theme.primary.opacity(position * MAX_OPACITY))
+    }
+}
+
+impl Drop for HeatMaps {
+    fn drop(&mut self) {
+        for heat_map in self.0.values() {
+            if let HeatMap::Scanning(progress) = heat_map {
+                progress.cancel();
+            }
+        }
+    }
+}
+
+impl PreviewView {
+    /// Shade the column's cells by value, scaled to its range over the whole
+    /// file, or stop shading them.
+    pub(crate) fn toggle_heat_map(
+        &mut self,
+        action: &ToggleHeatMap,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        if self.heat_maps.remove(&column) {
+            cx.notify();
+            return;
+        }
+        let progress = Progress::default();
+        self.heat_maps
+            .insert(column.clone(), HeatMap::Scanning(progress.clone()));
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let column = column.clone();
+            let progress = progress.clone();
+            async move { numeric_range(&path, &column, &bytes_read, &progress) }
+        });
+        cx.spawn_in(window, async move |view, cx| {
+            let range = task.await;
+            view.update_in(cx, |view, window, cx| {
+                // A cancelled scan belonged to a heat map that was turned off.
+                if progress.is_cancelled() {
+                    return;
+                }
+                match range {
+                    Ok(Some((min, max))) => {
+                        view.heat_maps.insert(column, HeatMap::Ready { min, max });
+                    }
+                    Ok(None) => {
+                        view.heat_maps.remove(&column);
+                        window.push_notification(
+                            format!("{column} has no numeric values to map"),
+                            cx,
+                        );
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "failed to compute the heat map range");
+                        view.heat_maps.remove(&column);
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_are_placed_between_the_column_min_and_max() {
+        let heat_map = HeatMap::Ready {
+            min: -10.0,
+            max: 30.0,
+        };
+        assert_eq!(heat_map.position("-10"), Some(0.0));
+        assert_eq!(heat_map.position("10.0"), Some(0.5));
+        assert_eq!(heat_map.position("30"), Some(1.0));
+        assert_eq!(heat_map.position("null"), None);
+        assert_eq!(heat_map.position("NaN"), None);
+
+        let constant = HeatMap::Ready { min: 4.0, max: 4.0 };
+        assert_eq!(constant.position("4"), Some(1.0));
+        assert_eq!(HeatMap::Scanning(Progress::default()).position("4"), None);
+    }
+}
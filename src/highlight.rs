@@ -0,0 +1,132 @@
+use std::ops::Range;
+
+use gpui::{actions, div, prelude::*, px, App, Entity};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Escape, Input, InputEvent, InputState};
+use gpui_component::ActiveTheme;
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [HighlightInView]);
+
+/// Byte ranges of `needle` in `text`, ignoring ASCII case; matches don't overlap.
+///
+/// Only ASCII letters are folded, so that the ranges stay valid for `text`.
+pub fn match_ranges(text: &str, needle: &str) -> Vec<Range<usize>> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let needle = needle.to_ascii_lowercase();
+    text.to_ascii_lowercase()
+        .match_indices(&needle)
+        .map(|(start, found)| start..start + found.len())
+        .collect()
+}
+
+impl PreviewView {
+    /// Show the highlight box, or focus it if it's already open.
+    pub(crate) fn open_highlight(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let input = match &self.highlight {
+            Some(input) => input.clone(),
+            None => {
+                let input = cx.new(|cx| InputState::new(window, cx).placeholder("Highlight…"));
+                self._highlight_changes = Some(cx.subscribe_in(
+                    &input,
+                    window,
+                    |_, _, event: &InputEvent, _window, cx| {
+                        if matches!(event, InputEvent::Change) {
+                            cx.notify();
+                        }
+                    },
+                ));
+                self.highlight = Some(input.clone());
+                input
+            }
+        };
+        input.update(cx, |input, cx| input.focus(window, cx));
+        cx.notify();
+    }
+
+    pub(crate) fn close_highlight(
+        &mut self,
+        _: &Escape,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if self.highlight.take().is_some() {
+            self._highlight_changes = None;
+            window.focus(&self.focus_handle);
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    /// Text to mark in the visible cells, if the highlight box is open and not empty.
+    pub(crate) fn highlight_text(&self, cx: &App) -> Option<String> {
+        let text = self.highlight.as_ref()?.read(cx).value();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+/// The highlight box and how many visible cells match it.
+pub fn render_highlight_bar(
+    view: &PreviewView,
+    input: Entity<InputState>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(|view: &mut PreviewView, _: &gpui::ClickEvent, window, cx| {
+        view.close_highlight(&Escape, window, cx);
+    });
+
+    let matches = view.highlight_text(cx).map(|needle| {
+        let columns = view.displayed_columns();
+        view.visible_rows
+            .iter()
+            .flat_map(|row| columns.iter().map(move |&column| &row[column]))
+            .map(|value| match_ranges(value, &needle).len())
+            .sum::<usize>()
+    });
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .w_full()
+        .text_sm()
+        .child(div().w(px(240.0)).child(Input::new(&input)))
+        .child(
+            div()
+                .flex_1()
+                .text_color(theme.muted_foreground)
+                .when_some(matches, |this, matches| {
+                    this.child(format!("{matches} match(es) in the visible rows"))
+                }),
+        )
+        .child(
+            Button::new("close-highlight")
+                .ghost()
+                .label("Close")
+                .on_click(close),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_ignore_ascii_case_and_keep_byte_offsets() {
+        assert_eq!(match_ranges("Alpha alpha", "ALPHA"), vec![0..5, 6..11]);
+        assert_eq!(match_ranges("größe GRÖSSE", "gr"), vec![0..2, 8..10]);
+        assert_eq!(match_ranges("aaaa", "aa"), vec![0..2, 2..4]);
+        assert!(match_ranges("value", "").is_empty());
+    }
+}
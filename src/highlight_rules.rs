@@ -0,0 +1,214 @@
+use gpui::{actions, div, prelude::*, px, Hsla};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputState};
+use gpui_component::{ActiveTheme, Sizable, StyledExt, Theme, WindowExt};
+use parquet_viewer_core::rules::{HighlightRule, RuleColor};
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ToggleHighlightRules]);
+
+/// Size of the color swatch in front of each rule.
+const SWATCH_SIZE: f32 = 10.0;
+
+/// Background of cells matched by a rule painted `color`.
+pub fn rule_background(color: RuleColor, theme: &Theme) -> Hsla {
+    let color = match color {
+        RuleColor::Red => theme.danger,
+        RuleColor::Yellow => theme.warning,
+        RuleColor::Green => theme.success,
+        RuleColor::Blue => theme.info,
+    };
+    color.opacity(0.3)
+}
+
+/// Color of the first rule on `column` that a cell showing `text` matches.
+pub fn matching_color(rules: &[HighlightRule], column: &str, text: &str) -> Option<RuleColor> {
+    rules
+        .iter()
+        .find(|rule| rule.column == column && rule.matches(text))
+        .map(|rule| rule.color)
+}
+
+/// Panel listing the highlight rules, first match winning, with a button to add one.
+pub fn render_highlight_rules(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let add = cx.listener(|view: &mut PreviewView, _: &gpui::ClickEvent, window, cx| {
+        view.add_highlight_rule(window, cx);
+    });
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_highlight_rules(cx);
+        },
+    );
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child("Highlight rules"))
+                .child(
+                    Button::new("add-highlight-rule")
+                        .ghost()
+                        .label("Add Rule…")
+                        .on_click(add),
+                )
+                .child(
+                    Button::new("close-highlight-rules")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .when(view.highlight_rules.is_empty(), |this| {
+            this.child(
+                div()
+                    .text_color(theme.muted_foreground)
+                    .child("No rules yet, e.g. `amount < 0 => red` or `ts is null => yellow`"),
+            )
+        })
+        .children(
+            view.highlight_rules
+                .iter()
+                .enumerate()
+                .map(|(index, rule)| {
+                    let remove = cx.listener(
+                        move |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                            view.remove_highlight_rule(index, cx);
+                        },
+                    );
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            div()
+                                .size(px(SWATCH_SIZE))
+                                .flex_none()
+                                .rounded(theme.radius)
+                                .bg(rule_background(rule.color, theme)),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .font_family("monospace")
+                                .child(rule.to_string()),
+                        )
+                        .child(
+                            Button::new(("remove-highlight-rule", index))
+                                .ghost()
+                                .xsmall()
+                                .label("×")
+                                .on_click(remove),
+                        )
+                }),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn toggle_highlight_rules(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.show_highlight_rules = !self.show_highlight_rules;
+        cx.notify();
+    }
+
+    /// Ask for a rule such as `amount < 0 => red` and add it after the others.
+    pub(crate) fn add_highlight_rule(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Rule, e.g. amount < 0 => red or ts is null")
+        });
+        let view = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let input = dialog_input.clone();
+            let view = view.clone();
+            dialog
+                .title("Add highlight rule")
+                .child(Input::new(&dialog_input))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let rule = match HighlightRule::parse(&input.read(cx).value()) {
+                        Ok(rule) => rule,
+                        Err(error) => {
+                            window.push_notification(error.to_string(), cx);
+                            return false;
+                        }
+                    };
+                    let added = view.update(cx, |view, cx| {
+                        if !view.preview.columns.contains(&rule.column) {
+                            return Err(format!("There is no column named {}", rule.column));
+                        }
+                        view.highlight_rules.push(rule);
+                        view.show_highlight_rules = true;
+                        cx.notify();
+                        Ok(())
+                    });
+                    match added {
+                        Ok(Err(message)) => {
+                            window.push_notification(message, cx);
+                            false
+                        }
+                        _ => true,
+                    }
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    pub(crate) fn remove_highlight_rule(
+        &mut self,
+        index: usize,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if index < self.highlight_rules.len() {
+            self.highlight_rules.remove(index);
+        }
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_first_matching_rule_on_the_column_wins() {
+        let rules = [
+            HighlightRule::parse("amount < 0 => red").unwrap(),
+            HighlightRule::parse("amount < 100 => green").unwrap(),
+            HighlightRule::parse("note is null").unwrap(),
+        ];
+
+        assert_eq!(matching_color(&rules, "amount", "-5"), Some(RuleColor::Red));
+        assert_eq!(
+            matching_color(&rules, "amount", "50"),
+            Some(RuleColor::Green)
+        );
+        assert_eq!(matching_color(&rules, "amount", "500"), None);
+        assert_eq!(
+            matching_color(&rules, "note", "null"),
+            Some(RuleColor::Yellow)
+        );
+        assert_eq!(matching_color(&rules, "other", "null"), None);
+    }
+}
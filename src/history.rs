@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use gpui::{actions, div, prelude::*, px, Action};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, StyledExt};
+use parquet_viewer_core::explain::ScanReport;
+use parquet_viewer_core::filter::{SortKey, ValueFilter};
+
+use crate::explain::ExplainQuery;
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ToggleQueryHistory]);
+
+/// Queries kept for the session; running another drops the oldest.
+const MAX_HISTORY: usize = 200;
+
+/// Tallest the history panel grows before it scrolls.
+const HISTORY_PANEL_HEIGHT: f32 = 240.0;
+
+/// Run the filters and sort of a history entry again.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct RerunQuery {
+    pub index: usize,
+}
+
+/// A filter and sort that ran, typed in the query bar or picked from a menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The query as the query bar writes it.
+    pub query: String,
+    pub filters: Vec<ValueFilter>,
+    pub sort: Option<SortKey>,
+    /// How long the scan took.
+    pub elapsed: Duration,
+    /// Rows the query kept.
+    pub rows: usize,
+    /// What the scan pruned and read, for the explain panel.
+    pub report: ScanReport,
+}
+
+/// Queries run in this session, oldest first, and where Up and Down in the
+/// query bar have stepped to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueryHistory {
+    entries: Vec<HistoryEntry>,
+    /// Entry shown in the query bar while stepping; `None` past the newest.
+    cursor: Option<usize>,
+}
+
+impl QueryHistory {
+    /// Add `entry` as the newest and stop stepping.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == MAX_HISTORY {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+        self.cursor = None;
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Step to the next older query (Up), stopping at the oldest.
+    pub fn older(&mut self) -> Option<&str> {
+        let index = match self.cursor {
+            Some(index) => index.saturating_sub(1),
+            None => self.entries.len().checked_sub(1)?,
+        };
+        self.cursor = Some(index);
+        Some(&self.entries[index].query)
+    }
+
+    /// Step to the next newer query (Down); past the newest gives an empty
+    /// query. `None` when not stepping.
+    pub fn newer(&mut self) -> Option<&str> {
+        let index = self.cursor? + 1;
+        if index < self.entries.len() {
+            self.cursor = Some(index);
+            Some(&self.entries[index].query)
+        } else {
+            self.cursor = None;
+            Some("")
+        }
+    }
+}
+
+/// Short duration for the history panel, e.g. `850 ms` or `12.4 s`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(1) {
+        format!("{} ms", elapsed.as_millis())
+    } else {
+        format!("{:.1} s", elapsed.as_secs_f64())
+    }
+}
+
+/// Queries run so far, newest first, with how long each took and the rows it
+/// kept, and a button to run one again.
+pub fn render_history_panel(
+    history: &QueryHistory,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_query_history(cx);
+        },
+    );
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .child(div().flex_1().font_semibold().child("Query history"))
+                .child(
+                    Button::new("close-query-history")
+                        .ghost()
+                        .xsmall()
+                        .label("×")
+                        .on_click(close),
+                ),
+        )
+        .when(history.entries().is_empty(), |this| {
+            this.child(
+                div()
+                    .text_color(theme.muted_foreground)
+                    .child("No queries yet. Type one above, e.g. status = ERROR | level != DEBUG"),
+            )
+        })
+        .child(
+            div()
+                .id("query-history")
+                .flex()
+                .flex_col()
+                .max_h(px(HISTORY_PANEL_HEIGHT))
+                .overflow_y_scroll()
+                .children(
+                    history
+                        .entries()
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .map(|(index, entry)| {
+                            let rerun = cx.listener(
+                                move |view: &mut PreviewView, _: &gpui::ClickEvent, window, cx| {
+                                    view.rerun_query(&RerunQuery { index }, window, cx);
+                                },
+                            );
+                            let explain = cx.listener(
+                                move |view: &mut PreviewView, _: &gpui::ClickEvent, window, cx| {
+                                    view.explain_query(&ExplainQuery { index }, window, cx);
+                                },
+                            );
+                            div()
+                                .flex()
+                                .flex_row()
+                                .items_center()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .min_w_0()
+                                        .truncate()
+                                        .font_family("monospace")
+                                        .child(entry.query.clone()),
+                                )
+                                .child(div().flex_none().text_color(theme.muted_foreground).child(
+                                    format!(
+                                        "{} rows · {}",
+                                        entry.rows,
+                                        format_elapsed(entry.elapsed)
+                                    ),
+                                ))
+                                .child(
+                                    Button::new(("explain-query", index))
+                                        .ghost()
+                                        .xsmall()
+                                        .label("Explain")
+                                        .on_click(explain),
+                                )
+                                .child(
+                                    Button::new(("rerun-query", index))
+                                        .ghost()
+                                        .xsmall()
+                                        .label("Run")
+                                        .on_click(rerun),
+                                )
+                        }),
+                ),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn toggle_query_history(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.show_history = !self.show_history;
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(query: &str) -> HistoryEntry {
+        HistoryEntry {
+            query: query.to_string(),
+            filters: Vec::new(),
+            sort: None,
+            elapsed: Duration::from_millis(5),
+            rows: 1,
+            report: ScanReport::default(),
+        }
+    }
+
+    #[test]
+    fn up_and_down_step_through_the_history() {
+        let mut history = QueryHistory::default();
+        assert_eq!(history.older(), None);
+        assert_eq!(history.newer(), None);
+
+        history.push(entry("a = 1"));
+        history.push(entry("b = 2"));
+        assert_eq!(history.older(), Some("b = 2"));
+        assert_eq!(history.older(), Some("a = 1"));
+        assert_eq!(history.older(), Some("a = 1"));
+        assert_eq!(history.newer(), Some("b = 2"));
+        assert_eq!(history.newer(), Some(""));
+        assert_eq!(history.newer(), None);
+
+        history.older();
+        history.push(entry("c = 3"));
+        assert_eq!(history.older(), Some("c = 3"));
+        assert_eq!(format_elapsed(Duration::from_millis(12_400)), "12.4 s");
+    }
+}
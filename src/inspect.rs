@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use gpui::{div, prelude::*, px};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::popover::Popover;
+use gpui_component::{ActiveTheme, StyledExt};
+use parquet_viewer_core::inspect::{CellDetail, ImageKind};
+use parquet_viewer_core::provenance::CellOrigin;
+
+use crate::context_menu::InspectCell;
+use crate::PreviewView;
+
+/// Largest width and height of an image shown from a binary cell.
+const IMAGE_PREVIEW_SIZE: f32 = 480.0;
+
+impl PreviewView {
+    pub(crate) fn inspect_cell(
+        &mut self,
+        action: &InspectCell,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let row = self.file_row(action.row);
+        self.cell_detail = match inspect_cell(&self.preview, row, action.column) {
+            Ok(detail) => detail.map(Ok),
+            Err(error) => {
+                tracing::error!(%error, row, column = action.column, "failed to inspect cell");
+                Some(Err(format!("Row {} could not be read: {error}", row + 1)))
+            }
+        };
+        self.cell_image = match &self.cell_detail {
+            Some(Ok(CellDetail {
+                image: Some(image), ..
+            })) => {
+                let format = match image.kind {
+                    ImageKind::Png => gpui::ImageFormat::Png,
+                    ImageKind::Jpeg => gpui::ImageFormat::Jpeg,
+                };
+                Some(Arc::new(gpui::Image::from_bytes(
+                    format,
+                    image.bytes.clone(),
+                )))
+            }
+            _ => None,
+        };
+        cx.notify();
+    }
+}
+
+/// Cell inspector with the selected value in full, e.g. pretty-printed JSON,
+/// and for cells holding a PNG or JPEG, a popover that shows the image.
+pub fn render_cell_detail(
+    detail: &Result<CellDetail, String>,
+    image: Option<Arc<gpui::Image>>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.cell_detail = None;
+            view.cell_image = None;
+            cx.notify();
+        },
+    );
+
+    let (title, text, origin) = match detail {
+        Ok(detail) => (
+            format!("{} ({})", detail.column, detail.data_type),
+            detail.text.clone(),
+            detail.origin.as_ref().map(CellOrigin::summary),
+        ),
+        Err(message) => ("Cell inspector".to_string(), message.clone(), None),
+    };
+
+    div()
+        .flex()
+        .flex_row()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .flex_1()
+                .gap_1()
+                .child(div().font_medium().child(title))
+                .when_some(origin, |this, origin| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .children(origin.into_iter().map(|line| div().child(line))),
+                    )
+                })
+                .child(
+                    div()
+                        .id("cell-detail-text")
+                        .max_h(px(240.0))
+                        .overflow_y_scroll()
+                        .font_family("monospace")
+                        .whitespace_normal()
+                        .children(text.lines().map(|line| div().child(line.to_string()))),
+                ),
+        )
+        .when_some(image, |this, image| {
+            this.child(
+                Popover::new("cell-image-preview")
+                    .anchor(gpui::Corner::TopRight)
+                    .trigger(Button::new("preview-cell-image").label("Preview image"))
+                    .content(move |_, _, _| {
+                        gpui::img(image.clone())
+                            .max_w(px(IMAGE_PREVIEW_SIZE))
+                            .max_h(px(IMAGE_PREVIEW_SIZE))
+                            .object_fit(gpui::ObjectFit::Contain)
+                    }),
+            )
+        })
+        .child(
+            Button::new("close-cell-detail")
+                .ghost()
+                .label("Close")
+                .on_click(close),
+        )
+}
@@ -0,0 +1,109 @@
+use gpui::{Action, Task};
+use parquet_viewer_core::join::{join_files, JoinKind, JoinSpec, JoinSummary};
+use parquet_viewer_core::progress::Progress;
+use parquet_viewer_core::ViewerError;
+use tempfile::NamedTempFile;
+
+use crate::PreviewView;
+
+/// Pick a second file and join the file shown with it on a key column.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct JoinWithFile {
+    pub kind: JoinKind,
+}
+
+/// Read the key columns typed in the join dialog: `left = right`, or one name
+/// for a column both files have.
+pub fn parse_join_keys(text: &str) -> Option<(String, String)> {
+    let (left, right) = text.split_once('=').unwrap_or((text, text));
+    let (left, right) = (left.trim(), right.trim());
+    (!left.is_empty() && !right.is_empty()).then(|| (left.to_string(), right.to_string()))
+}
+
+/// What to fill the join dialog in with: the selected column when the other
+/// file has it too, otherwise the first column both files have.
+pub fn guess_join_keys(left: &[String], right: &[String], selected: Option<&str>) -> String {
+    selected
+        .filter(|column| right.iter().any(|name| name == column))
+        .or_else(|| {
+            left.iter()
+                .map(String::as_str)
+                .find(|column| right.iter().any(|name| name == column))
+        })
+        .map(|column| format!("{column} = {column}"))
+        .unwrap_or_default()
+}
+
+impl PreviewView {
+    /// Join this file with `spec.right` in the background, showing progress, and
+    /// resolve to the temporary file holding the result.
+    pub(crate) fn join_file(
+        &mut self,
+        spec: JoinSpec,
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Task<Result<(NamedTempFile, JoinSummary), ViewerError>> {
+        let progress = Progress::default();
+        self.start_operation("Joining", "rows", progress.clone(), cx);
+        let join = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let progress = progress.clone();
+            async move {
+                let file = tempfile::Builder::new()
+                    .prefix("parquet-viewer-join-")
+                    .suffix(".parquet")
+                    .tempfile()?;
+                let summary = join_files(&path, &spec, file.path(), &progress)?;
+                Ok((file, summary))
+            }
+        });
+
+        cx.spawn(async move |view, cx| {
+            let result = join.await;
+            if !progress.is_cancelled() {
+                view.update(cx, |view, cx| {
+                    view.operation = None;
+                    cx.notify();
+                })
+                .ok();
+            }
+            result
+        })
+    }
+
+    /// Names of the file's top-level columns and the one the selected cell is in.
+    pub(crate) fn columns_and_selected(&self) -> (&[String], Option<&str>) {
+        let selected = self
+            .selected_cell
+            .and_then(|(_, column)| self.preview.columns.get(column))
+            .map(String::as_str);
+        (&self.preview.columns, selected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_keys_are_read_and_guessed() {
+        assert_eq!(
+            parse_join_keys(" customer_id = id "),
+            Some(("customer_id".to_string(), "id".to_string()))
+        );
+        assert_eq!(
+            parse_join_keys("id"),
+            Some(("id".to_string(), "id".to_string()))
+        );
+        assert_eq!(parse_join_keys("id ="), None);
+
+        let left = ["id".to_string(), "region".to_string()];
+        let right = ["region".to_string(), "id".to_string()];
+        assert_eq!(guess_join_keys(&left, &right, None), "id = id");
+        assert_eq!(
+            guess_join_keys(&left, &right, Some("region")),
+            "region = region"
+        );
+        assert_eq!(guess_join_keys(&left, &["x".to_string()], Some("id")), "");
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::Args as ClapArgs;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::prelude::*;
+
+/// Logging flags, shared by the viewer and every subcommand.
+#[derive(ClapArgs, Debug, Default)]
+pub struct LogArgs {
+    /// Log only errors to the terminal.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Log more to the terminal and the log file: `-v` for debug messages, `-vv`
+    /// for everything.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Also append logs to this file, one JSON object per line.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub log_file: Option<PathBuf>,
+}
+
+/// `-v` shows the viewer's own debug messages without those of GPUI and the
+/// other libraries.
+const DEBUG: &str = "info,parquet_viewer=debug,parquet_viewer_core=debug";
+const TRACE: &str = "trace";
+const QUIET: &str = "error";
+
+impl LogArgs {
+    /// Filter of the terminal log, or `None` to leave it to `RUST_LOG`, and
+    /// info without it.
+    pub fn terminal_filter(&self) -> Option<&'static str> {
+        if self.quiet {
+            Some(QUIET)
+        } else {
+            self.verbose_filter()
+        }
+    }
+
+    /// Filter of the log file; `--quiet` only quiets the terminal.
+    pub fn file_filter(&self) -> Option<&'static str> {
+        self.verbose_filter()
+    }
+
+    fn verbose_filter(&self) -> Option<&'static str> {
+        match self.verbose {
+            0 => None,
+            1 => Some(DEBUG),
+            _ => Some(TRACE),
+        }
+    }
+}
+
+fn filter(directives: Option<&str>) -> EnvFilter {
+    match directives {
+        Some(directives) => EnvFilter::new(directives),
+        None => EnvFilter::builder()
+            .with_default_directive(LevelFilter::INFO.into())
+            .from_env_lossy(),
+    }
+}
+
+/// Send logs to stderr and, with `--log-file`, to that file, at the levels the
+/// flags ask for.
+pub fn init(args: &LogArgs) -> std::io::Result<()> {
+    let file = match &args.log_file {
+        Some(path) => Some(File::options().create(true).append(true).open(path)?),
+        None => None,
+    };
+    let terminal = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(filter(args.terminal_filter()));
+    let file = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(Arc::new(file))
+            .with_filter(filter(args.file_filter()))
+    });
+    tracing_subscriber::registry()
+        .with(terminal)
+        .with(file)
+        .init();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_pick_the_terminal_and_file_filters() {
+        let quiet = LogArgs {
+            quiet: true,
+            ..LogArgs::default()
+        };
+        assert_eq!(quiet.terminal_filter(), Some(QUIET));
+        assert_eq!(quiet.file_filter(), None);
+
+        let verbose = |count| LogArgs {
+            verbose: count,
+            ..LogArgs::default()
+        };
+        assert_eq!(verbose(0).terminal_filter(), None);
+        assert_eq!(verbose(1).terminal_filter(), Some(DEBUG));
+        assert_eq!(verbose(3).file_filter(), Some(TRACE));
+        assert!(DEBUG.parse::<EnvFilter>().is_ok());
+    }
+}
@@ -1,344 +1,1721 @@
-use std::fs::File;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
 use std::ops::Range;
-use std::path::PathBuf;
-
-use arrow::record_batch::RecordBatch;
-use arrow::util::display::array_value_to_string;
-use arrow::util::pretty::pretty_format_batches;
-use clap::Parser;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::error::ErrorKind;
+use clap::{Args as ClapArgs, CommandFactory, Parser, Subcommand};
+use futures::StreamExt;
 use gpui::{
-    div, prelude::*, px, size, App, Application, Bounds, MouseButton, Pixels, WindowBounds,
-    WindowOptions,
+    div, prelude::*, px, App, Application, Entity, FocusHandle, Pixels, Subscription, WindowOptions,
 };
-use gpui_component::{ActiveTheme, StyledExt};
-use parquet::arrow::arrow_reader::{ParquetRecordBatchReaderBuilder, RowSelection, RowSelector};
-use parquet::file::reader::FileReader;
-use parquet::file::reader::SerializedFileReader;
-use thiserror::Error;
+use gpui_component::input::{InputEvent, InputState};
+use gpui_component::{ActiveTheme, Root, StyledExt, WindowExt};
+use parquet::basic::Compression;
+use tempfile::NamedTempFile;
 use tracing::info;
 
+mod bookmarks;
+mod cancel;
+mod column_widths;
+mod columns;
+mod compare;
+mod context_menu;
+mod deep_link;
+mod duplicates;
+mod explain;
+mod export;
+mod file_info;
+mod go_to_row;
+mod group_by;
+mod heatmap;
+mod highlight;
+mod highlight_rules;
+mod history;
+mod inspect;
+mod join;
+mod logging;
+mod minimap;
+mod operation;
+mod page_boundaries;
+mod presets;
+mod profile;
+mod query;
+mod recent;
+mod record;
+mod row_diff;
+mod row_groups;
+mod session;
+mod settings;
+mod split;
+mod start_screen;
+mod status_bar;
+mod storage;
+mod summary;
+mod table;
+mod table_style;
+mod theme;
+mod time_scrubber;
+mod transpose;
+mod viewport;
+mod window_geometry;
+mod word_wrap;
+mod workspace;
+
+use bookmarks::render_bookmarks;
+use cancel::CancellableTask;
+use column_widths::ColumnWidths;
+use columns::render_column_stats;
+use compare::{render_compare_strip, Pins};
+use context_menu::ContextTarget;
+use deep_link::DeepLink;
+use duplicates::{render_duplicates_panel, DuplicatesPanel};
+use explain::render_explain_panel;
+use file_info::{
+    render_dictionary_panel, render_metadata_panel, render_size_panel, render_timeline_panel,
+    FileInfo,
+};
+use go_to_row::StartPosition;
+use group_by::{render_group_by_panel, GroupByPanel};
+use heatmap::HeatMaps;
+use highlight::render_highlight_bar;
+use highlight_rules::render_highlight_rules;
+use history::{render_history_panel, QueryHistory};
+use inspect::render_cell_detail;
+use logging::LogArgs;
+use minimap::Minimap;
+use operation::{render_operation, RunningOperation};
+use parquet_viewer_core::batching;
+use parquet_viewer_core::bench::{bench_file, BenchOptions, Cache};
+use parquet_viewer_core::contract::{check_contract, Contract};
+use parquet_viewer_core::dictionary::DictionaryChunk;
+use parquet_viewer_core::doctor::writer_report;
+use parquet_viewer_core::filter::{SortKey, ValueFilter};
+use parquet_viewer_core::histogram::Histogram;
+use parquet_viewer_core::inspect::{CellDetail, RecordDetail};
+use parquet_viewer_core::intern::Cell;
+use parquet_viewer_core::locale::{is_localized_column, Locale, LocaleRenderer};
+use parquet_viewer_core::merge::merge_files;
+use parquet_viewer_core::output::{ensure_distinct, write_atomically};
+use parquet_viewer_core::pagedump::dump_pages;
+use parquet_viewer_core::pages::PageBoundaries;
+use parquet_viewer_core::placeholders::{init_placeholders, Placeholders};
+use parquet_viewer_core::postgres::{create_table, quote_identifier, stream_copy, CopyFormat};
+use parquet_viewer_core::prefetch::{RowCache, MAX_PREFETCH_SCREENS};
+use parquet_viewer_core::preview::{format_rows, UnreadableRange};
+use parquet_viewer_core::profile::{profile, sample_profile};
+use parquet_viewer_core::progress::{Progress, TerminalProgress};
+use parquet_viewer_core::projection::select_columns;
+use parquet_viewer_core::recover::recover_file;
+use parquet_viewer_core::render::{self, RendererMatch, RendererRegistry};
+use parquet_viewer_core::report::html_report;
+use parquet_viewer_core::retype::{rewrite_schema, SchemaChanges};
+use parquet_viewer_core::rewrite::{rewrite_file, RewriteOptions};
+use parquet_viewer_core::rules::HighlightRule;
+use parquet_viewer_core::selection::SelectedRows;
+use parquet_viewer_core::sizes::SizeBreakdown;
+use parquet_viewer_core::snapshot::{snapshot_digest, snapshot_text};
+use parquet_viewer_core::sortedness::{sorted_columns, Sortedness};
+use parquet_viewer_core::spill;
+use parquet_viewer_core::stats::ColumnStats;
+use parquet_viewer_core::stream::{stream_csv, RowStream};
+use parquet_viewer_core::thrift::footer_json;
+use parquet_viewer_core::timeindex::time_index;
+use parquet_viewer_core::timeline::ColumnTimeline;
+use parquet_viewer_core::truncate::{truncate, Truncation};
+use parquet_viewer_core::utf8;
+use parquet_viewer_core::validate::{load_expected_schema, validate_schema};
+use parquet_viewer_core::verify::verify_file;
+use parquet_viewer_core::{load_preview, DataPreview, ViewerError};
+use profile::{render_profile_panel, ProfilePanel};
+use query::render_query_bar;
+use record::render_record_view;
+use row_diff::{render_row_diff, MarkedRows};
+use session::FileSession;
+use settings::Settings;
+use status_bar::{render_geo_metadata, render_status_bar};
+use summary::SummaryRow;
+use table::{render_read_errors, render_table};
+use table_style::TableStyle;
+use time_scrubber::{render_time_scrubber, TimeScrubber};
+use transpose::render_transposed_table;
+use window_geometry::{initial_window, WindowSize};
+use workspace::{Workspace, MENU_BAR_HEIGHT};
+
 /// Command line arguments for the viewer.
 #[derive(Parser, Debug)]
 #[command(
     name = "parquet-viewer",
-    about = "Inspect Parquet files with a GPUI front-end"
+    about = "Inspect Parquet files with a GPUI front-end",
+    args_conflicts_with_subcommands = true
 )]
 struct Args {
-    /// Path to the Parquet file.
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to the Parquet file, or a `parquet-viewer://open?path=...` link.
+    /// Without it the viewer opens on its start screen.
     #[arg(value_name = "FILE")]
-    path: PathBuf,
+    path: Option<PathBuf>,
 
     /// Number of rows to preview from the top of the file.
     #[arg(short, long, default_value_t = 20)]
     rows: usize,
 
     /// Render the preview to stdout instead of launching the UI.
-    #[arg(long, default_value_t = false)]
+    #[arg(long, default_value_t = false, requires = "path")]
     headless: bool,
+
+    /// Write a standalone HTML report of the file to this path instead of
+    /// launching the UI: overview, schema, column statistics, metadata and the
+    /// first `--rows` rows, for sharing with people who don't use the viewer.
+    #[arg(
+        long,
+        value_name = "OUTPUT",
+        requires = "path",
+        conflicts_with = "headless"
+    )]
+    to_html: Option<PathBuf>,
+
+    /// Cut cells of the `--headless` table to this many terminal columns, with an
+    /// ellipsis; wide characters count two and letters keep their accents.
+    #[arg(long, value_name = "WIDTH", requires = "headless")]
+    truncate: Option<usize>,
+
+    /// Open scrolled to this row: a row number counting from 1, e.g. `5,432,101`,
+    /// or a percentage such as `50%`.
+    #[arg(long, value_name = "ROW", value_parser = go_to_row::parse_goto,
+          requires = "path", conflicts_with_all = ["headless", "select"])]
+    goto: Option<StartPosition>,
+
+    /// Open with one cell selected: a row as for `--goto`, then a comma and a
+    /// column name or number, e.g. `5432101,user_id`.
+    #[arg(long, value_name = "ROW,COL", value_parser = go_to_row::parse_select,
+          requires = "path", conflicts_with = "headless")]
+    select: Option<StartPosition>,
+
+    /// Memory-map the file instead of reopening and reading it for every viewport.
+    #[arg(long, default_value_t = false)]
+    mmap: bool,
+
+    /// Show strings that aren't valid UTF-8 with replacement characters, marked
+    /// with a badge, instead of failing to read their row group.
+    #[arg(long, default_value_t = false, global = true)]
+    lossy_utf8: bool,
+
+    /// Number of threads used to decode row groups in parallel. Defaults to one per CPU core.
+    #[arg(long, value_name = "N", global = true)]
+    threads: Option<usize>,
+
+    /// Memory budget for decoded data, e.g. `2GiB`. Sorts beyond it spill to
+    /// temporary files, joins hold the right file a part at a time, rows read
+    /// ahead of scrolling are dropped past it, Excel exports that outgrow it are
+    /// refused, and the preloaded head of the file is shortened to fit.
+    #[arg(long, value_name = "SIZE", value_parser = spill::parse_size, global = true)]
+    memory_limit: Option<usize>,
+
+    /// Rows decoded per batch. By default batches are sized to the file, so
+    /// that very wide rows are read a few at a time and narrow ones in bulk.
+    #[arg(long, value_name = "ROWS", value_parser = clap::value_parser!(u32).range(1..), global = true)]
+    batch_size: Option<u32>,
+
+    /// Theme to use: `light`, `dark`, `system`, or the name of a custom theme.
+    /// Custom themes are JSON theme sets in the `themes` folder of the config directory.
+    #[arg(long, value_name = "THEME", conflicts_with = "headless")]
+    theme: Option<String>,
+
+    /// Open the window maximized.
+    #[arg(long, default_value_t = false, conflicts_with = "headless")]
+    maximized: bool,
+
+    /// Open the window at this size in pixels, e.g. `1280x800`, instead of the
+    /// size and position it had when last closed.
+    #[arg(long, value_name = "WxH", value_parser = window_geometry::parse_window_size,
+          conflicts_with = "headless")]
+    window_size: Option<WindowSize>,
+
+    /// Write numbers and dates as this locale does, e.g. `de-DE` for `1.234,5`
+    /// and `05.03.2024`, in the table, copies and exports and terminal output;
+    /// `C` writes them as stored. Defaults to `locale` in `settings.json`.
+    #[arg(long, value_name = "LOCALE", global = true)]
+    locale: Option<Locale>,
+
+    /// Show null values as this text, `null` by default; filters and highlight
+    /// rules for nulls match it too.
+    #[arg(long, value_name = "TEXT", global = true)]
+    show_null: Option<String>,
+
+    /// Show NaN as this text, `NaN` by default.
+    #[arg(long, value_name = "TEXT", global = true)]
+    show_nan: Option<String>,
+
+    /// Show infinity as this text, `inf` by default, with a `-` in front for
+    /// negative infinity.
+    #[arg(long, value_name = "TEXT", global = true)]
+    show_inf: Option<String>,
+
+    /// Show empty strings as this text; they are left empty by default and
+    /// marked `""` in the table.
+    #[arg(long, value_name = "TEXT", global = true)]
+    show_empty: Option<String>,
+
+    /// How failures are reported on stderr: as text, or as one JSON object with
+    /// the error's kind, exit code and message, for scripts.
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Text, global = true)]
+    error_format: ErrorFormat,
+
+    #[command(flatten)]
+    log: LogArgs,
 }
 
-#[derive(Debug, Error)]
-enum ViewerError {
-    #[error("failed to open parquet file: {0}")]
-    OpenFailed(#[from] std::io::Error),
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorFormat {
+    Text,
+    Json,
+}
 
-    #[error("failed to read parquet batches: {0}")]
-    ReadFailed(#[from] parquet::errors::ParquetError),
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Rewrite a Parquet file with different writer properties.
+    Rewrite(RewriteArgs),
+
+    /// Rewrite a Parquet file with columns renamed or cast to wider types, e.g.
+    /// Int32 to Int64 or Utf8 to LargeUtf8, a batch at a time.
+    RewriteSchema(RewriteSchemaArgs),
+
+    /// Write a copy of a file with only some of its columns, copying their
+    /// column chunks as they are so that even a huge file slims down quickly.
+    Select {
+        /// Parquet file to read.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Comma-separated top-level columns to keep, in order.
+        #[arg(
+            short,
+            long,
+            value_name = "COLUMNS",
+            value_delimiter = ',',
+            required = true
+        )]
+        columns: Vec<String>,
+
+        /// Destination for the slimmed-down file.
+        #[arg(short, long, value_name = "OUTPUT")]
+        output: PathBuf,
+    },
+
+    /// Concatenate Parquet files with matching schemas into one file.
+    Merge(MergeArgs),
+
+    /// Salvage the row groups of a file whose footer is missing or corrupt, e.g.
+    /// one cut off while it was written, into a new file.
+    Recover(RecoverArgs),
+
+    /// Write the rows of a file that pass some filters to CSV or PostgreSQL
+    /// `COPY` format, streaming it so that a file of any size exports at disk
+    /// speed in constant memory.
+    Export {
+        /// Parquet file to export.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Destination file.
+        #[arg(short, long, value_name = "OUTPUT")]
+        output: PathBuf,
+
+        /// Output format; `pg-text` and `pg-binary` are what `COPY ... FROM`
+        /// reads with `FORMAT text` and `FORMAT binary`, and also print the
+        /// `CREATE TABLE` statement and `\copy` command that load the file.
+        #[arg(long, value_enum, default_value_t = ExportFileFormat::Csv)]
+        format: ExportFileFormat,
+
+        /// Keep only rows passing this filter, written as in the query bar,
+        /// e.g. `status = ERROR` or `message ~ timed? out`; repeat to require several.
+        #[arg(long, value_name = "FILTER")]
+        filter: Vec<String>,
+
+        /// Comma-separated top-level columns to write, in order; all by default.
+        #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+        columns: Vec<String>,
+    },
+
+    /// Decode every page of a file and report corrupt column chunks.
+    Verify {
+        /// Parquet file to check.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+
+    /// Print the page headers of one column chunk: type, encoding, value counts,
+    /// sizes and statistics.
+    Pages {
+        /// Parquet file to read.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Leaf column, as a dotted path for nested columns.
+        #[arg(long, value_name = "COLUMN")]
+        column: String,
+
+        /// Row group of the column chunk, counted from zero.
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        row_group: usize,
+
+        /// Also write each page's decompressed bytes, back to back, to this file.
+        #[arg(long, value_name = "OUTPUT")]
+        raw: Option<PathBuf>,
+    },
+
+    /// Print the footer as JSON with the field names of its Thrift structures
+    /// (FileMetaData, RowGroup, ColumnChunk), to debug files other writers or
+    /// readers disagree about.
+    Footer {
+        /// Parquet file to read.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+
+    /// Report how a file was written: writer, statistics, page index, bloom filters and encodings.
+    Doctor {
+        /// Parquet file to describe.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+
+    /// Report per-column data quality: nulls, distinct values, min/max, top values,
+    /// blank strings and out-of-range timestamps.
+    Profile {
+        /// Parquet file to profile.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Report format.
+        #[arg(long, value_enum, default_value_t = ProfileFormat::Text)]
+        format: ProfileFormat,
+
+        /// Write the report to this file instead of stdout.
+        #[arg(short, long, value_name = "OUTPUT")]
+        output: Option<PathBuf>,
+
+        /// Read only this many row groups, picked at random, for a quick
+        /// estimate of a file too big to scan whole.
+        #[arg(long, value_name = "ROW_GROUPS",
+              value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        sample: Option<usize>,
+
+        /// Pick the sampled row groups with this seed, as a sampled report
+        /// notes, to repeat it.
+        #[arg(long, value_name = "SEED", requires = "sample")]
+        seed: Option<u64>,
+    },
+
+    /// Print a completion script for a shell, e.g.
+    /// `parquet-viewer completions bash > ~/.local/share/bash-completion/completions/parquet-viewer`.
+    Completions {
+        /// Shell to complete in.
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the man page in roff format, e.g. `parquet-viewer man | man -l -`.
+    Man {
+        /// Write a page for the viewer and one for each subcommand into this
+        /// directory instead.
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
+    /// Make this executable the handler of `parquet-viewer://` links for the
+    /// current user.
+    RegisterLinks,
+
+    /// Measure read performance: metadata parse time, full-scan throughput and
+    /// decode speed per column.
+    Bench {
+        /// Parquet file to measure.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Times each measurement is taken; the report gives the median.
+        #[arg(long, value_name = "N", default_value_t = 3,
+              value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        runs: usize,
+
+        /// Drop the file from the OS page cache before every timed read (Linux
+        /// only), instead of reading it once beforehand to warm the cache.
+        #[arg(long)]
+        cold: bool,
+
+        /// Report format.
+        #[arg(long, value_enum, default_value_t = BenchFormat::Text)]
+        format: BenchFormat,
+    },
+
+    /// Print the first rows of a file as canonical text, schema first, or with
+    /// `--hash` its SHA-256, to assert in tests that a pipeline's output is unchanged.
+    Snapshot {
+        /// Parquet file to snapshot.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Number of rows from the top of the file to include.
+        #[arg(short, long, default_value_t = 20)]
+        rows: usize,
+
+        /// Print only the SHA-256 of the snapshot, in hex.
+        #[arg(long)]
+        hash: bool,
+    },
+
+    /// Check a file's row count and columns, exiting with code 11 if any check
+    /// fails, for CI jobs that guard a pipeline's output.
+    Assert {
+        /// Parquet file to check.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Fail if the file has fewer rows; `--min-rows 1` rejects empty files.
+        #[arg(long, value_name = "N")]
+        min_rows: Option<usize>,
+
+        /// Fail if the file has more rows.
+        #[arg(long, value_name = "N")]
+        max_rows: Option<usize>,
+
+        /// Comma-separated top-level columns the file must have.
+        #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+        require_columns: Vec<String>,
+
+        /// Comma-separated top-level columns that must have no nulls.
+        #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+        non_null: Vec<String>,
+    },
+
+    /// Check that a file's columns have the names, types, nullability and order
+    /// of an expected schema, exiting with code 7 and each difference if not.
+    Validate {
+        /// Parquet file to check.
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+
+        /// Expected schema: a JSON file such as
+        /// `{"fields": [{"name": "id", "type": "Int64", "nullable": false}]}`,
+        /// or an Arrow IPC or Parquet file whose schema is expected.
+        #[arg(long, value_name = "SCHEMA")]
+        schema: PathBuf,
+    },
+}
 
-    #[error("failed to format parquet preview: {0}")]
-    FormatFailed(#[from] arrow::error::ArrowError),
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum BenchFormat {
+    Text,
+    Json,
 }
 
-#[derive(Clone)]
-struct DataPreview {
-    path: PathBuf,
-    formatted_rows: String,
-    columns: Vec<String>,
-    rows: Vec<Vec<String>>,
-    row_count: usize,
-    column_count: usize,
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportFileFormat {
+    Csv,
+    PgText,
+    PgBinary,
 }
 
-fn main() -> Result<(), ViewerError> {
-    tracing_subscriber::fmt::init();
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ProfileFormat {
+    Text,
+    Json,
+    Html,
+}
 
-    let args = Args::parse();
-    info!(
-        path = %args.path.to_string_lossy(),
-        rows = args.rows,
-        "loading parquet file"
-    );
-    let preview = load_preview(&args.path, args.rows)?;
+#[derive(ClapArgs, Debug)]
+struct RewriteArgs {
+    /// Parquet file to read.
+    #[arg(value_name = "FILE")]
+    input: PathBuf,
 
-    if args.headless {
-        print_to_terminal(&preview);
-        return Ok(());
-    }
+    /// Destination for the rewritten file.
+    #[arg(short, long, value_name = "OUTPUT")]
+    output: PathBuf,
 
-    launch_ui(preview);
+    #[command(flatten)]
+    writer: WriterArgs,
+}
 
-    Ok(())
+#[derive(ClapArgs, Debug)]
+struct RewriteSchemaArgs {
+    /// Parquet file to read.
+    #[arg(value_name = "FILE")]
+    input: PathBuf,
+
+    /// Destination for the rewritten file.
+    #[arg(short, long, value_name = "OUTPUT")]
+    output: PathBuf,
+
+    /// Rename a top-level column, written as `OLD=NEW`; repeat for several.
+    #[arg(long, value_name = "OLD=NEW")]
+    rename: Vec<String>,
+
+    /// Cast a top-level column to a type that holds all its values, written
+    /// as `COLUMN=TYPE` with the type as the viewer shows it, e.g. `id=Int64`;
+    /// repeat for several.
+    #[arg(long, value_name = "COLUMN=TYPE")]
+    cast: Vec<String>,
+
+    /// JSON file of renames and casts, e.g.
+    /// `{"rename": {"ts": "event_time"}, "cast": {"id": "Int64"}}`, applied
+    /// before the flags. Columns are named as in the input file.
+    #[arg(long, value_name = "MAPPING")]
+    mapping: Option<PathBuf>,
+
+    #[command(flatten)]
+    writer: WriterArgs,
 }
 
-fn load_preview(path: &PathBuf, row_limit: usize) -> Result<DataPreview, ViewerError> {
-    let file = File::open(path)?;
-    let metadata = SerializedFileReader::new(file.try_clone()?)?
-        .metadata()
-        .clone();
-    let row_count = metadata.file_metadata().num_rows() as usize;
-    let column_count = metadata.file_metadata().schema_descr().columns().len();
-
-    let columns = load_columns(&file)?;
-    let preview_limit = row_limit.min(row_count);
-    let batches = load_batches(path, 0, preview_limit)?;
-    let rows = batches_to_rows(&batches, preview_limit)?;
-
-    let formatted_rows = if batches.is_empty() {
-        "(no rows found)".to_string()
-    } else {
-        pretty_format_batches(&batches)?.to_string()
-    };
+#[derive(ClapArgs, Debug)]
+struct MergeArgs {
+    /// Parquet files to concatenate, in order.
+    #[arg(value_name = "FILES", required = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Destination for the merged file.
+    #[arg(short, long, value_name = "OUTPUT")]
+    output: PathBuf,
 
-    Ok(DataPreview {
-        path: path.clone(),
-        formatted_rows,
-        columns,
-        rows,
-        row_count,
-        column_count,
-    })
+    #[command(flatten)]
+    writer: WriterArgs,
 }
 
-fn load_columns(file: &File) -> Result<Vec<String>, ViewerError> {
-    let mut reader = ParquetRecordBatchReaderBuilder::try_new(file.try_clone()?)?.build()?;
+#[derive(ClapArgs, Debug)]
+struct RecoverArgs {
+    /// Damaged Parquet file to salvage.
+    #[arg(value_name = "FILE")]
+    input: PathBuf,
 
-    if let Some(batch) = reader.next() {
-        let batch = batch?;
-        Ok(batch
-            .schema()
-            .fields()
-            .iter()
-            .map(|field| field.name().clone())
-            .collect())
-    } else {
-        Ok(Vec::new())
+    /// Destination for the recovered rows.
+    #[arg(short, long, value_name = "OUTPUT")]
+    output: PathBuf,
+
+    /// Healthy file written the same way, whose schema and compression the
+    /// damaged file shares; needed when the damaged file's footer is unreadable.
+    #[arg(long, value_name = "REFERENCE")]
+    schema: Option<PathBuf>,
+
+    #[command(flatten)]
+    writer: WriterArgs,
+}
+
+/// Writer properties shared by the subcommands that produce Parquet files.
+#[derive(ClapArgs, Debug)]
+struct WriterArgs {
+    /// Compression codec, e.g. `snappy`, `zstd(3)`, `gzip(6)` or `uncompressed`.
+    #[arg(short, long, default_value = "snappy")]
+    compression: Compression,
+
+    /// Maximum number of rows per row group.
+    #[arg(long, value_name = "ROWS")]
+    row_group_size: Option<usize>,
+
+    /// Enable dictionary encoding.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    dictionary: bool,
+
+    /// Write page-level statistics and the offset index.
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    page_index: bool,
+}
+
+impl From<WriterArgs> for RewriteOptions {
+    fn from(args: WriterArgs) -> Self {
+        Self {
+            compression: args.compression,
+            row_group_size: args.row_group_size,
+            dictionary: args.dictionary,
+            page_index: args.page_index,
+        }
     }
 }
 
-fn load_batches(
-    path: &PathBuf,
-    start: usize,
-    limit: usize,
-) -> Result<Vec<RecordBatch>, ViewerError> {
-    if limit == 0 {
-        return Ok(Vec::new());
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let error_format = args.error_format;
+    match logging::init(&args.log)
+        .map_err(ViewerError::from)
+        .and_then(|()| run(args))
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            report_error(&error, error_format);
+            ExitCode::from(error.exit_code())
+        }
     }
+}
 
-    let selection = RowSelection::from(vec![RowSelector::skip(start), RowSelector::select(limit)]);
-    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?
-        .with_row_selection(selection)
-        .with_batch_size(limit)
-        .build()?;
+/// Print `error` to stderr; as JSON it is one line such as
+/// `{"error":"file_missing","code":3,"message":"..."}`.
+fn report_error(error: &ViewerError, format: ErrorFormat) {
+    match format {
+        ErrorFormat::Text => eprintln!("Error: {error}"),
+        ErrorFormat::Json => eprintln!(
+            "{}",
+            serde_json::json!({
+                "error": error.kind(),
+                "code": error.exit_code(),
+                "message": error.to_string(),
+            })
+        ),
+    }
+}
 
-    let mut batches = Vec::new();
-    for batch in reader {
-        batches.push(batch?);
+fn run(args: Args) -> Result<(), ViewerError> {
+    init_thread_pool(args.threads);
+    spill::init_memory_limit(args.memory_limit);
+    batching::init_batch_size(args.batch_size.map(|rows| rows as usize));
+    utf8::init_lossy_utf8(args.lossy_utf8);
+    let settings = Settings::load();
+    init_placeholders(Placeholders {
+        null: args.show_null.unwrap_or(settings.placeholders.null),
+        nan: args.show_nan.unwrap_or(settings.placeholders.nan),
+        infinity: args.show_inf.unwrap_or(settings.placeholders.infinity),
+        empty: args.show_empty.unwrap_or(settings.placeholders.empty),
+    });
+    let locale = args
+        .locale
+        .or_else(|| saved_locale(settings.locale.as_deref()?));
+    render::install_registry(cell_renderers(locale));
+    if let Some(command) = args.command {
+        return run_command(command);
     }
 
-    Ok(batches)
+    let start = args.select.or(args.goto);
+    let link = args.path.map(|path| match path.to_str() {
+        Some(text) if DeepLink::is_link(text) => DeepLink::parse(text).unwrap_or_else(|message| {
+            Args::command()
+                .error(ErrorKind::ValueValidation, message)
+                .exit()
+        }),
+        _ => DeepLink {
+            path,
+            start,
+            columns: Vec::new(),
+        },
+    });
+
+    if args.headless {
+        let path = link.expect("clap requires FILE with --headless").path;
+        info!(
+            path = %path.to_string_lossy(),
+            rows = args.rows,
+            "loading parquet file"
+        );
+        let preview = load_preview(&path, args.rows, args.mmap)?;
+        print_to_terminal(&preview, args.truncate)?;
+        return Ok(());
+    }
+
+    if let Some(output) = args.to_html {
+        let path = link.expect("clap requires FILE with --to-html").path;
+        info!(
+            path = %path.to_string_lossy(),
+            output = %output.to_string_lossy(),
+            "writing html report"
+        );
+        ensure_distinct([path.as_path()], &output)?;
+        let preview = load_preview(&path, args.rows, args.mmap)?;
+        let report = html_report(&preview)?;
+        write_atomically(&output, |mut file| {
+            file.write_all(report.as_bytes())?;
+            Ok(())
+        })?;
+        println!("Wrote report to {}", output.to_string_lossy());
+        return Ok(());
+    }
+
+    launch_ui(
+        link,
+        args.rows,
+        args.mmap,
+        args.theme,
+        args.window_size,
+        args.maximized,
+    );
+
+    Ok(())
 }
 
-fn batches_to_rows(
-    batches: &[RecordBatch],
-    row_limit: usize,
-) -> Result<Vec<Vec<String>>, ViewerError> {
-    let mut rows = Vec::new();
-
-    for batch in batches {
-        for row_index in 0..batch.num_rows() {
-            let mut row = Vec::new();
-            for column_index in 0..batch.num_columns() {
-                let column = batch.column(column_index);
-                let value = if column.is_null(row_index) {
-                    "null".to_string()
-                } else {
-                    array_value_to_string(column.as_ref(), row_index)?
+/// Size the pool that decodes row groups concurrently.
+fn init_thread_pool(threads: Option<usize>) {
+    let Some(threads) = threads else {
+        return;
+    };
+    if let Err(error) = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+    {
+        tracing::warn!(%error, threads, "failed to configure the decoding thread pool");
+    }
+}
+
+/// Renderers for columns that shouldn't use the default Arrow formatting;
+/// extensions register theirs here.
+fn cell_renderers(locale: Option<Locale>) -> RendererRegistry {
+    let mut registry = RendererRegistry::builtin();
+    if let Some(locale) = locale {
+        registry.register(
+            RendererMatch::Detect(is_localized_column),
+            Arc::new(LocaleRenderer(locale)),
+        );
+    }
+    registry
+}
+
+/// The locale `tag` saved in `settings.json`, if it names one.
+fn saved_locale(tag: &str) -> Option<Locale> {
+    tag.parse()
+        .inspect_err(|error| tracing::warn!(%error, "ignoring the locale in settings.json"))
+        .ok()
+}
+
+fn run_command(command: Command) -> Result<(), ViewerError> {
+    match command {
+        Command::Rewrite(args) => {
+            let options = RewriteOptions::from(args.writer);
+            info!(
+                input = %args.input.to_string_lossy(),
+                output = %args.output.to_string_lossy(),
+                "rewriting parquet file"
+            );
+            let progress = Progress::default();
+            let summary = {
+                let _line = TerminalProgress::start(&progress, "Rewriting", "rows");
+                rewrite_file(&args.input, &args.output, &options, &progress)
+            }?;
+            println!(
+                "Rewrote {} rows: {} -> {} row groups, {} -> {} bytes",
+                summary.rows,
+                summary.input_row_groups,
+                summary.output_row_groups,
+                summary.input_bytes,
+                summary.output_bytes
+            );
+            Ok(())
+        }
+        Command::RewriteSchema(args) => {
+            let mut changes = SchemaChanges::default();
+            if let Some(mapping) = &args.mapping {
+                changes.add_mapping_file(mapping)?;
+            }
+            for rename in &args.rename {
+                changes.add_rename(rename)?;
+            }
+            for cast in &args.cast {
+                changes.add_cast(cast)?;
+            }
+            if changes.is_empty() {
+                return Err(ViewerError::InvalidValue(
+                    "nothing to change; pass --rename, --cast or --mapping".to_string(),
+                ));
+            }
+            let options = RewriteOptions::from(args.writer);
+            info!(
+                input = %args.input.to_string_lossy(),
+                output = %args.output.to_string_lossy(),
+                renames = changes.renames.len(),
+                casts = changes.casts.len(),
+                "rewriting parquet schema"
+            );
+            let progress = Progress::default();
+            let summary = {
+                let _line = TerminalProgress::start(&progress, "Rewriting", "rows");
+                rewrite_schema(&args.input, &args.output, &changes, &options, &progress)
+            }?;
+            println!(
+                "Rewrote {} rows with {} renamed and {} cast columns: {} -> {} bytes",
+                summary.rows,
+                changes.renames.len(),
+                changes.casts.len(),
+                summary.input_bytes,
+                summary.output_bytes
+            );
+            Ok(())
+        }
+        Command::Select {
+            path,
+            columns,
+            output,
+        } => {
+            info!(
+                path = %path.to_string_lossy(),
+                output = %output.to_string_lossy(),
+                columns = columns.len(),
+                "selecting parquet columns"
+            );
+            let progress = Progress::default();
+            let summary = {
+                let _line = TerminalProgress::start(&progress, "Copying", "row groups");
+                select_columns(&path, &output, &columns, &progress)
+            }?;
+            println!(
+                "Kept {} columns of {} rows in {} row groups: {} -> {} bytes",
+                columns.len(),
+                summary.rows,
+                summary.output_row_groups,
+                summary.input_bytes,
+                summary.output_bytes
+            );
+            Ok(())
+        }
+        Command::Merge(args) => {
+            let options = RewriteOptions::from(args.writer);
+            info!(
+                files = args.inputs.len(),
+                output = %args.output.to_string_lossy(),
+                "merging parquet files"
+            );
+            let progress = Progress::default();
+            let summary = {
+                let _line = TerminalProgress::start(&progress, "Merging", "rows");
+                merge_files(&args.inputs, &args.output, &options, &progress)
+            }?;
+            println!(
+                "Merged {} files ({} rows) into {} row groups, {} bytes",
+                summary.files, summary.rows, summary.output_row_groups, summary.output_bytes
+            );
+            Ok(())
+        }
+        Command::Recover(args) => {
+            let options = RewriteOptions::from(args.writer);
+            info!(
+                input = %args.input.to_string_lossy(),
+                output = %args.output.to_string_lossy(),
+                "recovering parquet file"
+            );
+            let progress = Progress::default();
+            let summary = {
+                let _line = TerminalProgress::start(&progress, "Recovering", "row groups");
+                recover_file(
+                    &args.input,
+                    &args.output,
+                    args.schema.as_deref(),
+                    &options,
+                    &progress,
+                )
+            }?;
+            if let Some((offset, reason)) = &summary.stopped {
+                println!("Stopped at byte {offset}: {reason}");
+            }
+            println!(
+                "Recovered {} rows in {} row groups from {} pages, {} bytes",
+                summary.rows, summary.row_groups, summary.pages, summary.output_bytes
+            );
+            Ok(())
+        }
+        Command::Export {
+            path,
+            output,
+            format,
+            filter,
+            columns,
+        } => {
+            let filters = filter
+                .iter()
+                .map(|text| ValueFilter::parse(text))
+                .collect::<Result<Vec<_>, _>>()?;
+            info!(
+                path = %path.to_string_lossy(),
+                output = %output.to_string_lossy(),
+                ?format,
+                filters = filters.len(),
+                "exporting parquet file"
+            );
+            let copy_format = match format {
+                ExportFileFormat::Csv => None,
+                ExportFileFormat::PgText => Some(CopyFormat::Text),
+                ExportFileFormat::PgBinary => Some(CopyFormat::Binary),
+            };
+            let progress = Progress::default();
+            let bytes_read = Default::default();
+            let summary = {
+                let _line = TerminalProgress::start(&progress, "Exporting", "row groups");
+                match copy_format {
+                    Some(copy_format) => stream_copy(
+                        &path,
+                        &filters,
+                        &columns,
+                        &output,
+                        copy_format,
+                        &bytes_read,
+                        &progress,
+                    ),
+                    None => stream_csv(&path, &filters, &columns, &output, &bytes_read, &progress),
+                }
+            }?;
+            println!(
+                "Wrote {} of {} rows read to {}",
+                summary.rows_written,
+                summary.rows_read,
+                output.to_string_lossy()
+            );
+            if let Some(copy_format) = copy_format {
+                let table = output.file_stem().unwrap_or_default().to_string_lossy();
+                let stream = RowStream::new(&path, &filters, &columns, &bytes_read)?;
+                print!("\n{}", create_table(&table, stream.schema())?);
+                let options = match copy_format {
+                    CopyFormat::Text => "",
+                    CopyFormat::Binary => " WITH (FORMAT binary)",
                 };
-                row.push(value);
+                println!(
+                    "\\copy {} FROM '{}'{options}",
+                    quote_identifier(&table),
+                    output.to_string_lossy().replace('\'', "''")
+                );
             }
-            rows.push(row);
-
-            if rows.len() >= row_limit {
-                return Ok(rows);
+            Ok(())
+        }
+        Command::Verify { path } => {
+            info!(path = %path.to_string_lossy(), "verifying parquet file");
+            let progress = Progress::default();
+            let report = {
+                let _line = TerminalProgress::start(&progress, "Verifying", "column chunks");
+                verify_file(&path, &progress)
+            }?;
+            for failure in &report.failures {
+                let page = failure
+                    .page
+                    .map(|page| format!(", page {page}"))
+                    .unwrap_or_default();
+                println!(
+                    "row group {}, column `{}`{page}: {}",
+                    failure.row_group, failure.column, failure.message
+                );
+            }
+            println!(
+                "Checked {} row groups, {} column chunks, {} pages, {} rows: {}",
+                report.row_groups,
+                report.column_chunks,
+                report.pages,
+                report.rows,
+                if report.is_ok() { "OK" } else { "CORRUPT" }
+            );
+            if report.is_ok() {
+                Ok(())
+            } else {
+                Err(ViewerError::VerificationFailed(report.failures.len()))
+            }
+        }
+        Command::Pages {
+            path,
+            column,
+            row_group,
+            raw,
+        } => {
+            for line in dump_pages(&path, &column, row_group, raw.as_deref())?.lines() {
+                println!("{line}");
+            }
+            Ok(())
+        }
+        Command::Footer { path } => {
+            println!("{}", footer_json(&path)?);
+            Ok(())
+        }
+        Command::Doctor { path } => {
+            for line in writer_report(&path)?.lines() {
+                println!("{line}");
+            }
+            Ok(())
+        }
+        Command::Profile {
+            path,
+            format,
+            output,
+            sample,
+            seed,
+        } => {
+            info!(path = %path.to_string_lossy(), ?sample, "profiling parquet file");
+            let progress = Progress::default();
+            let profile = {
+                let _line = TerminalProgress::start(&progress, "Profiling", "row groups");
+                match sample {
+                    Some(row_groups) => {
+                        sample_profile(&path, row_groups, seed, &Default::default(), &progress)
+                    }
+                    None => profile(&path, &Default::default(), &progress),
+                }
+            }?;
+            let report = match format {
+                ProfileFormat::Text => profile.lines().join("\n") + "\n",
+                ProfileFormat::Json => profile.to_json() + "\n",
+                ProfileFormat::Html => profile.to_html(&path.to_string_lossy()),
+            };
+            match output {
+                Some(output) => {
+                    ensure_distinct([path.as_path()], &output)?;
+                    write_atomically(&output, |mut file| {
+                        file.write_all(report.as_bytes())?;
+                        Ok(())
+                    })?;
+                }
+                None => print!("{report}"),
+            }
+            Ok(())
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Args::command(),
+                bin_name(),
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+        Command::Man { dir } => {
+            match dir {
+                Some(dir) => {
+                    std::fs::create_dir_all(&dir)?;
+                    clap_mangen::generate_to(Args::command(), &dir)?;
+                }
+                None => clap_mangen::Man::new(Args::command()).render(&mut std::io::stdout())?,
+            }
+            Ok(())
+        }
+        Command::RegisterLinks => {
+            println!("{}", deep_link::register()?);
+            Ok(())
+        }
+        Command::Bench {
+            path,
+            runs,
+            cold,
+            format,
+        } => {
+            info!(path = %path.to_string_lossy(), runs, cold, "benchmarking parquet file");
+            let options = BenchOptions {
+                runs,
+                cache: if cold { Cache::Cold } else { Cache::Warm },
+            };
+            let progress = Progress::default();
+            let report = {
+                let _line = TerminalProgress::start(&progress, "Benchmarking", "runs");
+                bench_file(&path, &options, &progress)
+            }?;
+            match format {
+                BenchFormat::Text => {
+                    for line in report.lines() {
+                        println!("{line}");
+                    }
+                }
+                BenchFormat::Json => println!("{}", report.to_json()),
+            }
+            Ok(())
+        }
+        Command::Snapshot { path, rows, hash } => {
+            let preview = load_preview(&path, rows, false)?;
+            if hash {
+                println!("{}", snapshot_digest(&preview));
+            } else {
+                print!("{}", snapshot_text(&preview));
+            }
+            Ok(())
+        }
+        Command::Assert {
+            path,
+            min_rows,
+            max_rows,
+            require_columns,
+            non_null,
+        } => {
+            info!(path = %path.to_string_lossy(), "checking parquet file");
+            let contract = Contract {
+                min_rows,
+                max_rows,
+                require_columns,
+                non_null,
+            };
+            let violations = check_contract(&path, &contract, &Default::default())?;
+            for violation in &violations {
+                println!("{violation}");
+            }
+            if violations.is_empty() {
+                println!("{}: OK", path.display());
+                Ok(())
+            } else {
+                Err(ViewerError::ContractViolated(violations.len()))
+            }
+        }
+        Command::Validate { path, schema } => {
+            info!(path = %path.to_string_lossy(), schema = %schema.to_string_lossy(), "validating parquet schema");
+            let expected = load_expected_schema(&schema)?;
+            let differences = validate_schema(&path, &expected, &Default::default())?;
+            if differences.is_empty() {
+                println!("{}: OK", path.display());
+                Ok(())
+            } else {
+                Err(ViewerError::SchemaMismatch(differences))
             }
         }
     }
+}
 
-    Ok(rows)
+/// Name the viewer was started as, which completions are generated for.
+fn bin_name() -> String {
+    std::env::args_os()
+        .next()
+        .map(PathBuf::from)
+        .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| Args::command().get_name().to_string())
 }
 
-fn print_to_terminal(preview: &DataPreview) {
+fn print_to_terminal(preview: &DataPreview, max_width: Option<usize>) -> Result<(), ViewerError> {
     println!(
         "Rows: {} | Columns: {}\n",
         preview.row_count, preview.column_count
     );
-    println!("{}", preview.formatted_rows);
-}
-
-impl DataPreview {
-    fn rows_for_range(&self, range: Range<usize>) -> Result<Vec<Vec<String>>, ViewerError> {
-        if range.start >= self.row_count {
-            return Ok(Vec::new());
+    if let Some(geo) = &preview.geo {
+        for line in geo.summary() {
+            println!("GeoParquet {line}");
         }
+        println!();
+    }
+    match max_width {
+        Some(max_width) if !preview.rows.is_empty() => {
+            let rows: Vec<Vec<Cell>> = preview
+                .rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| Cell::from(truncate(cell, max_width, Truncation::End)))
+                        .collect()
+                })
+                .collect();
+            println!("{}", format_rows(&preview.columns, &rows)?);
+        }
+        _ => println!("{}", preview.formatted_rows),
+    }
 
-        let available = (self.row_count - range.start).min(range.end.saturating_sub(range.start));
-        let batches = load_batches(&self.path, range.start, available)?;
-
-        batches_to_rows(&batches, available)
+    for range in &preview.unreadable {
+        println!(
+            "\nRow group {} (rows {}-{}) could not be read: {}",
+            range.row_group,
+            range.rows.start + 1,
+            range.rows.end,
+            range.message
+        );
+    }
+    if !preview.lossy_cells.is_empty() {
+        println!(
+            "\n{} cell(s) held invalid UTF-8, shown with replacement characters",
+            preview.lossy_cells.len()
+        );
     }
+    for error in &preview.cell_errors {
+        println!(
+            "\nRow {}, column {} could not be shown: {}",
+            error.row + 1,
+            preview.columns[error.column],
+            error.message
+        );
+    }
+    Ok(())
 }
 
-const ROW_HEIGHT: f32 = 28.0;
 const MIN_TABLE_HEIGHT: f32 = 200.0;
 const TABLE_VERTICAL_MARGIN: f32 = 32.0;
 const TABLE_CHROME_HEIGHT: f32 = 180.0;
 const TABLE_BOTTOM_PADDING: f32 = 12.0;
+/// Width of the column left of the cells that shows and toggles bookmarks.
+const GUTTER_WIDTH: f32 = 24.0;
 
-fn rows_per_view(height: Pixels) -> usize {
-    ((f32::from(height) / ROW_HEIGHT).floor().max(1.0)) as usize
+fn rows_per_view(height: Pixels, row_height: f32) -> usize {
+    ((f32::from(height) / row_height).floor().max(1.0)) as usize
 }
 
-fn table_height_for_window(window: &gpui::Window) -> Pixels {
+/// Height left for table rows, in a view that has the window to itself or, when
+/// `stacked`, shares its height with a second pane.
+fn table_height_for_window(window: &gpui::Window, stacked: bool) -> Pixels {
     // Use the viewport size so that maximized windows report their actual content
     // height instead of the restore size stored in `window_bounds`.
     let window_height: f32 = window.viewport_size().height.into();
-    let available =
-        (window_height - TABLE_VERTICAL_MARGIN - TABLE_CHROME_HEIGHT - TABLE_BOTTOM_PADDING)
-            .max(MIN_TABLE_HEIGHT);
+    let panes = if stacked { 2.0 } else { 1.0 };
+    let available = ((window_height - MENU_BAR_HEIGHT) / panes
+        - TABLE_VERTICAL_MARGIN
+        - TABLE_CHROME_HEIGHT
+        - TABLE_BOTTOM_PADDING)
+        .max(MIN_TABLE_HEIGHT);
     px(available)
 }
 
-/// Launch a GPUI window that renders the formatted preview.
-fn launch_ui(preview: DataPreview) {
-    let preview_data = preview.clone();
-
-    Application::new().run(move |app: &mut App| {
+/// Launch a GPUI window that previews `path`, or shows the start screen when no
+/// file was given or it couldn't be opened. The window opens where it was last
+/// closed unless `window_size` is given.
+fn launch_ui(
+    link: Option<DeepLink>,
+    row_limit: usize,
+    mmap: bool,
+    theme: Option<String>,
+    window_size: Option<WindowSize>,
+    maximized: bool,
+) {
+    // Links clicked while the viewer runs arrive here on macOS; elsewhere each
+    // starts a new viewer with the link as FILE.
+    let (opened_links, mut links) = futures::channel::mpsc::unbounded();
+    let application = Application::new();
+    application.on_open_urls(move |urls| {
+        let _ = opened_links.unbounded_send(urls);
+    });
+    application.run(move |app: &mut App| {
         gpui_component::init(app);
-
-        let bounds = Bounds::centered(None, size(px(900.0), px(700.0)), app);
-        app.open_window(
-            WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(bounds)),
-                titlebar: Some(gpui::TitlebarOptions {
-                    title: Some("Parquet Viewer".into()),
+        theme::init(theme, app);
+        table_style::init(app);
+        workspace::init(app);
+
+        let (bounds, display_id) = initial_window(window_size, maximized, app);
+        let window = app
+            .open_window(
+                WindowOptions {
+                    window_bounds: Some(bounds),
+                    display_id,
+                    titlebar: Some(gpui::TitlebarOptions {
+                        title: Some("Parquet Viewer".into()),
+                        ..Default::default()
+                    }),
                     ..Default::default()
-                }),
-                ..Default::default()
-            },
-            move |window, cx| {
-                cx.new(|cx| {
-                    let table_height = table_height_for_window(window);
-                    let mut view = PreviewView {
-                        preview: preview_data.clone(),
-                        visible_rows: Vec::new(),
-                        visible_range: 0..0,
-                        table_height,
-                        rows_per_view: rows_per_view(table_height),
-                        selected_cell: None,
-                    };
-
-                    view.load_visible_rows(0, cx);
-
-                    cx.observe_window_bounds(window, |view, window, cx| {
-                        view.update_rows_for_resize(window, cx)
-                    })
-                    .detach();
-
-                    view
-                })
-            },
-        )
-        .unwrap();
+                },
+                move |window, cx| {
+                    let workspace = cx.new(|cx| Workspace::new(link, row_limit, mmap, window, cx));
+                    cx.new(|cx| Root::new(workspace, window, cx))
+                },
+            )
+            .unwrap();
         app.activate(true);
+
+        app.spawn(async move |cx| {
+            while let Some(urls) = links.next().await {
+                for url in urls {
+                    let link = DeepLink::parse(&url);
+                    let opened = window.update(cx, |root, window, cx| {
+                        window.activate_window();
+                        match link {
+                            Ok(link) => {
+                                if let Ok(workspace) = root.view().clone().downcast::<Workspace>() {
+                                    workspace.update(cx, |workspace, cx| {
+                                        workspace.open_link(link, window, cx)
+                                    });
+                                }
+                            }
+                            Err(message) => window.push_notification(message, cx),
+                        }
+                    });
+                    if opened.is_err() {
+                        return;
+                    }
+                }
+            }
+        })
+        .detach();
     });
 }
 
 struct PreviewView {
+    focus_handle: FocusHandle,
     preview: DataPreview,
-    visible_rows: Vec<Vec<String>>,
+    visible_rows: Vec<Vec<Cell>>,
+    /// Display rows currently shown; these map to file rows through `row_order`.
     visible_range: Range<usize>,
     table_height: Pixels,
     rows_per_view: usize,
     selected_cell: Option<(usize, usize)>,
+    /// Row groups that failed to decode so far, shown in the error panel.
+    unreadable: Vec<UnreadableRange>,
+    /// Why cells shown as `<error>` couldn't be rendered, by file row and column.
+    cell_errors: BTreeMap<(usize, usize), String>,
+    /// File row and column of cells whose invalid UTF-8 was replaced, badged in the table.
+    lossy_cells: BTreeSet<(usize, usize)>,
+    /// Source column index for each displayed column, left to right.
+    column_order: Vec<usize>,
+    /// Rows picked with click, Shift+click and Ctrl+click, used by copy and export.
+    selected_rows: SelectedRows,
+    hidden_columns: BTreeSet<usize>,
+    filters: Vec<ValueFilter>,
+    sort: Option<SortKey>,
+    /// File row for each display row while filtering or sorting; `None` shows the file as is.
+    row_order: Option<Vec<usize>>,
+    context_target: Option<ContextTarget>,
+    /// Statistics panel for one column, or the reason they couldn't be read.
+    column_stats: Option<Result<ColumnStats, String>>,
+    /// Cell inspector showing one value in full, or the reason it couldn't be read.
+    cell_detail: Option<Result<CellDetail, String>>,
+    /// Record view of the selected row, or the reason it couldn't be read.
+    record_view: Option<Result<RecordDetail, String>>,
+    /// Image held by the inspected cell, ready for the preview popover.
+    cell_image: Option<Arc<gpui::Image>>,
+    /// Writer report and metadata panel, or the reason the footer couldn't be read.
+    metadata_panel: Option<Result<FileInfo, String>>,
+    /// Compressed bytes per column and row group, or the reason they couldn't be read.
+    size_panel: Option<Result<SizeBreakdown, String>>,
+    /// Dictionary of one column chunk, or the reason it couldn't be read.
+    dictionary_panel: Option<Result<DictionaryChunk, String>>,
+    /// How each row group stored one column, from the Show Chunk Timeline menu item.
+    timeline_panel: Option<Result<ColumnTimeline, String>>,
+    /// How long the most recent viewport load took.
+    last_load: Option<Duration>,
+    /// First display row of the viewport load still streaming in, if any.
+    loading_start: Option<usize>,
+    /// The streaming load; replacing it cancels the previous one and its reads.
+    _load_task: Option<CancellableTask>,
+    /// Rows outside the preloaded head decoded around the viewport.
+    row_cache: RowCache,
+    /// Screens read ahead in each direction, from `settings.json`.
+    prefetch_screens: usize,
+    /// Display rows the last prefetch covered, so that it only restarts when they change.
+    prefetch_range: Option<Range<usize>>,
+    _prefetch_task: Option<CancellableTask>,
+    /// Filter scan or export running in the background.
+    operation: Option<RunningOperation>,
+    /// Bookmarked rows, by file row so that they survive filtering and sorting.
+    bookmarks: BTreeSet<usize>,
+    show_bookmarks: bool,
+    /// Sampled distribution of each numeric column, drawn in its header; empty
+    /// until the sample has been read.
+    histograms: Vec<Option<Histogram>>,
+    /// Numeric columns whose cells are shaded by value.
+    heat_maps: HeatMaps,
+    /// Rules painting the cells they match; saved with the file's session.
+    highlight_rules: Vec<HighlightRule>,
+    show_highlight_rules: bool,
+    /// Where data pages begin, read from the offset index the first time the
+    /// overlay is shown.
+    page_boundaries: Option<PageBoundaries>,
+    show_page_boundaries: bool,
+    /// Cells pinned to the comparison strip.
+    pins: Pins,
+    /// Timeline strip, for files with a timestamp column.
+    time_scrubber: Option<TimeScrubber>,
+    /// Order of each file column found from the row group statistics.
+    sorted_columns: Vec<Option<Sortedness>>,
+    /// Row group strip beside the table rows, when shown.
+    minimap: Option<Minimap>,
+    /// Data quality report, when Analyze → Profile Columns is on.
+    profile_panel: Option<ProfilePanel>,
+    /// Duplicate scan, once one has been asked for.
+    duplicates: Option<DuplicatesPanel>,
+    /// Group-by explorer, once a column has been picked to group by.
+    group_by: Option<GroupByPanel>,
+    /// Aggregates shown under the table, when the summary row is on.
+    summary: Option<SummaryRow>,
+    /// Whether the table is drawn with rows and columns swapped.
+    transposed: bool,
+    /// Text typed into the highlight box, marked in the visible cells while the box is open.
+    highlight: Option<Entity<InputState>>,
+    _highlight_changes: Option<Subscription>,
+    /// Query bar box where filters and a sort are typed; Enter runs them.
+    query_input: Entity<InputState>,
+    _query_submits: Subscription,
+    /// Queries run in this session, stepped through with Up and Down in the query bar.
+    history: QueryHistory,
+    show_history: bool,
+    /// History entry whose scan the explain panel shows.
+    explained_query: Option<usize>,
+    /// Backing file of a table pasted from the clipboard, deleted with the view.
+    scratch: Option<NamedTempFile>,
+    /// Second pane of a split tab, which starts at the top of the file and
+    /// leaves its session to the first pane.
+    split_pane: bool,
+    /// Whether the view shares the window's height with the other pane of a split.
+    stacked: bool,
+    /// Preferred width of each column, which the header and rows are laid out by.
+    column_widths: ColumnWidths,
+    /// Columns whose long text wraps onto several lines, by name.
+    wrapped_columns: BTreeSet<String>,
+    /// Rows of `visible_rows` that fit the table as last drawn, fewer than were
+    /// loaded when wrapped cells make rows taller.
+    fitted_rows: usize,
 }
 
-impl PreviewView {
-    fn load_visible_rows(&mut self, start: usize, cx: &mut gpui::Context<PreviewView>) {
-        if self.preview.row_count == 0 {
-            self.visible_rows.clear();
-            self.visible_range = 0..0;
-            cx.notify();
-            return;
+/// Resolve a saved column order by name, appending columns the saved order doesn't mention.
+fn column_order_from_names(columns: &[String], names: &[String]) -> Vec<usize> {
+    let mut order = Vec::with_capacity(columns.len());
+    for name in names {
+        if let Some(column) = columns.iter().position(|column| column == name) {
+            if !order.contains(&column) {
+                order.push(column);
+            }
         }
+    }
 
-        let start = start.min(self.preview.row_count.saturating_sub(1));
-        let end = (start + self.rows_per_view).min(self.preview.row_count);
-
-        match self.preview.rows_for_range(start..end) {
-            Ok(rows) => {
-                self.visible_range = start..(start + rows.len());
-                self.visible_rows = rows;
-                cx.notify();
-            }
-            Err(error) => {
-                tracing::error!(?error, "failed to load rows for viewport");
-            }
+    for column in 0..columns.len() {
+        if !order.contains(&column) {
+            order.push(column);
         }
     }
 
-    fn update_rows_for_resize(
-        &mut self,
+    order
+}
+
+/// Move the displayed column at position `from` so that it ends up at position `to`.
+fn move_column(order: &mut Vec<usize>, from: usize, to: usize) {
+    if from >= order.len() || to >= order.len() || from == to {
+        return;
+    }
+
+    let column = order.remove(from);
+    order.insert(to, column);
+}
+
+impl PreviewView {
+    /// View a Parquet file, restoring the state it was last left in.
+    fn new(
+        preview: DataPreview,
         window: &mut gpui::Window,
         cx: &mut gpui::Context<PreviewView>,
-    ) {
-        let new_table_height = table_height_for_window(window);
-        let new_rows_per_view = rows_per_view(new_table_height);
+    ) -> Self {
+        Self::build(preview, None, false, window, cx)
+    }
 
-        let rows_changed = new_rows_per_view != self.rows_per_view;
-        self.table_height = new_table_height;
-        self.rows_per_view = new_rows_per_view;
+    /// View a file in the second pane of a split, scrolled independently of
+    /// the first and without a session of its own.
+    pub(crate) fn new_pane(
+        preview: DataPreview,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Self {
+        Self::build(preview, None, true, window, cx)
+    }
 
-        if rows_changed {
-            self.load_visible_rows(self.visible_range.start, cx);
-        } else {
-            cx.notify();
-        }
+    /// View a table pasted from the clipboard or a join result, written to
+    /// `file`, which is removed when the view closes. Scratch tables have no session.
+    fn new_scratch(
+        preview: DataPreview,
+        file: NamedTempFile,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Self {
+        Self::build(preview, Some(file), false, window, cx)
     }
 
-    fn scroll_view(&mut self, delta_rows: isize, cx: &mut gpui::Context<PreviewView>) {
-        if self.preview.row_count == 0 {
-            return;
+    fn build(
+        preview: DataPreview,
+        scratch: Option<NamedTempFile>,
+        split_pane: bool,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Self {
+        let table_height = table_height_for_window(window, false);
+        let restore = scratch.is_none() && !split_pane;
+        let query_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Filter, e.g. status = ERROR | level != DEBUG")
+        });
+        let query_submits = cx.subscribe_in(
+            &query_input,
+            window,
+            |view, _, event: &InputEvent, window, cx| {
+                if matches!(event, InputEvent::PressEnter { .. }) {
+                    view.run_query(window, cx);
+                }
+            },
+        );
+        let time_scrubber = time_index(&preview.path, &preview.bytes_read)
+            .unwrap_or_else(|error| {
+                tracing::warn!(%error, "failed to read the time statistics");
+                None
+            })
+            .map(TimeScrubber::new);
+        let sorted_columns =
+            sorted_columns(&preview.path, &preview.bytes_read).unwrap_or_else(|error| {
+                tracing::warn!(%error, "failed to check which columns are sorted");
+                Vec::new()
+            });
+        let mut view = PreviewView {
+            focus_handle: cx.focus_handle(),
+            unreadable: preview.unreadable.clone(),
+            cell_errors: preview
+                .cell_errors
+                .iter()
+                .map(|error| ((error.row, error.column), error.message.clone()))
+                .collect(),
+            lossy_cells: preview.lossy_cells.iter().copied().collect(),
+            preview,
+            visible_rows: Vec::new(),
+            visible_range: 0..0,
+            table_height,
+            rows_per_view: rows_per_view(table_height, TableStyle::global(cx).scaled_row_height()),
+            selected_cell: None,
+            column_order: Vec::new(),
+            selected_rows: SelectedRows::default(),
+            hidden_columns: BTreeSet::new(),
+            filters: Vec::new(),
+            sort: None,
+            row_order: None,
+            context_target: None,
+            column_stats: None,
+            cell_detail: None,
+            record_view: None,
+            cell_image: None,
+            metadata_panel: None,
+            size_panel: None,
+            dictionary_panel: None,
+            timeline_panel: None,
+            last_load: None,
+            loading_start: None,
+            _load_task: None,
+            row_cache: RowCache::new(spill::memory_limit()),
+            prefetch_screens: Settings::load().prefetch_screens.min(MAX_PREFETCH_SCREENS),
+            prefetch_range: None,
+            _prefetch_task: None,
+            operation: None,
+            bookmarks: BTreeSet::new(),
+            show_bookmarks: false,
+            histograms: Vec::new(),
+            heat_maps: HeatMaps::default(),
+            highlight_rules: Vec::new(),
+            show_highlight_rules: false,
+            page_boundaries: None,
+            show_page_boundaries: false,
+            pins: Pins::default(),
+            time_scrubber,
+            sorted_columns,
+            minimap: None,
+            profile_panel: None,
+            duplicates: None,
+            group_by: None,
+            summary: None,
+            transposed: false,
+            highlight: None,
+            _highlight_changes: None,
+            query_input,
+            _query_submits: query_submits,
+            history: QueryHistory::default(),
+            show_history: false,
+            explained_query: None,
+            scratch,
+            split_pane,
+            stacked: false,
+            column_widths: ColumnWidths::default(),
+            wrapped_columns: BTreeSet::new(),
+            fitted_rows: usize::MAX,
+        };
+
+        let session = restore
+            .then(|| session::restore(&view.preview.path))
+            .flatten()
+            .unwrap_or_default();
+        view.column_order = column_order_from_names(&view.preview.columns, &session.column_order);
+        view.hidden_columns = session
+            .hidden_columns
+            .iter()
+            .filter_map(|name| {
+                view.preview
+                    .columns
+                    .iter()
+                    .position(|column| column == name)
+            })
+            .collect();
+        if view.hidden_columns.len() == view.preview.columns.len() {
+            view.hidden_columns.clear();
+        }
+        view.project_displayed_columns();
+        // Restoring the row order resets the selection, so it goes first.
+        if !session.filters.is_empty() || session.sort.is_some() {
+            view.set_row_order(session.filters, session.sort);
         }
+        view.bookmarks = session
+            .bookmarks
+            .into_iter()
+            .filter(|&row| row < view.preview.row_count)
+            .collect();
+        view.highlight_rules = session.highlight_rules;
+        view.selected_cell = session.selected_cell;
+        if let Some((row, _)) = session.selected_cell {
+            view.selected_rows.select(row);
+        }
+        view.load_visible_rows(session.scroll_row, cx);
+        view.load_histograms(cx);
+
+        cx.observe_window_bounds(window, |view, window, cx| {
+            view.update_rows_for_resize(window, cx)
+        })
+        .detach();
+        cx.observe_global_in::<TableStyle>(window, |view, window, cx| {
+            view.update_rows_for_resize(window, cx)
+        })
+        .detach();
+        // Rows marked in other tabs show the diff here too.
+        cx.observe_global_in::<MarkedRows>(window, |_, _, cx| cx.notify())
+            .detach();
+        cx.on_release(|view, _| view.remember_session()).detach();
+        cx.on_app_quit(|view, _| {
+            view.remember_session();
+            async {}
+        })
+        .detach();
+
+        view
+    }
 
-        let max_start = self
-            .preview
-            .row_count
-            .saturating_sub(self.rows_per_view)
-            .max(0);
+    pub(crate) fn path(&self) -> &Path {
+        &self.preview.path
+    }
 
-        let current_start = self.visible_range.start as isize;
-        let mut target_start = current_start + delta_rows;
-        if target_start < 0 {
-            target_start = 0;
-        }
+    /// Whether this view shows a pasted table or join result rather than a file.
+    pub(crate) fn is_scratch(&self) -> bool {
+        self.scratch.is_some()
+    }
 
-        if target_start as usize > max_start {
-            target_start = max_start as isize;
+    /// Save the view state so that reopening the file restores it.
+    fn remember_session(&self) {
+        if self.scratch.is_some() || self.split_pane {
+            return;
         }
+        session::remember(
+            &self.preview.path,
+            FileSession {
+                scroll_row: self.visible_range.start,
+                selected_cell: self.selected_cell,
+                column_order: self
+                    .column_order
+                    .iter()
+                    .map(|&column| self.preview.columns[column].clone())
+                    .collect(),
+                hidden_columns: self
+                    .hidden_columns
+                    .iter()
+                    .map(|&column| self.preview.columns[column].clone())
+                    .collect(),
+                filters: self.filters.clone(),
+                sort: self.sort.clone(),
+                bookmarks: self.bookmarks.iter().copied().collect(),
+                highlight_rules: self.highlight_rules.clone(),
+            },
+        );
+    }
 
-        if target_start as usize != self.visible_range.start {
-            self.load_visible_rows(target_start as usize, cx);
+    /// Move column `from` to where column `to` is shown, both as file column indices.
+    fn move_column(&mut self, from: usize, to: usize, cx: &mut gpui::Context<PreviewView>) {
+        let position = |column| self.column_order.iter().position(|&shown| shown == column);
+        if let (Some(from), Some(to)) = (position(from), position(to)) {
+            move_column(&mut self.column_order, from, to);
         }
+        cx.notify();
+    }
+
+    /// File column indices in display order, skipping hidden columns.
+    fn displayed_columns(&self) -> Vec<usize> {
+        self.column_order
+            .iter()
+            .copied()
+            .filter(|column| !self.hidden_columns.contains(column))
+            .collect()
+    }
+
+    /// Name of the file shown, which per-file filter presets are saved under.
+    fn file_name(&self) -> String {
+        self.preview
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
     }
 }
 
@@ -353,26 +1730,74 @@ impl gpui::Render for PreviewView {
             self.preview.row_count, self.preview.column_count
         );
 
-        let range_text = if self.preview.row_count == 0 {
+        let row_count = self.display_row_count();
+        let range_text = if row_count == 0 {
             "No rows available".to_string()
         } else {
-            let range_end =
-                (self.visible_range.start + self.visible_rows.len()).min(self.preview.row_count);
-            format!(
+            let range_end = self.shown_range().end.min(row_count);
+            let shown = format!(
                 "Showing rows {}-{}",
                 self.visible_range.start + 1,
                 range_end.max(self.visible_range.start + 1)
-            )
+            );
+            if self.row_order.is_some() {
+                format!("{shown} of {row_count} matching")
+            } else {
+                shown
+            }
         };
 
         let selected_text = self
             .selected_cell
-            .map(|(row, col)| format!("Selected: row {}, column {}", row + 1, col + 1))
+            .map(|(row, col)| {
+                format!(
+                    "Selected: row {}, column {}",
+                    self.file_row(row) + 1,
+                    col + 1
+                )
+            })
             .unwrap_or_else(|| "Click a cell to select it".to_string());
 
         let theme = cx.theme();
 
+        let status = render_status_bar(self, cx);
+
         div()
+            .track_focus(&self.focus_handle)
+            .key_context("PreviewView")
+            .on_action(cx.listener(Self::copy_value))
+            .on_action(cx.listener(Self::copy_row))
+            .on_action(cx.listener(Self::pin_cell))
+            .on_action(cx.listener(Self::filter_by_value))
+            .on_action(cx.listener(Self::filter_by_pattern))
+            .on_action(cx.listener(Self::jump_to_value))
+            .on_action(cx.listener(Self::sort_by_column))
+            .on_action(cx.listener(Self::clear_filters))
+            .on_action(cx.listener(Self::hide_column))
+            .on_action(cx.listener(Self::show_all_columns))
+            .on_action(cx.listener(Self::show_column_stats))
+            .on_action(cx.listener(Self::toggle_heat_map))
+            .on_action(cx.listener(Self::toggle_word_wrap))
+            .on_action(cx.listener(Self::show_dictionary))
+            .on_action(cx.listener(Self::show_chunk_timeline))
+            .on_action(cx.listener(Self::view_column_as))
+            .on_action(cx.listener(Self::add_computed_column))
+            .on_action(cx.listener(Self::remove_computed_column))
+            .on_action(cx.listener(Self::find_duplicates_by_column))
+            .on_action(cx.listener(Self::group_by_column))
+            .on_action(cx.listener(Self::aggregate_in_groups))
+            .on_action(cx.listener(Self::inspect_cell))
+            .on_action(cx.listener(Self::go_to_row_group))
+            .on_action(cx.listener(Self::rerun_query))
+            .on_action(cx.listener(Self::explain_query))
+            .on_action(cx.listener(Self::apply_filter_preset))
+            .on_action(cx.listener(Self::save_filter_preset))
+            .on_action(cx.listener(Self::delete_filter_preset))
+            .on_action(cx.listener(Self::import_filter_presets))
+            .on_action(cx.listener(Self::export_filter_presets))
+            .on_action(cx.listener(Self::show_record))
+            .on_action(cx.listener(Self::mark_row_for_diff))
+            .on_action(cx.listener(Self::close_highlight))
             .flex()
             .flex_col()
             .gap_3()
@@ -394,247 +1819,141 @@ impl gpui::Render for PreviewView {
                             .gap_2()
                             .children([div().child(metadata), div().child(range_text)]),
                     )
+                    .when_some(self.preview.geo.as_ref(), |this, geo| {
+                        this.child(render_geo_metadata(geo, cx))
+                    })
                     .child(
                         div()
                             .text_sm()
                             .text_color(theme.muted_foreground)
                             .child(selected_text),
                     )
-                    .child(render_table(self, cx)),
+                    .when(
+                        !self.unreadable.is_empty() || !self.cell_errors.is_empty(),
+                        |this| this.child(render_read_errors(self, cx)),
+                    )
+                    .child(render_query_bar(self, cx))
+                    .when_some(self.column_stats.as_ref(), |this, stats| {
+                        this.child(render_column_stats(stats, cx))
+                    })
+                    .when_some(self.metadata_panel.as_ref(), |this, entries| {
+                        this.child(render_metadata_panel(entries, cx))
+                    })
+                    .when_some(self.size_panel.as_ref(), |this, breakdown| {
+                        this.child(render_size_panel(breakdown, cx))
+                    })
+                    .when_some(self.profile_panel.as_ref(), |this, panel| {
+                        this.child(render_profile_panel(panel, cx))
+                    })
+                    .when_some(self.duplicates.as_ref(), |this, panel| {
+                        this.child(render_duplicates_panel(panel, cx))
+                    })
+                    .when_some(self.group_by.as_ref(), |this, panel| {
+                        this.child(render_group_by_panel(panel, cx))
+                    })
+                    .when_some(self.dictionary_panel.as_ref(), |this, chunk| {
+                        this.child(render_dictionary_panel(chunk, cx))
+                    })
+                    .when_some(self.timeline_panel.as_ref(), |this, timeline| {
+                        this.child(render_timeline_panel(timeline, cx))
+                    })
+                    .when_some(self.record_view.as_ref(), |this, record| {
+                        this.child(render_record_view(record, cx))
+                    })
+                    .when_some(
+                        MarkedRows::global(cx).and_then(MarkedRows::pair),
+                        |this, (left, right)| this.child(render_row_diff(left, right, cx)),
+                    )
+                    .when_some(self.cell_detail.as_ref(), |this, detail| {
+                        this.child(render_cell_detail(detail, self.cell_image.clone(), cx))
+                    })
+                    .when_some(self.operation.as_ref(), |this, operation| {
+                        this.child(render_operation(operation, cx))
+                    })
+                    .when_some(self.highlight.clone(), |this, input| {
+                        this.child(render_highlight_bar(self, input, cx))
+                    })
+                    .when(self.show_bookmarks, |this| {
+                        this.child(render_bookmarks(self, cx))
+                    })
+                    .when(self.show_highlight_rules, |this| {
+                        this.child(render_highlight_rules(self, cx))
+                    })
+                    .when(self.show_history, |this| {
+                        this.child(render_history_panel(&self.history, cx))
+                    })
+                    .when_some(
+                        self.explained_query
+                            .and_then(|index| self.history.entries().get(index)),
+                        |this, entry| this.child(render_explain_panel(entry, cx)),
+                    )
+                    .when_some(
+                        self.time_scrubber
+                            .as_ref()
+                            .filter(|scrubber| scrubber.shown),
+                        |this, scrubber| this.child(render_time_scrubber(scrubber, cx)),
+                    )
+                    .map(|this| {
+                        if self.transposed {
+                            this.child(render_transposed_table(self, cx))
+                        } else {
+                            this.child(render_table(self, cx))
+                        }
+                    }),
             )
+            .when(!self.pins.is_empty(), |this| {
+                this.child(render_compare_strip(&self.pins, cx))
+            })
+            .child(status)
     }
 }
 
-fn render_table(
-    view: &mut PreviewView,
-    cx: &mut gpui::Context<PreviewView>,
-) -> impl gpui::IntoElement {
-    let theme = cx.theme();
-
-    let header = div()
-        .flex()
-        .flex_row()
-        .w_full()
-        .bg(theme.table_head)
-        .text_color(theme.table_head_foreground)
-        .border_b_1()
-        .border_color(theme.table_row_border)
-        .children(view.preview.columns.iter().map(|name| {
-            div()
-                .px_2()
-                .py_1()
-                .font_medium()
-                .flex_1()
-                .min_w(px(80.0))
-                .border_r_1()
-                .border_color(theme.table_row_border)
-                .child(name.clone())
-        }));
-
-    let rows = view
-        .visible_rows
-        .iter()
-        .enumerate()
-        .map(|(row_index, row)| {
-            let global_row_index = view.visible_range.start + row_index;
-            div()
-                .flex()
-                .flex_row()
-                .w_full()
-                .border_b_1()
-                .border_color(theme.table_row_border)
-                .children(row.iter().enumerate().map(|(col_index, value)| {
-                    let is_selected = view.selected_cell == Some((global_row_index, col_index));
-                    let click_handler = cx.listener(
-                        move |view: &mut PreviewView, _: &gpui::MouseDownEvent, _window, _cx| {
-                            view.selected_cell = Some((global_row_index, col_index));
-                        },
-                    );
-
-                    let background = if is_selected {
-                        theme.table_active
-                    } else if row_index % 2 == 0 {
-                        theme.table
-                    } else {
-                        theme.table_even
-                    };
-
-                    div()
-                        .px_2()
-                        .py_1()
-                        .flex_1()
-                        .min_w(px(80.0))
-                        .border_r_1()
-                        .border_color(if is_selected {
-                            theme.table_active_border
-                        } else {
-                            theme.table_row_border
-                        })
-                        .bg(background)
-                        .text_color(theme.foreground)
-                        .hover(|this| this.bg(theme.table_hover))
-                        .cursor_pointer()
-                        .on_mouse_down(MouseButton::Left, click_handler)
-                        .child(value.clone())
-                }))
-        });
-
-    let scroll_handler = cx.listener(
-        |view: &mut PreviewView, event: &gpui::ScrollWheelEvent, _window, cx| {
-            let delta = event.delta.pixel_delta(px(ROW_HEIGHT));
-            let rows_delta = -(f32::from(delta.y) / ROW_HEIGHT).round() as isize;
-
-            if rows_delta != 0 {
-                view.scroll_view(rows_delta, cx);
-            }
-        },
-    );
-
-    div()
-        .border_1()
-        .border_color(theme.table_row_border)
-        .rounded(theme.radius)
-        .overflow_hidden()
-        .w_full()
-        .flex_1()
-        .child(
-            div()
-                .flex()
-                .flex_col()
-                .w_full()
-                .h_full()
-                .font_family("monospace")
-                .child(header)
-                .child(
-                    div()
-                        .h(view.table_height)
-                        .min_h(px(MIN_TABLE_HEIGHT))
-                        .w_full()
-                        .overflow_hidden()
-                        .on_scroll_wheel(scroll_handler)
-                        .flex()
-                        .flex_col()
-                        .children(rows),
-                ),
-        )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Arc;
-
-    use arrow::array::{Int32Array, StringArray};
-    use arrow::datatypes::{DataType, Field, Schema};
-    use parquet::arrow::arrow_writer::ArrowWriter;
-    use parquet::file::properties::WriterProperties;
-    use tempfile::NamedTempFile;
-
-    fn write_test_parquet(rows: usize) -> Result<NamedTempFile, ViewerError> {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("id", DataType::Int32, false),
-            Field::new("name", DataType::Utf8, false),
-        ]));
-
-        let ids: Vec<i32> = (0..rows as i32).collect();
-        let names: Vec<String> = ids.iter().map(|v| format!("name-{v}")).collect();
-
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![
-                Arc::new(Int32Array::from(ids)),
-                Arc::new(StringArray::from(names)),
-            ],
-        )?;
-
-        let file = NamedTempFile::new()?;
-        let props = WriterProperties::builder().build();
-        let mut writer = ArrowWriter::try_new(file.reopen()?, schema, Some(props))?;
-        writer.write(&batch)?;
-        writer.close()?;
-
-        Ok(file)
-    }
 
     #[test]
-    fn load_preview_reports_metadata() {
-        let file = write_test_parquet(4).expect("parquet write should succeed");
-
-        let preview = load_preview(&file.path().to_path_buf(), 10).expect("preview should load");
-
-        assert_eq!(preview.row_count, 4);
-        assert_eq!(preview.column_count, 2);
-        assert!(preview.formatted_rows.contains("id"));
-        assert!(preview.formatted_rows.contains("name-0"));
-        assert_eq!(preview.columns, vec!["id".to_string(), "name".to_string()]);
-        assert_eq!(preview.rows.len(), 4);
-        assert_eq!(preview.rows[0], vec!["0".to_string(), "name-0".to_string()]);
-    }
-
-    #[test]
-    fn load_preview_respects_row_limit() {
-        let file = write_test_parquet(5).expect("parquet write should succeed");
-
-        let preview = load_preview(&file.path().to_path_buf(), 2).expect("preview should load");
-
-        assert!(preview.formatted_rows.contains("name-0"));
-        assert!(preview.formatted_rows.contains("name-1"));
-        assert!(!preview.formatted_rows.contains("name-2"));
-        assert_eq!(preview.rows.len(), 2);
+    fn command_line_completes_and_documents_every_subcommand() {
+        Args::command().debug_assert();
+
+        let mut script = Vec::new();
+        clap_complete::generate(
+            clap_complete::Shell::Bash,
+            &mut Args::command(),
+            "parquet-viewer",
+            &mut script,
+        );
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("bench") && script.contains("--goto"));
+
+        let mut page = Vec::new();
+        clap_mangen::Man::new(Args::command())
+            .render(&mut page)
+            .unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.contains("parquet\\-viewer\\-bench"));
     }
 
     #[test]
-    fn rows_for_range_fetches_requested_slice() {
-        let file = write_test_parquet(6).expect("parquet write should succeed");
+    fn move_column_shifts_the_columns_between_positions() {
+        let mut order = vec![0, 1, 2, 3];
 
-        let preview = load_preview(&file.path().to_path_buf(), 6).expect("preview should load");
+        move_column(&mut order, 0, 2);
+        assert_eq!(order, vec![1, 2, 0, 3]);
 
-        let rows = preview
-            .rows_for_range(2..5)
-            .expect("range fetch should succeed");
+        move_column(&mut order, 3, 0);
+        assert_eq!(order, vec![3, 1, 2, 0]);
 
-        assert_eq!(rows.len(), 3);
-        assert_eq!(rows[0], vec!["2".to_string(), "name-2".to_string()]);
-        assert_eq!(rows[2], vec!["4".to_string(), "name-4".to_string()]);
+        move_column(&mut order, 1, 9);
+        assert_eq!(order, vec![3, 1, 2, 0]);
     }
 
     #[test]
-    fn rows_for_range_returns_empty_when_start_out_of_bounds() {
-        let file = write_test_parquet(2).expect("parquet write should succeed");
-
-        let preview = load_preview(&file.path().to_path_buf(), 2).expect("preview should load");
-
-        let rows = preview
-            .rows_for_range(5..8)
-            .expect("range fetch should succeed");
+    fn saved_column_order_skips_unknown_names_and_appends_new_columns() {
+        let columns = vec!["id".to_string(), "name".to_string(), "score".to_string()];
+        let saved = vec!["score".to_string(), "dropped".to_string(), "id".to_string()];
 
-        assert!(rows.is_empty());
-    }
-
-    #[test]
-    fn batches_to_rows_stops_at_limit() {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("id", DataType::Int32, false),
-            Field::new("name", DataType::Utf8, false),
-        ]));
-
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![
-                Arc::new(Int32Array::from(vec![1, 2, 3])),
-                Arc::new(StringArray::from(vec![
-                    "name-1".to_string(),
-                    "name-2".to_string(),
-                    "name-3".to_string(),
-                ])),
-            ],
-        )
-        .expect("record batch should build");
-
-        let rows = batches_to_rows(&[batch], 2).expect("rows should convert");
-
-        assert_eq!(rows.len(), 2);
-        assert_eq!(rows[0], vec!["1".to_string(), "name-1".to_string()]);
-        assert_eq!(rows[1], vec!["2".to_string(), "name-2".to_string()]);
+        assert_eq!(column_order_from_names(&columns, &saved), vec![2, 0, 1]);
+        assert_eq!(column_order_from_names(&columns, &[]), vec![0, 1, 2]);
     }
 }
@@ -0,0 +1,154 @@
+use gpui::{actions, div, prelude::*, px, relative, Action, MouseButton};
+use gpui_component::{ActiveTheme, WindowExt};
+use parquet_viewer_core::minimap::{equalize, row_group_metrics, MinimapMetric};
+
+use crate::row_groups::GoToRowGroup;
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ToggleMinimap]);
+
+/// Width of the minimap beside the table rows.
+pub const MINIMAP_WIDTH: f32 = 14.0;
+
+/// Shortest the viewport marker gets, as a share of the minimap.
+const MIN_MARKER_SHARE: f32 = 0.01;
+
+/// Show the minimap with its row groups shaded by `metric`, or with `None`,
+/// only their boundaries.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SetMinimapMetric {
+    pub metric: Option<MinimapMetric>,
+}
+
+/// Strip beside the table rows with a segment per row group, as tall as its
+/// share of the rows and optionally shaded by a metric, and a marker for the
+/// rows on screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Minimap {
+    pub metric: Option<MinimapMetric>,
+    /// Shade of each row group from 0 to 1, see [`equalize`]; empty without a metric.
+    pub shades: Vec<f32>,
+}
+
+impl Minimap {
+    pub fn new(metric: Option<MinimapMetric>, values: &[f64]) -> Minimap {
+        Minimap {
+            metric,
+            shades: metric.map(|_| equalize(values)).unwrap_or_default(),
+        }
+    }
+}
+
+pub fn render_minimap(
+    view: &PreviewView,
+    minimap: &Minimap,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let total = view.preview.row_count.max(1) as f32;
+    let first = match view.display_row_count() {
+        0 => 0,
+        count => view.file_row(view.visible_range.start.min(count - 1)),
+    };
+    let marker_top = first as f32 / total;
+    let marker_height = (view.visible_range.len() as f32 / total).max(MIN_MARKER_SHARE);
+
+    let segments = view
+        .preview
+        .row_groups
+        .iter()
+        .enumerate()
+        .map(|(index, rows)| {
+            let jump = cx.listener(
+                move |view: &mut PreviewView, _: &gpui::MouseDownEvent, window, cx| {
+                    view.go_to_row_group(&GoToRowGroup { index }, window, cx);
+                },
+            );
+            let background = match minimap.shades.get(index) {
+                Some(shade) => theme.primary.opacity(0.1 + 0.8 * shade),
+                None if index % 2 == 0 => theme.secondary,
+                None => theme.muted,
+            };
+            div()
+                .id(("minimap-row-group", index))
+                .w_full()
+                .h(relative(rows.len() as f32 / total))
+                .bg(background)
+                .border_b_1()
+                .border_color(theme.border)
+                .cursor_pointer()
+                .on_mouse_down(MouseButton::Left, jump)
+        });
+
+    div()
+        .relative()
+        .flex_none()
+        .w(px(MINIMAP_WIDTH))
+        .h_full()
+        .flex()
+        .flex_col()
+        .border_l_1()
+        .border_color(theme.table_row_border)
+        .children(segments)
+        .child(
+            div()
+                .absolute()
+                .left_0()
+                .w_full()
+                .top(relative(marker_top.min(1.0 - marker_height)))
+                .h(relative(marker_height))
+                .border_1()
+                .border_color(theme.foreground),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn toggle_minimap(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.minimap = match self.minimap.take() {
+            Some(_) => None,
+            None => Some(Minimap::default()),
+        };
+        cx.notify();
+    }
+
+    /// Show the minimap shaded by `metric`, read from the footer.
+    pub(crate) fn set_minimap_metric(
+        &mut self,
+        metric: Option<MinimapMetric>,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let values = match metric {
+            Some(metric) => {
+                match row_group_metrics(&self.preview.path, metric, &self.preview.bytes_read) {
+                    Ok(values) => values,
+                    Err(error) => {
+                        tracing::error!(%error, "failed to read the row group metrics");
+                        window.push_notification(
+                            format!("The row groups' {metric} could not be read: {error}"),
+                            cx,
+                        );
+                        return;
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
+        self.minimap = Some(Minimap::new(metric, &values));
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_metric_shades_the_row_groups() {
+        assert!(Minimap::new(None, &[]).shades.is_empty());
+
+        let minimap = Minimap::new(Some(MinimapMetric::BytesPerRow), &[30.0, 10.0, 20.0]);
+        assert_eq!(minimap.shades, vec![1.0, 0.0, 0.5]);
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use gpui::{div, prelude::*, px, Task};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::progress::Progress as ProgressBar;
+use gpui_component::ActiveTheme;
+use parquet_viewer_core::progress::{progress_line, Progress};
+
+use crate::PreviewView;
+
+/// How often the progress bar of a running operation is redrawn.
+const PROGRESS_REFRESH: Duration = Duration::from_millis(100);
+
+/// A long operation shown above the table with a progress bar and a Cancel button.
+///
+/// Dropping it, when it finishes or another operation replaces it, cancels the work.
+pub struct RunningOperation {
+    label: &'static str,
+    unit: &'static str,
+    progress: Progress,
+    /// Redraws the view while the operation runs; dropped with it.
+    _refresh: Task<()>,
+}
+
+impl Drop for RunningOperation {
+    fn drop(&mut self) {
+        self.progress.cancel();
+    }
+}
+
+impl PreviewView {
+    /// Show `progress` above the table until the operation clears `operation`,
+    /// cancelling whatever ran before.
+    pub(crate) fn start_operation(
+        &mut self,
+        label: &'static str,
+        unit: &'static str,
+        progress: Progress,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.cancel_operation(cx);
+        let refresh = cx.spawn(async move |view, cx| loop {
+            cx.background_executor().timer(PROGRESS_REFRESH).await;
+            if view.update(cx, |_, cx| cx.notify()).is_err() {
+                break;
+            }
+        });
+        self.operation = Some(RunningOperation {
+            label,
+            unit,
+            progress,
+            _refresh: refresh,
+        });
+        cx.notify();
+    }
+
+    fn cancel_operation(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        if self.operation.take().is_some() {
+            cx.notify();
+        }
+    }
+}
+
+/// Progress bar with a Cancel button for the operation running in the background.
+pub fn render_operation(
+    operation: &RunningOperation,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let cancel = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.cancel_operation(cx);
+        },
+    );
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .w_full()
+        .text_sm()
+        .child(
+            div()
+                .w(px(240.0))
+                .child(ProgressBar::new().value(operation.progress.percent())),
+        )
+        .child(
+            div()
+                .flex_1()
+                .text_color(theme.muted_foreground)
+                .child(progress_line(
+                    &operation.progress,
+                    operation.label,
+                    operation.unit,
+                )),
+        )
+        .child(
+            Button::new("cancel-operation")
+                .ghost()
+                .label("Cancel")
+                .on_click(cancel),
+        )
+}
@@ -0,0 +1,84 @@
+use gpui::{actions, div, prelude::*, px};
+use gpui_component::{Theme, WindowExt};
+use parquet_viewer_core::pages::{page_boundaries, PageStart};
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [TogglePageBoundaries]);
+
+/// Thickness of the line drawn where a page begins.
+const MARKER_WIDTH: f32 = 2.0;
+
+/// Short label of a page, e.g. `rg 2 · p 3`, both counted from one.
+pub fn page_label(start: &PageStart) -> String {
+    format!("rg {} · p {}", start.row_group + 1, start.page + 1)
+}
+
+/// Line across the top of a cell whose row begins one of the column's data
+/// pages, labelled with the page. The cell has to be positioned relatively.
+pub fn page_marker(start: &PageStart, theme: &Theme) -> impl gpui::IntoElement {
+    div()
+        .absolute()
+        .top_0()
+        .left_0()
+        .w_full()
+        .h(px(MARKER_WIDTH))
+        .bg(theme.warning)
+        .child(
+            div()
+                .absolute()
+                .top(px(MARKER_WIDTH))
+                .right_0()
+                .px_1()
+                .text_xs()
+                .bg(theme.background)
+                .text_color(theme.warning)
+                .child(page_label(start)),
+        )
+}
+
+impl PreviewView {
+    /// Mark the rows where each column's data pages begin, or stop marking them.
+    pub(crate) fn toggle_page_boundaries(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if self.show_page_boundaries || self.page_boundaries.is_some() {
+            self.show_page_boundaries = !self.show_page_boundaries;
+            cx.notify();
+            return;
+        }
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            async move { page_boundaries(&path, &bytes_read) }
+        });
+        cx.spawn_in(window, async move |view, cx| {
+            let boundaries = task.await;
+            view.update_in(cx, |view, window, cx| {
+                match boundaries {
+                    Ok(Some(boundaries)) => {
+                        view.page_boundaries = Some(boundaries);
+                        view.show_page_boundaries = true;
+                    }
+                    Ok(None) => window.push_notification(
+                        "This file has no offset index, so its page boundaries are unknown",
+                        cx,
+                    ),
+                    Err(error) => {
+                        tracing::error!(%error, "failed to read the offset index");
+                        window.push_notification(
+                            format!("Reading the offset index failed: {error}"),
+                            cx,
+                        );
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
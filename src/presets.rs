@@ -0,0 +1,413 @@
+use std::io;
+use std::path::Path;
+
+use gpui::{actions, prelude::*, px, Action, FocusHandle, PathPromptOptions};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputState};
+use gpui_component::menu::DropdownMenu;
+use gpui_component::WindowExt;
+use parquet_viewer_core::filter::{SortKey, ValueFilter};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_json, read_json, save_json, write_json};
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ImportFilterPresets, ExportFilterPresets]);
+
+/// File name of the preset library inside the config directory.
+const PRESETS_NAME: &str = "filter_presets.json";
+
+/// Filters and sort saved under a name to apply again later.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    /// Name of the file the preset is for, or `None` for every file. Names
+    /// rather than paths, so that shared presets work on other copies of a file.
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub filters: Vec<ValueFilter>,
+    #[serde(default)]
+    pub sort: Option<SortKey>,
+}
+
+impl FilterPreset {
+    pub fn applies_to(&self, file_name: &str) -> bool {
+        self.file.as_deref().is_none_or(|file| file == file_name)
+    }
+
+    /// Columns the preset filters or sorts on that aren't among `columns`.
+    pub fn missing_columns(&self, columns: &[String]) -> Vec<String> {
+        let mut missing: Vec<String> = self
+            .filters
+            .iter()
+            .map(|filter| &filter.column)
+            .chain(self.sort.iter().map(|sort| &sort.column))
+            .filter(|column| !columns.contains(column))
+            .cloned()
+            .collect();
+        missing.dedup();
+        missing
+    }
+}
+
+/// Apply a saved preset's filters and sort.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ApplyFilterPreset {
+    pub preset: FilterPreset,
+}
+
+/// Ask for a name and save the current filters and sort under it, for this
+/// file only or for every file.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SaveFilterPreset {
+    pub for_file: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct DeleteFilterPreset {
+    pub name: String,
+    pub file: Option<String>,
+}
+
+/// Every saved preset, in the order they were saved; this is also the format
+/// presets are exported in for sharing.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetLibrary {
+    presets: Vec<FilterPreset>,
+}
+
+impl PresetLibrary {
+    pub fn load() -> Self {
+        load_json(PRESETS_NAME).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_json(PRESETS_NAME, self);
+    }
+
+    /// Presets for the file named `file_name` and for every file.
+    pub fn for_file<'a>(&'a self, file_name: &'a str) -> impl Iterator<Item = &'a FilterPreset> {
+        self.presets
+            .iter()
+            .filter(move |preset| preset.applies_to(file_name))
+    }
+
+    /// Add `preset`, replacing the one of the same name for the same files.
+    pub fn add(&mut self, preset: FilterPreset) {
+        self.remove(&preset.name, preset.file.as_deref());
+        self.presets.push(preset);
+    }
+
+    pub fn remove(&mut self, name: &str, file: Option<&str>) {
+        self.presets
+            .retain(|preset| preset.name != name || preset.file.as_deref() != file);
+    }
+
+    /// Add the presets of an exported library, replacing those of the same
+    /// name; returns how many were read.
+    pub fn import(&mut self, path: &Path) -> io::Result<usize> {
+        let imported: PresetLibrary = read_json(path)?;
+        let count = imported.presets.len();
+        for preset in imported.presets {
+            self.add(preset);
+        }
+        Ok(count)
+    }
+
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        write_json(path, self)
+    }
+}
+
+/// Tallest the filter preset dropdown grows before it scrolls.
+const PRESET_MENU_HEIGHT: f32 = 360.0;
+
+impl PreviewView {
+    pub(crate) fn apply_filter_preset(
+        &mut self,
+        action: &ApplyFilterPreset,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let preset = &action.preset;
+        let missing = preset.missing_columns(&self.preview.columns);
+        if !missing.is_empty() {
+            window.push_notification(
+                format!(
+                    "Preset {} uses columns this file doesn't have: {}",
+                    preset.name,
+                    missing.join(", ")
+                ),
+                cx,
+            );
+            return;
+        }
+        self.update_row_order(preset.filters.clone(), preset.sort.clone(), cx);
+    }
+
+    /// Ask for a name and save the filters and sort under it.
+    pub(crate) fn save_filter_preset(
+        &mut self,
+        action: &SaveFilterPreset,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if self.filters.is_empty() && self.sort.is_none() {
+            window.push_notification("There are no filters or sort to save", cx);
+            return;
+        }
+
+        let preset = FilterPreset {
+            name: String::new(),
+            file: action.for_file.then(|| self.file_name()),
+            filters: self.filters.clone(),
+            sort: self.sort.clone(),
+        };
+        let title = match &preset.file {
+            Some(file) => format!("Save filters for {file}"),
+            None => "Save filters for all files".to_string(),
+        };
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Preset name"));
+        let view = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let input = dialog_input.clone();
+            let view = view.clone();
+            let preset = preset.clone();
+            dialog
+                .title(title.clone())
+                .child(Input::new(&dialog_input))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let name = input.read(cx).value().trim().to_string();
+                    if name.is_empty() {
+                        window.push_notification("Enter a name for the preset", cx);
+                        return false;
+                    }
+                    let mut library = PresetLibrary::load();
+                    library.add(FilterPreset {
+                        name,
+                        ..preset.clone()
+                    });
+                    library.save();
+                    view.update(cx, |_, cx| cx.notify()).ok();
+                    true
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    pub(crate) fn delete_filter_preset(
+        &mut self,
+        action: &DeleteFilterPreset,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let mut library = PresetLibrary::load();
+        library.remove(&action.name, action.file.as_deref());
+        library.save();
+        cx.notify();
+    }
+
+    /// Add the presets of a JSON file exported by someone else.
+    pub(crate) fn import_filter_presets(
+        &mut self,
+        _: &ImportFilterPresets,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import".into()),
+        });
+
+        cx.spawn_in(window, async move |view, cx| {
+            let path = match paths.await {
+                Ok(Ok(Some(paths))) => paths.into_iter().next(),
+                Ok(Err(error)) => {
+                    tracing::error!(?error, "failed to show the file picker");
+                    None
+                }
+                _ => None,
+            };
+            let Some(path) = path else {
+                return;
+            };
+
+            let mut library = PresetLibrary::load();
+            let message = match library.import(&path) {
+                Ok(count) => {
+                    library.save();
+                    format!("Imported {count} filter preset(s)")
+                }
+                Err(error) => {
+                    tracing::error!(%error, path = %path.to_string_lossy(), "failed to import filter presets");
+                    format!("Could not import filter presets: {error}")
+                }
+            };
+            view.update_in(cx, |_, window, cx| {
+                window.push_notification(message, cx);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Write every saved preset to a JSON file to share.
+    pub(crate) fn export_filter_presets(
+        &mut self,
+        _: &ExportFilterPresets,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let directory = self.preview.path.parent().unwrap_or(Path::new("."));
+        let destination = cx.prompt_for_new_path(directory, Some("filter-presets.json"));
+
+        cx.spawn_in(window, async move |view, cx| {
+            let destination = match destination.await {
+                Ok(Ok(Some(destination))) => destination,
+                Ok(Err(error)) => {
+                    tracing::error!(?error, "failed to show the save dialog");
+                    return;
+                }
+                _ => return,
+            };
+
+            if let Err(error) = PresetLibrary::load().export(&destination) {
+                tracing::error!(%error, path = %destination.to_string_lossy(), "failed to export filter presets");
+                view.update_in(cx, |_, window, cx| {
+                    window.push_notification(
+                        format!("Could not export filter presets: {error}"),
+                        cx,
+                    );
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+}
+
+/// Dropdown applying the saved filter presets for `file_name`, read when it
+/// opens so that presets saved from other tabs show up.
+pub fn render_preset_menu(
+    file_name: String,
+    filtered: bool,
+    focus_handle: FocusHandle,
+) -> impl gpui::IntoElement {
+    Button::new("filter-presets")
+        .ghost()
+        .label("Presets")
+        .dropdown_menu(move |menu, window, cx| {
+            let library = PresetLibrary::load();
+            let presets: Vec<FilterPreset> = library.for_file(&file_name).cloned().collect();
+            let label = |preset: &FilterPreset| match preset.file {
+                Some(_) => preset.name.clone(),
+                None => format!("{} (all files)", preset.name),
+            };
+
+            let mut menu = menu
+                .action_context(focus_handle.clone())
+                .scrollable(true)
+                .max_h(px(PRESET_MENU_HEIGHT));
+            for preset in &presets {
+                menu = menu.menu(
+                    label(preset),
+                    Box::new(ApplyFilterPreset {
+                        preset: preset.clone(),
+                    }),
+                );
+            }
+            if !presets.is_empty() {
+                let focus_handle = focus_handle.clone();
+                menu = menu
+                    .separator()
+                    .submenu("Delete", window, cx, move |menu, _, _| {
+                        presets.iter().fold(
+                            menu.action_context(focus_handle.clone()),
+                            |menu, preset| {
+                                menu.menu(
+                                    label(preset),
+                                    Box::new(DeleteFilterPreset {
+                                        name: preset.name.clone(),
+                                        file: preset.file.clone(),
+                                    }),
+                                )
+                            },
+                        )
+                    });
+            }
+            menu.separator()
+                .menu_with_disabled(
+                    "Save for This File…",
+                    Box::new(SaveFilterPreset { for_file: true }),
+                    !filtered,
+                )
+                .menu_with_disabled(
+                    "Save for All Files…",
+                    Box::new(SaveFilterPreset { for_file: false }),
+                    !filtered,
+                )
+                .separator()
+                .menu("Import…", Box::new(ImportFilterPresets))
+                .menu("Export…", Box::new(ExportFilterPresets))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, file: Option<&str>, column: &str) -> FilterPreset {
+        FilterPreset {
+            name: name.to_string(),
+            file: file.map(str::to_string),
+            filters: vec![ValueFilter {
+                column: column.to_string(),
+                value: "x".to_string(),
+                exclude: false,
+                regex: false,
+            }],
+            sort: None,
+        }
+    }
+
+    #[test]
+    fn presets_are_scoped_replaced_and_shared() {
+        let mut library = PresetLibrary::default();
+        library.add(preset("errors", None, "level"));
+        library.add(preset("errors", Some("a.parquet"), "status"));
+        library.add(preset("mine", Some("b.parquet"), "owner"));
+        library.add(preset("errors", None, "severity"));
+
+        let names: Vec<(&str, Option<&str>)> = library
+            .for_file("a.parquet")
+            .map(|preset| (preset.name.as_str(), preset.file.as_deref()))
+            .collect();
+        assert_eq!(names, [("errors", Some("a.parquet")), ("errors", None)]);
+        assert_eq!(
+            library.presets[2].missing_columns(&["level".to_string()]),
+            ["severity"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.json");
+        library.export(&path).unwrap();
+        let mut imported = PresetLibrary::default();
+        imported.add(preset("mine", Some("b.parquet"), "stale"));
+        assert_eq!(imported.import(&path).unwrap(), 3);
+        assert_eq!(imported, library);
+
+        library.remove("errors", None);
+        assert_eq!(library.for_file("a.parquet").count(), 1);
+    }
+}
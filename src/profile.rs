@@ -0,0 +1,255 @@
+use std::io::Write;
+use std::path::Path;
+
+use gpui::{actions, div, prelude::*, px};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Disableable, StyledExt};
+use parquet_viewer_core::output::{ensure_distinct, write_atomically};
+use parquet_viewer_core::profile::{
+    profile, sample_profile, Profile, SAMPLE_ABOVE_ROWS, SAMPLE_ROW_GROUPS,
+};
+use parquet_viewer_core::progress::Progress;
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ProfileColumns]);
+
+/// Tallest the column list grows before it scrolls.
+const PROFILE_HEIGHT: f32 = 320.0;
+
+/// Format a profile report is exported in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// The data quality report of the open file, computed in the background.
+pub struct ProfilePanel {
+    pub profile: Option<Result<Profile, String>>,
+    /// Progress of the running scan, cancelled when the panel closes.
+    pub progress: Option<Progress>,
+}
+
+impl Drop for ProfilePanel {
+    fn drop(&mut self) {
+        if let Some(progress) = self.progress.take() {
+            progress.cancel();
+        }
+    }
+}
+
+/// Panel with one line per column: nulls, distinct values, min/max, top values,
+/// blank strings and out-of-range timestamps, with JSON and HTML export. A
+/// profile of a sample of a huge file is headed by how far it can be trusted.
+pub fn render_profile_panel(
+    panel: &ProfilePanel,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_profile_panel(cx);
+        },
+    );
+    let export = |format: ReportFormat| {
+        cx.listener(
+            move |view: &mut PreviewView, _: &gpui::ClickEvent, window, cx| {
+                view.export_profile(format, window, cx);
+            },
+        )
+    };
+
+    let (title, body) = match &panel.profile {
+        None => (
+            "Profile".to_string(),
+            div()
+                .text_color(theme.muted_foreground)
+                .child("Profiling columns…")
+                .into_any_element(),
+        ),
+        Some(Err(message)) => (
+            "Profile".to_string(),
+            div().child(message.clone()).into_any_element(),
+        ),
+        Some(Ok(profile)) => (
+            format!("Profile of {} rows", profile.rows),
+            div()
+                .id("profile-columns")
+                .flex()
+                .flex_col()
+                .gap_1()
+                .max_h(px(PROFILE_HEIGHT))
+                .overflow_y_scroll()
+                .children(profile.sample.as_ref().map(|sample| {
+                    div()
+                        .text_color(theme.muted_foreground)
+                        .italic()
+                        .child(sample.note())
+                }))
+                .children(profile.columns.iter().map(|column| {
+                    let flagged = column.blank_strings.is_some_and(|count| count > 0)
+                        || column
+                            .out_of_range_timestamps
+                            .is_some_and(|count| count > 0);
+                    div()
+                        .when(flagged, |this| this.text_color(theme.warning))
+                        .child(column.line())
+                }))
+                .into_any_element(),
+        ),
+    };
+    let ready = matches!(panel.profile, Some(Ok(_)));
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child(title))
+                .child(
+                    Button::new("export-profile-json")
+                        .ghost()
+                        .label("Export JSON…")
+                        .disabled(!ready)
+                        .on_click(export(ReportFormat::Json)),
+                )
+                .child(
+                    Button::new("export-profile-html")
+                        .ghost()
+                        .label("Export HTML…")
+                        .disabled(!ready)
+                        .on_click(export(ReportFormat::Html)),
+                )
+                .child(
+                    Button::new("close-profile")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(body)
+}
+
+impl PreviewView {
+    /// Profile every column of the file in the background, or close the report.
+    pub(crate) fn toggle_profile_panel(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        if self.profile_panel.take().is_some() {
+            cx.notify();
+            return;
+        }
+        let progress = Progress::default();
+        self.profile_panel = Some(ProfilePanel {
+            profile: None,
+            progress: Some(progress.clone()),
+        });
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let progress = progress.clone();
+            let sampled = self.preview.row_count > SAMPLE_ABOVE_ROWS;
+            async move {
+                if sampled {
+                    sample_profile(&path, SAMPLE_ROW_GROUPS, None, &bytes_read, &progress)
+                } else {
+                    profile(&path, &bytes_read, &progress)
+                }
+            }
+        });
+        cx.spawn(async move |view, cx| {
+            let profile = task.await;
+            view.update(cx, |view, cx| {
+                // A cancelled scan belonged to a report that was closed.
+                if progress.is_cancelled() {
+                    return;
+                }
+                let Some(panel) = &mut view.profile_panel else {
+                    return;
+                };
+                panel.progress = None;
+                panel.profile = Some(profile.map_err(|error| {
+                    tracing::error!(%error, "failed to profile columns");
+                    format!("Columns could not be profiled: {error}")
+                }));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+
+    /// Ask where to save the profile report and write it there as `format`.
+    pub(crate) fn export_profile(
+        &mut self,
+        format: ReportFormat,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let Some(Ok(profile)) = self
+            .profile_panel
+            .as_ref()
+            .and_then(|panel| panel.profile.as_ref())
+        else {
+            return;
+        };
+        let path = &self.preview.path;
+        let report = match format {
+            ReportFormat::Json => profile.to_json(),
+            ReportFormat::Html => profile.to_html(&path.to_string_lossy()),
+        };
+        let directory = path.parent().unwrap_or(Path::new("."));
+        let suggested_name = format!(
+            "{}-profile.{}",
+            path.file_stem().unwrap_or_default().to_string_lossy(),
+            format.extension()
+        );
+        let destination = cx.prompt_for_new_path(directory, Some(&suggested_name));
+        let path = path.clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let destination = match destination.await {
+                Ok(Ok(Some(destination))) => destination,
+                Ok(Err(error)) => {
+                    tracing::error!(?error, "failed to show the save dialog");
+                    return;
+                }
+                _ => return,
+            };
+            let written = cx
+                .background_spawn(async move {
+                    ensure_distinct([path.as_path()], &destination)?;
+                    write_atomically(&destination, |mut file| {
+                        file.write_all(report.as_bytes())?;
+                        Ok(())
+                    })
+                })
+                .await;
+            if let Err(error) = written {
+                tracing::error!(%error, "failed to write the profile report");
+            }
+        })
+        .detach();
+    }
+}
@@ -0,0 +1,526 @@
+use std::time::Instant;
+
+use gpui::{div, prelude::*, px};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputState, MoveDown, MoveUp};
+use gpui_component::{ActiveTheme, Disableable, Sizable, WindowExt};
+use parquet_viewer_core::filter::{
+    describe_query, explain_row_order, parse_query, row_order, SortKey, ValueFilter,
+};
+use parquet_viewer_core::progress::Progress;
+use parquet_viewer_core::seek::seek_value;
+use parquet_viewer_core::selection::SelectedRows;
+use parquet_viewer_core::sortedness::Sortedness;
+use parquet_viewer_core::ViewerError;
+
+use crate::context_menu::{
+    ClearFilters, FilterByPattern, FilterByValue, JumpToValue, ShowAllColumns, SortByColumn,
+};
+use crate::history::{HistoryEntry, RerunQuery};
+use crate::presets::render_preset_menu;
+use crate::{row_groups, PreviewView};
+
+/// Width of the query bar's text box.
+const QUERY_INPUT_WIDTH: f32 = 360.0;
+
+impl PreviewView {
+    /// Re-scan the file for the rows matching `filters`, in `sort` order.
+    ///
+    /// Returns `false` and leaves the view unchanged if the scan fails.
+    pub(crate) fn set_row_order(
+        &mut self,
+        filters: Vec<ValueFilter>,
+        sort: Option<SortKey>,
+    ) -> bool {
+        if let Err(error) = self.check_query_columns(&filters, sort.as_ref()) {
+            tracing::error!(%error, "failed to filter rows");
+            return false;
+        }
+        let order = if filters.is_empty() && sort.is_none() {
+            None
+        } else {
+            match row_order(
+                &self.preview.path,
+                &filters,
+                sort.as_ref(),
+                &self.preview.casts,
+                &self.preview.bytes_read,
+                &Progress::default(),
+            ) {
+                Ok(order) => Some(order),
+                Err(error) => {
+                    tracing::error!(%error, "failed to filter rows");
+                    return false;
+                }
+            }
+        };
+
+        self.apply_row_order(filters, sort, order);
+        true
+    }
+
+    pub(crate) fn apply_row_order(
+        &mut self,
+        filters: Vec<ValueFilter>,
+        sort: Option<SortKey>,
+        order: Option<Vec<usize>>,
+    ) {
+        self.filters = filters;
+        self.sort = sort;
+        self.row_order = order;
+        self.prefetch_range = None;
+        self.selected_rows = SelectedRows::default();
+        self.selected_cell = None;
+        if let Some(panel) = &mut self.duplicates {
+            panel.showing_duplicates = false;
+        }
+    }
+
+    /// Computed columns aren't in the file the scan reads, so a filter or sort
+    /// naming one is [`ViewerError::InvalidFilter`] rather than silently ignored.
+    fn check_query_columns(
+        &self,
+        filters: &[ValueFilter],
+        sort: Option<&SortKey>,
+    ) -> Result<(), ViewerError> {
+        let computed = &self.preview.columns[self.preview.computed_start()..];
+        match filters
+            .iter()
+            .map(|value_filter| &value_filter.column)
+            .chain(sort.map(|sort| &sort.column))
+            .find(|column| computed.contains(column))
+        {
+            Some(column) => Err(ViewerError::InvalidFilter(format!(
+                "{column} is a computed column, which can't be filtered or sorted on"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Scan for the new row order in the background, showing its progress, and
+    /// switch to it once done. A failed or cancelled scan leaves the view unchanged.
+    pub(crate) fn update_row_order(
+        &mut self,
+        filters: Vec<ValueFilter>,
+        sort: Option<SortKey>,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if let Err(error) = self.check_query_columns(&filters, sort.as_ref()) {
+            tracing::error!(%error, "failed to filter rows");
+            return;
+        }
+        if filters.is_empty() && sort.is_none() {
+            self.apply_row_order(filters, sort, None);
+            self.load_visible_rows(0, cx);
+            self.refresh_summary(cx);
+            return;
+        }
+
+        let progress = Progress::default();
+        let started = Instant::now();
+        let scan = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let filters = filters.clone();
+            let sort = sort.clone();
+            let casts = self.preview.casts.clone();
+            let progress = progress.clone();
+            async move {
+                explain_row_order(
+                    &path,
+                    &filters,
+                    sort.as_ref(),
+                    &casts,
+                    &bytes_read,
+                    &progress,
+                )
+            }
+        });
+        self.start_operation("Filtering", "row groups", progress.clone(), cx);
+
+        cx.spawn(async move |view, cx| {
+            let order = scan.await;
+            view.update(cx, |view, cx| {
+                // Cancelled scans were replaced or dismissed; the view moved on.
+                if progress.is_cancelled() {
+                    return;
+                }
+                view.operation = None;
+                match order {
+                    Ok((order, report)) => {
+                        view.history.push(HistoryEntry {
+                            query: describe_query(&filters, sort.as_ref()),
+                            filters: filters.clone(),
+                            sort: sort.clone(),
+                            elapsed: started.elapsed(),
+                            rows: order.len(),
+                            report,
+                        });
+                        if view.explained_query.is_some() {
+                            view.explained_query = Some(view.history.entries().len() - 1);
+                        }
+                        view.apply_row_order(filters, sort, Some(order));
+                        view.load_visible_rows(0, cx);
+                        view.refresh_summary(cx);
+                    }
+                    Err(ViewerError::Cancelled) => {}
+                    Err(error) => tracing::error!(%error, "failed to filter rows"),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub(crate) fn filter_by_value(
+        &mut self,
+        action: &FilterByValue,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let mut filters = self.filters.clone();
+        filters.push(ValueFilter {
+            column: self.preview.columns[action.column].clone(),
+            value: action.value.clone(),
+            exclude: action.exclude,
+            regex: false,
+        });
+        self.update_row_order(filters, self.sort.clone(), cx);
+    }
+
+    /// Ask for a regular expression and add it as a filter on the column.
+    pub(crate) fn filter_by_pattern(
+        &mut self,
+        action: &FilterByPattern,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Regular expression, e.g. ^(ERROR|WARN)")
+        });
+        let view = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let input = dialog_input.clone();
+            let view = view.clone();
+            let title = format!("Filter {column} by pattern");
+            let column = column.clone();
+            dialog
+                .title(title)
+                .child(Input::new(&dialog_input))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let value_filter = ValueFilter {
+                        column: column.clone(),
+                        value: input.read(cx).value().to_string(),
+                        exclude: false,
+                        regex: true,
+                    };
+                    // Report a bad pattern here rather than letting the scan fail.
+                    if let Err(error) = value_filter.pattern() {
+                        window.push_notification(error.to_string(), cx);
+                        return false;
+                    }
+
+                    view.update(cx, |view, cx| {
+                        let mut filters = view.filters.clone();
+                        filters.push(value_filter);
+                        view.update_row_order(filters, view.sort.clone(), cx);
+                    })
+                    .ok();
+                    true
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    /// Ask for a value and scroll to the first row at or past it in a sorted column.
+    pub(crate) fn jump_to_value(
+        &mut self,
+        action: &JumpToValue,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let Some(Some(order)) = self.sorted_columns.get(action.column).copied() else {
+            return;
+        };
+        let column = action.column;
+        let name = self.preview.columns[column].clone();
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Value, e.g. 2024-01-31"));
+        let view = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let input = dialog_input.clone();
+            let view = view.clone();
+            let title = match order {
+                Sortedness::Ascending => format!("Jump to the first {name} ≥ value"),
+                Sortedness::Descending => format!("Jump to the first {name} ≤ value"),
+            };
+            dialog
+                .title(title)
+                .child(Input::new(&dialog_input))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let value = input.read(cx).value().to_string();
+                    view.update(cx, |view, cx| {
+                        view.seek_to_value(column, order, value, window, cx)
+                    })
+                    .ok();
+                    true
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    /// Find the first row of the sorted `column` at or past `value` in the
+    /// background, showing its progress above the table, and scroll to it once
+    /// found.
+    fn seek_to_value(
+        &mut self,
+        column: usize,
+        order: Sortedness,
+        value: String,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let name = self.preview.columns[column].clone();
+        let progress = Progress::default();
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let value = value.clone();
+            let progress = progress.clone();
+            async move { seek_value(&path, column, order, &value, &bytes_read, &progress) }
+        });
+        self.start_operation("Seeking", "row groups", progress.clone(), cx);
+
+        cx.spawn_in(window, async move |view, cx| {
+            let found = task.await;
+            view.update_in(cx, |view, window, cx| {
+                // Cancelled seeks were replaced or dismissed; the view moved on.
+                if progress.is_cancelled() {
+                    return;
+                }
+                view.operation = None;
+                match found {
+                    Ok(None) => {
+                        window.push_notification(format!("No {name} is at or past {value}"), cx)
+                    }
+                    Ok(Some(row)) => {
+                        match row_groups::first_display_row(
+                            &(row..row + 1),
+                            view.row_order.as_deref(),
+                        ) {
+                            Some(row) => view.reveal_row(row, cx),
+                            None => window.push_notification(
+                                format!("Row {} is hidden by the current filter", row + 1),
+                                cx,
+                            ),
+                        }
+                    }
+                    Err(ViewerError::Cancelled) => {}
+                    Err(error @ ViewerError::InvalidValue(_)) => {
+                        window.push_notification(error.to_string(), cx)
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, column = name, "failed to find the value");
+                        window.push_notification(
+                            format!("{name} could not be searched: {error}"),
+                            cx,
+                        );
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub(crate) fn sort_by_column(
+        &mut self,
+        action: &SortByColumn,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let sort = SortKey {
+            column: self.preview.columns[action.column].clone(),
+            descending: action.descending,
+        };
+        self.update_row_order(self.filters.clone(), Some(sort), cx);
+    }
+
+    pub(crate) fn clear_filters(
+        &mut self,
+        _: &ClearFilters,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.update_row_order(Vec::new(), None, cx);
+    }
+
+    /// Run the filters and sort typed in the query bar in place of the current
+    /// ones, emptying the box for the next query.
+    pub(crate) fn run_query(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let text = self.query_input.read(cx).value();
+        match parse_query(&text) {
+            Ok((filters, sort)) => {
+                if let Some(column) = filters
+                    .iter()
+                    .map(|filter| &filter.column)
+                    .chain(sort.iter().map(|sort| &sort.column))
+                    .find(|column| !self.preview.columns.contains(column))
+                {
+                    window.push_notification(format!("There is no column named {column}"), cx);
+                    return;
+                }
+                if let Err(error) = self.check_query_columns(&filters, sort.as_ref()) {
+                    window.push_notification(error.to_string(), cx);
+                    return;
+                }
+                self.query_input
+                    .update(cx, |input, cx| input.set_value("", window, cx));
+                self.update_row_order(filters, sort, cx);
+            }
+            Err(error) => window.push_notification(error.to_string(), cx),
+        }
+    }
+
+    /// Fill the query bar with an older query from the history.
+    fn query_history_older(
+        &mut self,
+        _: &MoveUp,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if let Some(query) = self.history.older().map(str::to_string) {
+            self.query_input
+                .update(cx, |input, cx| input.set_value(query, window, cx));
+        }
+        cx.stop_propagation();
+    }
+
+    /// Fill the query bar with a newer query from the history, or empty it past the newest.
+    fn query_history_newer(
+        &mut self,
+        _: &MoveDown,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if let Some(query) = self.history.newer().map(str::to_string) {
+            self.query_input
+                .update(cx, |input, cx| input.set_value(query, window, cx));
+        }
+        cx.stop_propagation();
+    }
+
+    pub(crate) fn rerun_query(
+        &mut self,
+        action: &RerunQuery,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if let Some(entry) = self.history.entries().get(action.index) {
+            self.update_row_order(entry.filters.clone(), entry.sort.clone(), cx);
+        }
+    }
+}
+
+/// The query box, where Up and Down step through the history, next to the
+/// active filters, sort and hidden columns, with buttons to reset them, saved
+/// filter presets and the history panel.
+pub fn render_query_bar(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let filtered = !view.filters.is_empty() || view.sort.is_some();
+
+    let mut parts: Vec<String> = Vec::new();
+    if filtered {
+        parts.push(describe_query(&view.filters, view.sort.as_ref()));
+    }
+    if !view.hidden_columns.is_empty() {
+        parts.push(format!("{} hidden column(s)", view.hidden_columns.len()));
+    }
+    let toggle_history = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_query_history(cx);
+        },
+    );
+    let toggle_explain = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.toggle_query_explain(cx);
+        },
+    );
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .w_full()
+        .text_sm()
+        .text_color(theme.muted_foreground)
+        .child(
+            div()
+                .w(px(QUERY_INPUT_WIDTH))
+                .flex_none()
+                .capture_action(cx.listener(PreviewView::query_history_older))
+                .capture_action(cx.listener(PreviewView::query_history_newer))
+                .child(Input::new(&view.query_input).small()),
+        )
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .truncate()
+                .child(if parts.is_empty() {
+                    "No filters".to_string()
+                } else {
+                    parts.join(" | ")
+                }),
+        )
+        .child(
+            Button::new("query-history")
+                .ghost()
+                .label("History")
+                .on_click(toggle_history),
+        )
+        .child(
+            Button::new("query-explain")
+                .ghost()
+                .label("Explain")
+                .disabled(view.history.entries().is_empty())
+                .on_click(toggle_explain),
+        )
+        .child(render_preset_menu(
+            view.file_name(),
+            filtered,
+            view.focus_handle.clone(),
+        ))
+        .when(filtered, |this| {
+            this.child(
+                Button::new("clear-filters")
+                    .ghost()
+                    .label("Clear filters")
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(ClearFilters), cx)),
+            )
+        })
+        .when(!view.hidden_columns.is_empty(), |this| {
+            this.child(
+                Button::new("show-all-columns")
+                    .ghost()
+                    .label("Show all columns")
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(ShowAllColumns), cx)),
+            )
+        })
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::storage::config_dir;
+
+/// Number of paths kept in the recent files list.
+const MAX_RECENT_FILES: usize = 10;
+
+/// File name of the recent files list inside the config directory.
+const RECENT_FILES_NAME: &str = "recent_files";
+
+/// Most recently opened files, newest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecentFiles {
+    paths: Vec<PathBuf>,
+}
+
+impl RecentFiles {
+    /// Load the list from the config directory, starting empty if it can't be read.
+    pub fn load() -> Self {
+        let Some(path) = Self::storage_path() else {
+            return Self::default();
+        };
+
+        match Self::load_from(&path) {
+            Ok(recent) => recent,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                tracing::warn!(%error, path = %path.to_string_lossy(), "failed to read recent files");
+                Self::default()
+            }
+        }
+    }
+
+    /// Write the list to the config directory, logging instead of failing.
+    pub fn save(&self) {
+        let Some(path) = Self::storage_path() else {
+            return;
+        };
+
+        if let Err(error) = self.save_to(&path) {
+            tracing::warn!(%error, path = %path.to_string_lossy(), "failed to save recent files");
+        }
+    }
+
+    fn storage_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(RECENT_FILES_NAME))
+    }
+
+    /// Read a list stored as one path per line.
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let paths = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(PathBuf::from)
+            .take(MAX_RECENT_FILES)
+            .collect();
+
+        Ok(Self { paths })
+    }
+
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = String::new();
+        for recent in &self.paths {
+            contents.push_str(&recent.to_string_lossy());
+            contents.push('\n');
+        }
+
+        fs::write(path, contents)
+    }
+
+    /// Move `path` to the front of the list, dropping the oldest entry when full.
+    pub fn push(&mut self, path: &Path) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.paths.retain(|recent| recent != &path);
+        self.paths.insert(0, path);
+        self.paths.truncate(MAX_RECENT_FILES);
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn push_moves_existing_entry_to_front_and_caps_length() {
+        let mut recent = RecentFiles::default();
+        for index in 0..MAX_RECENT_FILES + 2 {
+            recent.push(Path::new(&format!("/data/{index}.parquet")));
+        }
+        recent.push(Path::new("/data/5.parquet"));
+
+        assert_eq!(recent.paths().len(), MAX_RECENT_FILES);
+        assert_eq!(recent.paths()[0], PathBuf::from("/data/5.parquet"));
+        assert_eq!(
+            recent
+                .paths()
+                .iter()
+                .filter(|path| path.ends_with("5.parquet"))
+                .count(),
+            1
+        );
+        assert!(!recent.paths().contains(&PathBuf::from("/data/0.parquet")));
+    }
+
+    #[test]
+    fn recent_files_round_trip_through_disk() {
+        let dir = TempDir::new().expect("temp dir should be created");
+        let storage = dir.path().join("nested").join(RECENT_FILES_NAME);
+        let mut recent = RecentFiles::default();
+        recent.push(Path::new("/lake/a.parquet"));
+        recent.push(Path::new("/lake/b.parquet"));
+
+        recent.save_to(&storage).expect("save should succeed");
+        let loaded = RecentFiles::load_from(&storage).expect("load should succeed");
+
+        assert_eq!(loaded, recent);
+    }
+}
@@ -0,0 +1,140 @@
+use gpui::{actions, div, prelude::*, px, ClipboardItem};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, StyledExt};
+use parquet_viewer_core::inspect::{inspect_row, RecordDetail};
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [ShowRecord]);
+
+/// Tallest the record view grows before it scrolls.
+const RECORD_VIEW_HEIGHT: f32 = 360.0;
+
+/// Width of the column name and type labels of the record view.
+const RECORD_LABEL_WIDTH: f32 = 220.0;
+
+/// Record view with every column of one row as a name and value pair, one per
+/// line, for rows too wide to read across the table.
+pub fn render_record_view(
+    record: &Result<RecordDetail, String>,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(
+        |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+            view.record_view = None;
+            cx.notify();
+        },
+    );
+
+    let (title, body) = match record {
+        Ok(record) => (
+            format!("Row {}", record.row + 1),
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .children(record.cells.iter().map(|cell| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_2()
+                        .child(
+                            div()
+                                .w(px(RECORD_LABEL_WIDTH))
+                                .flex_none()
+                                .flex()
+                                .flex_col()
+                                .child(div().font_medium().truncate().child(cell.column.clone()))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(theme.muted_foreground)
+                                        .truncate()
+                                        .child(cell.data_type.clone()),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .font_family("monospace")
+                                .whitespace_normal()
+                                .children(
+                                    cell.text.lines().map(|line| div().child(line.to_string())),
+                                ),
+                        )
+                }))
+                .into_any_element(),
+        ),
+        Err(message) => (
+            "Record".to_string(),
+            div().child(message.clone()).into_any_element(),
+        ),
+    };
+    let json = record.as_ref().ok().map(RecordDetail::to_json);
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child(title))
+                .when_some(json, |this, json| {
+                    this.child(
+                        Button::new("copy-record-json")
+                            .ghost()
+                            .label("Copy as JSON")
+                            .on_click(move |_, _, cx| {
+                                cx.write_to_clipboard(ClipboardItem::new_string(json.clone()))
+                            }),
+                    )
+                })
+                .child(
+                    Button::new("close-record")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(
+            div()
+                .id("record-fields")
+                .max_h(px(RECORD_VIEW_HEIGHT))
+                .overflow_y_scroll()
+                .child(body),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn show_record(
+        &mut self,
+        _: &ShowRecord,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let Some((display_row, _)) = self.selected_cell else {
+            return;
+        };
+        let row = self.file_row(display_row);
+        self.record_view = match inspect_row(&self.preview, row) {
+            Ok(record) => record.map(Ok),
+            Err(error) => {
+                tracing::error!(%error, row, "failed to read record");
+                Some(Err(format!("Row {} could not be read: {error}", row + 1)))
+            }
+        };
+        cx.notify();
+    }
+}
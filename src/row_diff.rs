@@ -0,0 +1,223 @@
+use gpui::{div, prelude::*, px, Action, App, Global};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, StyledExt, WindowExt};
+use parquet_viewer_core::inspect::{inspect_row, RecordDetail};
+use parquet_viewer_core::rowdiff::diff_records;
+
+use crate::PreviewView;
+
+/// Tallest the diff grows before it scrolls.
+const DIFF_HEIGHT: f32 = 360.0;
+
+/// Width of the column name labels of the diff.
+const DIFF_LABEL_WIDTH: f32 = 220.0;
+
+/// Mark one row for the row diff. `row` is a display row.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct MarkRowForDiff {
+    pub row: usize,
+}
+
+/// A row marked for the diff, with the name of the file it came from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkedRow {
+    pub file: String,
+    pub record: RecordDetail,
+}
+
+impl MarkedRow {
+    fn label(&self) -> String {
+        format!("{} · row {}", self.file, self.record.row + 1)
+    }
+}
+
+/// The last two rows marked in any tab, oldest first; two of them show the
+/// diff in every tab.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarkedRows(Vec<MarkedRow>);
+
+impl Global for MarkedRows {}
+
+impl MarkedRows {
+    /// Mark `row`, replacing the older of two marked rows.
+    pub fn mark(&mut self, row: MarkedRow) {
+        if self.0.len() == 2 {
+            self.0.remove(0);
+        }
+        self.0.push(row);
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// The two rows to compare, once both are marked.
+    pub fn pair(&self) -> Option<(&MarkedRow, &MarkedRow)> {
+        match self.0.as_slice() {
+            [left, right] => Some((left, right)),
+            _ => None,
+        }
+    }
+
+    pub fn global(cx: &App) -> Option<&Self> {
+        cx.try_global::<Self>()
+    }
+}
+
+/// Panel with the cells of the two marked rows side by side, column by column,
+/// the columns whose values differ highlighted.
+pub fn render_row_diff(
+    left: &MarkedRow,
+    right: &MarkedRow,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let close = cx.listener(|_: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+        cx.default_global::<MarkedRows>().clear();
+    });
+    let diffs = diff_records(&left.record, &right.record);
+    let changed = diffs.iter().filter(|diff| diff.changed()).count();
+
+    let value = |text: &Option<String>| {
+        div()
+            .flex_1()
+            .min_w_0()
+            .font_family("monospace")
+            .whitespace_normal()
+            .map(|this| match text {
+                Some(text) => this.children(text.lines().map(|line| div().child(line.to_string()))),
+                None => this
+                    .text_color(theme.muted_foreground)
+                    .child("(no such column)"),
+            })
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_sm()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_2()
+                .child(div().flex_1().font_medium().child(format!(
+                    "Row diff: {changed} of {} columns differ",
+                    diffs.len()
+                )))
+                .child(
+                    Button::new("close-row-diff")
+                        .ghost()
+                        .label("Close")
+                        .on_click(close),
+                ),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .text_color(theme.muted_foreground)
+                .child(div().w(px(DIFF_LABEL_WIDTH)).flex_none().child("Column"))
+                .child(div().flex_1().min_w_0().truncate().child(left.label()))
+                .child(div().flex_1().min_w_0().truncate().child(right.label())),
+        )
+        .child(
+            div()
+                .id("row-diff")
+                .flex()
+                .flex_col()
+                .gap_1()
+                .max_h(px(DIFF_HEIGHT))
+                .overflow_y_scroll()
+                .children(diffs.iter().map(|diff| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_2()
+                        .px_1()
+                        .rounded(theme.radius)
+                        .when(diff.changed(), |this| {
+                            this.bg(theme.warning.opacity(0.25)).font_medium()
+                        })
+                        .child(
+                            div()
+                                .w(px(DIFF_LABEL_WIDTH))
+                                .flex_none()
+                                .truncate()
+                                .child(diff.column.clone()),
+                        )
+                        .child(value(&diff.left))
+                        .child(value(&diff.right))
+                })),
+        )
+}
+
+impl PreviewView {
+    /// Mark a row for the row diff; the second row marked, in this tab or
+    /// another, opens the diff.
+    pub(crate) fn mark_row_for_diff(
+        &mut self,
+        action: &MarkRowForDiff,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let row = self.file_row(action.row);
+        let record = match inspect_row(&self.preview, row) {
+            Ok(Some(record)) => record,
+            Ok(None) => return,
+            Err(error) => {
+                tracing::error!(%error, row, "failed to read the row to diff");
+                window.push_notification(format!("Row {} could not be read: {error}", row + 1), cx);
+                return;
+            }
+        };
+        let marked = cx.default_global::<MarkedRows>();
+        marked.mark(MarkedRow {
+            file: self.file_name(),
+            record,
+        });
+        if marked.pair().is_none() {
+            window.push_notification(
+                format!("Row {} marked; mark another row to compare", row + 1),
+                cx,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marked(row: usize) -> MarkedRow {
+        MarkedRow {
+            file: "a.parquet".to_string(),
+            record: RecordDetail {
+                row,
+                cells: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn marking_keeps_the_last_two_rows() {
+        let mut rows = MarkedRows::default();
+        rows.mark(marked(1));
+        assert!(rows.pair().is_none());
+
+        rows.mark(marked(2));
+        rows.mark(marked(3));
+        let (left, right) = rows.pair().expect("two rows are marked");
+        assert_eq!((left.record.row, right.record.row), (2, 3));
+        assert_eq!(right.label(), "a.parquet · row 4");
+    }
+}
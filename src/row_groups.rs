@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use gpui::{actions, div, prelude::*, px, Action};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::menu::DropdownMenu;
+use gpui_component::{Disableable, Sizable, WindowExt};
+
+use crate::PreviewView;
+
+actions!(parquet_viewer, [NextRowGroup, PreviousRowGroup]);
+
+/// Scroll to the first shown row of row group `index`.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct GoToRowGroup {
+    pub index: usize,
+}
+
+/// The row group holding file row `row`; `groups` are the file's row groups in order.
+pub fn containing(groups: &[Range<usize>], row: usize) -> Option<usize> {
+    let index = groups.partition_point(|group| group.end <= row);
+    groups
+        .get(index)
+        .filter(|group| group.contains(&row))
+        .map(|_| index)
+}
+
+/// The first display row showing a row of `group`, or `None` if the filter hides
+/// all of them. `order` maps display rows to file rows when filtering or sorting.
+pub fn first_display_row(group: &Range<usize>, order: Option<&[usize]>) -> Option<usize> {
+    match order {
+        Some(order) => order.iter().position(|row| group.contains(row)),
+        None => (!group.is_empty()).then_some(group.start),
+    }
+}
+
+/// Tallest the row group dropdown grows before it scrolls.
+const ROW_GROUP_MENU_HEIGHT: f32 = 320.0;
+
+impl PreviewView {
+    /// The row group holding the row at the top of the table.
+    fn current_row_group(&self) -> Option<usize> {
+        if self.display_row_count() == 0 {
+            return None;
+        }
+        containing(
+            &self.preview.row_groups,
+            self.file_row(self.visible_range.start),
+        )
+    }
+
+    /// Scroll the first shown row of the next row group, or with `backwards` the
+    /// previous one, to the top; row groups the filter hides entirely are skipped.
+    pub(crate) fn step_row_group(&mut self, backwards: bool, cx: &mut gpui::Context<PreviewView>) {
+        let Some(current) = self.current_row_group() else {
+            return;
+        };
+        let order = self.row_order.as_deref();
+        let groups = &self.preview.row_groups;
+        let target = if backwards {
+            groups[..current]
+                .iter()
+                .rev()
+                .find_map(|group| first_display_row(group, order))
+        } else {
+            groups[current + 1..]
+                .iter()
+                .find_map(|group| first_display_row(group, order))
+        };
+        if let Some(row) = target {
+            self.load_visible_rows(row, cx);
+        }
+    }
+
+    pub(crate) fn go_to_row_group(
+        &mut self,
+        action: &GoToRowGroup,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let Some(group) = self.preview.row_groups.get(action.index) else {
+            return;
+        };
+        match first_display_row(group, self.row_order.as_deref()) {
+            Some(row) => self.load_visible_rows(row, cx),
+            None => window.push_notification(
+                format!(
+                    "No rows of row group {} match the current filter",
+                    action.index + 1
+                ),
+                cx,
+            ),
+        }
+    }
+}
+
+/// The row group at the top of the table, with buttons to step through the row
+/// groups and a dropdown to jump to one.
+pub fn render_row_group_controls(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let groups = &view.preview.row_groups;
+    let current = view.current_row_group();
+    let step = |backwards: bool| {
+        cx.listener(
+            move |view: &mut PreviewView, _: &gpui::ClickEvent, _window, cx| {
+                view.step_row_group(backwards, cx);
+            },
+        )
+    };
+    let label = match current {
+        Some(index) => format!("Row group {} of {}", index + 1, groups.len()),
+        None => format!("{} row group(s)", groups.len()),
+    };
+    let sizes: Vec<(usize, usize)> = groups
+        .iter()
+        .map(|group| (group.start, group.len()))
+        .collect();
+    let focus_handle = view.focus_handle.clone();
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_1()
+        .ml_auto()
+        .child(
+            Button::new("previous-row-group")
+                .ghost()
+                .xsmall()
+                .label("◀")
+                .disabled(current.is_none_or(|index| index == 0))
+                .on_click(step(true)),
+        )
+        .child(
+            Button::new("row-group-menu")
+                .ghost()
+                .xsmall()
+                .label(label)
+                .disabled(groups.is_empty())
+                .dropdown_menu(move |menu, _window, _cx| {
+                    let menu = menu
+                        .action_context(focus_handle.clone())
+                        .scrollable(true)
+                        .max_h(px(ROW_GROUP_MENU_HEIGHT));
+                    sizes
+                        .iter()
+                        .enumerate()
+                        .fold(menu, |menu, (index, &(start, rows))| {
+                            menu.menu_with_check(
+                                format!(
+                                    "Row group {}: rows {}–{}",
+                                    index + 1,
+                                    start + 1,
+                                    start + rows
+                                ),
+                                current == Some(index),
+                                Box::new(GoToRowGroup { index }),
+                            )
+                        })
+                }),
+        )
+        .child(
+            Button::new("next-row-group")
+                .ghost()
+                .xsmall()
+                .label("▶")
+                .disabled(current.is_none_or(|index| index + 1 >= groups.len()))
+                .on_click(step(false)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_map_to_their_row_group_and_back() {
+        let groups = [0..100, 100..150, 150..400];
+
+        assert_eq!(containing(&groups, 0), Some(0));
+        assert_eq!(containing(&groups, 149), Some(1));
+        assert_eq!(containing(&groups, 150), Some(2));
+        assert_eq!(containing(&groups, 400), None);
+
+        assert_eq!(first_display_row(&groups[1], None), Some(100));
+        let order = [5, 160, 120, 110];
+        assert_eq!(first_display_row(&groups[1], Some(&order)), Some(2));
+        assert_eq!(first_display_row(&groups[2], Some(&order)), Some(1));
+        assert_eq!(first_display_row(&(300..310), Some(&order)), None);
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_json, save_json};
+use parquet_viewer_core::filter::{SortKey, ValueFilter};
+use parquet_viewer_core::rules::HighlightRule;
+
+/// File name of the per-file session store inside the config directory.
+const SESSIONS_NAME: &str = "sessions.json";
+
+/// Number of files whose state is kept; the least recently saved are dropped first.
+const MAX_SESSIONS: usize = 100;
+
+/// View state restored when a file is reopened.
+///
+/// Fields default individually so that state saved by older versions still loads.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileSession {
+    /// First row of the viewport.
+    pub scroll_row: usize,
+    /// Selected cell as `(row, column)`, using the file's column index.
+    pub selected_cell: Option<(usize, usize)>,
+    /// Display order of the columns, by name.
+    pub column_order: Vec<String>,
+    /// Names of hidden columns.
+    pub hidden_columns: Vec<String>,
+    pub filters: Vec<ValueFilter>,
+    pub sort: Option<SortKey>,
+    /// Bookmarked rows, by row index in the file.
+    pub bookmarks: Vec<usize>,
+    /// Rules painting suspect cells, first match winning.
+    pub highlight_rules: Vec<HighlightRule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct StoredSession {
+    /// Modification time of the file when the state was saved, in milliseconds.
+    modified: u64,
+    /// When the state was saved, used to evict old entries.
+    saved_at: u64,
+    session: FileSession,
+}
+
+/// Saved view state for every recently viewed file, keyed by canonical path.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionStore {
+    files: BTreeMap<String, StoredSession>,
+}
+
+impl SessionStore {
+    pub fn load() -> Self {
+        load_json(SESSIONS_NAME).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_json(SESSIONS_NAME, self);
+    }
+
+    /// State saved for `path`, unless the file was modified since.
+    pub fn get(&self, path: &Path) -> Option<FileSession> {
+        let (key, modified) = file_key(path)?;
+        self.files
+            .get(&key)
+            .filter(|stored| stored.modified == modified)
+            .map(|stored| stored.session.clone())
+    }
+
+    pub fn set(&mut self, path: &Path, session: FileSession) {
+        let Some((key, modified)) = file_key(path) else {
+            return;
+        };
+
+        let saved_at = millis_since_epoch(SystemTime::now());
+        self.files.insert(
+            key,
+            StoredSession {
+                modified,
+                saved_at,
+                session,
+            },
+        );
+
+        while self.files.len() > MAX_SESSIONS {
+            let oldest = self
+                .files
+                .iter()
+                .min_by_key(|(_, stored)| stored.saved_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => self.files.remove(&key),
+                None => break,
+            };
+        }
+    }
+}
+
+/// Restore the saved state for `path`, if any.
+pub fn restore(path: &Path) -> Option<FileSession> {
+    SessionStore::load().get(path)
+}
+
+/// Persist `session` as the latest state for `path`.
+pub fn remember(path: &Path, session: FileSession) {
+    let mut store = SessionStore::load();
+    store.set(path, session);
+    store.save();
+}
+
+fn file_key(path: &Path) -> Option<(String, u64)> {
+    let path = path.canonicalize().ok()?;
+    let modified = path.metadata().ok()?.modified().ok()?;
+    Some((
+        path.to_string_lossy().into_owned(),
+        millis_since_epoch(modified),
+    ))
+}
+
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use std::time::Duration;
+
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn session_is_restored_until_the_file_changes() {
+        let file = NamedTempFile::new().expect("temp file should be created");
+        let mut store = SessionStore::default();
+        let session = FileSession {
+            scroll_row: 120,
+            selected_cell: Some((121, 2)),
+            column_order: vec!["b".to_string(), "a".to_string()],
+            hidden_columns: vec!["a".to_string()],
+            filters: vec![ValueFilter {
+                column: "b".to_string(),
+                value: "x".to_string(),
+                exclude: true,
+                regex: false,
+            }],
+            sort: Some(SortKey {
+                column: "b".to_string(),
+                descending: false,
+            }),
+            bookmarks: vec![3, 500],
+            highlight_rules: vec![HighlightRule::parse("b < 0 => red").unwrap()],
+        };
+
+        store.set(file.path(), session.clone());
+        assert_eq!(store.get(file.path()), Some(session));
+
+        let mut handle = File::options().append(true).open(file.path()).unwrap();
+        handle.write_all(b"changed").unwrap();
+        handle
+            .set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(store.get(file.path()), None);
+    }
+
+    #[test]
+    fn older_session_format_loads_with_defaults() {
+        let store: SessionStore = serde_json::from_str(
+            r#"{"files":{"/data/a.parquet":{"modified":1,"saved_at":2,"session":{"scroll_row":7}}}}"#,
+        )
+        .expect("store should parse");
+
+        let stored = store
+            .files
+            .get("/data/a.parquet")
+            .expect("entry should exist");
+        assert_eq!(stored.session.scroll_row, 7);
+        assert_eq!(stored.session.selected_cell, None);
+    }
+}
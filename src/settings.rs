@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_json, save_json};
+use crate::table_style::TableStyle;
+use parquet_viewer_core::placeholders::Placeholders;
+use parquet_viewer_core::prefetch::DEFAULT_PREFETCH_SCREENS;
+
+/// File name of the user settings inside the config directory.
+const SETTINGS_NAME: &str = "settings.json";
+
+/// User preferences that apply to every file.
+///
+/// Fields default individually so that a hand-edited file only needs the keys it changes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// `light`, `dark`, `system` or the name of a theme in the themes directory.
+    pub theme: Option<String>,
+    pub table: TableStyle,
+    /// Screens of rows decoded ahead of the viewport in each scroll direction.
+    pub prefetch_screens: usize,
+    /// Locale numbers and dates are written in, e.g. `de-DE`; `--locale` overrides it.
+    pub locale: Option<String>,
+    /// Text shown for nulls, NaN, infinities and empty strings; the `--show-*`
+    /// options override it.
+    pub placeholders: Placeholders,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            table: TableStyle::default(),
+            prefetch_screens: DEFAULT_PREFETCH_SCREENS,
+            locale: None,
+            placeholders: Placeholders::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        load_json(SETTINGS_NAME).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_json(SETTINGS_NAME, self);
+    }
+
+    /// Load the settings, apply `change` and write them back.
+    pub fn update(change: impl FnOnce(&mut Settings)) {
+        let mut settings = Self::load();
+        change(&mut settings);
+        settings.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_keys_fall_back_to_defaults() {
+        let settings: Settings = serde_json::from_str("{}").expect("settings should parse");
+        assert_eq!(settings, Settings::default());
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"theme":"dark"}"#).expect("settings should parse");
+        assert_eq!(settings.theme.as_deref(), Some("dark"));
+        assert_eq!(settings.prefetch_screens, DEFAULT_PREFETCH_SCREENS);
+    }
+}
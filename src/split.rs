@@ -0,0 +1,73 @@
+use gpui::{actions, div, prelude::*, px, Entity};
+use gpui_component::ActiveTheme;
+
+use crate::workspace::Workspace;
+use crate::PreviewView;
+
+actions!(parquet_viewer, [SplitSideBySide, SplitStacked, CloseSplit]);
+
+/// How the two panes of a split tab are arranged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitAxis {
+    /// Left and right, for wide screens.
+    SideBySide,
+    /// One above the other, each with half the window's height.
+    Stacked,
+}
+
+/// Second pane of a tab split in two: another view of the same file, scrolled,
+/// filtered and sorted independently, e.g. to compare its head with its tail.
+pub struct Split {
+    pub axis: SplitAxis,
+    pub view: Entity<PreviewView>,
+    /// Whether the second pane was clicked last, so that menu actions go to it.
+    pub second_active: bool,
+}
+
+/// The two panes of a split tab, the one menu actions go to marked with an
+/// accent line; clicking a pane makes it that one.
+pub fn render_split(
+    first: &Entity<PreviewView>,
+    split: &Split,
+    cx: &gpui::Context<Workspace>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let pane = |index: usize, view: &Entity<PreviewView>, active: bool| {
+        let second = index == 1;
+        div()
+            .id(("split-pane", index))
+            .relative()
+            .flex_1()
+            .min_w_0()
+            .min_h_0()
+            .overflow_hidden()
+            .when(second, |this| match split.axis {
+                SplitAxis::SideBySide => this.border_l_1(),
+                SplitAxis::Stacked => this.border_t_1(),
+            })
+            .border_color(theme.border)
+            .capture_any_mouse_down(cx.listener(move |workspace: &mut Workspace, _, _, cx| {
+                workspace.activate_pane(second, cx);
+            }))
+            .child(view.clone())
+            .when(active, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .w_full()
+                        .h(px(2.0))
+                        .bg(theme.primary),
+                )
+            })
+    };
+
+    div()
+        .flex()
+        .when(split.axis == SplitAxis::SideBySide, |this| this.flex_row())
+        .when(split.axis == SplitAxis::Stacked, |this| this.flex_col())
+        .size_full()
+        .child(pane(0, first, !split.second_active))
+        .child(pane(1, &split.view, split.second_active))
+}
@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use gpui::{div, prelude::*, App};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, StyledExt};
+
+use crate::workspace::{OpenFile, OpenRecent};
+
+/// A file that could not be opened, shown on the start screen.
+#[derive(Clone, Debug)]
+pub struct LoadFailure {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+/// Content shown when no file is loaded: the last load error, if any, and the
+/// recently opened files.
+pub fn render_start_screen(
+    failure: Option<&LoadFailure>,
+    recent: &[PathBuf],
+    cx: &App,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+
+    let message = failure.map(|failure| {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_3()
+            .w_full()
+            .border_1()
+            .border_color(theme.danger)
+            .rounded(theme.radius)
+            .child(
+                div()
+                    .font_medium()
+                    .text_color(theme.danger)
+                    .child(format!("Could not open {}", failure.path.display())),
+            )
+            .child(div().text_sm().child(failure.message.clone()))
+    });
+
+    let recent_list = (!recent.is_empty()).then(|| {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .w_full()
+            .child(
+                div()
+                    .text_sm()
+                    .font_medium()
+                    .text_color(theme.muted_foreground)
+                    .child("Recent files"),
+            )
+            .children(recent.iter().enumerate().map(|(index, path)| {
+                let action = OpenRecent { path: path.clone() };
+                Button::new(("recent-file", index))
+                    .ghost()
+                    .label(path.display().to_string())
+                    .on_click(move |_, window, cx| {
+                        window.dispatch_action(Box::new(action.clone()), cx)
+                    })
+            }))
+    });
+
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .justify_center()
+        .gap_4()
+        .p_4()
+        .size_full()
+        .bg(theme.background)
+        .text_color(theme.foreground)
+        .child(div().text_xl().font_medium().child("Parquet Viewer"))
+        .children(message)
+        .child(
+            Button::new("open-file")
+                .primary()
+                .label(if failure.is_some() {
+                    "Open another file…"
+                } else {
+                    "Open file…"
+                })
+                .on_click(|_, window, cx| window.dispatch_action(Box::new(OpenFile), cx)),
+        )
+        .children(recent_list)
+}
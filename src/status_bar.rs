@@ -0,0 +1,73 @@
+use gpui::{div, prelude::*};
+use gpui_component::ActiveTheme;
+use parquet_viewer_core::geo::GeoMetadata;
+use parquet_viewer_core::metrics::format_bytes;
+
+use crate::row_groups::render_row_group_controls;
+use crate::PreviewView;
+
+/// Geometry columns of a GeoParquet file with their encoding, CRS and bounding box.
+pub fn render_geo_metadata(
+    geo: &GeoMetadata,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    div()
+        .flex()
+        .flex_col()
+        .text_sm()
+        .text_color(theme.muted_foreground)
+        .children(
+            geo.summary()
+                .into_iter()
+                .map(|line| div().child(format!("GeoParquet {line}"))),
+        )
+}
+
+/// Footer with file facts and read metrics, to help reason about read performance.
+pub fn render_status_bar(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let preview = &view.preview;
+
+    let last_load = view
+        .last_load
+        .map(|elapsed| format!("last load {:.1} ms", elapsed.as_secs_f64() * 1000.0))
+        .unwrap_or_else(|| "no rows loaded".to_string());
+    let items = [
+        format_bytes(preview.file_size),
+        preview.codecs.join(", "),
+        format!("{} read", format_bytes(preview.bytes_read.bytes())),
+        last_load,
+        selection_status(view.selected_rows.len()),
+    ];
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_3()
+        .pt_1()
+        .w_full()
+        .border_t_1()
+        .border_color(theme.border)
+        .text_sm()
+        .text_color(theme.muted_foreground)
+        .children(
+            items
+                .into_iter()
+                .filter(|item| !item.is_empty())
+                .map(|item| div().child(item)),
+        )
+        .child(render_row_group_controls(view, cx))
+}
+
+fn selection_status(selected: usize) -> String {
+    match selected {
+        0 => "No rows selected".to_string(),
+        1 => "1 row selected".to_string(),
+        count => format!("{count} rows selected"),
+    }
+}
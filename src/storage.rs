@@ -0,0 +1,53 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Directory holding the viewer's persisted state, e.g. `$XDG_CONFIG_HOME/parquet-viewer`.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("parquet-viewer"))
+}
+
+/// Read a JSON document stored under `name` in the config directory.
+///
+/// Missing or unreadable files yield `None`; the latter is logged since it means
+/// the user's state is being ignored.
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let path = config_dir()?.join(name);
+    match read_json(&path) {
+        Ok(value) => Some(value),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => {
+            tracing::warn!(%error, path = %path.to_string_lossy(), "failed to read saved state");
+            None
+        }
+    }
+}
+
+/// Write `value` as JSON under `name` in the config directory, logging failures.
+pub fn save_json<T: Serialize>(name: &str, value: &T) {
+    let Some(dir) = config_dir() else {
+        return;
+    };
+
+    let path = dir.join(name);
+    if let Err(error) = write_json(&path, value) {
+        tracing::warn!(%error, path = %path.to_string_lossy(), "failed to save state");
+    }
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(io::Error::from)
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents = serde_json::to_string_pretty(value).map_err(io::Error::from)?;
+    fs::write(path, contents)
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use gpui::{actions, div, prelude::*, px, MouseButton, Pixels};
+use gpui_component::{ActiveTheme, StyledExt};
+use parquet_viewer_core::aggregate::{summarize, Aggregate};
+use parquet_viewer_core::progress::Progress;
+
+use crate::column_widths::column_cell;
+use crate::minimap::MINIMAP_WIDTH;
+use crate::{PreviewView, GUTTER_WIDTH};
+
+actions!(parquet_viewer, [ToggleSummaryRow]);
+
+/// Aggregates shown under the table, one per column, computed in the background
+/// over the whole file or the rows the filters keep.
+#[derive(Default)]
+pub struct SummaryRow {
+    /// Aggregate picked per column name; columns default to a non-null count.
+    pub aggregates: HashMap<String, Aggregate>,
+    /// Results computed so far by column name, with the aggregate they are for.
+    pub values: HashMap<String, (Aggregate, Option<String>)>,
+    /// Why the last computation failed, shown instead of the values.
+    pub error: Option<String>,
+    /// Computations still running, cancelled when the rows they cover change.
+    pub running: Vec<Progress>,
+}
+
+impl SummaryRow {
+    pub fn aggregate(&self, column: &str) -> Aggregate {
+        self.aggregates.get(column).copied().unwrap_or_default()
+    }
+
+    /// Cancel the running computations, e.g. because the filters changed.
+    pub fn cancel(&mut self) {
+        for progress in self.running.drain(..) {
+            progress.cancel();
+        }
+    }
+
+    /// Text of `column`'s cell: its result once computed for the picked aggregate,
+    /// `…` while computing.
+    fn cell_text(&self, column: &str) -> String {
+        let aggregate = self.aggregate(column);
+        let value = match self.values.get(column) {
+            Some((computed, value)) if *computed == aggregate => {
+                value.clone().unwrap_or_else(|| "—".to_string())
+            }
+            _ => "…".to_string(),
+        };
+        format!("{}: {value}", aggregate.name())
+    }
+}
+
+impl Drop for SummaryRow {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Footer row of the table with one aggregate per displayed column; clicking a
+/// cell switches it to the next aggregate.
+pub fn render_summary_row(
+    summary: &SummaryRow,
+    columns: &[usize],
+    widths: &[Pixels],
+    names: &[String],
+    row_height: f32,
+    minimap: bool,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let error = summary.error.clone();
+
+    div()
+        .flex()
+        .flex_row()
+        .w_full()
+        .h(px(row_height))
+        .flex_none()
+        .bg(theme.table_head)
+        .text_color(theme.table_head_foreground)
+        .border_t_1()
+        .border_color(theme.table_row_border)
+        .font_medium()
+        .child(
+            div()
+                .flex()
+                .items_center()
+                .justify_center()
+                .w(px(GUTTER_WIDTH))
+                .h_full()
+                .flex_none()
+                .border_r_1()
+                .border_color(theme.table_row_border)
+                .child("Σ"),
+        )
+        .children(columns.iter().zip(widths).map(|(&column, &width)| {
+            let name = names[column].clone();
+            let text = match &error {
+                Some(message) => message.clone(),
+                None => summary.cell_text(&name),
+            };
+            let click_handler = cx.listener(
+                move |view: &mut PreviewView, _: &gpui::MouseDownEvent, _window, cx| {
+                    view.cycle_summary_aggregate(&name, cx);
+                },
+            );
+            column_cell(div(), width)
+                .flex()
+                .items_center()
+                .px_2()
+                .h_full()
+                .border_r_1()
+                .border_color(theme.table_row_border)
+                .cursor_pointer()
+                .hover(|this| this.bg(theme.table_hover))
+                .on_mouse_down(MouseButton::Left, click_handler)
+                .child(text)
+        }))
+        .when(minimap, |this| {
+            this.child(div().flex_none().w(px(MINIMAP_WIDTH)))
+        })
+}
+
+impl PreviewView {
+    pub(crate) fn toggle_summary_row(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.summary = match self.summary.take() {
+            Some(_) => None,
+            None => Some(SummaryRow::default()),
+        };
+        self.refresh_summary(cx);
+    }
+
+    /// Recompute every column of the summary row, e.g. after the filters changed.
+    pub(crate) fn refresh_summary(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        let Some(summary) = &mut self.summary else {
+            return;
+        };
+        summary.cancel();
+        summary.values.clear();
+        summary.error = None;
+        self.compute_summary(self.preview.columns.clone(), cx);
+    }
+
+    /// Switch `column` of the summary row to its next aggregate and compute it.
+    pub(crate) fn cycle_summary_aggregate(
+        &mut self,
+        column: &str,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let Some(summary) = &mut self.summary else {
+            return;
+        };
+        let next = summary.aggregate(column).next();
+        summary.aggregates.insert(column.to_string(), next);
+        self.compute_summary(vec![column.to_string()], cx);
+    }
+
+    /// Compute the picked aggregates of `columns` in the background, over the rows
+    /// the filters keep, and merge them into the summary row.
+    fn compute_summary(&mut self, columns: Vec<String>, cx: &mut gpui::Context<PreviewView>) {
+        let Some(summary) = &mut self.summary else {
+            return;
+        };
+        let columns: Vec<_> = columns
+            .into_iter()
+            .map(|column| {
+                let aggregate = summary.aggregate(&column);
+                (column, aggregate)
+            })
+            .collect();
+        // Sorting alone keeps every row, which the summary reads faster in file order.
+        let rows = if self.filters.is_empty() {
+            None
+        } else {
+            self.row_order.clone()
+        };
+        let progress = Progress::default();
+        summary.running.push(progress.clone());
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let columns = columns.clone();
+            let progress = progress.clone();
+            async move { summarize(&path, &columns, rows.as_deref(), &bytes_read, &progress) }
+        });
+        cx.spawn(async move |view, cx| {
+            let result = task.await;
+            view.update(cx, |view, cx| {
+                // Cancelled computations cover rows the view no longer shows.
+                if progress.is_cancelled() {
+                    return;
+                }
+                let Some(summary) = &mut view.summary else {
+                    return;
+                };
+                match result {
+                    Ok(values) => summary.values.extend(
+                        columns
+                            .into_iter()
+                            .zip(values)
+                            .map(|((column, aggregate), value)| (column, (aggregate, value))),
+                    ),
+                    Err(error) => {
+                        tracing::error!(%error, "failed to compute the summary row");
+                        summary.error = Some(format!("Summary could not be computed: {error}"));
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+}
@@ -0,0 +1,658 @@
+use std::borrow::Cow;
+
+use gpui::{
+    div, prelude::*, px, HighlightStyle, Modifiers, MouseButton, Pixels, SharedString, StyledText,
+};
+use gpui_component::menu::{ContextMenuExt, PopupMenu};
+use gpui_component::tooltip::Tooltip;
+use gpui_component::{ActiveTheme, StyledExt};
+use parquet_viewer_core::histogram::sample_histograms;
+use parquet_viewer_core::intern::Cell;
+use parquet_viewer_core::placeholders::{placeholders, Special};
+use parquet_viewer_core::preview::{ERROR_CELL, UNREADABLE_CELL};
+use parquet_viewer_core::progress::Progress;
+use parquet_viewer_core::selection::CLIPBOARD_ROW_LIMIT;
+use parquet_viewer_core::truncate::{truncate, Truncation};
+use parquet_viewer_core::width::{display_width, is_rtl};
+use parquet_viewer_core::ViewerError;
+
+use crate::column_widths::{chars_per_line, column_cell};
+use crate::context_menu::{build_menu, ContextTarget};
+use crate::highlight::match_ranges;
+use crate::highlight_rules::{matching_color, rule_background};
+use crate::minimap::{render_minimap, MINIMAP_WIDTH};
+use crate::page_boundaries::page_marker;
+use crate::summary::render_summary_row;
+use crate::table_style::TableStyle;
+use crate::word_wrap::{rows_fitting, wrapped_lines, wrapped_row_height, MAX_WRAP_LINES};
+use crate::{PreviewView, GUTTER_WIDTH, MIN_TABLE_HEIGHT};
+
+/// Drawn in cells holding an empty string whose placeholder is empty too.
+const EMPTY_MARK: &str = "\"\"";
+
+/// Longest value shown in a cell's hover tooltip, in characters.
+const MAX_TOOLTIP_CHARS: usize = 2000;
+
+/// Cell errors the error panel lists before summing up the rest.
+const LISTED_CELL_ERRORS: usize = 10;
+
+/// A column header being dragged to a new position; also renders the drag preview.
+#[derive(Clone)]
+struct DraggedColumn {
+    column: usize,
+    name: gpui::SharedString,
+}
+
+impl gpui::Render for DraggedColumn {
+    fn render(
+        &mut self,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+
+        div()
+            .px_2()
+            .py_1()
+            .font_medium()
+            .font_family(TableStyle::global(cx).font_family.clone())
+            .bg(theme.table_head)
+            .text_color(theme.table_head_foreground)
+            .border_1()
+            .border_color(theme.drag_border)
+            .rounded(theme.radius)
+            .child(self.name.clone())
+    }
+}
+
+impl PreviewView {
+    /// Select the clicked cell, extending or toggling the row selection with Shift or Ctrl.
+    pub(crate) fn click_cell(
+        &mut self,
+        row: usize,
+        column: usize,
+        modifiers: Modifiers,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        if modifiers.shift {
+            self.selected_rows.extend_to(row);
+        } else if modifiers.secondary() {
+            self.selected_rows.toggle(row);
+        } else {
+            self.selected_rows.select(row);
+        }
+        self.selected_cell = Some((row, column));
+        cx.notify();
+    }
+
+    /// The selected rows, in display column order without hidden columns.
+    ///
+    /// They are read on the UI thread, so selections of more than
+    /// [`CLIPBOARD_ROW_LIMIT`] rows are refused; those are exported instead.
+    pub(crate) fn selected_table_rows(&mut self) -> Result<Option<Vec<Vec<Cell>>>, ViewerError> {
+        if self.selected_rows.is_empty() {
+            return Ok(None);
+        }
+        let selected = self.selected_rows.len();
+        if selected > CLIPBOARD_ROW_LIMIT {
+            return Err(ViewerError::InvalidValue(format!(
+                "{selected} rows are selected, more than the {CLIPBOARD_ROW_LIMIT} that can be copied; export them to a file instead"
+            )));
+        }
+
+        let columns = self.displayed_columns();
+        let mut rows = Vec::with_capacity(self.selected_rows.len());
+        for run in self.selected_rows.runs() {
+            for row in self.rows_for_display(run)?.rows {
+                rows.push(columns.iter().map(|&column| row[column].clone()).collect());
+            }
+        }
+        Ok(Some(rows))
+    }
+
+    /// Names of the displayed columns, in display order.
+    pub(crate) fn displayed_header(&self) -> Vec<String> {
+        self.displayed_columns()
+            .iter()
+            .map(|&column| self.preview.columns[column].clone())
+            .collect()
+    }
+
+    /// Sample the numeric columns in the background for the header histograms.
+    pub(crate) fn load_histograms(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            async move { sample_histograms(&path, &bytes_read, &Progress::default()) }
+        });
+        cx.spawn(async move |view, cx| {
+            let histograms = task.await;
+            view.update(cx, |view, cx| match histograms {
+                Ok(histograms) => {
+                    view.histograms = histograms;
+                    cx.notify();
+                }
+                Err(error) => tracing::warn!(%error, "failed to sample column histograms"),
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
+
+/// Corner mark of a cell whose invalid UTF-8 is shown as replacement characters.
+fn lossy_utf8_badge(theme: &gpui_component::Theme) -> impl gpui::IntoElement {
+    div()
+        .absolute()
+        .top_0()
+        .right_0()
+        .px_1()
+        .text_xs()
+        .bg(theme.background)
+        .text_color(theme.warning)
+        .child("UTF-8")
+}
+
+/// Panel listing the row groups that could not be decoded and the first cells
+/// that could not be rendered.
+pub fn render_read_errors(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let unreadable = &view.unreadable;
+    let cell_errors = &view.cell_errors;
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.danger)
+        .rounded(theme.radius)
+        .text_sm()
+        .text_color(theme.danger)
+        .when(!unreadable.is_empty(), |this| {
+            this.child(div().font_medium().child(format!(
+                "{} row group(s) could not be read; their rows are marked {UNREADABLE_CELL}",
+                unreadable.len()
+            )))
+            .children(unreadable.iter().map(|range| {
+                div().child(format!(
+                    "Row group {} (rows {}-{}): {}",
+                    range.row_group,
+                    range.rows.start + 1,
+                    range.rows.end,
+                    range.message
+                ))
+            }))
+        })
+        .when(!cell_errors.is_empty(), |this| {
+            this.child(div().font_medium().child(format!(
+                "{} cell(s) could not be shown and are marked {ERROR_CELL}",
+                cell_errors.len()
+            )))
+            .children(cell_errors.iter().take(LISTED_CELL_ERRORS).map(
+                |((row, column), message)| {
+                    div().child(format!(
+                        "Row {}, column {}: {message}",
+                        row + 1,
+                        view.preview.columns[*column]
+                    ))
+                },
+            ))
+            .when(cell_errors.len() > LISTED_CELL_ERRORS, |this| {
+                this.child(div().child(format!(
+                    "and {} more",
+                    cell_errors.len() - LISTED_CELL_ERRORS
+                )))
+            })
+        })
+}
+
+/// Menu builder for right-clicks on the table, built for the cell or header
+/// recorded in `context_target` by the mouse-down handler.
+pub fn table_context_menu(
+    view: &PreviewView,
+    cx: &gpui::Context<PreviewView>,
+) -> impl Fn(PopupMenu, &mut gpui::Window, &mut gpui::Context<PopupMenu>) -> PopupMenu + 'static {
+    let menu_view = cx.entity().downgrade();
+    let focus_handle = view.focus_handle.clone();
+    move |menu: PopupMenu, _: &mut gpui::Window, cx: &mut gpui::Context<PopupMenu>| {
+        let (target, view_as, computed, sorted) = menu_view
+            .upgrade()
+            .map(|view| {
+                let view = view.read(cx);
+                let target = view.context_target.clone();
+                let view_as = target
+                    .as_ref()
+                    .map(|target| view.view_as_choices(target.column()))
+                    .unwrap_or_default();
+                let computed = target
+                    .as_ref()
+                    .is_some_and(|target| target.column() >= view.preview.computed_start());
+                let sorted = target.as_ref().is_some_and(|target| {
+                    matches!(view.sorted_columns.get(target.column()), Some(Some(_)))
+                });
+                (target, view_as, computed, sorted)
+            })
+            .unwrap_or_default();
+        build_menu(
+            menu.action_context(focus_handle.clone()),
+            target.as_ref(),
+            &view_as,
+            computed,
+            sorted,
+        )
+    }
+}
+
+/// Name shown in the header of `column`, with the type it is viewed as if cast.
+fn header_name(view: &PreviewView, column: usize) -> String {
+    match view.preview.casts.get(&column) {
+        Some(view_as) => format!("{} → {view_as}", view.preview.columns[column]),
+        None => view.preview.columns[column].clone(),
+    }
+}
+
+pub fn render_table(
+    view: &mut PreviewView,
+    cx: &mut gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let columns = view.displayed_columns();
+    let sorted_column = view.sort.as_ref().and_then(|sort| {
+        let column = view
+            .preview
+            .columns
+            .iter()
+            .position(|name| *name == sort.column)?;
+        Some((column, sort.descending))
+    });
+    let build_context_menu = table_context_menu(view, cx);
+
+    let style = TableStyle::global(cx).clone();
+    let row_height = style.scaled_row_height();
+    let highlight = view.highlight_text(cx);
+
+    // Header labels, with room for the sort arrow, sortedness badge and
+    // sparkline, and the rows shown widen the columns before they are laid out.
+    for &column in &columns {
+        let badge = view.sorted_columns.get(column).copied().flatten();
+        let sparkline = view.histograms.get(column).and_then(Option::as_ref);
+        let chars = display_width(&header_name(view, column))
+            + 2
+            + badge.map_or(0, |order| order.to_string().chars().count() + 1)
+            + sparkline.map_or(0, |histogram| histogram.sparkline().chars().count());
+        view.column_widths.fit_chars(column, chars);
+    }
+    view.column_widths.fit_rows(&columns, &view.visible_rows);
+    let widths: Vec<Pixels> = columns
+        .iter()
+        .map(|&column| view.column_widths.width(column, style.scaled_font_size()))
+        .collect();
+
+    // Rows grow by a line for each extra line their tallest wrapped cell takes,
+    // and only as many as fit are drawn.
+    let font_size = style.scaled_font_size();
+    let wrapped: Vec<Option<usize>> = columns
+        .iter()
+        .zip(&widths)
+        .map(|(&column, &width)| {
+            view.wrapped_columns
+                .contains(&view.preview.columns[column])
+                .then(|| chars_per_line(width, font_size))
+        })
+        .collect();
+    let row_heights: Vec<f32> = view
+        .visible_rows
+        .iter()
+        .map(|row| {
+            let lines = columns
+                .iter()
+                .zip(&wrapped)
+                .filter_map(|(&column, line_chars)| {
+                    Some(wrapped_lines(&row[column], (*line_chars)?))
+                })
+                .max()
+                .unwrap_or(1);
+            wrapped_row_height(row_height, font_size, lines)
+        })
+        .collect();
+    // The summary row takes the place of the last row rather than growing the table.
+    let rows_height = match view.summary {
+        Some(_) => view.table_height - px(row_height),
+        None => view.table_height,
+    };
+    view.fitted_rows = rows_fitting(&row_heights, f32::from(rows_height));
+
+    let theme = cx.theme();
+    let highlight_style = HighlightStyle {
+        background_color: Some(theme.warning.opacity(0.4)),
+        ..Default::default()
+    };
+
+    let header = div()
+        .flex()
+        .flex_row()
+        .flex_none()
+        .w_full()
+        .bg(theme.table_head)
+        .text_color(theme.table_head_foreground)
+        .border_b_1()
+        .border_color(theme.table_row_border)
+        .child(
+            div()
+                .w(px(GUTTER_WIDTH))
+                .flex_none()
+                .border_r_1()
+                .border_color(theme.table_row_border),
+        )
+        .children(columns.iter().zip(&widths).map(|(&column, &width)| {
+            let name = header_name(view, column);
+            let label = match sorted_column {
+                Some((sorted, descending)) if sorted == column => {
+                    format!("{name} {}", if descending { "▼" } else { "▲" })
+                }
+                _ => name.clone(),
+            };
+            let dragged = DraggedColumn {
+                column,
+                name: name.into(),
+            };
+            let drop_handler = cx.listener(
+                move |view: &mut PreviewView, dragged: &DraggedColumn, _window, cx| {
+                    view.move_column(dragged.column, column, cx);
+                },
+            );
+            let right_click_handler = cx.listener(
+                move |view: &mut PreviewView, _: &gpui::MouseDownEvent, window, _cx| {
+                    window.focus(&view.focus_handle);
+                    view.context_target = Some(ContextTarget::Header { column });
+                },
+            );
+
+            column_cell(div().id(("column-header", column)), width)
+                .px_2()
+                .py_1()
+                .font_medium()
+                .border_r_1()
+                .border_color(theme.table_row_border)
+                .cursor_grab()
+                .on_drag(dragged, |dragged, _, _, cx| cx.new(|_| dragged.clone()))
+                .drag_over::<DraggedColumn>(|style, _, _, cx| style.bg(cx.theme().drop_target))
+                .on_drop(drop_handler)
+                .on_mouse_down(MouseButton::Right, right_click_handler)
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_1()
+                .child(div().flex_1().min_w_0().truncate().child(label))
+                .when_some(
+                    view.sorted_columns.get(column).copied().flatten(),
+                    |this, order| {
+                        this.child(
+                            div()
+                                .flex_none()
+                                .px_1()
+                                .text_xs()
+                                .rounded(theme.radius)
+                                .bg(theme.secondary)
+                                .text_color(theme.muted_foreground)
+                                .child(order.to_string()),
+                        )
+                    },
+                )
+                .when_some(
+                    view.histograms.get(column).and_then(Option::as_ref),
+                    |this, histogram| {
+                        this.child(
+                            div()
+                                .flex_none()
+                                .text_xs()
+                                .font_family("monospace")
+                                .text_color(theme.muted_foreground)
+                                .child(histogram.sparkline()),
+                        )
+                    },
+                )
+        }))
+        // Keeps the columns clear of the minimap beside the rows, as they are.
+        .when(view.minimap.is_some(), |this| {
+            this.child(div().flex_none().w(px(MINIMAP_WIDTH)))
+        });
+
+    let rows = view
+        .visible_rows
+        .iter()
+        .take(view.fitted_rows)
+        .enumerate()
+        .map(|(row_index, row)| {
+            let global_row_index = view.visible_range.start + row_index;
+            let unreadable = view.is_unreadable(view.file_row(global_row_index));
+            let row_selected = view.selected_rows.contains(global_row_index);
+            let bookmarked = view.bookmarks.contains(&view.file_row(global_row_index));
+            let gutter_click_handler = cx.listener(
+                move |view: &mut PreviewView, _: &gpui::MouseDownEvent, _window, cx| {
+                    view.toggle_bookmark_at(global_row_index, cx);
+                },
+            );
+            div()
+                .id(("table-row", global_row_index))
+                .flex()
+                .flex_row()
+                .w_full()
+                .h(px(row_heights[row_index]))
+                .border_b_1()
+                .border_color(theme.table_row_border)
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .w(px(GUTTER_WIDTH))
+                        .h_full()
+                        .flex_none()
+                        .border_r_1()
+                        .border_color(theme.table_row_border)
+                        .text_color(theme.primary)
+                        .cursor_pointer()
+                        .on_mouse_down(MouseButton::Left, gutter_click_handler)
+                        .when(bookmarked, |this| this.child("●")),
+                )
+                .children(columns.iter().zip(&widths).zip(&wrapped).map(
+                    |((&col_index, &width), &line_chars)| {
+                        let value = &row[col_index];
+                        let cell = (view.file_row(global_row_index), col_index);
+                        let failed = unreadable || view.cell_errors.contains_key(&cell);
+                        let lossy = view.lossy_cells.contains(&cell);
+                        let is_selected = view.selected_cell == Some((global_row_index, col_index));
+                        let click_handler = cx.listener(
+                            move |view: &mut PreviewView,
+                                  event: &gpui::MouseDownEvent,
+                                  window,
+                                  cx| {
+                                window.focus(&view.focus_handle);
+                                view.click_cell(global_row_index, col_index, event.modifiers, cx);
+                            },
+                        );
+                        let context_value = value.clone();
+                        let right_click_handler = cx.listener(
+                            move |view: &mut PreviewView, _: &gpui::MouseDownEvent, window, _cx| {
+                                window.focus(&view.focus_handle);
+                                view.context_target = Some(ContextTarget::Cell {
+                                    row: global_row_index,
+                                    column: col_index,
+                                    value: context_value.to_string(),
+                                });
+                            },
+                        );
+
+                        let rule_color = matching_color(
+                            &view.highlight_rules,
+                            &view.preview.columns[col_index],
+                            value,
+                        );
+                        let page_start = view
+                            .page_boundaries
+                            .as_ref()
+                            .filter(|_| view.show_page_boundaries)
+                            .and_then(|pages| {
+                                pages.page_at(col_index, view.file_row(global_row_index))
+                            });
+                        let background = if is_selected {
+                            theme.table_active
+                        } else if row_selected {
+                            theme.table_active.opacity(0.5)
+                        } else if failed {
+                            theme.danger.opacity(0.15)
+                        } else if let Some(color) = rule_color {
+                            rule_background(color, theme)
+                        } else if let Some(heat) = view.heat_maps.background(
+                            &view.preview.columns[col_index],
+                            value,
+                            theme,
+                        ) {
+                            heat
+                        } else if row_index % 2 == 0 {
+                            theme.table
+                        } else {
+                            theme.table_even
+                        };
+
+                        // Wrapped cells keep as much as their lines hold.
+                        let max_chars =
+                            line_chars.map_or(style.max_cell_chars, |chars| chars * MAX_WRAP_LINES);
+                        let shown = truncate(value, max_chars, style.truncation);
+                        // Cut values show in full on hover, up to a limit; the cell
+                        // inspector has the rest.
+                        let full_value = matches!(shown, Cow::Owned(_)).then(|| {
+                            SharedString::from(
+                                truncate(value, MAX_TOOLTIP_CHARS, Truncation::End).into_owned(),
+                            )
+                        });
+                        let special = placeholders().special(value);
+                        let text = match shown {
+                            // Empty strings need a mark, or they'd look like empty cells.
+                            Cow::Borrowed("") => Cell::from(EMPTY_MARK),
+                            Cow::Borrowed(_) => value.clone(),
+                            Cow::Owned(text) => Cell::from(text),
+                        };
+
+                        column_cell(div().id(("cell", col_index)), width)
+                            .flex()
+                            .map(|this| match line_chars {
+                                Some(_) => this.items_start().py_1(),
+                                None => this.items_center().whitespace_nowrap(),
+                            })
+                            // Right-to-left text lines up with the cell's right edge.
+                            .when(is_rtl(value), |this| this.justify_end())
+                            .px_2()
+                            .h_full()
+                            .border_r_1()
+                            .border_color(if is_selected {
+                                theme.table_active_border
+                            } else {
+                                theme.table_row_border
+                            })
+                            .bg(background)
+                            .text_color(match special {
+                                _ if failed => theme.danger,
+                                Some(Special::Null | Special::Empty) => theme.muted_foreground,
+                                Some(Special::NaN | Special::Infinity) => theme.warning,
+                                None => theme.foreground,
+                            })
+                            .when(special.is_some(), |this| this.italic())
+                            .hover(|this| this.bg(theme.table_hover))
+                            .cursor_pointer()
+                            .on_mouse_down(MouseButton::Left, click_handler)
+                            .on_mouse_down(MouseButton::Right, right_click_handler)
+                            .relative()
+                            .when_some(page_start, |this, start| {
+                                this.child(page_marker(start, theme))
+                            })
+                            .when(lossy, |this| this.child(lossy_utf8_badge(theme)))
+                            .when_some(full_value, |this, full_value| {
+                                this.tooltip(move |window, cx| {
+                                    Tooltip::new(full_value.clone()).build(window, cx)
+                                })
+                            })
+                            .child(match &highlight {
+                                Some(needle) => StyledText::new(text.clone())
+                                    .with_highlights(
+                                        match_ranges(&text, needle)
+                                            .into_iter()
+                                            .map(|range| (range, highlight_style)),
+                                    )
+                                    .into_any_element(),
+                                None => SharedString::from(text).into_any_element(),
+                            })
+                    },
+                ))
+        });
+
+    let scroll_handler = cx.listener(
+        |view: &mut PreviewView, event: &gpui::ScrollWheelEvent, _window, cx| {
+            let row_height = TableStyle::global(cx).scaled_row_height();
+            let delta = event.delta.pixel_delta(px(row_height));
+            let rows_delta = -(f32::from(delta.y) / row_height).round() as isize;
+
+            if rows_delta != 0 {
+                view.scroll_view(rows_delta, cx);
+            }
+        },
+    );
+
+    div()
+        .border_1()
+        .border_color(theme.table_row_border)
+        .rounded(theme.radius)
+        .overflow_hidden()
+        .w_full()
+        .flex_1()
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .w_full()
+                .h_full()
+                .text_size(px(style.scaled_font_size()))
+                .font_family(style.font_family)
+                .child(header)
+                .child(
+                    div()
+                        .h(rows_height)
+                        .min_h(px(MIN_TABLE_HEIGHT))
+                        .w_full()
+                        .flex()
+                        .flex_row()
+                        .child(
+                            div()
+                                .h_full()
+                                .flex_1()
+                                .min_w_0()
+                                .overflow_hidden()
+                                .on_scroll_wheel(scroll_handler)
+                                .flex()
+                                .flex_col()
+                                .children(rows),
+                        )
+                        .when_some(view.minimap.as_ref(), |this, minimap| {
+                            this.child(render_minimap(view, minimap, cx))
+                        }),
+                )
+                .when_some(view.summary.as_ref(), |this, summary| {
+                    this.child(render_summary_row(
+                        summary,
+                        &columns,
+                        &widths,
+                        &view.preview.columns,
+                        row_height,
+                        view.minimap.is_some(),
+                        cx,
+                    ))
+                })
+                .context_menu(build_context_menu),
+        )
+}
@@ -0,0 +1,210 @@
+use gpui::{actions, Action, App, Global};
+use parquet_viewer_core::truncate::Truncation;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+const MIN_FONT_SIZE: f32 = 8.0;
+const MAX_FONT_SIZE: f32 = 40.0;
+/// Space kept around the text so that rows never clip their own font.
+const ROW_PADDING: f32 = 6.0;
+const MAX_ROW_HEIGHT: f32 = 120.0;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 3.0;
+/// Factor applied per zoom step.
+const ZOOM_STEP: f32 = 1.1;
+/// Fewest characters a cell is cut to, leaving room for text either side of the ellipsis.
+const MIN_CELL_CHARS: usize = 4;
+
+actions!(parquet_viewer, [ZoomIn, ZoomOut, ResetZoom]);
+
+/// Font and row geometry of the data table, stored under `table` in `settings.json`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableStyle {
+    pub font_family: String,
+    /// Font size in pixels.
+    pub font_size: f32,
+    /// Row height in pixels.
+    pub row_height: f32,
+    /// Longest a cell's text gets, in characters with wide ones counting two,
+    /// before it is cut with an ellipsis; the full value shows on hover.
+    pub max_cell_chars: usize,
+    /// Whether long values lose their end or their middle.
+    pub truncation: Truncation,
+    /// Temporary scale from Ctrl+= / Ctrl+-, applied on top of the saved sizes.
+    #[serde(skip)]
+    pub zoom: f32,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self {
+            font_family: "monospace".to_string(),
+            font_size: 14.0,
+            row_height: 28.0,
+            max_cell_chars: 60,
+            truncation: Truncation::End,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Global for TableStyle {}
+
+impl TableStyle {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// Font size after zooming.
+    pub fn scaled_font_size(&self) -> f32 {
+        self.font_size * self.zoom
+    }
+
+    /// Row height after zooming; rows per viewport are computed from this.
+    pub fn scaled_row_height(&self) -> f32 {
+        self.row_height * self.zoom
+    }
+
+    /// Keep hand-edited values within a usable range.
+    pub fn clamped(mut self) -> Self {
+        if !self.font_size.is_finite() {
+            self.font_size = Self::default().font_size;
+        }
+        if !self.row_height.is_finite() {
+            self.row_height = Self::default().row_height;
+        }
+        if self.font_family.trim().is_empty() {
+            self.font_family = Self::default().font_family;
+        }
+
+        if !self.zoom.is_finite() || self.zoom <= 0.0 {
+            self.zoom = 1.0;
+        }
+
+        self.max_cell_chars = self.max_cell_chars.max(MIN_CELL_CHARS);
+        self.zoom = self.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self.font_size = self.font_size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+        self.row_height = self
+            .row_height
+            .clamp(self.font_size + ROW_PADDING, MAX_ROW_HEIGHT);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SetTableFont {
+    pub family: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SetTableFontSize {
+    pub size: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SetRowHeight {
+    pub height: f32,
+}
+
+/// Cut long values at their end or in their middle.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SetTruncation {
+    pub truncation: Truncation,
+}
+
+/// Font families offered in the View menu; any other family can be set in `settings.json`.
+pub const FONT_CHOICES: [(&str, &str); 2] =
+    [("Monospace", "monospace"), ("System", ".SystemUIFont")];
+pub const FONT_SIZE_CHOICES: [f32; 5] = [11.0, 12.0, 14.0, 16.0, 20.0];
+pub const ROW_HEIGHT_CHOICES: [(&str, f32); 4] = [
+    ("Compact", 20.0),
+    ("Normal", 28.0),
+    ("Comfortable", 36.0),
+    ("Large", 48.0),
+];
+
+/// Install the saved table style and the actions that change it.
+pub fn init(cx: &mut App) {
+    cx.set_global(Settings::load().table.clamped());
+
+    cx.on_action(|action: &SetTableFont, cx| {
+        update(cx, |style| style.font_family = action.family.clone())
+    });
+    cx.on_action(|action: &SetTableFontSize, cx| {
+        update(cx, |style| {
+            // Grow the rows along with the font so that text isn't clipped.
+            style.row_height += action.size - style.font_size;
+            style.font_size = action.size;
+        })
+    });
+    cx.on_action(|action: &SetRowHeight, cx| update(cx, |style| style.row_height = action.height));
+    cx.on_action(|action: &SetTruncation, cx| {
+        update(cx, |style| style.truncation = action.truncation)
+    });
+
+    // Zooming is per session, so it isn't written to the settings file.
+    cx.on_action(|_: &ZoomIn, cx| zoom(cx, |zoom| zoom * ZOOM_STEP));
+    cx.on_action(|_: &ZoomOut, cx| zoom(cx, |zoom| zoom / ZOOM_STEP));
+    cx.on_action(|_: &ResetZoom, cx| zoom(cx, |_| 1.0));
+}
+
+fn zoom(cx: &mut App, change: impl FnOnce(f32) -> f32) {
+    let mut style = TableStyle::global(cx).clone();
+    style.zoom = change(style.zoom);
+    cx.set_global(style.clamped());
+}
+
+fn update(cx: &mut App, change: impl FnOnce(&mut TableStyle)) {
+    let mut style = TableStyle::global(cx).clone();
+    change(&mut style);
+    let style = style.clamped();
+
+    Settings::update(|settings| settings.table = style.clone());
+    cx.set_global(style);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamping_keeps_rows_taller_than_the_font() {
+        let style = TableStyle {
+            font_family: " ".to_string(),
+            font_size: 90.0,
+            row_height: 10.0,
+            zoom: 1.0,
+            ..TableStyle::default()
+        }
+        .clamped();
+
+        assert_eq!(style.font_family, "monospace");
+        assert_eq!(style.font_size, MAX_FONT_SIZE);
+        assert_eq!(style.row_height, MAX_FONT_SIZE + ROW_PADDING);
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"table":{"row_height":22}}"#).expect("settings should parse");
+        assert_eq!(settings.table.row_height, 22.0);
+        assert_eq!(settings.table.font_size, TableStyle::default().font_size);
+        assert_eq!(settings.table.zoom, 1.0);
+    }
+
+    #[test]
+    fn zoom_scales_sizes_within_limits() {
+        let mut style = TableStyle {
+            zoom: 2.0,
+            ..TableStyle::default()
+        };
+        assert_eq!(style.scaled_font_size(), 28.0);
+        assert_eq!(style.scaled_row_height(), 56.0);
+
+        style.zoom = 100.0;
+        assert_eq!(style.clamped().zoom, MAX_ZOOM);
+    }
+}
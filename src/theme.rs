@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use gpui::{App, SharedString};
+use gpui_component::{Theme, ThemeMode, ThemeRegistry};
+
+use crate::settings::Settings;
+use crate::storage::config_dir;
+use crate::workspace::{SelectTheme, ToggleDarkMode};
+
+/// Which theme to show, as given by `--theme` or the settings file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThemeChoice {
+    /// Follow the system's light or dark appearance.
+    System,
+    Mode(ThemeMode),
+    /// A theme loaded from the themes directory, by name.
+    Named(String),
+}
+
+impl ThemeChoice {
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "system" => Self::System,
+            "light" => Self::Mode(ThemeMode::Light),
+            "dark" => Self::Mode(ThemeMode::Dark),
+            _ => Self::Named(name.trim().to_string()),
+        }
+    }
+}
+
+/// Directory scanned for custom theme files, in gpui-component's theme set JSON format.
+pub fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+/// Apply the theme from the command line, falling back to the saved setting,
+/// and start watching the themes directory for custom themes.
+pub fn init(cli_theme: Option<String>, cx: &mut App) {
+    let name = cli_theme.or_else(|| Settings::load().theme);
+    let choice = name
+        .as_deref()
+        .map_or(ThemeChoice::System, ThemeChoice::parse);
+
+    // Named themes only exist once the themes directory has been read.
+    if !matches!(choice, ThemeChoice::Named(_)) {
+        apply(&choice, cx);
+    }
+
+    if let Some(dir) = themes_dir() {
+        let result = ThemeRegistry::watch_dir(dir, cx, move |cx| {
+            if matches!(choice, ThemeChoice::Named(_)) {
+                apply(&choice, cx);
+            }
+        });
+        if let Err(error) = result {
+            tracing::warn!(%error, "failed to load custom themes");
+        }
+    }
+
+    cx.on_action(toggle_dark_mode);
+    cx.on_action(select_theme);
+}
+
+/// Switch to `choice`, returning `false` if it names a theme that isn't loaded.
+pub fn apply(choice: &ThemeChoice, cx: &mut App) -> bool {
+    match choice {
+        ThemeChoice::System => Theme::sync_system_appearance(None, cx),
+        ThemeChoice::Mode(mode) => Theme::change(*mode, None, cx),
+        ThemeChoice::Named(name) => {
+            let Some(config) = ThemeRegistry::global(cx)
+                .themes()
+                .get(&SharedString::from(name.clone()))
+                .cloned()
+            else {
+                tracing::warn!(theme = %name, "unknown theme");
+                return false;
+            };
+
+            let theme = Theme::global_mut(cx);
+            if config.mode.is_dark() {
+                theme.dark_theme = config.clone();
+            } else {
+                theme.light_theme = config.clone();
+            }
+            Theme::change(config.mode, None, cx);
+        }
+    }
+
+    cx.refresh_windows();
+    true
+}
+
+/// Names of every loaded theme, built-in ones first.
+pub fn theme_names(cx: &App) -> Vec<SharedString> {
+    ThemeRegistry::global(cx)
+        .sorted_themes()
+        .into_iter()
+        .map(|theme| theme.name.clone())
+        .collect()
+}
+
+fn toggle_dark_mode(_: &ToggleDarkMode, cx: &mut App) {
+    let mode = if Theme::global(cx).is_dark() {
+        ThemeMode::Light
+    } else {
+        ThemeMode::Dark
+    };
+    apply(&ThemeChoice::Mode(mode), cx);
+    Settings::update(|settings| settings.theme = Some(mode.name().to_string()));
+}
+
+fn select_theme(action: &SelectTheme, cx: &mut App) {
+    if apply(&ThemeChoice::parse(&action.name), cx) {
+        Settings::update(|settings| settings.theme = Some(action.name.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_names_parse_case_insensitively() {
+        assert_eq!(ThemeChoice::parse("System"), ThemeChoice::System);
+        assert_eq!(
+            ThemeChoice::parse(" DARK "),
+            ThemeChoice::Mode(ThemeMode::Dark)
+        );
+        assert_eq!(
+            ThemeChoice::parse("light"),
+            ThemeChoice::Mode(ThemeMode::Light)
+        );
+        assert_eq!(
+            ThemeChoice::parse("Solarized Light"),
+            ThemeChoice::Named("Solarized Light".to_string())
+        );
+    }
+}
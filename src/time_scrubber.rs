@@ -0,0 +1,207 @@
+use gpui::{actions, div, prelude::*, px, relative, MouseButton, MouseMoveEvent};
+use gpui_component::{ActiveTheme, WindowExt};
+use parquet_viewer_core::timeindex::{row_near, TimeIndex};
+
+use crate::{row_groups, PreviewView};
+
+actions!(parquet_viewer, [ToggleTimeScrubber]);
+
+/// Slices of the time range the scrubber shows, and jumps between.
+pub const SCRUBBER_BUCKETS: usize = 80;
+
+/// Height of the density bars.
+const SCRUBBER_HEIGHT: f32 = 28.0;
+
+/// Width of the earliest and latest time labels.
+const SCRUBBER_LABEL_WIDTH: f32 = 180.0;
+
+/// Timeline strip for files with a timestamp column: their time range, how many
+/// rows fall in each slice of it, and jumps to the rows of a clicked time.
+pub struct TimeScrubber {
+    pub index: TimeIndex,
+    /// Rows per slice of the time range, see [`TimeIndex::density`].
+    pub density: Vec<usize>,
+    pub first: String,
+    pub last: String,
+    pub shown: bool,
+    /// Slice jumped to last, so that dragging within it doesn't read it again.
+    pub bucket: Option<usize>,
+}
+
+impl TimeScrubber {
+    pub fn new(index: TimeIndex) -> TimeScrubber {
+        let text = |time| {
+            index.format(time).unwrap_or_else(|error| {
+                tracing::warn!(%error, "failed to format a time");
+                time.to_string()
+            })
+        };
+        TimeScrubber {
+            density: index.density(SCRUBBER_BUCKETS),
+            first: text(index.min()),
+            last: text(index.max()),
+            index,
+            shown: true,
+            bucket: None,
+        }
+    }
+
+    /// Earliest time of slice `bucket`.
+    pub fn bucket_time(&self, bucket: usize) -> i64 {
+        self.index
+            .time_at(bucket as f64 / self.density.len().max(1) as f64)
+    }
+}
+
+pub fn render_time_scrubber(
+    scrubber: &TimeScrubber,
+    cx: &gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let theme = cx.theme();
+    let busiest = scrubber.density.iter().copied().max().unwrap_or(0).max(1);
+
+    let bars = scrubber.density.iter().enumerate().map(|(bucket, &rows)| {
+        let click = cx.listener(
+            move |view: &mut PreviewView, _: &gpui::MouseDownEvent, window, cx| {
+                view.scrub_to(bucket, window, cx);
+            },
+        );
+        let drag = cx.listener(
+            move |view: &mut PreviewView, event: &MouseMoveEvent, window, cx| {
+                if event.pressed_button == Some(MouseButton::Left) {
+                    view.scrub_to(bucket, window, cx);
+                }
+            },
+        );
+        let current = scrubber.bucket == Some(bucket);
+        div()
+            .id(("time-bucket", bucket))
+            .flex_1()
+            .h_full()
+            .flex()
+            .flex_col()
+            .justify_end()
+            .cursor_pointer()
+            .hover(|this| this.bg(theme.table_hover))
+            .when(current, |this| this.bg(theme.table_active))
+            .on_mouse_down(MouseButton::Left, click)
+            .on_mouse_move(drag)
+            .child(
+                div()
+                    .w_full()
+                    .h(relative(rows as f32 / busiest as f32))
+                    .bg(theme.primary.opacity(0.6)),
+            )
+    });
+
+    div()
+        .flex()
+        .flex_row()
+        .items_center()
+        .gap_2()
+        .p_2()
+        .w_full()
+        .border_1()
+        .border_color(theme.border)
+        .rounded(theme.radius)
+        .text_xs()
+        .child(
+            div()
+                .w(px(SCRUBBER_LABEL_WIDTH))
+                .flex_none()
+                .flex()
+                .flex_col()
+                .child(
+                    div()
+                        .text_color(theme.muted_foreground)
+                        .child(scrubber.index.column.clone()),
+                )
+                .child(div().truncate().child(scrubber.first.clone())),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .flex_1()
+                .items_end()
+                .h(px(SCRUBBER_HEIGHT))
+                .border_b_1()
+                .border_color(theme.border)
+                .children(bars),
+        )
+        .child(
+            div()
+                .w(px(SCRUBBER_LABEL_WIDTH))
+                .flex_none()
+                .text_right()
+                .truncate()
+                .child(scrubber.last.clone()),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn toggle_time_scrubber(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        if let Some(scrubber) = &mut self.time_scrubber {
+            scrubber.shown = !scrubber.shown;
+            cx.notify();
+        }
+    }
+
+    /// Scroll to the row nearest the start of slice `bucket` of the timeline,
+    /// found in the background. A drag moving on to another slice before the
+    /// row is found drops it.
+    fn scrub_to(
+        &mut self,
+        bucket: usize,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let Some(scrubber) = &mut self.time_scrubber else {
+            return;
+        };
+        if scrubber.bucket == Some(bucket) {
+            return;
+        }
+        scrubber.bucket = Some(bucket);
+        cx.notify();
+
+        let task = cx.background_spawn({
+            let path = self.preview.path.clone();
+            let bytes_read = self.preview.bytes_read.clone();
+            let index = scrubber.index.clone();
+            let time = scrubber.bucket_time(bucket);
+            async move { row_near(&path, &index, time, &bytes_read) }
+        });
+        cx.spawn_in(window, async move |view, cx| {
+            let row = task.await;
+            view.update_in(cx, |view, window, cx| {
+                let current = view
+                    .time_scrubber
+                    .as_ref()
+                    .and_then(|scrubber| scrubber.bucket);
+                if current != Some(bucket) {
+                    return;
+                }
+                let row = match row {
+                    Ok(Some(row)) => row,
+                    Ok(None) => return,
+                    Err(error) => {
+                        tracing::error!(%error, "failed to find the rows of a time");
+                        window
+                            .push_notification(format!("The rows could not be read: {error}"), cx);
+                        return;
+                    }
+                };
+                match row_groups::first_display_row(&(row..row + 1), view.row_order.as_deref()) {
+                    Some(row) => view.reveal_row(row, cx),
+                    None => window.push_notification(
+                        format!("Row {} is hidden by the current filter", row + 1),
+                        cx,
+                    ),
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+}
@@ -0,0 +1,204 @@
+use gpui::{actions, div, prelude::*, px, MouseButton, SharedString};
+use gpui_component::menu::ContextMenuExt;
+use gpui_component::{ActiveTheme, StyledExt};
+
+use crate::context_menu::ContextTarget;
+use crate::highlight_rules::{matching_color, rule_background};
+use crate::table_style::TableStyle;
+use crate::{table_context_menu, PreviewView, MIN_TABLE_HEIGHT};
+
+actions!(parquet_viewer, [ToggleTranspose]);
+
+/// Width of the column name cells that head each transposed row.
+const NAME_WIDTH: f32 = 200.0;
+
+/// Narrowest a transposed value cell gets.
+const MIN_VALUE_WIDTH: f32 = 120.0;
+
+/// The table with rows and columns swapped: each displayed column becomes a row
+/// and each row in the viewport a column, for files with few rows but hundreds
+/// of columns. Columns scroll vertically; horizontal scrolling moves through rows.
+pub fn render_transposed_table(
+    view: &PreviewView,
+    cx: &mut gpui::Context<PreviewView>,
+) -> impl gpui::IntoElement {
+    let columns = view.displayed_columns();
+    let build_context_menu = table_context_menu(view, cx);
+    let style = TableStyle::global(cx).clone();
+    let row_height = style.scaled_row_height();
+    let theme = cx.theme();
+    let rows: Vec<usize> = (0..view.visible_rows.len())
+        .map(|index| view.visible_range.start + index)
+        .collect();
+
+    let header = div()
+        .flex()
+        .flex_row()
+        .w_full()
+        .bg(theme.table_head)
+        .text_color(theme.table_head_foreground)
+        .border_b_1()
+        .border_color(theme.table_row_border)
+        .font_medium()
+        .child(
+            div()
+                .w(px(NAME_WIDTH))
+                .flex_none()
+                .px_2()
+                .py_1()
+                .border_r_1()
+                .border_color(theme.table_row_border)
+                .child("Column"),
+        )
+        .children(rows.iter().map(|&row| {
+            div()
+                .flex_1()
+                .min_w(px(MIN_VALUE_WIDTH))
+                .px_2()
+                .py_1()
+                .border_r_1()
+                .border_color(theme.table_row_border)
+                .child(format!("Row {}", view.file_row(row) + 1))
+        }));
+
+    let body = columns.iter().enumerate().map(|(line, &column)| {
+        let header_click = cx.listener(
+            move |view: &mut PreviewView, _: &gpui::MouseDownEvent, window, _cx| {
+                window.focus(&view.focus_handle);
+                view.context_target = Some(ContextTarget::Header { column });
+            },
+        );
+        div()
+            .flex()
+            .flex_row()
+            .w_full()
+            .h(px(row_height))
+            .border_b_1()
+            .border_color(theme.table_row_border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .w(px(NAME_WIDTH))
+                    .h_full()
+                    .flex_none()
+                    .px_2()
+                    .overflow_hidden()
+                    .border_r_1()
+                    .border_color(theme.table_row_border)
+                    .bg(theme.table_head)
+                    .text_color(theme.table_head_foreground)
+                    .font_medium()
+                    .on_mouse_down(MouseButton::Right, header_click)
+                    .child(view.preview.columns[column].clone()),
+            )
+            .children(view.visible_rows.iter().zip(&rows).map(|(values, &row)| {
+                let value = values[column].clone();
+                let is_selected = view.selected_cell == Some((row, column));
+                let click_handler = cx.listener(
+                    move |view: &mut PreviewView, event: &gpui::MouseDownEvent, window, cx| {
+                        window.focus(&view.focus_handle);
+                        view.click_cell(row, column, event.modifiers, cx);
+                    },
+                );
+                let context_value = value.clone();
+                let right_click_handler = cx.listener(
+                    move |view: &mut PreviewView, _: &gpui::MouseDownEvent, window, _cx| {
+                        window.focus(&view.focus_handle);
+                        view.context_target = Some(ContextTarget::Cell {
+                            row,
+                            column,
+                            value: context_value.to_string(),
+                        });
+                    },
+                );
+                let rule_color =
+                    matching_color(&view.highlight_rules, &view.preview.columns[column], &value);
+                let background = if is_selected {
+                    theme.table_active
+                } else if view.selected_rows.contains(row) {
+                    theme.table_active.opacity(0.5)
+                } else if let Some(color) = rule_color {
+                    rule_background(color, theme)
+                } else if let Some(heat) =
+                    view.heat_maps
+                        .background(&view.preview.columns[column], &value, theme)
+                {
+                    heat
+                } else if line % 2 == 0 {
+                    theme.table
+                } else {
+                    theme.table_even
+                };
+
+                div()
+                    .flex()
+                    .items_center()
+                    .px_2()
+                    .h_full()
+                    .flex_1()
+                    .min_w(px(MIN_VALUE_WIDTH))
+                    .overflow_hidden()
+                    .border_r_1()
+                    .border_color(if is_selected {
+                        theme.table_active_border
+                    } else {
+                        theme.table_row_border
+                    })
+                    .bg(background)
+                    .hover(|this| this.bg(theme.table_hover))
+                    .cursor_pointer()
+                    .on_mouse_down(MouseButton::Left, click_handler)
+                    .on_mouse_down(MouseButton::Right, right_click_handler)
+                    .child(SharedString::from(value))
+            }))
+    });
+
+    let scroll_handler = cx.listener(
+        |view: &mut PreviewView, event: &gpui::ScrollWheelEvent, _window, cx| {
+            let delta = event.delta.pixel_delta(px(MIN_VALUE_WIDTH));
+            let rows_delta = -(f32::from(delta.x) / MIN_VALUE_WIDTH).round() as isize;
+            if rows_delta != 0 {
+                view.scroll_view(rows_delta, cx);
+            }
+        },
+    );
+
+    div()
+        .border_1()
+        .border_color(theme.table_row_border)
+        .rounded(theme.radius)
+        .overflow_hidden()
+        .w_full()
+        .flex_1()
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .w_full()
+                .h_full()
+                .text_size(px(style.scaled_font_size()))
+                .font_family(style.font_family)
+                .on_scroll_wheel(scroll_handler)
+                .child(header)
+                .child(
+                    div()
+                        .id("transposed-rows")
+                        .h(view.table_height)
+                        .min_h(px(MIN_TABLE_HEIGHT))
+                        .w_full()
+                        .overflow_y_scroll()
+                        .flex()
+                        .flex_col()
+                        .children(body),
+                )
+                .context_menu(build_context_menu),
+        )
+}
+
+impl PreviewView {
+    pub(crate) fn toggle_transpose(&mut self, cx: &mut gpui::Context<PreviewView>) {
+        self.transposed = !self.transposed;
+        cx.notify();
+    }
+}
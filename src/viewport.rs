@@ -0,0 +1,381 @@
+use std::ops::Range;
+use std::time::Instant;
+
+use futures::StreamExt;
+use parquet_viewer_core::cancel::CancellationToken;
+use parquet_viewer_core::prefetch::prefetch_range;
+use parquet_viewer_core::preview::{
+    window_part, CellError, RowWindow, UnreadableRange, WindowPart,
+};
+use parquet_viewer_core::selection::runs;
+use parquet_viewer_core::source::spawn_reads;
+use parquet_viewer_core::ViewerError;
+
+use crate::cancel::CancellableTask;
+use crate::go_to_row::{parse_row_target, resolve_column, StartPosition};
+use crate::table_style::TableStyle;
+use crate::{rows_per_view, table_height_for_window, PreviewView};
+
+impl PreviewView {
+    /// Number of rows after filtering.
+    pub(crate) fn display_row_count(&self) -> usize {
+        self.row_order
+            .as_ref()
+            .map_or(self.preview.row_count, Vec::len)
+    }
+
+    pub(crate) fn file_row(&self, display_row: usize) -> usize {
+        self.row_order
+            .as_ref()
+            .map_or(display_row, |order| order[display_row])
+    }
+
+    /// Runs of file rows behind `range` of the display, in display order.
+    pub(crate) fn file_runs(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let end = range.end.min(self.display_row_count());
+        if range.start >= end {
+            return Vec::new();
+        }
+
+        match &self.row_order {
+            Some(order) => runs(order[range.start..end].iter().copied()),
+            None => std::iter::once(range.start..end).collect(),
+        }
+    }
+
+    /// Whether the rows of `run` are decoded already, in the head or read ahead.
+    fn is_loaded(&self, run: &Range<usize>) -> bool {
+        run.end <= self.preview.rows.len() || self.row_cache.contains(run.clone())
+    }
+
+    /// Decode the rows shown at `range` of the display, following the filter and sort order.
+    ///
+    /// Rows read ahead are only turned into text here; the cells among them
+    /// that fail or hold invalid UTF-8 are recorded as they are.
+    pub(crate) fn rows_for_display(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<RowWindow, ViewerError> {
+        let mut window = RowWindow::default();
+        for run in self.file_runs(range) {
+            if let Some(part) = self.preview.head_rows(run.clone()) {
+                window.rows.extend(part.rows);
+                window.unreadable.extend(part.unreadable);
+            } else if let Some(part) = self.row_cache.get(run.clone()) {
+                window.rows.extend(part.rows);
+                self.record_cell_errors(part.cell_errors);
+                self.lossy_cells.extend(part.lossy_cells);
+            } else {
+                let part = self.preview.rows_for_range(run)?;
+                window.rows.extend(part.rows);
+                window.unreadable.extend(part.unreadable);
+                window.cell_errors.extend(part.cell_errors);
+                window.lossy_cells.extend(part.lossy_cells);
+            }
+        }
+
+        Ok(window)
+    }
+
+    /// Scroll to and select where `--goto` or `--select` points. Rows count in
+    /// the file, so filters and a sort restored from the last session are cleared.
+    pub(crate) fn open_at(
+        &mut self,
+        start: &StartPosition,
+        cx: &mut gpui::Context<PreviewView>,
+    ) -> Result<(), String> {
+        let column = start
+            .column
+            .as_deref()
+            .map(|column| resolve_column(&self.preview.columns, column))
+            .transpose()?;
+        if self.row_order.is_some() {
+            self.apply_row_order(Vec::new(), None, None);
+            self.refresh_summary(cx);
+        }
+        if self.display_row_count() == 0 {
+            return Ok(());
+        }
+
+        let row = parse_row_target(&start.row, self.display_row_count())?;
+        if let Some(column) = column {
+            self.hidden_columns.remove(&column);
+            if self.project_displayed_columns() {
+                self.reload_rows(cx);
+            }
+            self.selected_cell = Some((row, column));
+        }
+        self.reveal_row(row, cx);
+        Ok(())
+    }
+
+    /// Select display row `row`, scrolling it to the top unless it's already shown.
+    pub(crate) fn reveal_row(&mut self, row: usize, cx: &mut gpui::Context<PreviewView>) {
+        let column = self
+            .selected_cell
+            .map(|(_, column)| column)
+            .or_else(|| self.displayed_columns().first().copied())
+            .unwrap_or(0);
+        self.selected_rows.select(row);
+        self.selected_cell = Some((row, column));
+
+        if self.shown_range().contains(&row) {
+            cx.notify();
+        } else {
+            self.load_visible_rows(row, cx);
+        }
+    }
+
+    /// Display rows drawn in the table: the loaded rows that fit its height.
+    pub(crate) fn shown_range(&self) -> Range<usize> {
+        let shown = self.visible_rows.len().min(self.fitted_rows);
+        self.visible_range.start..self.visible_range.start + shown
+    }
+
+    pub(crate) fn load_visible_rows(&mut self, start: usize, cx: &mut gpui::Context<PreviewView>) {
+        let row_count = self.display_row_count();
+        if row_count == 0 {
+            self.visible_rows.clear();
+            self.visible_range = 0..0;
+            cx.notify();
+            return;
+        }
+
+        let start = start.min(row_count.saturating_sub(1));
+        let end = (start + self.rows_per_view).min(row_count);
+        let started = Instant::now();
+
+        // The head of the file and the rows read ahead are cached; anything else
+        // streams in from the I/O runtime so that the UI thread never waits on the disk.
+        let file_runs = self.file_runs(start..end);
+        if file_runs.iter().all(|run| self.is_loaded(run)) {
+            let window = self.rows_for_display(start..end);
+            self.loading_start = None;
+            self._load_task = None;
+            self.show_window(start, window, started, cx);
+            self.prefetch_around(start, cx);
+            return;
+        }
+
+        // Under a tight memory limit, a chunk must not evict another of the
+        // same viewport.
+        self.row_cache.protect(file_runs.clone());
+        let chunks = file_runs
+            .into_iter()
+            .filter(|run| !self.is_loaded(run))
+            .flat_map(|run| self.preview.chunks(run))
+            .collect();
+        let token = CancellationToken::default();
+        let receiver = spawn_reads(self.preview.source.clone(), chunks, token.clone());
+
+        self.loading_start = Some(start);
+        let task = cx.spawn({
+            let token = token.clone();
+            async move |view, cx| {
+                let parts: Vec<_> = receiver
+                    .map(|(chunk, result)| (chunk.rows.start, window_part(chunk, result)))
+                    .collect()
+                    .await;
+                // A superseded load stops partway; its rows must not reach the view.
+                if token.is_cancelled() {
+                    return;
+                }
+
+                view.update(cx, |view, cx| {
+                    view.loading_start = None;
+                    let mut loaded = Ok(());
+                    for (first_row, part) in parts {
+                        loaded = loaded.and_then(|()| view.cache_part(first_row, part?));
+                    }
+                    let window = loaded.and_then(|()| view.rows_for_display(start..end));
+                    view.show_window(start, window, started, cx);
+                    view.prefetch_around(start, cx);
+                })
+                .ok();
+            }
+        });
+        self._load_task = Some(CancellableTask::new(token, task));
+    }
+
+    /// Keep the rows of a streamed chunk starting at file row `first_row`, as
+    /// decoded; they are turned into text when shown.
+    fn cache_part(&mut self, first_row: usize, part: WindowPart) -> Result<(), ViewerError> {
+        match part {
+            WindowPart::Batches(batches) => {
+                let batches = self.preview.display_batches(&batches);
+                self.row_cache.insert(first_row, batches);
+            }
+            WindowPart::Unreadable(range) => {
+                self.row_cache
+                    .insert_unreadable(range.rows.clone(), self.preview.columns.len());
+                self.record_unreadable(range);
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the screens around the viewport at `start` in the background, rows
+    /// below it first, so that continued scrolling finds them decoded.
+    fn prefetch_around(&mut self, start: usize, cx: &mut gpui::Context<PreviewView>) {
+        let range = prefetch_range(
+            start,
+            self.rows_per_view,
+            self.prefetch_screens,
+            self.display_row_count(),
+        );
+        if self.prefetch_range.as_ref() == Some(&range) {
+            return;
+        }
+
+        let mut runs = self.file_runs(start..range.end);
+        runs.extend(self.file_runs(range.start..start));
+        self.row_cache.retain(&runs);
+        self.row_cache.protect(runs.clone());
+        self.prefetch_range = Some(range);
+
+        let chunks: Vec<_> = runs
+            .into_iter()
+            .filter(|run| !self.is_loaded(run))
+            .flat_map(|run| self.preview.chunks(run))
+            .collect();
+        if chunks.is_empty() {
+            self._prefetch_task = None;
+            return;
+        }
+
+        let token = CancellationToken::default();
+        let mut receiver = spawn_reads(self.preview.source.clone(), chunks, token.clone());
+        let task = cx.spawn(async move |view, cx| {
+            while let Some((chunk, result)) = receiver.next().await {
+                let first_row = chunk.rows.start;
+                let cached = view.update(cx, |view, _| {
+                    window_part(chunk, result).and_then(|part| view.cache_part(first_row, part))
+                });
+                match cached {
+                    Ok(Ok(())) => {}
+                    Ok(Err(error)) => {
+                        tracing::warn!(%error, "stopping prefetch");
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+        self._prefetch_task = Some(CancellableTask::new(token, task));
+    }
+
+    fn show_window(
+        &mut self,
+        start: usize,
+        window: Result<RowWindow, ViewerError>,
+        started: Instant,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.last_load = Some(started.elapsed());
+
+        match window {
+            Ok(window) => {
+                self.visible_range = start..(start + window.rows.len());
+                self.visible_rows = window.rows;
+                for range in window.unreadable {
+                    self.record_unreadable(range);
+                }
+                self.record_cell_errors(window.cell_errors);
+                self.lossy_cells.extend(window.lossy_cells);
+                cx.notify();
+            }
+            Err(error) => {
+                tracing::error!(?error, "failed to load rows for viewport");
+            }
+        }
+    }
+
+    /// Remember an unreadable range, widening an existing entry for the same row group.
+    fn record_unreadable(&mut self, range: UnreadableRange) {
+        match self
+            .unreadable
+            .iter_mut()
+            .find(|known| known.row_group == range.row_group)
+        {
+            Some(known) => {
+                known.rows =
+                    known.rows.start.min(range.rows.start)..known.rows.end.max(range.rows.end);
+            }
+            None => {
+                self.unreadable.push(range);
+                self.unreadable.sort_by_key(|range| range.row_group);
+            }
+        }
+    }
+
+    pub(crate) fn record_cell_errors(&mut self, errors: Vec<CellError>) {
+        self.cell_errors.extend(
+            errors
+                .into_iter()
+                .map(|error| ((error.row, error.column), error.message)),
+        );
+    }
+
+    pub(crate) fn is_unreadable(&self, row: usize) -> bool {
+        self.unreadable
+            .iter()
+            .any(|range| range.rows.contains(&row))
+    }
+
+    /// Fit the rows to half the window's height while `stacked` in a split.
+    pub(crate) fn set_stacked(
+        &mut self,
+        stacked: bool,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        self.stacked = stacked;
+        self.update_rows_for_resize(window, cx);
+    }
+
+    pub(crate) fn update_rows_for_resize(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let new_table_height = table_height_for_window(window, self.stacked);
+        let new_rows_per_view =
+            rows_per_view(new_table_height, TableStyle::global(cx).scaled_row_height());
+
+        let rows_changed = new_rows_per_view != self.rows_per_view;
+        self.table_height = new_table_height;
+        self.rows_per_view = new_rows_per_view;
+
+        if rows_changed {
+            self.load_visible_rows(self.visible_range.start, cx);
+        } else {
+            cx.notify();
+        }
+    }
+
+    pub(crate) fn scroll_view(&mut self, delta_rows: isize, cx: &mut gpui::Context<PreviewView>) {
+        let row_count = self.display_row_count();
+        if row_count == 0 {
+            return;
+        }
+
+        // Tall wrapped rows fit fewer to a screen, so the last screen starts later.
+        let shown = self.rows_per_view.min(self.shown_range().len().max(1));
+        let max_start = row_count.saturating_sub(shown);
+
+        // Keep scrolling from where a load in flight will land.
+        let current_start = self.loading_start.unwrap_or(self.visible_range.start) as isize;
+        let mut target_start = current_start + delta_rows;
+        if target_start < 0 {
+            target_start = 0;
+        }
+
+        if target_start as usize > max_start {
+            target_start = max_start as isize;
+        }
+
+        if target_start != current_start {
+            self.load_visible_rows(target_start as usize, cx);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+
+use gpui::{
+    point, px, size, App, Bounds, DisplayId, Pixels, PlatformDisplay, Window, WindowBounds,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_json, save_json};
+
+/// File name of the saved window geometry inside the config directory.
+const WINDOW_GEOMETRY_NAME: &str = "window.json";
+
+/// Size of the window when none is saved or given.
+const DEFAULT_WINDOW_SIZE: WindowSize = WindowSize {
+    width: 900,
+    height: 700,
+};
+
+/// Window size in logical pixels, as given with `--window-size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Parse `--window-size`, e.g. `1280x800`.
+pub fn parse_window_size(text: &str) -> Result<WindowSize, String> {
+    let (width, height) = text
+        .trim()
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, e.g. 1280x800, not `{text}`"))?;
+    let parse = |value: &str| match value.trim().parse::<u32>() {
+        Ok(pixels) if pixels > 0 => Ok(pixels),
+        _ => Err(format!("`{value}` is not a size in pixels")),
+    };
+    Ok(WindowSize {
+        width: parse(width)?,
+        height: parse(height)?,
+    })
+}
+
+/// Where the window was on one display, and whether it was maximized; the
+/// bounds are those it returns to when no longer maximized.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedWindow {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+}
+
+impl SavedWindow {
+    fn new(bounds: WindowBounds) -> Self {
+        let (restore, maximized) = match bounds {
+            WindowBounds::Windowed(bounds) => (bounds, false),
+            WindowBounds::Maximized(bounds) | WindowBounds::Fullscreen(bounds) => (bounds, true),
+        };
+        Self {
+            x: restore.origin.x.into(),
+            y: restore.origin.y.into(),
+            width: restore.size.width.into(),
+            height: restore.size.height.into(),
+            maximized,
+        }
+    }
+
+    fn bounds(&self) -> Bounds<Pixels> {
+        Bounds::new(
+            point(px(self.x), px(self.y)),
+            size(px(self.width), px(self.height)),
+        )
+    }
+}
+
+/// Window geometry saved per display, keyed by the display's stable id, so that
+/// moving between a laptop screen and a monitor restores each one's layout.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowGeometry {
+    displays: BTreeMap<String, SavedWindow>,
+    /// Display the window was on when last saved.
+    last_display: Option<String>,
+}
+
+impl WindowGeometry {
+    pub fn load() -> Self {
+        load_json(WINDOW_GEOMETRY_NAME).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        save_json(WINDOW_GEOMETRY_NAME, self);
+    }
+
+    pub fn remember(&mut self, display: String, window: SavedWindow) {
+        self.displays.insert(display.clone(), window);
+        self.last_display = Some(display);
+    }
+
+    /// Saved geometry to restore among the `connected` displays: that of the
+    /// display the window was last on if it is connected, or else of the first
+    /// connected display with any.
+    fn restore<'a>(&self, connected: &[&'a str]) -> Option<(&'a str, SavedWindow)> {
+        let last = self
+            .last_display
+            .as_deref()
+            .and_then(|last| connected.iter().find(|display| **display == last));
+        last.into_iter()
+            .chain(connected)
+            .find_map(|display| Some((*display, *self.displays.get(*display)?)))
+    }
+}
+
+/// Stable name of `display` to save geometry under.
+fn display_key(display: &dyn PlatformDisplay) -> String {
+    display
+        .uuid()
+        .map(|uuid| uuid.to_string())
+        .unwrap_or_else(|_| format!("display-{}", u32::from(display.id())))
+}
+
+/// Bounds and display to open the window with: `size` if given, or else the
+/// geometry saved for a connected display as long as it is still on screen, or
+/// else the default size, centered. `maximized` maximizes it in any case.
+pub fn initial_window(
+    size: Option<WindowSize>,
+    maximized: bool,
+    cx: &App,
+) -> (WindowBounds, Option<DisplayId>) {
+    let displays = cx.displays();
+    let saved = size
+        .is_none()
+        .then(WindowGeometry::load)
+        .and_then(|geometry| {
+            let keys: Vec<String> = displays
+                .iter()
+                .map(|display| display_key(&**display))
+                .collect();
+            let connected: Vec<&str> = keys.iter().map(String::as_str).collect();
+            let (key, window) = geometry.restore(&connected)?;
+            let display = &displays[keys.iter().position(|candidate| candidate == key)?];
+            display
+                .bounds()
+                .intersects(&window.bounds())
+                .then(|| (window, display.id()))
+        });
+
+    let (bounds, display, was_maximized) = match saved {
+        Some((window, display)) => (window.bounds(), Some(display), window.maximized),
+        None => {
+            let size = size.unwrap_or(DEFAULT_WINDOW_SIZE);
+            let size = gpui::size(px(size.width as f32), px(size.height as f32));
+            (Bounds::centered(None, size, cx), None, false)
+        }
+    };
+    let bounds = if maximized || was_maximized {
+        WindowBounds::Maximized(bounds)
+    } else {
+        WindowBounds::Windowed(bounds)
+    };
+    (bounds, display)
+}
+
+/// The display `window` is on and its geometry, to save when it closes.
+pub fn current_window(window: &Window, cx: &App) -> Option<(String, SavedWindow)> {
+    let display = window.display(cx)?;
+    Some((
+        display_key(&*display),
+        SavedWindow::new(window.window_bounds()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_sizes_parse_as_width_by_height() {
+        assert_eq!(
+            parse_window_size("1280x800"),
+            Ok(WindowSize {
+                width: 1280,
+                height: 800
+            })
+        );
+        assert!(parse_window_size("1280").is_err());
+        assert!(parse_window_size("0x800").is_err());
+    }
+
+    #[test]
+    fn the_last_display_is_restored_while_connected() {
+        let window = |x| SavedWindow {
+            x,
+            y: 0.0,
+            width: 900.0,
+            height: 700.0,
+            maximized: false,
+        };
+        let mut geometry = WindowGeometry::default();
+        geometry.remember("laptop".to_string(), window(10.0));
+        geometry.remember("monitor".to_string(), window(20.0));
+
+        assert_eq!(
+            geometry.restore(&["laptop", "monitor"]),
+            Some(("monitor", window(20.0)))
+        );
+        assert_eq!(
+            geometry.restore(&["projector", "laptop"]),
+            Some(("laptop", window(10.0)))
+        );
+        assert_eq!(geometry.restore(&["projector"]), None);
+    }
+}
@@ -0,0 +1,87 @@
+use gpui::Action;
+use parquet_viewer_core::width::display_width;
+
+use crate::PreviewView;
+
+/// Wrap the long text of a column onto several lines, or stop wrapping it.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ToggleWordWrap {
+    pub column: usize,
+}
+
+/// Most lines a wrapped cell grows to; the rest of its value is cut with an
+/// ellipsis and shows on hover.
+pub const MAX_WRAP_LINES: usize = 8;
+
+/// Lines `text` takes when wrapped at `chars_per_line` columns, wide characters
+/// counting two, with its own line breaks; at most [`MAX_WRAP_LINES`].
+pub fn wrapped_lines(text: &str, chars_per_line: usize) -> usize {
+    let chars_per_line = chars_per_line.max(1);
+    text.split('\n')
+        .map(|line| display_width(line).div_ceil(chars_per_line).max(1))
+        .sum::<usize>()
+        .min(MAX_WRAP_LINES)
+}
+
+/// Height of a line of text as a share of the font size, gpui's default.
+const LINE_HEIGHT: f32 = 1.618;
+
+/// Height of a row whose tallest cell takes `lines` lines, `row_height` being
+/// that of a row of single lines.
+pub fn wrapped_row_height(row_height: f32, font_size: f32, lines: usize) -> f32 {
+    row_height + lines.saturating_sub(1) as f32 * font_size * LINE_HEIGHT
+}
+
+/// How many rows of the given heights, from the first, fit in `height`; the
+/// first row always counts, however tall.
+pub fn rows_fitting(heights: &[f32], height: f32) -> usize {
+    let mut used = 0.0;
+    let fitting = heights
+        .iter()
+        .take_while(|&&row| {
+            used += row;
+            used <= height
+        })
+        .count();
+    fitting.max(1).min(heights.len())
+}
+
+impl PreviewView {
+    /// Wrap the column's long values onto several lines, making their rows
+    /// taller, or cut them to one line again.
+    pub(crate) fn toggle_word_wrap(
+        &mut self,
+        action: &ToggleWordWrap,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<PreviewView>,
+    ) {
+        let column = self.preview.columns[action.column].clone();
+        if !self.wrapped_columns.remove(&column) {
+            self.wrapped_columns.insert(column);
+        }
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_text_takes_a_line_per_width_and_line_break() {
+        assert_eq!(wrapped_lines("", 10), 1);
+        assert_eq!(wrapped_lines("short", 10), 1);
+        assert_eq!(wrapped_lines(&"x".repeat(25), 10), 3);
+        assert_eq!(wrapped_lines(&"語".repeat(6), 10), 2);
+        assert_eq!(wrapped_lines("first\nsecond\n", 10), 3);
+        assert_eq!(wrapped_lines(&"x".repeat(1000), 10), MAX_WRAP_LINES);
+
+        assert_eq!(wrapped_row_height(28.0, 10.0, 1), 28.0);
+        assert!((wrapped_row_height(28.0, 10.0, 3) - 60.36).abs() < 0.01);
+
+        assert_eq!(rows_fitting(&[28.0, 56.0, 28.0, 28.0], 112.0), 3);
+        assert_eq!(rows_fitting(&[200.0, 28.0], 112.0), 1);
+        assert_eq!(rows_fitting(&[], 112.0), 0);
+    }
+}
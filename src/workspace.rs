@@ -0,0 +1,1337 @@
+use std::path::{Path, PathBuf};
+
+use gpui::{
+    actions, div, prelude::*, px, Action, App, Entity, FocusHandle, KeyBinding, Menu, MenuItem,
+    PathPromptOptions, SharedString,
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::{Input, InputState};
+use gpui_component::menu::AppMenuBar;
+use gpui_component::{ActiveTheme, Root, Sizable, WindowExt};
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet_viewer_core::join::{column_names, JoinKind, JoinSpec};
+use parquet_viewer_core::minimap::MinimapMetric;
+use parquet_viewer_core::scratch::write_scratch;
+use parquet_viewer_core::selection::ClipboardFormat;
+use parquet_viewer_core::truncate::Truncation;
+use parquet_viewer_core::ViewerError;
+
+use crate::bookmarks::{NextBookmark, PreviousBookmark, ToggleBookmark, ToggleBookmarksPanel};
+use crate::context_menu::AddComputedColumn;
+use crate::deep_link::DeepLink;
+use crate::duplicates::FindDuplicateRows;
+use crate::explain::ToggleQueryExplain;
+use crate::file_info::{ToggleMetadataPanel, ToggleSizePanel};
+use crate::go_to_row::GoToRow;
+use crate::highlight::HighlightInView;
+use crate::highlight_rules::ToggleHighlightRules;
+use crate::history::ToggleQueryHistory;
+use crate::join::{guess_join_keys, parse_join_keys, JoinWithFile};
+use crate::minimap::{SetMinimapMetric, ToggleMinimap};
+use crate::page_boundaries::TogglePageBoundaries;
+use crate::profile::ProfileColumns;
+use crate::recent::RecentFiles;
+use crate::record::ShowRecord;
+use crate::row_groups::{NextRowGroup, PreviousRowGroup};
+use crate::split::{render_split, CloseSplit, Split, SplitAxis, SplitSideBySide, SplitStacked};
+use crate::start_screen::{render_start_screen, LoadFailure};
+use crate::summary::ToggleSummaryRow;
+use crate::table_style::{
+    ResetZoom, SetRowHeight, SetTableFont, SetTableFontSize, SetTruncation, ZoomIn, ZoomOut,
+    FONT_CHOICES, FONT_SIZE_CHOICES, ROW_HEIGHT_CHOICES,
+};
+use crate::theme::theme_names;
+use crate::time_scrubber::ToggleTimeScrubber;
+use crate::transpose::ToggleTranspose;
+use crate::window_geometry::{current_window, SavedWindow, WindowGeometry};
+use crate::{load_preview, PreviewView};
+
+actions!(
+    parquet_viewer,
+    [
+        OpenFile,
+        Quit,
+        CopySelection,
+        CopyViewAsMarkdown,
+        ExportSelection,
+        ExportXlsx,
+        PasteScratchTable,
+        CloseTab,
+        ToggleDarkMode
+    ]
+);
+
+/// Write the selected rows, or every row the filter shows, to a new Parquet file.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct ExportParquet {
+    pub compression: Compression,
+}
+
+/// Copy the selected rows to the clipboard in one of the text formats.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct CopySelectionAs {
+    pub format: ClipboardFormat,
+}
+
+/// Codecs offered by File → Export to Parquet, at their default levels.
+fn export_codecs() -> [(&'static str, Compression); 5] {
+    [
+        ("Snappy", Compression::SNAPPY),
+        ("Zstandard", Compression::ZSTD(ZstdLevel::default())),
+        ("Gzip", Compression::GZIP(GzipLevel::default())),
+        ("LZ4", Compression::LZ4_RAW),
+        ("Uncompressed", Compression::UNCOMPRESSED),
+    ]
+}
+
+/// Name of the file at `path`, for a tab title.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Open a file from the recent files list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct OpenRecent {
+    pub path: PathBuf,
+}
+
+/// Switch to a theme by name; see [`crate::theme::ThemeChoice::parse`].
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = parquet_viewer, no_json)]
+pub struct SelectTheme {
+    pub name: String,
+}
+
+/// Height of the in-window menu bar drawn on platforms without a global menu.
+pub const MENU_BAR_HEIGHT: f32 = if cfg!(target_os = "macos") { 0.0 } else { 30.0 };
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("secondary-o", OpenFile, None),
+        KeyBinding::new("secondary-q", Quit, None),
+        KeyBinding::new("secondary-w", CloseTab, None),
+        KeyBinding::new("secondary-shift-v", PasteScratchTable, None),
+        KeyBinding::new("secondary-c", CopySelection, None),
+        KeyBinding::new("secondary-shift-e", ExportSelection, None),
+        KeyBinding::new("secondary-g", GoToRow, None),
+        KeyBinding::new("secondary-f", HighlightInView, None),
+        KeyBinding::new("secondary-b", ToggleBookmark, None),
+        KeyBinding::new("enter", ShowRecord, Some("PreviewView")),
+        KeyBinding::new("f2", NextBookmark, None),
+        KeyBinding::new("shift-f2", PreviousBookmark, None),
+        KeyBinding::new("alt-pagedown", NextRowGroup, None),
+        KeyBinding::new("alt-pageup", PreviousRowGroup, None),
+        KeyBinding::new("secondary-=", ZoomIn, None),
+        KeyBinding::new("secondary-+", ZoomIn, None),
+        KeyBinding::new("secondary--", ZoomOut, None),
+        KeyBinding::new("secondary-0", ResetZoom, None),
+        KeyBinding::new("secondary-\\", SplitSideBySide, None),
+    ]);
+    cx.on_action(|_: &Quit, cx| cx.quit());
+}
+
+fn set_menus(recent: &RecentFiles, cx: &mut App) {
+    let recent_items = recent
+        .paths()
+        .iter()
+        .map(|path| {
+            MenuItem::action(
+                path.display().to_string(),
+                OpenRecent { path: path.clone() },
+            )
+        })
+        .collect();
+
+    let theme_items = std::iter::once("System".into())
+        .chain(theme_names(cx))
+        .map(|name| {
+            MenuItem::action(
+                name.clone(),
+                SelectTheme {
+                    name: name.to_string(),
+                },
+            )
+        })
+        .collect();
+
+    cx.set_menus(vec![
+        Menu {
+            name: "File".into(),
+            items: vec![
+                MenuItem::action("Open…", OpenFile),
+                MenuItem::submenu(Menu {
+                    name: "Open Recent".into(),
+                    items: recent_items,
+                }),
+                MenuItem::separator(),
+                MenuItem::action("Export Selection…", ExportSelection),
+                MenuItem::submenu(Menu {
+                    name: "Export to Parquet".into(),
+                    items: export_codecs()
+                        .into_iter()
+                        .map(|(label, compression)| {
+                            MenuItem::action(format!("{label}…"), ExportParquet { compression })
+                        })
+                        .collect(),
+                }),
+                MenuItem::action("Export to Excel…", ExportXlsx),
+                MenuItem::separator(),
+                MenuItem::action("Close Tab", CloseTab),
+                MenuItem::action("Quit", Quit),
+            ],
+        },
+        Menu {
+            name: "Edit".into(),
+            items: vec![
+                MenuItem::action("Copy Selected Rows", CopySelection),
+                MenuItem::submenu(Menu {
+                    name: "Copy Selected Rows As".into(),
+                    items: ClipboardFormat::ALL
+                        .into_iter()
+                        .map(|format| MenuItem::action(format.label(), CopySelectionAs { format }))
+                        .collect(),
+                }),
+                MenuItem::action("Copy View as Markdown", CopyViewAsMarkdown),
+                MenuItem::action("Paste as Scratch Table", PasteScratchTable),
+                MenuItem::separator(),
+                MenuItem::action("Go to Row…", GoToRow),
+                MenuItem::action("Highlight in View…", HighlightInView),
+                MenuItem::action("Show Record", ShowRecord),
+                MenuItem::separator(),
+                MenuItem::action("Toggle Bookmark", ToggleBookmark),
+                MenuItem::action("Next Bookmark", NextBookmark),
+                MenuItem::action("Previous Bookmark", PreviousBookmark),
+                MenuItem::separator(),
+                MenuItem::action("Next Row Group", NextRowGroup),
+                MenuItem::action("Previous Row Group", PreviousRowGroup),
+            ],
+        },
+        Menu {
+            name: "Analyze".into(),
+            items: vec![
+                MenuItem::action("Profile Columns", ProfileColumns),
+                MenuItem::action("Find Duplicate Rows", FindDuplicateRows),
+                MenuItem::action("Add Computed Column…", AddComputedColumn),
+                MenuItem::separator(),
+                MenuItem::submenu(Menu {
+                    name: "Join With File".into(),
+                    items: [JoinKind::Inner, JoinKind::Left]
+                        .into_iter()
+                        .map(|kind| {
+                            MenuItem::action(format!("{}…", kind.label()), JoinWithFile { kind })
+                        })
+                        .collect(),
+                }),
+            ],
+        },
+        Menu {
+            name: "View".into(),
+            items: vec![
+                MenuItem::action("Bookmarks", ToggleBookmarksPanel),
+                MenuItem::action("File Metadata", ToggleMetadataPanel),
+                MenuItem::action("File Size", ToggleSizePanel),
+                MenuItem::action("Highlight Rules", ToggleHighlightRules),
+                MenuItem::action("Query History", ToggleQueryHistory),
+                MenuItem::action("Explain Last Query", ToggleQueryExplain),
+                MenuItem::separator(),
+                MenuItem::action("Transpose Table", ToggleTranspose),
+                MenuItem::submenu(Menu {
+                    name: "Split View".into(),
+                    items: vec![
+                        MenuItem::action("Side by Side", SplitSideBySide),
+                        MenuItem::action("Stacked", SplitStacked),
+                        MenuItem::action("Close Split", CloseSplit),
+                    ],
+                }),
+                MenuItem::action("Summary Row", ToggleSummaryRow),
+                MenuItem::action("Timeline Scrubber", ToggleTimeScrubber),
+                MenuItem::submenu(Menu {
+                    name: "Minimap".into(),
+                    items: [MenuItem::action("Show Minimap", ToggleMinimap)]
+                        .into_iter()
+                        .chain([None].into_iter().chain(MinimapMetric::ALL.map(Some)).map(
+                            |metric| {
+                                let label = match metric {
+                                    Some(metric) => format!("Shade by {metric}"),
+                                    None => "Row Groups Only".to_string(),
+                                };
+                                MenuItem::action(label, SetMinimapMetric { metric })
+                            },
+                        ))
+                        .collect(),
+                }),
+                MenuItem::action("Toggle Dark Mode", ToggleDarkMode),
+                MenuItem::submenu(Menu {
+                    name: "Theme".into(),
+                    items: theme_items,
+                }),
+                MenuItem::separator(),
+                MenuItem::action("Zoom In", ZoomIn),
+                MenuItem::action("Zoom Out", ZoomOut),
+                MenuItem::action("Reset Zoom", ResetZoom),
+                MenuItem::separator(),
+                MenuItem::submenu(Menu {
+                    name: "Table Font".into(),
+                    items: FONT_CHOICES
+                        .iter()
+                        .map(|(label, family)| {
+                            MenuItem::action(
+                                *label,
+                                SetTableFont {
+                                    family: family.to_string(),
+                                },
+                            )
+                        })
+                        .collect(),
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Table Font Size".into(),
+                    items: FONT_SIZE_CHOICES
+                        .iter()
+                        .map(|&size| {
+                            MenuItem::action(format!("{size} px"), SetTableFontSize { size })
+                        })
+                        .collect(),
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Truncate Long Values".into(),
+                    items: vec![
+                        MenuItem::action(
+                            "At the End",
+                            SetTruncation {
+                                truncation: Truncation::End,
+                            },
+                        ),
+                        MenuItem::action(
+                            "In the Middle",
+                            SetTruncation {
+                                truncation: Truncation::Middle,
+                            },
+                        ),
+                    ],
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Row Height".into(),
+                    items: ROW_HEIGHT_CHOICES
+                        .iter()
+                        .map(|&(label, height)| {
+                            MenuItem::action(
+                                format!("{label} ({height} px)"),
+                                SetRowHeight { height },
+                            )
+                        })
+                        .collect(),
+                }),
+                MenuItem::separator(),
+                MenuItem::submenu(Menu {
+                    name: "Debug".into(),
+                    items: vec![MenuItem::action("Page Boundaries", TogglePageBoundaries)],
+                }),
+            ],
+        },
+    ]);
+}
+
+/// An open file or scratch table.
+struct Tab {
+    title: SharedString,
+    view: Entity<PreviewView>,
+    /// Second pane over the same file, while the tab is split.
+    split: Option<Split>,
+}
+
+impl Tab {
+    fn new(title: impl Into<SharedString>, view: Entity<PreviewView>) -> Self {
+        Self {
+            title: title.into(),
+            view,
+            split: None,
+        }
+    }
+
+    /// Whether `view` is one of the tab's panes.
+    fn shows(&self, view: &Entity<PreviewView>) -> bool {
+        self.view == *view || self.split.as_ref().is_some_and(|split| split.view == *view)
+    }
+}
+
+/// Window root that owns the menu bar and swaps between the start screen and a preview.
+pub struct Workspace {
+    focus_handle: FocusHandle,
+    menu_bar: Entity<AppMenuBar>,
+    tabs: Vec<Tab>,
+    /// Index of the tab shown; `None` shows the start screen.
+    active: Option<usize>,
+    /// Scratch tables pasted so far, for naming the next one.
+    scratch_count: usize,
+    failure: Option<LoadFailure>,
+    recent: RecentFiles,
+    row_limit: usize,
+    /// Whether opened files are memory-mapped.
+    mmap: bool,
+    /// Display the window is on and its geometry, saved when it closes.
+    geometry: Option<(String, SavedWindow)>,
+}
+
+impl Workspace {
+    /// Open the file `link` names, if any, where it points.
+    pub fn new(
+        link: Option<DeepLink>,
+        row_limit: usize,
+        mmap: bool,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let recent = RecentFiles::load();
+        set_menus(&recent, cx);
+
+        let focus_handle = cx.focus_handle();
+        window.focus(&focus_handle);
+
+        let mut workspace = Self {
+            focus_handle,
+            menu_bar: AppMenuBar::new(window, cx),
+            tabs: Vec::new(),
+            active: None,
+            scratch_count: 0,
+            failure: None,
+            recent,
+            row_limit,
+            mmap,
+            geometry: current_window(window, cx),
+        };
+
+        cx.observe_window_bounds(window, |workspace, window, cx| {
+            workspace.geometry = current_window(window, cx);
+        })
+        .detach();
+        cx.on_release(|workspace, _| workspace.remember_geometry())
+            .detach();
+        cx.on_app_quit(|workspace, _| {
+            workspace.remember_geometry();
+            async {}
+        })
+        .detach();
+
+        if let Some(link) = link {
+            workspace.open_link(link, window, cx);
+        }
+
+        workspace
+    }
+
+    fn remember_geometry(&self) {
+        if let Some((display, window)) = self.geometry.clone() {
+            let mut geometry = WindowGeometry::load();
+            geometry.remember(display, window);
+            geometry.save();
+        }
+    }
+
+    /// The view of the tab shown, or of its pane clicked last while split.
+    fn preview(&self) -> Option<&Entity<PreviewView>> {
+        let tab = self.tabs.get(self.active?)?;
+        match &tab.split {
+            Some(split) if split.second_active => Some(&split.view),
+            _ => Some(&tab.view),
+        }
+    }
+
+    /// Show `tab` in a new tab, or in place of the current one with `replace`.
+    fn show_tab(&mut self, tab: Tab, replace: bool) {
+        match self.active {
+            Some(index) if replace => self.tabs[index] = tab,
+            _ => {
+                self.tabs.push(tab);
+                self.active = Some(self.tabs.len() - 1);
+            }
+        }
+        self.failure = None;
+    }
+
+    fn activate_tab(&mut self, index: usize, cx: &mut gpui::Context<Self>) {
+        if index < self.tabs.len() {
+            self.active = Some(index);
+            self.failure = None;
+            cx.notify();
+        }
+    }
+
+    fn close_tab_at(&mut self, index: usize, cx: &mut gpui::Context<Self>) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        self.tabs.remove(index);
+        self.active = match self.active {
+            _ if self.tabs.is_empty() => None,
+            Some(active) if active > index => Some(active - 1),
+            Some(active) => Some(active.min(self.tabs.len() - 1)),
+            None => None,
+        };
+        cx.notify();
+    }
+
+    fn close_tab(
+        &mut self,
+        _: &CloseTab,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(index) = self.active {
+            self.close_tab_at(index, cx);
+        }
+    }
+
+    /// Open the table on the clipboard, tab- or comma-separated with a header
+    /// line, in a new scratch tab.
+    fn paste_scratch_table(
+        &mut self,
+        _: &PasteScratchTable,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            window.push_notification("The clipboard has no text to paste", cx);
+            return;
+        };
+        let loaded = tempfile::Builder::new()
+            .prefix("parquet-viewer-scratch-")
+            .suffix(".parquet")
+            .tempfile()
+            .map_err(ViewerError::from)
+            .and_then(|file| {
+                write_scratch(&text, file.path())?;
+                let preview = load_preview(&file.path().to_path_buf(), self.row_limit, false)?;
+                Ok((file, preview))
+            });
+        match loaded {
+            Ok((file, preview)) => {
+                self.scratch_count += 1;
+                let tab = Tab::new(
+                    format!("Scratch {}", self.scratch_count),
+                    cx.new(|cx| PreviewView::new_scratch(preview, file, window, cx)),
+                );
+                self.show_tab(tab, false);
+                cx.notify();
+            }
+            Err(error) => {
+                tracing::error!(%error, "failed to paste a scratch table");
+                window.push_notification(error.to_string(), cx);
+            }
+        }
+    }
+
+    /// Ask for a second file to join the one shown with, then for the key columns.
+    fn join_with_file(
+        &mut self,
+        action: &JoinWithFile,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let Some(preview) = self.preview().cloned() else {
+            return;
+        };
+        let kind = action.kind;
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Join".into()),
+        });
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let right = match paths.await {
+                Ok(Ok(Some(paths))) => paths.into_iter().next(),
+                Ok(Err(error)) => {
+                    tracing::error!(?error, "failed to show the file picker");
+                    None
+                }
+                _ => None,
+            };
+            let Some(right) = right else {
+                return;
+            };
+
+            let columns = cx
+                .background_spawn({
+                    let right = right.clone();
+                    async move { column_names(&right) }
+                })
+                .await;
+            workspace
+                .update_in(cx, |workspace, window, cx| match columns {
+                    Ok(columns) => {
+                        workspace.open_join_dialog(preview, right, columns, kind, window, cx)
+                    }
+                    Err(error) => window.push_notification(error.to_string(), cx),
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    fn open_join_dialog(
+        &mut self,
+        preview: Entity<PreviewView>,
+        right: PathBuf,
+        right_columns: Vec<String>,
+        kind: JoinKind,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let (left, left_columns, guess) = {
+            let view = preview.read(cx);
+            let (columns, selected) = view.columns_and_selected();
+            (
+                view.path().to_path_buf(),
+                columns.to_vec(),
+                guess_join_keys(columns, &right_columns, selected),
+            )
+        };
+        let right_name = file_name(&right);
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Key columns, e.g. customer_id = id")
+                .default_value(guess)
+        });
+        let hint = format!("Columns of {right_name}: {}", right_columns.join(", "));
+        let title = format!("{} with {right_name}", kind.label());
+        let workspace = cx.entity().downgrade();
+        let dialog_input = input.clone();
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let input = dialog_input.clone();
+            let workspace = workspace.clone();
+            let preview = preview.clone();
+            let left = left.clone();
+            let right = right.clone();
+            let left_columns = left_columns.clone();
+            let right_columns = right_columns.clone();
+            dialog
+                .title(title.clone())
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .child(Input::new(&dialog_input))
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(hint.clone()),
+                        ),
+                )
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let Some((left_key, right_key)) = parse_join_keys(&input.read(cx).value())
+                    else {
+                        window.push_notification("Enter the key columns as left = right", cx);
+                        return false;
+                    };
+                    for (key, columns, path) in [
+                        (&left_key, &left_columns, left.as_path()),
+                        (&right_key, &right_columns, right.as_path()),
+                    ] {
+                        if !columns.contains(key) {
+                            let error = ViewerError::NoSuchColumn {
+                                column: key.clone(),
+                                path: path.to_path_buf(),
+                            };
+                            window.push_notification(error.to_string(), cx);
+                            return false;
+                        }
+                    }
+
+                    let spec = JoinSpec {
+                        right: right.clone(),
+                        left_key,
+                        right_key,
+                        kind,
+                    };
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            workspace.start_join(preview.clone(), spec, window, cx)
+                        })
+                        .ok();
+                    true
+                })
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    /// Run a join from the `preview` tab and open its result in a new tab.
+    fn start_join(
+        &mut self,
+        preview: Entity<PreviewView>,
+        spec: JoinSpec,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let left = self
+            .tabs
+            .iter()
+            .find(|tab| tab.shows(&preview))
+            .map(|tab| tab.title.clone())
+            .unwrap_or_default();
+        let title: SharedString = format!("{left} ⋈ {}", file_name(&spec.right)).into();
+        let join = preview.update(cx, |preview, cx| preview.join_file(spec, cx));
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let result = join.await;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let loaded = result.and_then(|(file, summary)| {
+                        let preview =
+                            load_preview(&file.path().to_path_buf(), workspace.row_limit, false)?;
+                        Ok((file, preview, summary))
+                    });
+                    match loaded {
+                        Ok((file, preview, summary)) => {
+                            let tab = Tab::new(
+                                title,
+                                cx.new(|cx| PreviewView::new_scratch(preview, file, window, cx)),
+                            );
+                            workspace.show_tab(tab, false);
+                            window.push_notification(summary.summary(), cx);
+                            cx.notify();
+                        }
+                        Err(ViewerError::Cancelled) => {}
+                        Err(error) => {
+                            tracing::error!(%error, "failed to join files");
+                            window.push_notification(error.to_string(), cx);
+                        }
+                    }
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    fn open_path(
+        &mut self,
+        path: PathBuf,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        tracing::info!(
+            path = %path.to_string_lossy(),
+            rows = self.row_limit,
+            "loading parquet file"
+        );
+
+        match load_preview(&path, self.row_limit, self.mmap) {
+            Ok(preview) => {
+                // Files open in place of the file shown, next to scratch tables.
+                let replace = self
+                    .preview()
+                    .is_some_and(|view| !view.read(cx).is_scratch());
+                let tab = Tab::new(
+                    file_name(&path),
+                    cx.new(|cx| PreviewView::new(preview, window, cx)),
+                );
+                self.show_tab(tab, replace);
+
+                self.recent.push(&path);
+                self.recent.save();
+                set_menus(&self.recent, cx);
+                self.menu_bar = AppMenuBar::new(window, cx);
+            }
+            Err(error) => {
+                // GUI users won't see stderr, so failures are reported in the window instead.
+                tracing::error!(%error, "failed to load parquet file");
+                self.active = None;
+                self.failure = Some(LoadFailure {
+                    path,
+                    message: error.to_string(),
+                });
+            }
+        }
+
+        cx.notify();
+    }
+
+    /// Open the file `link` names and show the columns and row it points at,
+    /// from the command line or a `parquet-viewer://` link.
+    pub fn open_link(
+        &mut self,
+        link: DeepLink,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        self.open_path(link.path, window, cx);
+        let Some(preview) = self.preview().cloned() else {
+            return;
+        };
+        let opened = preview.update(cx, |preview, cx| {
+            let columns = if link.columns.is_empty() {
+                Ok(())
+            } else {
+                preview.show_only_columns(&link.columns, cx)
+            };
+            let start = match &link.start {
+                Some(start) => preview.open_at(start, cx),
+                None => Ok(()),
+            };
+            columns.and(start)
+        });
+        if let Err(message) = opened {
+            tracing::warn!(%message, "failed to open at the linked position");
+            // The window may still be opening, so the notification waits for it.
+            cx.defer_in(window, move |_, window, cx| {
+                window.push_notification(message, cx);
+            });
+        }
+    }
+
+    fn open_file(&mut self, _: &OpenFile, window: &mut gpui::Window, cx: &mut gpui::Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Open".into()),
+        });
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let path = match paths.await {
+                Ok(Ok(Some(paths))) => paths.into_iter().next(),
+                Ok(Err(error)) => {
+                    tracing::error!(?error, "failed to show the file picker");
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(path) = path {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.open_path(path, window, cx)
+                    })
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn open_recent(
+        &mut self,
+        action: &OpenRecent,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        self.open_path(action.path.clone(), window, cx);
+    }
+
+    fn copy_selection(
+        &mut self,
+        _: &CopySelection,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.copy_selection(window, cx));
+        }
+    }
+
+    fn copy_selection_as(
+        &mut self,
+        action: &CopySelectionAs,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| {
+                preview.copy_selection_as(action.format, window, cx)
+            });
+        }
+    }
+
+    fn export_parquet(
+        &mut self,
+        action: &ExportParquet,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| {
+                preview.export_parquet(action.compression, window, cx)
+            });
+        }
+    }
+
+    fn export_xlsx(
+        &mut self,
+        _: &ExportXlsx,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.export_xlsx(window, cx));
+        }
+    }
+
+    fn copy_view_as_markdown(
+        &mut self,
+        _: &CopyViewAsMarkdown,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.copy_view_as_markdown(window, cx));
+        }
+    }
+
+    fn export_selection(
+        &mut self,
+        _: &ExportSelection,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.export_selection(window, cx));
+        }
+    }
+
+    fn go_to_row(&mut self, _: &GoToRow, window: &mut gpui::Window, cx: &mut gpui::Context<Self>) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.open_go_to_row(window, cx));
+        }
+    }
+
+    fn highlight_in_view(
+        &mut self,
+        _: &HighlightInView,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.open_highlight(window, cx));
+        }
+    }
+
+    fn toggle_bookmark(
+        &mut self,
+        _: &ToggleBookmark,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_bookmark(cx));
+        }
+    }
+
+    fn next_bookmark(
+        &mut self,
+        _: &NextBookmark,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.step_bookmark(false, cx));
+        }
+    }
+
+    fn previous_bookmark(
+        &mut self,
+        _: &PreviousBookmark,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.step_bookmark(true, cx));
+        }
+    }
+
+    fn next_row_group(
+        &mut self,
+        _: &NextRowGroup,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.step_row_group(false, cx));
+        }
+    }
+
+    fn previous_row_group(
+        &mut self,
+        _: &PreviousRowGroup,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.step_row_group(true, cx));
+        }
+    }
+
+    fn toggle_bookmarks_panel(
+        &mut self,
+        _: &ToggleBookmarksPanel,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_bookmarks_panel(cx));
+        }
+    }
+
+    fn toggle_metadata_panel(
+        &mut self,
+        _: &ToggleMetadataPanel,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_metadata_panel(cx));
+        }
+    }
+
+    fn toggle_size_panel(
+        &mut self,
+        _: &ToggleSizePanel,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_size_panel(cx));
+        }
+    }
+
+    fn toggle_highlight_rules(
+        &mut self,
+        _: &ToggleHighlightRules,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_highlight_rules(cx));
+        }
+    }
+
+    fn toggle_query_history(
+        &mut self,
+        _: &ToggleQueryHistory,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_query_history(cx));
+        }
+    }
+
+    fn toggle_query_explain(
+        &mut self,
+        _: &ToggleQueryExplain,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_query_explain(cx));
+        }
+    }
+
+    fn toggle_summary_row(
+        &mut self,
+        _: &ToggleSummaryRow,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_summary_row(cx));
+        }
+    }
+
+    fn toggle_minimap(
+        &mut self,
+        _: &ToggleMinimap,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_minimap(cx));
+        }
+    }
+
+    fn set_minimap_metric(
+        &mut self,
+        action: &SetMinimapMetric,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| {
+                preview.set_minimap_metric(action.metric, window, cx)
+            });
+        }
+    }
+
+    fn toggle_time_scrubber(
+        &mut self,
+        _: &ToggleTimeScrubber,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_time_scrubber(cx));
+        }
+    }
+
+    fn profile_columns(
+        &mut self,
+        _: &ProfileColumns,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_profile_panel(cx));
+        }
+    }
+
+    fn toggle_page_boundaries(
+        &mut self,
+        _: &TogglePageBoundaries,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_page_boundaries(window, cx));
+        }
+    }
+
+    fn find_duplicate_rows(
+        &mut self,
+        _: &FindDuplicateRows,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.find_duplicate_rows(cx));
+        }
+    }
+
+    fn toggle_transpose(
+        &mut self,
+        _: &ToggleTranspose,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        if let Some(preview) = self.preview() {
+            preview.update(cx, |preview, cx| preview.toggle_transpose(cx));
+        }
+    }
+
+    fn split_side_by_side(
+        &mut self,
+        _: &SplitSideBySide,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        self.split(SplitAxis::SideBySide, window, cx);
+    }
+
+    fn split_stacked(
+        &mut self,
+        _: &SplitStacked,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        self.split(SplitAxis::Stacked, window, cx);
+    }
+
+    /// Split the tab shown into two panes over its file along `axis`, or
+    /// rearrange its panes if it is split already. The new pane starts at the
+    /// top of the file.
+    fn split(&mut self, axis: SplitAxis, window: &mut gpui::Window, cx: &mut gpui::Context<Self>) {
+        let Some(tab) = self.active.and_then(|index| self.tabs.get_mut(index)) else {
+            return;
+        };
+        match &mut tab.split {
+            Some(split) => split.axis = axis,
+            None => {
+                let path = tab.view.read(cx).path().to_path_buf();
+                match load_preview(&path, self.row_limit, self.mmap) {
+                    Ok(preview) => {
+                        tab.split = Some(Split {
+                            axis,
+                            view: cx.new(|cx| PreviewView::new_pane(preview, window, cx)),
+                            second_active: true,
+                        });
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "failed to split the view");
+                        window.push_notification(error.to_string(), cx);
+                        return;
+                    }
+                }
+            }
+        }
+        let stacked = axis == SplitAxis::Stacked;
+        let panes = tab.split.iter().map(|split| &split.view).chain([&tab.view]);
+        for view in panes.cloned().collect::<Vec<_>>() {
+            view.update(cx, |view, cx| view.set_stacked(stacked, window, cx));
+        }
+        cx.notify();
+    }
+
+    fn close_split(
+        &mut self,
+        _: &CloseSplit,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) {
+        let Some(tab) = self.active.and_then(|index| self.tabs.get_mut(index)) else {
+            return;
+        };
+        if tab.split.take().is_some() {
+            tab.view
+                .update(cx, |view, cx| view.set_stacked(false, window, cx));
+            cx.notify();
+        }
+    }
+
+    /// Send menu actions to the second pane of the split tab shown, or the first.
+    pub(crate) fn activate_pane(&mut self, second: bool, cx: &mut gpui::Context<Self>) {
+        let split = self
+            .active
+            .and_then(|index| self.tabs.get_mut(index))
+            .and_then(|tab| tab.split.as_mut());
+        if let Some(split) = split.filter(|split| split.second_active != second) {
+            split.second_active = second;
+            cx.notify();
+        }
+    }
+}
+
+impl Workspace {
+    /// Strip with a button per open tab, shown once there is more than one.
+    fn render_tabs(&self, cx: &gpui::Context<Self>) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_1()
+            .px_2()
+            .py_1()
+            .w_full()
+            .border_b_1()
+            .border_color(theme.border)
+            .text_sm()
+            .children(self.tabs.iter().enumerate().map(|(index, tab)| {
+                let active = self.active == Some(index);
+                div()
+                    .id(("tab", index))
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_1()
+                    .pl_2()
+                    .rounded(theme.radius)
+                    .cursor_pointer()
+                    .when(active, |this| this.bg(theme.secondary))
+                    .when(!active, |this| this.text_color(theme.muted_foreground))
+                    .on_click(cx.listener(move |workspace, _, _window, cx| {
+                        workspace.activate_tab(index, cx);
+                    }))
+                    .child(tab.title.clone())
+                    .child(
+                        Button::new(("close-tab", index))
+                            .ghost()
+                            .xsmall()
+                            .label("×")
+                            .on_click(cx.listener(move |workspace, _, _window, cx| {
+                                // Closing the tab shouldn't also activate it.
+                                cx.stop_propagation();
+                                workspace.close_tab_at(index, cx);
+                            })),
+                    )
+            }))
+    }
+}
+
+impl gpui::Render for Workspace {
+    fn render(
+        &mut self,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> impl gpui::IntoElement {
+        let dialog_layer = Root::render_dialog_layer(window, cx);
+        let notification_layer = Root::render_notification_layer(window, cx);
+        let theme = cx.theme();
+
+        let tab = self.active.and_then(|index| self.tabs.get(index));
+        let content = match tab {
+            Some(Tab {
+                view,
+                split: Some(split),
+                ..
+            }) => render_split(view, split, cx).into_any_element(),
+            Some(tab) => tab.view.clone().into_any_element(),
+            None => render_start_screen(self.failure.as_ref(), self.recent.paths(), cx)
+                .into_any_element(),
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .key_context("Workspace")
+            .on_action(cx.listener(Self::open_file))
+            .on_action(cx.listener(Self::open_recent))
+            .on_action(cx.listener(Self::close_tab))
+            .on_action(cx.listener(Self::paste_scratch_table))
+            .on_action(cx.listener(Self::join_with_file))
+            .on_action(cx.listener(Self::copy_selection))
+            .on_action(cx.listener(Self::copy_selection_as))
+            .on_action(cx.listener(Self::copy_view_as_markdown))
+            .on_action(cx.listener(Self::export_selection))
+            .on_action(cx.listener(Self::export_parquet))
+            .on_action(cx.listener(Self::export_xlsx))
+            .on_action(cx.listener(Self::go_to_row))
+            .on_action(cx.listener(Self::highlight_in_view))
+            .on_action(cx.listener(Self::toggle_bookmark))
+            .on_action(cx.listener(Self::next_bookmark))
+            .on_action(cx.listener(Self::previous_bookmark))
+            .on_action(cx.listener(Self::next_row_group))
+            .on_action(cx.listener(Self::previous_row_group))
+            .on_action(cx.listener(Self::toggle_bookmarks_panel))
+            .on_action(cx.listener(Self::toggle_metadata_panel))
+            .on_action(cx.listener(Self::toggle_size_panel))
+            .on_action(cx.listener(Self::toggle_transpose))
+            .on_action(cx.listener(Self::split_side_by_side))
+            .on_action(cx.listener(Self::split_stacked))
+            .on_action(cx.listener(Self::close_split))
+            .on_action(cx.listener(Self::toggle_summary_row))
+            .on_action(cx.listener(Self::toggle_time_scrubber))
+            .on_action(cx.listener(Self::toggle_minimap))
+            .on_action(cx.listener(Self::set_minimap_metric))
+            .on_action(cx.listener(Self::toggle_page_boundaries))
+            .on_action(cx.listener(Self::toggle_highlight_rules))
+            .on_action(cx.listener(Self::toggle_query_history))
+            .on_action(cx.listener(Self::toggle_query_explain))
+            .on_action(cx.listener(Self::profile_columns))
+            .on_action(cx.listener(Self::find_duplicate_rows))
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(theme.background)
+            .when(MENU_BAR_HEIGHT > 0.0, |this| {
+                this.child(
+                    div()
+                        .h(px(MENU_BAR_HEIGHT))
+                        .w_full()
+                        .border_b_1()
+                        .border_color(theme.border)
+                        .child(self.menu_bar.clone()),
+                )
+            })
+            .when(self.tabs.len() > 1, |this| this.child(self.render_tabs(cx)))
+            .child(div().flex_1().w_full().min_h_0().child(content))
+            .children(dialog_layer)
+            .children(notification_layer)
+    }
+}